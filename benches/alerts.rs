@@ -0,0 +1,57 @@
+//! Alert generation latency at deployment scale.
+//!
+//! Run with `cargo bench --bench alerts`. Populates an in-memory database
+//! with 5,000 buckets (a tenth of them gone silent) and times
+//! `generate_alerts` over several runs.
+
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use infrared::aggregation::generate_alerts;
+use infrared::model::LifeSignal;
+use infrared::storage::{SignalStore, Storage};
+
+const BUCKETS: usize = 5_000;
+const RUNS: u32 = 5;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let storage = Storage::new("sqlite::memory:").await?;
+    let now = Utc::now();
+
+    // Every bucket was active an hour ago; all but every tenth still are
+    for i in 0..BUCKETS {
+        let bucket = format!("zone-{}", i);
+        let mut minutes_ago = vec![45];
+        if i % 10 != 0 {
+            minutes_ago.push(2);
+        }
+        for minutes in minutes_ago {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.clone(),
+                    timestamp: now - chrono::Duration::minutes(minutes),
+                    weight: 10,
+                    channel: None,
+                })
+                .await?;
+        }
+    }
+
+    let mut total = Duration::ZERO;
+    let mut alerts = 0;
+    for _ in 0..RUNS {
+        let started = Instant::now();
+        alerts = generate_alerts(&storage, 60, now).await?.alerts.len();
+        total += started.elapsed();
+    }
+
+    println!(
+        "generate_alerts: {} buckets, {} alerts, {:.1} ms per run (mean of {})",
+        BUCKETS,
+        alerts,
+        total.as_secs_f64() * 1000.0 / f64::from(RUNS),
+        RUNS
+    );
+    Ok(())
+}
@@ -0,0 +1,117 @@
+-- Baseline: the schema as of version 14, the last version created by the
+-- pre-migration schema initialization. Databases created before migrations
+-- already have these tables and indexes, so everything here is idempotent.
+--
+-- PRIVACY: only aggregate-safe columns. No user IDs, IPs, device IDs or any
+-- identifying fields; signals are a bucket, a timestamp and a weight.
+
+CREATE TABLE IF NOT EXISTS life_signals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bucket TEXT NOT NULL,
+    ts INTEGER NOT NULL,
+    weight INTEGER NOT NULL
+);
+
+-- Efficient time-range queries by bucket
+CREATE INDEX IF NOT EXISTS idx_life_signals_bucket_ts
+ON life_signals(bucket, ts);
+
+-- Dashboard snapshots hold country-level issue sets from public sources only
+CREATE TABLE IF NOT EXISTS dashboard_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    taken_at INTEGER NOT NULL,
+    payload TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_dashboard_snapshots_taken_at
+ON dashboard_snapshots(taken_at);
+
+-- Tension scores recorded with dashboard snapshots; `scope` is a country
+-- name, or '*' for the global score
+CREATE TABLE IF NOT EXISTS tension_history (
+    scope TEXT NOT NULL,
+    taken_at INTEGER NOT NULL,
+    score REAL NOT NULL,
+    PRIMARY KEY (scope, taken_at)
+);
+
+-- Issues reported by partners' detectors: country-level, like every
+-- dashboard issue
+CREATE TABLE IF NOT EXISTS external_issues (
+    id TEXT PRIMARY KEY,
+    received_at INTEGER NOT NULL,
+    payload TEXT NOT NULL
+);
+
+-- Scheduled warmth evaluations: bucket-level totals and statuses only
+CREATE TABLE IF NOT EXISTS warmth_snapshots (
+    bucket TEXT NOT NULL,
+    window_minutes INTEGER NOT NULL,
+    taken_at INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    current_window_total INTEGER NOT NULL,
+    recent_average REAL NOT NULL,
+    PRIMARY KEY (bucket, window_minutes, taken_at)
+);
+
+-- Incident acknowledgments and notes: actor label and operator text only
+CREATE TABLE IF NOT EXISTS incident_annotations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    incident_id TEXT NOT NULL,
+    ts INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    text TEXT
+);
+
+-- Audit log of admin operations: actor label only, no network identifiers
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ts INTEGER NOT NULL,
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    details TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_ts
+ON audit_log(ts);
+
+-- The audit log is append-only
+CREATE TRIGGER IF NOT EXISTS audit_log_no_update BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER IF NOT EXISTS audit_log_no_delete BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+-- Per-bucket metadata (settings only, never anything about senders)
+CREATE TABLE IF NOT EXISTS buckets (
+    bucket TEXT PRIMARY KEY,
+    heartbeat_seconds INTEGER,
+    heartbeat_since INTEGER,
+    switch_seconds INTEGER,
+    switch_armed_at INTEGER,
+    switch_notified_deadline INTEGER,
+    switch_notified_steps INTEGER NOT NULL DEFAULT 0,
+    timezone TEXT,
+    baseline_windows INTEGER,
+    baseline_span_minutes INTEGER,
+    archived_at INTEGER,
+    country TEXT
+);
+
+-- Named bucket groups
+CREATE TABLE IF NOT EXISTS bucket_groups (
+    group_name TEXT NOT NULL,
+    bucket TEXT NOT NULL,
+    PRIMARY KEY (group_name, bucket)
+);
+
+-- Which build last wrote the schema
+CREATE TABLE IF NOT EXISTS schema_meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
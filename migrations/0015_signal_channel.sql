@@ -0,0 +1,7 @@
+-- Signal source attribution: the delivery path a signal arrived over, one
+-- of the channels configured in INFRARED_SIGNAL_CHANNELS, or NULL.
+--
+-- PRIVACY: a channel is shared by everyone using that path ("sms", "mesh"),
+-- never a per-client identifier.
+
+ALTER TABLE life_signals ADD COLUMN channel TEXT;
@@ -0,0 +1,23 @@
+-- Alert history: every alert generated, one row per episode. An alert seen
+-- again shortly after it was last seen extends its row's last_seen; a later
+-- recurrence opens a new row.
+--
+-- PRIVACY: bucket- and group-level statuses only, like the alerts themselves.
+
+CREATE TABLE IF NOT EXISTS alert_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    bucket TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    message TEXT NOT NULL,
+    first_seen INTEGER NOT NULL,
+    last_seen INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_alert_history_bucket_last_seen
+ON alert_history(bucket, last_seen);
+
+CREATE INDEX IF NOT EXISTS idx_alert_history_last_seen
+ON alert_history(last_seen);
@@ -0,0 +1,11 @@
+-- Bucket metadata: operator annotations (a display name, a description and
+-- the expected signal interval) and per-bucket warmth threshold overrides.
+--
+-- PRIVACY: operator-written, bucket-level text and settings only. Nothing
+-- about senders.
+
+ALTER TABLE buckets ADD COLUMN display_name TEXT;
+ALTER TABLE buckets ADD COLUMN description TEXT;
+ALTER TABLE buckets ADD COLUMN expected_interval_minutes INTEGER;
+ALTER TABLE buckets ADD COLUMN stressed_ratio REAL;
+ALTER TABLE buckets ADD COLUMN collapsing_ratio REAL;
@@ -0,0 +1,16 @@
+-- Idempotency keys of recent batch submissions (`POST /signals/backfill`),
+-- so a batch retried after a lost response is not stored twice. Keys are
+-- forgotten after INFRARED_IDEMPOTENCY_TTL_HOURS.
+--
+-- PRIVACY: the key is a random UUID chosen per batch, never a device or
+-- sender identifier; only the batch's signal and bucket counts are kept.
+
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    key TEXT PRIMARY KEY,
+    received_at INTEGER NOT NULL,
+    signals INTEGER NOT NULL,
+    buckets INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_received_at
+ON idempotency_keys(received_at);
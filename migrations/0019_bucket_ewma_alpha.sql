@@ -0,0 +1,7 @@
+-- Per-bucket smoothing factor of an exponentially weighted warmth baseline.
+-- When set, the bucket's status is judged against that baseline instead of
+-- the flat average of its baseline windows.
+--
+-- PRIVACY: an operator-written, bucket-level setting. Nothing about senders.
+
+ALTER TABLE buckets ADD COLUMN ewma_alpha REAL;
@@ -6,21 +6,256 @@
 //! No individual signals can be traced back to specific users or entities.
 //! The warmth index reflects population-level activity, not individual behavior.
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+use tokio::sync::Semaphore;
+
+use crate::canary::{is_canary, is_pipeline, pipeline_subsystem};
+use crate::failover::ChannelFailover;
+use crate::heartbeat::HeartbeatStatus;
+use crate::model::{
+    Alert, AlertReason, AlertSubject, AlertsResponse, Baseline, BaselineMethod, BucketGroup,
+    ChannelWarmth, Detector, GroupWarmthResponse, Trend, TrendDirection, WarmthResponse,
+    WarmthStatus, WarmthThresholds,
+};
+use crate::severity::SeverityTiers;
+use crate::storage::SignalStore;
+use crate::timezone::{SEASONAL_WEEKS, parse_timezone, seasonal_reference_times};
+
+/// Number of historical windows to use when computing the recent average,
+/// unless the bucket or the query sets a [`Baseline`].
+pub const NUM_HISTORICAL_WINDOWS: u32 = 6;
+
+/// Smoothing factor of `ewma_average` for buckets that do not set their own.
+pub const DEFAULT_EWMA_ALPHA: f64 = 0.3;
+
+/// Deviations below the baseline at which the `zscore` and `mad` detectors
+/// flag a bucket, unless configured otherwise.
+pub const DEFAULT_DETECTOR_SIGMAS: f64 = 3.0;
+
+/// Ratio of a normal distribution's standard deviation to its median
+/// absolute deviation.
+const MAD_SCALE: f64 = 1.4826;
+
+/// Windows a bucket's trend is fitted over, counting the current one.
+pub const TREND_WINDOWS: usize = 6;
+
+/// Change per window, as a fraction of the mean total, within which a
+/// trend is flat.
+pub const FLAT_TREND_RATE: f64 = 0.05;
+
+/// Buckets evaluated at once while generating alerts.
+pub const ALERT_CONCURRENCY: usize = 16;
+
+/// Flat average of baseline window totals, or 0.0 without any.
+pub fn mean_average(totals: &[i64]) -> f64 {
+    if totals.is_empty() {
+        return 0.0;
+    }
+    totals.iter().sum::<i64>() as f64 / totals.len() as f64
+}
+
+/// Exponentially weighted moving average of baseline window totals, oldest
+/// first, or 0.0 without any.
+///
+/// Seeded with the oldest total; each later window moves the average
+/// `alpha` of the way towards its own total, so one unusual window long ago
+/// matters less than a shift in the last few.
+pub fn ewma_average(totals: &[i64], alpha: f64) -> f64 {
+    let mut totals = totals.iter().map(|&total| total as f64);
+    let Some(oldest) = totals.next() else {
+        return 0.0;
+    };
+    totals.fold(oldest, |average, total| {
+        alpha * total + (1.0 - alpha) * average
+    })
+}
+
+/// Standard deviations `current` lies from the mean of baseline window
+/// `totals`, or `None` without any history.
+///
+/// The population standard deviation is floored at 1, so a bucket with a
+/// perfectly steady history is not infinitely far off after a dip of one.
+pub fn z_score(current: i64, totals: &[i64]) -> Option<f64> {
+    let mean = mean_average(totals);
+    if totals.is_empty() || mean <= 0.0 {
+        return None;
+    }
+    let variance = totals
+        .iter()
+        .map(|&total| (total as f64 - mean).powi(2))
+        .sum::<f64>()
+        / totals.len() as f64;
+    Some((current as f64 - mean) / variance.sqrt().max(1.0))
+}
+
+/// Median of `values`, or `None` without any.
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// Robust z-score of `current` against baseline window `totals`: its
+/// distance from their median in median absolute deviations, scaled to be
+/// comparable with [`z_score`], or `None` without any history.
+///
+/// A single spike moves the median and the MAD far less than the mean and
+/// the standard deviation. The scaled MAD is floored at 1, as in
+/// [`z_score`].
+pub fn mad_score(current: i64, totals: &[i64]) -> Option<f64> {
+    let median_total = median(totals.iter().map(|&total| total as f64).collect())?;
+    if median_total <= 0.0 {
+        return None;
+    }
+    let mad = median(
+        totals
+            .iter()
+            .map(|&total| (total as f64 - median_total).abs())
+            .collect(),
+    )?;
+    Some((current as f64 - median_total) / (MAD_SCALE * mad).max(1.0))
+}
 
-use crate::model::{Alert, AlertsResponse, WarmthResponse, WarmthStatus};
-use crate::storage::Storage;
+/// Least-squares trend of the last [`TREND_WINDOWS`] windows: baseline
+/// window `totals`, oldest first, then the `current` total. `None` with
+/// fewer than three windows.
+///
+/// The trend is `rising` or `declining` once its slope is at least
+/// [`FLAT_TREND_RATE`] of the windows' mean total per window. A bucket
+/// without any activity is `flat`.
+pub fn trend(current: i64, totals: &[i64]) -> Option<Trend> {
+    let series: Vec<f64> = totals
+        .iter()
+        .chain(std::iter::once(&current))
+        .map(|&total| total as f64)
+        .collect();
+    let series = &series[series.len().saturating_sub(TREND_WINDOWS)..];
+    if series.len() < 3 {
+        return None;
+    }
 
-/// Number of historical windows to use when computing the recent average.
-const NUM_HISTORICAL_WINDOWS: u32 = 6;
+    let n = series.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = series.iter().sum::<f64>() / n;
+    let (covariance, variance) =
+        series
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(covariance, variance), (x, &y)| {
+                let dx = x as f64 - mean_x;
+                (covariance + dx * (y - mean_y), variance + dx * dx)
+            });
+    let slope = covariance / variance;
+    let rate = if mean_y > 0.0 { slope / mean_y } else { 0.0 };
+    let direction = if rate >= FLAT_TREND_RATE {
+        TrendDirection::Rising
+    } else if rate <= -FLAT_TREND_RATE {
+        TrendDirection::Declining
+    } else {
+        TrendDirection::Flat
+    };
+    Some(Trend {
+        direction,
+        slope,
+        rate,
+        windows: series.len() as u32,
+    })
+}
+
+/// Status of a bucket `z` standard deviations from its mean.
+///
+/// More than `sigmas` below is `collapsing`, or `dead` without any
+/// activity; more than half that is `stressed`. Without history the
+/// bucket is `alive`, as with ratio thresholds.
+pub fn zscore_status(current: i64, z: Option<f64>, sigmas: f64) -> WarmthStatus {
+    match z {
+        Some(z) if z < -sigmas && current == 0 => WarmthStatus::Dead,
+        Some(z) if z < -sigmas => WarmthStatus::Collapsing,
+        Some(z) if z < -sigmas / 2.0 => WarmthStatus::Stressed,
+        _ => WarmthStatus::Alive,
+    }
+}
+
+/// The baseline `thresholds` judge a bucket against, from its baseline
+/// window totals.
+pub fn judged_baseline(thresholds: &WarmthThresholds, totals: &[i64]) -> f64 {
+    match thresholds.ewma_alpha {
+        Some(alpha) => ewma_average(totals, alpha),
+        None => mean_average(totals),
+    }
+}
+
+/// A bucket's threshold overrides, else the storage's defaults.
+async fn thresholds_for(
+    storage: &dyn SignalStore,
+    bucket: &str,
+) -> anyhow::Result<WarmthThresholds> {
+    Ok(storage
+        .get_thresholds(&[bucket.to_string()])
+        .await?
+        .remove(bucket)
+        .unwrap_or_else(|| storage.default_thresholds()))
+}
+
+/// Build a warmth response from a bucket's current window and baseline
+/// window totals.
+fn warmth_response(
+    bucket: String,
+    window_minutes: u32,
+    current_window_total: i64,
+    totals: &[i64],
+    baseline_windows: u32,
+    thresholds: WarmthThresholds,
+) -> WarmthResponse {
+    let recent_average = mean_average(totals);
+    let ewma_average = ewma_average(totals, thresholds.ewma_alpha.unwrap_or(DEFAULT_EWMA_ALPHA));
+    let baseline_method = thresholds.baseline_method();
+    let baseline = match baseline_method {
+        BaselineMethod::Mean => recent_average,
+        BaselineMethod::Ewma => ewma_average,
+    };
+
+    WarmthResponse {
+        bucket,
+        window_minutes,
+        current_window_total,
+        recent_average,
+        baseline_windows,
+        ewma_average,
+        baseline_method,
+        detector: Detector::Ratio,
+        z_score: None,
+        status: thresholds.classify(current_window_total, baseline),
+        trend: trend(current_window_total, totals),
+        sparkline: None,
+        seasonal_average: None,
+        timezone: None,
+        channels: None,
+    }
+}
 
 /// Compute the warmth index for a specific bucket.
 ///
 /// This function queries the storage layer to get:
 /// 1. Current window total (sum of weights in the latest time window)
-/// 2. Recent average (average of the previous N windows)
+/// 2. Recent average (average of the previous N windows), both flat and
+///    exponentially weighted
 ///
-/// It then derives the `WarmthStatus` based on the ratio of current to average.
+/// It then derives the `WarmthStatus` based on the ratio of current to
+/// average: the flat one, unless the bucket's thresholds set an
+/// `ewma_alpha`.
 ///
 /// # Arguments
 ///
@@ -33,37 +268,324 @@ const NUM_HISTORICAL_WINDOWS: u32 = 6;
 ///
 /// A `WarmthResponse` containing the bucket's current warmth index and status.
 pub async fn compute_warmth(
-    storage: &Storage,
+    storage: &dyn SignalStore,
+    bucket: &str,
+    window_minutes: u32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<WarmthResponse> {
+    compute_warmth_with_baseline(storage, bucket, window_minutes, None, now).await
+}
+
+/// Compute the warmth index for a bucket against a given baseline.
+///
+/// Without an explicit `baseline`, the bucket's own baseline is used, or
+/// [`NUM_HISTORICAL_WINDOWS`] windows if it has none. The status uses the
+/// bucket's threshold overrides, if any (see [`crate::bucket_meta`]).
+pub async fn compute_warmth_with_baseline(
+    storage: &dyn SignalStore,
     bucket: &str,
     window_minutes: u32,
+    baseline: Option<Baseline>,
     now: DateTime<Utc>,
 ) -> anyhow::Result<WarmthResponse> {
+    let (detector, sigmas) = storage.detector();
+    compute_warmth_detected(
+        storage,
+        bucket,
+        window_minutes,
+        baseline,
+        detector,
+        sigmas,
+        now,
+    )
+    .await
+}
+
+/// Compute the warmth index for a bucket, deriving its status with
+/// `detector`.
+///
+/// Like [`compute_warmth_with_baseline`], which uses the server's
+/// configured detector (see [`SignalStore::detector`]). Instead of ratio
+/// thresholds, [`Detector::Zscore`] flags the bucket once its current total
+/// is more than `sigmas` standard deviations below the mean of the same
+/// baseline windows, and [`Detector::Mad`] once it is as far below their
+/// median (see [`zscore_status`]).
+pub async fn compute_warmth_detected(
+    storage: &dyn SignalStore,
+    bucket: &str,
+    window_minutes: u32,
+    baseline: Option<Baseline>,
+    detector: Detector,
+    sigmas: f64,
+    now: DateTime<Utc>,
+) -> anyhow::Result<WarmthResponse> {
+    let baseline = match baseline {
+        Some(baseline) => Some(baseline),
+        None => storage.get_baseline(bucket).await?,
+    };
+    let baseline_windows = baseline.map_or(NUM_HISTORICAL_WINDOWS, |b| b.windows(window_minutes));
+
     // Get current window total
     let current_window_total = storage
         .query_bucket_window(bucket, window_minutes, now)
         .await?;
 
-    // Get recent average (excluding current window)
-    let recent_average = storage
-        .compute_recent_average(bucket, window_minutes, NUM_HISTORICAL_WINDOWS, now)
+    // Get baseline window totals (excluding current window)
+    let totals = storage
+        .baseline_totals(bucket, window_minutes, baseline_windows, now)
         .await?;
 
     // Derive status
-    let status = WarmthStatus::from_activity(current_window_total, recent_average);
+    let thresholds = thresholds_for(storage, bucket).await?;
+
+    let mut response = warmth_response(
+        bucket.to_string(),
+        window_minutes,
+        current_window_total,
+        &totals,
+        baseline_windows,
+        thresholds,
+    );
+    detect(&mut response, &totals, detector, sigmas);
+    Ok(response)
+}
+
+/// Rederive `response`'s status with `detector`, from the same baseline
+/// window `totals`; [`Detector::Ratio`] keeps the threshold status.
+fn detect(response: &mut WarmthResponse, totals: &[i64], detector: Detector, sigmas: f64) {
+    let current = response.current_window_total;
+    let z = match detector {
+        Detector::Ratio => return,
+        Detector::Zscore => z_score(current, totals),
+        Detector::Mad => mad_score(current, totals),
+    };
+    response.detector = detector;
+    response.z_score = z;
+    response.status = zscore_status(current, z, sigmas);
+}
+
+/// Compute the warmth of several buckets, in the order first given.
+///
+/// Equivalent to [`compute_warmth_with_baseline`] for each bucket, but the
+/// signals are read with one grouped query per distinct baseline length
+/// rather than two queries per bucket. Duplicate names are answered once.
+pub async fn compute_batch_warmth(
+    storage: &dyn SignalStore,
+    buckets: &[String],
+    window_minutes: u32,
+    baseline: Option<Baseline>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Vec<WarmthResponse>> {
+    let mut unique: Vec<String> = buckets.to_vec();
+    unique.sort();
+    unique.dedup();
+
+    let baselines = match baseline {
+        Some(_) => BTreeMap::new(),
+        None => storage.get_baselines(&unique).await?,
+    };
+    let thresholds = storage.get_thresholds(&unique).await?;
+    let (detector, sigmas) = storage.detector();
+    let baseline_windows = |bucket: &str| {
+        baseline
+            .or_else(|| baselines.get(bucket).copied())
+            .map_or(NUM_HISTORICAL_WINDOWS, |b| b.windows(window_minutes))
+    };
+
+    let mut by_windows: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for bucket in unique {
+        by_windows
+            .entry(baseline_windows(&bucket))
+            .or_default()
+            .push(bucket);
+    }
+
+    let mut warmth = BTreeMap::new();
+    for (num_windows, members) in by_windows {
+        let totals = storage
+            .bucket_totals(&members, window_minutes, num_windows, now)
+            .await?;
+        for bucket in members {
+            let (current_window_total, baseline) = match totals.get(&bucket) {
+                Some((current, baseline)) => (*current, baseline.as_slice()),
+                None => (0, [].as_slice()),
+            };
+            let mut response = warmth_response(
+                bucket.clone(),
+                window_minutes,
+                current_window_total,
+                baseline,
+                num_windows,
+                thresholds
+                    .get(&bucket)
+                    .copied()
+                    .unwrap_or_else(|| storage.default_thresholds()),
+            );
+            detect(&mut response, baseline, detector, sigmas);
+            warmth.insert(bucket, response);
+        }
+    }
+
+    Ok(buckets
+        .iter()
+        .filter_map(|bucket| warmth.remove(bucket))
+        .collect())
+}
+
+/// Compute the aggregate warmth of a bucket group.
+///
+/// Member current windows and recent averages are summed before deriving
+/// the status, so a region is judged on its total activity. `uptime` is the
+/// share of members that are not `Dead`. An explicit `baseline` applies to
+/// every member; otherwise each member uses its own.
+pub async fn compute_group_warmth(
+    storage: &dyn SignalStore,
+    group: &BucketGroup,
+    window_minutes: u32,
+    baseline: Option<Baseline>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<GroupWarmthResponse> {
+    let mut members = Vec::with_capacity(group.buckets.len());
+    for bucket in &group.buckets {
+        members.push(
+            compute_warmth_with_baseline(storage, bucket, window_minutes, baseline, now).await?,
+        );
+    }
 
-    Ok(WarmthResponse {
-        bucket: bucket.to_string(),
+    let current_window_total = members.iter().map(|m| m.current_window_total).sum();
+    let recent_average = members.iter().map(|m| m.recent_average).sum();
+    let up = members
+        .iter()
+        .filter(|m| m.status != WarmthStatus::Dead)
+        .count();
+    let uptime = if members.is_empty() {
+        0.0
+    } else {
+        up as f64 / members.len() as f64
+    };
+
+    Ok(GroupWarmthResponse {
+        group: group.name.clone(),
         window_minutes,
         current_window_total,
         recent_average,
-        status,
+        status: storage
+            .default_thresholds()
+            .classify(current_window_total, recent_average),
+        uptime,
+        sparkline: None,
+        members,
     })
 }
 
+/// Attach the totals of the last `windows` windows to a warmth response.
+pub async fn attach_sparkline(
+    storage: &dyn SignalStore,
+    warmth: &mut WarmthResponse,
+    windows: u32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    warmth.sparkline = Some(
+        storage
+            .window_totals(&warmth.bucket, warmth.window_minutes, windows, now)
+            .await?,
+    );
+    Ok(())
+}
+
+/// Attach the seasonal baseline to a warmth response.
+///
+/// The baseline averages the same window at the same local weekday and
+/// time over the previous [`SEASONAL_WEEKS`] weeks, in the bucket's
+/// timezone (UTC if none is set).
+pub async fn attach_seasonal(
+    storage: &dyn SignalStore,
+    warmth: &mut WarmthResponse,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let timezone = storage.get_timezone(&warmth.bucket).await?;
+    let tz = match timezone.as_deref() {
+        Some(name) => parse_timezone(name)?,
+        None => Tz::UTC,
+    };
+
+    let references = seasonal_reference_times(now, tz, SEASONAL_WEEKS);
+    let mut total = 0;
+    for reference in &references {
+        total += storage
+            .query_bucket_window(&warmth.bucket, warmth.window_minutes, *reference)
+            .await?;
+    }
+    warmth.seasonal_average = Some(if references.is_empty() {
+        0.0
+    } else {
+        total as f64 / references.len() as f64
+    });
+    warmth.timezone = timezone;
+    Ok(())
+}
+
+/// Attach the warmth of each signal channel to a warmth response, over the
+/// response's own window and baseline and by the bucket's thresholds.
+pub async fn attach_channels(
+    storage: &dyn SignalStore,
+    warmth: &mut WarmthResponse,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let totals = storage
+        .channel_totals(
+            &warmth.bucket,
+            warmth.window_minutes,
+            warmth.baseline_windows,
+            now,
+        )
+        .await?;
+    let thresholds = thresholds_for(storage, &warmth.bucket).await?;
+    warmth.channels = Some(
+        totals
+            .into_iter()
+            .map(
+                |(channel, current_window_total, recent_average)| ChannelWarmth {
+                    channel,
+                    current_window_total,
+                    recent_average,
+                    status: thresholds.classify(current_window_total, recent_average),
+                },
+            )
+            .collect(),
+    );
+    Ok(())
+}
+
+/// Attach sparklines to every group member, and their sum to the group.
+pub async fn attach_group_sparkline(
+    storage: &dyn SignalStore,
+    warmth: &mut GroupWarmthResponse,
+    windows: u32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    let mut sum = vec![0; windows as usize];
+    for member in &mut warmth.members {
+        attach_sparkline(storage, member, windows, now).await?;
+        for (total, value) in sum.iter_mut().zip(member.sparkline.iter().flatten()) {
+            *total += value;
+        }
+    }
+    warmth.sparkline = Some(sum);
+    Ok(())
+}
+
 /// Generate alerts for all buckets in distress.
 ///
 /// Scans all known buckets and identifies those with `Collapsing` or `Dead` status.
-/// Returns a list of alerts with human-readable messages.
+/// Returns a list of alerts with human-readable messages, ordered by bucket.
+///
+/// Buckets are evaluated concurrently, at most [`ALERT_CONCURRENCY`] at a
+/// time, so alert latency does not grow with one database round trip per
+/// bucket.
+///
+/// The alerts are also recorded in the alert history (see
+/// [`crate::alert_history`]).
 ///
 /// # Arguments
 ///
@@ -75,8 +597,18 @@ pub async fn compute_warmth(
 ///
 /// An `AlertsResponse` containing all current alerts.
 pub async fn generate_alerts(
-    storage: &Storage,
+    storage: &dyn SignalStore,
+    lookback_minutes: u32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<AlertsResponse> {
+    generate_alerts_with_tiers(storage, lookback_minutes, &SeverityTiers::default(), now).await
+}
+
+/// Generate alerts, grading their severity with the given tiers.
+pub async fn generate_alerts_with_tiers(
+    storage: &dyn SignalStore,
     lookback_minutes: u32,
+    tiers: &SeverityTiers,
     now: DateTime<Utc>,
 ) -> anyhow::Result<AlertsResponse> {
     // Use a reasonable window size for alert checking
@@ -85,33 +617,213 @@ pub async fn generate_alerts(
     // Get all buckets that have ever had signals
     let buckets = storage.get_all_known_buckets().await?;
 
+    let permits = Semaphore::new(ALERT_CONCURRENCY);
+    let mut pending: FuturesUnordered<_> = buckets
+        .into_iter()
+        .map(|bucket| {
+            let permits = &permits;
+            async move {
+                let _permit = permits.acquire().await?;
+                bucket_alert(storage, bucket, window_minutes, tiers, now).await
+            }
+        })
+        .collect();
+
     let mut alerts = Vec::new();
+    while let Some(alert) = pending.next().await {
+        alerts.extend(alert?);
+    }
+    alerts.sort_by(|a, b| a.bucket.cmp(&b.bucket));
 
-    for bucket in buckets {
-        let warmth = compute_warmth(storage, &bucket, window_minutes, now).await?;
+    // Missed heartbeats are Dead regardless of window-ratio math
+    for heartbeat in storage.get_heartbeats().await? {
+        if !heartbeat.is_missed(now) {
+            continue;
+        }
 
-        // Only alert on collapsing or dead buckets
-        if matches!(warmth.status, WarmthStatus::Collapsing | WarmthStatus::Dead) {
-            let last_seen = storage.get_last_seen(&bucket).await?;
+        let recent_average = compute_warmth(storage, &heartbeat.bucket, window_minutes, now)
+            .await?
+            .recent_average;
+        let since = Some(heartbeat.due_at());
+        let alert = Alert {
+            bucket: heartbeat.bucket.clone(),
+            subject: AlertSubject::Bucket,
+            status: WarmthStatus::Dead,
+            last_seen_timestamp: heartbeat.last_seen,
+            recent_average,
+            reason: AlertReason::MissedHeartbeat,
+            message: generate_heartbeat_message(&heartbeat),
+            severity: tiers.severity(since, now),
+            distressed_since: since,
+        };
 
-            let message = generate_alert_message(&bucket, warmth.status, &warmth);
+        match alerts.iter_mut().find(|a| a.bucket == heartbeat.bucket) {
+            Some(existing) => *existing = alert,
+            None => alerts.push(alert),
+        }
+    }
 
-            alerts.push(Alert {
-                bucket: bucket.clone(),
-                status: warmth.status,
-                last_seen_timestamp: last_seen,
-                recent_average: warmth.recent_average,
-                message,
-            });
+    // Groups alert on their aggregate activity, as entities of their own
+    for group in storage.get_groups().await? {
+        let warmth = compute_group_warmth(storage, &group, window_minutes, None, now).await?;
+        if !matches!(warmth.status, WarmthStatus::Collapsing | WarmthStatus::Dead) {
+            continue;
         }
+
+        let mut last_seen = None;
+        for bucket in &group.buckets {
+            last_seen = last_seen.max(storage.get_last_seen(bucket).await?);
+        }
+
+        // Groups have no snapshots; a silent group has been dead since its
+        // last member signal
+        let since = last_seen.filter(|_| warmth.status == WarmthStatus::Dead);
+        alerts.push(Alert {
+            bucket: group.name.clone(),
+            subject: AlertSubject::Group,
+            status: warmth.status,
+            last_seen_timestamp: last_seen,
+            recent_average: warmth.recent_average,
+            reason: AlertReason::WarmthDrop,
+            message: generate_group_alert_message(&warmth),
+            severity: tiers.severity(since, now),
+            distressed_since: since,
+        });
     }
 
+    storage.record_alerts(&alerts, now).await?;
+
     Ok(AlertsResponse {
         alerts,
         lookback_minutes,
     })
 }
 
+/// Evaluate one bucket, returning its alert if it is in distress.
+async fn bucket_alert(
+    storage: &dyn SignalStore,
+    bucket: String,
+    window_minutes: u32,
+    tiers: &SeverityTiers,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Option<Alert>> {
+    let warmth = compute_warmth(storage, &bucket, window_minutes, now).await?;
+
+    // Pipeline buckets follow Infrared's own activity, which varies like any
+    // other bucket's, so only collapse is a self-health alert
+    if is_pipeline(&bucket) {
+        if !matches!(warmth.status, WarmthStatus::Collapsing | WarmthStatus::Dead) {
+            return Ok(None);
+        }
+        let message = format!(
+            "CRITICAL: Pipeline bucket '{}' is {:?}. Infrared's {} may have stopped.",
+            bucket,
+            warmth.status,
+            pipeline_subsystem(&bucket)
+        );
+        let last_seen = storage.get_last_seen(&bucket).await?;
+        let since = distressed_since(storage, &bucket, warmth.status, last_seen).await?;
+        return Ok(Some(Alert {
+            last_seen_timestamp: last_seen,
+            bucket,
+            subject: AlertSubject::Bucket,
+            status: warmth.status,
+            recent_average: warmth.recent_average,
+            reason: AlertReason::PipelineStalled,
+            message,
+            severity: tiers.severity(since, now),
+            distressed_since: since,
+        }));
+    }
+
+    // Canaries have a known cadence, so any degradation is a self-health alert
+    if is_canary(&bucket) {
+        if warmth.status == WarmthStatus::Alive {
+            return Ok(None);
+        }
+        let message = format!(
+            "CRITICAL: Canary bucket '{}' is {:?}. Infrared's own ingest or \
+             clock may be broken; other alerts may be unreliable.",
+            bucket, warmth.status
+        );
+        let last_seen = storage.get_last_seen(&bucket).await?;
+        let since = distressed_since(storage, &bucket, warmth.status, last_seen).await?;
+        return Ok(Some(Alert {
+            last_seen_timestamp: last_seen,
+            bucket,
+            subject: AlertSubject::Bucket,
+            status: warmth.status,
+            recent_average: warmth.recent_average,
+            reason: AlertReason::CanaryDegraded,
+            message,
+            severity: tiers.severity(since, now),
+            distressed_since: since,
+        }));
+    }
+
+    // Channels are only broken down for buckets below normal, which is
+    // where a path failure or an all-channel collapse can show
+    let mut warmth = warmth;
+    let failover = if warmth.status == WarmthStatus::Alive {
+        None
+    } else {
+        attach_channels(storage, &mut warmth, now).await?;
+        ChannelFailover::analyze(warmth.channels.as_deref().unwrap_or_default())
+    };
+    let distressed = matches!(warmth.status, WarmthStatus::Collapsing | WarmthStatus::Dead);
+
+    // Otherwise only alert on collapsing or dead buckets
+    if failover.is_none() && !distressed {
+        return Ok(None);
+    }
+
+    let last_seen = storage.get_last_seen(&bucket).await?;
+    let since = if distressed {
+        distressed_since(storage, &bucket, warmth.status, last_seen).await?
+    } else {
+        None
+    };
+    let (reason, message, severity) = match &failover {
+        Some(failover) => (
+            failover.reason(),
+            failover.message(&warmth),
+            failover.severity(tiers, since, now),
+        ),
+        None => (
+            AlertReason::WarmthDrop,
+            generate_alert_message(&bucket, warmth.status, &warmth),
+            tiers.severity(since, now),
+        ),
+    };
+    Ok(Some(Alert {
+        last_seen_timestamp: last_seen,
+        bucket,
+        subject: AlertSubject::Bucket,
+        status: warmth.status,
+        recent_average: warmth.recent_average,
+        reason,
+        message,
+        severity,
+        distressed_since: since,
+    }))
+}
+
+/// When a bucket's distress began.
+///
+/// That is the first of its latest unbroken run of collapsing or dead
+/// scheduled snapshots, or, for a dead bucket, its last signal if that is
+/// earlier (a bucket is dead from the moment it stopped signalling).
+async fn distressed_since(
+    storage: &dyn SignalStore,
+    bucket: &str,
+    status: WarmthStatus,
+    last_seen: Option<DateTime<Utc>>,
+) -> anyhow::Result<Option<DateTime<Utc>>> {
+    let snapshots = storage.distressed_since(bucket).await?;
+    let silent = last_seen.filter(|_| status == WarmthStatus::Dead);
+    Ok(snapshots.into_iter().chain(silent).min())
+}
+
 /// Generate a human-readable alert message.
 fn generate_alert_message(bucket: &str, status: WarmthStatus, warmth: &WarmthResponse) -> String {
     match status {
@@ -139,10 +851,56 @@ fn generate_alert_message(bucket: &str, status: WarmthStatus, warmth: &WarmthRes
     }
 }
 
+/// Generate a human-readable alert message for a bucket group.
+fn generate_group_alert_message(warmth: &GroupWarmthResponse) -> String {
+    let silent = warmth
+        .members
+        .iter()
+        .filter(|m| m.status == WarmthStatus::Dead)
+        .count();
+    let total = warmth.members.len();
+
+    match warmth.status {
+        WarmthStatus::Dead => format!(
+            "CRITICAL: Group '{}' has gone completely silent ({} of {} members). \
+             Historical average was {:.1} signals per window.",
+            warmth.group, silent, total, warmth.recent_average
+        ),
+        _ => format!(
+            "WARNING: Group '{}' is {:?}. Current activity ({}) vs recent average ({:.1}); \
+             {} of {} members silent.",
+            warmth.group,
+            warmth.status,
+            warmth.current_window_total,
+            warmth.recent_average,
+            silent,
+            total
+        ),
+    }
+}
+
+/// Generate a human-readable message for a missed heartbeat.
+fn generate_heartbeat_message(heartbeat: &HeartbeatStatus) -> String {
+    let since = match heartbeat.last_seen {
+        Some(ts) => format!("Last signal at {}.", ts.to_rfc3339()),
+        None => "No signal since the heartbeat was registered.".to_string(),
+    };
+    format!(
+        "CRITICAL: Bucket '{}' missed its expected heartbeat \
+         (at least one signal every {} minutes). {}",
+        heartbeat.bucket,
+        heartbeat.interval_seconds / 60,
+        since
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::LifeSignal;
+    use crate::canary::{PIPELINE_DASHBOARD_BUCKET, PIPELINE_INGEST_BUCKET};
+    use crate::model::{IssueSeverity, LifeSignal};
+    use crate::storage::Storage;
+    use chrono::TimeZone;
 
     async fn setup_test_storage() -> Storage {
         Storage::new("sqlite::memory:").await.unwrap()
@@ -175,6 +933,7 @@ mod tests {
                 bucket: "test-bucket".to_string(),
                 timestamp: now - chrono::Duration::minutes(i64::from(i) * 10 + 5),
                 weight: 100,
+                channel: None,
             };
             storage.insert_life_signal(&signal).await.unwrap();
         }
@@ -184,6 +943,7 @@ mod tests {
             bucket: "test-bucket".to_string(),
             timestamp: now - chrono::Duration::minutes(5),
             weight: 100,
+            channel: None,
         };
         storage.insert_life_signal(&current_signal).await.unwrap();
 
@@ -194,6 +954,423 @@ mod tests {
         assert_eq!(warmth.status, WarmthStatus::Alive);
     }
 
+    #[tokio::test]
+    async fn test_bucket_baseline_override() {
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+
+        // Busy a day ago, quiet through the last hour
+        let signal = LifeSignal {
+            bucket: "weekly-site".to_string(),
+            timestamp: now - chrono::Duration::hours(20),
+            weight: 100,
+            channel: None,
+        };
+        storage.insert_life_signal(&signal).await.unwrap();
+
+        // Six windows of 10 minutes remember nothing
+        let warmth = compute_warmth(&storage, "weekly-site", 10, now)
+            .await
+            .unwrap();
+        assert_eq!(warmth.baseline_windows, NUM_HISTORICAL_WINDOWS);
+        assert_eq!(warmth.status, WarmthStatus::Alive);
+
+        storage
+            .set_baseline("weekly-site", Baseline::SpanMinutes(48 * 60))
+            .await
+            .unwrap();
+        let warmth = compute_warmth(&storage, "weekly-site", 10, now)
+            .await
+            .unwrap();
+        assert_eq!(warmth.baseline_windows, 288);
+        assert_eq!(warmth.status, WarmthStatus::Dead);
+
+        // An explicit baseline wins over the bucket's
+        let warmth = compute_warmth_with_baseline(
+            &storage,
+            "weekly-site",
+            10,
+            Some(Baseline::Windows(3)),
+            now,
+        )
+        .await
+        .unwrap();
+        assert_eq!(warmth.baseline_windows, 3);
+        assert_eq!(warmth.status, WarmthStatus::Alive);
+    }
+
+    #[tokio::test]
+    async fn test_batch_warmth_matches_single_queries() {
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+
+        let mut signals = Vec::new();
+        for (bucket, minutes_ago, weight) in [
+            ("zone-a", 2, 5),
+            ("zone-a", 15, 5),
+            ("zone-a", 35, 3),
+            ("zone-b", 25, 8),
+            ("weekly-site", 20 * 60, 100),
+        ] {
+            signals.push(LifeSignal {
+                bucket: bucket.to_string(),
+                timestamp: now - chrono::Duration::minutes(minutes_ago),
+                weight,
+                channel: None,
+            });
+        }
+        storage.insert_life_signals(&signals).await.unwrap();
+        storage
+            .set_baseline("weekly-site", Baseline::SpanMinutes(48 * 60))
+            .await
+            .unwrap();
+
+        let buckets: Vec<String> = ["zone-b", "weekly-site", "zone-a", "silent", "zone-b"]
+            .iter()
+            .map(|b| b.to_string())
+            .collect();
+        for baseline in [None, Some(Baseline::Windows(3))] {
+            let batch = compute_batch_warmth(&storage, &buckets, 10, baseline, now)
+                .await
+                .unwrap();
+            // Request order, duplicates answered once
+            assert_eq!(
+                batch.iter().map(|w| w.bucket.as_str()).collect::<Vec<_>>(),
+                ["zone-b", "weekly-site", "zone-a", "silent"]
+            );
+            for warmth in batch {
+                let single =
+                    compute_warmth_with_baseline(&storage, &warmth.bucket, 10, baseline, now)
+                        .await
+                        .unwrap();
+                assert_eq!(
+                    (
+                        warmth.current_window_total,
+                        warmth.recent_average,
+                        warmth.baseline_windows,
+                        warmth.ewma_average,
+                        warmth.status
+                    ),
+                    (
+                        single.current_window_total,
+                        single.recent_average,
+                        single.baseline_windows,
+                        single.ewma_average,
+                        single.status
+                    ),
+                    "{}",
+                    warmth.bucket
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_ewma_average() {
+        assert_eq!(ewma_average(&[], 0.3), 0.0);
+        assert_eq!(ewma_average(&[10], 0.3), 10.0);
+        assert_eq!(ewma_average(&[100, 20], 1.0), 20.0);
+        assert_eq!(ewma_average(&[100, 100, 20, 20], 0.5), 40.0);
+        assert_eq!(mean_average(&[100, 100, 20, 20]), 60.0);
+    }
+
+    #[test]
+    fn test_z_score() {
+        assert_eq!(z_score(5, &[]), None);
+        assert_eq!(z_score(5, &[0, 0]), None);
+        // Mean 50, standard deviation 10
+        assert_eq!(z_score(20, &[40, 60, 40, 60]), Some(-3.0));
+        // A steady history is floored at one standard deviation
+        assert_eq!(z_score(48, &[50, 50, 50]), Some(-2.0));
+
+        assert_eq!(zscore_status(10, None, 3.0), WarmthStatus::Alive);
+        assert_eq!(zscore_status(10, Some(-1.0), 3.0), WarmthStatus::Alive);
+        assert_eq!(zscore_status(10, Some(-2.0), 3.0), WarmthStatus::Stressed);
+        assert_eq!(zscore_status(10, Some(-3.5), 3.0), WarmthStatus::Collapsing);
+        assert_eq!(zscore_status(0, Some(-3.5), 3.0), WarmthStatus::Dead);
+    }
+
+    #[test]
+    fn test_trend() {
+        assert_eq!(trend(5, &[5]), None);
+
+        let rising = trend(30, &[10, 20]).unwrap();
+        assert_eq!(rising.direction, TrendDirection::Rising);
+        assert_eq!(rising.slope, 10.0);
+        assert_eq!(rising.rate, 0.5);
+
+        // Only the last windows count: the old collapse is over
+        let flat = trend(100, &[0, 0, 0, 100, 98, 102, 100, 99]).unwrap();
+        assert_eq!(flat.direction, TrendDirection::Flat);
+        assert_eq!(flat.windows, TREND_WINDOWS as u32);
+
+        assert_eq!(
+            trend(0, &[0, 0, 0]).unwrap().direction,
+            TrendDirection::Flat
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_collapse_trend() {
+        let storage = setup_test_storage().await;
+        let now = Utc.timestamp_opt(1_700_000_400, 0).unwrap();
+
+        // A few percent less every window, each within the thresholds
+        let mut signals = Vec::new();
+        for (windows_ago, weight) in [
+            (6, 105),
+            (5, 100),
+            (4, 95),
+            (3, 90),
+            (2, 85),
+            (1, 80),
+            (0, 76),
+        ] {
+            signals.push(LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: now - chrono::Duration::minutes(windows_ago * 10 + 5),
+                weight,
+                channel: None,
+            });
+        }
+        storage.insert_life_signals(&signals).await.unwrap();
+
+        let warmth = compute_warmth(&storage, "zone-a", 10, now).await.unwrap();
+        assert_eq!(warmth.status, WarmthStatus::Alive);
+        let trend = warmth.trend.unwrap();
+        assert_eq!(trend.direction, TrendDirection::Declining);
+        assert!((trend.slope + 4.86).abs() < 0.01);
+        assert!((trend.rate + 0.055).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_mad_score() {
+        assert_eq!(mad_score(5, &[]), None);
+        // Median 100, MAD 10; one spike barely moves either
+        let totals = [90, 100, 110, 100, 90, 110, 5000];
+        assert!((mad_score(70, &totals).unwrap() + 2.02).abs() < 0.01);
+        assert!(z_score(70, &totals).unwrap() > -1.0);
+        // A steady history is floored at one deviation, as with z-scores
+        assert_eq!(mad_score(98, &[100, 100, 100]), Some(-2.0));
+    }
+
+    #[tokio::test]
+    async fn test_mad_detector_alerts() {
+        let now = Utc.timestamp_opt(1_700_000_400, 0).unwrap();
+
+        // A steady bucket with one past spike, now at 40% of its usual level
+        let mut signals = Vec::new();
+        for (windows_ago, weight) in [
+            (6, 100),
+            (5, 95),
+            (4, 105),
+            (3, 3000),
+            (2, 98),
+            (1, 100),
+            (0, 40),
+        ] {
+            signals.push(LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: now - chrono::Duration::minutes(windows_ago * 10 + 5),
+                weight,
+                channel: None,
+            });
+        }
+
+        // The spike inflates the standard deviation enough to hide the drop
+        let storage = setup_test_storage()
+            .await
+            .with_detector(Detector::Zscore, 3.0);
+        storage.insert_life_signals(&signals).await.unwrap();
+        let alerts = generate_alerts(&storage, 60, now).await.unwrap();
+        assert!(alerts.alerts.is_empty());
+
+        let storage = setup_test_storage().await.with_detector(Detector::Mad, 3.0);
+        storage.insert_life_signals(&signals).await.unwrap();
+        let alerts = generate_alerts(&storage, 60, now).await.unwrap();
+        assert_eq!(alerts.alerts.len(), 1);
+        assert_eq!(alerts.alerts[0].status, WarmthStatus::Collapsing);
+
+        let warmth = compute_warmth(&storage, "zone-a", 10, now).await.unwrap();
+        assert_eq!(warmth.detector, Detector::Mad);
+        assert!(warmth.z_score.unwrap() < -10.0);
+    }
+
+    #[tokio::test]
+    async fn test_zscore_detector() {
+        let storage = setup_test_storage().await;
+        let now = Utc.timestamp_opt(1_700_000_400, 0).unwrap();
+
+        // A noisy bucket, currently at 60% of its mean of 100
+        let mut signals = Vec::new();
+        for (windows_ago, weight) in [
+            (6, 40),
+            (5, 160),
+            (4, 50),
+            (3, 150),
+            (2, 40),
+            (1, 160),
+            (0, 60),
+        ] {
+            signals.push(LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: now - chrono::Duration::minutes(windows_ago * 10 + 5),
+                weight,
+                channel: None,
+            });
+        }
+        storage.insert_life_signals(&signals).await.unwrap();
+
+        let warmth = compute_warmth(&storage, "zone-a", 10, now).await.unwrap();
+        assert_eq!(warmth.status, WarmthStatus::Stressed);
+        assert_eq!(warmth.z_score, None);
+
+        // Well within its usual swings
+        let warmth =
+            compute_warmth_detected(&storage, "zone-a", 10, None, Detector::Zscore, 3.0, now)
+                .await
+                .unwrap();
+        assert_eq!(warmth.status, WarmthStatus::Alive);
+        assert_eq!(warmth.detector, Detector::Zscore);
+        assert!((warmth.z_score.unwrap() + 0.70).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_default_thresholds() {
+        let storage = setup_test_storage()
+            .await
+            .with_default_thresholds(WarmthThresholds {
+                stressed_ratio: 0.95,
+                collapsing_ratio: 0.5,
+                ewma_alpha: None,
+            });
+        let now = Utc.timestamp_opt(1_700_000_400, 0).unwrap();
+
+        // 90% of the baseline, for two buckets
+        let mut signals = Vec::new();
+        for bucket in ["zone-a", "zone-b"] {
+            for windows_ago in 1..=6 {
+                signals.push(LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: now - chrono::Duration::minutes(windows_ago * 10 + 5),
+                    weight: 100,
+                    channel: None,
+                });
+            }
+            signals.push(LifeSignal {
+                bucket: bucket.to_string(),
+                timestamp: now - chrono::Duration::minutes(5),
+                weight: 90,
+                channel: None,
+            });
+        }
+        storage.insert_life_signals(&signals).await.unwrap();
+
+        // A bucket's own thresholds still take precedence
+        let meta = crate::bucket_meta::BucketMeta {
+            thresholds: Some(WarmthThresholds::default()),
+            ..Default::default()
+        };
+        storage.set_bucket_meta("zone-b", &meta).await.unwrap();
+
+        let warmth = compute_warmth(&storage, "zone-a", 10, now).await.unwrap();
+        assert_eq!(warmth.status, WarmthStatus::Stressed);
+        let warmth = compute_warmth(&storage, "zone-b", 10, now).await.unwrap();
+        assert_eq!(warmth.status, WarmthStatus::Alive);
+
+        let buckets = ["zone-a".to_string(), "zone-b".to_string()];
+        let batch = compute_batch_warmth(&storage, &buckets, 10, None, now)
+            .await
+            .unwrap();
+        assert_eq!(
+            batch.iter().map(|w| w.status).collect::<Vec<_>>(),
+            [WarmthStatus::Stressed, WarmthStatus::Alive]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ewma_baseline() {
+        let storage = setup_test_storage().await;
+        let now = Utc.timestamp_opt(1_700_000_400, 0).unwrap();
+
+        // Four busy windows, then two quiet ones, then a little recovery
+        let mut signals = Vec::new();
+        for (windows_ago, weight) in [(6, 100), (5, 100), (4, 100), (3, 100), (2, 20), (1, 20)] {
+            signals.push(LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: now - chrono::Duration::minutes(windows_ago * 10 + 5),
+                weight,
+                channel: None,
+            });
+        }
+        signals.push(LifeSignal {
+            bucket: "zone-a".to_string(),
+            timestamp: now - chrono::Duration::minutes(5),
+            weight: 35,
+            channel: None,
+        });
+        storage.insert_life_signals(&signals).await.unwrap();
+
+        // Judged against the flat average, which still remembers the busy hour
+        let warmth = compute_warmth(&storage, "zone-a", 10, now).await.unwrap();
+        assert!((warmth.recent_average - 440.0 / 6.0).abs() < 1e-9);
+        assert!((warmth.ewma_average - 59.2).abs() < 1e-9);
+        assert_eq!(warmth.baseline_method, BaselineMethod::Mean);
+        assert_eq!(warmth.status, WarmthStatus::Stressed);
+
+        // Judged against the weighted one, which has mostly caught up
+        let meta = crate::bucket_meta::BucketMeta {
+            thresholds: Some(WarmthThresholds {
+                ewma_alpha: Some(0.5),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        storage.set_bucket_meta("zone-a", &meta).await.unwrap();
+        let warmth = compute_warmth(&storage, "zone-a", 10, now).await.unwrap();
+        assert!((warmth.recent_average - 440.0 / 6.0).abs() < 1e-9);
+        assert_eq!(warmth.ewma_average, 40.0);
+        assert_eq!(warmth.baseline_method, BaselineMethod::Ewma);
+        assert_eq!(warmth.status, WarmthStatus::Alive);
+
+        let batch = compute_batch_warmth(&storage, &["zone-a".to_string()], 10, None, now)
+            .await
+            .unwrap();
+        assert_eq!(batch[0].ewma_average, 40.0);
+        assert_eq!(batch[0].status, WarmthStatus::Alive);
+    }
+
+    #[tokio::test]
+    async fn test_seasonal_baseline_uses_local_time() {
+        let storage = setup_test_storage().await;
+        // 09:00 in New York, the day after clocks went forward
+        let now = Utc.with_ymd_and_hms(2024, 3, 11, 13, 0, 0).unwrap();
+
+        // A week earlier, 09:00 local was 14:00 UTC
+        for (hour, weight) in [(12, 10), (13, 40)] {
+            let signal = LifeSignal {
+                bucket: "market".to_string(),
+                timestamp: Utc.with_ymd_and_hms(2024, 3, 4, hour, 55, 0).unwrap(),
+                weight,
+                channel: None,
+            };
+            storage.insert_life_signal(&signal).await.unwrap();
+        }
+
+        let mut warmth = compute_warmth(&storage, "market", 10, now).await.unwrap();
+        attach_seasonal(&storage, &mut warmth, now).await.unwrap();
+        assert_eq!(warmth.seasonal_average, Some(2.5));
+        assert_eq!(warmth.timezone, None);
+
+        storage
+            .set_timezone("market", "America/New_York")
+            .await
+            .unwrap();
+        attach_seasonal(&storage, &mut warmth, now).await.unwrap();
+        assert_eq!(warmth.seasonal_average, Some(10.0));
+        assert_eq!(warmth.timezone.as_deref(), Some("America/New_York"));
+    }
+
     #[tokio::test]
     async fn test_generate_alerts_empty() {
         let storage = setup_test_storage().await;
@@ -211,7 +1388,17 @@ mod tests {
             window_minutes: 10,
             current_window_total: 0,
             recent_average: 50.0,
+            baseline_windows: NUM_HISTORICAL_WINDOWS,
+            ewma_average: 50.0,
+            baseline_method: BaselineMethod::Mean,
+            detector: Detector::Ratio,
+            z_score: None,
             status: WarmthStatus::Dead,
+            trend: None,
+            sparkline: None,
+            seasonal_average: None,
+            timezone: None,
+            channels: None,
         };
 
         let message = generate_alert_message("zone-a", WarmthStatus::Dead, &warmth);
@@ -228,7 +1415,17 @@ mod tests {
             window_minutes: 10,
             current_window_total: 5,
             recent_average: 100.0,
+            baseline_windows: NUM_HISTORICAL_WINDOWS,
+            ewma_average: 100.0,
+            baseline_method: BaselineMethod::Mean,
+            detector: Detector::Ratio,
+            z_score: None,
             status: WarmthStatus::Collapsing,
+            trend: None,
+            sparkline: None,
+            seasonal_average: None,
+            timezone: None,
+            channels: None,
         };
 
         let message = generate_alert_message("zone-b", WarmthStatus::Collapsing, &warmth);
@@ -237,4 +1434,284 @@ mod tests {
         assert!(message.contains("zone-b"));
         assert!(message.contains("collapsing"));
     }
+
+    #[tokio::test]
+    async fn test_channel_failover_alerts() {
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+        let signal = |bucket: &str, minutes_ago: i64, channel: &str| LifeSignal {
+            bucket: bucket.to_string(),
+            timestamp: now - chrono::Duration::minutes(minutes_ago),
+            weight: 10,
+            channel: Some(channel.to_string()),
+        };
+
+        // Both buckets used to hear from internet and SMS every window
+        let mut signals = Vec::new();
+        for bucket in ["path-out", "all-out"] {
+            for minutes_ago in [15, 25, 35, 45, 55] {
+                signals.push(signal(bucket, minutes_ago, "internet"));
+                signals.push(signal(bucket, minutes_ago, "sms"));
+            }
+        }
+        // Only SMS still arrives in one; nothing in the other
+        signals.push(signal("path-out", 2, "sms"));
+        storage.insert_life_signals(&signals).await.unwrap();
+
+        let response = generate_alerts(&storage, 60, now).await.unwrap();
+        assert_eq!(response.alerts.len(), 2, "{:?}", response.alerts);
+
+        let all_out = &response.alerts[0];
+        assert_eq!(all_out.bucket, "all-out");
+        assert_eq!(all_out.status, WarmthStatus::Dead);
+        assert_eq!(all_out.reason, AlertReason::AllChannelsCollapsed);
+        assert!(all_out.severity >= IssueSeverity::Critical);
+        assert!(all_out.message.contains("every channel"));
+
+        // Half its usual activity: stressed, but flagged as a path failure
+        let path_out = &response.alerts[1];
+        assert_eq!(path_out.bucket, "path-out");
+        assert_eq!(path_out.status, WarmthStatus::Stressed);
+        assert_eq!(path_out.reason, AlertReason::ChannelFailure);
+        assert_eq!(path_out.severity, IssueSeverity::Warning);
+        assert!(
+            path_out
+                .message
+                .contains("over internet while sms remains healthy")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missed_heartbeat_raises_dead_alert() {
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+
+        // A sparse bucket: one signal 20 minutes ago, expected every 15 minutes
+        storage
+            .set_heartbeat("site-1", 15 * 60, now - chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "site-1".to_string(),
+                timestamp: now - chrono::Duration::minutes(20),
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+
+        let response = generate_alerts(&storage, 60, now).await.unwrap();
+
+        assert_eq!(response.alerts.len(), 1);
+        assert_eq!(response.alerts[0].bucket, "site-1");
+        assert_eq!(response.alerts[0].status, WarmthStatus::Dead);
+        assert_eq!(response.alerts[0].reason, AlertReason::MissedHeartbeat);
+
+        // A fresh signal clears it
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "site-1".to_string(),
+                timestamp: now,
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+        let response = generate_alerts(&storage, 60, now).await.unwrap();
+        assert!(response.alerts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_group_warmth_and_alerts() {
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+
+        // zone-1 keeps signalling; zone-2 went silent in the current window
+        for bucket in ["zone-1", "zone-2"] {
+            for i in 1..=6 {
+                let signal = LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: now - chrono::Duration::minutes(i * 10 + 5),
+                    weight: 100,
+                    channel: None,
+                };
+                storage.insert_life_signal(&signal).await.unwrap();
+            }
+        }
+        let current = LifeSignal {
+            bucket: "zone-1".to_string(),
+            timestamp: now - chrono::Duration::minutes(5),
+            weight: 100,
+            channel: None,
+        };
+        storage.insert_life_signal(&current).await.unwrap();
+
+        let north = BucketGroup {
+            name: "north".to_string(),
+            buckets: vec!["zone-1".to_string(), "zone-2".to_string()],
+        };
+        let warmth = compute_group_warmth(&storage, &north, 10, None, now)
+            .await
+            .unwrap();
+        assert_eq!(warmth.current_window_total, 100);
+        assert_eq!(warmth.status, WarmthStatus::Stressed);
+        assert_eq!(warmth.uptime, 0.5);
+        assert_eq!(warmth.members.len(), 2);
+
+        storage.set_group("north", &north.buckets).await.unwrap();
+        storage
+            .set_group("south", &["zone-2".to_string()])
+            .await
+            .unwrap();
+
+        // Only the fully silent group raises a group alert
+        let response = generate_alerts(&storage, 60, now).await.unwrap();
+        let group_alerts: Vec<_> = response
+            .alerts
+            .iter()
+            .filter(|a| a.subject == AlertSubject::Group)
+            .collect();
+        assert_eq!(group_alerts.len(), 1);
+        assert_eq!(group_alerts[0].bucket, "south");
+        assert_eq!(group_alerts[0].status, WarmthStatus::Dead);
+    }
+
+    #[tokio::test]
+    async fn test_alert_severity_escalates_with_duration() {
+        use crate::scheduler::WarmthSnapshot;
+
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+
+        // Silent for three hours against a day-long baseline: dead since the
+        // last signal
+        storage
+            .set_baseline("site-quiet", Baseline::Windows(144))
+            .await
+            .unwrap();
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "site-quiet".to_string(),
+                timestamp: now - chrono::Duration::hours(3),
+                weight: 50,
+                channel: None,
+            })
+            .await
+            .unwrap();
+
+        // Trickling in, and collapsing at every snapshot for 13 hours
+        for minutes in [5, 20, 60, 120] {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: "site-trickle".to_string(),
+                    timestamp: now - chrono::Duration::minutes(minutes),
+                    weight: if minutes == 5 { 1 } else { 100 },
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+        storage
+            .set_baseline("site-trickle", Baseline::Windows(18))
+            .await
+            .unwrap();
+        let snapshot = |hours_ago: i64, status| WarmthSnapshot {
+            bucket: "site-trickle".to_string(),
+            window_minutes: 10,
+            taken_at: now - chrono::Duration::hours(hours_ago),
+            status,
+            current_window_total: 0,
+            recent_average: 0.0,
+        };
+        storage
+            .insert_warmth_snapshots(&[
+                snapshot(20, WarmthStatus::Alive),
+                snapshot(13, WarmthStatus::Collapsing),
+                snapshot(6, WarmthStatus::Dead),
+                snapshot(1, WarmthStatus::Collapsing),
+            ])
+            .await
+            .unwrap();
+
+        let response = generate_alerts(&storage, 60, now).await.unwrap();
+        let severity = |bucket| {
+            response
+                .alerts
+                .iter()
+                .find(|a| a.bucket == bucket)
+                .map(|a| a.severity)
+        };
+        assert_eq!(severity("site-quiet"), Some(IssueSeverity::Critical));
+        assert_eq!(severity("site-trickle"), Some(IssueSeverity::Emergency));
+
+        // Tighter tiers escalate sooner
+        let tiers = SeverityTiers::parse("1:2").unwrap();
+        let response = generate_alerts_with_tiers(&storage, 60, &tiers, now)
+            .await
+            .unwrap();
+        assert!(
+            response
+                .alerts
+                .iter()
+                .all(|a| a.severity == IssueSeverity::Emergency)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_degraded_canary_raises_self_health_alert() {
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+
+        // Canary signals stopped 20 minutes ago; stressed is enough to alert
+        for minute in 20..=80 {
+            let signal = LifeSignal {
+                bucket: "_canary:ingest".to_string(),
+                timestamp: now - chrono::Duration::minutes(minute),
+                weight: 1,
+                channel: None,
+            };
+            storage.insert_life_signal(&signal).await.unwrap();
+        }
+
+        let response = generate_alerts(&storage, 60, now).await.unwrap();
+
+        assert_eq!(response.alerts.len(), 1);
+        assert_eq!(response.alerts[0].reason, AlertReason::CanaryDegraded);
+        assert_eq!(response.alerts[0].status, WarmthStatus::Dead);
+    }
+
+    #[tokio::test]
+    async fn test_stalled_pipeline_raises_self_health_alert() {
+        let storage = setup_test_storage().await;
+        let now = Utc::now();
+
+        // The dashboard refresher stopped 20 minutes ago; ingest merely slowed
+        for minute in 1..=80 {
+            let at = now - chrono::Duration::minutes(minute);
+            if minute >= 20 {
+                let dashboard = LifeSignal {
+                    bucket: PIPELINE_DASHBOARD_BUCKET.to_string(),
+                    timestamp: at,
+                    weight: 1,
+                    channel: None,
+                };
+                storage.insert_life_signal(&dashboard).await.unwrap();
+            }
+            let ingest = LifeSignal {
+                bucket: PIPELINE_INGEST_BUCKET.to_string(),
+                timestamp: at,
+                weight: if minute < 10 { 6 } else { 10 },
+                channel: None,
+            };
+            storage.insert_life_signal(&ingest).await.unwrap();
+        }
+
+        let response = generate_alerts(&storage, 60, now).await.unwrap();
+
+        assert_eq!(response.alerts.len(), 1);
+        assert_eq!(response.alerts[0].bucket, PIPELINE_DASHBOARD_BUCKET);
+        assert_eq!(response.alerts[0].reason, AlertReason::PipelineStalled);
+        assert!(response.alerts[0].message.contains("dashboard refresher"));
+    }
 }
@@ -0,0 +1,111 @@
+//! Alert history.
+//!
+//! Alerts are computed on demand from the current warmth of every bucket,
+//! so once a bucket recovers its alert is gone. Every time alerts are
+//! generated they are also recorded here, one entry per episode: an alert
+//! seen again within [`REFIRE_GAP_MINUTES`] of its last sighting extends
+//! its entry's `last_seen`; after a longer quiet spell it opens a new one.
+//! An episode is identified by its bucket (or group), subject and reason;
+//! status, severity and message are those of its latest sighting.
+//!
+//! Alerts are generated by every alert-consuming endpoint and in the
+//! background every `INFRARED_ALERT_CHECK_SECONDS`, by the alert router or,
+//! with no routes configured, by [`spawn_alert_recorder`]. `first_seen` and
+//! `last_seen` are therefore accurate to about that interval while the
+//! server runs.
+//!
+//! # Privacy
+//!
+//! Entries hold exactly what alerts do: bucket- and group-level statuses.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::aggregation::generate_alerts_with_tiers;
+use crate::model::{AlertReason, AlertSubject, IssueSeverity, WarmthStatus};
+use crate::severity::SeverityTiers;
+use crate::storage::Storage;
+
+/// Longest gap between two sightings of an alert that still counts as one
+/// episode.
+pub const REFIRE_GAP_MINUTES: i64 = 10;
+
+/// Default span of `GET /alerts/history`, in days.
+pub const DEFAULT_HISTORY_DAYS: i64 = 7;
+
+/// Most entries returned by `GET /alerts/history`.
+pub const MAX_HISTORY_ENTRIES: u32 = 1000;
+
+/// One alert episode.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertRecord {
+    /// Entry identifier.
+    pub id: i64,
+
+    /// The bucket in distress, or the group name for group alerts.
+    pub bucket: String,
+
+    /// Whether the alert concerned a single bucket or a bucket group.
+    pub subject: AlertSubject,
+
+    /// Why the alert was raised.
+    pub reason: AlertReason,
+
+    /// Status at the latest sighting.
+    pub status: WarmthStatus,
+
+    /// Severity at the latest sighting.
+    pub severity: IssueSeverity,
+
+    /// Message at the latest sighting.
+    pub message: String,
+
+    /// When the alert was first generated.
+    pub first_seen: DateTime<Utc>,
+
+    /// When the alert was last generated.
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Query parameters for `GET /alerts/history`.
+#[derive(Debug, Deserialize)]
+pub struct AlertHistoryQuery {
+    /// Only return this bucket's (or group's) alerts.
+    pub bucket: Option<String>,
+
+    /// Start of the range, inclusive (default: [`DEFAULT_HISTORY_DAYS`]
+    /// before `until`).
+    pub from: Option<DateTime<Utc>>,
+
+    /// End of the range, exclusive (default: now).
+    pub until: Option<DateTime<Utc>>,
+}
+
+/// Response for `GET /alerts/history`.
+#[derive(Debug, Serialize)]
+pub struct AlertHistoryResponse {
+    /// Episodes overlapping the range, most recently seen first.
+    pub alerts: Vec<AlertRecord>,
+}
+
+/// Spawn the task generating (and so recording) alerts every `interval`,
+/// for when no alert router does.
+pub fn spawn_alert_recorder(
+    storage: Storage,
+    tiers: SeverityTiers,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = generate_alerts_with_tiers(&storage, 60, &tiers, Utc::now()).await {
+                warn!(error = %e, "Failed to record alerts");
+            }
+        }
+    })
+}
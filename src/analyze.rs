@@ -0,0 +1,315 @@
+//! Long-term history across archived databases.
+//!
+//! Keeping years of signals in the live database makes every scan, backup
+//! and maintenance run slower. A cheaper arrangement is monthly rotation:
+//! take a backup at the start of each month (`POST /admin/backup`), keep it
+//! as that month's archive, and let retention prune the live database.
+//! `infrared analyze --attach <archive>...` then reads the live database
+//! and the archives together, read-only, for as long as the command runs.
+//!
+//! An archive taken mid-month overlaps the live database, and either may
+//! hold a day only in part (a backup taken during the day, a day partly
+//! pruned). Each UTC day is therefore read from the one database holding
+//! the most signal weight for it, never summed across databases; warmth
+//! snapshots likewise come from the database holding the most for the day.
+//!
+//! The report gives each bucket's daily signal totals and its uptime: the
+//! share of scheduled warmth snapshots (see [`crate::scheduler`]) whose
+//! status was not `dead`.
+//!
+//! # Privacy
+//!
+//! The report holds bucket-level daily totals and snapshot counts only.
+//! Archives are opened read-only and nothing is written.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::partition;
+use crate::storage::{AttachedDatabase, SignalFilter, Storage};
+
+/// Report printed by `infrared analyze`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalysisReport {
+    /// The databases read, and the days taken from each.
+    pub databases: Vec<AnalyzedDatabase>,
+
+    /// Only days at or after this time, if bounded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<DateTime<Utc>>,
+
+    /// Only days before this time, if bounded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<DateTime<Utc>>,
+
+    /// History per bucket, by name.
+    pub buckets: Vec<BucketHistory>,
+}
+
+/// A database read by the analysis.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalyzedDatabase {
+    #[serde(flatten)]
+    pub database: AttachedDatabase,
+
+    /// Days of signals taken from this database.
+    pub days: u64,
+}
+
+/// History of one bucket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BucketHistory {
+    pub bucket: String,
+
+    /// Total signal weight across all days.
+    pub total_weight: i64,
+
+    /// Scheduled warmth snapshots taken.
+    pub snapshots: u64,
+
+    /// Share of snapshots whose status was not `dead`, if any were taken.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime: Option<f64>,
+
+    /// Days with signals, oldest first.
+    pub days: Vec<DayActivity>,
+}
+
+/// Signals of a bucket on one UTC day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayActivity {
+    pub day: NaiveDate,
+
+    /// Signal rows (compacted hourly sums count once).
+    pub signals: u64,
+
+    /// Total weight.
+    pub weight: i64,
+}
+
+/// Build the report from every database open on `storage`.
+pub async fn analyze(storage: &Storage, filter: &SignalFilter) -> anyhow::Result<AnalysisReport> {
+    let databases = storage.attached_databases().await?;
+
+    // Day -> (database index, weight) of the database holding the most
+    let mut signal_source: BTreeMap<i64, (usize, i64)> = BTreeMap::new();
+    let mut snapshot_source: BTreeMap<i64, (usize, u64)> = BTreeMap::new();
+    let mut signals = Vec::with_capacity(databases.len());
+    let mut snapshots = Vec::with_capacity(databases.len());
+    for (index, database) in databases.iter().enumerate() {
+        let daily = storage.daily_signals(&database.schema, filter).await?;
+        let mut weights: HashMap<i64, i64> = HashMap::new();
+        for row in &daily {
+            *weights.entry(row.day).or_default() += row.weight;
+        }
+        for (day, weight) in weights {
+            let best = signal_source.entry(day).or_insert((index, weight));
+            if weight > best.1 {
+                *best = (index, weight);
+            }
+        }
+        signals.push(daily);
+
+        let daily = storage.daily_snapshots(&database.schema, filter).await?;
+        let mut counts: HashMap<i64, u64> = HashMap::new();
+        for row in &daily {
+            *counts.entry(row.day).or_default() += row.snapshots;
+        }
+        for (day, count) in counts {
+            let best = snapshot_source.entry(day).or_insert((index, count));
+            if count > best.1 {
+                *best = (index, count);
+            }
+        }
+        snapshots.push(daily);
+    }
+
+    let mut buckets: BTreeMap<String, BucketHistory> = BTreeMap::new();
+    let history = |buckets: &mut BTreeMap<String, BucketHistory>, bucket: &str| {
+        if !buckets.contains_key(bucket) {
+            buckets.insert(
+                bucket.to_string(),
+                BucketHistory {
+                    bucket: bucket.to_string(),
+                    total_weight: 0,
+                    snapshots: 0,
+                    uptime: None,
+                    days: Vec::new(),
+                },
+            );
+        }
+    };
+
+    let mut days_read = vec![0; databases.len()];
+    for (day, (index, _)) in &signal_source {
+        days_read[*index] += 1;
+        for row in signals[*index].iter().filter(|row| row.day == *day) {
+            history(&mut buckets, &row.bucket);
+            let entry = buckets.get_mut(&row.bucket).expect("just inserted");
+            entry.total_weight += row.weight;
+            entry.days.push(DayActivity {
+                day: date_of(row.day),
+                signals: row.signals,
+                weight: row.weight,
+            });
+        }
+    }
+
+    let mut not_dead: HashMap<String, u64> = HashMap::new();
+    for (day, (index, _)) in &snapshot_source {
+        for row in snapshots[*index].iter().filter(|row| row.day == *day) {
+            history(&mut buckets, &row.bucket);
+            buckets
+                .get_mut(&row.bucket)
+                .expect("just inserted")
+                .snapshots += row.snapshots;
+            *not_dead.entry(row.bucket.clone()).or_default() += row.snapshots - row.dead;
+        }
+    }
+    for entry in buckets.values_mut() {
+        if entry.snapshots > 0 {
+            let alive = not_dead.get(&entry.bucket).copied().unwrap_or_default();
+            entry.uptime = Some(alive as f64 / entry.snapshots as f64);
+        }
+    }
+
+    Ok(AnalysisReport {
+        databases: databases
+            .into_iter()
+            .zip(days_read)
+            .map(|(database, days)| AnalyzedDatabase { database, days })
+            .collect(),
+        from: filter.from,
+        to: filter.to,
+        buckets: buckets.into_values().collect(),
+    })
+}
+
+/// The UTC date of a day number.
+fn date_of(day: i64) -> NaiveDate {
+    DateTime::from_timestamp(day * partition::DAY_SECONDS, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{LifeSignal, WarmthStatus};
+    use crate::scheduler::WarmthSnapshot;
+    use crate::storage::{ConnectionOptions, SignalStore};
+    use chrono::TimeZone;
+
+    fn signal(bucket: &str, day: u32, hour: u32, weight: i32) -> LifeSignal {
+        LifeSignal {
+            bucket: bucket.to_string(),
+            timestamp: Utc.with_ymd_and_hms(2024, 5, day, hour, 0, 0).unwrap(),
+            weight,
+            channel: None,
+        }
+    }
+
+    fn snapshot(day: u32, hour: u32, status: WarmthStatus) -> WarmthSnapshot {
+        WarmthSnapshot {
+            bucket: "zone-a".to_string(),
+            window_minutes: 60,
+            taken_at: Utc.with_ymd_and_hms(2024, 5, day, hour, 0, 0).unwrap(),
+            status,
+            current_window_total: 0,
+            recent_average: 1.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archives_are_read_day_by_day() {
+        let root = std::env::temp_dir().join(format!("infrared-analyze-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let url = |name: &str| format!("sqlite:{}?mode=rwc", root.join(name).display());
+
+        // April's archive was taken on May 2nd at noon
+        let archive = Storage::new(&url("2024-04.db")).await.unwrap();
+        archive
+            .insert_life_signals(&[
+                signal("zone-a", 1, 8, 5),
+                signal("zone-a", 2, 8, 3),
+                signal("zone-b", 2, 9, 1),
+            ])
+            .await
+            .unwrap();
+        archive
+            .insert_warmth_snapshots(&[
+                snapshot(1, 8, WarmthStatus::Alive),
+                snapshot(1, 9, WarmthStatus::Dead),
+            ])
+            .await
+            .unwrap();
+        archive.close().await;
+
+        // Retention has since pruned May 1st, but May 2nd is complete
+        let live = Storage::new(&url("live.db")).await.unwrap();
+        live.insert_life_signals(&[
+            signal("zone-a", 2, 8, 3),
+            signal("zone-b", 2, 9, 1),
+            signal("zone-a", 2, 18, 4),
+            signal("zone-a", 3, 8, 2),
+        ])
+        .await
+        .unwrap();
+        live.insert_warmth_snapshots(&[snapshot(3, 8, WarmthStatus::Alive)])
+            .await
+            .unwrap();
+        live.close().await;
+
+        let storage = Storage::open_with_archives(
+            &url("live.db"),
+            ConnectionOptions::default(),
+            &[root.join("2024-04.db")],
+        )
+        .await
+        .unwrap();
+        let report = analyze(&storage, &SignalFilter::default()).await.unwrap();
+
+        let schemas: Vec<(&str, u64)> = report
+            .databases
+            .iter()
+            .map(|d| (d.database.schema.as_str(), d.days))
+            .collect();
+        assert_eq!(schemas, vec![("main", 2), ("archive1", 1)]);
+
+        let zone_a = &report.buckets[0];
+        assert_eq!(zone_a.bucket, "zone-a");
+        // May 2nd is counted once, from the live database
+        assert_eq!(zone_a.total_weight, 5 + 7 + 2);
+        assert_eq!(
+            zone_a.days.iter().map(|d| d.weight).collect::<Vec<_>>(),
+            vec![5, 7, 2]
+        );
+        assert_eq!(zone_a.snapshots, 3);
+        assert_eq!(zone_a.uptime, Some(2.0 / 3.0));
+        assert_eq!(report.buckets[1].total_weight, 1);
+        assert_eq!(report.buckets[1].uptime, None);
+
+        // Bounded to May 2nd and one bucket
+        let filter = SignalFilter {
+            bucket: Some("zone-a".to_string()),
+            from: Some(Utc.with_ymd_and_hms(2024, 5, 2, 0, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2024, 5, 3, 0, 0, 0).unwrap()),
+        };
+        let report = analyze(&storage, &filter).await.unwrap();
+        assert_eq!(report.buckets.len(), 1);
+        assert_eq!(report.buckets[0].total_weight, 7);
+
+        // Nothing can be written through an analysis connection
+        assert!(
+            storage
+                .insert_life_signal(&signal("zone-a", 4, 8, 1))
+                .await
+                .is_err()
+        );
+
+        storage.close().await;
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -9,7 +9,22 @@
 //!
 //! - **GET /warmth**: Returns aggregate statistics only. No individual signals are exposed.
 //!
-//! - **GET /alerts/recent**: Reports bucket-level status. No user data is revealed.
+//! - **GET /alerts/recent**: Reports bucket- and group-level status. No user data is revealed.
+//! - **GET /alerts/history**: The same alerts, as recorded over time. Nothing new is revealed.
+//!
+//! - **GET /events**: The alerts and dashboard issues above, merged. Nothing new is revealed.
+//!
+//! - **GET /stats**: Bucket-level ingest rates only.
+//!
+//! - **GET /admin/audit**: Admin operations with actor labels only, no network identifiers.
+//!
+//! - **GET /heartbeats**: Bucket-level expected intervals and due times only.
+//!
+//! - **GET /buckets/:bucket/meta**: Operator-written bucket annotations and thresholds only.
+//!
+//! - **GET /switches**: Bucket-level dead-man's switch countdowns only.
+//!
+//! - **GET /metrics**: Operational counters and timings only.
 //!
 //! All logging uses structured tracing that explicitly excludes:
 //! - IP addresses
@@ -17,192 +32,2498 @@
 //! - Session identifiers
 //! - Any personally identifiable information
 
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
 use axum::{
-    Json,
+    Extension, Json, Router,
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
 };
-use chrono::Utc;
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
+use serde::{Deserialize, Serialize};
 use tracing::{info, instrument, warn};
 
-use crate::aggregation::{compute_warmth, generate_alerts};
-use crate::dashboard::{Dashboard, DashboardResponse, IssueSource};
+use crate::aggregation::{
+    attach_channels, attach_group_sparkline, attach_seasonal, attach_sparkline,
+    compute_batch_warmth, compute_group_warmth, compute_warmth_detected,
+    generate_alerts_with_tiers,
+};
+use crate::alert_history::{
+    AlertHistoryQuery, AlertHistoryResponse, DEFAULT_HISTORY_DAYS, MAX_HISTORY_ENTRIES,
+};
+use crate::attribution::{self, AttributionResponse};
+use crate::audit::{self, AuditQuery, AuditResponse, actions};
+use crate::auth::{AuthConfig, Principal, require_admin, require_ingest, require_read};
+use crate::backfill::{
+    self, BackfillAccepted, BackfillRequest, DEFAULT_IDEMPOTENCY_TTL_HOURS, IDEMPOTENCY_KEY_HEADER,
+};
+use crate::backup::{self, BACKUP_CONTENT_TYPE, BackupQuery};
+use crate::bucket_meta::BucketMeta;
+use crate::bundle::{Bundle, BundleQuery, build_bundle};
+use crate::canary::is_canary;
+use crate::cap::{self, ATOM_CONTENT_TYPE, DEFAULT_CAP_SENDER};
+use crate::channel::SignalChannels;
+use crate::clock::IngestClock;
+use crate::dashboard::{Dashboard, DashboardResponse, Issue, IssueSource};
+use crate::deadman::{ArmRequest, SwitchesResponse, list_switches};
+use crate::events::{Event, EventBus};
+use crate::external::{self, IssueAccepted};
+use crate::feed::{self, FeedQuery, FeedResponse};
+use crate::forecast::{ForecastQuery, compute_forecast};
+use crate::heartbeat::{HeartbeatRequest, HeartbeatsResponse, list_heartbeats};
+use crate::incidents::{
+    DEFAULT_INCIDENT_HOURS, Incident, IncidentsQuery, IncidentsResponse, MAX_INCIDENT_HOURS,
+    build_incidents, find_incident,
+};
+use crate::ingest::{BucketIngestStats, IngestTracker, QuotaStatus};
+use crate::journal::Journal;
+use crate::limits::QueryLimits;
+use crate::metrics::Metrics;
 use crate::model::{
-    AlertsQuery, AlertsResponse, LifeSignal, SignalRequest, WarmthQuery, WarmthResponse,
+    Alert, AlertSubject, AlertsQuery, Baseline, BatchWarmthRequest, BatchWarmthResponse,
+    BucketEntry, BucketGroup, Detector, GroupWarmthResponse, IssueSeverity, LifeSignal,
+    SignalRequest, WarmthHistoryQuery, WarmthHistoryResponse, WarmthQuery,
 };
-use crate::storage::Storage;
+use crate::naming::{LintReport, NamingConvention};
+use crate::oidc;
+use crate::public::{PublicMirror, PublicStatus, build_public_status};
+use crate::severity::SeverityTiers;
+use crate::signal_stream::{ExportFormat, stream_signals};
+use crate::situation::{CountryRequest, SituationReport, build_situation, parse_country_code};
+use crate::storage::{SignalFilter, SignalStore, StorageStats};
+use crate::tension::{TensionHistory, TensionQuery};
+use crate::timeline::{
+    Annotation, AnnotationKind, IncidentTimeline, MAX_NOTE_CHARS, NoteRequest,
+    build_incident_timeline,
+};
+use crate::timeouts::{
+    RequestTimeouts, timeout_admin, timeout_dashboard, timeout_ingest, timeout_read,
+};
+use crate::timezone::{TimezoneRequest, parse_timezone};
+use crate::transitions::StatusTracker;
 
 /// Application state shared across handlers.
 #[derive(Clone)]
 pub struct AppState {
-    pub storage: Storage,
+    pub storage: Arc<dyn SignalStore>,
     pub dashboard: Option<Dashboard>,
+    pub metrics: Metrics,
+    pub ingest: IngestTracker,
+    pub auth: AuthConfig,
+    pub events: EventBus,
+    pub transitions: StatusTracker,
+    pub timeouts: RequestTimeouts,
+    pub limits: QueryLimits,
+    pub severity: SeverityTiers,
+    pub cap_sender: String,
+    pub channels: SignalChannels,
+    pub backup_dir: Option<PathBuf>,
+    pub clock: IngestClock,
+    pub journal: Option<Journal>,
+    pub idempotency_ttl: Duration,
+    pub naming: NamingConvention,
+    pub public: Option<PublicMirror>,
+    pub snapshot_interval: Duration,
+}
+
+/// Default least time between dashboard snapshots persisted by
+/// `GET /dashboard`.
+pub const DEFAULT_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+impl AppState {
+    /// Create application state with an empty metrics registry, a private
+    /// event bus, no ingest quotas, default request timeouts, query limits,
+    /// alert severity tiers and CAP sender, no signal channels, no backup
+    /// directory, a fresh ingest clock, no journal, idempotency keys
+    /// remembered for the default time, no bucket naming convention, the
+    /// full endpoint set rather than the public mirror, dashboard snapshots
+    /// persisted at most once per [`DEFAULT_SNAPSHOT_INTERVAL`], and
+    /// authentication disabled.
+    ///
+    /// `storage` is any [`SignalStore`]; [`crate::storage::Storage`] is the
+    /// SQLite one.
+    pub fn new(storage: impl SignalStore + 'static, dashboard: Option<Dashboard>) -> Self {
+        let events = EventBus::new();
+        Self {
+            storage: Arc::new(storage),
+            dashboard,
+            metrics: Metrics::new(),
+            ingest: IngestTracker::new(),
+            auth: AuthConfig::disabled(),
+            transitions: StatusTracker::new().with_events(events.clone()),
+            events,
+            timeouts: RequestTimeouts::default(),
+            limits: QueryLimits::default(),
+            severity: SeverityTiers::default(),
+            cap_sender: DEFAULT_CAP_SENDER.to_string(),
+            channels: SignalChannels::default(),
+            backup_dir: None,
+            clock: IngestClock::new(),
+            journal: None,
+            idempotency_ttl: Duration::from_secs(DEFAULT_IDEMPOTENCY_TTL_HOURS * 3600),
+            naming: NamingConvention::default(),
+            public: None,
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+        }
+    }
+
+    /// Record metrics into `metrics` (e.g., one background tasks also use).
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Append accepted signals to `journal`.
+    pub fn with_journal(mut self, journal: Journal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Remember backfill idempotency keys for `ttl`.
+    pub fn with_idempotency_ttl(mut self, ttl: Duration) -> Self {
+        self.idempotency_ttl = ttl;
+        self
+    }
+
+    /// Lint bucket names against `naming`, and normalize them on ingest if
+    /// it asks for that.
+    pub fn with_naming(mut self, naming: NamingConvention) -> Self {
+        self.naming = naming;
+        self
+    }
+
+    /// Serve only the public mirror's endpoints (see [`crate::public`]).
+    pub fn with_public_mirror(mut self, mirror: PublicMirror) -> Self {
+        self.public = Some(mirror);
+        self
+    }
+
+    /// Persist a live `GET /dashboard` response as a snapshot only if the
+    /// latest snapshot is at least `interval` old.
+    pub fn with_snapshot_interval(mut self, interval: Duration) -> Self {
+        self.snapshot_interval = interval;
+        self
+    }
+
+    /// Write `POST /admin/backup` backups into `dir`.
+    pub fn with_backup_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.backup_dir = Some(dir.into());
+        self
+    }
+
+    /// Accept signals attributed to the given channels.
+    pub fn with_channels(mut self, channels: SignalChannels) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    /// Identify CAP messages as coming from `sender`.
+    pub fn with_cap_sender(mut self, sender: impl Into<String>) -> Self {
+        self.cap_sender = sender.into();
+        self
+    }
+
+    /// Grade alert severity with the given tiers.
+    pub fn with_severity_tiers(mut self, severity: SeverityTiers) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Apply the given query limits.
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Apply the given per-route-class request timeouts.
+    pub fn with_timeouts(mut self, timeouts: RequestTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Use the given ingest tracker (e.g., one enforcing per-bucket quotas).
+    pub fn with_ingest(mut self, ingest: IngestTracker) -> Self {
+        self.ingest = ingest;
+        self
+    }
+
+    /// Require API tokens according to the given configuration.
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Publish events (ingested signals, status changes) on the given bus.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.transitions = self.transitions.with_events(events.clone());
+        self.events = events;
+        self
+    }
+}
+
+/// Build the HTTP router, gating each endpoint group by token role.
+///
+/// - Open: `/health`, and `/auth/*` when OIDC login is configured
+/// - Ingest role: `/signal`, `/ingest/issue`
+/// - Read role: `/warmth`, `/warmth/batch`, `/warmth/history`, `/warmth/forecast`, `/alerts/recent`, `/alerts/history`,
+///   `/alerts/cap`, `/incidents`,
+///   `/incidents/:id/timeline`, `/stats`, `/metrics`, `/heartbeats`,
+///   `/switches`, `/groups`, `/buckets`, `GET /buckets/:bucket/meta`, `/events`, `/dashboard/*`, `/situation/:country`,
+///   `/feed/bundle`
+/// - Admin role: `/admin/*` (including `POST /admin/backup`), `/signals/export`, `/signals/backfill`, `/buckets/:bucket/heartbeat`, `/buckets/:bucket/switch`,
+///   `/buckets/:bucket/baseline`, `/buckets/:bucket/timezone`, `/buckets/:bucket/country`,
+///   `PUT`/`DELETE /buckets/:bucket/meta`, `/buckets/:bucket/archive`, `/groups/:group`, `/incidents/:id/ack`, `/incidents/:id/notes`
+///
+/// Admin tokens pass every role check. SSO sessions are accepted on read
+/// and admin endpoints.
+///
+/// Each group also runs under its route class's time limit (see
+/// [`crate::timeouts`]); `/dashboard/*` has a limit of its own.
+///
+/// Dashboard, situation report and feed bundle routes are only mounted when
+/// a dashboard is configured.
+///
+/// A public mirror (see [`crate::public`]) serves nothing but `/health` and
+/// an open `/public/status`, under the read time limit.
+///
+/// # Privacy Note
+///
+/// No middleware here logs IP addresses or headers.
+pub fn router(state: AppState) -> Router {
+    if state.public.is_some() {
+        return Router::new()
+            .route("/public/status", get(get_public_status))
+            .route_layer(middleware::from_fn_with_state(state.clone(), timeout_read))
+            .route("/health", get(health_check))
+            .with_state(state);
+    }
+
+    let ingest = Router::new()
+        .route("/signal", post(post_signal))
+        .route("/ingest/issue", post(post_issue))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_ingest,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            timeout_ingest,
+        ));
+
+    let read = Router::new()
+        .route("/warmth", get(get_warmth))
+        .route("/warmth/batch", post(post_warmth_batch))
+        .route("/warmth/history", get(get_warmth_history))
+        .route("/warmth/forecast", get(get_warmth_forecast))
+        .route("/alerts/recent", get(get_alerts))
+        .route("/alerts/history", get(get_alert_history))
+        .route("/stats", get(get_stats))
+        .route("/metrics", get(get_metrics))
+        .route("/heartbeats", get(get_heartbeats))
+        .route("/switches", get(get_switches))
+        .route("/groups", get(get_groups))
+        .route("/buckets", get(get_buckets))
+        .route("/buckets/:bucket/meta", get(get_bucket_meta))
+        .route("/events", get(get_events))
+        .route("/alerts/cap", get(get_cap_alerts))
+        .route("/incidents", get(get_incidents))
+        .route("/incidents/:id/timeline", get(get_incident_timeline))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_read))
+        .route_layer(middleware::from_fn_with_state(state.clone(), timeout_read));
+
+    let mut dashboard = Router::new();
+    if state.dashboard.is_some() {
+        dashboard = dashboard
+            .route("/dashboard", get(get_dashboard))
+            .route("/dashboard/summary", get(get_dashboard_summary))
+            .route("/dashboard/attribution", get(get_dashboard_attribution))
+            .route("/dashboard/tension", get(get_tension_history))
+            .route("/dashboard/country/:code", get(get_dashboard_by_country))
+            .route("/dashboard/source/:source", get(get_dashboard_by_source))
+            .route("/situation/:country", get(get_situation))
+            .route("/feed/bundle", get(get_feed_bundle))
+            .route_layer(middleware::from_fn_with_state(state.clone(), require_read))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                timeout_dashboard,
+            ));
+    }
+
+    let admin = Router::new()
+        .route("/admin/audit", get(get_audit))
+        .route("/admin/backup", post(post_backup))
+        .route("/admin/storage/stats", get(get_storage_stats))
+        .route("/admin/buckets/lint", get(get_bucket_lint))
+        .route("/admin/signals.parquet", get(get_signal_export))
+        .route("/signals/export", get(get_signal_stream))
+        .route("/signals/backfill", post(post_backfill))
+        .route("/incidents/:id/ack", post(post_incident_ack))
+        .route("/incidents/:id/notes", post(post_incident_note))
+        .route(
+            "/buckets/:bucket/heartbeat",
+            put(put_heartbeat).delete(delete_heartbeat),
+        )
+        .route(
+            "/buckets/:bucket/switch",
+            put(put_switch).delete(delete_switch),
+        )
+        .route(
+            "/buckets/:bucket/baseline",
+            put(put_baseline).delete(delete_baseline),
+        )
+        .route(
+            "/buckets/:bucket/timezone",
+            put(put_timezone).delete(delete_timezone),
+        )
+        .route(
+            "/buckets/:bucket/country",
+            put(put_country).delete(delete_country),
+        )
+        .route(
+            "/buckets/:bucket/meta",
+            put(put_bucket_meta).delete(delete_bucket_meta),
+        )
+        .route(
+            "/buckets/:bucket/archive",
+            put(put_archive).delete(delete_archive),
+        )
+        .route("/groups/:group", put(put_group).delete(delete_group))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_admin))
+        .route_layer(middleware::from_fn_with_state(state.clone(), timeout_admin));
+
+    let mut open = Router::new().route("/health", get(health_check));
+    if state.auth.oidc().is_some() {
+        open = open
+            .route("/auth/login", get(oidc::login))
+            .route("/auth/callback", get(oidc::callback))
+            .route("/auth/logout", get(oidc::logout));
+    }
+
+    open.merge(ingest)
+        .merge(read)
+        .merge(dashboard)
+        .merge(admin)
+        .with_state(state)
+}
+
+/// POST /signal - Record a life signal.
+///
+/// # Privacy Note
+///
+/// This handler intentionally does NOT:
+/// - Log the client's IP address
+/// - Store any request headers
+/// - Record any identifying information
+///
+/// Only the bucket, weight and channel are stored, with a server-assigned
+/// timestamp. Timestamps never move backwards, even when the system clock
+/// does (see [`crate::clock`]).
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///     "bucket": "zone-a",
+///     "weight": 1,
+///     "channel": "sms"
+/// }
+/// ```
+///
+/// Weight is optional and defaults to 1. Channel is optional and must be
+/// one of the configured signal channels (see [`crate::channel`]). If the
+/// naming convention normalizes names, the bucket is trimmed and lowercased
+/// first (see [`crate::naming`]).
+///
+/// # Response
+///
+/// Returns `202 Accepted` on success, `400 Bad Request` for reserved
+/// `_canary:*` buckets, an unconfigured channel or a client-supplied
+/// `timestamp` (see [`crate::backfill`] for importing history), `429 Too Many
+/// Requests` (with `Retry-After`) if the bucket's per-minute quota is
+/// exhausted, or `503 Service Unavailable` if the signal could not be
+/// stored, so the client retries.
+///
+/// For buckets with a quota, accepted and rejected responses carry
+/// `RateLimit-Limit`, `RateLimit-Remaining`, and `RateLimit-Reset` (seconds
+/// until the minute window resets) so clients can pace themselves.
+#[instrument(skip(state), fields(bucket, weight))]
+pub async fn post_signal(
+    State(state): State<AppState>,
+    Json(mut request): Json<SignalRequest>,
+) -> Response {
+    request.bucket = state.naming.ingest_name(request.bucket);
+
+    // Log only non-identifying information
+    // PRIVACY: We explicitly do NOT log client IP, headers, or any PII
+    tracing::Span::current().record("bucket", &request.bucket);
+    tracing::Span::current().record("weight", request.weight);
+
+    if is_canary(&request.bucket) {
+        warn!(bucket = %request.bucket, "Rejected signal for reserved canary bucket");
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    if request.timestamp.is_some() {
+        warn!(bucket = %request.bucket, "Rejected signal with a client-supplied timestamp");
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    if let Some(channel) = &request.channel
+        && !state.channels.contains(channel)
+    {
+        warn!(bucket = %request.bucket, "Rejected signal for unconfigured channel");
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    // Server-assigned timestamp, never earlier than the last one
+    let stamp = state.clock.stamp(&state.metrics);
+    let signal = LifeSignal {
+        bucket: request.bucket.clone(),
+        timestamp: stamp.timestamp,
+        weight: request.weight,
+        channel: request.channel,
+    };
+
+    let quota = state
+        .ingest
+        .record(&signal.bucket, signal.timestamp, &state.metrics);
+    let mut headers = rate_limit_headers(&quota);
+    if !quota.allowed {
+        warn!(bucket = %signal.bucket, "Bucket ingest quota exceeded");
+        headers.insert(header::RETRY_AFTER, HeaderValue::from(quota.reset_seconds));
+        return (StatusCode::TOO_MANY_REQUESTS, headers).into_response();
+    }
+
+    match state.storage.insert_life_signal(&signal).await {
+        Ok(()) => {
+            info!(
+                bucket = %signal.bucket,
+                weight = signal.weight,
+                clamped = stamp.clamped,
+                "Life signal recorded"
+            );
+            append_to_journal(&state, vec![signal.clone()]).await;
+            state.events.publish(Event::SignalIngested(signal));
+            (StatusCode::ACCEPTED, headers).into_response()
+        }
+        Err(e) => {
+            state.ingest.forget(&signal.bucket);
+            warn!(
+                bucket = %signal.bucket,
+                error = %e,
+                "Failed to record life signal"
+            );
+            StatusCode::SERVICE_UNAVAILABLE.into_response()
+        }
+    }
+}
+
+/// Append accepted signals to the journal, if one is configured, and wait
+/// until they are on disk.
+///
+/// The signals are already stored, so a failed append is logged and
+/// counted in `infrared_journal_failures_total` rather than failing the
+/// request.
+async fn append_to_journal(state: &AppState, signals: Vec<LifeSignal>) {
+    if let Some(journal) = &state.journal
+        && let Err(e) = journal.write(signals).await
+    {
+        warn!(error = %e, "Failed to append signals to the journal");
+        state
+            .metrics
+            .inc_counter("infrared_journal_failures_total", &[], 1.0);
+    }
+}
+
+/// POST /signals/backfill - Import historical signals with their original
+/// timestamps.
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///     "signals": [
+///         { "bucket": "zone-a", "timestamp": "2024-03-01T12:00:00Z", "weight": 1, "channel": "sms" }
+///     ]
+/// }
+/// ```
+///
+/// Weight and channel are optional, as for `POST /signal`. At most
+/// [`backfill::MAX_BACKFILL_SIGNALS`] signals are accepted per request, and
+/// they are written all-or-nothing.
+///
+/// # Headers
+///
+/// - `Idempotency-Key` (optional): A random UUID identifying the batch. A
+///   retry with the same key is not stored again.
+///
+/// # Response
+///
+/// Returns `201 Created` with the number of signals and buckets imported,
+/// `200 OK` with the original counts and `"replayed": true` for a batch
+/// already imported under its idempotency key, or `422 Unprocessable
+/// Entity` naming what failed validation (see [`crate::backfill`]).
+pub async fn post_backfill(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    headers: HeaderMap,
+    Json(mut request): Json<BackfillRequest>,
+) -> Response {
+    for signal in &mut request.signals {
+        signal.bucket = state.naming.ingest_name(std::mem::take(&mut signal.bucket));
+    }
+    let now = Utc::now();
+    let key = match headers
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|key| backfill::parse_idempotency_key(key.to_str().unwrap_or_default()))
+        .transpose()
+    {
+        Ok(key) => key,
+        Err(rejected) => return rejected.into_response(),
+    };
+    let signals = match backfill::accept(request, &state.channels, now) {
+        Ok(signals) => signals,
+        Err(rejected) => {
+            warn!(message = %rejected.message, "Rejected signal backfill");
+            return rejected.into_response();
+        }
+    };
+
+    let stored = match &key {
+        Some(key) => {
+            let since = now
+                - chrono::Duration::from_std(state.idempotency_ttl)
+                    .unwrap_or(chrono::Duration::MAX);
+            state
+                .storage
+                .insert_life_signals_once(key, &signals, now, since)
+                .await
+        }
+        None => state
+            .storage
+            .insert_life_signals(&signals)
+            .await
+            .map(|()| None),
+    };
+    match stored {
+        Ok(None) => {}
+        Ok(Some(batch)) => {
+            info!(signals = batch.signals, "Backfill retry ignored");
+            state
+                .metrics
+                .inc_counter("infrared_backfill_replays_total", &[], 1.0);
+            let accepted = BackfillAccepted {
+                inserted: batch.signals as usize,
+                buckets: batch.buckets as usize,
+                replayed: true,
+            };
+            return (StatusCode::OK, Json(accepted)).into_response();
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to backfill signals");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+    let accepted = BackfillAccepted {
+        inserted: signals.len(),
+        buckets: backfill::bucket_count(&signals),
+        replayed: false,
+    };
+    append_to_journal(&state, signals).await;
+    info!(
+        inserted = accepted.inserted,
+        buckets = accepted.buckets,
+        "Signals backfilled"
+    );
+    let details = format!(
+        "{} signals into {} buckets",
+        accepted.inserted, accepted.buckets
+    );
+    record_audit(&state, principal, actions::SIGNAL_BACKFILL, &details).await;
+    (StatusCode::CREATED, Json(accepted)).into_response()
+}
+
+/// POST /admin/backup - Take a consistent backup of the live database.
+///
+/// # Query Parameters
+///
+/// - `download` (optional): Stream the backup back even when a backup
+///   directory is configured
+///
+/// # Response
+///
+/// With a backup directory configured, returns `201 Created` with where
+/// the backup was written and its size. Otherwise, or with
+/// `download=true`, returns `200 OK` streaming the database file itself
+/// (see [`crate::backup`]).
+pub async fn post_backup(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Query(query): Query<BackupQuery>,
+) -> Response {
+    let now = Utc::now();
+
+    match state.backup_dir.as_deref().filter(|_| !query.download) {
+        Some(dir) => match backup::write_backup(state.storage.as_ref(), dir, now).await {
+            Ok(written) => {
+                info!(path = %written.path.display(), bytes = written.bytes, "Backup written");
+                let details = written.path.display().to_string();
+                record_audit(&state, principal, actions::BACKUP, &details).await;
+                (StatusCode::CREATED, Json(written)).into_response()
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to write backup");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        },
+        None => {
+            let dir = state.backup_dir.clone().unwrap_or_else(std::env::temp_dir);
+            match backup::stream_backup(state.storage.as_ref(), &dir, now).await {
+                Ok((bytes, chunks)) => {
+                    info!(bytes, "Backup streamed");
+                    record_audit(&state, principal, actions::BACKUP, "download").await;
+                    (
+                        [
+                            (header::CONTENT_TYPE, BACKUP_CONTENT_TYPE.to_string()),
+                            (header::CONTENT_LENGTH, bytes.to_string()),
+                            (
+                                header::CONTENT_DISPOSITION,
+                                format!("attachment; filename=\"{}\"", backup::file_name(now)),
+                            ),
+                        ],
+                        Body::from_stream(chunks),
+                    )
+                        .into_response()
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to take backup");
+                    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+                }
+            }
+        }
+    }
+}
+
+/// POST /ingest/issue - Contribute an issue from a partner's own detector.
+///
+/// # Request Body
+///
+/// An issue in the same schema as `/dashboard` issues. `id` is the
+/// partner's own identifier for the event; `source` is replaced with
+/// `external`.
+///
+/// # Response
+///
+/// Returns `201 Created` for a new issue or `200 OK` when an earlier post
+/// with the same `id` was replaced, with the issue's Infrared ID; or `422
+/// Unprocessable Entity` naming what failed validation (see
+/// [`crate::external`]).
+pub async fn post_issue(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Json(issue): Json<Issue>,
+) -> Response {
+    let principal = principal.map(|Extension(p)| p);
+    let actor = audit::actor_label(principal.as_ref());
+    let now = Utc::now();
+
+    let issue = match external::accept(issue, actor, now) {
+        Ok(issue) => issue,
+        Err(rejected) => {
+            warn!(actor, message = %rejected.message, "Rejected external issue");
+            return rejected.into_response();
+        }
+    };
+
+    match state.storage.upsert_external_issue(&issue, now).await {
+        Ok(created) => {
+            info!(
+                id = %issue.id,
+                location_code = %issue.location_code,
+                created,
+                "External issue recorded"
+            );
+            let status = if created {
+                StatusCode::CREATED
+            } else {
+                StatusCode::OK
+            };
+            (
+                status,
+                Json(IssueAccepted {
+                    id: issue.id,
+                    created,
+                }),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            warn!(id = %issue.id, error = %e, "Failed to record external issue");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// `RateLimit-Limit`, `RateLimit-Remaining`, and `RateLimit-Reset` headers
+/// for a bucket's quota status.
+///
+/// Buckets without a quota get no headers.
+fn rate_limit_headers(quota: &QuotaStatus) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let (Some(limit), Some(remaining)) = (quota.limit, quota.remaining) {
+        headers.insert("ratelimit-limit", HeaderValue::from(limit));
+        headers.insert("ratelimit-remaining", HeaderValue::from(remaining));
+        headers.insert("ratelimit-reset", HeaderValue::from(quota.reset_seconds));
+    }
+    headers
+}
+
+/// GET /warmth - Query the warmth index for a bucket or bucket group.
+///
+/// # Query Parameters
+///
+/// - `bucket`: The bucket to query
+/// - `group`: The bucket group to query (instead of `bucket`)
+/// - `window_minutes` (optional): Time window in minutes (default: 10)
+/// - `include` (optional): `sparkline` to add the last N window totals, oldest first
+/// - `sparkline_windows` (optional): Windows in the sparkline (default: 12, max: 100)
+///
+/// - `baseline_windows` (optional): Historical windows to compare against
+/// - `baseline_span` (optional): History to compare against as a span (e.g., `7d`)
+///
+/// Without `baseline_windows` or `baseline_span`, each bucket uses its own
+/// baseline (see `PUT /buckets/:bucket/baseline`), or 6 windows. Giving
+/// both returns `400 Bad Request`.
+///
+/// `include=seasonal` adds `seasonal_average`, the same window at the same
+/// local weekday and time averaged over previous weeks, and the bucket's
+/// `timezone` if one is set. It applies to bucket queries only and does not
+/// change `status`.
+///
+/// `include=channels` adds `channels`, the warmth of each signal channel
+/// (see [`crate::channel`]) over the same window and baseline, so a drop on
+/// one delivery path can be told apart from people no longer checking in.
+/// Unattributed signals are listed with a `null` channel. It applies to
+/// bucket queries only.
+///
+/// `detector=zscore` derives the status from how many standard deviations
+/// the current total lies below the mean of the baseline windows, reported
+/// as `z_score`, instead of from ratio thresholds: more than `sigmas`
+/// below is `collapsing` (or `dead`), more than half that `stressed`.
+/// `detector=mad` does the same from their median and median absolute
+/// deviation, which one past spike cannot skew. Both default to the
+/// server's `INFRARED_DETECTOR` and `INFRARED_DETECTOR_SIGMAS`. Other
+/// detectors than `ratio` apply to bucket queries only; an unknown detector
+/// or a `sigmas` that is not positive returns `400 Bad Request`.
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "bucket": "zone-a",
+///     "window_minutes": 10,
+///     "current_window_total": 42,
+///     "recent_average": 50.5,
+///     "baseline_windows": 6,
+///     "status": "alive"
+/// }
+/// ```
+///
+/// Status can be: "alive", "stressed", "collapsing", or "dead"
+///
+/// Group queries return the summed `current_window_total` and
+/// `recent_average` across members, the derived `status`, an `uptime`
+/// fraction, and per-member warmth under `members`. Unknown groups return
+/// `404 Not Found`; giving both or neither of `bucket` and `group` returns
+/// `400 Bad Request`.
+///
+/// A `window_minutes`, baseline or sparkline over the configured query
+/// limits returns `422 Unprocessable Entity` (see [`crate::limits`]).
+#[instrument(skip(state))]
+pub async fn get_warmth(
+    State(state): State<AppState>,
+    Query(query): Query<WarmthQuery>,
+) -> Result<Response, StatusCode> {
+    let now = Utc::now();
+
+    let sparkline_windows = query
+        .includes("sparkline")
+        .then_some(query.sparkline_windows.clamp(1, MAX_SPARKLINE_WINDOWS));
+    let seasonal = query.includes("seasonal");
+    let channels = query.includes("channels");
+    let baseline = query.baseline().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let (detector, sigmas) = query
+        .detector(state.storage.detector())
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    if let Err(exceeded) =
+        state
+            .limits
+            .check_warmth(query.window_minutes, baseline, sparkline_windows)
+    {
+        return Ok(exceeded.into_response());
+    }
+
+    let bucket = match (query.bucket, query.group) {
+        (Some(bucket), None) => bucket,
+        (None, Some(group)) if query.detector.is_none() || detector == Detector::Ratio => {
+            return get_group_warmth(
+                &state,
+                &group,
+                query.window_minutes,
+                baseline,
+                sparkline_windows,
+                now,
+            )
+            .await
+            .map(IntoResponse::into_response);
+        }
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let result = async {
+        let mut response = compute_warmth_detected(
+            state.storage.as_ref(),
+            &bucket,
+            query.window_minutes,
+            baseline,
+            detector,
+            sigmas,
+            now,
+        )
+        .await?;
+        if let Some(windows) = sparkline_windows {
+            attach_sparkline(state.storage.as_ref(), &mut response, windows, now).await?;
+        }
+        if seasonal {
+            attach_seasonal(state.storage.as_ref(), &mut response, now).await?;
+        }
+        if channels {
+            attach_channels(state.storage.as_ref(), &mut response, now).await?;
+        }
+        state.transitions.observe(&response, now);
+        anyhow::Ok(response)
+    }
+    .await;
+
+    match result {
+        Ok(response) => {
+            info!(
+                bucket = %response.bucket,
+                status = ?response.status,
+                current = response.current_window_total,
+                average = %response.recent_average,
+                "Warmth queried"
+            );
+            Ok(Json(response).into_response())
+        }
+        Err(e) => {
+            warn!(
+                bucket = %bucket,
+                error = %e,
+                "Failed to compute warmth"
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// POST /warmth/batch - Get the warmth of a known set of buckets at once.
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///     "buckets": ["zone-a", "zone-b"],
+///     "window_minutes": 10
+/// }
+/// ```
+///
+/// `window_minutes`, `baseline_windows` and `baseline_span` are optional and
+/// mean the same as on `GET /warmth`; a baseline given here applies to every
+/// bucket. Signals are read with one grouped query rather than one per
+/// bucket.
+///
+/// # Response
+///
+/// `window_minutes` and a `buckets` array of warmth responses in request
+/// order (a bucket named twice is answered once). Unknown buckets report no
+/// activity. An empty `buckets` list or an invalid baseline returns `400 Bad
+/// Request`; more buckets than `INFRARED_MAX_BATCH_BUCKETS`, or a window or
+/// baseline over the query limits, returns `422 Unprocessable Entity`.
+#[instrument(skip(state, request), fields(buckets = request.buckets.len()))]
+pub async fn post_warmth_batch(
+    State(state): State<AppState>,
+    Json(request): Json<BatchWarmthRequest>,
+) -> Result<Response, StatusCode> {
+    let now = Utc::now();
+
+    if request.buckets.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let baseline = request.baseline().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let checked = state
+        .limits
+        .check_batch(request.buckets.len())
+        .and_then(|()| {
+            state
+                .limits
+                .check_warmth(request.window_minutes, baseline, None)
+        });
+    if let Err(exceeded) = checked {
+        return Ok(exceeded.into_response());
+    }
+
+    match compute_batch_warmth(
+        state.storage.as_ref(),
+        &request.buckets,
+        request.window_minutes,
+        baseline,
+        now,
+    )
+    .await
+    {
+        Ok(buckets) => {
+            for warmth in &buckets {
+                state.transitions.observe(warmth, now);
+            }
+            info!(buckets = buckets.len(), "Batch warmth queried");
+            Ok(Json(BatchWarmthResponse {
+                window_minutes: request.window_minutes,
+                buckets,
+            })
+            .into_response())
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to compute batch warmth");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Maximum number of windows in a warmth sparkline.
+const MAX_SPARKLINE_WINDOWS: u32 = 100;
+
+async fn get_group_warmth(
+    state: &AppState,
+    group: &str,
+    window_minutes: u32,
+    baseline: Option<Baseline>,
+    sparkline_windows: Option<u32>,
+    now: DateTime<Utc>,
+) -> Result<Json<GroupWarmthResponse>, StatusCode> {
+    let group = match state.storage.get_group(group).await {
+        Ok(Some(group)) => group,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            warn!(group = %group, error = %e, "Failed to load bucket group");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let result = async {
+        let mut response = compute_group_warmth(
+            state.storage.as_ref(),
+            &group,
+            window_minutes,
+            baseline,
+            now,
+        )
+        .await?;
+        if let Some(windows) = sparkline_windows {
+            attach_group_sparkline(state.storage.as_ref(), &mut response, windows, now).await?;
+        }
+        for member in &response.members {
+            state.transitions.observe(member, now);
+        }
+        anyhow::Ok(response)
+    }
+    .await;
+
+    match result {
+        Ok(response) => {
+            info!(
+                group = %response.group,
+                status = ?response.status,
+                uptime = response.uptime,
+                "Group warmth queried"
+            );
+            Ok(Json(response))
+        }
+        Err(e) => {
+            warn!(group = %group.name, error = %e, "Failed to compute group warmth");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Longest lookback accepted by GET /warmth/history, in hours.
+const MAX_HISTORY_HOURS: u32 = 24 * 30;
+
+/// GET /warmth/history - Scheduled warmth snapshots of a bucket.
+///
+/// # Query Parameters
+///
+/// - `bucket`: The bucket to query
+/// - `hours` (optional): Lookback in hours (default: 24, max: 720)
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "bucket": "zone-a",
+///     "snapshots": [
+///         {
+///             "bucket": "zone-a",
+///             "window_minutes": 10,
+///             "taken_at": "2024-01-15T10:30:00Z",
+///             "status": "alive",
+///             "current_window_total": 42,
+///             "recent_average": 50.5
+///         }
+///     ]
+/// }
+/// ```
+///
+/// Snapshots are taken by the warmth scheduler at every window boundary;
+/// the list is empty if the scheduler is disabled.
+#[instrument(skip(state))]
+pub async fn get_warmth_history(
+    State(state): State<AppState>,
+    Query(query): Query<WarmthHistoryQuery>,
+) -> Result<Json<WarmthHistoryResponse>, StatusCode> {
+    let to = Utc::now();
+    let from = to - chrono::Duration::hours(i64::from(query.hours.min(MAX_HISTORY_HOURS)));
+
+    match state
+        .storage
+        .get_warmth_snapshots(&query.bucket, from, to)
+        .await
+    {
+        Ok(snapshots) => Ok(Json(WarmthHistoryResponse {
+            bucket: query.bucket,
+            snapshots,
+        })),
+        Err(e) => {
+            warn!(bucket = %query.bucket, error = %e, "Failed to load warmth history");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /warmth/forecast - Holt-Winters forecast of a bucket's activity.
+///
+/// # Query Parameters
+///
+/// - `bucket`: The bucket to forecast
+/// - `window_minutes` (optional): Window size (default: 10)
+/// - `days` (optional): Days of history to fit the model on (default: 3)
+/// - `sigmas` (optional): Deviations below the forecast at which the bucket
+///   is underperforming (default: `INFRARED_DETECTOR_SIGMAS`)
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "bucket": "zone-a",
+///     "window_minutes": 10,
+///     "history_windows": 432,
+///     "season_windows": 144,
+///     "current_window_total": 12,
+///     "expected_current": 48.3,
+///     "expected_next": 45.1,
+///     "residual_sd": 6.2,
+///     "deviation": -5.85,
+///     "underperforming": true
+/// }
+/// ```
+///
+/// Returns 422 if `window_minutes` or `days` is over its limit (see
+/// [`crate::limits`]). Forecasts are `null` with fewer than three windows
+/// of history.
+#[instrument(skip(state))]
+pub async fn get_warmth_forecast(
+    State(state): State<AppState>,
+    Query(query): Query<ForecastQuery>,
+) -> Result<Response, StatusCode> {
+    let sigmas = query.sigmas.unwrap_or(state.storage.detector().1);
+    if !(sigmas.is_finite() && sigmas > 0.0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if let Err(exceeded) = state
+        .limits
+        .check_forecast(query.window_minutes, query.days)
+    {
+        return Ok(exceeded.into_response());
+    }
+
+    match compute_forecast(
+        state.storage.as_ref(),
+        &query.bucket,
+        query.window_minutes,
+        query.days,
+        sigmas,
+        Utc::now(),
+    )
+    .await
+    {
+        Ok(forecast) => Ok(Json(forecast).into_response()),
+        Err(e) => {
+            warn!(bucket = %query.bucket, error = %e, "Failed to compute forecast");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /alerts/recent - Get recent alerts for buckets and bucket groups in distress.
+///
+/// # Query Parameters
+///
+/// - `minutes` (optional): Lookback window in minutes (default: 60). Over
+///   the window limit it returns `422 Unprocessable Entity` (see
+///   [`crate::limits`]).
+/// - `group` (optional): Only return alerts for this group and its member buckets
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "alerts": [
+///         {
+///             "bucket": "zone-a",
+///             "subject": "bucket",
+///             "status": "dead",
+///             "last_seen_timestamp": "2024-01-15T10:30:00Z",
+///             "recent_average": 50.0,
+///             "reason": "warmth_drop",
+///             "message": "CRITICAL: Bucket 'zone-a' has gone completely silent...",
+///             "severity": "critical",
+///             "distressed_since": "2024-01-15T10:30:00Z"
+///         }
+///     ],
+///     "lookback_minutes": 60
+/// }
+/// ```
+///
+/// Group alerts have `"subject": "group"` and carry the group name in `bucket`.
+/// `severity` escalates with how long the distress has lasted (see
+/// [`crate::severity`]).
+#[instrument(skip(state))]
+pub async fn get_alerts(
+    State(state): State<AppState>,
+    Query(query): Query<AlertsQuery>,
+) -> Result<Response, StatusCode> {
+    let now = Utc::now();
+    if let Err(exceeded) = state.limits.check_alerts(query.minutes) {
+        return Ok(exceeded.into_response());
+    }
+
+    let group = match &query.group {
+        Some(name) => match state.storage.get_group(name).await {
+            Ok(Some(group)) => Some(group),
+            Ok(None) => return Err(StatusCode::NOT_FOUND),
+            Err(e) => {
+                warn!(group = %name, error = %e, "Failed to load bucket group");
+                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        },
+        None => None,
+    };
+
+    match generate_alerts_with_tiers(state.storage.as_ref(), query.minutes, &state.severity, now)
+        .await
+    {
+        Ok(mut response) => {
+            if let Some(group) = &group {
+                response.alerts.retain(|alert| match alert.subject {
+                    AlertSubject::Group => alert.bucket == group.name,
+                    AlertSubject::Bucket => group.buckets.contains(&alert.bucket),
+                });
+            }
+            info!(
+                alert_count = response.alerts.len(),
+                lookback_minutes = query.minutes,
+                "Alerts queried"
+            );
+            Ok(Json(response).into_response())
+        }
+        Err(e) => {
+            warn!(
+                lookback_minutes = query.minutes,
+                error = %e,
+                "Failed to generate alerts"
+            );
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /alerts/history - Alerts generated in a time range, one entry per
+/// episode (see [`crate::alert_history`]).
+///
+/// # Query Parameters
+///
+/// - `bucket` (optional): Only return this bucket's (or group's) alerts
+/// - `from` (optional): RFC 3339 start, inclusive (default: 7 days before `until`)
+/// - `until` (optional): RFC 3339 end, exclusive (default: now)
+///
+/// Returns the episodes seen in the range, most recently seen first, at
+/// most [`MAX_HISTORY_ENTRIES`]. `from` after `until` returns `400 Bad
+/// Request`.
+pub async fn get_alert_history(
+    State(state): State<AppState>,
+    Query(query): Query<AlertHistoryQuery>,
+) -> Result<Json<AlertHistoryResponse>, StatusCode> {
+    let until = query.until.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| until - chrono::Duration::days(DEFAULT_HISTORY_DAYS));
+    if from > until {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    match state
+        .storage
+        .get_alert_history(query.bucket.as_deref(), from, until, MAX_HISTORY_ENTRIES)
+        .await
+    {
+        Ok(alerts) => Ok(Json(AlertHistoryResponse { alerts })),
+        Err(e) => {
+            warn!(error = %e, "Failed to load alert history");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /events - Bucket alerts and dashboard issues in one feed, most
+/// severe first.
+///
+/// # Query Parameters
+///
+/// - `min_severity` (optional): Only events at or above this severity
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "timestamp": "2024-01-15T12:00:00Z",
+///     "events": [
+///         {
+///             "kind": "bucket_alert",
+///             "id": "bucket:zone-a",
+///             "severity": "critical",
+///             "source": "infrared",
+///             "subject": "zone-a",
+///             "title": "bucket zone-a is dead",
+///             "description": "CRITICAL: Bucket 'zone-a' has gone completely silent...",
+///             "since": "2024-01-15T09:30:00Z"
+///         }
+///     ],
+///     "errors": []
+/// }
+/// ```
+///
+/// Issues are only included when a dashboard is configured. An unknown
+/// `min_severity` returns `400 Bad Request`.
+pub async fn get_events(
+    State(state): State<AppState>,
+    Query(query): Query<FeedQuery>,
+) -> Result<Json<FeedResponse>, StatusCode> {
+    let min_severity = match query.min_severity.as_deref() {
+        Some(name) => IssueSeverity::parse(name).ok_or(StatusCode::BAD_REQUEST)?,
+        None => IssueSeverity::Info,
+    };
+
+    let now = Utc::now();
+    let alerts = generate_alerts_with_tiers(state.storage.as_ref(), 60, &state.severity, now)
+        .await
+        .map_err(|e| {
+            warn!(error = %e, "Failed to generate alerts for the event feed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .alerts;
+
+    let (issues, errors) = match &state.dashboard {
+        Some(dashboard) => {
+            let response = dashboard.get_all_issues().await.map_err(|e| {
+                warn!(error = %e, "Failed to fetch dashboard issues for the event feed");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            (response.issues, response.errors)
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let events = feed::merge(&alerts, &issues, min_severity);
+    info!(event_count = events.len(), "Event feed queried");
+    Ok(Json(FeedResponse {
+        timestamp: now,
+        events,
+        errors,
+    }))
+}
+
+/// GET /alerts/cap - Current alerts as Common Alerting Protocol messages.
+///
+/// Serves an Atom feed whose entries each carry a CAP 1.2 `<alert>`: one
+/// per `dead` or `collapsing` bucket or group over the last 60 minutes, and
+/// one per `emergency` dashboard issue (see [`crate::cap`]).
+#[instrument(skip(state))]
+pub async fn get_cap_alerts(State(state): State<AppState>) -> Response {
+    let now = Utc::now();
+    let alerts =
+        match generate_alerts_with_tiers(state.storage.as_ref(), 60, &state.severity, now).await {
+            Ok(response) => response.alerts,
+            Err(e) => {
+                warn!(error = %e, "Failed to generate alerts for the CAP feed");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+    let issues = match &state.dashboard {
+        Some(dashboard) => match dashboard.get_all_issues().await {
+            Ok(response) => response.issues,
+            Err(e) => {
+                warn!(error = %e, "Failed to fetch dashboard issues for the CAP feed");
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let messages = cap::collect(&alerts, &issues);
+    info!(message_count = messages.len(), "CAP feed queried");
+    (
+        [(header::CONTENT_TYPE, ATOM_CONTENT_TYPE)],
+        cap::atom_feed(&messages, &state.cap_sender, now),
+    )
+        .into_response()
+}
+
+/// GET /incidents - Related bucket alerts grouped into incidents.
+///
+/// # Query Parameters
+///
+/// - `hours` (optional): lookback in hours (default 24, at most 168)
+///
+/// Buckets in the same group or country whose distress overlaps in time
+/// form one incident (see [`crate::incidents`]). Returns `400 Bad Request`
+/// for a lookback of zero or over the maximum.
+#[instrument(skip(state))]
+pub async fn get_incidents(
+    State(state): State<AppState>,
+    Query(query): Query<IncidentsQuery>,
+) -> Result<Json<IncidentsResponse>, StatusCode> {
+    let hours = query.hours.unwrap_or(DEFAULT_INCIDENT_HOURS);
+    if hours == 0 || hours > MAX_INCIDENT_HOURS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let now = Utc::now();
+    let storage = state.storage.as_ref();
+    let incidents = async {
+        let alerts = generate_alerts_with_tiers(storage, 60, &state.severity, now)
+            .await?
+            .alerts;
+        build_incidents(storage, &alerts, hours, now).await
+    }
+    .await
+    .map_err(|e| {
+        warn!(error = %e, "Failed to build incidents");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    info!(incident_count = incidents.len(), hours, "Incidents queried");
+    Ok(Json(IncidentsResponse {
+        timestamp: now,
+        hours,
+        incidents,
+    }))
+}
+
+/// The incident with the given ID, and the current alerts it was found with.
+async fn lookup_incident(
+    state: &AppState,
+    id: &str,
+    now: DateTime<Utc>,
+) -> anyhow::Result<(Option<Incident>, Vec<Alert>)> {
+    let storage = state.storage.as_ref();
+    let alerts = generate_alerts_with_tiers(storage, 60, &state.severity, now)
+        .await?
+        .alerts;
+    let incident = find_incident(storage, &alerts, id, now).await?;
+    Ok((incident, alerts))
+}
+
+/// GET /incidents/:id/timeline - Everything that happened during an incident.
+///
+/// Merges its buckets' status changes, dashboard issues in its countries,
+/// and acknowledgments and notes, oldest first (see [`crate::timeline`]).
+/// Returns `404 Not Found` for an unknown incident.
+#[instrument(skip(state))]
+pub async fn get_incident_timeline(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<IncidentTimeline>, StatusCode> {
+    let now = Utc::now();
+    let timeline = async {
+        let (incident, alerts) = lookup_incident(&state, &id, now).await?;
+        match incident {
+            Some(incident) => build_incident_timeline(
+                state.storage.as_ref(),
+                state.dashboard.as_ref(),
+                incident,
+                &alerts,
+                now,
+            )
+            .await
+            .map(Some),
+            None => Ok(None),
+        }
+    }
+    .await
+    .map_err(|e| {
+        warn!(id = %id, error = %e, "Failed to build incident timeline");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let Some(timeline) = timeline else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    info!(
+        id = %id,
+        entry_count = timeline.entries.len(),
+        "Incident timeline queried"
+    );
+    Ok(Json(timeline))
+}
+
+/// Record an annotation on the incident `id`, if it exists.
+async fn annotate_incident(
+    state: &AppState,
+    principal: Option<Extension<Principal>>,
+    id: &str,
+    kind: AnnotationKind,
+    text: Option<String>,
+) -> Response {
+    let now = Utc::now();
+    let incident = match lookup_incident(state, id, now).await {
+        Ok((incident, _)) => incident,
+        Err(e) => {
+            warn!(id = %id, error = %e, "Failed to look up incident");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if incident.is_none() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let principal = principal.map(|Extension(p)| p);
+    let annotation = Annotation {
+        incident_id: id.to_string(),
+        timestamp: now,
+        kind,
+        actor: audit::actor_label(principal.as_ref()).to_string(),
+        text,
+    };
+    match state.storage.insert_annotation(&annotation).await {
+        Ok(()) => {
+            info!(id = %id, kind = kind.name(), "Incident annotated");
+            (StatusCode::CREATED, Json(annotation)).into_response()
+        }
+        Err(e) => {
+            warn!(id = %id, error = %e, "Failed to annotate incident");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// POST /incidents/:id/ack - Acknowledge an incident.
+///
+/// Returns `201 Created` with the acknowledgment, or `404 Not Found` for an
+/// unknown incident.
+pub async fn post_incident_ack(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(id): Path<String>,
+) -> Response {
+    annotate_incident(&state, principal, &id, AnnotationKind::Acknowledgment, None).await
+}
+
+/// POST /incidents/:id/notes - Add a note to an incident.
+///
+/// Returns `201 Created` with the note, `400 Bad Request` for an empty note
+/// or one over [`MAX_NOTE_CHARS`] characters, or `404 Not Found` for an
+/// unknown incident.
+pub async fn post_incident_note(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(id): Path<String>,
+    Json(request): Json<NoteRequest>,
+) -> Response {
+    let text = request.text.trim();
+    if text.is_empty() || text.chars().count() > MAX_NOTE_CHARS {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+    let text = Some(text.to_string());
+    annotate_incident(&state, principal, &id, AnnotationKind::Note, text).await
+}
+
+/// Response for the groups endpoint.
+#[derive(Debug, Serialize)]
+pub struct GroupsResponse {
+    /// All bucket groups, sorted by name.
+    pub groups: Vec<BucketGroup>,
+}
+
+/// Request body for `PUT /groups/:group`.
+#[derive(Debug, Deserialize)]
+pub struct GroupRequest {
+    /// Member buckets.
+    pub buckets: Vec<String>,
+}
+
+/// GET /groups - List bucket groups and their members.
+pub async fn get_groups(State(state): State<AppState>) -> Result<Json<GroupsResponse>, StatusCode> {
+    match state.storage.get_groups().await {
+        Ok(groups) => Ok(Json(GroupsResponse { groups })),
+        Err(e) => {
+            warn!(error = %e, "Failed to list bucket groups");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// PUT /groups/:group - Create or replace a bucket group.
+///
+/// # Request Body
+///
+/// ```json
+/// { "buckets": ["zone-1", "zone-2", "zone-5"] }
+/// ```
+///
+/// Returns `204 No Content`, or `400 Bad Request` for an empty member list.
+pub async fn put_group(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(group): Path<String>,
+    Json(request): Json<GroupRequest>,
+) -> StatusCode {
+    if request.buckets.is_empty() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    if let Err(e) = state.storage.set_group(&group, &request.buckets).await {
+        warn!(group = %group, error = %e, "Failed to save bucket group");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(group = %group, members = request.buckets.len(), "Bucket group saved");
+    let details = format!("{} = {}", group, request.buckets.join(","));
+    record_audit(&state, principal, actions::GROUP_CHANGE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /groups/:group - Delete a bucket group.
+///
+/// Returns `204 No Content`, or `404 Not Found` if no such group exists.
+pub async fn delete_group(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(group): Path<String>,
+) -> StatusCode {
+    match state.storage.delete_group(&group).await {
+        Ok(true) => {
+            info!(group = %group, "Bucket group deleted");
+            let details = format!("{} deleted", group);
+            record_audit(&state, principal, actions::GROUP_CHANGE, &details).await;
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            warn!(group = %group, error = %e, "Failed to delete bucket group");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// PUT /buckets/:bucket/country - Set the country a bucket reports on.
+///
+/// # Request Body
+///
+/// ```json
+/// { "country": "SD" }
+/// ```
+///
+/// The bucket's warmth and status changes then appear in
+/// `GET /situation/:country`.
+///
+/// Returns `204 No Content`, or `400 Bad Request` for a malformed country
+/// code.
+pub async fn put_country(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+    Json(request): Json<CountryRequest>,
+) -> StatusCode {
+    let Ok(country) = parse_country_code(&request.country) else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    if let Err(e) = state.storage.set_country(&bucket, &country).await {
+        warn!(bucket = %bucket, error = %e, "Failed to set bucket country");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(bucket = %bucket, country = %country, "Bucket country set");
+    let details = format!("{} in {}", bucket, country);
+    record_audit(&state, principal, actions::BUCKET_COUNTRY_CHANGE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /buckets/:bucket/country - Remove a bucket's country.
+///
+/// Returns `204 No Content`, or `404 Not Found` if no country was set.
+pub async fn delete_country(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    match state.storage.clear_country(&bucket).await {
+        Ok(true) => {
+            info!(bucket = %bucket, "Bucket country removed");
+            let details = format!("{} removed", bucket);
+            record_audit(&state, principal, actions::BUCKET_COUNTRY_CHANGE, &details).await;
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            warn!(bucket = %bucket, error = %e, "Failed to remove bucket country");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Query parameters for GET /buckets.
+#[derive(Debug, Deserialize)]
+pub struct BucketsQuery {
+    /// `archived` to list archived buckets too.
+    pub include: Option<String>,
+}
+
+/// Response for the buckets endpoint.
+#[derive(Debug, Serialize)]
+pub struct BucketsResponse {
+    /// Known buckets, sorted by name.
+    pub buckets: Vec<BucketEntry>,
+}
+
+/// GET /buckets - List known buckets.
+///
+/// Archived buckets are left out unless `?include=archived` is given.
+pub async fn get_buckets(
+    State(state): State<AppState>,
+    Query(query): Query<BucketsQuery>,
+) -> Result<Json<BucketsResponse>, StatusCode> {
+    let include_archived = query.include.as_deref() == Some("archived");
+    match state.storage.get_buckets().await {
+        Ok(mut buckets) => {
+            if !include_archived {
+                buckets.retain(|bucket| bucket.archived_at.is_none());
+            }
+            Ok(Json(BucketsResponse { buckets }))
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to list buckets");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// PUT /buckets/:bucket/archive - Archive a decommissioned bucket.
+///
+/// The bucket's history is kept for reporting, but it no longer raises
+/// alerts, its heartbeat and switch are no longer monitored, and it is left
+/// out of `GET /buckets`.
+///
+/// Returns `204 No Content`.
+pub async fn put_archive(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    if let Err(e) = state.storage.archive_bucket(&bucket, Utc::now()).await {
+        warn!(bucket = %bucket, error = %e, "Failed to archive bucket");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(bucket = %bucket, "Bucket archived");
+    let details = format!("{} archived", bucket);
+    record_audit(&state, principal, actions::BUCKET_ARCHIVE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /buckets/:bucket/archive - Return an archived bucket to service.
+///
+/// Returns `204 No Content`, or `404 Not Found` if the bucket was not
+/// archived.
+pub async fn delete_archive(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    match state.storage.unarchive_bucket(&bucket).await {
+        Ok(true) => {
+            info!(bucket = %bucket, "Bucket unarchived");
+            let details = format!("{} unarchived", bucket);
+            record_audit(&state, principal, actions::BUCKET_ARCHIVE, &details).await;
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            warn!(bucket = %bucket, error = %e, "Failed to unarchive bucket");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// GET /health - Simple health check endpoint.
+pub async fn health_check() -> impl IntoResponse {
+    StatusCode::OK
+}
+
+/// GET /public/status - Coarse country and group statuses of a public mirror.
+///
+/// Only countries and labeled groups covering at least the configured
+/// number of buckets are listed, under their display labels (see
+/// [`crate::public`]).
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "generated_at": "2024-05-01T12:00:00Z",
+///     "window_minutes": 10,
+///     "countries": [{ "label": "Sudan", "status": "stressed" }],
+///     "groups": [{ "label": "Northern Districts", "status": "alive" }]
+/// }
+/// ```
+pub async fn get_public_status(
+    State(state): State<AppState>,
+) -> Result<Json<PublicStatus>, StatusCode> {
+    let Some(mirror) = &state.public else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    match build_public_status(state.storage.as_ref(), mirror, Utc::now()).await {
+        Ok(status) => Ok(Json(status)),
+        Err(e) => {
+            warn!(error = %e, "Failed to compute public status");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Response for the stats endpoint.
+#[derive(Debug, Serialize)]
+pub struct StatsResponse {
+    /// Ingest rates for every bucket seen since startup.
+    pub buckets: Vec<BucketIngestStats>,
+}
+
+/// GET /stats - Per-bucket ingest rates and quota usage.
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "buckets": [
+///         {
+///             "bucket": "zone-a",
+///             "current_minute": 12,
+///             "signals_per_minute": 40,
+///             "quota_per_minute": 600,
+///             "rejected_total": 0
+///         }
+///     ]
+/// }
+/// ```
+pub async fn get_stats(State(state): State<AppState>) -> Json<StatsResponse> {
+    Json(StatsResponse {
+        buckets: state.ingest.stats(Utc::now()),
+    })
+}
+
+/// Maximum number of audit entries returned per request.
+const MAX_AUDIT_ENTRIES: u32 = 1000;
+
+/// GET /admin/audit - List recorded admin operations, newest first.
+///
+/// # Query Parameters
+///
+/// - `action` (optional): Only return entries with this action
+/// - `limit` (optional): Maximum entries to return (default: 100, max: 1000)
+///
+/// # Privacy Note
+///
+/// Entries carry only the actor's credential label, never network identifiers.
+pub async fn get_audit(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<AuditResponse>, StatusCode> {
+    match state
+        .storage
+        .get_audit_entries(query.action.as_deref(), query.limit.min(MAX_AUDIT_ENTRIES))
+        .await
+    {
+        Ok(entries) => Ok(Json(AuditResponse { entries })),
+        Err(e) => {
+            warn!(error = %e, "Failed to read audit log");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /admin/storage/stats - Row counts, signal time span and size of the
+/// database (see [`crate::storage::StorageStats`]).
+///
+/// Counting scans every stored signal, so poll this every few minutes at
+/// most.
+pub async fn get_storage_stats(
+    State(state): State<AppState>,
+) -> Result<Json<StorageStats>, StatusCode> {
+    match state.storage.stats().await {
+        Ok(stats) => Ok(Json(stats)),
+        Err(e) => {
+            warn!(error = %e, "Failed to read storage statistics");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /admin/buckets/lint - Check stored bucket names against the naming
+/// convention, reporting violations and likely duplicates (see
+/// [`crate::naming`]).
+pub async fn get_bucket_lint(
+    State(state): State<AppState>,
+) -> Result<Json<LintReport>, StatusCode> {
+    match state.storage.get_buckets().await {
+        Ok(buckets) => Ok(Json(
+            state
+                .naming
+                .lint(buckets.iter().map(|entry| entry.bucket.as_str())),
+        )),
+        Err(e) => {
+            warn!(error = %e, "Failed to list buckets");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// Query parameters for `GET /admin/signals.parquet`.
+#[derive(Debug, Deserialize)]
+pub struct SignalExportQuery {
+    /// Only export this bucket's signals.
+    pub bucket: Option<String>,
+
+    /// Earliest signal timestamp, inclusive (default: the raw history limit
+    /// before `to`).
+    pub from: Option<DateTime<Utc>>,
+
+    /// Latest signal timestamp, exclusive (default: now).
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// GET /admin/signals.parquet - Download raw life signals as Parquet.
+///
+/// # Query Parameters
+///
+/// - `bucket` (optional): Only export this bucket's signals
+/// - `from` (optional): RFC 3339 start, inclusive (default: `to` minus the
+///   raw history limit)
+/// - `to` (optional): RFC 3339 end, exclusive (default: now)
+///
+/// A range longer than the raw history limit returns `422 Unprocessable
+/// Entity` (see [`crate::limits`]); `from` after `to` returns `400 Bad
+/// Request`. Without the `parquet` feature it returns `501 Not
+/// Implemented`.
+pub async fn get_signal_export(
+    State(state): State<AppState>,
+    Query(query): Query<SignalExportQuery>,
+) -> Result<Response, StatusCode> {
+    let filter = match export_filter(&state.limits, query.bucket, query.from, query.to) {
+        Ok(filter) => filter,
+        Err(response) => return Ok(*response),
+    };
+    signal_export_parquet(&state, &filter).await
+}
+
+/// The filter for a raw signal export, defaulting `to` to now and `from` to
+/// the raw history limit before it.
+///
+/// Returns `400 Bad Request` if `from` is after `to`, or the limit's `422`
+/// for a longer range.
+fn export_filter(
+    limits: &QueryLimits,
+    bucket: Option<String>,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> Result<SignalFilter, Box<Response>> {
+    let to = to.unwrap_or_else(Utc::now);
+    let from =
+        from.unwrap_or_else(|| to - chrono::Duration::days(i64::from(limits.max_raw_history_days)));
+    if from > to {
+        return Err(Box::new(StatusCode::BAD_REQUEST.into_response()));
+    }
+    limits
+        .check_export(from, to)
+        .map_err(|exceeded| Box::new(exceeded.into_response()))?;
+
+    Ok(SignalFilter {
+        bucket,
+        from: Some(from),
+        to: Some(to),
+    })
+}
+
+/// Query parameters for `GET /signals/export`.
+#[derive(Debug, Deserialize)]
+pub struct SignalStreamQuery {
+    /// Only export this bucket's signals.
+    pub bucket: Option<String>,
+
+    /// Earliest signal timestamp, inclusive (default: the raw history limit
+    /// before `until`).
+    pub from: Option<DateTime<Utc>>,
+
+    /// Latest signal timestamp, exclusive (default: now).
+    pub until: Option<DateTime<Utc>>,
+
+    /// `ndjson` (default) or `csv`.
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// GET /signals/export - Stream raw life signals as NDJSON or CSV.
+///
+/// # Query Parameters
+///
+/// - `bucket` (optional): Only export this bucket's signals
+/// - `from` (optional): RFC 3339 start, inclusive (default: `until` minus
+///   the raw history limit)
+/// - `until` (optional): RFC 3339 end, exclusive (default: now)
+/// - `format` (optional): `ndjson` (default) or `csv`
+///
+/// Rows are streamed as they are read (see [`crate::signal_stream`]), oldest
+/// first. A range longer than the raw history limit returns `422
+/// Unprocessable Entity`; `from` after `until` or an unknown format returns
+/// `400 Bad Request`.
+pub async fn get_signal_stream(
+    State(state): State<AppState>,
+    Query(query): Query<SignalStreamQuery>,
+) -> Response {
+    let filter = match export_filter(&state.limits, query.bucket, query.from, query.until) {
+        Ok(filter) => filter,
+        Err(response) => return *response,
+    };
+
+    info!(bucket = ?filter.bucket, format = ?query.format, "Life signal export started");
+    let rows = stream_signals(state.storage.clone(), filter, query.format)
+        .inspect_err(|e| warn!(error = %e, "Life signal export failed mid-stream"));
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                query.format.content_type().to_string(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"life_signals.{}\"",
+                    query.format.extension()
+                ),
+            ),
+        ],
+        Body::from_stream(rows),
+    )
+        .into_response()
+}
+
+#[cfg(feature = "parquet")]
+async fn signal_export_parquet(
+    state: &AppState,
+    filter: &SignalFilter,
+) -> Result<Response, StatusCode> {
+    let mut bytes = Vec::new();
+    match crate::signal_export::write_signals(state.storage.as_ref(), filter, &mut bytes).await {
+        Ok(rows) => {
+            info!(bucket = ?filter.bucket, rows, "Life signals exported");
+            Ok((
+                [
+                    (
+                        header::CONTENT_TYPE,
+                        crate::signal_export::PARQUET_CONTENT_TYPE,
+                    ),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        "attachment; filename=\"life_signals.parquet\"",
+                    ),
+                ],
+                bytes,
+            )
+                .into_response())
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to export life signals");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[cfg(not(feature = "parquet"))]
+async fn signal_export_parquet(
+    _state: &AppState,
+    _filter: &SignalFilter,
+) -> Result<Response, StatusCode> {
+    Err(StatusCode::NOT_IMPLEMENTED)
+}
+
+/// GET /heartbeats - List registered heartbeats and whether each is missed.
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "heartbeats": [
+///         {
+///             "bucket": "site-1",
+///             "interval_seconds": 900,
+///             "registered_at": "2024-01-15T09:00:00Z",
+///             "last_seen": "2024-01-15T10:20:00Z",
+///             "due_at": "2024-01-15T10:35:00Z",
+///             "missed": false
+///         }
+///     ]
+/// }
+/// ```
+pub async fn get_heartbeats(
+    State(state): State<AppState>,
+) -> Result<Json<HeartbeatsResponse>, StatusCode> {
+    match list_heartbeats(state.storage.as_ref(), Utc::now()).await {
+        Ok(heartbeats) => Ok(Json(HeartbeatsResponse { heartbeats })),
+        Err(e) => {
+            warn!(error = %e, "Failed to list heartbeats");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// PUT /buckets/:bucket/heartbeat - Register a bucket's expected signal interval.
+///
+/// # Request Body
+///
+/// ```json
+/// { "interval_minutes": 15 }
+/// ```
+///
+/// Once registered, the bucket is reported as `dead` as soon as the interval
+/// passes without a signal. Re-registering replaces the interval and restarts
+/// the grace period for buckets that have never signalled.
+///
+/// # Response
+///
+/// Returns `204 No Content`, or `400 Bad Request` for a zero interval.
+pub async fn put_heartbeat(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+    Json(request): Json<HeartbeatRequest>,
+) -> StatusCode {
+    if request.interval_minutes == 0 {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let interval_seconds = i64::from(request.interval_minutes) * 60;
+    if let Err(e) = state
+        .storage
+        .set_heartbeat(&bucket, interval_seconds, Utc::now())
+        .await
+    {
+        warn!(bucket = %bucket, error = %e, "Failed to register heartbeat");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(bucket = %bucket, interval_seconds, "Heartbeat registered");
+    let details = format!("{} every {}m", bucket, request.interval_minutes);
+    record_audit(&state, principal, actions::HEARTBEAT_CHANGE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /buckets/:bucket/heartbeat - Stop expecting heartbeats from a bucket.
+///
+/// Returns `204 No Content`, or `404 Not Found` if none was registered.
+pub async fn delete_heartbeat(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    match state.storage.clear_heartbeat(&bucket).await {
+        Ok(true) => {
+            info!(bucket = %bucket, "Heartbeat removed");
+            let details = format!("{} removed", bucket);
+            record_audit(&state, principal, actions::HEARTBEAT_CHANGE, &details).await;
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            warn!(bucket = %bucket, error = %e, "Failed to remove heartbeat");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// PUT /buckets/:bucket/baseline - Set how much history the bucket's warmth
+/// is compared against.
+///
+/// # Request Body
+///
+/// ```json
+/// { "span_minutes": 40320 }
+/// ```
+///
+/// or `{ "windows": 48 }` for a fixed number of windows.
+///
+/// # Response
+///
+/// Returns `204 No Content`, or `400 Bad Request` for a zero baseline.
+pub async fn put_baseline(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+    Json(baseline): Json<Baseline>,
+) -> StatusCode {
+    if matches!(baseline, Baseline::Windows(0) | Baseline::SpanMinutes(0)) {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    if let Err(e) = state.storage.set_baseline(&bucket, baseline).await {
+        warn!(bucket = %bucket, error = %e, "Failed to set baseline");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(bucket = %bucket, baseline = ?baseline, "Baseline set");
+    let details = match baseline {
+        Baseline::Windows(windows) => format!("{} over {} windows", bucket, windows),
+        Baseline::SpanMinutes(span) => format!("{} over {}m", bucket, span),
+    };
+    record_audit(&state, principal, actions::BASELINE_CHANGE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /buckets/:bucket/baseline - Return a bucket to the default baseline.
+///
+/// Returns `204 No Content`, or `404 Not Found` if no baseline was set.
+pub async fn delete_baseline(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    match state.storage.clear_baseline(&bucket).await {
+        Ok(true) => {
+            info!(bucket = %bucket, "Baseline removed");
+            let details = format!("{} removed", bucket);
+            record_audit(&state, principal, actions::BASELINE_CHANGE, &details).await;
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            warn!(bucket = %bucket, error = %e, "Failed to remove baseline");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
 }
 
-/// POST /signal - Record a life signal.
-///
-/// # Privacy Note
-///
-/// This handler intentionally does NOT:
-/// - Log the client's IP address
-/// - Store any request headers
-/// - Record any identifying information
-///
-/// Only the bucket and weight are stored, with a server-assigned timestamp.
+/// PUT /buckets/:bucket/timezone - Set the bucket's local timezone.
 ///
 /// # Request Body
 ///
 /// ```json
-/// {
-///     "bucket": "zone-a",
-///     "weight": 1
-/// }
+/// { "timezone": "Africa/Nairobi" }
 /// ```
 ///
-/// Weight is optional and defaults to 1.
+/// Seasonal baselines (`/warmth?include=seasonal`) are then computed in
+/// local time instead of UTC.
 ///
 /// # Response
 ///
-/// Returns `202 Accepted` on success.
-#[instrument(skip(state), fields(bucket, weight))]
-pub async fn post_signal(
+/// Returns `204 No Content`, or `400 Bad Request` for an unknown IANA name.
+pub async fn put_timezone(
     State(state): State<AppState>,
-    Json(request): Json<SignalRequest>,
-) -> impl IntoResponse {
-    // Log only non-identifying information
-    // PRIVACY: We explicitly do NOT log client IP, headers, or any PII
-    tracing::Span::current().record("bucket", &request.bucket);
-    tracing::Span::current().record("weight", request.weight);
-
-    let signal = LifeSignal {
-        bucket: request.bucket.clone(),
-        timestamp: Utc::now(), // Server-assigned timestamp
-        weight: request.weight,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+    Json(request): Json<TimezoneRequest>,
+) -> StatusCode {
+    let tz = match parse_timezone(&request.timezone) {
+        Ok(tz) => tz,
+        Err(_) => return StatusCode::BAD_REQUEST,
     };
 
-    match state.storage.insert_life_signal(&signal).await {
-        Ok(()) => {
-            info!(
-                bucket = %signal.bucket,
-                weight = signal.weight,
-                "Life signal recorded"
-            );
-            StatusCode::ACCEPTED
+    if let Err(e) = state.storage.set_timezone(&bucket, tz.name()).await {
+        warn!(bucket = %bucket, error = %e, "Failed to set timezone");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(bucket = %bucket, timezone = %tz.name(), "Timezone set");
+    let details = format!("{} in {}", bucket, tz.name());
+    record_audit(&state, principal, actions::TIMEZONE_CHANGE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /buckets/:bucket/timezone - Return a bucket to UTC.
+///
+/// Returns `204 No Content`, or `404 Not Found` if no timezone was set.
+pub async fn delete_timezone(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    match state.storage.clear_timezone(&bucket).await {
+        Ok(true) => {
+            info!(bucket = %bucket, "Timezone removed");
+            let details = format!("{} removed", bucket);
+            record_audit(&state, principal, actions::TIMEZONE_CHANGE, &details).await;
+            StatusCode::NO_CONTENT
         }
+        Ok(false) => StatusCode::NOT_FOUND,
         Err(e) => {
-            warn!(
-                bucket = %signal.bucket,
-                error = %e,
-                "Failed to record life signal"
-            );
+            warn!(bucket = %bucket, error = %e, "Failed to remove timezone");
             StatusCode::INTERNAL_SERVER_ERROR
         }
     }
 }
 
-/// GET /warmth - Query the warmth index for a bucket.
-///
-/// # Query Parameters
-///
-/// - `bucket` (required): The bucket to query
-/// - `window_minutes` (optional): Time window in minutes (default: 10)
+/// GET /buckets/:bucket/meta - Get the bucket's metadata.
 ///
 /// # Response
 ///
 /// ```json
 /// {
-///     "bucket": "zone-a",
-///     "window_minutes": 10,
-///     "current_window_total": 42,
-///     "recent_average": 50.5,
-///     "status": "alive"
+///     "display_name": "Gulu market",
+///     "description": "Stallholders' check-ins at the central market",
+///     "expected_interval_minutes": 15,
+///     "thresholds": { "stressed_ratio": 0.5, "collapsing_ratio": 0.1 }
 /// }
 /// ```
 ///
-/// Status can be: "alive", "stressed", "collapsing", or "dead"
-#[instrument(skip(state))]
-pub async fn get_warmth(
+/// Unset fields are left out. Returns `404 Not Found` if no metadata is set.
+pub async fn get_bucket_meta(
     State(state): State<AppState>,
-    Query(query): Query<WarmthQuery>,
-) -> Result<Json<WarmthResponse>, StatusCode> {
-    let now = Utc::now();
-
-    match compute_warmth(&state.storage, &query.bucket, query.window_minutes, now).await {
-        Ok(response) => {
-            info!(
-                bucket = %response.bucket,
-                status = ?response.status,
-                current = response.current_window_total,
-                average = %response.recent_average,
-                "Warmth queried"
-            );
-            Ok(Json(response))
-        }
+    Path(bucket): Path<String>,
+) -> Result<Json<BucketMeta>, StatusCode> {
+    match state.storage.get_bucket_meta(&bucket).await {
+        Ok(Some(meta)) => Ok(Json(meta)),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
         Err(e) => {
-            warn!(
-                bucket = %query.bucket,
-                error = %e,
-                "Failed to compute warmth"
-            );
+            warn!(bucket = %bucket, error = %e, "Failed to get bucket metadata");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
-/// GET /alerts/recent - Get recent alerts for buckets in distress.
+/// PUT /buckets/:bucket/meta - Set (or replace) the bucket's metadata.
 ///
-/// # Query Parameters
+/// The body has the shape `GET` returns; every field is optional and
+/// fields left out are cleared. `thresholds` replaces the default warmth
+/// thresholds for this bucket; a ratio left out of it takes its default.
+///
+/// # Response
+///
+/// Returns `204 No Content`, or `400 Bad Request` for an empty body, a
+/// zero interval, over-long text or thresholds outside
+/// `0 < collapsing_ratio < stressed_ratio <= 1`.
+pub async fn put_bucket_meta(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+    Json(meta): Json<BucketMeta>,
+) -> StatusCode {
+    if meta.is_empty() || meta.validate().is_err() {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    if let Err(e) = state.storage.set_bucket_meta(&bucket, &meta).await {
+        warn!(bucket = %bucket, error = %e, "Failed to set bucket metadata");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(bucket = %bucket, "Bucket metadata set");
+    let details = match meta.thresholds {
+        Some(t) => format!(
+            "{} with thresholds {}/{}",
+            bucket, t.stressed_ratio, t.collapsing_ratio
+        ),
+        None => bucket.clone(),
+    };
+    record_audit(&state, principal, actions::BUCKET_META_CHANGE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /buckets/:bucket/meta - Remove the bucket's metadata, returning
+/// it to the default thresholds.
 ///
-/// - `minutes` (optional): Lookback window in minutes (default: 60)
+/// Returns `204 No Content`, or `404 Not Found` if no metadata was set.
+pub async fn delete_bucket_meta(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    match state.storage.clear_bucket_meta(&bucket).await {
+        Ok(true) => {
+            info!(bucket = %bucket, "Bucket metadata removed");
+            let details = format!("{} removed", bucket);
+            record_audit(&state, principal, actions::BUCKET_META_CHANGE, &details).await;
+            StatusCode::NO_CONTENT
+        }
+        Ok(false) => StatusCode::NOT_FOUND,
+        Err(e) => {
+            warn!(bucket = %bucket, error = %e, "Failed to remove bucket metadata");
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// GET /switches - List armed dead-man's switches and their countdowns.
 ///
 /// # Response
 ///
 /// ```json
 /// {
-///     "alerts": [
+///     "switches": [
 ///         {
-///             "bucket": "zone-a",
-///             "status": "dead",
-///             "last_seen_timestamp": "2024-01-15T10:30:00Z",
-///             "recent_average": 50.0,
-///             "message": "CRITICAL: Bucket 'zone-a' has gone completely silent..."
+///             "bucket": "site-1",
+///             "countdown_seconds": 3600,
+///             "armed_at": "2024-01-15T09:00:00Z",
+///             "last_seen": "2024-01-15T10:20:00Z",
+///             "deadline": "2024-01-15T11:20:00Z",
+///             "expired": false,
+///             "notifications_sent": 0
 ///         }
-///     ],
-///     "lookback_minutes": 60
+///     ]
 /// }
 /// ```
-#[instrument(skip(state))]
-pub async fn get_alerts(
+pub async fn get_switches(
     State(state): State<AppState>,
-    Query(query): Query<AlertsQuery>,
-) -> Result<Json<AlertsResponse>, StatusCode> {
-    let now = Utc::now();
+) -> Result<Json<SwitchesResponse>, StatusCode> {
+    match list_switches(state.storage.as_ref(), Utc::now()).await {
+        Ok(switches) => Ok(Json(SwitchesResponse { switches })),
+        Err(e) => {
+            warn!(error = %e, "Failed to list switches");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
 
-    match generate_alerts(&state.storage, query.minutes, now).await {
-        Ok(response) => {
-            info!(
-                alert_count = response.alerts.len(),
-                lookback_minutes = query.minutes,
-                "Alerts queried"
-            );
-            Ok(Json(response))
+/// PUT /buckets/:bucket/switch - Arm a bucket's dead-man's switch.
+///
+/// # Request Body
+///
+/// ```json
+/// { "countdown_minutes": 60 }
+/// ```
+///
+/// Every accepted signal for the bucket resets the countdown. When it runs
+/// out, the configured escalation sequence fires. Re-arming restarts both.
+///
+/// # Response
+///
+/// Returns `204 No Content`, or `400 Bad Request` for a zero countdown.
+pub async fn put_switch(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+    Json(request): Json<ArmRequest>,
+) -> StatusCode {
+    if request.countdown_minutes == 0 {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let countdown_seconds = i64::from(request.countdown_minutes) * 60;
+    if let Err(e) = state
+        .storage
+        .arm_switch(&bucket, countdown_seconds, Utc::now())
+        .await
+    {
+        warn!(bucket = %bucket, error = %e, "Failed to arm switch");
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+
+    info!(bucket = %bucket, countdown_seconds, "Dead-man's switch armed");
+    let details = format!("{} armed for {}m", bucket, request.countdown_minutes);
+    record_audit(&state, principal, actions::SWITCH_CHANGE, &details).await;
+    StatusCode::NO_CONTENT
+}
+
+/// DELETE /buckets/:bucket/switch - Disarm a bucket's dead-man's switch.
+///
+/// Returns `204 No Content`, or `404 Not Found` if none was armed.
+pub async fn delete_switch(
+    State(state): State<AppState>,
+    principal: Option<Extension<Principal>>,
+    Path(bucket): Path<String>,
+) -> StatusCode {
+    match state.storage.disarm_switch(&bucket).await {
+        Ok(true) => {
+            info!(bucket = %bucket, "Dead-man's switch disarmed");
+            let details = format!("{} disarmed", bucket);
+            record_audit(&state, principal, actions::SWITCH_CHANGE, &details).await;
+            StatusCode::NO_CONTENT
         }
+        Ok(false) => StatusCode::NOT_FOUND,
         Err(e) => {
-            warn!(
-                lookback_minutes = query.minutes,
-                error = %e,
-                "Failed to generate alerts"
-            );
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            warn!(bucket = %bucket, error = %e, "Failed to disarm switch");
+            StatusCode::INTERNAL_SERVER_ERROR
         }
     }
 }
 
-/// GET /health - Simple health check endpoint.
-pub async fn health_check() -> impl IntoResponse {
-    StatusCode::OK
+/// Record an admin operation, logging (but not failing on) storage errors.
+async fn record_audit(
+    state: &AppState,
+    principal: Option<Extension<Principal>>,
+    action: &str,
+    details: &str,
+) {
+    let principal = principal.map(|Extension(p)| p);
+    if let Err(e) = audit::record(
+        state.storage.as_ref(),
+        principal.as_ref(),
+        action,
+        Some(details),
+    )
+    .await
+    {
+        warn!(action, error = %e, "Failed to record audit entry");
+    }
+}
+
+/// GET /metrics - Operational metrics in the Prometheus text format.
+///
+/// # Privacy Note
+///
+/// Only operational counters and timings are exposed; no signal-level data.
+pub async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    state.ingest.publish_rates(&state.metrics, Utc::now());
+
+    (
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; version=0.0.4",
+        )],
+        state.metrics.render(),
+    )
 }
 
 // ============================================================================
@@ -212,18 +2533,22 @@ pub async fn health_check() -> impl IntoResponse {
 /// Query parameters for the dashboard endpoint.
 #[derive(Debug, Deserialize)]
 pub struct DashboardQuery {
-    /// Filter by source (ioda, cloudflare_radar, hdx_hapi, acled, reliefweb).
+    /// Filter by source (ioda, cloudflare_radar, hdx_hapi, acled, reliefweb, iom_dtm, tor_metrics, wikimedia, acaps, external).
     pub source: Option<String>,
     /// Filter by country code.
     pub country: Option<String>,
+    /// Reconstruct the issue set as it was at this time from persisted snapshots.
+    pub at: Option<DateTime<Utc>>,
 }
 
 /// GET /dashboard - Get aggregated issues from all data sources.
 ///
 /// # Query Parameters
 ///
-/// - `source` (optional): Filter by source (ioda, cloudflare_radar, hdx_hapi, acled, reliefweb)
+/// - `source` (optional): Filter by source (ioda, cloudflare_radar, hdx_hapi, acled, reliefweb, iom_dtm, tor_metrics, wikimedia, acaps, external)
 /// - `country` (optional): Filter by country code
+/// - `at` (optional): RFC 3339 timestamp; serve the latest persisted snapshot taken at or
+///   before this time instead of querying the sources live
 ///
 /// # Response
 ///
@@ -232,6 +2557,13 @@ pub struct DashboardQuery {
 /// - `summary`: Summary statistics (counts by severity, source, category)
 /// - `issues`: List of issues sorted by severity and timestamp
 /// - `errors`: Any errors encountered while fetching from sources
+/// - `health`: Per source, whether it was fetched and how many records were
+///   skipped as malformed (`parse_warnings`); empty when filtered
+///
+/// A source that fails keeps its issues from the latest snapshot if it last
+/// succeeded within the lookback window, and is marked `stale` in `health`
+/// besides being listed in `errors`. Filtered
+/// by a source that fails, the response is `503 Service Unavailable`.
 #[instrument(skip(state))]
 pub async fn get_dashboard(
     State(state): State<AppState>,
@@ -242,16 +2574,54 @@ pub async fn get_dashboard(
         StatusCode::SERVICE_UNAVAILABLE
     })?;
 
+    // Time-travel: answer from the persisted snapshot instead of live sources
+    if let Some(at) = query.at {
+        let source = match &query.source {
+            Some(source_str) => Some(IssueSource::parse(source_str).ok_or_else(|| {
+                warn!(source = %source_str, "Invalid source filter");
+                StatusCode::BAD_REQUEST
+            })?),
+            None => None,
+        };
+
+        return match state.storage.get_dashboard_snapshot_at(at).await {
+            Ok(Some(snapshot)) => {
+                let response = snapshot.filtered(
+                    query.country.as_deref(),
+                    source,
+                    dashboard.tension_weights(),
+                );
+                info!(
+                    at = %at,
+                    snapshot_timestamp = %response.timestamp,
+                    issue_count = response.issues.len(),
+                    "Dashboard snapshot queried"
+                );
+                Ok(Json(response))
+            }
+            Ok(None) => {
+                info!(at = %at, "No dashboard snapshot at requested time");
+                Err(StatusCode::NOT_FOUND)
+            }
+            Err(e) => {
+                warn!(at = %at, error = %e, "Failed to load dashboard snapshot");
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            }
+        };
+    }
+
     // Filter by country if specified
     if let Some(country) = &query.country {
         match dashboard.get_issues_by_country(country).await {
             Ok(issues) => {
-                let summary = crate::dashboard::DashboardSummary::from_issues(&issues);
+                let summary = dashboard.summarize(&issues);
                 let response = DashboardResponse {
                     timestamp: Utc::now(),
                     summary,
                     issues,
                     errors: vec![],
+                    health: vec![],
+                    suppressed: vec![],
                 };
                 info!(
                     country = %country,
@@ -269,26 +2639,21 @@ pub async fn get_dashboard(
 
     // Filter by source if specified
     if let Some(source_str) = &query.source {
-        let source = match source_str.as_str() {
-            "ioda" => IssueSource::Ioda,
-            "cloudflare_radar" | "cloudflare" => IssueSource::CloudflareRadar,
-            "hdx_hapi" | "hdx" | "hapi" => IssueSource::HdxHapi,
-            "acled" => IssueSource::Acled,
-            "reliefweb" => IssueSource::ReliefWeb,
-            _ => {
-                warn!(source = %source_str, "Invalid source filter");
-                return Err(StatusCode::BAD_REQUEST);
-            }
+        let Some(source) = IssueSource::parse(source_str) else {
+            warn!(source = %source_str, "Invalid source filter");
+            return Err(StatusCode::BAD_REQUEST);
         };
 
         match dashboard.get_issues_by_source(source).await {
             Ok(issues) => {
-                let summary = crate::dashboard::DashboardSummary::from_issues(&issues);
+                let summary = dashboard.summarize(&issues);
                 let response = DashboardResponse {
                     timestamp: Utc::now(),
                     summary,
                     issues,
                     errors: vec![],
+                    health: vec![],
+                    suppressed: vec![],
                 };
                 info!(
                     source = %source_str,
@@ -299,29 +2664,115 @@ pub async fn get_dashboard(
             }
             Err(e) => {
                 warn!(source = %source_str, error = %e, "Failed to fetch dashboard by source");
-                return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                return Err(StatusCode::SERVICE_UNAVAILABLE);
             }
         }
     }
 
-    // Get all issues
-    match dashboard.get_all_issues().await {
-        Ok(response) => {
+    // Get all issues; failing sources fall back to the latest snapshot's
+    let previous = match state.storage.get_dashboard_snapshot_at(Utc::now()).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            warn!(error = %e, "Failed to load latest dashboard snapshot");
+            None
+        }
+    };
+    let response = dashboard.get_all_issues_or_stale(previous.as_ref()).await;
+    info!(
+        issue_count = response.issues.len(),
+        error_count = response.errors.len(),
+        "Dashboard queried"
+    );
+
+    // Live fetches double as snapshots for later time-travel queries, at
+    // most one per snapshot interval however often clients poll
+    let interval = chrono::Duration::from_std(state.snapshot_interval).unwrap_or_default();
+    if previous.is_none_or(|p| response.timestamp - p.timestamp >= interval)
+        && let Err(e) = state.storage.insert_dashboard_snapshot(&response).await
+    {
+        warn!(error = %e, "Failed to persist dashboard snapshot");
+    }
+
+    Ok(Json(response))
+}
+
+/// GET /situation/:country - One-page situation report for a country.
+///
+/// Combines the country's dashboard issues, a composite risk score, the
+/// warmth of buckets reporting on it, their recent status changepoints and
+/// the latest ReliefWeb reports (see [`crate::situation`]).
+///
+/// `GET /situation/:country.pdf` serves the same report as a printable
+/// brief (see [`crate::brief`]); without the `pdf` feature it returns
+/// `501 Not Implemented`.
+///
+/// Returns `400 Bad Request` for a malformed country code.
+pub async fn get_situation(
+    State(state): State<AppState>,
+    Path(country): Path<String>,
+) -> Result<Response, StatusCode> {
+    let dashboard = state.dashboard.as_ref().ok_or_else(|| {
+        warn!("Dashboard not configured");
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+    let (country, pdf) = match country.strip_suffix(".pdf") {
+        Some(country) => (country, true),
+        None => (country.as_str(), false),
+    };
+    let country = parse_country_code(country).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    match build_situation(state.storage.as_ref(), dashboard, &country, Utc::now()).await {
+        Ok(report) => {
             info!(
-                issue_count = response.issues.len(),
-                error_count = response.errors.len(),
-                "Dashboard queried"
+                country = %country,
+                issue_count = report.issues.len(),
+                bucket_count = report.buckets.len(),
+                pdf,
+                "Situation report queried"
             );
-            Ok(Json(response))
+            if pdf {
+                return situation_pdf(&report);
+            }
+            Ok(Json(report).into_response())
         }
         Err(e) => {
-            warn!(error = %e, "Failed to fetch dashboard");
+            warn!(country = %country, error = %e, "Failed to build situation report");
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
 }
 
+#[cfg(feature = "pdf")]
+fn situation_pdf(report: &SituationReport) -> Result<Response, StatusCode> {
+    match crate::brief::render_pdf(report) {
+        Ok(bytes) => Ok((
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("inline; filename=\"situation-{}.pdf\"", report.country),
+                ),
+            ],
+            bytes,
+        )
+            .into_response()),
+        Err(e) => {
+            warn!(country = %report.country, error = %e, "Failed to render situation brief");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+#[cfg(not(feature = "pdf"))]
+fn situation_pdf(_report: &SituationReport) -> Result<Response, StatusCode> {
+    Err(StatusCode::NOT_IMPLEMENTED)
+}
+
 /// GET /dashboard/summary - Get just the summary statistics.
+///
+/// The summary also carries `trends`: the daily issue counts of each of its
+/// categories and top countries over the last seven UTC days, from
+/// persisted snapshots (see [`crate::dashboard::DashboardSummary::attach_trends`]).
 #[instrument(skip(state))]
 pub async fn get_dashboard_summary(
     State(state): State<AppState>,
@@ -331,15 +2782,24 @@ pub async fn get_dashboard_summary(
         StatusCode::SERVICE_UNAVAILABLE
     })?;
 
-    match dashboard.get_all_issues().await {
-        Ok(response) => {
+    let result = async {
+        let mut summary = dashboard.get_all_issues().await?.summary;
+        summary
+            .attach_trends(state.storage.as_ref(), Utc::now())
+            .await?;
+        anyhow::Ok(summary)
+    }
+    .await;
+
+    match result {
+        Ok(summary) => {
             info!(
-                total_issues = response.summary.total_issues,
-                emergency_count = response.summary.emergency_count,
-                critical_count = response.summary.critical_count,
+                total_issues = summary.total_issues,
+                emergency_count = summary.emergency_count,
+                critical_count = summary.critical_count,
                 "Dashboard summary queried"
             );
-            Ok(Json(response.summary))
+            Ok(Json(summary))
         }
         Err(e) => {
             warn!(error = %e, "Failed to fetch dashboard summary");
@@ -348,6 +2808,109 @@ pub async fn get_dashboard_summary(
     }
 }
 
+/// GET /dashboard/attribution - License and credit line of every source.
+///
+/// # Response
+///
+/// ```json
+/// {
+///     "sources": [
+///         {
+///             "source": "cloudflare_radar",
+///             "provider": "Cloudflare, Inc.",
+///             "license": "CC BY-NC 4.0",
+///             "terms_url": "https://creativecommons.org/licenses/by-nc/4.0/",
+///             "credit": "Data from Cloudflare Radar (radar.cloudflare.com), CC BY-NC 4.0",
+///             "non_commercial": true
+///         }
+///     ]
+/// }
+/// ```
+///
+/// Each issue carries the same object for its own source as `attribution`.
+pub async fn get_dashboard_attribution() -> Json<AttributionResponse> {
+    Json(attribution::all())
+}
+
+/// GET /dashboard/tension - Recorded tension scores over time.
+///
+/// # Query Parameters
+///
+/// - `country` (optional): a country's scores instead of the global score
+/// - `hours` (optional): how far back to go (default 168)
+///
+/// Scores are recorded with each dashboard snapshot, so the history is
+/// empty when snapshots are disabled.
+pub async fn get_tension_history(
+    State(state): State<AppState>,
+    Query(query): Query<TensionQuery>,
+) -> Result<Json<TensionHistory>, StatusCode> {
+    let since = Utc::now() - chrono::Duration::hours(i64::from(query.hours.unwrap_or(168)));
+
+    match state
+        .storage
+        .get_tension_history(query.country.as_deref(), since)
+        .await
+    {
+        Ok(points) => {
+            info!(
+                country = query.country.as_deref().unwrap_or("global"),
+                point_count = points.len(),
+                "Tension history queried"
+            );
+            Ok(Json(TensionHistory {
+                country: query.country,
+                points,
+            }))
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to load tension history");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// GET /feed/bundle - The latest persisted dashboard snapshot as a STIX 2.1
+/// bundle (see [`crate::bundle`]).
+///
+/// # Query Parameters
+///
+/// - `at` (optional): RFC 3339 timestamp; export the latest snapshot taken at or before this
+///   time instead
+///
+/// Returns `404 Not Found` until a snapshot has been persisted.
+#[instrument(skip(state))]
+pub async fn get_feed_bundle(
+    State(state): State<AppState>,
+    Query(query): Query<BundleQuery>,
+) -> Result<Json<Bundle>, StatusCode> {
+    let dashboard = state.dashboard.as_ref().ok_or_else(|| {
+        warn!("Dashboard not configured");
+        StatusCode::SERVICE_UNAVAILABLE
+    })?;
+
+    let at = query.at.unwrap_or_else(Utc::now);
+    match state.storage.get_dashboard_snapshot_at(at).await {
+        Ok(Some(snapshot)) => {
+            let bundle = build_bundle(&snapshot, |code| dashboard.canonical_country_code(code));
+            info!(
+                snapshot_timestamp = %snapshot.timestamp,
+                object_count = bundle.objects.len(),
+                "Feed bundle exported"
+            );
+            Ok(Json(bundle))
+        }
+        Ok(None) => {
+            info!(at = %at, "No dashboard snapshot to export");
+            Err(StatusCode::NOT_FOUND)
+        }
+        Err(e) => {
+            warn!(at = %at, error = %e, "Failed to load dashboard snapshot for export");
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
 /// GET /dashboard/country/:code - Get issues for a specific country.
 #[instrument(skip(state))]
 pub async fn get_dashboard_by_country(
@@ -361,12 +2924,14 @@ pub async fn get_dashboard_by_country(
 
     match dashboard.get_issues_by_country(&country_code).await {
         Ok(issues) => {
-            let summary = crate::dashboard::DashboardSummary::from_issues(&issues);
+            let summary = dashboard.summarize(&issues);
             let response = DashboardResponse {
                 timestamp: Utc::now(),
                 summary,
                 issues,
                 errors: vec![],
+                health: vec![],
+                suppressed: vec![],
             };
             info!(
                 country = %country_code,
@@ -383,6 +2948,8 @@ pub async fn get_dashboard_by_country(
 }
 
 /// GET /dashboard/source/:source - Get issues from a specific source.
+///
+/// Returns `503 Service Unavailable` if the source cannot be fetched.
 #[instrument(skip(state))]
 pub async fn get_dashboard_by_source(
     State(state): State<AppState>,
@@ -393,26 +2960,21 @@ pub async fn get_dashboard_by_source(
         StatusCode::SERVICE_UNAVAILABLE
     })?;
 
-    let source = match source_str.as_str() {
-        "ioda" => IssueSource::Ioda,
-        "cloudflare_radar" | "cloudflare" => IssueSource::CloudflareRadar,
-        "hdx_hapi" | "hdx" | "hapi" => IssueSource::HdxHapi,
-        "acled" => IssueSource::Acled,
-        "reliefweb" => IssueSource::ReliefWeb,
-        _ => {
-            warn!(source = %source_str, "Invalid source");
-            return Err(StatusCode::BAD_REQUEST);
-        }
+    let Some(source) = IssueSource::parse(&source_str) else {
+        warn!(source = %source_str, "Invalid source");
+        return Err(StatusCode::BAD_REQUEST);
     };
 
     match dashboard.get_issues_by_source(source).await {
         Ok(issues) => {
-            let summary = crate::dashboard::DashboardSummary::from_issues(&issues);
+            let summary = dashboard.summarize(&issues);
             let response = DashboardResponse {
                 timestamp: Utc::now(),
                 summary,
                 issues,
                 errors: vec![],
+                health: vec![],
+                suppressed: vec![],
             };
             info!(
                 source = %source_str,
@@ -423,7 +2985,7 @@ pub async fn get_dashboard_by_source(
         }
         Err(e) => {
             warn!(source = %source_str, error = %e, "Failed to fetch dashboard by source");
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(StatusCode::SERVICE_UNAVAILABLE)
         }
     }
 }
@@ -0,0 +1,241 @@
+//! Portable state archives behind `infrared export-state` and
+//! `infrared import-state`.
+//!
+//! An archive is a single JSON document holding every table that carries
+//! state worth keeping: raw life signals (the source of every baseline),
+//! bucket settings (heartbeats, switches, timezones, baselines), bucket
+//! groups, scheduled warmth snapshots (status history), persisted
+//! dashboard snapshots, alert history and the admin audit log. Rows are keyed by column
+//! name rather than position, so an archive can be loaded into another
+//! host, or read by a migration tool for a different database, without
+//! depending on SQLite's file format.
+//!
+//! Imports only go into an empty database, so an archive can never be
+//! merged into (and double-count) an existing deployment.
+//!
+//! # Privacy
+//!
+//! An archive holds exactly what the database holds and nothing more:
+//! buckets, timestamps, weights and bucket-level settings.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{SCHEMA_VERSION, Storage};
+
+/// Version of the archive layout itself.
+pub const ARCHIVE_FORMAT: u32 = 1;
+
+/// Tables included in an archive, in import order.
+pub const ARCHIVED_TABLES: &[&str] = &[
+    "life_signals",
+    "buckets",
+    "bucket_groups",
+    "warmth_snapshots",
+    "dashboard_snapshots",
+    "tension_history",
+    "external_issues",
+    "incident_annotations",
+    "audit_log",
+    "alert_history",
+];
+
+/// A portable copy of an instance's state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateArchive {
+    /// Archive layout version ([`ARCHIVE_FORMAT`]).
+    pub format: u32,
+
+    /// Schema version of the exporting database.
+    pub schema_version: i64,
+
+    /// Infrared version that wrote the archive.
+    pub infrared_version: String,
+
+    /// When the archive was written.
+    pub exported_at: DateTime<Utc>,
+
+    /// Rows per table, each an object keyed by column name.
+    pub tables: BTreeMap<String, Vec<serde_json::Value>>,
+}
+
+impl StateArchive {
+    /// Number of rows per table.
+    pub fn counts(&self) -> BTreeMap<String, usize> {
+        self.tables
+            .iter()
+            .map(|(table, rows)| (table.clone(), rows.len()))
+            .collect()
+    }
+}
+
+/// Copy every archived table out of `storage`.
+pub async fn export_state(storage: &Storage, now: DateTime<Utc>) -> anyhow::Result<StateArchive> {
+    let mut tables = BTreeMap::new();
+    for table in ARCHIVED_TABLES {
+        tables.insert(table.to_string(), storage.export_table(table).await?);
+    }
+
+    Ok(StateArchive {
+        format: ARCHIVE_FORMAT,
+        schema_version: storage.schema_version().await?,
+        infrared_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: now,
+        tables,
+    })
+}
+
+/// Load an archive into an empty database, returning rows imported per
+/// table.
+///
+/// Archives from a newer schema are refused, as are unknown tables and
+/// columns. The import is all-or-nothing.
+pub async fn import_state(
+    storage: &Storage,
+    archive: &StateArchive,
+) -> anyhow::Result<BTreeMap<String, usize>> {
+    anyhow::ensure!(
+        archive.format == ARCHIVE_FORMAT,
+        "unsupported archive format {} (expected {})",
+        archive.format,
+        ARCHIVE_FORMAT
+    );
+    anyhow::ensure!(
+        archive.schema_version <= SCHEMA_VERSION,
+        "archive schema version {} is newer than this build's version {}; import it with \
+         infrared {} or later",
+        archive.schema_version,
+        SCHEMA_VERSION,
+        archive.infrared_version
+    );
+    if let Some(unknown) = archive
+        .tables
+        .keys()
+        .find(|table| !ARCHIVED_TABLES.contains(&table.as_str()))
+    {
+        anyhow::bail!("archive contains unknown table '{}'", unknown);
+    }
+    anyhow::ensure!(
+        storage.tables_empty(ARCHIVED_TABLES).await?,
+        "refusing to import into a database that already holds data"
+    );
+
+    let tables: Vec<(&str, &[serde_json::Value])> = ARCHIVED_TABLES
+        .iter()
+        .map(|table| {
+            let rows = archive.tables.get(*table).map_or(&[][..], Vec::as_slice);
+            (*table, rows)
+        })
+        .collect();
+    storage.import_tables(&tables).await?;
+
+    Ok(tables
+        .iter()
+        .map(|(table, rows)| (table.to_string(), rows.len()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Baseline, LifeSignal};
+    use crate::storage::SignalStore;
+
+    #[tokio::test]
+    async fn test_export_import_round_trip() {
+        let source = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        for weight in [1, 2, 3] {
+            source
+                .insert_life_signal(&LifeSignal {
+                    bucket: "zone-a".to_string(),
+                    timestamp: now - chrono::Duration::minutes(i64::from(weight)),
+                    weight,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+        source.set_heartbeat("zone-a", 300, now).await.unwrap();
+        source
+            .set_baseline("zone-a", Baseline::Windows(12))
+            .await
+            .unwrap();
+        source
+            .set_group("north", &["zone-a".to_string(), "zone-b".to_string()])
+            .await
+            .unwrap();
+        source
+            .insert_audit_entry(now, "ops", "group.change", Some("north"))
+            .await
+            .unwrap();
+
+        let archive = export_state(&source, now).await.unwrap();
+        assert_eq!(archive.counts()["life_signals"], 3);
+        assert_eq!(archive.counts()["bucket_groups"], 2);
+
+        // Survives the trip through JSON
+        let archive: StateArchive =
+            serde_json::from_str(&serde_json::to_string(&archive).unwrap()).unwrap();
+
+        let target = Storage::new("sqlite::memory:").await.unwrap();
+        let imported = import_state(&target, &archive).await.unwrap();
+        assert_eq!(imported, archive.counts());
+
+        assert_eq!(
+            target.window_totals("zone-a", 10, 1, now).await.unwrap(),
+            vec![6]
+        );
+        assert_eq!(
+            target.get_baseline("zone-a").await.unwrap(),
+            Some(Baseline::Windows(12))
+        );
+        assert_eq!(target.get_heartbeats().await.unwrap().len(), 1);
+        assert_eq!(
+            target
+                .get_group("north")
+                .await
+                .unwrap()
+                .unwrap()
+                .buckets
+                .len(),
+            2
+        );
+        assert_eq!(
+            target.get_audit_entries(None, 10).await.unwrap()[0].actor,
+            "ops"
+        );
+
+        // Never merged into existing data
+        let err = import_state(&target, &archive).await.unwrap_err();
+        assert!(err.to_string().contains("already holds data"));
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_incompatible_archives() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let mut archive = export_state(&storage, Utc::now()).await.unwrap();
+
+        archive.schema_version = SCHEMA_VERSION + 1;
+        assert!(import_state(&storage, &archive).await.is_err());
+
+        archive.schema_version = SCHEMA_VERSION;
+        archive.tables.insert("secrets".to_string(), vec![]);
+        assert!(import_state(&storage, &archive).await.is_err());
+
+        archive.tables.remove("secrets");
+        archive.tables.insert(
+            "life_signals".to_string(),
+            vec![serde_json::json!({ "bucket": "zone-a", "ts": 0, "weight": 1 })],
+        );
+        archive.tables.insert(
+            "buckets".to_string(),
+            vec![serde_json::json!({ "bucket": "zone-a", "owner": "someone" })],
+        );
+        assert!(import_state(&storage, &archive).await.is_err());
+        // Nothing was written by the failed imports
+        assert!(storage.tables_empty(ARCHIVED_TABLES).await.unwrap());
+    }
+}
@@ -0,0 +1,192 @@
+//! Licensing and attribution of data sources.
+//!
+//! Every dashboard source publishes its data under its own terms, and some
+//! of them bind whoever passes the data on: Cloudflare Radar data is CC
+//! BY-NC 4.0, ACLED requires a citation and restricts commercial use. Each
+//! issue therefore carries its source's [`Attribution`], and
+//! `GET /dashboard/attribution` lists them all, so re-publishers of
+//! Infrared output can credit sources and honour their terms without
+//! looking each one up.
+//!
+//! These are summaries for convenience; the provider's own terms, linked
+//! from each entry, are authoritative.
+//!
+//! # Privacy
+//!
+//! Attribution is static, per-source text. Nothing about anyone is added.
+
+use serde::{Deserialize, Serialize};
+
+use crate::dashboard::{Issue, IssueSource};
+
+/// Sources with published terms, in the order listed by
+/// `GET /dashboard/attribution`. Partner issues carry whatever terms their
+/// partner states.
+pub const ATTRIBUTED_SOURCES: [IssueSource; 9] = [
+    IssueSource::Ioda,
+    IssueSource::CloudflareRadar,
+    IssueSource::HdxHapi,
+    IssueSource::Acled,
+    IssueSource::ReliefWeb,
+    IssueSource::IomDtm,
+    IssueSource::TorMetrics,
+    IssueSource::Wikimedia,
+    IssueSource::Acaps,
+];
+
+/// The terms a source's data is published under.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Attribution {
+    /// Who publishes the data.
+    pub provider: String,
+
+    /// The license or terms the data is published under.
+    pub license: String,
+
+    /// Where the license or terms can be read.
+    pub terms_url: String,
+
+    /// Credit line to reproduce alongside the data.
+    pub credit: String,
+
+    /// Whether the terms rule out commercial re-use without the provider's
+    /// permission.
+    pub non_commercial: bool,
+}
+
+/// Response for `GET /dashboard/attribution`.
+#[derive(Debug, Serialize)]
+pub struct AttributionResponse {
+    /// Every source with published terms.
+    pub sources: Vec<SourceAttribution>,
+}
+
+/// One source's attribution.
+#[derive(Debug, Serialize)]
+pub struct SourceAttribution {
+    /// The source.
+    pub source: IssueSource,
+
+    /// Its terms.
+    #[serde(flatten)]
+    pub attribution: Attribution,
+}
+
+/// The attribution of a source's data, or `None` for partner issues.
+pub fn for_source(source: IssueSource) -> Option<Attribution> {
+    let (provider, license, terms_url, credit, non_commercial) = match source {
+        IssueSource::Ioda => (
+            "Internet Outage Detection and Analysis (IODA), Georgia Institute of Technology",
+            "IODA terms of use",
+            "https://ioda.inetintel.cc.gatech.edu/",
+            "Data from IODA (ioda.inetintel.cc.gatech.edu)",
+            false,
+        ),
+        IssueSource::CloudflareRadar => (
+            "Cloudflare, Inc.",
+            "CC BY-NC 4.0",
+            "https://creativecommons.org/licenses/by-nc/4.0/",
+            "Data from Cloudflare Radar (radar.cloudflare.com), CC BY-NC 4.0",
+            true,
+        ),
+        IssueSource::HdxHapi => (
+            "OCHA Centre for Humanitarian Data",
+            "HDX terms of service; each dataset's own license applies",
+            "https://data.humdata.org/faqs/terms",
+            "Data from the Humanitarian Data Exchange (data.humdata.org)",
+            false,
+        ),
+        IssueSource::Acled => (
+            "Armed Conflict Location & Event Data (ACLED)",
+            "ACLED terms of use",
+            "https://acleddata.com/terms-of-use/",
+            "Armed Conflict Location & Event Data (ACLED); www.acleddata.com",
+            true,
+        ),
+        IssueSource::ReliefWeb => (
+            "OCHA ReliefWeb",
+            "ReliefWeb terms and conditions; content remains its original publishers'",
+            "https://reliefweb.int/terms-conditions",
+            "Data from ReliefWeb (reliefweb.int)",
+            false,
+        ),
+        IssueSource::IomDtm => (
+            "International Organization for Migration (IOM)",
+            "IOM DTM terms of use",
+            "https://dtm.iom.int/",
+            "Data from IOM Displacement Tracking Matrix (dtm.iom.int)",
+            false,
+        ),
+        IssueSource::TorMetrics => (
+            "The Tor Project",
+            "CC0 1.0",
+            "https://creativecommons.org/publicdomain/zero/1.0/",
+            "Data from Tor Metrics (metrics.torproject.org)",
+            false,
+        ),
+        IssueSource::Wikimedia => (
+            "Wikimedia Foundation",
+            "CC0 1.0",
+            "https://creativecommons.org/publicdomain/zero/1.0/",
+            "Pageview data from the Wikimedia Foundation",
+            false,
+        ),
+        IssueSource::Acaps => (
+            "ACAPS",
+            "ACAPS terms of use",
+            "https://www.acaps.org/",
+            "Data from the ACAPS INFORM Severity Index (acaps.org)",
+            false,
+        ),
+        IssueSource::External => return None,
+    };
+
+    Some(Attribution {
+        provider: provider.to_string(),
+        license: license.to_string(),
+        terms_url: terms_url.to_string(),
+        credit: credit.to_string(),
+        non_commercial,
+    })
+}
+
+/// The attribution of every source with published terms.
+pub fn all() -> AttributionResponse {
+    AttributionResponse {
+        sources: ATTRIBUTED_SOURCES
+            .into_iter()
+            .filter_map(|source| {
+                for_source(source).map(|attribution| SourceAttribution {
+                    source,
+                    attribution,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Give issues without an attribution their source's, for issues stored
+/// before attribution was recorded.
+pub fn fill_missing(issues: &mut [Issue]) {
+    for issue in issues.iter_mut().filter(|i| i.attribution.is_none()) {
+        issue.attribution = for_source(issue.source);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_source_attributed() {
+        let response = all();
+        assert_eq!(response.sources.len(), ATTRIBUTED_SOURCES.len());
+        assert!(for_source(IssueSource::External).is_none());
+
+        let radar = for_source(IssueSource::CloudflareRadar).unwrap();
+        assert_eq!(radar.license, "CC BY-NC 4.0");
+        assert!(radar.non_commercial);
+        assert!(for_source(IssueSource::Acled).unwrap().non_commercial);
+        assert!(!for_source(IssueSource::TorMetrics).unwrap().non_commercial);
+    }
+}
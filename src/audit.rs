@@ -0,0 +1,147 @@
+//! Append-only audit log of administrative operations.
+//!
+//! Admin operations (bucket configuration changes, archives, renames,
+//! backfills and backups) are recorded with the acting credential's label
+//! and a timestamp. The underlying table rejects updates and deletes.
+//!
+//! # Privacy Guarantees
+//!
+//! Entries never contain network identifiers: no IP addresses, user agents,
+//! or request headers. The actor is the operator-chosen token label or the
+//! SSO subject, never the credential itself.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::Principal;
+use crate::storage::SignalStore;
+
+/// Actor recorded when authentication is disabled.
+pub const ANONYMOUS_ACTOR: &str = "anonymous";
+
+/// Actor recorded for operations run from the command line.
+pub const CLI_ACTOR: &str = "cli";
+
+/// Audit action names.
+pub mod actions {
+    /// A bucket's expected heartbeat was registered or removed.
+    pub const HEARTBEAT_CHANGE: &str = "heartbeat.change";
+    /// A bucket's dead-man's switch was armed or disarmed.
+    pub const SWITCH_CHANGE: &str = "switch.change";
+    /// A bucket group was created, replaced, or deleted.
+    pub const GROUP_CHANGE: &str = "group.change";
+    /// A bucket's warmth baseline was set or removed.
+    pub const BASELINE_CHANGE: &str = "baseline.change";
+    /// A bucket's timezone was set or removed.
+    pub const TIMEZONE_CHANGE: &str = "timezone.change";
+    /// The country a bucket reports on was set or removed.
+    pub const BUCKET_COUNTRY_CHANGE: &str = "bucket_country.change";
+    /// A bucket's metadata was set or removed.
+    pub const BUCKET_META_CHANGE: &str = "bucket_meta.change";
+    /// A bucket was archived or returned to service.
+    pub const BUCKET_ARCHIVE: &str = "bucket.archive";
+    /// Buckets were renamed or merged (`infrared buckets`).
+    pub const BUCKET_RENAME: &str = "bucket.rename";
+    /// Historical signals were imported.
+    pub const SIGNAL_BACKFILL: &str = "signals.backfill";
+    /// A database backup was taken.
+    pub const BACKUP: &str = "backup";
+}
+
+/// A single audit log entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Monotonic entry ID.
+    pub id: i64,
+
+    /// When the operation happened.
+    pub timestamp: DateTime<Utc>,
+
+    /// Label of the credential that performed the operation.
+    pub actor: String,
+
+    /// What was done (see [`actions`]).
+    pub action: String,
+
+    /// Free-form, non-identifying details (e.g., the purged bucket).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+/// Query parameters for `GET /admin/audit`.
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    /// Only return entries with this action.
+    pub action: Option<String>,
+
+    /// Maximum number of entries, newest first (default: 100, max: 1000).
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+}
+
+fn default_limit() -> u32 {
+    100
+}
+
+/// Response for `GET /admin/audit`.
+#[derive(Debug, Serialize)]
+pub struct AuditResponse {
+    /// Entries, newest first.
+    pub entries: Vec<AuditEntry>,
+}
+
+/// Label to record for a (possibly unauthenticated) principal.
+pub fn actor_label(principal: Option<&Principal>) -> &str {
+    principal.map_or(ANONYMOUS_ACTOR, |p| p.label.as_str())
+}
+
+/// Record an admin operation performed by `principal` now.
+pub async fn record(
+    storage: &dyn SignalStore,
+    principal: Option<&Principal>,
+    action: &str,
+    details: Option<&str>,
+) -> anyhow::Result<()> {
+    storage
+        .insert_audit_entry(Utc::now(), actor_label(principal), action, details)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::Role;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_record_and_list() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let admin = Principal {
+            role: Role::Admin,
+            label: "ops".to_string(),
+        };
+
+        record(
+            &storage,
+            Some(&admin),
+            actions::BUCKET_ARCHIVE,
+            Some("zone-a"),
+        )
+        .await
+        .unwrap();
+        record(&storage, None, actions::BACKUP, None).await.unwrap();
+
+        let entries = storage.get_audit_entries(None, 10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, actions::BACKUP);
+        assert_eq!(entries[0].actor, ANONYMOUS_ACTOR);
+        assert_eq!(entries[1].actor, "ops");
+        assert_eq!(entries[1].details.as_deref(), Some("zone-a"));
+
+        let archives = storage
+            .get_audit_entries(Some(actions::BUCKET_ARCHIVE), 10)
+            .await
+            .unwrap();
+        assert_eq!(archives.len(), 1);
+    }
+}
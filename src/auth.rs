@@ -0,0 +1,417 @@
+//! Role-based API token authentication.
+//!
+//! Tokens are configured with one of three roles:
+//!
+//! - **ingest**: may only submit signals (`POST /signal`)
+//! - **read**: may query warmth, alerts, stats, metrics, and the dashboard
+//! - **admin**: may do everything, including administrative endpoints
+//!
+//! This lets hundreds of field devices carry ingest tokens without any of
+//! them being able to read data or perform admin operations.
+//!
+//! Roles can come from static tokens or, optionally, from JWTs minted by an
+//! existing identity provider (see [`crate::jwt`]). Read and admin endpoints
+//! also accept SSO session cookies when OIDC login is configured (see
+//! [`crate::oidc`]).
+//!
+//! When no tokens or JWT validation are configured, authentication is
+//! disabled and every endpoint is open (the historical behavior). OIDC alone
+//! protects read and admin endpoints but leaves ingestion open.
+//!
+//! # Privacy
+//!
+//! Tokens identify a configured *role*, never a person or device. Tokens are
+//! never logged; only the optional operator-chosen label is.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::api::AppState;
+use crate::jwt::JwtValidator;
+use crate::oidc::{Oidc, SESSION_COOKIE, cookie_value};
+
+/// Access level granted to an API token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// May submit signals only.
+    Ingest,
+    /// May query data but not submit signals.
+    Read,
+    /// Full access.
+    Admin,
+}
+
+impl Role {
+    /// Parse a role name (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "ingest" => Some(Role::Ingest),
+            "read" => Some(Role::Read),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    /// Whether a holder of this role may access endpoints requiring `required`.
+    pub fn allows(&self, required: Role) -> bool {
+        *self == Role::Admin || *self == required
+    }
+}
+
+/// A configured API token.
+#[derive(Clone)]
+struct ApiToken {
+    secret: String,
+    role: Role,
+    label: String,
+}
+
+/// Identity attached to an authenticated request.
+///
+/// Inserted into request extensions by the auth middleware.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    /// Role the request was authenticated with.
+    pub role: Role,
+
+    /// Operator-chosen label for the credential (e.g., "field-devices").
+    pub label: String,
+}
+
+/// Authentication configuration shared by the middleware.
+///
+/// Cloning is cheap.
+#[derive(Clone, Default)]
+pub struct AuthConfig {
+    tokens: Arc<Vec<ApiToken>>,
+    jwt: Option<Arc<JwtValidator>>,
+    oidc: Option<Arc<Oidc>>,
+}
+
+impl AuthConfig {
+    /// Authentication disabled: all endpoints are open.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Parse tokens from a comma-separated list of `role:token` or
+    /// `role:label:token` entries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use infrared::auth::{AuthConfig, Role};
+    ///
+    /// let auth = AuthConfig::parse("ingest:field:s3cret,admin:0ps").unwrap();
+    /// assert_eq!(auth.authenticate("s3cret").unwrap().role, Role::Ingest);
+    /// ```
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let tokens = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let parts: Vec<&str> = entry.splitn(3, ':').collect();
+                let (role, label, secret) = match parts.as_slice() {
+                    [role, secret] => (*role, *role, *secret),
+                    [role, label, secret] => (*role, *label, *secret),
+                    _ => anyhow::bail!("expected role:token or role:label:token"),
+                };
+                let role =
+                    Role::parse(role).ok_or_else(|| anyhow::anyhow!("unknown role '{}'", role))?;
+                if secret.is_empty() {
+                    anyhow::bail!("empty token for role '{}'", label);
+                }
+                Ok(ApiToken {
+                    secret: secret.to_string(),
+                    role,
+                    label: label.to_string(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            tokens: Arc::new(tokens),
+            jwt: None,
+            oidc: None,
+        })
+    }
+
+    /// Also accept JWT bearer tokens verified by `validator`.
+    pub fn with_jwt(mut self, validator: JwtValidator) -> Self {
+        self.jwt = Some(Arc::new(validator));
+        self
+    }
+
+    /// Accept SSO session cookies on read and admin endpoints.
+    pub fn with_oidc(mut self, oidc: Oidc) -> Self {
+        self.oidc = Some(Arc::new(oidc));
+        self
+    }
+
+    /// The OIDC login provider, if configured.
+    pub fn oidc(&self) -> Option<&Oidc> {
+        self.oidc.as_deref()
+    }
+
+    /// Whether any tokens or JWT validation are configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.tokens.is_empty() || self.jwt.is_some()
+    }
+
+    /// Whether endpoints requiring `required` need credentials.
+    pub fn requires_auth(&self, required: Role) -> bool {
+        self.is_enabled() || (self.oidc.is_some() && required != Role::Ingest)
+    }
+
+    /// Look up the principal for a bearer token.
+    pub fn authenticate(&self, token: &str) -> Option<Principal> {
+        // Check every token so timing doesn't reveal which one matched
+        let mut found = None;
+        for candidate in self.tokens.iter() {
+            if constant_time_eq(candidate.secret.as_bytes(), token.as_bytes()) && found.is_none() {
+                found = Some(Principal {
+                    role: candidate.role,
+                    label: candidate.label.clone(),
+                });
+            }
+        }
+        found
+    }
+}
+
+/// Compare two byte strings without short-circuiting on the first difference.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Extract the token from an `Authorization: Bearer <token>` header.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// Why a request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// No credentials, or credentials that don't match any token.
+    Unauthenticated,
+    /// Valid credentials without the required role.
+    Forbidden,
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthError::Unauthenticated => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))],
+            )
+                .into_response(),
+            AuthError::Forbidden => StatusCode::FORBIDDEN.into_response(),
+        }
+    }
+}
+
+/// Check a request's credentials against the required role.
+///
+/// Bearer tokens are checked against static tokens first, then JWT
+/// validation if configured. Without a bearer token, read and admin
+/// endpoints fall back to the SSO session cookie.
+/// Returns `Ok(None)` when the endpoint needs no credentials.
+pub async fn authorize(
+    auth: &AuthConfig,
+    headers: &HeaderMap,
+    required: Role,
+) -> Result<Option<Principal>, AuthError> {
+    if !auth.requires_auth(required) {
+        return Ok(None);
+    }
+
+    let principal = match bearer_token(headers) {
+        Some(token) => match auth.authenticate(token) {
+            Some(principal) => Some(principal),
+            None => match &auth.jwt {
+                Some(jwt) => jwt.validate(token).await,
+                None => None,
+            },
+        },
+        // Ingestion stays token-based; sessions only cover read/admin
+        None if required != Role::Ingest => auth.oidc().and_then(|oidc| {
+            cookie_value(headers, SESSION_COOKIE)
+                .and_then(|session| oidc.session_principal(session))
+        }),
+        None => None,
+    }
+    .ok_or(AuthError::Unauthenticated)?;
+
+    if !principal.role.allows(required) {
+        // PRIVACY: only the configured label is logged, never the token
+        warn!(label = %principal.label, required = ?required, "Token lacks required role");
+        return Err(AuthError::Forbidden);
+    }
+
+    Ok(Some(principal))
+}
+
+async fn require_role(
+    state: AppState,
+    mut request: Request,
+    next: Next,
+    required: Role,
+) -> Result<Response, AuthError> {
+    if let Some(principal) = authorize(&state.auth, request.headers(), required).await? {
+        request.extensions_mut().insert(principal);
+    }
+    Ok(next.run(request).await)
+}
+
+/// Middleware: require a token with the `ingest` role.
+pub async fn require_ingest(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    require_role(state, request, next, Role::Ingest).await
+}
+
+/// Middleware: require a token with the `read` role.
+pub async fn require_read(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    require_role(state, request, next, Role::Read).await
+}
+
+/// Middleware: require a token with the `admin` role.
+pub async fn require_admin(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    require_role(state, request, next, Role::Admin).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_role_allows() {
+        assert!(Role::Admin.allows(Role::Ingest));
+        assert!(Role::Admin.allows(Role::Read));
+        assert!(Role::Ingest.allows(Role::Ingest));
+        assert!(!Role::Ingest.allows(Role::Read));
+        assert!(!Role::Read.allows(Role::Ingest));
+        assert!(!Role::Read.allows(Role::Admin));
+    }
+
+    #[test]
+    fn test_parse_tokens() {
+        let auth = AuthConfig::parse("ingest:abc, read:dashboards:def").unwrap();
+
+        let principal = auth.authenticate("def").unwrap();
+        assert_eq!(principal.role, Role::Read);
+        assert_eq!(principal.label, "dashboards");
+        assert_eq!(auth.authenticate("abc").unwrap().label, "ingest");
+        assert!(auth.authenticate("nope").is_none());
+
+        assert!(AuthConfig::parse("superuser:abc").is_err());
+        assert!(AuthConfig::parse("abc").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_authorize() {
+        let auth = AuthConfig::parse("ingest:abc,admin:xyz").unwrap();
+
+        assert_eq!(
+            authorize(&auth, &HeaderMap::new(), Role::Ingest).await,
+            Err(AuthError::Unauthenticated)
+        );
+        assert_eq!(
+            authorize(&auth, &headers_with("abc"), Role::Read).await,
+            Err(AuthError::Forbidden)
+        );
+        assert!(
+            authorize(&auth, &headers_with("abc"), Role::Ingest)
+                .await
+                .is_ok()
+        );
+        assert!(
+            authorize(&auth, &headers_with("xyz"), Role::Read)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_allows_everything() {
+        let auth = AuthConfig::disabled();
+        assert_eq!(
+            authorize(&auth, &HeaderMap::new(), Role::Admin).await,
+            Ok(None)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jwt_fallback() {
+        use jsonwebtoken::{EncodingKey, Header, encode};
+
+        let auth = AuthConfig::parse("ingest:abc")
+            .unwrap()
+            .with_jwt(JwtValidator::hs256("secret"));
+        let jwt = encode(
+            &Header::default(),
+            &serde_json::json!({
+                "role": "read",
+                "exp": chrono::Utc::now().timestamp() + 300,
+            }),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+
+        assert!(
+            authorize(&auth, &headers_with("abc"), Role::Ingest)
+                .await
+                .is_ok()
+        );
+        assert_eq!(
+            authorize(&auth, &headers_with(&jwt), Role::Read)
+                .await
+                .unwrap()
+                .unwrap()
+                .label,
+            "jwt"
+        );
+        assert_eq!(
+            authorize(&auth, &headers_with(&jwt), Role::Ingest).await,
+            Err(AuthError::Forbidden)
+        );
+    }
+}
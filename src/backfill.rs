@@ -0,0 +1,287 @@
+//! Historical signal backfill.
+//!
+//! A deployment replacing an older one would otherwise start with no
+//! history: every bucket has a zero baseline and reads as `alive` (or
+//! `dead`) until weeks of signals accumulate. `POST /signals/backfill` lets
+//! an admin import the old deployment's signals with their original
+//! timestamps. They land in `life_signals` like any other row, so baselines,
+//! sparklines and exports pick them up with no special casing.
+//!
+//! Backfill is the only way to set a signal's timestamp: `POST /signal`
+//! rejects a client-supplied one, since letting ordinary clients date their
+//! own signals would let them rewrite a bucket's history.
+//!
+//! Imported signals are written in one transaction, bypassing ingest quotas
+//! and the write buffer, and are not published as live events. Signals older
+//! than `INFRARED_RETENTION_DAYS` are accepted but pruned on the next
+//! retention run.
+//!
+//! A client on a flaky link cannot tell a lost request from a lost
+//! response, and retrying the latter would store the batch twice. A batch
+//! sent with an `Idempotency-Key` header holding a random (version 4) UUID
+//! is recorded under that key, in the same transaction as its signals; a
+//! retry with the same key within `INFRARED_IDEMPOTENCY_TTL_HOURS` stores
+//! nothing and gets the original counts back.
+//!
+//! # Privacy
+//!
+//! A backfilled signal carries exactly what a live one does: bucket,
+//! timestamp, weight and channel. The audit log records how many signals
+//! were imported into how many buckets, not the signals themselves.
+//! Idempotency keys must be random UUIDs, so a client cannot use a device
+//! identifier as one, and are deleted once their time to live has passed.
+
+use std::collections::BTreeSet;
+
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::canary::is_canary;
+use crate::channel::SignalChannels;
+use crate::model::LifeSignal;
+
+/// Most signals accepted in one backfill request.
+pub const MAX_BACKFILL_SIGNALS: usize = 10_000;
+
+/// Header carrying a batch's idempotency key.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Default hours an idempotency key is remembered.
+pub const DEFAULT_IDEMPOTENCY_TTL_HOURS: u64 = 24;
+
+/// Request body for `POST /signals/backfill`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillRequest {
+    /// The signals to import.
+    pub signals: Vec<BackfillSignal>,
+}
+
+/// One historical signal.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackfillSignal {
+    /// The bucket the signal was recorded in.
+    pub bucket: String,
+
+    /// When the signal was originally recorded.
+    pub timestamp: DateTime<Utc>,
+
+    /// Weight of the signal (defaults to 1).
+    #[serde(default = "default_weight")]
+    pub weight: i32,
+
+    /// Optional delivery path, one of the configured channels.
+    #[serde(default)]
+    pub channel: Option<String>,
+}
+
+fn default_weight() -> i32 {
+    1
+}
+
+/// A backfill that failed validation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackfillRejected {
+    /// Always "invalid_backfill".
+    pub error: &'static str,
+
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl IntoResponse for BackfillRejected {
+    fn into_response(self) -> Response {
+        (StatusCode::UNPROCESSABLE_ENTITY, Json(self)).into_response()
+    }
+}
+
+/// Response for `POST /signals/backfill`.
+#[derive(Debug, Serialize)]
+pub struct BackfillAccepted {
+    /// Signals imported.
+    pub inserted: usize,
+
+    /// Distinct buckets they were imported into.
+    pub buckets: usize,
+
+    /// Whether the batch had already been imported under its idempotency
+    /// key, so nothing was stored this time.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub replayed: bool,
+}
+
+/// Validate a backfill against the configured `channels`.
+///
+/// Every signal must be dated no later than `now`, name a non-empty,
+/// non-canary bucket and, if it has one, a configured channel. The whole
+/// batch is rejected if any signal is invalid, naming the first by index.
+pub fn accept(
+    request: BackfillRequest,
+    channels: &SignalChannels,
+    now: DateTime<Utc>,
+) -> Result<Vec<LifeSignal>, BackfillRejected> {
+    let reject = |message: String| BackfillRejected {
+        error: "invalid_backfill",
+        message,
+    };
+
+    if request.signals.is_empty() {
+        return Err(reject("signals must not be empty".to_string()));
+    }
+    if request.signals.len() > MAX_BACKFILL_SIGNALS {
+        return Err(reject(format!(
+            "{} signals in one request; at most {} are allowed",
+            request.signals.len(),
+            MAX_BACKFILL_SIGNALS
+        )));
+    }
+
+    request
+        .signals
+        .into_iter()
+        .enumerate()
+        .map(|(i, signal)| {
+            if signal.bucket.trim().is_empty() {
+                return Err(reject(format!("signals[{}]: bucket must not be empty", i)));
+            }
+            if is_canary(&signal.bucket) {
+                return Err(reject(format!(
+                    "signals[{}]: bucket '{}' is reserved for canaries",
+                    i, signal.bucket
+                )));
+            }
+            if signal.timestamp > now {
+                return Err(reject(format!(
+                    "signals[{}]: timestamp is in the future",
+                    i
+                )));
+            }
+            if let Some(channel) = &signal.channel
+                && !channels.contains(channel)
+            {
+                return Err(reject(format!(
+                    "signals[{}]: channel '{}' is not configured",
+                    i, channel
+                )));
+            }
+            Ok(LifeSignal {
+                bucket: signal.bucket,
+                timestamp: signal.timestamp,
+                weight: signal.weight,
+                channel: signal.channel,
+            })
+        })
+        .collect()
+}
+
+/// Parse an idempotency key, which must be a version 4 (random) UUID.
+///
+/// Returns the key in its lowercase hyphenated form, so a retry spelled
+/// differently still matches.
+pub fn parse_idempotency_key(key: &str) -> Result<String, BackfillRejected> {
+    match Uuid::parse_str(key.trim()) {
+        Ok(uuid) if uuid.get_version_num() == 4 => Ok(uuid.hyphenated().to_string()),
+        _ => Err(BackfillRejected {
+            error: "invalid_backfill",
+            message: "Idempotency-Key must be a random (version 4) UUID".to_string(),
+        }),
+    }
+}
+
+/// Number of distinct buckets among `signals`.
+pub fn bucket_count(signals: &[LifeSignal]) -> usize {
+    signals
+        .iter()
+        .map(|s| s.bucket.as_str())
+        .collect::<BTreeSet<_>>()
+        .len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(signals: serde_json::Value) -> BackfillRequest {
+        serde_json::from_value(serde_json::json!({ "signals": signals })).unwrap()
+    }
+
+    #[test]
+    fn test_accept() {
+        let now = Utc::now();
+        let channels = SignalChannels::parse("sms").unwrap();
+        let past = (now - chrono::Duration::days(3)).to_rfc3339();
+
+        let signals = accept(
+            request(serde_json::json!([
+                { "bucket": "zone-a", "timestamp": past },
+                { "bucket": "zone-b", "timestamp": past, "weight": 4, "channel": "sms" },
+                { "bucket": "zone-a", "timestamp": past },
+            ])),
+            &channels,
+            now,
+        )
+        .unwrap();
+        assert_eq!(signals.len(), 3);
+        assert_eq!(signals[0].weight, 1);
+        assert_eq!(signals[1].weight, 4);
+        assert_eq!(signals[1].channel.as_deref(), Some("sms"));
+        assert_eq!(bucket_count(&signals), 2);
+    }
+
+    #[test]
+    fn test_idempotency_key() {
+        assert_eq!(
+            parse_idempotency_key(" 6F9619FF-8B86-4D01-B42D-00C04FC964FF ").unwrap(),
+            "6f9619ff-8b86-4d01-b42d-00c04fc964ff"
+        );
+        // Name-based UUIDs can be derived from an identity
+        let v5 = Uuid::new_v5(&Uuid::NAMESPACE_DNS, b"device-17").to_string();
+        assert!(parse_idempotency_key(&v5).is_err());
+        assert!(parse_idempotency_key("batch-17").is_err());
+    }
+
+    #[test]
+    fn test_reject() {
+        let now = Utc::now();
+        let channels = SignalChannels::parse("sms").unwrap();
+        let past = (now - chrono::Duration::days(3)).to_rfc3339();
+        let future = (now + chrono::Duration::minutes(1)).to_rfc3339();
+        let message = |signals: serde_json::Value| {
+            accept(request(signals), &channels, now)
+                .unwrap_err()
+                .message
+        };
+
+        assert_eq!(message(serde_json::json!([])), "signals must not be empty");
+        assert_eq!(
+            message(serde_json::json!([
+                { "bucket": "zone-a", "timestamp": past },
+                { "bucket": "zone-a", "timestamp": future },
+            ])),
+            "signals[1]: timestamp is in the future"
+        );
+        assert!(
+            message(serde_json::json!([{ "bucket": " ", "timestamp": past }]))
+                .contains("must not be empty")
+        );
+        assert!(
+            message(serde_json::json!([{ "bucket": "_canary:probe", "timestamp": past }]))
+                .contains("reserved")
+        );
+        assert!(
+            message(serde_json::json!([
+                { "bucket": "zone-a", "timestamp": past, "channel": "radio" }
+            ]))
+            .contains("not configured")
+        );
+
+        let too_many = vec![
+            serde_json::json!({ "bucket": "zone-a", "timestamp": past });
+            MAX_BACKFILL_SIGNALS + 1
+        ];
+        assert!(message(serde_json::Value::from(too_many)).contains("at most"));
+    }
+}
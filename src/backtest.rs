@@ -0,0 +1,455 @@
+//! Alert-rule backtesting behind `infrared backtest`.
+//!
+//! Replays stored signals through candidate threshold/baseline
+//! configurations and reports the alerts each would have raised, scored
+//! against a recorded alert history (e.g., incidents exported from a paging
+//! system), so threshold changes can be justified with evidence.
+//!
+//! # Thresholds File
+//!
+//! ```json
+//! {
+//!     "candidates": [
+//!         { "name": "current" },
+//!         { "name": "sensitive", "stressed_ratio": 0.9, "collapsing_ratio": 0.3,
+//!           "window_minutes": 15, "historical_windows": 8 },
+//!         { "name": "smoothed", "ewma_alpha": 0.3 }
+//!     ],
+//!     "history": [
+//!         { "bucket": "zone-a", "from": "2024-01-15T10:00:00Z", "to": "2024-01-15T12:00:00Z" }
+//!     ]
+//! }
+//! ```
+//!
+//! Omitted thresholds fall back to the service's defaults
+//! (`INFRARED_STRESSED_RATIO`, `INFRARED_COLLAPSING_RATIO`), so `current`
+//! above replays what production would have raised; omitted windows fall
+//! back to the service's 10-minute window and 6-window baseline.
+//!
+//! # Privacy
+//!
+//! Only bucket-level window totals are replayed; reports name buckets and
+//! time ranges, nothing finer.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::{NUM_HISTORICAL_WINDOWS, judged_baseline};
+use crate::model::{WarmthStatus, WarmthThresholds};
+use crate::storage::{SignalStore, Storage};
+
+/// A threshold/baseline configuration to evaluate.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Candidate {
+    /// Label used in the report.
+    pub name: String,
+
+    /// Ratio below which a bucket is `stressed` (default: the service's).
+    #[serde(default)]
+    pub stressed_ratio: Option<f64>,
+
+    /// Ratio below which a bucket is `collapsing` (default: the service's).
+    #[serde(default)]
+    pub collapsing_ratio: Option<f64>,
+
+    /// Smoothing factor of an exponentially weighted baseline (default:
+    /// the service's).
+    #[serde(default)]
+    pub ewma_alpha: Option<f64>,
+
+    /// Window size in minutes (default: 10).
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: u32,
+
+    /// Windows averaged into the baseline (default: 6).
+    #[serde(default = "default_historical_windows")]
+    pub historical_windows: u32,
+}
+
+fn default_window_minutes() -> u32 {
+    10
+}
+
+impl Candidate {
+    /// The candidate's thresholds, with omitted fields taken from `defaults`.
+    pub fn thresholds(&self, defaults: WarmthThresholds) -> WarmthThresholds {
+        WarmthThresholds {
+            stressed_ratio: self.stressed_ratio.unwrap_or(defaults.stressed_ratio),
+            collapsing_ratio: self.collapsing_ratio.unwrap_or(defaults.collapsing_ratio),
+            ewma_alpha: self.ewma_alpha.or(defaults.ewma_alpha),
+        }
+    }
+}
+
+fn default_historical_windows() -> u32 {
+    NUM_HISTORICAL_WINDOWS
+}
+
+/// A recorded alert: a bucket known to have been in distress over a period.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedAlert {
+    /// The bucket that was in distress.
+    pub bucket: String,
+
+    /// Start of the recorded alert.
+    pub from: DateTime<Utc>,
+
+    /// End of the recorded alert.
+    pub to: DateTime<Utc>,
+}
+
+/// Contents of the `--thresholds` file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BacktestConfig {
+    /// Configurations to evaluate.
+    pub candidates: Vec<Candidate>,
+
+    /// Recorded alert history to score against.
+    #[serde(default)]
+    pub history: Vec<RecordedAlert>,
+}
+
+/// A contiguous run of alerting windows for one bucket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimulatedAlert {
+    /// The alerting bucket.
+    pub bucket: String,
+
+    /// Worst status reached during the episode.
+    pub status: WarmthStatus,
+
+    /// End of the first alerting window.
+    pub from: DateTime<Utc>,
+
+    /// End of the last alerting window.
+    pub to: DateTime<Utc>,
+
+    /// Whether the episode overlaps a recorded alert for the same bucket.
+    pub matched: bool,
+}
+
+/// Results for one candidate.
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateReport {
+    /// Candidate label.
+    pub name: String,
+
+    /// Thresholds evaluated.
+    pub thresholds: WarmthThresholds,
+
+    /// Window size in minutes.
+    pub window_minutes: u32,
+
+    /// Windows averaged into the baseline.
+    pub historical_windows: u32,
+
+    /// Simulated alerts that overlap a recorded alert.
+    pub true_positives: usize,
+
+    /// Simulated alerts that overlap no recorded alert.
+    pub false_positives: usize,
+
+    /// Recorded alerts that no simulated alert overlaps.
+    pub missed: usize,
+
+    /// Every simulated alert, in time order per bucket.
+    pub alerts: Vec<SimulatedAlert>,
+}
+
+/// Full report produced by `infrared backtest`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    /// Start of the replayed range.
+    pub from: DateTime<Utc>,
+
+    /// End of the replayed range.
+    pub to: DateTime<Utc>,
+
+    /// Number of recorded alerts scored against.
+    pub recorded_alerts: usize,
+
+    /// Results per candidate, in file order.
+    pub candidates: Vec<CandidateReport>,
+}
+
+/// Replay stored signals between `from` and `to` through every candidate.
+///
+/// Each candidate is evaluated at the end of every window in the range,
+/// using the same window and baseline queries as the live service, and
+/// the service's `defaults` for thresholds it does not set.
+pub async fn run_backtest(
+    storage: &Storage,
+    config: &BacktestConfig,
+    defaults: WarmthThresholds,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> anyhow::Result<BacktestReport> {
+    anyhow::ensure!(from < to, "--from must be before --to");
+
+    let buckets = storage.get_all_known_buckets().await?;
+    let mut candidates = Vec::with_capacity(config.candidates.len());
+
+    for candidate in &config.candidates {
+        anyhow::ensure!(
+            candidate.window_minutes > 0,
+            "candidate '{}' has a zero window",
+            candidate.name
+        );
+        let thresholds = candidate.thresholds(defaults);
+        thresholds
+            .validate()
+            .map_err(|e| anyhow::anyhow!("candidate '{}': {}", candidate.name, e))?;
+
+        let mut alerts = Vec::new();
+        for bucket in &buckets {
+            alerts
+                .extend(simulate_bucket(storage, candidate, &thresholds, bucket, from, to).await?);
+        }
+        candidates.push(score(candidate, thresholds, alerts, &config.history));
+    }
+
+    Ok(BacktestReport {
+        from,
+        to,
+        recorded_alerts: config.history.len(),
+        candidates,
+    })
+}
+
+/// Step through one bucket's windows, merging consecutive alerting windows.
+async fn simulate_bucket(
+    storage: &Storage,
+    candidate: &Candidate,
+    thresholds: &WarmthThresholds,
+    bucket: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> anyhow::Result<Vec<SimulatedAlert>> {
+    let step = chrono::Duration::minutes(candidate.window_minutes.into());
+    let mut alerts: Vec<SimulatedAlert> = Vec::new();
+    let mut open = false;
+
+    let mut now = from + step;
+    while now <= to {
+        let current = storage
+            .query_bucket_window(bucket, candidate.window_minutes, now)
+            .await?;
+        let totals = storage
+            .baseline_totals(
+                bucket,
+                candidate.window_minutes,
+                candidate.historical_windows,
+                now,
+            )
+            .await?;
+        let average = judged_baseline(thresholds, &totals);
+        let status = thresholds.classify(current, average);
+
+        if matches!(status, WarmthStatus::Collapsing | WarmthStatus::Dead) {
+            match alerts.last_mut() {
+                Some(alert) if open => {
+                    alert.to = now;
+                    if status == WarmthStatus::Dead {
+                        alert.status = WarmthStatus::Dead;
+                    }
+                }
+                _ => alerts.push(SimulatedAlert {
+                    bucket: bucket.to_string(),
+                    status,
+                    from: now,
+                    to: now,
+                    matched: false,
+                }),
+            }
+            open = true;
+        } else {
+            open = false;
+        }
+
+        now += step;
+    }
+
+    Ok(alerts)
+}
+
+/// Score simulated alerts against the recorded history.
+fn score(
+    candidate: &Candidate,
+    thresholds: WarmthThresholds,
+    mut alerts: Vec<SimulatedAlert>,
+    history: &[RecordedAlert],
+) -> CandidateReport {
+    let overlaps = |alert: &SimulatedAlert, recorded: &RecordedAlert| {
+        alert.bucket == recorded.bucket && alert.from <= recorded.to && recorded.from <= alert.to
+    };
+
+    for alert in &mut alerts {
+        alert.matched = history.iter().any(|recorded| overlaps(alert, recorded));
+    }
+    let true_positives = alerts.iter().filter(|a| a.matched).count();
+    let missed = history
+        .iter()
+        .filter(|recorded| !alerts.iter().any(|alert| overlaps(alert, recorded)))
+        .count();
+
+    CandidateReport {
+        name: candidate.name.clone(),
+        thresholds,
+        window_minutes: candidate.window_minutes,
+        historical_windows: candidate.historical_windows,
+        true_positives,
+        false_positives: alerts.len() - true_positives,
+        missed,
+        alerts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LifeSignal;
+    use chrono::TimeZone;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_400 + minutes * 60, 0).unwrap()
+    }
+
+    #[test]
+    fn test_candidate_defaults() {
+        let config: BacktestConfig = serde_json::from_str(
+            r#"{ "candidates": [{ "name": "current" }, { "name": "s", "stressed_ratio": 0.9 }] }"#,
+        )
+        .unwrap();
+
+        let defaults = WarmthThresholds::default();
+        assert_eq!(config.candidates[0].thresholds(defaults), defaults);
+        assert_eq!(config.candidates[0].window_minutes, 10);
+        assert_eq!(
+            config.candidates[0].historical_windows,
+            NUM_HISTORICAL_WINDOWS
+        );
+        let sensitive = config.candidates[1].thresholds(defaults);
+        assert_eq!(sensitive.stressed_ratio, 0.9);
+        assert_eq!(sensitive.collapsing_ratio, 0.2);
+        assert!(config.history.is_empty());
+
+        // Omitted thresholds follow the service's tuned defaults
+        let tuned = WarmthThresholds {
+            stressed_ratio: 0.6,
+            collapsing_ratio: 0.1,
+            ewma_alpha: None,
+        };
+        assert_eq!(config.candidates[0].thresholds(tuned), tuned);
+        let sensitive = config.candidates[1].thresholds(tuned);
+        assert_eq!(sensitive.stressed_ratio, 0.9);
+        assert_eq!(sensitive.collapsing_ratio, 0.1);
+    }
+
+    #[tokio::test]
+    async fn test_backtest_scores_against_history() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        // Steady traffic for two hours, then an hour of silence, then recovery
+        for minute in (0..240).step_by(5) {
+            if (120..180).contains(&minute) {
+                continue;
+            }
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: "zone-a".to_string(),
+                    timestamp: at(minute),
+                    weight: 10,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let config: BacktestConfig = serde_json::from_value(serde_json::json!({
+            "candidates": [{ "name": "current" }],
+            "history": [
+                { "bucket": "zone-a", "from": at(125), "to": at(175) },
+                { "bucket": "zone-b", "from": at(10), "to": at(20) }
+            ]
+        }))
+        .unwrap();
+
+        let report = run_backtest(
+            &storage,
+            &config,
+            WarmthThresholds::default(),
+            at(60),
+            at(240),
+        )
+        .await
+        .unwrap();
+        let current = &report.candidates[0];
+
+        assert_eq!(report.recorded_alerts, 2);
+        assert_eq!(current.true_positives, 1);
+        assert_eq!(current.false_positives, 0);
+        assert_eq!(current.missed, 1);
+        assert_eq!(current.alerts.len(), 1);
+        assert_eq!(current.alerts[0].status, WarmthStatus::Dead);
+
+        assert!(
+            run_backtest(
+                &storage,
+                &config,
+                WarmthThresholds::default(),
+                at(60),
+                at(0)
+            )
+            .await
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_current_candidate_uses_service_thresholds() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        // Traffic drops to a third for an hour
+        for minute in (0..240).step_by(5) {
+            let weight = if (120..180).contains(&minute) { 3 } else { 10 };
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: "zone-a".to_string(),
+                    timestamp: at(minute),
+                    weight,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+        let config: BacktestConfig =
+            serde_json::from_value(serde_json::json!({ "candidates": [{ "name": "current" }] }))
+                .unwrap();
+
+        // A third of the usual traffic is only stressed by the built-in ratios...
+        let report = run_backtest(
+            &storage,
+            &config,
+            WarmthThresholds::default(),
+            at(60),
+            at(240),
+        )
+        .await
+        .unwrap();
+        assert!(report.candidates[0].alerts.is_empty());
+
+        // ...but collapsing on an instance tuned to collapse below 60%
+        let tuned = WarmthThresholds {
+            stressed_ratio: 0.9,
+            collapsing_ratio: 0.6,
+            ewma_alpha: None,
+        };
+        let report = run_backtest(&storage, &config, tuned, at(60), at(240))
+            .await
+            .unwrap();
+        let current = &report.candidates[0];
+        assert_eq!(current.thresholds, tuned);
+        assert_eq!(current.alerts.len(), 1);
+        assert_eq!(current.alerts[0].status, WarmthStatus::Collapsing);
+    }
+}
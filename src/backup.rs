@@ -0,0 +1,180 @@
+//! Online database backups behind `POST /admin/backup`.
+//!
+//! A backup is a consistent copy of the whole database, taken with
+//! SQLite's `VACUUM INTO` while the server keeps serving: readers and
+//! writers carry on, and the copy reflects a single moment. Buffered
+//! signals are flushed first, so the copy holds everything accepted so far.
+//!
+//! With `INFRARED_BACKUP_DIR` set, backups are written there as
+//! `infrared-YYYYMMDDTHHMMSSZ.db`. Otherwise, or when asked to, the copy is
+//! streamed back in the response and never kept on the server.
+//!
+//! # Privacy
+//!
+//! A backup holds exactly what the database holds (see
+//! [`crate::storage`]). Streamed copies pass through a temporary file that
+//! is unlinked before the first byte is sent.
+
+use std::path::{Path, PathBuf};
+
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::Stream;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncReadExt;
+
+use crate::storage::SignalStore;
+
+/// Content type of a streamed backup.
+pub const BACKUP_CONTENT_TYPE: &str = "application/vnd.sqlite3";
+
+/// Bytes per chunk of a streamed backup.
+const STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Query parameters for `POST /admin/backup`.
+#[derive(Debug, Default, Deserialize)]
+pub struct BackupQuery {
+    /// Stream the backup back even when a backup directory is configured.
+    #[serde(default)]
+    pub download: bool,
+}
+
+/// Response for a backup written to the backup directory.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BackupResponse {
+    /// Where the backup was written.
+    pub path: PathBuf,
+
+    /// Size of the backup file.
+    pub bytes: u64,
+
+    /// When the backup was taken.
+    pub taken_at: DateTime<Utc>,
+}
+
+/// File name of a backup taken at `now`.
+pub fn file_name(now: DateTime<Utc>) -> String {
+    format!("infrared-{}.db", now.format("%Y%m%dT%H%M%SZ"))
+}
+
+/// A hidden, unique temporary path in `dir` for a backup taken at `now`.
+pub(crate) fn temp_path(dir: &Path, now: DateTime<Utc>) -> PathBuf {
+    dir.join(format!(
+        ".{}-{}-{}.tmp",
+        file_name(now),
+        std::process::id(),
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ))
+}
+
+/// Write a backup of `storage` into `dir`.
+pub async fn write_backup(
+    storage: &dyn SignalStore,
+    dir: &Path,
+    now: DateTime<Utc>,
+) -> anyhow::Result<BackupResponse> {
+    let path = dir.join(file_name(now));
+    storage.backup_to(&path).await?;
+
+    Ok(BackupResponse {
+        bytes: tokio::fs::metadata(&path).await?.len(),
+        path,
+        taken_at: now,
+    })
+}
+
+/// Take a backup of `storage` and stream it, without keeping a copy.
+///
+/// The backup is written to a temporary file under `dir`, which is opened
+/// and unlinked before streaming starts. Returns the backup's size with
+/// the stream.
+pub async fn stream_backup(
+    storage: &dyn SignalStore,
+    dir: &Path,
+    now: DateTime<Utc>,
+) -> anyhow::Result<(
+    u64,
+    impl Stream<Item = std::io::Result<Bytes>> + Send + 'static,
+)> {
+    let path = temp_path(dir, now);
+    storage.backup_to(&path).await?;
+
+    let opened = tokio::fs::File::open(&path).await;
+    tokio::fs::remove_file(&path).await?;
+    let file = opened?;
+    let bytes = file.metadata().await?.len();
+
+    Ok((bytes, file_chunks(file)))
+}
+
+/// The rest of `file`, as a stream of chunks.
+pub(crate) fn file_chunks(
+    file: tokio::fs::File,
+) -> impl Stream<Item = std::io::Result<Bytes>> + Send + 'static {
+    stream::try_unfold(file, |mut file| async move {
+        let mut chunk = vec![0; STREAM_CHUNK_BYTES];
+        let read = file.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        chunk.truncate(read);
+        Ok(Some((Bytes::from(chunk), file)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LifeSignal;
+    use crate::storage::Storage;
+    use chrono::TimeZone;
+    use futures_util::TryStreamExt;
+
+    #[tokio::test]
+    async fn test_backup_is_a_working_database() {
+        let root = std::env::temp_dir().join(format!("infrared-backup-{}", std::process::id()));
+        let dir = root.join("backups");
+        std::fs::create_dir_all(&dir).unwrap();
+        let url = format!("sqlite:{}?mode=rwc", root.join("live.db").display());
+        let storage = Storage::new(&url).await.unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: now,
+                weight: 3,
+                channel: None,
+            })
+            .await
+            .unwrap();
+
+        let backup = write_backup(&storage, &dir, now).await.unwrap();
+        assert_eq!(backup.path, dir.join("infrared-20240501T120000Z.db"));
+        assert!(backup.bytes > 0);
+        let copy = Storage::connect(&format!("sqlite:{}", backup.path.display()))
+            .await
+            .unwrap();
+        assert_eq!(
+            copy.query_bucket_window("zone-a", 60, now).await.unwrap(),
+            3
+        );
+
+        // Taking the same backup twice does not overwrite the first
+        assert!(write_backup(&storage, &dir, now).await.is_err());
+
+        let (bytes, chunks) = stream_backup(&storage, &dir, now).await.unwrap();
+        let streamed: Vec<Bytes> = chunks.try_collect().await.unwrap();
+        assert_eq!(streamed.iter().map(|c| c.len() as u64).sum::<u64>(), bytes);
+        // The streamed copy is not left behind
+        assert!(
+            std::fs::read_dir(&dir).unwrap().all(|entry| !entry
+                .unwrap()
+                .path()
+                .to_string_lossy()
+                .ends_with(".tmp"))
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
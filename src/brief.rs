@@ -0,0 +1,243 @@
+//! Printable situation briefs.
+//!
+//! Coordination meetings in low-connectivity settings still run on printed
+//! handouts. `GET /situation/:country.pdf` renders the same
+//! [`SituationReport`] as `GET /situation/:country` onto A4 pages: the
+//! composite risk, the most severe issues, bucket statuses, recent
+//! changepoints and the latest reports. Built only with the `pdf` feature.
+//!
+//! Text uses the PDF built-in Helvetica, so no font files are needed;
+//! characters outside Windows-1252 are left out.
+//!
+//! # Privacy
+//!
+//! A brief prints what the JSON report serves, nothing more.
+
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference};
+use printpdf::{PdfLayerReference, PdfPageIndex};
+
+use crate::situation::SituationReport;
+
+/// A4 portrait, in millimetres.
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 18.0;
+
+/// Longest line printed before truncation, in characters.
+const MAX_LINE_CHARS: usize = 100;
+
+/// Most issues printed; the JSON report has them all.
+pub const MAX_BRIEF_ISSUES: usize = 15;
+
+/// Lays out lines top to bottom, starting a new page when one fills up.
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+    pages: usize,
+}
+
+impl Writer {
+    fn new(title: &str) -> anyhow::Result<Self> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Brief");
+        let regular = doc.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let layer = doc.get_page(page).get_layer(layer);
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            y: PAGE_HEIGHT - MARGIN,
+            pages: 1,
+        })
+    }
+
+    fn line(&mut self, text: &str, size: f32, bold: bool) {
+        let height = size * 0.5;
+        if self.y - height < MARGIN {
+            self.pages += 1;
+            let (page, layer): (PdfPageIndex, _) = self.doc.add_page(
+                Mm(PAGE_WIDTH),
+                Mm(PAGE_HEIGHT),
+                format!("Brief {}", self.pages),
+            );
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= height;
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer
+            .use_text(truncate(text), size, Mm(MARGIN), Mm(self.y), font);
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.y -= 4.0;
+        self.line(text, 13.0, true);
+    }
+
+    fn text(&mut self, text: &str) {
+        self.line(text, 10.0, false);
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_LINE_CHARS {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(MAX_LINE_CHARS - 3).collect();
+    short.push_str("...");
+    short
+}
+
+/// Render a situation report as a PDF document.
+pub fn render_pdf(report: &SituationReport) -> anyhow::Result<Vec<u8>> {
+    let title = format!("Situation brief: {}", report.country);
+    let mut w = Writer::new(&title)?;
+
+    w.line(&title, 20.0, true);
+    w.text(&format!(
+        "Generated {}",
+        report.generated_at.format("%Y-%m-%d %H:%M UTC")
+    ));
+    w.heading(&format!(
+        "Composite risk: {:.1} / 100",
+        report.composite_risk
+    ));
+
+    w.heading(&format!("Issues ({})", report.issues.len()));
+    if report.issues.is_empty() {
+        w.text("None reported.");
+    }
+    for issue in report.issues.iter().take(MAX_BRIEF_ISSUES) {
+        w.text(&format!(
+            "[{}] {} - {} ({})",
+            issue.severity.label(),
+            issue.title,
+            issue.location,
+            issue.source.label()
+        ));
+    }
+    if report.issues.len() > MAX_BRIEF_ISSUES {
+        w.text(&format!(
+            "... and {} more",
+            report.issues.len() - MAX_BRIEF_ISSUES
+        ));
+    }
+
+    w.heading("Buckets");
+    if report.buckets.is_empty() {
+        w.text("No buckets report on this country.");
+    }
+    for bucket in &report.buckets {
+        w.text(&format!(
+            "{}: {} ({} in the last {} min, average {:.1})",
+            bucket.bucket,
+            bucket.status.name(),
+            bucket.current_window_total,
+            bucket.window_minutes,
+            bucket.recent_average
+        ));
+    }
+
+    w.heading("Status changes");
+    if report.changepoints.is_empty() {
+        w.text("None.");
+    }
+    for change in &report.changepoints {
+        w.text(&format!(
+            "{}  {}: {} -> {}",
+            change.at.format("%Y-%m-%d %H:%M"),
+            change.bucket,
+            change.previous_status.name(),
+            change.status.name()
+        ));
+    }
+
+    w.heading("Latest reports");
+    if report.reports.is_empty() {
+        w.text("None.");
+    }
+    for link in &report.reports {
+        let published = link
+            .published
+            .map(|at| at.format("%Y-%m-%d  ").to_string())
+            .unwrap_or_default();
+        w.text(&format!("{}{}", published, link.title));
+        w.text(&format!("    {}", link.url));
+    }
+
+    if !report.errors.is_empty() {
+        let sources: Vec<&str> = report.errors.iter().map(|e| e.source.label()).collect();
+        w.heading("Unavailable sources");
+        w.text(&sources.join(", "));
+    }
+
+    Ok(w.doc.save_to_bytes()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn report(changes: usize) -> SituationReport {
+        use crate::dashboard::{Issue, IssueCategory, IssueSource};
+        use crate::model::{IssueSeverity, WarmthStatus};
+        use crate::situation::Changepoint;
+
+        let issues = (0..3)
+            .map(|i| {
+                Issue::new(
+                    IssueSource::Ioda,
+                    IssueCategory::InternetOutage,
+                    IssueSeverity::Critical,
+                    "Sudan",
+                    "SD",
+                    &format!("Outage {} — الخرطوم", i),
+                    "Connectivity dropped",
+                    Utc::now(),
+                )
+            })
+            .collect();
+        let changepoints = (0..changes)
+            .map(|_| Changepoint {
+                bucket: "khartoum-1".to_string(),
+                at: Utc::now(),
+                previous_status: WarmthStatus::Alive,
+                status: WarmthStatus::Dead,
+            })
+            .collect();
+        SituationReport {
+            country: "SD".to_string(),
+            generated_at: Utc::now(),
+            composite_risk: 72.5,
+            issues,
+            buckets: Vec::new(),
+            changepoints,
+            reports: Vec::new(),
+            errors: Vec::new(),
+            health: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_pdf() {
+        // Characters outside Windows-1252 are dropped, not an error
+        let pdf = render_pdf(&report(2)).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+
+        // Long reports spill onto further pages
+        let long = render_pdf(&report(500)).unwrap();
+        assert!(long.len() > pdf.len());
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short"), "short");
+        let long = "x".repeat(150);
+        assert_eq!(truncate(&long).chars().count(), MAX_LINE_CHARS);
+    }
+}
@@ -0,0 +1,141 @@
+//! Bucket metadata.
+//!
+//! A bucket is otherwise known only by its name and its signals. Operators
+//! can annotate it with a display name, a description of what it stands
+//! for and how often it is expected to signal, and can override the warmth
+//! thresholds for buckets whose normal activity is burstier (or steadier)
+//! than most, or judge it against an exponentially weighted baseline.
+//! Threshold overrides apply to the bucket's warmth wherever it is
+//! computed; the other fields are for people reading the API and do not
+//! change detection (see `PUT /buckets/:bucket/heartbeat` for interval
+//! monitoring).
+//!
+//! # Privacy
+//!
+//! Metadata is operator-written and bucket-level. Nothing about senders is
+//! stored.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::WarmthThresholds;
+
+/// Longest accepted display name, in characters.
+pub const MAX_DISPLAY_NAME_CHARS: usize = 200;
+
+/// Longest accepted description, in characters.
+pub const MAX_DESCRIPTION_CHARS: usize = 4000;
+
+/// Operator-set metadata for one bucket, the body of
+/// `PUT /buckets/:bucket/meta` and the response of `GET`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BucketMeta {
+    /// Human-readable name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+
+    /// What the bucket stands for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// How often the bucket is expected to signal, in minutes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_interval_minutes: Option<u32>,
+
+    /// Warmth thresholds used instead of the defaults.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thresholds: Option<WarmthThresholds>,
+}
+
+impl BucketMeta {
+    /// Whether no field is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check the metadata is acceptable to store.
+    ///
+    /// Text fields are bounded in length, the interval must be positive and
+    /// threshold ratios must satisfy `0 < collapsing_ratio <
+    /// stressed_ratio <= 1`, and `0 < ewma_alpha <= 1`.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(name) = &self.display_name {
+            anyhow::ensure!(
+                name.chars().count() <= MAX_DISPLAY_NAME_CHARS,
+                "display_name is longer than {} characters",
+                MAX_DISPLAY_NAME_CHARS
+            );
+        }
+        if let Some(description) = &self.description {
+            anyhow::ensure!(
+                description.chars().count() <= MAX_DESCRIPTION_CHARS,
+                "description is longer than {} characters",
+                MAX_DESCRIPTION_CHARS
+            );
+        }
+        anyhow::ensure!(
+            self.expected_interval_minutes != Some(0),
+            "expected_interval_minutes must be positive"
+        );
+        if let Some(thresholds) = &self.thresholds {
+            thresholds.validate()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(BucketMeta::default().validate().is_ok());
+
+        let meta: BucketMeta = serde_json::from_str(
+            r#"{ "display_name": "Gulu market", "thresholds": { "stressed_ratio": 0.5 } }"#,
+        )
+        .unwrap();
+        assert!(meta.validate().is_ok());
+        assert_eq!(meta.thresholds.unwrap().collapsing_ratio, 0.2);
+
+        let zero_interval = BucketMeta {
+            expected_interval_minutes: Some(0),
+            ..Default::default()
+        };
+        assert!(zero_interval.validate().is_err());
+
+        for (stressed_ratio, collapsing_ratio) in [(0.2, 0.5), (1.5, 0.2), (0.8, 0.0), (0.5, 0.5)] {
+            let meta = BucketMeta {
+                thresholds: Some(WarmthThresholds {
+                    stressed_ratio,
+                    collapsing_ratio,
+                    ewma_alpha: None,
+                }),
+                ..Default::default()
+            };
+            assert!(
+                meta.validate().is_err(),
+                "{} / {}",
+                stressed_ratio,
+                collapsing_ratio
+            );
+        }
+
+        for ewma_alpha in [0.0, 1.5, f64::NAN] {
+            let meta = BucketMeta {
+                thresholds: Some(WarmthThresholds {
+                    ewma_alpha: Some(ewma_alpha),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            assert!(meta.validate().is_err(), "{}", ewma_alpha);
+        }
+
+        let long_name = BucketMeta {
+            display_name: Some("x".repeat(MAX_DISPLAY_NAME_CHARS + 1)),
+            ..Default::default()
+        };
+        assert!(long_name.validate().is_err());
+    }
+}
@@ -0,0 +1,475 @@
+//! Structured bundle export of persisted issues.
+//!
+//! National emergency systems ingest threat and incident data as STIX 2.1
+//! bundles, usually over TAXII. `GET /feed/bundle` serves the latest
+//! persisted dashboard snapshot in that shape, so Infrared's output can be
+//! loaded into their platforms without a custom importer:
+//!
+//! - every issue is an `incident`, created by the Infrared `identity`;
+//! - every affected country is a `location`, linked by a `located-at`
+//!   relationship;
+//! - issues from different sources about the same country, overlapping in
+//!   time (within [`CORRELATION_WINDOW_HOURS`]), are linked by `related-to`.
+//!
+//! IDs are name-based UUIDs derived from issue IDs and country codes, so the
+//! same incident keeps its ID across bundles. Each snapshot is a new version
+//! of the incidents in it: `created` is when the issue started and
+//! `modified` when the snapshot was taken. Infrared-specific fields carry an
+//! `x_infrared_` prefix.
+//!
+//! # Privacy
+//!
+//! A bundle carries exactly what the persisted dashboard snapshot does:
+//! country-level issues from public sources and partner detectors.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::dashboard::{DashboardResponse, Issue, IssueCategory, IssueSource};
+use crate::model::IssueSeverity;
+
+/// STIX version of every object in a bundle.
+pub const SPEC_VERSION: &str = "2.1";
+
+/// How far apart two issues may be in time and still be related, in hours.
+pub const CORRELATION_WINDOW_HOURS: i64 = 24;
+
+/// Namespace of the name-based object IDs.
+const NAMESPACE: Uuid = Uuid::from_u128(0x6f1d_2c7a_43b8_5e09_9a4d_1b2e_8c3f_7d60);
+
+/// Stable ID of the object of `kind` named `name`.
+fn object_id(kind: &str, name: &str) -> String {
+    let uuid = Uuid::new_v5(&NAMESPACE, format!("{}:{}", kind, name).as_bytes());
+    format!("{}--{}", kind, uuid)
+}
+
+/// Query parameters for GET /feed/bundle.
+#[derive(Debug, Deserialize)]
+pub struct BundleQuery {
+    /// Export the latest snapshot taken at or before this time instead of
+    /// the latest one.
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// A STIX bundle.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bundle {
+    /// Always "bundle".
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+
+    /// Derived from the snapshot time, so re-exporting a snapshot yields
+    /// the same bundle.
+    pub id: String,
+
+    /// When the exported snapshot was taken.
+    pub x_infrared_snapshot_at: DateTime<Utc>,
+
+    /// The producer, locations, incidents and relationships, in that order.
+    pub objects: Vec<BundleObject>,
+}
+
+/// An object in a bundle.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum BundleObject {
+    /// The producer.
+    Identity(Identity),
+    /// An affected country.
+    Location(BundleLocation),
+    /// An issue.
+    Incident(Incident),
+    /// A link between two of the above.
+    Relationship(Relationship),
+}
+
+impl BundleObject {
+    /// The object's ID.
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Identity(o) => &o.id,
+            Self::Location(o) => &o.id,
+            Self::Incident(o) => &o.id,
+            Self::Relationship(o) => &o.id,
+        }
+    }
+}
+
+/// Infrared itself, the producer of every incident.
+///
+/// Identities and locations never change, so they carry the Unix epoch as
+/// `created` and `modified`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Identity {
+    pub spec_version: &'static str,
+    pub id: String,
+    pub created: DateTime<Utc>,
+    pub modified: DateTime<Utc>,
+    pub name: String,
+    pub identity_class: &'static str,
+}
+
+/// An affected country.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BundleLocation {
+    pub spec_version: &'static str,
+    pub id: String,
+    pub created: DateTime<Utc>,
+    pub modified: DateTime<Utc>,
+    pub name: String,
+
+    /// ISO country code, as the issue reported it.
+    pub country: String,
+}
+
+/// A pointer to the issue upstream or a document about it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExternalReference {
+    pub source_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+}
+
+/// An issue.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Incident {
+    pub spec_version: &'static str,
+    pub id: String,
+    pub created: DateTime<Utc>,
+    pub modified: DateTime<Utc>,
+    pub created_by_ref: String,
+    pub name: String,
+    pub description: String,
+
+    /// The Infrared issue ID first, then the upstream page and linked
+    /// documents.
+    pub external_references: Vec<ExternalReference>,
+
+    pub x_infrared_source: IssueSource,
+    pub x_infrared_category: IssueCategory,
+    pub x_infrared_severity: IssueSeverity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_infrared_normalized_severity: Option<f64>,
+    pub x_infrared_ongoing: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_infrared_ended_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_infrared_impact: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub x_infrared_impact_label: Option<String>,
+}
+
+/// A link between two objects.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Relationship {
+    pub spec_version: &'static str,
+    pub id: String,
+    pub created: DateTime<Utc>,
+    pub modified: DateTime<Utc>,
+
+    /// `located-at` or `related-to`.
+    pub relationship_type: &'static str,
+    pub source_ref: String,
+    pub target_ref: String,
+}
+
+impl Relationship {
+    fn new(
+        relationship_type: &'static str,
+        source_ref: &str,
+        target_ref: &str,
+        created: DateTime<Utc>,
+        modified: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            spec_version: SPEC_VERSION,
+            id: object_id(
+                "relationship",
+                &format!("{}:{}:{}", relationship_type, source_ref, target_ref),
+            ),
+            created,
+            modified,
+            relationship_type,
+            source_ref: source_ref.to_string(),
+            target_ref: target_ref.to_string(),
+        }
+    }
+}
+
+fn incident(issue: &Issue, producer: &str, modified: DateTime<Utc>) -> Incident {
+    let mut references = vec![ExternalReference {
+        source_name: "infrared".to_string(),
+        url: None,
+        external_id: Some(issue.id.clone()),
+    }];
+    if let Some(url) = &issue.url {
+        references.push(ExternalReference {
+            source_name: issue.source.label().to_string(),
+            url: Some(url.clone()),
+            external_id: None,
+        });
+    }
+    references.extend(issue.links.iter().map(|link| ExternalReference {
+        source_name: link.title.clone(),
+        url: Some(link.url.clone()),
+        external_id: None,
+    }));
+
+    Incident {
+        spec_version: SPEC_VERSION,
+        id: object_id("incident", &issue.id),
+        created: issue.timestamp,
+        modified: modified.max(issue.timestamp),
+        created_by_ref: producer.to_string(),
+        name: issue.title.clone(),
+        description: issue.description.clone(),
+        external_references: references,
+        x_infrared_source: issue.source,
+        x_infrared_category: issue.category,
+        x_infrared_severity: issue.severity,
+        x_infrared_normalized_severity: issue.normalized_severity,
+        x_infrared_ongoing: issue.is_ongoing,
+        x_infrared_ended_at: issue.end_timestamp,
+        x_infrared_impact: issue.impact_value,
+        x_infrared_impact_label: issue.impact_label.clone(),
+    }
+}
+
+/// Every country an issue affects, as `(name, code)`, primary first.
+fn countries(issue: &Issue) -> Vec<(&str, &str)> {
+    if issue.locations.is_empty() {
+        return vec![(issue.location.as_str(), issue.location_code.as_str())];
+    }
+    issue
+        .locations
+        .iter()
+        .map(|l| (l.name.as_str(), l.code.as_str()))
+        .collect()
+}
+
+/// Whether two issues are close enough in time to be related: their spans
+/// (an ongoing issue lasting until `now`) overlap once widened by
+/// [`CORRELATION_WINDOW_HOURS`].
+fn overlapping(a: &Issue, b: &Issue, now: DateTime<Utc>) -> bool {
+    let window = chrono::Duration::hours(CORRELATION_WINDOW_HOURS);
+    let a_end = a.end_timestamp.unwrap_or(now);
+    let b_end = b.end_timestamp.unwrap_or(now);
+    a.timestamp <= b_end + window && b.timestamp <= a_end + window
+}
+
+/// Export a dashboard snapshot as a bundle.
+///
+/// `canonical` maps a country code to the code countries are keyed by, so
+/// alpha-2 and alpha-3 sources share one location (see
+/// [`crate::dashboard::Dashboard::canonical_country_code`]).
+pub fn build_bundle(snapshot: &DashboardResponse, canonical: impl Fn(&str) -> String) -> Bundle {
+    let taken_at = snapshot.timestamp;
+    let producer = Identity {
+        spec_version: SPEC_VERSION,
+        id: object_id("identity", "infrared"),
+        created: DateTime::UNIX_EPOCH,
+        modified: DateTime::UNIX_EPOCH,
+        name: "Infrared".to_string(),
+        identity_class: "system",
+    };
+
+    let mut locations: BTreeMap<String, BundleLocation> = BTreeMap::new();
+    let mut by_country: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut incidents = Vec::new();
+    let mut relationships = Vec::new();
+
+    for (index, issue) in snapshot.issues.iter().enumerate() {
+        let incident = incident(issue, &producer.id, taken_at);
+        for (name, code) in countries(issue) {
+            let key = canonical(code);
+            let location = locations
+                .entry(key.clone())
+                .or_insert_with(|| BundleLocation {
+                    spec_version: SPEC_VERSION,
+                    id: object_id("location", &key),
+                    created: DateTime::UNIX_EPOCH,
+                    modified: DateTime::UNIX_EPOCH,
+                    name: name.to_string(),
+                    country: code.to_uppercase(),
+                });
+            let issues = by_country.entry(key).or_default();
+            if issues.contains(&index) {
+                continue;
+            }
+            issues.push(index);
+            relationships.push(Relationship::new(
+                "located-at",
+                &incident.id,
+                &location.id,
+                incident.created,
+                incident.modified,
+            ));
+        }
+        incidents.push(incident);
+    }
+
+    // Correlate across sources within each country, each pair once
+    let mut related = std::collections::BTreeSet::new();
+    for indices in by_country.values() {
+        for (n, &a) in indices.iter().enumerate() {
+            for &b in &indices[n + 1..] {
+                let (ia, ib) = (&snapshot.issues[a], &snapshot.issues[b]);
+                if ia.source != ib.source && overlapping(ia, ib, taken_at) {
+                    let (first, second) = if incidents[a].id <= incidents[b].id {
+                        (a, b)
+                    } else {
+                        (b, a)
+                    };
+                    related.insert((first, second));
+                }
+            }
+        }
+    }
+    for (a, b) in related {
+        let (first, second) = (&incidents[a], &incidents[b]);
+        relationships.push(Relationship::new(
+            "related-to",
+            &first.id,
+            &second.id,
+            first.created.max(second.created),
+            taken_at.max(first.created).max(second.created),
+        ));
+    }
+
+    let mut objects = vec![BundleObject::Identity(producer)];
+    objects.extend(locations.into_values().map(BundleObject::Location));
+    objects.extend(incidents.into_iter().map(BundleObject::Incident));
+    objects.extend(relationships.into_iter().map(BundleObject::Relationship));
+
+    Bundle {
+        kind: "bundle",
+        id: object_id("bundle", &taken_at.timestamp().to_string()),
+        x_infrared_snapshot_at: taken_at,
+        objects,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::DashboardSummary;
+
+    fn issue(source: IssueSource, code: &str, hours_ago: i64, now: DateTime<Utc>) -> Issue {
+        Issue::new(
+            source,
+            IssueCategory::Conflict,
+            IssueSeverity::Critical,
+            "Sudan",
+            code,
+            "Clashes reported",
+            "Fighting near the capital",
+            now - chrono::Duration::hours(hours_ago),
+        )
+    }
+
+    fn snapshot(issues: Vec<Issue>, now: DateTime<Utc>) -> DashboardResponse {
+        DashboardResponse {
+            timestamp: now,
+            summary: DashboardSummary::from_issues(&issues),
+            issues,
+            errors: Vec::new(),
+            health: Vec::new(),
+            suppressed: Vec::new(),
+        }
+    }
+
+    fn canonical(code: &str) -> String {
+        match code.to_uppercase().as_str() {
+            "SD" => "SDN".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    fn relationships<'a>(bundle: &'a Bundle, kind: &str) -> Vec<&'a Relationship> {
+        bundle
+            .objects
+            .iter()
+            .filter_map(|o| match o {
+                BundleObject::Relationship(r) if r.relationship_type == kind => Some(r),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_bundle() {
+        let now = Utc::now();
+        let issues = vec![
+            issue(IssueSource::Acled, "SDN", 2, now),
+            issue(IssueSource::Ioda, "SD", 1, now),
+            // Same source: not related to the first
+            issue(IssueSource::Acled, "SDN", 3, now),
+            // Long over by the time the others started
+            issue(IssueSource::ReliefWeb, "SDN", 200, now)
+                .with_end(now - chrono::Duration::hours(150)),
+            issue(IssueSource::Ioda, "TD", 1, now),
+        ];
+        let bundle = build_bundle(&snapshot(issues.clone(), now), canonical);
+
+        assert_eq!(bundle.kind, "bundle");
+        let locations = bundle
+            .objects
+            .iter()
+            .filter(|o| matches!(o, BundleObject::Location(_)))
+            .count();
+        assert_eq!(locations, 2);
+        assert_eq!(relationships(&bundle, "located-at").len(), 5);
+
+        // ACLED x IODA twice in Sudan; nothing across countries
+        assert_eq!(relationships(&bundle, "related-to").len(), 2);
+
+        let json = serde_json::to_value(&bundle).unwrap();
+        assert_eq!(json["objects"][0]["type"], "identity");
+        let incident = json["objects"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|o| o["type"] == "incident")
+            .unwrap();
+        assert_eq!(incident["spec_version"], "2.1");
+        assert_eq!(incident["x_infrared_source"], "acled");
+        assert_eq!(
+            incident["external_references"][0]["external_id"],
+            issues[0].id.as_str()
+        );
+    }
+
+    #[test]
+    fn test_ids_are_stable() {
+        let now = Utc::now();
+        let issues = vec![
+            issue(IssueSource::Acled, "SDN", 2, now),
+            issue(IssueSource::Ioda, "SD", 1, now),
+        ];
+        let first = build_bundle(&snapshot(issues.clone(), now), canonical);
+        let later = now + chrono::Duration::hours(1);
+        let second = build_bundle(&snapshot(issues, later), canonical);
+
+        let ids = |b: &Bundle| {
+            b.objects
+                .iter()
+                .map(|o| o.id().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&first), ids(&second));
+        assert_ne!(first.id, second.id);
+
+        // A new snapshot is a new version of each incident
+        let modified = |b: &Bundle| match &b.objects[2] {
+            BundleObject::Incident(i) => (i.created, i.modified),
+            other => panic!("expected an incident, got {:?}", other),
+        };
+        assert_eq!(modified(&first).0, modified(&second).0);
+        assert_eq!(modified(&second).1, later);
+    }
+}
@@ -0,0 +1,268 @@
+//! Severity-dependent refresh cadence of the background dashboard refresher.
+//!
+//! Refreshing every source on one fixed interval either leaves an
+//! unfolding emergency an hour stale or spends API quotas re-fetching
+//! sources with nothing to report. The refresher instead fetches "hot"
+//! sources every `DASHBOARD_HOT_REFRESH_MINUTES` and the rest every
+//! `DASHBOARD_SNAPSHOT_INTERVAL_MINUTES`. A source is hot while it reports
+//! an emergency or critical issue, or any issue for a country that some
+//! source rates emergency or critical: sources are fetched for every
+//! monitored country at once, so a hot country is refreshed by refreshing
+//! the sources that report on it.
+//!
+//! `DASHBOARD_SOURCE_BUDGETS` caps how often a source is fetched, however
+//! hot it is, e.g. `acled=2,reliefweb=6` fetches per hour. Sources not due
+//! on a refresh keep their issues, errors and health from the previous one.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::dashboard::{Issue, IssueSeverity, IssueSource};
+
+/// One hour, the period of a source's fetch budget.
+const BUDGET_PERIOD: Duration = Duration::from_secs(3600);
+
+/// How often each data source is refreshed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshCadence {
+    /// Interval between refreshes of sources without emergency or critical
+    /// issues.
+    pub quiet_interval: Duration,
+
+    /// Interval between refreshes of sources with them.
+    pub hot_interval: Duration,
+
+    /// Most fetches per hour, by source; unlisted sources are unlimited.
+    pub budgets: HashMap<IssueSource, u32>,
+}
+
+impl RefreshCadence {
+    /// Refresh every source every `interval`, whatever it reports.
+    pub fn fixed(interval: Duration) -> Self {
+        Self {
+            quiet_interval: interval,
+            hot_interval: interval,
+            budgets: HashMap::new(),
+        }
+    }
+
+    /// Parse per-source budgets as `source=fetches_per_hour` pairs.
+    ///
+    /// ```
+    /// use infrared::cadence::RefreshCadence;
+    /// use infrared::dashboard::IssueSource;
+    ///
+    /// let budgets = RefreshCadence::parse_budgets("acled=2, cloudflare=12").unwrap();
+    /// assert_eq!(budgets[&IssueSource::CloudflareRadar], 12);
+    /// ```
+    pub fn parse_budgets(spec: &str) -> anyhow::Result<HashMap<IssueSource, u32>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, budget) = pair
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("expected source=budget, got '{}'", pair))?;
+                let source = IssueSource::parse(&name.trim().to_lowercase())
+                    .ok_or_else(|| anyhow::anyhow!("unknown data source '{}'", name.trim()))?;
+                let budget: u32 = budget.trim().parse()?;
+                anyhow::ensure!(budget > 0, "budget for '{}' must be positive", name.trim());
+                Ok((source, budget))
+            })
+            .collect()
+    }
+
+    /// Period at which the refresher wakes up to look for due sources.
+    pub fn tick(&self) -> Duration {
+        self.hot_interval.min(self.quiet_interval)
+    }
+
+    /// Interval between refreshes of `source`, stretched to fit its budget.
+    pub fn interval_for(&self, source: IssueSource, hot: bool) -> Duration {
+        let interval = if hot {
+            self.hot_interval
+        } else {
+            self.quiet_interval
+        };
+        match self.budgets.get(&source) {
+            Some(&budget) => interval.max(BUDGET_PERIOD / budget),
+            None => interval,
+        }
+    }
+
+    /// Ticks between refreshes of `source`, at least one.
+    fn ticks_for(&self, source: IssueSource, hot: bool) -> u64 {
+        let tick = self.tick().as_secs().max(1);
+        self.interval_for(source, hot)
+            .as_secs()
+            .div_ceil(tick)
+            .max(1)
+    }
+}
+
+/// Sources due for a refresh, tick by tick.
+#[derive(Debug, Clone)]
+pub struct RefreshSchedule {
+    cadence: RefreshCadence,
+    tick: u64,
+    last_fetched: HashMap<IssueSource, u64>,
+}
+
+impl RefreshSchedule {
+    /// A schedule on which every source is due at the first tick.
+    pub fn new(cadence: RefreshCadence) -> Self {
+        Self {
+            cadence,
+            tick: 0,
+            last_fetched: HashMap::new(),
+        }
+    }
+
+    /// Advance one tick and return which of `sources` to fetch now, given
+    /// the `hot` ones; those returned count as fetched.
+    pub fn due(
+        &mut self,
+        sources: impl IntoIterator<Item = IssueSource>,
+        hot: &HashSet<IssueSource>,
+    ) -> HashSet<IssueSource> {
+        let tick = self.tick;
+        self.tick += 1;
+
+        let due: HashSet<IssueSource> = sources
+            .into_iter()
+            .filter(|source| match self.last_fetched.get(source) {
+                Some(&last) => tick - last >= self.cadence.ticks_for(*source, hot.contains(source)),
+                None => true,
+            })
+            .collect();
+        for source in &due {
+            self.last_fetched.insert(*source, tick);
+        }
+        due
+    }
+}
+
+/// Sources currently reporting an emergency or critical issue, or any
+/// issue for a country some source rates that severe.
+///
+/// Countries are compared by `canonical_code`, so alpha-2 and alpha-3
+/// sources are matched alike (see
+/// [`crate::dashboard::Dashboard::canonical_country_code`]).
+pub fn hot_sources(
+    issues: &[Issue],
+    canonical_code: impl Fn(&str) -> String,
+) -> HashSet<IssueSource> {
+    let codes = |issue: &Issue| -> Vec<String> {
+        std::iter::once(issue.location_code.as_str())
+            .chain(issue.locations.iter().map(|l| l.code.as_str()))
+            .filter(|code| !code.is_empty())
+            .map(&canonical_code)
+            .collect()
+    };
+    let severe = |issue: &Issue| issue.severity >= IssueSeverity::Critical;
+
+    let hot_countries: HashSet<String> = issues
+        .iter()
+        .filter(|issue| severe(issue))
+        .flat_map(codes)
+        .collect();
+    issues
+        .iter()
+        .filter(|issue| severe(issue) || codes(issue).iter().any(|c| hot_countries.contains(c)))
+        .map(|issue| issue.source)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::IssueCategory;
+    use chrono::Utc;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    fn issue(source: IssueSource, severity: IssueSeverity, code: &str) -> Issue {
+        Issue::new(
+            source,
+            IssueCategory::InternetOutage,
+            severity,
+            code,
+            code,
+            "Test",
+            "Test",
+            Utc::now(),
+        )
+    }
+
+    fn cadence() -> RefreshCadence {
+        RefreshCadence {
+            quiet_interval: 60 * MINUTE,
+            hot_interval: 15 * MINUTE,
+            budgets: RefreshCadence::parse_budgets("acled=2").unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_parse_budgets() {
+        let budgets = RefreshCadence::parse_budgets("acled=2,Tor=30").unwrap();
+        assert_eq!(budgets[&IssueSource::Acled], 2);
+        assert_eq!(budgets[&IssueSource::TorMetrics], 30);
+
+        assert!(RefreshCadence::parse_budgets("acled").is_err());
+        assert!(RefreshCadence::parse_budgets("acled=0").is_err());
+        assert!(RefreshCadence::parse_budgets("myspace=1").is_err());
+    }
+
+    #[test]
+    fn test_budget_stretches_interval() {
+        let cadence = cadence();
+        assert_eq!(cadence.tick(), 15 * MINUTE);
+        assert_eq!(cadence.interval_for(IssueSource::Ioda, true), 15 * MINUTE);
+        assert_eq!(cadence.interval_for(IssueSource::Acled, true), 30 * MINUTE);
+        assert_eq!(cadence.interval_for(IssueSource::Acled, false), 60 * MINUTE);
+    }
+
+    #[test]
+    fn test_hot_sources_refresh_more_often() {
+        let mut schedule = RefreshSchedule::new(cadence());
+        let sources = [
+            IssueSource::Ioda,
+            IssueSource::Acled,
+            IssueSource::ReliefWeb,
+        ];
+        let hot = HashSet::from([IssueSource::Ioda, IssueSource::Acled]);
+
+        let mut fetches: HashMap<IssueSource, usize> = HashMap::new();
+        for _ in 0..8 {
+            for source in schedule.due(sources, &hot) {
+                *fetches.entry(source).or_default() += 1;
+            }
+        }
+
+        // Two hours of 15-minute ticks
+        assert_eq!(fetches[&IssueSource::Ioda], 8);
+        assert_eq!(fetches[&IssueSource::Acled], 4);
+        assert_eq!(fetches[&IssueSource::ReliefWeb], 2);
+    }
+
+    #[test]
+    fn test_hot_sources() {
+        let issues = vec![
+            issue(IssueSource::Ioda, IssueSeverity::Critical, "SD"),
+            issue(IssueSource::Acled, IssueSeverity::Warning, "SDN"),
+            issue(IssueSource::ReliefWeb, IssueSeverity::Warning, "UKR"),
+            issue(IssueSource::TorMetrics, IssueSeverity::Info, "UA"),
+        ];
+        let canonical = |code: &str| match code {
+            "SD" => "SDN".to_string(),
+            code => code.to_string(),
+        };
+
+        // ACLED reports on Sudan, which IODA rates critical
+        assert_eq!(
+            hot_sources(&issues, canonical),
+            HashSet::from([IssueSource::Ioda, IssueSource::Acled])
+        );
+        assert!(hot_sources(&issues[2..], |code: &str| code.to_string()).is_empty());
+    }
+}
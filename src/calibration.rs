@@ -0,0 +1,276 @@
+//! Severity normalization across data sources.
+//!
+//! Every source scores problems on its own scale: IODA reports a percentage
+//! drop, ACLED a fatality count, HDX a 0-10 risk score, ACAPS a 1-5 severity
+//! index. A [`Calibration`] maps each source's native score (an issue's
+//! `impact_value`) onto a common 0-100 scale through a configurable
+//! [`TransferFunction`], stored as `normalized_severity` on the issue, so
+//! issues from different sources can be ranked against each other.
+//!
+//! Issues from sources without a transfer function, or without a native
+//! score, fall back to a fixed value per [`IssueSeverity`] level.
+//!
+//! # Configuration File
+//!
+//! Keys are source names as accepted by `?source=`; entries replace the
+//! built-in defaults for that source.
+//!
+//! ```json
+//! {
+//!     "ioda": { "linear": { "min": 10, "max": 90 } },
+//!     "acled": { "log": { "min": 1, "max": 500 } }
+//! }
+//! ```
+//!
+//! # Privacy
+//!
+//! Calibration only rescales country-level scores already held on issues.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dashboard::{Issue, IssueSeverity, IssueSource};
+
+/// Maps a native score onto 0-100.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferFunction {
+    /// `min` maps to 0 and `max` to 100, linearly in between.
+    Linear { min: f64, max: f64 },
+    /// Like `Linear` on a logarithmic scale, for counts spanning orders of
+    /// magnitude (fatalities, displaced people). Both bounds must be positive.
+    Log { min: f64, max: f64 },
+}
+
+impl TransferFunction {
+    /// Map `value` onto 0-100, clamping values outside the bounds.
+    pub fn apply(&self, value: f64) -> f64 {
+        let fraction = match *self {
+            TransferFunction::Linear { min, max } => (value - min) / (max - min),
+            TransferFunction::Log { min, max } => {
+                (value.max(min).ln() - min.ln()) / (max.ln() - min.ln())
+            }
+        };
+        if fraction.is_nan() {
+            return 0.0;
+        }
+        (fraction * 100.0).clamp(0.0, 100.0)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        let (min, max) = match *self {
+            TransferFunction::Linear { min, max } => (min, max),
+            TransferFunction::Log { min, max } => {
+                anyhow::ensure!(min > 0.0, "log transfer bounds must be positive");
+                (min, max)
+            }
+        };
+        anyhow::ensure!(
+            min.is_finite() && max.is_finite() && min < max,
+            "transfer bounds must satisfy min < max"
+        );
+        Ok(())
+    }
+}
+
+/// Normalized severity for issues no transfer function applies to.
+pub fn severity_fallback(severity: IssueSeverity) -> f64 {
+    match severity {
+        IssueSeverity::Info => 10.0,
+        IssueSeverity::Warning => 40.0,
+        IssueSeverity::Critical => 70.0,
+        IssueSeverity::Emergency => 90.0,
+    }
+}
+
+/// Per-source transfer functions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calibration {
+    transfers: HashMap<IssueSource, TransferFunction>,
+}
+
+impl Default for Calibration {
+    /// Built-in transfer functions for sources with a numeric native score.
+    fn default() -> Self {
+        let transfers = HashMap::from([
+            // Percentage drop from baseline
+            (
+                IssueSource::Ioda,
+                TransferFunction::Linear {
+                    min: 0.0,
+                    max: 100.0,
+                },
+            ),
+            // Fatalities
+            (
+                IssueSource::Acled,
+                TransferFunction::Log {
+                    min: 1.0,
+                    max: 1000.0,
+                },
+            ),
+            // National risk score out of 10
+            (
+                IssueSource::HdxHapi,
+                TransferFunction::Linear {
+                    min: 0.0,
+                    max: 10.0,
+                },
+            ),
+            // INFORM Severity Index, 1 to 5
+            (
+                IssueSource::Acaps,
+                TransferFunction::Linear { min: 1.0, max: 5.0 },
+            ),
+            // Newly displaced people
+            (
+                IssueSource::IomDtm,
+                TransferFunction::Log {
+                    min: 1000.0,
+                    max: 1_000_000.0,
+                },
+            ),
+            // Percentage below the weekly pattern
+            (
+                IssueSource::Wikimedia,
+                TransferFunction::Linear {
+                    min: 0.0,
+                    max: 100.0,
+                },
+            ),
+        ]);
+        Self { transfers }
+    }
+}
+
+impl Calibration {
+    /// Parse a calibration file, layering its entries over the defaults.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let overrides: HashMap<String, TransferFunction> = serde_json::from_str(json)?;
+        let mut calibration = Self::default();
+        for (name, transfer) in overrides {
+            let source = IssueSource::parse(&name)
+                .ok_or_else(|| anyhow::anyhow!("unknown source '{}'", name))?;
+            transfer
+                .validate()
+                .map_err(|e| anyhow::anyhow!("invalid transfer for '{}': {}", name, e))?;
+            calibration.transfers.insert(source, transfer);
+        }
+        Ok(calibration)
+    }
+
+    /// Transfer function used for a source, if any.
+    pub fn transfer(&self, source: IssueSource) -> Option<&TransferFunction> {
+        self.transfers.get(&source)
+    }
+
+    /// Normalized 0-100 severity of one issue.
+    pub fn normalize(&self, issue: &Issue) -> f64 {
+        match (self.transfer(issue.source), issue.impact_value) {
+            (Some(transfer), Some(value)) => transfer.apply(value),
+            _ => severity_fallback(issue.severity),
+        }
+    }
+
+    /// Set `normalized_severity` on every issue.
+    pub fn apply(&self, issues: &mut [Issue]) {
+        for issue in issues {
+            issue.normalized_severity = Some(self.normalize(issue));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::IssueCategory;
+    use chrono::Utc;
+
+    fn issue(source: IssueSource, severity: IssueSeverity, impact: Option<f64>) -> Issue {
+        let issue = Issue::new(
+            source,
+            IssueCategory::InternetOutage,
+            severity,
+            "Test",
+            "TS",
+            "Test",
+            "Test",
+            Utc::now(),
+        );
+        match impact {
+            Some(value) => issue.with_impact(value, "test"),
+            None => issue,
+        }
+    }
+
+    #[test]
+    fn test_transfer_functions() {
+        let linear = TransferFunction::Linear {
+            min: 0.0,
+            max: 10.0,
+        };
+        assert_eq!(linear.apply(5.0), 50.0);
+        assert_eq!(linear.apply(-1.0), 0.0);
+        assert_eq!(linear.apply(12.0), 100.0);
+
+        let log = TransferFunction::Log {
+            min: 1.0,
+            max: 1000.0,
+        };
+        assert!((log.apply(10.0) - 33.33).abs() < 0.01);
+        assert!((log.apply(100.0) - 66.67).abs() < 0.01);
+        assert_eq!(log.apply(0.0), 0.0);
+        assert_eq!(log.apply(5000.0), 100.0);
+    }
+
+    #[test]
+    fn test_normalize_across_sources() {
+        let calibration = Calibration::default();
+
+        // 100 fatalities outranks a 50% outage despite the same enum severity
+        let acled = issue(IssueSource::Acled, IssueSeverity::Critical, Some(100.0));
+        let ioda = issue(IssueSource::Ioda, IssueSeverity::Critical, Some(50.0));
+        assert!(calibration.normalize(&acled) > calibration.normalize(&ioda));
+
+        // No transfer, or no native score: fall back to the severity level
+        let cloudflare = issue(IssueSource::CloudflareRadar, IssueSeverity::Warning, None);
+        assert_eq!(calibration.normalize(&cloudflare), 40.0);
+        let unscored = issue(IssueSource::Ioda, IssueSeverity::Emergency, None);
+        assert_eq!(calibration.normalize(&unscored), 90.0);
+
+        let mut issues = vec![acled];
+        calibration.apply(&mut issues);
+        assert!((issues[0].normalized_severity.unwrap() - 66.67).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_from_json() {
+        let calibration = Calibration::from_json(
+            r#"{ "ioda": { "linear": { "min": 10, "max": 60 } },
+                 "cloudflare": { "linear": { "min": 0, "max": 1 } } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            calibration.transfer(IssueSource::Ioda),
+            Some(&TransferFunction::Linear {
+                min: 10.0,
+                max: 60.0
+            })
+        );
+        assert!(calibration.transfer(IssueSource::CloudflareRadar).is_some());
+        // Defaults remain for sources not in the file
+        assert!(calibration.transfer(IssueSource::Acled).is_some());
+
+        assert!(
+            Calibration::from_json(r#"{ "gdacs": { "linear": { "min": 0, "max": 1 } } }"#).is_err()
+        );
+        assert!(
+            Calibration::from_json(r#"{ "ioda": { "log": { "min": 0, "max": 1 } } }"#).is_err()
+        );
+        assert!(
+            Calibration::from_json(r#"{ "ioda": { "linear": { "min": 5, "max": 1 } } }"#).is_err()
+        );
+    }
+}
@@ -0,0 +1,372 @@
+//! Synthetic canary buckets for self-monitoring.
+//!
+//! A built-in generator writes one signal per interval into reserved
+//! `_canary:*` buckets. Because their cadence is known, any loss of warmth
+//! in a canary points at Infrared itself (a failing database, a stalled
+//! runtime, a jumping clock) rather than at the monitored population.
+//!
+//! Infrared's own pipeline is watched the same way. Reserved
+//! `_canary:pipeline:*` buckets are fed from the metrics registry once per
+//! canary interval:
+//!
+//! - `_canary:pipeline:ingest`: the number of signals accepted from clients
+//!   since the previous interval.
+//! - `_canary:pipeline:dashboard`: one signal per interval while the last
+//!   successful dashboard refresh is at most two refresh intervals old.
+//! - `_canary:pipeline:notify`: one signal per interval unless webhook
+//!   deliveries failed and none succeeded since the previous interval.
+//!
+//! These go through ordinary alerting: when one collapses or goes dead
+//! (e.g., the dashboard refresher silently stopped), a `pipeline_stalled`
+//! alert is raised like for any other bucket in distress.
+//!
+//! Clients cannot write to canary buckets; `POST /signal` rejects them.
+//!
+//! # Privacy
+//!
+//! Canary signals are synthetic and carry no information about anyone.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{error, info, warn};
+
+use crate::aggregation::compute_warmth;
+use crate::metrics::Metrics;
+use crate::model::{LifeSignal, WarmthStatus};
+use crate::storage::{SignalStore, Storage};
+
+/// Prefix reserved for canary buckets.
+pub const CANARY_PREFIX: &str = "_canary:";
+
+/// Canary bucket written by default.
+pub const DEFAULT_CANARY_BUCKET: &str = "_canary:ingest";
+
+/// Prefix reserved for pipeline self-monitoring buckets.
+pub const PIPELINE_PREFIX: &str = "_canary:pipeline:";
+
+/// Bucket counting signals accepted from clients.
+pub const PIPELINE_INGEST_BUCKET: &str = "_canary:pipeline:ingest";
+
+/// Bucket kept warm while the dashboard refreshes.
+pub const PIPELINE_DASHBOARD_BUCKET: &str = "_canary:pipeline:dashboard";
+
+/// Bucket kept warm while webhook notifications are delivered.
+pub const PIPELINE_NOTIFY_BUCKET: &str = "_canary:pipeline:notify";
+
+/// Minimum window used to judge canary warmth, in minutes.
+const MIN_CHECK_WINDOW_MINUTES: u32 = 10;
+
+/// Whether `bucket` is reserved for canaries.
+pub fn is_canary(bucket: &str) -> bool {
+    bucket.starts_with(CANARY_PREFIX)
+}
+
+/// Whether `bucket` is reserved for pipeline self-monitoring.
+pub fn is_pipeline(bucket: &str) -> bool {
+    bucket.starts_with(PIPELINE_PREFIX)
+}
+
+/// The subsystem a pipeline bucket watches, for alert messages.
+pub fn pipeline_subsystem(bucket: &str) -> &'static str {
+    match bucket {
+        PIPELINE_INGEST_BUCKET => "signal ingest",
+        PIPELINE_DASHBOARD_BUCKET => "dashboard refresher",
+        PIPELINE_NOTIFY_BUCKET => "notification delivery",
+        _ => "pipeline",
+    }
+}
+
+/// Window used to judge a canary emitting every `interval`.
+///
+/// At least two emissions always fall inside it.
+pub fn check_window_minutes(interval: Duration) -> u32 {
+    let two_intervals = (interval.as_secs() * 2).div_ceil(60);
+    u32::try_from(two_intervals)
+        .unwrap_or(u32::MAX)
+        .max(MIN_CHECK_WINDOW_MINUTES)
+}
+
+/// Spawn the canary generator and self-health check.
+///
+/// Every `interval`, one signal is written to each canary bucket and their
+/// warmth is re-evaluated. Any status other than `alive` is logged as an
+/// error and reported by the `infrared_canary_healthy{bucket}` gauge.
+pub fn spawn_canary(
+    storage: Storage,
+    metrics: Metrics,
+    buckets: Vec<String>,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let window_minutes = check_window_minutes(interval);
+        let mut ticker = tokio::time::interval(interval);
+        let mut healthy = vec![true; buckets.len()];
+        loop {
+            ticker.tick().await;
+
+            for (bucket, was_healthy) in buckets.iter().zip(healthy.iter_mut()) {
+                let now = Utc::now();
+                if let Err(e) = emit(&storage, bucket, now).await {
+                    warn!(bucket = %bucket, error = %e, "Failed to write canary signal");
+                }
+
+                match check(&storage, &metrics, bucket, window_minutes, now).await {
+                    Ok(status) => {
+                        let is_healthy = status == WarmthStatus::Alive;
+                        if !is_healthy && *was_healthy {
+                            error!(
+                                bucket = %bucket,
+                                status = ?status,
+                                "Canary degraded: Infrared ingest or clock may be broken"
+                            );
+                        } else if is_healthy && !*was_healthy {
+                            info!(bucket = %bucket, "Canary recovered");
+                        }
+                        *was_healthy = is_healthy;
+                    }
+                    Err(e) => warn!(bucket = %bucket, error = %e, "Failed to check canary"),
+                }
+            }
+        }
+    })
+}
+
+/// Counter readings at the previous pipeline interval.
+#[derive(Debug, Default)]
+struct PipelineCounters {
+    ingested: f64,
+    delivered: f64,
+    failed: f64,
+}
+
+impl PipelineCounters {
+    fn read(metrics: &Metrics) -> Self {
+        Self {
+            ingested: metrics.sum("infrared_signals_ingested_total"),
+            delivered: metrics.sum("infrared_notifications_delivered_total"),
+            failed: metrics.sum("infrared_notification_failures_total"),
+        }
+    }
+}
+
+/// Signals owed to each pipeline bucket for the interval ending at `now`.
+///
+/// `dashboard_interval` is how often the dashboard is refreshed, if it is.
+fn pipeline_signals(
+    metrics: &Metrics,
+    previous: &mut PipelineCounters,
+    dashboard_interval: Option<Duration>,
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, i32)> {
+    let current = PipelineCounters::read(metrics);
+    let mut signals = Vec::new();
+
+    let ingested = (current.ingested - previous.ingested).max(0.0);
+    if ingested > 0.0 {
+        signals.push((PIPELINE_INGEST_BUCKET, ingested.min(i32::MAX as f64) as i32));
+    }
+
+    if let Some(interval) = dashboard_interval {
+        let fresh = metrics
+            .get("infrared_dashboard_last_refresh_timestamp_seconds", &[])
+            .is_some_and(|last| (now.timestamp() as f64 - last) <= (interval.as_secs() * 2) as f64);
+        if fresh {
+            signals.push((PIPELINE_DASHBOARD_BUCKET, 1));
+        }
+    }
+
+    let failing = current.failed > previous.failed && current.delivered <= previous.delivered;
+    if !failing {
+        signals.push((PIPELINE_NOTIFY_BUCKET, 1));
+    }
+
+    *previous = current;
+    signals
+}
+
+/// Spawn the pipeline self-monitor.
+///
+/// Every `interval`, the pipeline buckets are fed from `metrics` (see the
+/// module documentation). The dashboard bucket is only kept when the
+/// dashboard is refreshed, every `dashboard_interval`.
+pub fn spawn_pipeline_signals(
+    storage: Storage,
+    metrics: Metrics,
+    interval: Duration,
+    dashboard_interval: Option<Duration>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut previous = PipelineCounters::read(&metrics);
+        loop {
+            ticker.tick().await;
+
+            let now = Utc::now();
+            for (bucket, weight) in
+                pipeline_signals(&metrics, &mut previous, dashboard_interval, now)
+            {
+                if let Err(e) = emit_weighted(&storage, bucket, weight, now).await {
+                    warn!(bucket, error = %e, "Failed to write pipeline signal");
+                }
+            }
+        }
+    })
+}
+
+/// Write one canary signal.
+async fn emit(storage: &Storage, bucket: &str, now: DateTime<Utc>) -> anyhow::Result<()> {
+    emit_weighted(storage, bucket, 1, now).await
+}
+
+async fn emit_weighted(
+    storage: &Storage,
+    bucket: &str,
+    weight: i32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    storage
+        .insert_life_signal(&LifeSignal {
+            bucket: bucket.to_string(),
+            timestamp: now,
+            weight,
+            channel: None,
+        })
+        .await
+}
+
+/// Evaluate a canary's warmth and publish its health gauge.
+async fn check(
+    storage: &Storage,
+    metrics: &Metrics,
+    bucket: &str,
+    window_minutes: u32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<WarmthStatus> {
+    let status = compute_warmth(storage, bucket, window_minutes, now)
+        .await?
+        .status;
+    metrics.set_gauge(
+        "infrared_canary_healthy",
+        &[("bucket", bucket)],
+        if status == WarmthStatus::Alive {
+            1.0
+        } else {
+            0.0
+        },
+    );
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_canary() {
+        assert!(is_canary("_canary:ingest"));
+        assert!(!is_canary("zone-a"));
+        assert!(!is_canary("canary:ingest"));
+        assert!(is_canary(PIPELINE_NOTIFY_BUCKET));
+        assert!(is_pipeline(PIPELINE_NOTIFY_BUCKET));
+        assert!(!is_pipeline(DEFAULT_CANARY_BUCKET));
+    }
+
+    #[test]
+    fn test_pipeline_signals() {
+        let metrics = Metrics::new();
+        let now = Utc::now();
+        let hour = Some(Duration::from_secs(3600));
+        let mut previous = PipelineCounters::read(&metrics);
+
+        // Nothing has happened yet: no ingest, and the dashboard has not
+        // refreshed, but no delivery has failed either
+        assert_eq!(
+            pipeline_signals(&metrics, &mut previous, hour, now),
+            vec![(PIPELINE_NOTIFY_BUCKET, 1)]
+        );
+
+        metrics.inc_counter("infrared_signals_ingested_total", &[], 3.0);
+        metrics.inc_counter("infrared_signals_ingested_total", &[], 2.0);
+        metrics.set_gauge(
+            "infrared_dashboard_last_refresh_timestamp_seconds",
+            &[],
+            now.timestamp() as f64,
+        );
+        metrics.inc_counter("infrared_notification_failures_total", &[], 1.0);
+        assert_eq!(
+            pipeline_signals(&metrics, &mut previous, hour, now),
+            vec![(PIPELINE_INGEST_BUCKET, 5), (PIPELINE_DASHBOARD_BUCKET, 1)]
+        );
+
+        // A delivery got through again; the refresher stopped three hours ago
+        metrics.inc_counter("infrared_notification_failures_total", &[], 1.0);
+        metrics.inc_counter("infrared_notifications_delivered_total", &[], 1.0);
+        let later = now + chrono::Duration::hours(3);
+        assert_eq!(
+            pipeline_signals(&metrics, &mut previous, hour, later),
+            vec![(PIPELINE_NOTIFY_BUCKET, 1)]
+        );
+
+        // Without a dashboard, its bucket is never fed
+        metrics.set_gauge(
+            "infrared_dashboard_last_refresh_timestamp_seconds",
+            &[],
+            later.timestamp() as f64,
+        );
+        assert_eq!(
+            pipeline_signals(&metrics, &mut previous, None, later),
+            vec![(PIPELINE_NOTIFY_BUCKET, 1)]
+        );
+    }
+
+    #[test]
+    fn test_check_window_minutes() {
+        assert_eq!(check_window_minutes(Duration::from_secs(60)), 10);
+        assert_eq!(check_window_minutes(Duration::from_secs(900)), 30);
+        assert_eq!(check_window_minutes(Duration::from_secs(61 * 10)), 21);
+    }
+
+    #[tokio::test]
+    async fn test_canary_degradation() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let metrics = Metrics::new();
+        let now = Utc::now();
+
+        // An hour of canary signals once a minute
+        for minute in 1..=60 {
+            emit(
+                &storage,
+                DEFAULT_CANARY_BUCKET,
+                now - chrono::Duration::minutes(minute),
+            )
+            .await
+            .unwrap();
+        }
+        let status = check(&storage, &metrics, DEFAULT_CANARY_BUCKET, 10, now)
+            .await
+            .unwrap();
+        assert_eq!(status, WarmthStatus::Alive);
+        assert_eq!(
+            metrics.get(
+                "infrared_canary_healthy",
+                &[("bucket", DEFAULT_CANARY_BUCKET)]
+            ),
+            Some(1.0)
+        );
+
+        // Ingest stops: fifteen minutes later the canary is dead
+        let later = now + chrono::Duration::minutes(15);
+        let status = check(&storage, &metrics, DEFAULT_CANARY_BUCKET, 10, later)
+            .await
+            .unwrap();
+        assert_eq!(status, WarmthStatus::Dead);
+        assert_eq!(
+            metrics.get(
+                "infrared_canary_healthy",
+                &[("bucket", DEFAULT_CANARY_BUCKET)]
+            ),
+            Some(0.0)
+        );
+    }
+}
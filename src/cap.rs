@@ -0,0 +1,461 @@
+//! Common Alerting Protocol output.
+//!
+//! Many civil-protection systems only consume CAP 1.2. Bucket alerts whose
+//! bucket is `dead` or `collapsing`, and dashboard issues at `emergency`
+//! severity, are rendered as CAP `<alert>` messages: `GET /alerts/cap`
+//! serves the current ones as an Atom feed with each message inline, and
+//! [`spawn_cap_push`] POSTs each new one to `INFRARED_CAP_ENDPOINTS`.
+//!
+//! Message identifiers are stable while the underlying alert or issue lasts,
+//! so a consumer polling the feed sees each message once.
+//!
+//! # Privacy
+//!
+//! Messages carry what `/alerts/recent` and `/dashboard` already serve:
+//! bucket or group names, countries, and source-level descriptions.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use crate::aggregation::generate_alerts_with_tiers;
+use crate::dashboard::{Issue, IssueCategory};
+use crate::events::{Event, EventBus, next_event};
+use crate::model::{Alert, AlertSubject, IssueSeverity, WarmthStatus};
+use crate::notify::WebhookNotifier;
+use crate::severity::SeverityTiers;
+use crate::storage::Storage;
+
+/// XML namespace of CAP 1.2.
+pub const CAP_NAMESPACE: &str = "urn:oasis:names:tc:emergency:cap:1.2";
+
+/// Media type of a CAP message.
+pub const CAP_CONTENT_TYPE: &str = "application/cap+xml";
+
+/// Media type of the feed served at `GET /alerts/cap`.
+pub const ATOM_CONTENT_TYPE: &str = "application/atom+xml";
+
+/// `sender` of messages when `INFRARED_CAP_SENDER` is not set.
+pub const DEFAULT_CAP_SENDER: &str = "infrared";
+
+/// A CAP message, before rendering.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapAlert {
+    /// Unique, stable while the alert or issue lasts.
+    pub identifier: String,
+
+    /// CAP category (`Infra`, `Security`, ...).
+    pub category: &'static str,
+
+    /// What is happening, in a few words.
+    pub event: String,
+
+    /// CAP urgency (`Immediate` or `Expected`).
+    pub urgency: &'static str,
+
+    /// CAP severity (`Extreme`, `Severe`, `Moderate` or `Minor`).
+    pub severity: &'static str,
+
+    /// Short summary.
+    pub headline: String,
+
+    /// Detailed description.
+    pub description: String,
+
+    /// When it began, if known.
+    pub onset: Option<DateTime<Utc>>,
+
+    /// Where to read more.
+    pub web: Option<String>,
+
+    /// The bucket, group or location affected.
+    pub area: String,
+
+    /// ISO country code of the area, for issues.
+    pub country: Option<String>,
+}
+
+/// CAP severity of an Infrared severity level.
+fn cap_severity(severity: IssueSeverity) -> &'static str {
+    match severity {
+        IssueSeverity::Emergency => "Extreme",
+        IssueSeverity::Critical => "Severe",
+        IssueSeverity::Warning => "Moderate",
+        IssueSeverity::Info => "Minor",
+    }
+}
+
+/// CAP urgency of an Infrared severity level.
+fn cap_urgency(severity: IssueSeverity) -> &'static str {
+    if severity >= IssueSeverity::Critical {
+        "Immediate"
+    } else {
+        "Expected"
+    }
+}
+
+/// CAP category of an issue category.
+fn cap_category(category: IssueCategory) -> &'static str {
+    match category {
+        IssueCategory::InternetOutage
+        | IssueCategory::TrafficAnomaly
+        | IssueCategory::Censorship => "Infra",
+        IssueCategory::Conflict => "Security",
+        IssueCategory::FoodSecurity => "Health",
+        IssueCategory::Displacement | IssueCategory::Disaster => "Safety",
+        IssueCategory::HumanitarianEmergency => "Other",
+    }
+}
+
+/// Replace characters CAP forbids in identifiers (whitespace, `,`, `<`, `&`).
+fn identifier(parts: &[&str]) -> String {
+    parts
+        .join(":")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-_.:".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl CapAlert {
+    /// The message for a bucket or group alert, if its status is `dead` or
+    /// `collapsing`.
+    pub fn from_alert(alert: &Alert) -> Option<Self> {
+        if !matches!(alert.status, WarmthStatus::Dead | WarmthStatus::Collapsing) {
+            return None;
+        }
+        let subject = match alert.subject {
+            AlertSubject::Bucket => "bucket",
+            AlertSubject::Group => "group",
+        };
+        let since = alert
+            .distressed_since
+            .map(|at| at.timestamp().to_string())
+            .unwrap_or_default();
+        let event = match alert.status {
+            WarmthStatus::Dead => "Signs of life lost",
+            _ => "Signs of life collapsing",
+        };
+
+        Some(Self {
+            identifier: identifier(&["infrared", subject, &alert.bucket, &since]),
+            category: "Other",
+            event: event.to_string(),
+            urgency: cap_urgency(alert.severity),
+            severity: cap_severity(alert.severity),
+            headline: format!("{} {} is {}", subject, alert.bucket, alert.status.name()),
+            description: alert.message.clone(),
+            onset: alert.distressed_since,
+            web: None,
+            area: alert.bucket.clone(),
+            country: None,
+        })
+    }
+
+    /// The message for a dashboard issue, if it is at `emergency` severity.
+    pub fn from_issue(issue: &Issue) -> Option<Self> {
+        if issue.severity != IssueSeverity::Emergency {
+            return None;
+        }
+        Some(Self {
+            identifier: identifier(&["infrared", "issue", &issue.id]),
+            category: cap_category(issue.category),
+            event: issue.category.label().to_string(),
+            urgency: cap_urgency(issue.severity),
+            severity: cap_severity(issue.severity),
+            headline: issue.title.clone(),
+            description: issue.description.clone(),
+            onset: Some(issue.timestamp),
+            web: issue.url.clone(),
+            area: issue.location.clone(),
+            country: Some(issue.location_code.to_uppercase()),
+        })
+    }
+
+    /// Render the `<alert>` element, without an XML declaration.
+    pub fn to_xml(&self, sender: &str, sent: DateTime<Utc>) -> String {
+        let mut xml = String::new();
+        let _ = write!(xml, r#"<alert xmlns="{}">"#, CAP_NAMESPACE);
+        element(&mut xml, "identifier", &self.identifier);
+        element(&mut xml, "sender", sender);
+        element(&mut xml, "sent", &cap_time(sent));
+        element(&mut xml, "status", "Actual");
+        element(&mut xml, "msgType", "Alert");
+        element(&mut xml, "scope", "Public");
+        xml.push_str("<info>");
+        element(&mut xml, "category", self.category);
+        element(&mut xml, "event", &self.event);
+        element(&mut xml, "urgency", self.urgency);
+        element(&mut xml, "severity", self.severity);
+        element(&mut xml, "certainty", "Observed");
+        if let Some(onset) = self.onset {
+            element(&mut xml, "onset", &cap_time(onset));
+        }
+        element(&mut xml, "senderName", "Infrared");
+        element(&mut xml, "headline", &self.headline);
+        element(&mut xml, "description", &self.description);
+        if let Some(web) = &self.web {
+            element(&mut xml, "web", web);
+        }
+        xml.push_str("<area>");
+        element(&mut xml, "areaDesc", &self.area);
+        if let Some(country) = &self.country {
+            xml.push_str("<geocode>");
+            element(&mut xml, "valueName", "ISO 3166-1");
+            element(&mut xml, "value", country);
+            xml.push_str("</geocode>");
+        }
+        xml.push_str("</area></info></alert>");
+        xml
+    }
+
+    /// Render a standalone CAP document.
+    pub fn to_document(&self, sender: &str, sent: DateTime<Utc>) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{}",
+            self.to_xml(sender, sent)
+        )
+    }
+}
+
+/// CAP date-times carry an explicit offset and no fractional seconds.
+fn cap_time(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%dT%H:%M:%S+00:00").to_string()
+}
+
+/// Append `<name>text</name>`, escaping the text.
+fn element(xml: &mut String, name: &str, text: &str) {
+    let _ = write!(xml, "<{}>{}</{}>", name, escape(text), name);
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The CAP messages for current alerts and issues, alerts first.
+pub fn collect(alerts: &[Alert], issues: &[Issue]) -> Vec<CapAlert> {
+    alerts
+        .iter()
+        .filter_map(CapAlert::from_alert)
+        .chain(issues.iter().filter_map(CapAlert::from_issue))
+        .collect()
+}
+
+/// Render messages as an Atom feed, each entry carrying its message inline.
+pub fn atom_feed(messages: &[CapAlert], sender: &str, now: DateTime<Utc>) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    element(
+        &mut xml,
+        "id",
+        &format!("urn:{}:cap", identifier(&[sender])),
+    );
+    element(&mut xml, "title", "Infrared CAP alerts");
+    element(&mut xml, "updated", &now.to_rfc3339());
+    xml.push_str("<author>");
+    element(&mut xml, "name", sender);
+    xml.push_str("</author>");
+    for message in messages {
+        xml.push_str("<entry>");
+        element(&mut xml, "id", &format!("urn:{}", message.identifier));
+        element(&mut xml, "title", &message.headline);
+        element(&mut xml, "updated", &now.to_rfc3339());
+        let _ = write!(xml, r#"<content type="{}">"#, CAP_CONTENT_TYPE);
+        xml.push_str(&message.to_xml(sender, now));
+        xml.push_str("</content></entry>");
+    }
+    xml.push_str("</feed>");
+    xml
+}
+
+/// POST a message to every endpoint. Returns whether all accepted it.
+async fn push(
+    message: &CapAlert,
+    urls: &[String],
+    sender: &str,
+    notifier: &WebhookNotifier,
+) -> bool {
+    let document = message.to_document(sender, Utc::now());
+    let mut delivered = true;
+    for url in urls {
+        if let Err(e) = notifier
+            .send_raw(url, CAP_CONTENT_TYPE, document.clone())
+            .await
+        {
+            warn!(identifier = %message.identifier, error = %e, "Failed to push CAP alert");
+            delivered = false;
+        }
+    }
+    if delivered {
+        info!(identifier = %message.identifier, "CAP alert pushed");
+    }
+    delivered
+}
+
+/// Spawn the CAP pusher.
+///
+/// Bucket alerts are checked every `interval`; each message is pushed once
+/// while its alert lasts, and retried on the next check if a delivery
+/// failed. Emergency issues are pushed as the dashboard detects them on
+/// `bus`.
+pub fn spawn_cap_push(
+    storage: Storage,
+    tiers: SeverityTiers,
+    bus: &EventBus,
+    urls: Vec<String>,
+    sender: String,
+    notifier: WebhookNotifier,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    let mut receiver = bus.subscribe();
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut pushed: HashSet<String> = HashSet::new();
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    let alerts = match generate_alerts_with_tiers(&storage, 60, &tiers, Utc::now()).await {
+                        Ok(response) => response.alerts,
+                        Err(e) => {
+                            warn!(error = %e, "Failed to check alerts for CAP push");
+                            continue;
+                        }
+                    };
+                    let messages = collect(&alerts, &[]);
+                    pushed.retain(|id| messages.iter().any(|m| &m.identifier == id));
+                    for message in messages {
+                        if !pushed.contains(&message.identifier)
+                            && push(&message, &urls, &sender, &notifier).await
+                        {
+                            pushed.insert(message.identifier);
+                        }
+                    }
+                }
+                event = next_event(&mut receiver) => {
+                    match event {
+                        Some(Event::IssueDetected(issue)) => {
+                            if let Some(message) = CapAlert::from_issue(&issue) {
+                                push(&message, &urls, &sender, &notifier).await;
+                            }
+                        }
+                        Some(_) => {}
+                        None => return,
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::IssueSource;
+    use crate::model::AlertReason;
+    use chrono::TimeZone;
+
+    fn alert(status: WarmthStatus) -> Alert {
+        Alert {
+            bucket: "zone a".to_string(),
+            subject: AlertSubject::Bucket,
+            status,
+            last_seen_timestamp: None,
+            recent_average: 12.0,
+            reason: AlertReason::WarmthDrop,
+            message: "CRITICAL: Bucket 'zone a' has gone <completely> silent & dark.".to_string(),
+            severity: IssueSeverity::Critical,
+            distressed_since: Some(Utc.with_ymd_and_hms(2024, 3, 1, 10, 0, 0).unwrap()),
+        }
+    }
+
+    fn issue(severity: IssueSeverity) -> Issue {
+        Issue::new(
+            IssueSource::Ioda,
+            IssueCategory::InternetOutage,
+            severity,
+            "Sudan",
+            "sd",
+            "Nationwide internet outage",
+            "Connectivity dropped",
+            Utc.with_ymd_and_hms(2024, 3, 1, 9, 0, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_collect() {
+        let alerts = [
+            alert(WarmthStatus::Dead),
+            alert(WarmthStatus::Collapsing),
+            alert(WarmthStatus::Stressed),
+        ];
+        let issues = [
+            issue(IssueSeverity::Emergency),
+            issue(IssueSeverity::Critical),
+        ];
+
+        let messages = collect(&alerts, &issues);
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].identifier, "infrared:bucket:zone_a:1709287200");
+        assert_eq!(messages[0].event, "Signs of life lost");
+        assert_eq!(messages[2].category, "Infra");
+        assert_eq!(messages[2].severity, "Extreme");
+        assert_eq!(messages[2].country.as_deref(), Some("SD"));
+    }
+
+    #[test]
+    fn test_to_xml() {
+        let sent = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let message = CapAlert::from_alert(&alert(WarmthStatus::Dead)).unwrap();
+        let xml = message.to_document("ops@example.org", sent);
+
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(r#"<alert xmlns="urn:oasis:names:tc:emergency:cap:1.2">"#));
+        assert!(xml.contains("<sent>2024-03-01T12:00:00+00:00</sent>"));
+        assert!(xml.contains("<severity>Severe</severity>"));
+        assert!(xml.contains("<urgency>Immediate</urgency>"));
+        assert!(xml.contains("&lt;completely&gt; silent &amp; dark."));
+        assert!(!xml.contains("<completely>"));
+
+        let issue = CapAlert::from_issue(&issue(IssueSeverity::Emergency)).unwrap();
+        let xml = issue.to_xml("ops@example.org", sent);
+        assert!(
+            xml.contains("<geocode><valueName>ISO 3166-1</valueName><value>SD</value></geocode>")
+        );
+    }
+
+    #[test]
+    fn test_atom_feed() {
+        let now = Utc::now();
+        let messages = collect(
+            &[alert(WarmthStatus::Dead)],
+            &[issue(IssueSeverity::Emergency)],
+        );
+        let feed = atom_feed(&messages, DEFAULT_CAP_SENDER, now);
+        assert_eq!(feed.matches("<entry>").count(), 2);
+        assert_eq!(
+            feed.matches(r#"<content type="application/cap+xml"><alert"#)
+                .count(),
+            2
+        );
+        assert!(feed.ends_with("</feed>"));
+    }
+}
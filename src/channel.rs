@@ -0,0 +1,110 @@
+//! Signal source attribution classes.
+//!
+//! A drop in warmth can mean people stopped checking in, or only that one
+//! path their signals travel over died. Deployments that receive signals
+//! over several paths (e.g., "sms", "mesh", "internet") list them in
+//! `INFRARED_SIGNAL_CHANNELS`; relays then tag each signal with the path it
+//! arrived over, and `GET /warmth?include=channels` breaks a bucket's
+//! warmth down per channel. If only the internet channel collapses while
+//! SMS holds steady, the outage is in the network, not the population.
+//!
+//! Channels are a closed set defined by configuration: a signal naming an
+//! unconfigured channel is rejected, so clients cannot invent labels.
+//!
+//! # Privacy
+//!
+//! A channel names a delivery path shared by everyone using it, never a
+//! client, device or network address. It is stored as one more coarse
+//! column beside bucket, timestamp and weight.
+
+use std::collections::BTreeSet;
+
+/// Most channels a deployment may configure.
+pub const MAX_CHANNELS: usize = 16;
+
+/// Longest channel name.
+pub const MAX_CHANNEL_LEN: usize = 32;
+
+/// The channels signals may be attributed to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignalChannels {
+    names: BTreeSet<String>,
+}
+
+impl SignalChannels {
+    /// Parse a comma-separated channel list such as `sms,mesh,internet`.
+    ///
+    /// Names are lowercase ASCII letters, digits, `-` and `_`, up to
+    /// [`MAX_CHANNEL_LEN`] characters.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let mut names = BTreeSet::new();
+        for name in spec.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            anyhow::ensure!(
+                is_valid_name(name),
+                "invalid channel name '{}' (expected up to {} lowercase letters, digits, '-' or '_')",
+                name,
+                MAX_CHANNEL_LEN
+            );
+            anyhow::ensure!(
+                names.insert(name.to_string()),
+                "duplicate channel '{}'",
+                name
+            );
+        }
+        anyhow::ensure!(
+            names.len() <= MAX_CHANNELS,
+            "{} channels configured; at most {} are allowed",
+            names.len(),
+            MAX_CHANNELS
+        );
+        Ok(Self { names })
+    }
+
+    /// Whether signals may be attributed to `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+
+    /// Whether no channels are configured, so signals carry none.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Configured channel names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(String::as_str)
+    }
+}
+
+fn is_valid_name(name: &str) -> bool {
+    name.len() <= MAX_CHANNEL_LEN
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let channels = SignalChannels::parse("sms, mesh,internet,").unwrap();
+        assert_eq!(
+            channels.names().collect::<Vec<_>>(),
+            ["internet", "mesh", "sms"]
+        );
+        assert!(channels.contains("sms"));
+        assert!(!channels.contains("SMS"));
+        assert!(!channels.contains("radio"));
+
+        assert!(SignalChannels::parse("").unwrap().is_empty());
+        assert!(SignalChannels::parse("sms,sms").is_err());
+        assert!(SignalChannels::parse("Satellite").is_err());
+        assert!(SignalChannels::parse("ham radio").is_err());
+        assert!(SignalChannels::parse(&"x".repeat(MAX_CHANNEL_LEN + 1)).is_err());
+
+        let many: Vec<String> = (0..=MAX_CHANNELS).map(|i| format!("c{}", i)).collect();
+        assert!(SignalChannels::parse(&many.join(",")).is_err());
+    }
+}
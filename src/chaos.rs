@@ -0,0 +1,640 @@
+//! Failure and latency injection for degradation tests (`chaos` feature).
+//!
+//! Infrared is meant to keep answering when a data source is down or the
+//! database is slow: a dashboard with one source failing still lists the
+//! others, a source that fails on refresh keeps its last issues, and an
+//! ingest the database cannot take is refused with `503` so the client
+//! retries. A [`Chaos`] handle injects those failures on demand, into
+//! every [`SignalStore`] method through a [`ChaosStore`] and into data
+//! source fetches through [`crate::dashboard::Dashboard::with_chaos`], so
+//! tests can assert each degradation.
+//!
+//! Faults can be changed while a server is running:
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use infrared::chaos::{Chaos, EVERY_OPERATION, Fault};
+//! use infrared::dashboard::IssueSource;
+//!
+//! let chaos = Chaos::new();
+//! chaos.fault_source(IssueSource::Ioda, Fault::Fail);
+//! chaos.fault_storage(EVERY_OPERATION, Fault::Delay(Duration::from_millis(50)));
+//! chaos.heal();
+//! ```
+//!
+//! The feature is for tests only; release builds never carry these hooks.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::alert_history::AlertRecord;
+use crate::audit::AuditEntry;
+use crate::bucket_meta::BucketMeta;
+use crate::compaction::CompactionReport;
+use crate::dashboard::{DashboardResponse, DashboardSummary, Issue, IssueSource};
+use crate::deadman::SwitchStatus;
+use crate::heartbeat::HeartbeatStatus;
+use crate::model::{
+    Alert, Baseline, BucketEntry, BucketGroup, Detector, LifeSignal, WarmthThresholds,
+};
+use crate::scheduler::WarmthSnapshot;
+use crate::storage::{SignalFilter, SignalStore, StorageStats, StoredBatch};
+use crate::tension::TensionPoint;
+use crate::timeline::Annotation;
+
+/// Storage operation name matching every [`SignalStore`] method.
+pub const EVERY_OPERATION: &str = "*";
+
+/// What to do to an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Fail with an injected error.
+    Fail,
+    /// Wait this long, then carry on.
+    Delay(Duration),
+}
+
+#[derive(Debug, Default)]
+struct Faults {
+    storage: HashMap<String, Fault>,
+    sources: HashMap<IssueSource, Fault>,
+}
+
+/// Faults to inject, shared by every clone.
+#[derive(Debug, Clone, Default)]
+pub struct Chaos {
+    faults: Arc<Mutex<Faults>>,
+}
+
+impl Chaos {
+    /// A handle injecting no faults yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inject `fault` into the [`SignalStore`] method named `operation`
+    /// (e.g., `"get_groups"`), or into every method with
+    /// [`EVERY_OPERATION`]. A named fault wins over the catch-all.
+    pub fn fault_storage(&self, operation: &str, fault: Fault) {
+        let mut faults = self.faults.lock().unwrap();
+        faults.storage.insert(operation.to_string(), fault);
+    }
+
+    /// Inject `fault` into every fetch from `source`.
+    pub fn fault_source(&self, source: IssueSource, fault: Fault) {
+        let mut faults = self.faults.lock().unwrap();
+        faults.sources.insert(source, fault);
+    }
+
+    /// Remove every fault.
+    pub fn heal(&self) {
+        let mut faults = self.faults.lock().unwrap();
+        faults.storage.clear();
+        faults.sources.clear();
+    }
+
+    /// Apply the fault injected into storage `operation`, if any.
+    pub async fn storage(&self, operation: &str) -> anyhow::Result<()> {
+        let fault = {
+            let faults = self.faults.lock().unwrap();
+            faults
+                .storage
+                .get(operation)
+                .or_else(|| faults.storage.get(EVERY_OPERATION))
+                .copied()
+        };
+        inject(fault, operation).await
+    }
+
+    /// Apply the fault injected into `source`, if any.
+    pub async fn source(&self, source: IssueSource) -> anyhow::Result<()> {
+        let fault = self.faults.lock().unwrap().sources.get(&source).copied();
+        inject(fault, source.label()).await
+    }
+}
+
+async fn inject(fault: Option<Fault>, target: &str) -> anyhow::Result<()> {
+    match fault {
+        Some(Fault::Fail) => anyhow::bail!("injected failure in {}", target),
+        Some(Fault::Delay(delay)) => {
+            tokio::time::sleep(delay).await;
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+/// A [`SignalStore`] applying [`Chaos`] faults before each call to `inner`.
+///
+/// `default_thresholds` and `detector` only read configuration and are
+/// never faulted.
+#[derive(Clone)]
+pub struct ChaosStore<S> {
+    inner: S,
+    chaos: Chaos,
+}
+
+impl<S> ChaosStore<S> {
+    /// Wrap `inner`, injecting `chaos`'s storage faults.
+    pub fn new(inner: S, chaos: Chaos) -> Self {
+        Self { inner, chaos }
+    }
+}
+
+#[async_trait]
+impl<S: SignalStore> SignalStore for ChaosStore<S> {
+    async fn insert_life_signal(&self, signal: &LifeSignal) -> anyhow::Result<()> {
+        self.chaos.storage("insert_life_signal").await?;
+        self.inner.insert_life_signal(signal).await
+    }
+
+    async fn insert_life_signals(&self, signals: &[LifeSignal]) -> anyhow::Result<()> {
+        self.chaos.storage("insert_life_signals").await?;
+        self.inner.insert_life_signals(signals).await
+    }
+
+    async fn insert_life_signals_once(
+        &self,
+        key: &str,
+        signals: &[LifeSignal],
+        now: DateTime<Utc>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Option<StoredBatch>> {
+        self.chaos.storage("insert_life_signals_once").await?;
+        self.inner
+            .insert_life_signals_once(key, signals, now, since)
+            .await
+    }
+
+    async fn query_bucket_window(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<i64> {
+        self.chaos.storage("query_bucket_window").await?;
+        self.inner
+            .query_bucket_window(bucket, window_minutes, now)
+            .await
+    }
+
+    async fn compute_recent_average(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<f64> {
+        self.chaos.storage("compute_recent_average").await?;
+        self.inner
+            .compute_recent_average(bucket, window_minutes, num_windows, now)
+            .await
+    }
+
+    async fn baseline_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i64>> {
+        self.chaos.storage("baseline_totals").await?;
+        self.inner
+            .baseline_totals(bucket, window_minutes, num_windows, now)
+            .await
+    }
+
+    async fn window_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        count: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i64>> {
+        self.chaos.storage("window_totals").await?;
+        self.inner
+            .window_totals(bucket, window_minutes, count, now)
+            .await
+    }
+
+    async fn channel_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<(Option<String>, i64, f64)>> {
+        self.chaos.storage("channel_totals").await?;
+        self.inner
+            .channel_totals(bucket, window_minutes, num_windows, now)
+            .await
+    }
+
+    async fn bucket_totals(
+        &self,
+        buckets: &[String],
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<BTreeMap<String, (i64, Vec<i64>)>> {
+        self.chaos.storage("bucket_totals").await?;
+        self.inner
+            .bucket_totals(buckets, window_minutes, num_windows, now)
+            .await
+    }
+
+    async fn get_last_seen(&self, bucket: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+        self.chaos.storage("get_last_seen").await?;
+        self.inner.get_last_seen(bucket).await
+    }
+
+    async fn get_active_buckets(
+        &self,
+        minutes: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<String>> {
+        self.chaos.storage("get_active_buckets").await?;
+        self.inner.get_active_buckets(minutes, now).await
+    }
+
+    async fn get_all_known_buckets(&self) -> anyhow::Result<Vec<String>> {
+        self.chaos.storage("get_all_known_buckets").await?;
+        self.inner.get_all_known_buckets().await
+    }
+
+    async fn delete_signals_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<u64> {
+        self.chaos.storage("delete_signals_before").await?;
+        self.inner.delete_signals_before(cutoff, limit).await
+    }
+
+    async fn compact_signals_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> anyhow::Result<CompactionReport> {
+        self.chaos.storage("compact_signals_before").await?;
+        self.inner.compact_signals_before(cutoff).await
+    }
+
+    async fn backup_to(&self, path: &Path) -> anyhow::Result<()> {
+        self.chaos.storage("backup_to").await?;
+        self.inner.backup_to(path).await
+    }
+
+    async fn stats(&self) -> anyhow::Result<StorageStats> {
+        self.chaos.storage("stats").await?;
+        self.inner.stats().await
+    }
+
+    async fn get_signals_after(
+        &self,
+        filter: &SignalFilter,
+        after: i64,
+        limit: u32,
+    ) -> anyhow::Result<Vec<(i64, LifeSignal)>> {
+        self.chaos.storage("get_signals_after").await?;
+        self.inner.get_signals_after(filter, after, limit).await
+    }
+
+    async fn insert_warmth_snapshots(&self, snapshots: &[WarmthSnapshot]) -> anyhow::Result<()> {
+        self.chaos.storage("insert_warmth_snapshots").await?;
+        self.inner.insert_warmth_snapshots(snapshots).await
+    }
+
+    async fn get_warmth_snapshots(
+        &self,
+        bucket: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<WarmthSnapshot>> {
+        self.chaos.storage("get_warmth_snapshots").await?;
+        self.inner.get_warmth_snapshots(bucket, from, to).await
+    }
+
+    async fn distressed_since(&self, bucket: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+        self.chaos.storage("distressed_since").await?;
+        self.inner.distressed_since(bucket).await
+    }
+
+    async fn insert_dashboard_snapshot(&self, response: &DashboardResponse) -> anyhow::Result<()> {
+        self.chaos.storage("insert_dashboard_snapshot").await?;
+        self.inner.insert_dashboard_snapshot(response).await
+    }
+
+    async fn delete_dashboard_snapshots_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> anyhow::Result<u64> {
+        self.chaos
+            .storage("delete_dashboard_snapshots_before")
+            .await?;
+        self.inner.delete_dashboard_snapshots_before(cutoff).await
+    }
+
+    async fn insert_tension(
+        &self,
+        at: DateTime<Utc>,
+        summary: &DashboardSummary,
+    ) -> anyhow::Result<()> {
+        self.chaos.storage("insert_tension").await?;
+        self.inner.insert_tension(at, summary).await
+    }
+
+    async fn get_tension_history(
+        &self,
+        country: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<TensionPoint>> {
+        self.chaos.storage("get_tension_history").await?;
+        self.inner.get_tension_history(country, since).await
+    }
+
+    async fn get_dashboard_snapshot_at(
+        &self,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Option<DashboardResponse>> {
+        self.chaos.storage("get_dashboard_snapshot_at").await?;
+        self.inner.get_dashboard_snapshot_at(at).await
+    }
+
+    async fn upsert_external_issue(
+        &self,
+        issue: &Issue,
+        received_at: DateTime<Utc>,
+    ) -> anyhow::Result<bool> {
+        self.chaos.storage("upsert_external_issue").await?;
+        self.inner.upsert_external_issue(issue, received_at).await
+    }
+
+    async fn get_external_issues(&self, since: DateTime<Utc>) -> anyhow::Result<Vec<Issue>> {
+        self.chaos.storage("get_external_issues").await?;
+        self.inner.get_external_issues(since).await
+    }
+
+    async fn set_heartbeat(
+        &self,
+        bucket: &str,
+        interval_seconds: i64,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        self.chaos.storage("set_heartbeat").await?;
+        self.inner
+            .set_heartbeat(bucket, interval_seconds, now)
+            .await
+    }
+
+    async fn clear_heartbeat(&self, bucket: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("clear_heartbeat").await?;
+        self.inner.clear_heartbeat(bucket).await
+    }
+
+    async fn get_heartbeats(&self) -> anyhow::Result<Vec<HeartbeatStatus>> {
+        self.chaos.storage("get_heartbeats").await?;
+        self.inner.get_heartbeats().await
+    }
+
+    async fn arm_switch(
+        &self,
+        bucket: &str,
+        countdown_seconds: i64,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        self.chaos.storage("arm_switch").await?;
+        self.inner.arm_switch(bucket, countdown_seconds, now).await
+    }
+
+    async fn disarm_switch(&self, bucket: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("disarm_switch").await?;
+        self.inner.disarm_switch(bucket).await
+    }
+
+    async fn get_switches(&self) -> anyhow::Result<Vec<SwitchStatus>> {
+        self.chaos.storage("get_switches").await?;
+        self.inner.get_switches().await
+    }
+
+    async fn set_switch_notified(
+        &self,
+        bucket: &str,
+        deadline: DateTime<Utc>,
+        steps: usize,
+    ) -> anyhow::Result<()> {
+        self.chaos.storage("set_switch_notified").await?;
+        self.inner
+            .set_switch_notified(bucket, deadline, steps)
+            .await
+    }
+
+    async fn set_timezone(&self, bucket: &str, timezone: &str) -> anyhow::Result<()> {
+        self.chaos.storage("set_timezone").await?;
+        self.inner.set_timezone(bucket, timezone).await
+    }
+
+    async fn clear_timezone(&self, bucket: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("clear_timezone").await?;
+        self.inner.clear_timezone(bucket).await
+    }
+
+    async fn get_timezone(&self, bucket: &str) -> anyhow::Result<Option<String>> {
+        self.chaos.storage("get_timezone").await?;
+        self.inner.get_timezone(bucket).await
+    }
+
+    async fn set_country(&self, bucket: &str, country: &str) -> anyhow::Result<()> {
+        self.chaos.storage("set_country").await?;
+        self.inner.set_country(bucket, country).await
+    }
+
+    async fn clear_country(&self, bucket: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("clear_country").await?;
+        self.inner.clear_country(bucket).await
+    }
+
+    async fn get_country(&self, bucket: &str) -> anyhow::Result<Option<String>> {
+        self.chaos.storage("get_country").await?;
+        self.inner.get_country(bucket).await
+    }
+
+    async fn get_buckets_in_country(&self, codes: &[String]) -> anyhow::Result<Vec<String>> {
+        self.chaos.storage("get_buckets_in_country").await?;
+        self.inner.get_buckets_in_country(codes).await
+    }
+
+    async fn get_countries(&self) -> anyhow::Result<BTreeMap<String, Vec<String>>> {
+        self.chaos.storage("get_countries").await?;
+        self.inner.get_countries().await
+    }
+
+    async fn set_baseline(&self, bucket: &str, baseline: Baseline) -> anyhow::Result<()> {
+        self.chaos.storage("set_baseline").await?;
+        self.inner.set_baseline(bucket, baseline).await
+    }
+
+    async fn clear_baseline(&self, bucket: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("clear_baseline").await?;
+        self.inner.clear_baseline(bucket).await
+    }
+
+    async fn get_baseline(&self, bucket: &str) -> anyhow::Result<Option<Baseline>> {
+        self.chaos.storage("get_baseline").await?;
+        self.inner.get_baseline(bucket).await
+    }
+
+    async fn get_baselines(
+        &self,
+        buckets: &[String],
+    ) -> anyhow::Result<BTreeMap<String, Baseline>> {
+        self.chaos.storage("get_baselines").await?;
+        self.inner.get_baselines(buckets).await
+    }
+
+    async fn set_bucket_meta(&self, bucket: &str, meta: &BucketMeta) -> anyhow::Result<()> {
+        self.chaos.storage("set_bucket_meta").await?;
+        self.inner.set_bucket_meta(bucket, meta).await
+    }
+
+    async fn clear_bucket_meta(&self, bucket: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("clear_bucket_meta").await?;
+        self.inner.clear_bucket_meta(bucket).await
+    }
+
+    async fn get_bucket_meta(&self, bucket: &str) -> anyhow::Result<Option<BucketMeta>> {
+        self.chaos.storage("get_bucket_meta").await?;
+        self.inner.get_bucket_meta(bucket).await
+    }
+
+    fn default_thresholds(&self) -> WarmthThresholds {
+        self.inner.default_thresholds()
+    }
+
+    fn detector(&self) -> (Detector, f64) {
+        self.inner.detector()
+    }
+
+    async fn get_thresholds(
+        &self,
+        buckets: &[String],
+    ) -> anyhow::Result<BTreeMap<String, WarmthThresholds>> {
+        self.chaos.storage("get_thresholds").await?;
+        self.inner.get_thresholds(buckets).await
+    }
+
+    async fn archive_bucket(&self, bucket: &str, now: DateTime<Utc>) -> anyhow::Result<()> {
+        self.chaos.storage("archive_bucket").await?;
+        self.inner.archive_bucket(bucket, now).await
+    }
+
+    async fn unarchive_bucket(&self, bucket: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("unarchive_bucket").await?;
+        self.inner.unarchive_bucket(bucket).await
+    }
+
+    async fn get_buckets(&self) -> anyhow::Result<Vec<BucketEntry>> {
+        self.chaos.storage("get_buckets").await?;
+        self.inner.get_buckets().await
+    }
+
+    async fn set_group(&self, name: &str, buckets: &[String]) -> anyhow::Result<()> {
+        self.chaos.storage("set_group").await?;
+        self.inner.set_group(name, buckets).await
+    }
+
+    async fn delete_group(&self, name: &str) -> anyhow::Result<bool> {
+        self.chaos.storage("delete_group").await?;
+        self.inner.delete_group(name).await
+    }
+
+    async fn get_group(&self, name: &str) -> anyhow::Result<Option<BucketGroup>> {
+        self.chaos.storage("get_group").await?;
+        self.inner.get_group(name).await
+    }
+
+    async fn get_groups(&self) -> anyhow::Result<Vec<BucketGroup>> {
+        self.chaos.storage("get_groups").await?;
+        self.inner.get_groups().await
+    }
+
+    async fn insert_audit_entry(
+        &self,
+        timestamp: DateTime<Utc>,
+        actor: &str,
+        action: &str,
+        details: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.chaos.storage("insert_audit_entry").await?;
+        self.inner
+            .insert_audit_entry(timestamp, actor, action, details)
+            .await
+    }
+
+    async fn get_audit_entries(
+        &self,
+        action: Option<&str>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<AuditEntry>> {
+        self.chaos.storage("get_audit_entries").await?;
+        self.inner.get_audit_entries(action, limit).await
+    }
+
+    async fn insert_annotation(&self, annotation: &Annotation) -> anyhow::Result<()> {
+        self.chaos.storage("insert_annotation").await?;
+        self.inner.insert_annotation(annotation).await
+    }
+
+    async fn get_annotations(&self, incident_id: &str) -> anyhow::Result<Vec<Annotation>> {
+        self.chaos.storage("get_annotations").await?;
+        self.inner.get_annotations(incident_id).await
+    }
+
+    async fn record_alerts(&self, alerts: &[Alert], now: DateTime<Utc>) -> anyhow::Result<()> {
+        self.chaos.storage("record_alerts").await?;
+        self.inner.record_alerts(alerts, now).await
+    }
+
+    async fn get_alert_history(
+        &self,
+        bucket: Option<&str>,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<AlertRecord>> {
+        self.chaos.storage("get_alert_history").await?;
+        self.inner
+            .get_alert_history(bucket, from, until, limit)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+
+    #[tokio::test]
+    async fn test_storage_faults() {
+        let chaos = Chaos::new();
+        let store = ChaosStore::new(
+            Storage::new("sqlite::memory:").await.unwrap(),
+            chaos.clone(),
+        );
+        assert!(store.get_groups().await.is_ok());
+
+        chaos.fault_storage(EVERY_OPERATION, Fault::Fail);
+        chaos.fault_storage("get_groups", Fault::Delay(Duration::from_millis(1)));
+        assert!(store.get_groups().await.is_ok());
+        let error = store.get_buckets().await.unwrap_err();
+        assert_eq!(error.to_string(), "injected failure in get_buckets");
+
+        chaos.heal();
+        assert!(store.get_buckets().await.is_ok());
+    }
+}
@@ -0,0 +1,152 @@
+//! Database integrity checks behind `infrared check`.
+//!
+//! Field hardware suffers power loss; this is the first-line check for a
+//! corrupted or half-initialized database. The report is machine-readable
+//! JSON and the command exits nonzero if any check fails.
+//!
+//! # Privacy
+//!
+//! Reports only describe database structure. No bucket names or signal data
+//! are included.
+
+use serde::Serialize;
+
+use crate::storage::{EXPECTED_INDEXES, SCHEMA_VERSION, Storage};
+
+/// Outcome of a single check.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    /// Check name (e.g., "integrity", "indexes").
+    pub name: String,
+
+    /// Whether the check passed.
+    pub ok: bool,
+
+    /// Problems found, if any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<String>,
+}
+
+impl CheckResult {
+    fn new(name: &str, problems: Vec<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok: problems.is_empty(),
+            problems,
+        }
+    }
+}
+
+/// Full report produced by `infrared check`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    /// True only if every check passed.
+    pub ok: bool,
+
+    /// Schema version recorded in the database.
+    pub schema_version: i64,
+
+    /// Schema version this build expects.
+    pub expected_schema_version: i64,
+
+    /// Individual check results.
+    pub checks: Vec<CheckResult>,
+}
+
+/// Run all integrity checks against a database.
+pub async fn run_checks(storage: &Storage) -> anyhow::Result<CheckReport> {
+    let integrity = CheckResult::new("integrity", storage.integrity_check().await?);
+
+    let present = storage.index_names().await?;
+    let indexes = CheckResult::new(
+        "indexes",
+        EXPECTED_INDEXES
+            .iter()
+            .filter(|name| !present.iter().any(|p| p == *name))
+            .map(|name| format!("missing index {}", name))
+            .collect(),
+    );
+
+    let orphaned_rows = CheckResult::new(
+        "orphaned_rows",
+        storage
+            .orphaned_rows()
+            .await?
+            .into_iter()
+            .map(|(table, rows)| format!("{} orphaned rows in {}", rows, table))
+            .collect(),
+    );
+
+    let schema_version = storage.schema_version().await?;
+    let schema = CheckResult::new(
+        "schema_version",
+        if schema_version == SCHEMA_VERSION {
+            vec![]
+        } else {
+            vec![format!(
+                "database schema version {} does not match expected {}",
+                schema_version, SCHEMA_VERSION
+            )]
+        },
+    );
+
+    let checks = vec![integrity, indexes, orphaned_rows, schema];
+
+    Ok(CheckReport {
+        ok: checks.iter().all(|c| c.ok),
+        schema_version,
+        expected_schema_version: SCHEMA_VERSION,
+        checks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SignalStore;
+
+    #[tokio::test]
+    async fn test_initialized_database_passes() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        let report = run_checks(&storage).await.unwrap();
+
+        assert!(report.ok, "{:?}", report);
+        assert_eq!(report.schema_version, SCHEMA_VERSION);
+    }
+
+    #[tokio::test]
+    async fn test_settings_without_signals_fail() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        storage
+            .set_group("north", &["zone-gone".to_string()])
+            .await
+            .unwrap();
+
+        let report = run_checks(&storage).await.unwrap();
+
+        assert!(!report.ok);
+        let orphaned = report
+            .checks
+            .iter()
+            .find(|c| c.name == "orphaned_rows")
+            .unwrap();
+        assert_eq!(orphaned.problems, ["1 orphaned rows in bucket_groups"]);
+    }
+
+    #[tokio::test]
+    async fn test_uninitialized_database_fails() {
+        let storage = Storage::connect("sqlite::memory:").await.unwrap();
+
+        let report = run_checks(&storage).await.unwrap();
+
+        assert!(!report.ok);
+        let failed: Vec<&str> = report
+            .checks
+            .iter()
+            .filter(|c| !c.ok)
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(failed, vec!["indexes", "schema_version"]);
+    }
+}
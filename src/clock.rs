@@ -0,0 +1,205 @@
+//! Server timestamps that survive system clock jumps.
+//!
+//! Field hardware often boots with a wrong clock and has it corrected by NTP
+//! later, sometimes by hours. Signals are stamped with the server's wall
+//! clock, so a backwards correction would file new signals under windows
+//! that already closed, inflating their totals and the baselines built from
+//! them without any sign that anything happened.
+//!
+//! [`IngestClock`] keeps a monotonic reference alongside the last timestamp
+//! it handed out. Each reading of the wall clock is compared with how far
+//! the monotonic clock moved since the previous one; a difference beyond
+//! [`JUMP_TOLERANCE`] is logged as a clock jump and counted in
+//! `infrared_clock_jumps_total{direction}`. Timestamps never move
+//! backwards: a signal whose wall-clock time is earlier than the last one
+//! handed out is stamped with that last time instead, and counted in
+//! `infrared_clock_clamped_signals_total`. Once the wall clock catches up,
+//! signals are stamped with it again.
+//!
+//! # Privacy
+//!
+//! Only the server's own clock readings are kept, never anything about a
+//! sender.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use crate::metrics::Metrics;
+
+/// Largest disagreement between wall and monotonic clock progress that is
+/// not reported as a jump.
+pub const JUMP_TOLERANCE: Duration = Duration::from_secs(2);
+
+/// A server timestamp handed out by [`IngestClock`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stamp {
+    /// The timestamp to store.
+    pub timestamp: DateTime<Utc>,
+
+    /// Whether the wall clock was behind the last timestamp handed out, so
+    /// the signal was stamped with that instead.
+    pub clamped: bool,
+}
+
+#[derive(Debug)]
+struct Reading {
+    /// Monotonic time of the last reading.
+    instant: Instant,
+    /// Wall clock at the last reading.
+    wall: DateTime<Utc>,
+    /// Latest timestamp handed out.
+    stamped: DateTime<Utc>,
+}
+
+/// Shared source of ingest timestamps.
+///
+/// Cloning is cheap; all clones share the same reference.
+#[derive(Clone, Default)]
+pub struct IngestClock {
+    last: Arc<Mutex<Option<Reading>>>,
+}
+
+impl IngestClock {
+    /// Create a clock with no reference yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Timestamp for a signal arriving now.
+    pub fn stamp(&self, metrics: &Metrics) -> Stamp {
+        self.stamp_at(Utc::now(), Instant::now(), metrics)
+    }
+
+    /// Timestamp for a signal arriving when the wall clock reads `wall` and
+    /// the monotonic clock `instant`.
+    ///
+    /// Jumps and clamped signals are published to `metrics`.
+    pub fn stamp_at(&self, wall: DateTime<Utc>, instant: Instant, metrics: &Metrics) -> Stamp {
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+
+        let stamp = match last.as_ref() {
+            None => Stamp {
+                timestamp: wall,
+                clamped: false,
+            },
+            Some(previous) => {
+                let elapsed = instant.saturating_duration_since(previous.instant);
+                let expected = previous.wall
+                    + chrono::Duration::from_std(elapsed).unwrap_or(chrono::Duration::MAX);
+                let skew = wall - expected;
+                let tolerance =
+                    chrono::Duration::from_std(JUMP_TOLERANCE).unwrap_or(chrono::Duration::MAX);
+                if skew.abs() > tolerance {
+                    let direction = if skew < chrono::Duration::zero() {
+                        "backward"
+                    } else {
+                        "forward"
+                    };
+                    warn!(
+                        direction,
+                        skew_seconds = skew.num_milliseconds() as f64 / 1000.0,
+                        "System clock jumped"
+                    );
+                    metrics.inc_counter(
+                        "infrared_clock_jumps_total",
+                        &[("direction", direction)],
+                        1.0,
+                    );
+                }
+
+                if wall < previous.stamped {
+                    metrics.inc_counter("infrared_clock_clamped_signals_total", &[], 1.0);
+                    Stamp {
+                        timestamp: previous.stamped,
+                        clamped: true,
+                    }
+                } else {
+                    Stamp {
+                        timestamp: wall,
+                        clamped: false,
+                    }
+                }
+            }
+        };
+
+        *last = Some(Reading {
+            instant,
+            wall,
+            stamped: stamp.timestamp,
+        });
+        stamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_backwards_jump_is_clamped() {
+        let clock = IngestClock::new();
+        let metrics = Metrics::new();
+        let start = Instant::now();
+        let noon = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let at = |seconds: u64| start + Duration::from_secs(seconds);
+        let wall = |seconds: i64| noon + chrono::Duration::seconds(seconds);
+
+        assert_eq!(clock.stamp_at(wall(0), at(0), &metrics).timestamp, wall(0));
+        assert_eq!(
+            clock.stamp_at(wall(10), at(10), &metrics).timestamp,
+            wall(10)
+        );
+        assert_eq!(
+            metrics.get("infrared_clock_jumps_total", &[("direction", "backward")]),
+            None
+        );
+
+        // NTP sets the clock back by a minute
+        let stamp = clock.stamp_at(wall(-50), at(11), &metrics);
+        assert_eq!(
+            stamp,
+            Stamp {
+                timestamp: wall(10),
+                clamped: true,
+            }
+        );
+        assert_eq!(
+            metrics.get("infrared_clock_jumps_total", &[("direction", "backward")]),
+            Some(1.0)
+        );
+
+        // Still behind, but no new jump
+        assert!(clock.stamp_at(wall(0), at(61), &metrics).clamped);
+        assert_eq!(
+            metrics.get("infrared_clock_jumps_total", &[("direction", "backward")]),
+            Some(1.0)
+        );
+        assert_eq!(
+            metrics.get("infrared_clock_clamped_signals_total", &[]),
+            Some(2.0)
+        );
+
+        // Caught up again
+        assert_eq!(
+            clock.stamp_at(wall(20), at(81), &metrics),
+            Stamp {
+                timestamp: wall(20),
+                clamped: false,
+            }
+        );
+
+        // Forward jumps are reported but taken as they are
+        assert_eq!(
+            clock.stamp_at(wall(3600), at(82), &metrics).timestamp,
+            wall(3600)
+        );
+        assert_eq!(
+            metrics.get("infrared_clock_jumps_total", &[("direction", "forward")]),
+            Some(1.0)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Compaction of old raw life signals into hourly sums.
+//!
+//! Warmth baselines only need how much weight a bucket received per
+//! window, not when each signal arrived to the second. Once signals are
+//! older than `INFRARED_COMPACT_AFTER_DAYS`, a background task replaces
+//! each day's raw rows with one row per bucket, channel and hour, stamped
+//! at the start of the hour and weighing the sum of the signals it
+//! replaces. Totals over any hour-aligned span are unchanged, so baselines
+//! built from windows of whole hours read the same; finer windows should
+//! not reach back past the compaction age.
+//!
+//! Only whole UTC days before the cutoff are compacted, each in its own
+//! transaction. Compacting a day twice changes nothing, and a day that
+//! gained raw rows since (e.g., a backfill) is compacted again.
+//!
+//! # Privacy
+//!
+//! Compaction shrinks what is kept: exact arrival times and individual
+//! weights of old signals are gone, leaving hourly totals per bucket. A
+//! leaked database says less about when anyone checked in.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::metrics::Metrics;
+use crate::storage::{SignalStore, Storage};
+
+/// Seconds summed into one compacted row.
+pub const COMPACTION_SECONDS: i64 = 60 * 60;
+
+/// Time between compaction runs.
+pub const COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// What a compaction run did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CompactionReport {
+    /// Days compacted.
+    pub days: u64,
+
+    /// Raw rows replaced.
+    pub raw_rows: u64,
+
+    /// Hourly rows written in their place.
+    pub compacted_rows: u64,
+}
+
+/// Signals older than this are compacted when compacting after
+/// `compact_after_days`.
+pub fn cutoff(now: DateTime<Utc>, compact_after_days: u32) -> DateTime<Utc> {
+    now - chrono::Duration::days(i64::from(compact_after_days))
+}
+
+/// Spawn the background task compacting signals older than
+/// `compact_after_days`.
+///
+/// The first run happens right away.
+pub fn spawn_compaction_task(
+    storage: Storage,
+    metrics: Metrics,
+    compact_after_days: u32,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(COMPACTION_INTERVAL);
+        loop {
+            ticker.tick().await;
+
+            match storage
+                .compact_signals_before(cutoff(Utc::now(), compact_after_days))
+                .await
+            {
+                Ok(report) => {
+                    metrics.inc_counter(
+                        "infrared_compaction_rows_removed_total",
+                        &[],
+                        report.raw_rows.saturating_sub(report.compacted_rows) as f64,
+                    );
+                    if report.days > 0 {
+                        info!(
+                            days = report.days,
+                            raw_rows = report.raw_rows,
+                            compacted_rows = report.compacted_rows,
+                            "Compacted old life signals"
+                        );
+                    }
+                }
+                Err(e) => {
+                    metrics.inc_counter("infrared_compaction_failures_total", &[], 1.0);
+                    warn!(error = %e, "Failed to compact old life signals");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LifeSignal;
+    use crate::partition;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn test_compaction_preserves_hourly_totals() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        let today = partition::day_of(Utc::now().timestamp());
+        let old = |day: i64, minute: i64| {
+            Utc.timestamp_opt(day * partition::DAY_SECONDS + minute * 60, 0)
+                .unwrap()
+        };
+        let signal = |timestamp, weight, channel: Option<&str>| LifeSignal {
+            bucket: "zone-a".to_string(),
+            timestamp,
+            weight,
+            channel: channel.map(str::to_string),
+        };
+        storage
+            .insert_life_signals(&[
+                signal(old(today - 10, 5), 1, None),
+                signal(old(today - 10, 50), 2, None),
+                signal(old(today - 10, 55), 4, Some("sms")),
+                signal(old(today - 10, 70), 8, None),
+                signal(old(today - 9, 0), 16, None),
+                signal(Utc::now(), 32, None),
+            ])
+            .await
+            .unwrap();
+
+        let report = storage
+            .compact_signals_before(old(today - 9, 30))
+            .await
+            .unwrap();
+        assert_eq!(
+            report,
+            CompactionReport {
+                days: 1,
+                raw_rows: 4,
+                compacted_rows: 3,
+            }
+        );
+
+        // Hour-aligned totals are unchanged
+        let day_total = storage
+            .query_bucket_window(
+                "zone-a",
+                24 * 60,
+                old(today - 9, 0) - chrono::Duration::seconds(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(day_total, 15);
+        let totals = storage
+            .channel_totals("zone-a", 60, 1, old(today - 10, 60 + 59))
+            .await
+            .unwrap();
+        assert_eq!(
+            totals,
+            vec![(None, 8, 3.0), (Some("sms".to_string()), 0, 4.0)]
+        );
+
+        // Nothing left to do until another day expires
+        assert_eq!(
+            storage
+                .compact_signals_before(old(today - 9, 30))
+                .await
+                .unwrap(),
+            CompactionReport::default()
+        );
+    }
+}
@@ -0,0 +1,1014 @@
+//! Configuration validation behind `infrared check-config`.
+//!
+//! The server reads its configuration from environment variables and, for
+//! many numeric settings, quietly falls back to the default when a value
+//! does not parse. `check-config` validates every known setting up front
+//! (values parse, thresholds are in range, country codes are well formed,
+//! referenced files load) and prints the effective configuration, so a
+//! misconfiguration fails before deployment rather than showing up as
+//! puzzling runtime behavior. With `--probe`, every configured notification
+//! endpoint is also contacted to confirm it is reachable.
+//!
+//! # Privacy
+//!
+//! Secrets (tokens, keys, client secrets) and the ACLED account email are
+//! redacted from the report. Probes send a `HEAD` request and no payload.
+
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::auth::{AuthConfig, Role};
+use crate::cadence::RefreshCadence;
+use crate::calibration::Calibration;
+use crate::channel::SignalChannels;
+use crate::dashboard::{IssueSource, RiskThresholds};
+use crate::data_sources::acled::AcledRegion;
+use crate::data_sources::wikimedia::WikimediaProject;
+use crate::deadman::EscalationPolicy;
+use crate::ingest::QuotaConfig;
+use crate::jwt::JwtValidator;
+use crate::limits::QueryLimits;
+use crate::maintenance::QuietHours;
+use crate::model::{Baseline, BucketGroup, Detector, WarmthThresholds};
+use crate::naming::NamingConvention;
+use crate::profile::{PROFILE_ENV, Profile, lookup};
+use crate::public::PublicMirror;
+use crate::severity::{SeverityRoutes, SeverityTiers};
+use crate::storage::{JournalMode, Synchronous};
+use crate::suppression::SuppressionRules;
+use crate::tension::TensionWeights;
+use crate::timeouts::RequestTimeouts;
+use crate::timezone::parse_timezone;
+
+/// Placeholder shown instead of secret values.
+const REDACTED: &str = "<redacted>";
+
+/// How long a `--probe` request may take before the endpoint counts as
+/// unreachable.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// HDX national risk scores run from 0 to 10.
+const MAX_RISK_SCORE: f64 = 10.0;
+
+/// Settings that must all be present once off-site backups are enabled.
+const OFFSITE_REQUIRED: &[&str] = &[
+    "INFRARED_OFFSITE_ACCESS_KEY_ID",
+    "INFRARED_OFFSITE_SECRET_ACCESS_KEY",
+    "INFRARED_OFFSITE_BACKUP_KEY",
+];
+
+/// Settings that must all be present once OIDC login is enabled.
+const OIDC_REQUIRED: &[&str] = &[
+    "INFRARED_OIDC_CLIENT_ID",
+    "INFRARED_OIDC_CLIENT_SECRET",
+    "INFRARED_OIDC_REDIRECT_URL",
+    "INFRARED_SESSION_SECRET",
+];
+
+/// A known environment variable.
+struct Setting {
+    name: &'static str,
+    default: Option<&'static str>,
+    secret: bool,
+    validate: fn(&str) -> anyhow::Result<()>,
+}
+
+impl Setting {
+    const fn new(
+        name: &'static str,
+        default: Option<&'static str>,
+        validate: fn(&str) -> anyhow::Result<()>,
+    ) -> Self {
+        Self {
+            name,
+            default,
+            secret: false,
+            validate,
+        }
+    }
+
+    const fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+}
+
+/// Every environment variable the server reads, with its default.
+const SETTINGS: &[Setting] = &[
+    Setting::new(PROFILE_ENV, None, profile),
+    Setting::new("INFRARED_PORT", Some("3000"), port),
+    Setting::new(
+        "INFRARED_DATABASE_URL",
+        Some("sqlite:infrared.db?mode=rwc"),
+        database_url,
+    ),
+    Setting::new("INFRARED_SQLITE_JOURNAL_MODE", Some("wal"), journal_mode),
+    Setting::new("INFRARED_SQLITE_SYNCHRONOUS", Some("normal"), synchronous),
+    Setting::new(
+        "INFRARED_SQLITE_BUSY_TIMEOUT_MS",
+        Some("5000"),
+        number::<u64>,
+    ),
+    Setting::new("INFRARED_SQLITE_CACHE_KIB", None, positive),
+    Setting::new("INFRARED_SQLITE_WRITE_CONNECTIONS", Some("1"), positive),
+    Setting::new("INFRARED_SQLITE_READ_CONNECTIONS", Some("4"), positive),
+    Setting::new("INFRARED_DATABASE_KEY", None, database_key).secret(),
+    Setting::new("INFRARED_API_TOKENS", None, api_tokens).secret(),
+    Setting::new("INFRARED_JWT_SECRET", None, anything).secret(),
+    Setting::new("INFRARED_JWT_PUBLIC_KEY_FILE", None, jwt_public_key),
+    Setting::new("INFRARED_JWT_JWKS_URL", None, url),
+    Setting::new("INFRARED_JWT_ISSUER", None, anything),
+    Setting::new("INFRARED_JWT_AUDIENCE", None, anything),
+    Setting::new("INFRARED_JWT_ROLE_CLAIM", Some("role"), anything),
+    Setting::new("INFRARED_OIDC_ISSUER", None, url),
+    Setting::new("INFRARED_OIDC_CLIENT_ID", None, anything),
+    Setting::new("INFRARED_OIDC_CLIENT_SECRET", None, anything).secret(),
+    Setting::new("INFRARED_OIDC_REDIRECT_URL", None, url),
+    Setting::new("INFRARED_SESSION_SECRET", None, anything).secret(),
+    Setting::new("INFRARED_OIDC_ROLE_CLAIM", Some("role"), anything),
+    Setting::new("INFRARED_OIDC_DEFAULT_ROLE", Some("read"), role),
+    Setting::new("INFRARED_REQUEST_TIMEOUTS", None, request_timeouts),
+    Setting::new("INFRARED_MAX_WINDOW_MINUTES", Some("1440"), positive),
+    Setting::new("INFRARED_MAX_RAW_HISTORY_DAYS", Some("31"), positive),
+    Setting::new("INFRARED_MAX_BATCH_BUCKETS", Some("100"), positive),
+    Setting::new("INFRARED_BUCKET_QUOTA_PER_MINUTE", None, number::<u32>),
+    Setting::new("INFRARED_BUCKET_QUOTAS", None, quotas),
+    Setting::new("INFRARED_SIGNAL_CHANNELS", None, signal_channels),
+    Setting::new("INFRARED_BUCKET_GROUPS", None, groups),
+    Setting::new("INFRARED_BUCKET_BASELINES", None, baselines),
+    Setting::new(
+        "INFRARED_ALERT_SEVERITY_HOURS",
+        Some("2:12"),
+        severity_tiers,
+    ),
+    Setting::new("INFRARED_ALERT_ROUTES", None, alert_routes),
+    Setting::new("INFRARED_ALERT_CHECK_SECONDS", Some("60"), number::<u64>),
+    Setting::new("INFRARED_STATUS_WEBHOOKS", None, url_list),
+    Setting::new("INFRARED_CAP_ENDPOINTS", None, url_list),
+    Setting::new("INFRARED_OPS_WEBHOOKS", None, url_list),
+    Setting::new("INFRARED_CAP_SENDER", Some("infrared"), anything),
+    Setting::new("INFRARED_SWITCH_ESCALATION", None, escalation),
+    Setting::new("INFRARED_SWITCH_CHECK_SECONDS", Some("30"), number::<u64>),
+    Setting::new(
+        "INFRARED_HEARTBEAT_CHECK_SECONDS",
+        Some("30"),
+        number::<u64>,
+    ),
+    Setting::new(
+        "INFRARED_MAINTENANCE_INTERVAL_MINUTES",
+        Some("360"),
+        number::<u64>,
+    ),
+    Setting::new("INFRARED_MAINTENANCE_QUIET_HOURS", None, quiet_hours),
+    Setting::new("INFRARED_MAINTENANCE_TIMEZONE", Some("UTC"), timezone),
+    Setting::new("INFRARED_RETENTION_DAYS", Some("90"), number::<u32>),
+    Setting::new("INFRARED_COMPACT_AFTER_DAYS", Some("0"), number::<u32>),
+    Setting::new("INFRARED_BACKUP_DIR", None, directory),
+    Setting::new("INFRARED_IDEMPOTENCY_TTL_HOURS", Some("24"), positive),
+    Setting::new("INFRARED_BUCKET_PATTERN", None, bucket_pattern),
+    Setting::new("INFRARED_BUCKET_NORMALIZE", Some("false"), boolean),
+    Setting::new("INFRARED_PUBLIC_MIRROR", Some("false"), boolean),
+    Setting::new("INFRARED_PUBLIC_MIN_BUCKETS", Some("5"), positive),
+    Setting::new("INFRARED_PUBLIC_LABELS", None, public_labels),
+    Setting::new("INFRARED_JOURNAL_DIR", None, anything),
+    Setting::new("INFRARED_JOURNAL_MAX_MB", Some("64"), positive),
+    Setting::new("INFRARED_JOURNAL_KEEP", Some("16"), positive),
+    Setting::new("INFRARED_OFFSITE_BUCKET", None, anything),
+    Setting::new("INFRARED_OFFSITE_ENDPOINT", None, url),
+    Setting::new("INFRARED_OFFSITE_REGION", Some("us-east-1"), anything),
+    Setting::new("INFRARED_OFFSITE_PREFIX", Some("infrared/"), anything),
+    Setting::new("INFRARED_OFFSITE_ACCESS_KEY_ID", None, anything),
+    Setting::new("INFRARED_OFFSITE_SECRET_ACCESS_KEY", None, anything).secret(),
+    Setting::new("INFRARED_OFFSITE_BACKUP_KEY", None, backup_key).secret(),
+    Setting::new("INFRARED_OFFSITE_INTERVAL_HOURS", Some("24"), positive),
+    Setting::new("INFRARED_OFFSITE_KEEP", Some("7"), positive),
+    Setting::new("INFRARED_WRITE_BUFFER_SIZE", Some("500"), number::<usize>),
+    Setting::new("INFRARED_WRITE_BUFFER_MS", Some("1000"), positive),
+    Setting::new(
+        "INFRARED_WARMTH_SNAPSHOT_MINUTES",
+        Some("10"),
+        number::<u32>,
+    ),
+    Setting::new(
+        "INFRARED_CANARY_INTERVAL_SECONDS",
+        Some("60"),
+        number::<u64>,
+    ),
+    Setting::new("INFRARED_CANARY_BUCKETS", Some("ingest"), anything),
+    Setting::new("INFRARED_TELEMETRY_URL", None, url),
+    Setting::new("INFRARED_TELEMETRY_INTERVAL_HOURS", Some("24"), positive),
+    Setting::new("INFRARED_STRESSED_RATIO", Some("0.8"), ratio),
+    Setting::new("INFRARED_COLLAPSING_RATIO", Some("0.2"), ratio),
+    Setting::new("INFRARED_DETECTOR", Some("ratio"), detector),
+    Setting::new("INFRARED_DETECTOR_SIGMAS", Some("3"), sigmas),
+    Setting::new("INFRARED_SEVERITY_CALIBRATION", None, calibration),
+    Setting::new("INFRARED_TENSION_WEIGHTS", None, tension_weights),
+    Setting::new("INFRARED_SUPPRESSION_RULES", None, suppression_rules),
+    Setting::new("DASHBOARD_APP_ID", Some("infrared"), anything),
+    Setting::new("DASHBOARD_LOOKBACK_HOURS", Some("24"), positive),
+    Setting::new("DASHBOARD_SOURCES", None, data_sources),
+    Setting::new("DASHBOARD_HTTP_USER_AGENT", None, anything),
+    Setting::new("DASHBOARD_HTTP_TIMEOUT_SECONDS", Some("30"), positive),
+    Setting::new(
+        "DASHBOARD_HTTP_CONNECT_TIMEOUT_SECONDS",
+        Some("10"),
+        positive,
+    ),
+    Setting::new("DASHBOARD_HTTP_PROXY", None, proxy).secret(),
+    Setting::new("DASHBOARD_HTTP_CA_CERT", None, ca_cert),
+    Setting::new(
+        "DASHBOARD_HTTP_POOL_MAX_IDLE_PER_HOST",
+        None,
+        number::<usize>,
+    ),
+    Setting::new("DASHBOARD_HTTP_POOL_IDLE_SECONDS", Some("90"), positive),
+    Setting::new("DASHBOARD_HTTP_MAX_RESPONSE_MB", Some("32"), positive),
+    Setting::new(
+        "DASHBOARD_SNAPSHOT_INTERVAL_MINUTES",
+        Some("60"),
+        number::<u64>,
+    ),
+    Setting::new(
+        "DASHBOARD_SNAPSHOT_RETENTION_DAYS",
+        Some("90"),
+        number::<u32>,
+    ),
+    Setting::new("DASHBOARD_HOT_REFRESH_MINUTES", Some("15"), number::<u64>),
+    Setting::new("DASHBOARD_SOURCE_BUDGETS", None, source_budgets),
+    Setting::new("DASHBOARD_STALE_SOURCE_HOURS", Some("6"), number::<u64>),
+    Setting::new("ACLED_EMAIL", None, anything).secret(),
+    Setting::new("ACLED_KEY", None, anything).secret(),
+    Setting::new("ACLED_REGIONS", None, acled_regions),
+    Setting::new("CLOUDFLARE_TOKEN", None, anything).secret(),
+    Setting::new("ACAPS_TOKEN", None, anything).secret(),
+    Setting::new("IOM_DTM_KEY", None, anything).secret(),
+    Setting::new("HDX_RISK_HIGH", Some("5.0"), risk_score),
+    Setting::new("HDX_RISK_VERY_HIGH", Some("7.0"), risk_score),
+    Setting::new("HDX_COUNTRY_RISK_THRESHOLDS", None, country_risk_thresholds),
+    Setting::new("HDX_RISING_RISK_DELTA", Some("1.0"), non_negative),
+    Setting::new("HDX_CHANGE_THRESHOLD_PERCENT", Some("25"), percent),
+    Setting::new("FOOD_PRICE_SPIKE_PERCENT", Some("20"), percent),
+    Setting::new("WIKIMEDIA_PROJECTS", None, wikimedia_projects),
+    Setting::new("EMDAT_CSV", None, readable_file),
+    Setting::new("EMDAT_API_KEY", None, anything).secret(),
+];
+
+/// One setting as the server would see it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingReport {
+    /// Environment variable name.
+    pub name: String,
+
+    /// Effective value: the configured one, else the deployment profile's,
+    /// else the default. Secrets are redacted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Whether the value came from the environment rather than the default.
+    pub configured: bool,
+
+    /// Why the configured value is invalid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Reachability of one notification endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProbeResult {
+    /// Setting the endpoint was configured in.
+    pub setting: String,
+
+    /// The endpoint.
+    pub url: String,
+
+    /// Whether the endpoint answered.
+    pub ok: bool,
+
+    /// Why it could not be reached.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Full report produced by `infrared check-config`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigReport {
+    /// True only if every setting is valid, no cross-setting problem was
+    /// found and every probed endpoint answered.
+    pub ok: bool,
+
+    /// Every known setting, in a fixed order.
+    pub settings: Vec<SettingReport>,
+
+    /// Problems involving more than one setting.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub problems: Vec<String>,
+
+    /// Notification endpoint probes (`--probe` only).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub probes: Vec<ProbeResult>,
+}
+
+impl ConfigReport {
+    fn update_ok(&mut self) {
+        self.ok = self.settings.iter().all(|s| s.error.is_none())
+            && self.problems.is_empty()
+            && self.probes.iter().all(|p| p.ok);
+    }
+}
+
+/// Validate the configuration returned by `env` (normally
+/// [`std::env::var`]).
+pub fn check_config(env: impl Fn(&str) -> Option<String>) -> ConfigReport {
+    let profile = Profile::from_env(&env).unwrap_or(None);
+    let effective = |name: &str| lookup(&env, profile, name);
+
+    let settings = SETTINGS
+        .iter()
+        .map(|setting| {
+            let configured = env(setting.name);
+            let error = configured
+                .as_deref()
+                .and_then(|value| (setting.validate)(value).err())
+                .map(|e| e.to_string());
+            let from_profile = profile.and_then(|profile| profile.default_for(setting.name));
+            let value = configured
+                .as_deref()
+                .or(from_profile)
+                .or(setting.default)
+                .map(|value| {
+                    if setting.secret && configured.is_some() {
+                        REDACTED.to_string()
+                    } else {
+                        value.to_string()
+                    }
+                });
+            SettingReport {
+                name: setting.name.to_string(),
+                value,
+                configured: configured.is_some(),
+                error,
+            }
+        })
+        .collect();
+
+    let mut report = ConfigReport {
+        ok: false,
+        settings,
+        problems: cross_setting_problems(&effective),
+        probes: Vec::new(),
+    };
+    report.update_ok();
+    report
+}
+
+/// Check that combinations of settings make sense.
+fn cross_setting_problems(env: &impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let defaults = RiskThresholds::default();
+    let high = env("HDX_RISK_HIGH")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults.high);
+    let very_high = env("HDX_RISK_VERY_HIGH")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(defaults.very_high);
+    if high > very_high {
+        problems.push(format!(
+            "HDX_RISK_HIGH ({}) is above HDX_RISK_VERY_HIGH ({})",
+            high, very_high
+        ));
+    }
+
+    let defaults = WarmthThresholds::default();
+    let thresholds = WarmthThresholds {
+        stressed_ratio: env("INFRARED_STRESSED_RATIO")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.stressed_ratio),
+        collapsing_ratio: env("INFRARED_COLLAPSING_RATIO")
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(defaults.collapsing_ratio),
+        ewma_alpha: None,
+    };
+    if thresholds.collapsing_ratio >= thresholds.stressed_ratio {
+        problems.push(format!(
+            "INFRARED_COLLAPSING_RATIO ({}) is not below INFRARED_STRESSED_RATIO ({})",
+            thresholds.collapsing_ratio, thresholds.stressed_ratio
+        ));
+    }
+
+    if env("INFRARED_OIDC_ISSUER").is_some() {
+        for name in OIDC_REQUIRED {
+            if env(name).is_none() {
+                problems.push(format!("{} is required when OIDC is enabled", name));
+            }
+        }
+    }
+
+    let retention_days: u32 = env("INFRARED_RETENTION_DAYS")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let raw_history_days: u32 = env("INFRARED_MAX_RAW_HISTORY_DAYS")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(QueryLimits::default().max_raw_history_days);
+    if retention_days > 0 && retention_days < raw_history_days {
+        problems.push(format!(
+            "INFRARED_RETENTION_DAYS ({}) is below INFRARED_MAX_RAW_HISTORY_DAYS ({}), so baselines would read pruned history",
+            retention_days, raw_history_days
+        ));
+    }
+    let compact_after_days: u32 = env("INFRARED_COMPACT_AFTER_DAYS")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if compact_after_days > 0 && retention_days > 0 && compact_after_days >= retention_days {
+        problems.push(format!(
+            "INFRARED_COMPACT_AFTER_DAYS ({}) is not below INFRARED_RETENTION_DAYS ({}), so signals would be pruned before being compacted",
+            compact_after_days, retention_days
+        ));
+    }
+
+    if env("INFRARED_OFFSITE_BUCKET").is_some() {
+        for name in OFFSITE_REQUIRED {
+            if env(name).is_none() {
+                problems.push(format!(
+                    "{} is required when off-site backups are enabled",
+                    name
+                ));
+            }
+        }
+    }
+
+    match (env("ACLED_EMAIL"), env("ACLED_KEY")) {
+        (Some(_), None) => problems.push("ACLED_EMAIL is set without ACLED_KEY".to_string()),
+        (None, Some(_)) => problems.push("ACLED_KEY is set without ACLED_EMAIL".to_string()),
+        (None, None) if env("ACLED_REGIONS").is_some() => {
+            problems.push("ACLED_REGIONS requires ACLED_EMAIL and ACLED_KEY".to_string())
+        }
+        _ => {}
+    }
+
+    problems
+}
+
+/// Notification endpoints in the configuration, with the setting each came
+/// from. Unparseable settings are skipped; [`check_config`] reports them.
+pub fn notification_urls(env: impl Fn(&str) -> Option<String>) -> Vec<(String, String)> {
+    let mut urls = Vec::new();
+    if let Some(spec) = env("INFRARED_STATUS_WEBHOOKS") {
+        for url in split_list(&spec) {
+            urls.push(("INFRARED_STATUS_WEBHOOKS".to_string(), url.to_string()));
+        }
+    }
+    if let Some(policy) =
+        env("INFRARED_SWITCH_ESCALATION").and_then(|spec| EscalationPolicy::parse(&spec).ok())
+    {
+        for step in policy.steps() {
+            urls.push((
+                "INFRARED_SWITCH_ESCALATION".to_string(),
+                step.webhook_url.clone(),
+            ));
+        }
+    }
+    if let Some(routes) =
+        env("INFRARED_ALERT_ROUTES").and_then(|spec| SeverityRoutes::parse(&spec).ok())
+    {
+        for (_, url) in routes.all() {
+            urls.push(("INFRARED_ALERT_ROUTES".to_string(), url.to_string()));
+        }
+    }
+    if let Some(spec) = env("INFRARED_CAP_ENDPOINTS") {
+        for url in split_list(&spec) {
+            urls.push(("INFRARED_CAP_ENDPOINTS".to_string(), url.to_string()));
+        }
+    }
+    if let Some(spec) = env("INFRARED_OPS_WEBHOOKS") {
+        for url in split_list(&spec) {
+            urls.push(("INFRARED_OPS_WEBHOOKS".to_string(), url.to_string()));
+        }
+    }
+    if let Some(url) = env("INFRARED_TELEMETRY_URL") {
+        urls.push(("INFRARED_TELEMETRY_URL".to_string(), url));
+    }
+    urls
+}
+
+/// Contact every notification endpoint in the configuration, adding the
+/// results to `report`.
+///
+/// Any HTTP response counts as reachable: endpoints often reject `HEAD`,
+/// and nothing is POSTed so that no notification fires.
+pub async fn probe_notification_channels(
+    report: &mut ConfigReport,
+    env: impl Fn(&str) -> Option<String>,
+) {
+    let client = reqwest::Client::new();
+    for (setting, url) in notification_urls(env) {
+        let error = match client.head(&url).timeout(PROBE_TIMEOUT).send().await {
+            Ok(_) => None,
+            Err(e) => Some(e.to_string()),
+        };
+        report.probes.push(ProbeResult {
+            setting,
+            url,
+            ok: error.is_none(),
+            error,
+        });
+    }
+    report.update_ok();
+}
+
+fn split_list(spec: &str) -> impl Iterator<Item = &str> {
+    spec.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+// ============================================================================
+// Validators
+// ============================================================================
+
+fn anything(_: &str) -> anyhow::Result<()> {
+    Ok(())
+}
+
+fn number<T: FromStr>(value: &str) -> anyhow::Result<()> {
+    value
+        .trim()
+        .parse::<T>()
+        .map(drop)
+        .map_err(|_| anyhow::anyhow!("'{}' is not a valid number", value))
+}
+
+fn float(value: &str) -> anyhow::Result<f64> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not a valid number", value))
+}
+
+fn port(value: &str) -> anyhow::Result<()> {
+    match value.trim().parse::<u16>() {
+        Ok(port) if port > 0 => Ok(()),
+        _ => anyhow::bail!("'{}' is not a valid port", value),
+    }
+}
+
+fn positive(value: &str) -> anyhow::Result<()> {
+    match value.trim().parse::<u64>() {
+        Ok(n) if n > 0 => Ok(()),
+        _ => anyhow::bail!("'{}' is not a positive whole number", value),
+    }
+}
+
+fn percent(value: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(float(value)? > 0.0, "percentage must be above 0");
+    Ok(())
+}
+
+fn ratio(value: &str) -> anyhow::Result<()> {
+    let ratio = float(value)?;
+    anyhow::ensure!(
+        ratio > 0.0 && ratio <= 1.0,
+        "ratio {} is outside (0, 1]",
+        ratio
+    );
+    Ok(())
+}
+
+fn detector(value: &str) -> anyhow::Result<()> {
+    Detector::parse(value.trim())
+        .map(drop)
+        .ok_or_else(|| anyhow::anyhow!("'{}' is not ratio, zscore or mad", value))
+}
+
+fn sigmas(value: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(float(value)? > 0.0, "deviations must be above 0");
+    Ok(())
+}
+
+fn non_negative(value: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(float(value)? >= 0.0, "value must not be negative");
+    Ok(())
+}
+
+fn risk_score(value: &str) -> anyhow::Result<()> {
+    let score = float(value)?;
+    anyhow::ensure!(
+        (0.0..=MAX_RISK_SCORE).contains(&score),
+        "risk score {} is outside 0-{}",
+        score,
+        MAX_RISK_SCORE
+    );
+    Ok(())
+}
+
+fn database_url(value: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        value.starts_with("sqlite:"),
+        "only sqlite: database URLs are supported"
+    );
+    Ok(())
+}
+
+fn url(value: &str) -> anyhow::Result<()> {
+    let parsed = reqwest::Url::parse(value.trim())
+        .map_err(|e| anyhow::anyhow!("'{}' is not a valid URL: {}", value, e))?;
+    anyhow::ensure!(
+        matches!(parsed.scheme(), "http" | "https"),
+        "'{}' is not an http(s) URL",
+        value
+    );
+    Ok(())
+}
+
+fn url_list(value: &str) -> anyhow::Result<()> {
+    split_list(value).try_for_each(url)
+}
+
+fn readable_file(value: &str) -> anyhow::Result<()> {
+    std::fs::read(value).map_err(|e| anyhow::anyhow!("cannot read '{}': {}", value, e))?;
+    Ok(())
+}
+
+fn database_key(_: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        cfg!(feature = "sqlcipher"),
+        "encrypting the database needs a build with `--features sqlcipher`"
+    );
+    Ok(())
+}
+
+fn backup_key(value: &str) -> anyhow::Result<()> {
+    crate::offsite::BackupKey::parse(value).map(drop)
+}
+
+fn directory(value: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        std::path::Path::new(value).is_dir(),
+        "'{}' is not a directory",
+        value
+    );
+    Ok(())
+}
+
+fn proxy(value: &str) -> anyhow::Result<()> {
+    reqwest::Proxy::all(value.trim())
+        .map(drop)
+        .map_err(Into::into)
+}
+
+fn ca_cert(value: &str) -> anyhow::Result<()> {
+    let certs = reqwest::Certificate::from_pem_bundle(read_to_string(value)?.as_bytes())?;
+    anyhow::ensure!(!certs.is_empty(), "'{}' holds no certificates", value);
+    Ok(())
+}
+
+fn read_to_string(path: &str) -> anyhow::Result<String> {
+    std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("cannot read '{}': {}", path, e))
+}
+
+fn jwt_public_key(value: &str) -> anyhow::Result<()> {
+    JwtValidator::rs256_pem(&read_to_string(value)?).map(drop)
+}
+
+fn calibration(value: &str) -> anyhow::Result<()> {
+    Calibration::from_json(&read_to_string(value)?).map(drop)
+}
+
+fn tension_weights(value: &str) -> anyhow::Result<()> {
+    TensionWeights::parse(value).map(drop)
+}
+
+fn suppression_rules(value: &str) -> anyhow::Result<()> {
+    SuppressionRules::from_json(&read_to_string(value)?).map(drop)
+}
+
+fn public_labels(value: &str) -> anyhow::Result<()> {
+    PublicMirror::parse_labels(value).map(drop)
+}
+
+fn role(value: &str) -> anyhow::Result<()> {
+    Role::parse(value)
+        .map(drop)
+        .ok_or_else(|| anyhow::anyhow!("unknown role '{}'", value))
+}
+
+fn boolean(value: &str) -> anyhow::Result<()> {
+    value
+        .trim()
+        .parse::<bool>()
+        .map(drop)
+        .map_err(|_| anyhow::anyhow!("expected true or false"))
+}
+
+fn bucket_pattern(value: &str) -> anyhow::Result<()> {
+    NamingConvention::new(Some(value), false).map(drop)
+}
+
+fn journal_mode(value: &str) -> anyhow::Result<()> {
+    JournalMode::parse(value).map(drop)
+}
+
+fn synchronous(value: &str) -> anyhow::Result<()> {
+    Synchronous::parse(value).map(drop)
+}
+
+fn api_tokens(value: &str) -> anyhow::Result<()> {
+    AuthConfig::parse(value).map(drop)
+}
+
+fn request_timeouts(value: &str) -> anyhow::Result<()> {
+    RequestTimeouts::parse(value).map(drop)
+}
+
+fn quotas(value: &str) -> anyhow::Result<()> {
+    QuotaConfig::parse_overrides(value).map(drop)
+}
+
+fn signal_channels(value: &str) -> anyhow::Result<()> {
+    SignalChannels::parse(value).map(drop)
+}
+
+fn groups(value: &str) -> anyhow::Result<()> {
+    BucketGroup::parse_list(value).map(drop)
+}
+
+fn baselines(value: &str) -> anyhow::Result<()> {
+    Baseline::parse_overrides(value).map(drop)
+}
+
+fn escalation(value: &str) -> anyhow::Result<()> {
+    EscalationPolicy::parse(value)?
+        .steps()
+        .iter()
+        .try_for_each(|step| url(&step.webhook_url))
+}
+
+fn severity_tiers(value: &str) -> anyhow::Result<()> {
+    SeverityTiers::parse(value).map(drop)
+}
+
+fn alert_routes(value: &str) -> anyhow::Result<()> {
+    SeverityRoutes::parse(value)?
+        .all()
+        .try_for_each(|(_, webhook)| url(webhook))
+}
+
+fn acled_regions(value: &str) -> anyhow::Result<()> {
+    AcledRegion::parse_list(value).map(drop)
+}
+
+fn country_code(code: &str, len: usize) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        code.len() == len && code.chars().all(|c| c.is_ascii_alphabetic()),
+        "'{}' is not an ISO 3166-1 alpha-{} country code",
+        code,
+        len
+    );
+    Ok(())
+}
+
+fn country_risk_thresholds(value: &str) -> anyhow::Result<()> {
+    for (code, thresholds) in RiskThresholds::parse_country_list(value)? {
+        country_code(&code, 3)?;
+        for score in [thresholds.high, thresholds.very_high] {
+            risk_score(&score.to_string())
+                .map_err(|e| anyhow::anyhow!("{} for country '{}'", e, code))?;
+        }
+    }
+    Ok(())
+}
+
+fn data_sources(value: &str) -> anyhow::Result<()> {
+    IssueSource::parse_list(value).map(drop)
+}
+
+fn source_budgets(value: &str) -> anyhow::Result<()> {
+    RefreshCadence::parse_budgets(value).map(drop)
+}
+
+fn quiet_hours(value: &str) -> anyhow::Result<()> {
+    QuietHours::parse(value, chrono_tz::Tz::UTC).map(drop)
+}
+
+fn timezone(value: &str) -> anyhow::Result<()> {
+    parse_timezone(value).map(drop)
+}
+
+fn profile(value: &str) -> anyhow::Result<()> {
+    Profile::from_env(|_| Some(value.to_string())).map(drop)
+}
+
+fn wikimedia_projects(value: &str) -> anyhow::Result<()> {
+    WikimediaProject::parse_list(value)?
+        .iter()
+        .try_for_each(|project| country_code(&project.country_code, 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    fn setting<'a>(report: &'a ConfigReport, name: &str) -> &'a SettingReport {
+        report.settings.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_defaults_are_valid() {
+        let report = check_config(env(&[]));
+        assert!(report.ok, "{:?}", report);
+
+        let port = setting(&report, "INFRARED_PORT");
+        assert_eq!(port.value.as_deref(), Some("3000"));
+        assert!(!port.configured);
+        assert!(setting(&report, "INFRARED_STATUS_WEBHOOKS").value.is_none());
+    }
+
+    #[test]
+    fn test_invalid_settings_reported() {
+        let report = check_config(env(&[
+            ("INFRARED_PORT", "eighty"),
+            ("HDX_RISK_HIGH", "12"),
+            ("FOOD_PRICE_SPIKE_PERCENT", "-5"),
+            ("HDX_COUNTRY_RISK_THRESHOLDS", "SO1=6:8"),
+            ("WIKIMEDIA_PROJECTS", "fa.wikipedia=I9"),
+            (
+                "INFRARED_STATUS_WEBHOOKS",
+                "https://ok.example/hook,not a url",
+            ),
+            ("INFRARED_SWITCH_ESCALATION", "0=ftp://files.example/hook"),
+        ]));
+        assert!(!report.ok);
+
+        let invalid: Vec<&str> = report
+            .settings
+            .iter()
+            .filter(|s| s.error.is_some())
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(
+            invalid,
+            [
+                "INFRARED_PORT",
+                "INFRARED_STATUS_WEBHOOKS",
+                "INFRARED_SWITCH_ESCALATION",
+                "HDX_RISK_HIGH",
+                "HDX_COUNTRY_RISK_THRESHOLDS",
+                "FOOD_PRICE_SPIKE_PERCENT",
+                "WIKIMEDIA_PROJECTS",
+            ]
+        );
+        // The configured value is shown even when invalid
+        assert_eq!(
+            setting(&report, "INFRARED_PORT").value.as_deref(),
+            Some("eighty")
+        );
+    }
+
+    #[test]
+    fn test_cross_setting_problems_and_redaction() {
+        let report = check_config(env(&[
+            ("HDX_RISK_HIGH", "8"),
+            ("HDX_RISK_VERY_HIGH", "6"),
+            ("INFRARED_COLLAPSING_RATIO", "0.9"),
+            ("INFRARED_OIDC_ISSUER", "https://login.example"),
+            ("INFRARED_OIDC_CLIENT_ID", "infrared"),
+            ("ACLED_REGIONS", "middle_east"),
+            ("INFRARED_API_TOKENS", "admin:s3cret"),
+        ]));
+        assert!(!report.ok);
+        assert!(report.settings.iter().all(|s| s.error.is_none()));
+        assert_eq!(report.problems.len(), 6, "{:?}", report.problems);
+        assert!(report.problems[0].contains("HDX_RISK_HIGH"));
+        assert!(report.problems[1].starts_with("INFRARED_COLLAPSING_RATIO (0.9)"));
+        assert!(
+            report
+                .problems
+                .iter()
+                .any(|p| p.starts_with("INFRARED_SESSION_SECRET"))
+        );
+
+        let tokens = setting(&report, "INFRARED_API_TOKENS");
+        assert_eq!(tokens.value.as_deref(), Some(REDACTED));
+        assert!(!serde_json::to_string(&report).unwrap().contains("s3cret"));
+    }
+
+    #[test]
+    fn test_retention_below_raw_history() {
+        let report = check_config(env(&[("INFRARED_RETENTION_DAYS", "14")]));
+        assert_eq!(report.problems.len(), 1, "{:?}", report.problems);
+        assert!(report.problems[0].starts_with("INFRARED_RETENTION_DAYS"));
+
+        let report = check_config(env(&[
+            ("INFRARED_RETENTION_DAYS", "14"),
+            ("INFRARED_MAX_RAW_HISTORY_DAYS", "7"),
+        ]));
+        assert!(report.problems.is_empty());
+
+        let report = check_config(env(&[
+            ("INFRARED_RETENTION_DAYS", "90"),
+            ("INFRARED_COMPACT_AFTER_DAYS", "90"),
+        ]));
+        assert_eq!(report.problems.len(), 1, "{:?}", report.problems);
+        assert!(report.problems[0].starts_with("INFRARED_COMPACT_AFTER_DAYS"));
+    }
+
+    #[test]
+    fn test_profiles_are_valid() {
+        for profile in Profile::ALL {
+            for (name, value) in profile.settings() {
+                let setting = SETTINGS.iter().find(|s| s.name == *name).unwrap();
+                assert!((setting.validate)(value).is_ok(), "{}", name);
+            }
+            let report = check_config(env(&[(PROFILE_ENV, profile.name())]));
+            assert!(report.ok, "{}: {:?}", profile.name(), report.problems);
+        }
+
+        // Profile values show as effective, and the environment still wins
+        let report = check_config(env(&[
+            (PROFILE_ENV, "global"),
+            ("INFRARED_COMPACT_AFTER_DAYS", "30"),
+        ]));
+        let retention = setting(&report, "INFRARED_RETENTION_DAYS");
+        assert_eq!(retention.value.as_deref(), Some("365"));
+        assert!(!retention.configured);
+        let compaction = setting(&report, "INFRARED_COMPACT_AFTER_DAYS");
+        assert_eq!(compaction.value.as_deref(), Some("30"));
+        assert!(compaction.configured);
+
+        // Cross-setting checks see profile values too
+        let report = check_config(env(&[
+            (PROFILE_ENV, "field"),
+            ("INFRARED_COMPACT_AFTER_DAYS", "60"),
+        ]));
+        assert_eq!(report.problems.len(), 1, "{:?}", report.problems);
+
+        assert!(!check_config(env(&[(PROFILE_ENV, "huge")])).ok);
+    }
+
+    #[test]
+    fn test_offsite_backups_need_credentials_and_key() {
+        let report = check_config(env(&[
+            ("INFRARED_OFFSITE_BUCKET", "backups"),
+            ("INFRARED_OFFSITE_ACCESS_KEY_ID", "id"),
+            ("INFRARED_OFFSITE_BACKUP_KEY", "not hex"),
+        ]));
+        assert_eq!(
+            report.problems,
+            ["INFRARED_OFFSITE_SECRET_ACCESS_KEY is required when off-site backups are enabled"]
+        );
+        let key = report
+            .settings
+            .iter()
+            .find(|s| s.name == "INFRARED_OFFSITE_BACKUP_KEY")
+            .unwrap();
+        assert_eq!(key.value.as_deref(), Some(REDACTED));
+        assert!(key.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_probe_notification_channels() {
+        let app = axum::Router::new().route("/hook", axum::routing::post(|| async {}));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let reachable = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // A port nothing listens on
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let unreachable = closed.local_addr().unwrap();
+        drop(closed);
+
+        let vars = [
+            (
+                "INFRARED_STATUS_WEBHOOKS",
+                format!("http://{}/hook", reachable),
+            ),
+            (
+                "INFRARED_SWITCH_ESCALATION",
+                format!("0=http://{}/hook", unreachable),
+            ),
+        ];
+        let pairs: Vec<(&str, &str)> = vars.iter().map(|(k, v)| (*k, v.as_str())).collect();
+
+        let mut report = check_config(env(&pairs));
+        assert!(report.ok);
+        probe_notification_channels(&mut report, env(&pairs)).await;
+
+        assert_eq!(report.probes.len(), 2);
+        // HEAD on a POST-only route answers 405, which still counts
+        assert!(report.probes[0].ok);
+        assert_eq!(report.probes[1].setting, "INFRARED_SWITCH_ESCALATION");
+        assert!(!report.probes[1].ok);
+        assert!(!report.ok);
+    }
+}
@@ -1,8 +1,11 @@
 //! Dashboard for aggregating issues from all data sources.
 //!
 //! This module provides a unified view of problems detected across all data sources:
-//! - Internet outages (IODA, Cloudflare)
-//! - Humanitarian crises (HDX HAPI, ReliefWeb)
+//! - Internet outages (IODA, Cloudflare, Tor Metrics)
+//! - Censorship onset (Tor Metrics)
+//! - Humanitarian crises (HDX HAPI, ACAPS, ReliefWeb)
+//! - Displacement (IOM DTM)
+//! - Collapses in ambient human activity (Wikimedia pageviews)
 //! - Conflict events (ACLED)
 //!
 //! # Usage
@@ -12,13 +15,63 @@
 //! let issues = dashboard.get_all_issues().await?;
 //! ```
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+use crate::attribution::{self, Attribution};
+use crate::cadence::{RefreshCadence, RefreshSchedule, hot_sources};
+use crate::calibration::Calibration;
+use crate::data_sources::acaps::AcapsCrisisSeverity;
+use crate::data_sources::acled::{AcledRegion, AcledResponse};
+use crate::data_sources::hdx_hapi::{
+    HdxFigureChange, HdxNationalRisk, HdxPriceChange, latest_change,
+};
+use crate::data_sources::reliefweb::{ReliefWebItem, ReliefWebReportFields};
+use crate::data_sources::tor_metrics::{
+    BASELINE_DAYS as TOR_BASELINE_DAYS, TorAnomalyKind, TorTransport, detect_anomalies,
+};
+use crate::data_sources::wikimedia::{WikimediaProject, detect_collapse};
 use crate::data_sources::{
-    AcledClient, CloudflareRadarClient, HdxHapiClient, IodaClient, ReliefWebClient,
+    AcapsClient, AcledClient, CloudflareRadarClient, EmdatBaseline, HdxHapiClient, HttpClient,
+    IodaClient, IomDtmClient, ReliefWebClient, TorMetricsClient, WikimediaClient, acaps, acled,
+    cloudflare, count_parse_warnings, hdx_hapi, ioda, iom_dtm, tor_metrics, wikimedia,
+};
+use crate::events::{Event, EventBus};
+use crate::issue_details::{
+    CensorshipDetails, ConflictDetails, DisasterDetails, DisplacementDetails, FigureChange,
+    FoodSecurityDetails, HumanitarianDetails, IssueDetails, OutageDetails, TrafficAnomalyDetails,
 };
+use crate::metrics::Metrics;
+use crate::storage::{SignalStore, Storage};
+use crate::suppression::{SuppressedIssues, SuppressionRules};
+use crate::tension::{self, CountryTension, TensionWeights};
+
+/// Issue severities share the bucket alerts' scale.
+pub use crate::model::IssueSeverity;
+
+/// Days of DTM reporting compared when looking for new displacement.
+const DTM_LOOKBACK_DAYS: u32 = 30;
+
+/// Days of Tor user estimates fetched (baseline plus publication lag).
+const TOR_LOOKBACK_DAYS: u32 = 14;
+
+/// Days of Wikimedia pageviews fetched (four weeks of pattern plus lag).
+const WIKIMEDIA_LOOKBACK_DAYS: u32 = 35;
+
+/// HDX dataset search matching the national risk scores (INFORM Risk).
+const HDX_RISK_QUERY: &str = "INFORM risk";
+
+/// Events fetched per watched ACLED region.
+const ACLED_REGION_LIMIT: u32 = 5000;
+
+/// Days of ReliefWeb situation reports fetched for monitored countries.
+const SITREP_LOOKBACK_DAYS: u32 = 7;
+
+/// Situation reports fetched per refresh, across all monitored countries.
+const SITREP_LIMIT: u32 = 50;
 
 /// Dashboard configuration.
 #[derive(Debug, Clone)]
@@ -32,14 +85,119 @@ pub struct DashboardConfig {
     /// Cloudflare API token (optional, for higher rate limits).
     pub cloudflare_token: Option<String>,
 
+    /// ACAPS API token (required for the INFORM Severity Index).
+    pub acaps_token: Option<String>,
+
+    /// IOM DTM API subscription key (required for DTM displacement data).
+    pub iom_dtm_key: Option<String>,
+
     /// Application identifier for HDX/ReliefWeb.
     pub app_identifier: String,
 
     /// Countries to monitor (ISO 3166-1 alpha-2 for IODA/Cloudflare, alpha-3 for others).
     pub monitored_countries: Vec<MonitoredCountry>,
 
+    /// ACLED regions watched with one query each, raising per-country issues.
+    pub acled_regions: Vec<AcledRegion>,
+
+    /// HDX national risk score cutoffs applied to every country.
+    pub hdx_risk_thresholds: RiskThresholds,
+
+    /// Per-country overrides of `hdx_risk_thresholds`, keyed by ISO 3166-1
+    /// alpha-3 code.
+    pub hdx_country_risk_thresholds: HashMap<String, RiskThresholds>,
+
+    /// Rise in a country's HDX national risk score since the previous period
+    /// that raises a warning, even below the high-risk cutoff.
+    pub hdx_rising_risk_delta: f64,
+
+    /// Rise in HDX people in need or IDPs between reporting periods, in
+    /// percent, that raises an issue for a monitored country.
+    pub hdx_change_threshold_percent: f64,
+
+    /// Month-over-month rise in staple food prices, in percent, that raises
+    /// a food security issue for a monitored country.
+    pub food_price_spike_percent: f64,
+
     /// Hours to look back for recent issues.
     pub lookback_hours: u32,
+
+    /// Wikipedia projects watched as proxies for activity in a country.
+    pub wikimedia_projects: Vec<WikimediaProject>,
+
+    /// Sources fetched; `None` fetches every configured source.
+    pub sources: Option<HashSet<IssueSource>>,
+}
+
+impl DashboardConfig {
+    /// HDX risk cutoffs for a country, honouring per-country overrides.
+    pub fn risk_thresholds_for(&self, country_code: &str) -> RiskThresholds {
+        self.hdx_country_risk_thresholds
+            .get(&country_code.to_uppercase())
+            .copied()
+            .unwrap_or(self.hdx_risk_thresholds)
+    }
+
+    /// Whether issues are fetched from `source`.
+    pub fn fetches(&self, source: IssueSource) -> bool {
+        self.sources
+            .as_ref()
+            .is_none_or(|sources| sources.contains(&source))
+    }
+}
+
+/// Cutoffs on the HDX national risk score (0-10).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskThresholds {
+    /// Score at or above which risk is high (critical issue).
+    pub high: f64,
+
+    /// Score at or above which risk is very high (emergency issue).
+    pub very_high: f64,
+}
+
+impl Default for RiskThresholds {
+    fn default() -> Self {
+        Self {
+            high: 5.0,
+            very_high: 7.0,
+        }
+    }
+}
+
+impl RiskThresholds {
+    /// Severity of a risk score, or `None` below the high cutoff.
+    pub fn severity(&self, score: f64) -> Option<IssueSeverity> {
+        if score >= self.very_high {
+            Some(IssueSeverity::Emergency)
+        } else if score >= self.high {
+            Some(IssueSeverity::Critical)
+        } else {
+            None
+        }
+    }
+
+    /// Parse per-country overrides as `code=high:very_high` pairs, e.g.
+    /// `SOM=6:8,AFG=5.5:7.5`.
+    pub fn parse_country_list(spec: &str) -> anyhow::Result<HashMap<String, Self>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let parsed = entry.split_once('=').and_then(|(code, cutoffs)| {
+                    let (high, very_high) = cutoffs.split_once(':')?;
+                    let thresholds = Self {
+                        high: high.trim().parse().ok()?,
+                        very_high: very_high.trim().parse().ok()?,
+                    };
+                    (thresholds.high <= thresholds.very_high)
+                        .then(|| (code.trim().to_uppercase(), thresholds))
+                });
+                parsed
+                    .ok_or_else(|| anyhow::anyhow!("expected code=high:very_high, got '{}'", entry))
+            })
+            .collect()
+    }
 }
 
 /// A country to monitor with both code formats.
@@ -61,41 +219,25 @@ impl Default for DashboardConfig {
             acled_email: None,
             acled_key: None,
             cloudflare_token: None,
+            acaps_token: None,
+            iom_dtm_key: None,
             app_identifier: "infrared".to_string(),
             monitored_countries: vec![],
+            acled_regions: vec![],
+            hdx_risk_thresholds: RiskThresholds::default(),
+            hdx_country_risk_thresholds: HashMap::new(),
+            hdx_rising_risk_delta: 1.0,
+            hdx_change_threshold_percent: 25.0,
+            food_price_spike_percent: 20.0,
             lookback_hours: 24,
-        }
-    }
-}
-
-/// Issue severity levels.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum IssueSeverity {
-    /// Informational - worth noting but not urgent.
-    Info,
-    /// Warning - potential problem developing.
-    Warning,
-    /// Critical - serious ongoing issue.
-    Critical,
-    /// Emergency - requires immediate attention.
-    Emergency,
-}
-
-impl IssueSeverity {
-    /// Get a human-readable label.
-    pub fn label(&self) -> &'static str {
-        match self {
-            IssueSeverity::Info => "Info",
-            IssueSeverity::Warning => "Warning",
-            IssueSeverity::Critical => "Critical",
-            IssueSeverity::Emergency => "Emergency",
+            wikimedia_projects: vec![],
+            sources: None,
         }
     }
 }
 
 /// The source of an issue.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum IssueSource {
     /// IODA Internet outage detection.
@@ -108,9 +250,33 @@ pub enum IssueSource {
     Acled,
     /// ReliefWeb disasters and reports.
     ReliefWeb,
+    /// IOM Displacement Tracking Matrix.
+    IomDtm,
+    /// Tor Metrics user estimates.
+    TorMetrics,
+    /// Wikimedia pageviews.
+    Wikimedia,
+    /// ACAPS INFORM Severity Index.
+    Acaps,
+    /// A partner's own detector, via `POST /ingest/issue`.
+    External,
 }
 
 impl IssueSource {
+    /// Every source, in the order their health is reported.
+    pub const ALL: [IssueSource; 10] = [
+        IssueSource::Ioda,
+        IssueSource::CloudflareRadar,
+        IssueSource::HdxHapi,
+        IssueSource::Acaps,
+        IssueSource::ReliefWeb,
+        IssueSource::Acled,
+        IssueSource::IomDtm,
+        IssueSource::TorMetrics,
+        IssueSource::Wikimedia,
+        IssueSource::External,
+    ];
+
     /// Get a human-readable label.
     pub fn label(&self) -> &'static str {
         match self {
@@ -119,12 +285,46 @@ impl IssueSource {
             IssueSource::HdxHapi => "HDX HAPI",
             IssueSource::Acled => "ACLED",
             IssueSource::ReliefWeb => "ReliefWeb",
+            IssueSource::IomDtm => "IOM DTM",
+            IssueSource::TorMetrics => "Tor Metrics",
+            IssueSource::Wikimedia => "Wikimedia",
+            IssueSource::Acaps => "ACAPS",
+            IssueSource::External => "External",
+        }
+    }
+
+    /// Parse a source name as accepted by the API (including short aliases).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ioda" => Some(IssueSource::Ioda),
+            "cloudflare_radar" | "cloudflare" => Some(IssueSource::CloudflareRadar),
+            "hdx_hapi" | "hdx" | "hapi" => Some(IssueSource::HdxHapi),
+            "acled" => Some(IssueSource::Acled),
+            "reliefweb" => Some(IssueSource::ReliefWeb),
+            "iom_dtm" | "dtm" => Some(IssueSource::IomDtm),
+            "tor_metrics" | "tor" => Some(IssueSource::TorMetrics),
+            "wikimedia" | "wikipedia" => Some(IssueSource::Wikimedia),
+            "acaps" => Some(IssueSource::Acaps),
+            "external" => Some(IssueSource::External),
+            _ => None,
         }
     }
+
+    /// Parse a comma-separated list of source names, e.g. `ioda,reliefweb`.
+    pub fn parse_list(spec: &str) -> anyhow::Result<HashSet<Self>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                Self::parse(&name.to_lowercase())
+                    .ok_or_else(|| anyhow::anyhow!("unknown data source '{}'", name))
+            })
+            .collect()
+    }
 }
 
 /// Category of issue.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum IssueCategory {
     /// Internet connectivity issues.
@@ -141,6 +341,8 @@ pub enum IssueCategory {
     Disaster,
     /// Humanitarian emergency.
     HumanitarianEmergency,
+    /// Censorship or blocking of Internet services.
+    Censorship,
 }
 
 impl IssueCategory {
@@ -154,14 +356,57 @@ impl IssueCategory {
             IssueCategory::Displacement => "Displacement",
             IssueCategory::Disaster => "Disaster",
             IssueCategory::HumanitarianEmergency => "Humanitarian Emergency",
+            IssueCategory::Censorship => "Censorship",
+        }
+    }
+
+    /// Parse a category from its snake_case name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "internet_outage" => Some(IssueCategory::InternetOutage),
+            "traffic_anomaly" => Some(IssueCategory::TrafficAnomaly),
+            "conflict" => Some(IssueCategory::Conflict),
+            "food_security" => Some(IssueCategory::FoodSecurity),
+            "displacement" => Some(IssueCategory::Displacement),
+            "disaster" => Some(IssueCategory::Disaster),
+            "humanitarian_emergency" => Some(IssueCategory::HumanitarianEmergency),
+            "censorship" => Some(IssueCategory::Censorship),
+            _ => None,
         }
     }
 }
 
+/// A country or region affected by an issue.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    /// Country or region name.
+    pub name: String,
+
+    /// ISO country code (alpha-2 or alpha-3 depending on source).
+    pub code: String,
+}
+
+/// A primary document giving context on an issue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IssueLink {
+    /// Document title.
+    pub title: String,
+
+    /// Where to read it.
+    pub url: String,
+
+    /// When it was published.
+    pub published: Option<DateTime<Utc>>,
+}
+
 /// A single issue detected from any data source.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
-    /// Unique issue identifier (source:type:location:timestamp).
+    /// Issue identifier, stable across refreshes for the same underlying event.
+    ///
+    /// `source:upstream_id` when the source names its events (see
+    /// [`Issue::with_upstream_id`]), otherwise `source:type:location:hash`
+    /// with a hash of the title and start time.
     pub id: String,
 
     /// Source system that detected this issue.
@@ -173,12 +418,20 @@ pub struct Issue {
     /// Severity level.
     pub severity: IssueSeverity,
 
-    /// Country or region affected.
+    /// Severity on a common 0-100 scale across sources (see [`crate::calibration`]).
+    #[serde(default)]
+    pub normalized_severity: Option<f64>,
+
+    /// Primary country or region affected.
     pub location: String,
 
     /// ISO country code (alpha-2 or alpha-3 depending on source).
     pub location_code: String,
 
+    /// Every country or region affected, primary first.
+    #[serde(default)]
+    pub locations: Vec<Location>,
+
     /// Short title/summary.
     pub title: String,
 
@@ -203,13 +456,24 @@ pub struct Issue {
     /// URL for more information.
     pub url: Option<String>,
 
-    /// Additional metadata.
+    /// Related documents such as situation reports, newest first.
     #[serde(default)]
-    pub metadata: std::collections::HashMap<String, String>,
+    pub links: Vec<IssueLink>,
+
+    /// Source-specific facts, typed by category (see [`crate::issue_details`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<IssueDetails>,
+
+    /// License and credit line of the source's data (see
+    /// [`crate::attribution`]); for partner issues, whatever the partner
+    /// states.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<Attribution>,
 }
 
 impl Issue {
     /// Create a new issue with basic fields.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         source: IssueSource,
         category: IssueCategory,
@@ -221,11 +485,11 @@ impl Issue {
         timestamp: DateTime<Utc>,
     ) -> Self {
         let id = format!(
-            "{}:{}:{}:{}",
-            source.label().to_lowercase().replace(' ', "_"),
-            category.label().to_lowercase().replace(' ', "_"),
+            "{}:{}:{}:{:016x}",
+            id_component(source.label()),
+            id_component(category.label()),
             location_code.to_lowercase(),
-            timestamp.timestamp()
+            stable_hash(&[title, &timestamp.timestamp().to_string()])
         );
 
         Self {
@@ -233,8 +497,13 @@ impl Issue {
             source,
             category,
             severity,
+            normalized_severity: None,
             location: location.to_string(),
             location_code: location_code.to_string(),
+            locations: vec![Location {
+                name: location.to_string(),
+                code: location_code.to_string(),
+            }],
             title: title.to_string(),
             description: description.to_string(),
             timestamp,
@@ -243,10 +512,26 @@ impl Issue {
             impact_value: None,
             impact_label: None,
             url: None,
-            metadata: std::collections::HashMap::new(),
+            links: Vec::new(),
+            details: None,
+            attribution: attribution::for_source(source),
         }
     }
 
+    /// Identify the issue by the upstream event it was built from.
+    ///
+    /// `upstream_id` must identify the event within its source (a GLIDE
+    /// number, an anomaly UUID, a place code, ...); it replaces the fallback
+    /// hash so the ID survives changes to the title or start time.
+    pub fn with_upstream_id(mut self, upstream_id: &str) -> Self {
+        self.id = format!(
+            "{}:{}",
+            id_component(self.source.label()),
+            id_component(upstream_id)
+        );
+        self
+    }
+
     /// Set the end timestamp and mark as not ongoing.
     pub fn with_end(mut self, end: DateTime<Utc>) -> Self {
         self.end_timestamp = Some(end);
@@ -267,11 +552,95 @@ impl Issue {
         self
     }
 
-    /// Add metadata.
-    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
-        self.metadata.insert(key.to_string(), value.to_string());
+    /// Attach a related document; a URL already linked is ignored.
+    pub fn with_link(mut self, title: &str, url: &str, published: Option<DateTime<Utc>>) -> Self {
+        self.push_link(IssueLink {
+            title: title.to_string(),
+            url: url.to_string(),
+            published,
+        });
+        self
+    }
+
+    fn push_link(&mut self, link: IssueLink) {
+        if !self.links.iter().any(|l| l.url == link.url) {
+            self.links.push(link);
+            self.links.sort_by_key(|l| std::cmp::Reverse(l.published));
+        }
+    }
+
+    /// Set the source-specific details.
+    pub fn with_details(mut self, details: impl Into<IssueDetails>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    /// Add another affected location; locations already listed are ignored.
+    pub fn with_location(mut self, name: &str, code: &str) -> Self {
+        let listed = self.locations.iter().any(|l| {
+            if code.is_empty() || l.code.is_empty() {
+                l.name.eq_ignore_ascii_case(name)
+            } else {
+                l.code.eq_ignore_ascii_case(code)
+            }
+        });
+        if !listed {
+            self.locations.push(Location {
+                name: name.to_string(),
+                code: code.to_string(),
+            });
+        }
         self
     }
+
+    /// Names of every affected location.
+    ///
+    /// Issues persisted before `locations` existed fall back to `location`.
+    pub(crate) fn location_names(&self) -> impl Iterator<Item = &str> {
+        let primary = self.locations.is_empty().then_some(self.location.as_str());
+        primary
+            .into_iter()
+            .chain(self.locations.iter().map(|l| l.name.as_str()))
+    }
+
+    /// Check whether this issue affects the given country (code or name
+    /// fragment), in any of its locations.
+    pub fn matches_country(&self, country_code: &str) -> bool {
+        let fragment = country_code.to_lowercase();
+        self.location_code.eq_ignore_ascii_case(country_code)
+            || self
+                .locations
+                .iter()
+                .any(|l| l.code.eq_ignore_ascii_case(country_code))
+            || self
+                .location_names()
+                .any(|name| name.to_lowercase().contains(&fragment))
+    }
+}
+
+/// Lowercase a label for use in an issue ID.
+fn id_component(label: &str) -> String {
+    label.trim().to_lowercase().replace(' ', "_")
+}
+
+/// 64-bit FNV-1a hash of `parts`.
+///
+/// Unlike `DefaultHasher`, the output is fixed across Rust releases, so IDs
+/// built from it stay valid in persisted snapshots.
+fn stable_hash(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (i, part) in parts.iter().enumerate() {
+        if i > 0 {
+            // Separator keeps ["ab", "c"] and ["a", "bc"] apart
+            hash ^= 0xff;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        for byte in part.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
 }
 
 /// Dashboard for aggregating issues from all sources.
@@ -280,9 +649,23 @@ pub struct Dashboard {
     config: Arc<DashboardConfig>,
     ioda: IodaClient,
     cloudflare: CloudflareRadarClient,
+    tor_metrics: TorMetricsClient,
+    wikimedia: WikimediaClient,
     hdx_hapi: HdxHapiClient,
+    acaps: Option<AcapsClient>,
     reliefweb: ReliefWebClient,
     acled: Option<AcledClient>,
+    iom_dtm: Option<IomDtmClient>,
+    emdat: Option<Arc<EmdatBaseline>>,
+    calibration: Arc<Calibration>,
+    tension: Arc<TensionWeights>,
+    suppression: Arc<SuppressionRules>,
+    external: Option<Arc<dyn SignalStore>>,
+    events: Option<EventBus>,
+    #[cfg(feature = "chaos")]
+    chaos: Option<crate::chaos::Chaos>,
+    /// Issue IDs from the last full fetch, to tell new issues apart.
+    seen_ids: Arc<Mutex<HashSet<String>>>,
 }
 
 impl Dashboard {
@@ -296,95 +679,427 @@ impl Dashboard {
         Self {
             ioda: IodaClient::new(),
             cloudflare: CloudflareRadarClient::new(config.cloudflare_token.clone()),
+            tor_metrics: TorMetricsClient::new(),
+            wikimedia: WikimediaClient::new(&config.app_identifier),
             hdx_hapi: HdxHapiClient::new(&config.app_identifier),
+            acaps: config.acaps_token.as_deref().map(AcapsClient::new),
             reliefweb: ReliefWebClient::new(&config.app_identifier),
             acled,
+            iom_dtm: config.iom_dtm_key.as_deref().map(IomDtmClient::new),
+            emdat: None,
+            calibration: Arc::new(Calibration::default()),
+            tension: Arc::new(TensionWeights::default()),
+            suppression: Arc::new(SuppressionRules::default()),
+            external: None,
+            events: None,
+            #[cfg(feature = "chaos")]
+            chaos: None,
+            seen_ids: Arc::default(),
             config: Arc::new(config),
         }
     }
 
+    /// Publish new issues and source failures from full fetches on the bus.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Inject `chaos`'s source faults into every fetch (see [`crate::chaos`]).
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: crate::chaos::Chaos) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Normalize issue severities with the given calibration instead of the defaults.
+    pub fn with_calibration(mut self, calibration: Calibration) -> Self {
+        self.calibration = Arc::new(calibration);
+        self
+    }
+
+    /// Score summary tension with the given weights instead of the defaults.
+    pub fn with_tension_weights(mut self, weights: TensionWeights) -> Self {
+        self.tension = Arc::new(weights);
+        self
+    }
+
+    /// Drop issues matching `rules` from every fetch (see
+    /// [`crate::suppression`]).
+    pub fn with_suppression_rules(mut self, rules: SuppressionRules) -> Self {
+        self.suppression = Arc::new(rules);
+        self
+    }
+
+    /// Sources this dashboard fetches, in the order their health is
+    /// reported.
+    pub fn fetched_sources(&self) -> Vec<IssueSource> {
+        IssueSource::ALL
+            .into_iter()
+            .filter(|&source| self.config.fetches(source))
+            .collect()
+    }
+
+    /// Weights used to score summary tension.
+    pub fn tension_weights(&self) -> &TensionWeights {
+        &self.tension
+    }
+
+    /// Summarize `issues` with this dashboard's tension weights.
+    pub fn summarize(&self, issues: &[Issue]) -> DashboardSummary {
+        DashboardSummary::weighted(issues, &self.tension)
+    }
+
+    /// Merge issues posted to `POST /ingest/issue` from `store` (see
+    /// [`crate::external`]).
+    pub fn with_external_issues(mut self, store: Arc<dyn SignalStore>) -> Self {
+        self.external = Some(store);
+        self
+    }
+
+    /// Send every source's requests through `client` (see
+    /// [`crate::data_sources::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.ioda = self.ioda.with_http_client(client.clone());
+        self.cloudflare = self.cloudflare.with_http_client(client.clone());
+        self.tor_metrics = self.tor_metrics.with_http_client(client.clone());
+        self.wikimedia = self.wikimedia.with_http_client(client.clone());
+        self.hdx_hapi = self.hdx_hapi.with_http_client(client.clone());
+        self.reliefweb = self.reliefweb.with_http_client(client.clone());
+        self.acaps = self.acaps.map(|c| c.with_http_client(client.clone()));
+        self.acled = self.acled.map(|c| c.with_http_client(client.clone()));
+        self.iom_dtm = self.iom_dtm.map(|c| c.with_http_client(client));
+        self
+    }
+
+    /// Annotate disaster issues with EM-DAT historical frequencies.
+    pub fn with_emdat_baseline(mut self, baseline: EmdatBaseline) -> Self {
+        self.emdat = Some(Arc::new(baseline));
+        self
+    }
+
     /// Get all issues from all data sources.
     pub async fn get_all_issues(&self) -> anyhow::Result<DashboardResponse> {
-        let mut all_issues = Vec::new();
-        let mut errors = Vec::new();
+        Ok(self.fetch_sources(|_| true, None).await)
+    }
 
-        // Fetch from all sources concurrently
-        let (ioda_result, cloudflare_result, hdx_result, reliefweb_result, acled_result) = tokio::join!(
-            self.fetch_ioda_issues(),
-            self.fetch_cloudflare_issues(),
-            self.fetch_hdx_issues(),
-            self.fetch_reliefweb_issues(),
-            self.fetch_acled_issues(),
-        );
+    /// Fetch every source like [`Self::get_all_issues`], except that
+    /// sources failing now keep their issues from `previous` (e.g., the
+    /// latest snapshot), if they last succeeded within the lookback window.
+    pub async fn get_all_issues_or_stale(
+        &self,
+        previous: Option<&DashboardResponse>,
+    ) -> DashboardResponse {
+        self.fetch_sources(|_| true, previous).await
+    }
 
-        // Collect results
-        match ioda_result {
-            Ok(issues) => all_issues.extend(issues),
-            Err(e) => errors.push(SourceError {
-                source: IssueSource::Ioda,
-                message: e.to_string(),
-            }),
-        }
+    /// Refetch only the `due` sources, keeping every other source's issues,
+    /// errors and health from `previous` (see [`crate::cadence`]), and the
+    /// issues of due sources that fail, if they last succeeded within the
+    /// lookback window.
+    ///
+    /// HDX and ACAPS are fetched together, so either being due refetches
+    /// both.
+    pub async fn refresh_sources(
+        &self,
+        due: &HashSet<IssueSource>,
+        previous: &DashboardResponse,
+    ) -> DashboardResponse {
+        self.fetch_sources(|source| due.contains(&source), Some(previous))
+            .await
+    }
 
-        match cloudflare_result {
-            Ok(issues) => all_issues.extend(issues),
-            Err(e) => errors.push(SourceError {
-                source: IssueSource::CloudflareRadar,
-                message: e.to_string(),
-            }),
-        }
+    /// Fetch the sources `due` selects concurrently, carrying the rest over
+    /// from `previous`.
+    async fn fetch_sources(
+        &self,
+        due: impl Fn(IssueSource) -> bool,
+        previous: Option<&DashboardResponse>,
+    ) -> DashboardResponse {
+        let mut all_issues = Vec::new();
+        let mut errors = Vec::new();
+        let risk_due = due(IssueSource::HdxHapi) || due(IssueSource::Acaps);
+
+        // Fetch from all due sources concurrently
+        let (
+            ioda_result,
+            cloudflare_result,
+            risk_result,
+            reliefweb_result,
+            acled_result,
+            dtm_result,
+            tor_result,
+            wikimedia_result,
+            external_result,
+        ) = tokio::join!(
+            fetch_if(
+                due(IssueSource::Ioda),
+                self.fetch_source(IssueSource::Ioda, self.fetch_ioda_issues())
+            ),
+            fetch_if(
+                due(IssueSource::CloudflareRadar),
+                self.fetch_source(IssueSource::CloudflareRadar, self.fetch_cloudflare_issues())
+            ),
+            async {
+                if risk_due {
+                    Some(self.fetch_risk_issues().await)
+                } else {
+                    None
+                }
+            },
+            fetch_if(
+                due(IssueSource::ReliefWeb),
+                self.fetch_source(IssueSource::ReliefWeb, self.fetch_reliefweb_issues())
+            ),
+            fetch_if(
+                due(IssueSource::Acled),
+                self.fetch_source(IssueSource::Acled, self.fetch_acled_issues())
+            ),
+            fetch_if(
+                due(IssueSource::IomDtm),
+                self.fetch_source(IssueSource::IomDtm, self.fetch_dtm_issues())
+            ),
+            fetch_if(
+                due(IssueSource::TorMetrics),
+                self.fetch_source(IssueSource::TorMetrics, self.fetch_tor_issues())
+            ),
+            fetch_if(
+                due(IssueSource::Wikimedia),
+                self.fetch_source(IssueSource::Wikimedia, self.fetch_wikimedia_issues())
+            ),
+            fetch_if(
+                due(IssueSource::External),
+                self.fetch_source(IssueSource::External, self.fetch_external_issues())
+            ),
+        );
 
-        match hdx_result {
-            Ok(issues) => all_issues.extend(issues),
-            Err(e) => errors.push(SourceError {
-                source: IssueSource::HdxHapi,
-                message: e.to_string(),
-            }),
+        // Collect results, with the parse warnings of each fetched source
+        let mut warnings = HashMap::new();
+        for (source, result) in [
+            (IssueSource::Ioda, ioda_result),
+            (IssueSource::CloudflareRadar, cloudflare_result),
+            (IssueSource::ReliefWeb, reliefweb_result),
+            (IssueSource::Acled, acled_result),
+            (IssueSource::IomDtm, dtm_result),
+            (IssueSource::TorMetrics, tor_result),
+            (IssueSource::Wikimedia, wikimedia_result),
+            (IssueSource::External, external_result),
+        ] {
+            let Some((result, parse_warnings)) = result else {
+                continue;
+            };
+            match result {
+                Ok(issues) => all_issues.extend(issues),
+                Err(e) => errors.push(SourceError {
+                    source,
+                    message: e.to_string(),
+                }),
+            }
+            warnings.insert(source, parse_warnings);
         }
-
-        match reliefweb_result {
-            Ok(issues) => all_issues.extend(issues),
-            Err(e) => errors.push(SourceError {
-                source: IssueSource::ReliefWeb,
-                message: e.to_string(),
-            }),
+        if let Some((risk_issues, risk_errors, [hdx_warnings, acaps_warnings])) = risk_result {
+            all_issues.extend(risk_issues);
+            errors.extend(risk_errors);
+            warnings.insert(IssueSource::HdxHapi, hdx_warnings);
+            warnings.insert(IssueSource::Acaps, acaps_warnings);
         }
 
-        match acled_result {
-            Ok(issues) => all_issues.extend(issues),
-            Err(e) => errors.push(SourceError {
-                source: IssueSource::Acled,
-                message: e.to_string(),
-            }),
+        // A failing source's issues are only carried over from a success
+        // within the lookback window, so they cannot outlive it
+        let now = Utc::now();
+        let cutoff = now - chrono::Duration::hours(i64::from(self.config.lookback_hours));
+        let last_success = |source: IssueSource| {
+            let previous = previous?;
+            let health = previous.health.iter().find(|h| h.source == source)?;
+            health
+                .last_success
+                .or_else(|| health.ok.then_some(previous.timestamp))
+        };
+        let mut health = IssueSource::ALL
+            .into_iter()
+            .filter(|&source| self.config.fetches(source))
+            .filter_map(|source| {
+                let parse_warnings = *warnings.get(&source)?;
+                let ok = !errors.iter().any(|e| e.source == source);
+                let last_success = if ok { Some(now) } else { last_success(source) };
+                Some(SourceHealth {
+                    source,
+                    ok,
+                    parse_warnings,
+                    last_success,
+                    stale: !ok && last_success.is_some_and(|at| at >= cutoff),
+                })
+            })
+            .collect::<Vec<_>>();
+        self.calibration.apply(&mut all_issues);
+        let mut suppressed = self.suppression.apply(&mut all_issues);
+
+        // Sources not fetched this time keep what they last reported; only
+        // fresh failures and warnings are published. Stale sources keep
+        // their last issues too, rather than dropping them until their next
+        // successful fetch.
+        let mut kept_errors = Vec::new();
+        let mut kept_health = Vec::new();
+        if let Some(previous) = previous {
+            let kept = |source: &IssueSource| !warnings.contains_key(source);
+            let stale =
+                |source: &IssueSource| health.iter().any(|h| h.source == *source && h.stale);
+            let fetched: HashSet<String> = all_issues.iter().map(|i| i.id.clone()).collect();
+            all_issues.extend(
+                previous
+                    .issues
+                    .iter()
+                    .filter(|i| kept(&i.source) || (stale(&i.source) && !fetched.contains(&i.id)))
+                    .cloned(),
+            );
+            kept_errors.extend(previous.errors.iter().filter(|e| kept(&e.source)).cloned());
+            kept_health.extend(previous.health.iter().filter(|h| kept(&h.source)).cloned());
+            suppressed.extend(
+                previous
+                    .suppressed
+                    .iter()
+                    .filter(|s| kept(&s.source) || stale(&s.source))
+                    .cloned(),
+            );
         }
+        self.publish_events(&all_issues, &errors, &health);
+        errors.extend(kept_errors);
+        health.extend(kept_health);
 
-        // Sort by severity (highest first) then by timestamp (newest first)
+        // Sort by normalized severity (highest first), then severity level,
+        // then timestamp (newest first)
         all_issues.sort_by(|a, b| {
-            b.severity
-                .cmp(&a.severity)
+            let normalized = |i: &Issue| i.normalized_severity.unwrap_or(0.0);
+            normalized(b)
+                .total_cmp(&normalized(a))
+                .then_with(|| b.severity.cmp(&a.severity))
                 .then_with(|| b.timestamp.cmp(&a.timestamp))
         });
 
         // Compute summary
-        let summary = DashboardSummary::from_issues(&all_issues);
+        let mut summary = self.summarize(&all_issues);
+        summary.suppressed_issues = suppressed.iter().map(|s| s.count).sum();
 
-        Ok(DashboardResponse {
-            timestamp: Utc::now(),
+        DashboardResponse {
+            timestamp: now,
             summary,
             issues: all_issues,
             errors,
-        })
+            health,
+            suppressed,
+        }
+    }
+
+    /// Publish issues absent from the previous fetch, every source failure
+    /// and success, and every source's skipped records.
+    ///
+    /// An issue that disappears and comes back is detected again.
+    fn publish_events(&self, issues: &[Issue], errors: &[SourceError], health: &[SourceHealth]) {
+        let Some(events) = &self.events else {
+            return;
+        };
+
+        let now = Utc::now();
+        for error in errors {
+            events.publish(Event::SourceFailed {
+                source: error.source,
+                message: error.message.clone(),
+                at: now,
+            });
+        }
+        for source in health.iter().filter(|h| h.parse_warnings > 0) {
+            events.publish(Event::SourceParseWarnings {
+                source: source.source,
+                count: source.parse_warnings,
+                at: now,
+            });
+        }
+        for source in health.iter().filter(|h| h.ok) {
+            events.publish(Event::SourceFetched {
+                source: source.source,
+                at: now,
+            });
+        }
+
+        let current: HashSet<String> = issues.iter().map(|i| i.id.clone()).collect();
+        let previous = std::mem::replace(&mut *self.seen_ids.lock().unwrap(), current);
+        for issue in issues.iter().filter(|i| !previous.contains(&i.id)) {
+            events.publish(Event::IssueDetected(Box::new(issue.clone())));
+        }
     }
 
     /// Get issues filtered by source.
     pub async fn get_issues_by_source(&self, source: IssueSource) -> anyhow::Result<Vec<Issue>> {
-        match source {
-            IssueSource::Ioda => self.fetch_ioda_issues().await,
-            IssueSource::CloudflareRadar => self.fetch_cloudflare_issues().await,
-            IssueSource::HdxHapi => self.fetch_hdx_issues().await,
-            IssueSource::Acled => self.fetch_acled_issues().await,
-            IssueSource::ReliefWeb => self.fetch_reliefweb_issues().await,
+        let mut issues = match source {
+            IssueSource::Ioda => self.fetch_source(source, self.fetch_ioda_issues()).await?,
+            IssueSource::CloudflareRadar => {
+                self.fetch_source(source, self.fetch_cloudflare_issues())
+                    .await?
+            }
+            IssueSource::HdxHapi | IssueSource::Acaps => {
+                let (issues, errors, _) = self.fetch_risk_issues().await;
+                if let Some(error) = errors.into_iter().find(|e| e.source == source) {
+                    anyhow::bail!(error.message);
+                }
+                issues.into_iter().filter(|i| i.source == source).collect()
+            }
+            IssueSource::Acled => self.fetch_source(source, self.fetch_acled_issues()).await?,
+            IssueSource::ReliefWeb => {
+                self.fetch_source(source, self.fetch_reliefweb_issues())
+                    .await?
+            }
+            IssueSource::IomDtm => self.fetch_source(source, self.fetch_dtm_issues()).await?,
+            IssueSource::TorMetrics => self.fetch_source(source, self.fetch_tor_issues()).await?,
+            IssueSource::Wikimedia => {
+                self.fetch_source(source, self.fetch_wikimedia_issues())
+                    .await?
+            }
+            IssueSource::External => {
+                self.fetch_source(source, self.fetch_external_issues())
+                    .await?
+            }
+        };
+        self.calibration.apply(&mut issues);
+        for suppressed in self.suppression.apply(&mut issues) {
+            info!(
+                rule = %suppressed.rule,
+                count = suppressed.count,
+                "Issues suppressed"
+            );
+        }
+        Ok(issues)
+    }
+
+    /// The given country code plus, for a monitored country, its other ISO
+    /// code, so alpha-2 and alpha-3 sources are matched alike.
+    pub fn country_codes(&self, country_code: &str) -> Vec<String> {
+        let mut codes = vec![country_code.to_uppercase()];
+        if let Some(country) = self.config.monitored_countries.iter().find(|c| {
+            c.alpha2.eq_ignore_ascii_case(country_code)
+                || c.alpha3.eq_ignore_ascii_case(country_code)
+        }) {
+            for code in [&country.alpha2, &country.alpha3] {
+                if !codes.iter().any(|c| c.eq_ignore_ascii_case(code)) {
+                    codes.push(code.to_uppercase());
+                }
+            }
         }
+        codes
+    }
+
+    /// The code a country is keyed by when issues from alpha-2 and alpha-3
+    /// sources are combined: the alpha-3 code for a monitored country,
+    /// otherwise the given code, uppercased.
+    pub fn canonical_country_code(&self, country_code: &str) -> String {
+        self.config
+            .monitored_countries
+            .iter()
+            .find(|c| {
+                c.alpha2.eq_ignore_ascii_case(country_code)
+                    || c.alpha3.eq_ignore_ascii_case(country_code)
+            })
+            .map(|c| c.alpha3.to_uppercase())
+            .unwrap_or_else(|| country_code.trim().to_uppercase())
     }
 
     /// Get issues filtered by country code.
@@ -393,17 +1108,49 @@ impl Dashboard {
         Ok(all
             .issues
             .into_iter()
-            .filter(|i| {
-                i.location_code.eq_ignore_ascii_case(country_code)
-                    || i.location.to_lowercase().contains(&country_code.to_lowercase())
-            })
+            .filter(|i| i.matches_country(country_code))
             .collect())
     }
 
+    /// Fetch issues posted by partners' detectors and refreshed within the
+    /// lookback window.
+    async fn fetch_external_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        let Some(store) = &self.external else {
+            return Ok(Vec::new());
+        };
+        if !self.config.fetches(IssueSource::External) {
+            return Ok(Vec::new());
+        }
+        let since = Utc::now() - chrono::Duration::hours(i64::from(self.config.lookback_hours));
+        store.get_external_issues(since).await
+    }
+
+    /// Run a fetch from `source`, after any fault injected into it (see
+    /// [`crate::chaos`]).
+    async fn fetch_source<T>(
+        &self,
+        source: IssueSource,
+        fetch: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.chaos {
+            chaos.source(source).await?;
+        }
+        #[cfg(not(feature = "chaos"))]
+        let _ = source;
+        fetch.await
+    }
+
     /// Fetch issues from IODA.
     async fn fetch_ioda_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        if !self.config.fetches(IssueSource::Ioda) {
+            return Ok(Vec::new());
+        }
         let mut issues = Vec::new();
-        let alerts = self.ioda.get_recent_alerts(self.config.lookback_hours).await?;
+        let alerts = self
+            .ioda
+            .get_recent_alerts(self.config.lookback_hours)
+            .await?;
 
         for alert in alerts.data {
             let severity = match alert.level.as_str() {
@@ -428,9 +1175,22 @@ impl Dashboard {
                 ),
                 timestamp,
             )
+            // Alerts carry no ID of their own; entity, signal and time identify one
+            .with_upstream_id(&format!(
+                "{}:{}:{}:{}",
+                alert.entity_type, alert.entity_code, alert.datasource, alert.time
+            ))
             .with_impact(drop_pct, &format!("{:.1}% drop from baseline", drop_pct))
-            .with_metadata("datasource", &alert.datasource)
-            .with_metadata("condition", &alert.condition);
+            .with_url(&ioda::dashboard_url(
+                &alert.entity_type,
+                &alert.entity_code,
+                alert.time,
+            ))
+            .with_details(OutageDetails {
+                datasource: Some(alert.datasource.clone()),
+                condition: Some(alert.condition.clone()),
+                transport: None,
+            });
 
             issues.push(issue);
         }
@@ -440,6 +1200,9 @@ impl Dashboard {
 
     /// Fetch issues from Cloudflare Radar.
     async fn fetch_cloudflare_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        if !self.config.fetches(IssueSource::CloudflareRadar) {
+            return Ok(Vec::new());
+        }
         let mut issues = Vec::new();
         let anomalies = self.cloudflare.get_traffic_anomalies(None, "7d").await?;
 
@@ -463,12 +1226,24 @@ impl Dashboard {
                     &anomaly.description,
                     timestamp,
                 )
-                .with_metadata("anomaly_type", &anomaly.anomaly_type)
-                .with_metadata("verified", &anomaly.verified.to_string());
-
+                .with_details(TrafficAnomalyDetails {
+                    anomaly_type: Some(anomaly.anomaly_type.clone()),
+                    verified: Some(anomaly.verified),
+                    project: None,
+                });
+
+                if !anomaly.id.is_empty() {
+                    issue = issue.with_upstream_id(&anomaly.id);
+                }
                 if let Some(end) = anomaly.end_datetime() {
                     issue = issue.with_end(end);
                 }
+                let end = issue.end_timestamp.unwrap_or_else(Utc::now);
+                issue = issue.with_url(&cloudflare::radar_url(
+                    &anomaly.location,
+                    timestamp.date_naive(),
+                    end.date_naive(),
+                ));
 
                 issues.push(issue);
             }
@@ -477,85 +1252,160 @@ impl Dashboard {
         Ok(issues)
     }
 
-    /// Fetch issues from HDX HAPI.
-    async fn fetch_hdx_issues(&self) -> anyhow::Result<Vec<Issue>> {
-        let mut issues = Vec::new();
+    /// Fetch humanitarian-risk issues from HDX HAPI and ACAPS.
+    ///
+    /// Both assessments are merged per country (see [`humanitarian_risk_issues`]);
+    /// a failing source is reported and the other still contributes. Jumps in
+    /// HDX needs and displacement figures are raised alongside. Also returns
+    /// the parse warnings of HDX HAPI and ACAPS, in that order.
+    async fn fetch_risk_issues(&self) -> (Vec<Issue>, Vec<SourceError>, [u64; 2]) {
+        let (
+            (hdx_result, hdx_warnings),
+            (acaps_result, acaps_warnings),
+            (change_result, change_warnings),
+        ) = tokio::join!(
+            count_parse_warnings(self.fetch_source(IssueSource::HdxHapi, self.fetch_hdx_risk())),
+            count_parse_warnings(
+                self.fetch_source(IssueSource::Acaps, self.fetch_acaps_severity())
+            ),
+            count_parse_warnings(
+                self.fetch_source(IssueSource::HdxHapi, self.fetch_hdx_change_issues())
+            ),
+        );
+        let mut errors = Vec::new();
 
-        // Check national risk for all available countries
-        let risk_response = self.hdx_hapi.get_national_risk(None).await?;
+        let hdx = match hdx_result {
+            Ok(risk) => risk,
+            Err(e) => {
+                errors.push(SourceError {
+                    source: IssueSource::HdxHapi,
+                    message: e.to_string(),
+                });
+                Vec::new()
+            }
+        };
 
-        for risk in risk_response.data {
-            if risk.is_very_high_risk() {
-                let timestamp = risk
-                    .reference_period_start
-                    .as_ref()
-                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(Utc::now);
+        let acaps = match acaps_result {
+            Ok(crises) => crises,
+            Err(e) => {
+                errors.push(SourceError {
+                    source: IssueSource::Acaps,
+                    message: e.to_string(),
+                });
+                Vec::new()
+            }
+        };
 
-                let issue = Issue::new(
-                    IssueSource::HdxHapi,
-                    IssueCategory::HumanitarianEmergency,
-                    IssueSeverity::Emergency,
-                    &risk.location_name,
-                    &risk.location_code,
-                    &format!("Very high humanitarian risk in {}", risk.location_name),
-                    &format!(
-                        "National risk score: {:.1}/10. Hazard exposure: {:.1}, Vulnerability: {:.1}, Coping capacity: {:.1}",
-                        risk.overall_risk.unwrap_or(0.0),
-                        risk.hazard_exposure.unwrap_or(0.0),
-                        risk.vulnerability.unwrap_or(0.0),
-                        risk.coping_capacity.unwrap_or(0.0)
-                    ),
-                    timestamp,
-                )
-                .with_impact(
-                    risk.overall_risk.unwrap_or(0.0),
-                    &format!("{:.1}/10 risk score", risk.overall_risk.unwrap_or(0.0)),
-                );
+        let mut issues = humanitarian_risk_issues(&hdx, &acaps, &self.config);
+        match change_result {
+            Ok(changes) => issues.extend(changes),
+            Err(e) => errors.push(SourceError {
+                source: IssueSource::HdxHapi,
+                message: e.to_string(),
+            }),
+        }
 
-                issues.push(issue);
-            } else if risk.is_high_risk() {
-                let timestamp = risk
-                    .reference_period_start
-                    .as_ref()
-                    .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&Utc))
-                    .unwrap_or_else(Utc::now);
+        (
+            issues,
+            errors,
+            [hdx_warnings + change_warnings, acaps_warnings],
+        )
+    }
 
-                let issue = Issue::new(
-                    IssueSource::HdxHapi,
-                    IssueCategory::HumanitarianEmergency,
-                    IssueSeverity::Critical,
-                    &risk.location_name,
-                    &risk.location_code,
-                    &format!("High humanitarian risk in {}", risk.location_name),
-                    &format!(
-                        "National risk score: {:.1}/10. Hazard exposure: {:.1}, Vulnerability: {:.1}, Coping capacity: {:.1}",
-                        risk.overall_risk.unwrap_or(0.0),
-                        risk.hazard_exposure.unwrap_or(0.0),
-                        risk.vulnerability.unwrap_or(0.0),
-                        risk.coping_capacity.unwrap_or(0.0)
-                    ),
-                    timestamp,
-                )
-                .with_impact(
-                    risk.overall_risk.unwrap_or(0.0),
-                    &format!("{:.1}/10 risk score", risk.overall_risk.unwrap_or(0.0)),
-                );
+    /// Fetch HDX HAPI national risk scores.
+    async fn fetch_hdx_risk(&self) -> anyhow::Result<Vec<HdxNationalRisk>> {
+        if !self.config.fetches(IssueSource::HdxHapi) {
+            return Ok(Vec::new());
+        }
+        Ok(self.hdx_hapi.get_national_risk(None).await?.data.into_vec())
+    }
 
-                issues.push(issue);
+    /// Compare the latest HDX people-in-need and IDP figures of each
+    /// monitored country with the reporting period before, and staple food
+    /// prices with the month before.
+    ///
+    /// Risk scores lag behind deteriorations that these counts already show,
+    /// and price shocks precede IPC phase changes.
+    ///
+    /// A figure that cannot be fetched for one country is logged and
+    /// skipped; the source only fails if every request did.
+    async fn fetch_hdx_change_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        if !self.config.fetches(IssueSource::HdxHapi) {
+            return Ok(Vec::new());
+        }
+        let mut issues = Vec::new();
+        let threshold = self.config.hdx_change_threshold_percent;
+        let mut failures = Vec::new();
+
+        for country in &self.config.monitored_countries {
+            let (needs, idps, prices) = tokio::join!(
+                self.hdx_hapi.get_humanitarian_needs(&country.alpha3),
+                self.hdx_hapi.get_idps(&country.alpha3),
+                self.hdx_hapi.get_food_prices(&country.alpha3),
+            );
+
+            if let Some(needs) = hdx_figure(needs, "people in need", country, &mut failures)
+                && let Some(change) = latest_change(&needs.people_in_need_by_period())
+            {
+                issues.extend(hdx_change_issue(
+                    "people in need",
+                    IssueCategory::HumanitarianEmergency,
+                    country,
+                    &change,
+                    threshold,
+                ));
+            }
+            if let Some(idps) = hdx_figure(idps, "IDPs", country, &mut failures)
+                && let Some(change) = latest_change(&idps.idps_by_period())
+            {
+                issues.extend(hdx_change_issue(
+                    "IDPs",
+                    IssueCategory::Displacement,
+                    country,
+                    &change,
+                    threshold,
+                ));
+            }
+            if let Some(prices) = hdx_figure(prices, "food prices", country, &mut failures) {
+                issues.extend(food_price_issue(
+                    country,
+                    &prices.staple_price_changes(),
+                    self.config.food_price_spike_percent,
+                ));
             }
         }
 
+        let requests = 3 * self.config.monitored_countries.len();
+        if failures.len() == requests
+            && let Some(error) = failures.pop()
+        {
+            return Err(error.context("every HDX HAPI figure request failed"));
+        }
+        if !failures.is_empty() {
+            warn!(
+                failed = failures.len(),
+                requests, "Some HDX HAPI figures could not be fetched"
+            );
+        }
+
         Ok(issues)
     }
 
+    /// Fetch the latest ACAPS INFORM Severity Index.
+    async fn fetch_acaps_severity(&self) -> anyhow::Result<Vec<AcapsCrisisSeverity>> {
+        match &self.acaps {
+            Some(client) if self.config.fetches(IssueSource::Acaps) => {
+                client.get_latest_severity_index().await
+            }
+            _ => Ok(Vec::new()), // ACAPS not configured or not fetched
+        }
+    }
+
     /// Fetch issues from ACLED.
     async fn fetch_acled_issues(&self) -> anyhow::Result<Vec<Issue>> {
         let acled = match &self.acled {
-            Some(client) => client,
-            None => return Ok(Vec::new()), // ACLED not configured
+            Some(client) if self.config.fetches(IssueSource::Acled) => client,
+            _ => return Ok(Vec::new()), // ACLED not configured or not fetched
         };
 
         let mut issues = Vec::new();
@@ -566,44 +1416,202 @@ impl Dashboard {
                 .get_events_with_fatalities(&country.name, 1, Some(100))
                 .await?;
 
-            // Group by event type and summarize
-            let total_fatalities = response.total_fatalities();
-            let event_count = response.count;
+            let timestamp = response
+                .most_recent()
+                .and_then(|e| e.datetime())
+                .unwrap_or_else(Utc::now);
 
-            if total_fatalities > 0 {
-                let severity = if total_fatalities >= 100 {
-                    IssueSeverity::Emergency
-                } else if total_fatalities >= 50 {
-                    IssueSeverity::Critical
-                } else if total_fatalities >= 10 {
-                    IssueSeverity::Warning
-                } else {
-                    IssueSeverity::Info
+            issues.extend(conflict_issue(
+                &country.name,
+                &country.alpha3,
+                response.count,
+                response.total_fatalities(),
+                timestamp,
+                self.config.lookback_hours,
+            ));
+        }
+
+        // One query per watched region covers all of its countries
+        for &region in &self.config.acled_regions {
+            let response = acled
+                .get_events_by_region(region.number(), Some(ACLED_REGION_LIMIT))
+                .await?;
+            issues.extend(acled_region_issues(
+                region,
+                &response,
+                &self.config.monitored_countries,
+                self.config.lookback_hours,
+            ));
+        }
+
+        Ok(issues)
+    }
+
+    /// Fetch issues from IOM DTM.
+    ///
+    /// Raises one issue per admin 1 area whose IDP count grew across the
+    /// reporting rounds of the last [`DTM_LOOKBACK_DAYS`] days.
+    async fn fetch_dtm_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        let dtm = match &self.iom_dtm {
+            Some(client) if self.config.fetches(IssueSource::IomDtm) => client,
+            _ => return Ok(Vec::new()), // DTM not configured or not fetched
+        };
+
+        let mut issues = Vec::new();
+        let response = dtm
+            .get_recent_admin1_displacement(None, DTM_LOOKBACK_DAYS)
+            .await?;
+
+        for trend in response.area_trends() {
+            let increase = trend.increase();
+            let severity = if increase >= 100_000 {
+                IssueSeverity::Emergency
+            } else if increase >= 25_000 {
+                IssueSeverity::Critical
+            } else if increase >= 5_000 {
+                IssueSeverity::Warning
+            } else {
+                continue;
+            };
+
+            let timestamp = trend
+                .reporting_date
+                .and_hms_opt(0, 0, 0)
+                .map(|dt| dt.and_utc())
+                .unwrap_or_else(Utc::now);
+            let location = format!("{}, {}", trend.admin1, trend.country);
+
+            let issue = Issue::new(
+                IssueSource::IomDtm,
+                IssueCategory::Displacement,
+                severity,
+                &location,
+                &trend.country_code,
+                &format!("New displacement in {}", location),
+                &format!(
+                    "{} people newly displaced in the last {} days ({} IDPs now present). Reason: {}",
+                    increase,
+                    DTM_LOOKBACK_DAYS,
+                    trend.idps,
+                    trend.displacement_reason
+                ),
+                timestamp,
+            )
+            .with_upstream_id(&trend.admin1_pcode)
+            .with_impact(increase as f64, &format!("{} newly displaced", increase))
+            .with_url(&iom_dtm::country_url(&trend.country))
+            .with_details(DisplacementDetails {
+                admin1_pcode: Some(trend.admin1_pcode.clone()),
+                idps: Some(trend.idps),
+                figure_change: None,
+            });
+
+            issues.push(issue);
+        }
+
+        Ok(issues)
+    }
+
+    /// Fetch issues from Tor Metrics.
+    ///
+    /// Spikes in relay or bridge users are raised as likely censorship;
+    /// drops as likely loss of connectivity.
+    async fn fetch_tor_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        if !self.config.fetches(IssueSource::TorMetrics) {
+            return Ok(Vec::new());
+        }
+        let mut issues = Vec::new();
+
+        for transport in [TorTransport::Relay, TorTransport::Bridge] {
+            let estimates = self
+                .tor_metrics
+                .get_recent_user_estimates(transport, TOR_LOOKBACK_DAYS)
+                .await?;
+
+            for anomaly in detect_anomalies(&estimates) {
+                let ratio = anomaly.ratio();
+                let code = anomaly.country.to_uppercase();
+                let (category, severity, title, change) = match anomaly.kind {
+                    TorAnomalyKind::Spike => (
+                        IssueCategory::Censorship,
+                        if ratio >= 4.0 {
+                            IssueSeverity::Critical
+                        } else {
+                            IssueSeverity::Warning
+                        },
+                        format!(
+                            "Tor {} usage spike in {} (possible censorship)",
+                            transport.name(),
+                            code
+                        ),
+                        format!("{:.1}x baseline", ratio),
+                    ),
+                    TorAnomalyKind::Drop => (
+                        IssueCategory::InternetOutage,
+                        if ratio <= 0.1 {
+                            IssueSeverity::Critical
+                        } else {
+                            IssueSeverity::Warning
+                        },
+                        format!(
+                            "Tor {} usage drop in {} (possible outage)",
+                            transport.name(),
+                            code
+                        ),
+                        format!("{:.0}% drop from baseline", (1.0 - ratio) * 100.0),
+                    ),
                 };
 
-                let most_recent = response.most_recent();
-                let timestamp = most_recent
-                    .and_then(|e| e.datetime())
+                let timestamp = anomaly
+                    .date
+                    .and_hms_opt(0, 0, 0)
+                    .map(|dt| dt.and_utc())
                     .unwrap_or_else(Utc::now);
 
                 let issue = Issue::new(
-                    IssueSource::Acled,
-                    IssueCategory::Conflict,
+                    IssueSource::TorMetrics,
+                    category,
                     severity,
-                    &country.name,
-                    &country.alpha3,
-                    &format!("Conflict activity in {}", country.name),
+                    &code,
+                    &code,
+                    &title,
                     &format!(
-                        "{} conflict events with {} fatalities in the last {} hours",
-                        event_count, total_fatalities, self.config.lookback_hours
+                        "An estimated {:.0} Tor {} users on {}, against a {}-day average of {:.0}",
+                        anomaly.users,
+                        transport.name(),
+                        anomaly.date,
+                        TOR_BASELINE_DAYS,
+                        anomaly.baseline
                     ),
                     timestamp,
                 )
-                .with_impact(
-                    total_fatalities as f64,
-                    &format!("{} fatalities", total_fatalities),
-                )
-                .with_metadata("event_count", &event_count.to_string());
+                // The same anomaly keeps its ID while it persists across days
+                .with_upstream_id(&format!(
+                    "{}:{}:{}",
+                    transport.name(),
+                    anomaly.country,
+                    match anomaly.kind {
+                        TorAnomalyKind::Spike => "spike",
+                        TorAnomalyKind::Drop => "drop",
+                    }
+                ))
+                .with_impact(ratio, &change)
+                .with_url(&tor_metrics::userstats_url(
+                    transport,
+                    &anomaly.country,
+                    anomaly.date - chrono::Duration::days(TOR_LOOKBACK_DAYS.into()),
+                    anomaly.date,
+                ));
+                let transport = Some(transport.name().to_string());
+                let issue = match category {
+                    IssueCategory::Censorship => {
+                        issue.with_details(CensorshipDetails { transport })
+                    }
+                    _ => issue.with_details(OutageDetails {
+                        transport,
+                        ..OutageDetails::default()
+                    }),
+                };
 
                 issues.push(issue);
             }
@@ -612,8 +1620,81 @@ impl Dashboard {
         Ok(issues)
     }
 
+    /// Fetch issues from Wikimedia pageviews.
+    ///
+    /// Raises an issue for each configured project whose latest daily views
+    /// collapsed against the same weekday in previous weeks.
+    async fn fetch_wikimedia_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        if !self.config.fetches(IssueSource::Wikimedia) {
+            return Ok(Vec::new());
+        }
+        let mut issues = Vec::new();
+
+        for watched in &self.config.wikimedia_projects {
+            let series = self
+                .wikimedia
+                .get_recent_project_daily_views(&watched.project, WIKIMEDIA_LOOKBACK_DAYS)
+                .await?;
+
+            let Some(collapse) = detect_collapse(&series) else {
+                continue;
+            };
+
+            let ratio = collapse.ratio();
+            let severity = if ratio <= 0.1 {
+                IssueSeverity::Critical
+            } else {
+                IssueSeverity::Warning
+            };
+            let drop_pct = (1.0 - ratio) * 100.0;
+            let timestamp = collapse
+                .date
+                .and_hms_opt(0, 0, 0)
+                .map(|dt| dt.and_utc())
+                .unwrap_or_else(Utc::now);
+
+            let issue = Issue::new(
+                IssueSource::Wikimedia,
+                IssueCategory::TrafficAnomaly,
+                severity,
+                &watched.country_code,
+                &watched.country_code,
+                &format!(
+                    "{} readership collapsed ({})",
+                    watched.project, watched.country_code
+                ),
+                &format!(
+                    "{} views of {} on {}, against {:.0} expected from the same weekday in previous weeks",
+                    collapse.views, watched.project, collapse.date, collapse.expected
+                ),
+                timestamp,
+            )
+            .with_upstream_id(&watched.project)
+            .with_impact(drop_pct, &format!("{:.1}% below weekly pattern", drop_pct))
+            .with_url(&wikimedia::siteviews_url(
+                &watched.project,
+                collapse.date - chrono::Duration::days(WIKIMEDIA_LOOKBACK_DAYS.into()),
+                collapse.date,
+            ))
+            .with_details(TrafficAnomalyDetails {
+                project: Some(watched.project.clone()),
+                ..TrafficAnomalyDetails::default()
+            });
+
+            issues.push(issue);
+        }
+
+        Ok(issues)
+    }
+
     /// Fetch issues from ReliefWeb.
+    ///
+    /// Recent situation reports on monitored countries are attached to the
+    /// disasters they cover (see [`attach_situation_reports`]).
     async fn fetch_reliefweb_issues(&self) -> anyhow::Result<Vec<Issue>> {
+        if !self.config.fetches(IssueSource::ReliefWeb) {
+            return Ok(Vec::new());
+        }
         let mut issues = Vec::new();
 
         // Get ongoing disasters
@@ -643,7 +1724,7 @@ impl Dashboard {
                 .map(|c| c.iso3.as_str())
                 .unwrap_or("");
 
-            let issue = Issue::new(
+            let mut issue = Issue::new(
                 IssueSource::ReliefWeb,
                 IssueCategory::Disaster,
                 severity,
@@ -653,16 +1734,591 @@ impl Dashboard {
                 &disaster.description,
                 timestamp,
             )
+            .with_upstream_id(if disaster.glide.is_empty() {
+                &item.id
+            } else {
+                &disaster.glide
+            })
             .with_url(&disaster.url)
-            .with_metadata("disaster_type", disaster.type_name().unwrap_or("Unknown"))
-            .with_metadata("glide", &disaster.glide)
-            .with_metadata("status", &disaster.status);
+            .with_details(DisasterDetails {
+                disaster_type: disaster.type_name().unwrap_or("Unknown").to_string(),
+                reliefweb_id: item.id.clone(),
+                glide: Some(disaster.glide.clone()).filter(|g| !g.is_empty()),
+                status: disaster.status.clone(),
+                historical_context: self.emdat.as_ref().and_then(|baseline| {
+                    disaster
+                        .type_name()
+                        .and_then(|t| baseline.annotate(country_code, t, timestamp))
+                }),
+            });
+
+            // Disasters often span several countries; list them all
+            for country in &disaster.country {
+                issue = issue.with_location(&country.name, &country.iso3);
+            }
 
             issues.push(issue);
         }
 
+        // Situation reports are supplementary; alerts are still worth serving without them
+        match self.fetch_situation_reports().await {
+            Ok(reports) => attach_situation_reports(&mut issues, &reports),
+            Err(e) => warn!(error = %e, "Failed to fetch ReliefWeb situation reports"),
+        }
+
         Ok(issues)
     }
+
+    /// Fetch recent situation reports on monitored countries.
+    async fn fetch_situation_reports(
+        &self,
+    ) -> anyhow::Result<Vec<ReliefWebItem<ReliefWebReportFields>>> {
+        let countries: Vec<&str> = self
+            .config
+            .monitored_countries
+            .iter()
+            .map(|c| c.alpha3.as_str())
+            .collect();
+        if countries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = self
+            .reliefweb
+            .get_situation_reports(&countries, SITREP_LOOKBACK_DAYS, Some(SITREP_LIMIT))
+            .await?;
+        Ok(response.data.into_vec())
+    }
+}
+
+/// One of a country's HDX figures, or `None` after logging its failure and
+/// adding it to `failures`.
+fn hdx_figure<T>(
+    result: anyhow::Result<T>,
+    figure: &str,
+    country: &MonitoredCountry,
+    failures: &mut Vec<anyhow::Error>,
+) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!(country = %country.alpha3, figure, error = %e, "Failed to fetch HDX HAPI figure");
+            failures.push(e);
+            None
+        }
+    }
+}
+
+/// Raise an issue when an HDX figure rose by at least `threshold` percent;
+/// twice the threshold is critical.
+fn hdx_change_issue(
+    figure: &str,
+    category: IssueCategory,
+    country: &MonitoredCountry,
+    change: &HdxFigureChange,
+    threshold: f64,
+) -> Option<Issue> {
+    let percent = change.percent_change();
+    let severity = if percent >= threshold * 2.0 {
+        IssueSeverity::Critical
+    } else if percent >= threshold {
+        IssueSeverity::Warning
+    } else {
+        return None;
+    };
+
+    let timestamp = DateTime::parse_from_rfc3339(&change.latest_period)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(&change.latest_period, "%Y-%m-%dT%H:%M:%S")
+                .ok()
+                .map(|dt| dt.and_utc())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(&change.latest_period, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc())
+        })
+        .unwrap_or_else(Utc::now);
+    let period = timestamp.format("%Y-%m-%d").to_string();
+
+    let issue = Issue::new(
+        IssueSource::HdxHapi,
+        category,
+        severity,
+        &country.name,
+        &country.alpha3,
+        &format!(
+            "Sharp rise in {} in {} (+{:.0}%)",
+            figure, country.name, percent
+        ),
+        &format!(
+            "{} {} in the period from {}, up from {} in the period before",
+            change.latest, figure, period, change.previous
+        ),
+        timestamp,
+    )
+    .with_upstream_id(&format!("{}:{}:{}", figure, country.alpha3, period))
+    .with_url(&hdx_hapi::dataset_search_url(&country.alpha3, figure));
+
+    let figure_change = Some(FigureChange {
+        previous: change.previous,
+        latest: change.latest,
+        percent_change: percent,
+    });
+    Some(match category {
+        IssueCategory::Displacement => issue.with_details(DisplacementDetails {
+            figure_change,
+            ..DisplacementDetails::default()
+        }),
+        _ => issue.with_details(HumanitarianDetails {
+            figure_change,
+            ..HumanitarianDetails::default()
+        }),
+    })
+}
+
+/// Raise one food security issue for the staples whose median price rose by
+/// at least `threshold` percent in a month; twice the threshold is critical.
+fn food_price_issue(
+    country: &MonitoredCountry,
+    changes: &[HdxPriceChange],
+    threshold: f64,
+) -> Option<Issue> {
+    let spikes: Vec<&HdxPriceChange> = changes
+        .iter()
+        .filter(|c| c.median_percent >= threshold)
+        .collect();
+    let worst = spikes
+        .iter()
+        .max_by(|a, b| a.median_percent.total_cmp(&b.median_percent))?;
+    let severity = if worst.median_percent >= threshold * 2.0 {
+        IssueSeverity::Critical
+    } else {
+        IssueSeverity::Warning
+    };
+
+    let month = spikes.iter().map(|c| c.month).max()?;
+    let timestamp = month.and_hms_opt(0, 0, 0)?.and_utc();
+    let details: Vec<String> = spikes
+        .iter()
+        .map(|c| {
+            format!(
+                "{} +{:.0}% ({} markets)",
+                c.commodity, c.median_percent, c.markets
+            )
+        })
+        .collect();
+
+    let issue = Issue::new(
+        IssueSource::HdxHapi,
+        IssueCategory::FoodSecurity,
+        severity,
+        &country.name,
+        &country.alpha3,
+        &format!("Staple food price shock in {}", country.name),
+        &format!(
+            "Median month-over-month price rises in {}: {}",
+            month.format("%B %Y"),
+            details.join(", ")
+        ),
+        timestamp,
+    )
+    .with_upstream_id(&format!(
+        "food_prices:{}:{}",
+        country.alpha3,
+        month.format("%Y-%m")
+    ))
+    .with_url(&hdx_hapi::dataset_search_url(
+        &country.alpha3,
+        "food prices",
+    ))
+    .with_details(FoodSecurityDetails {
+        commodity: worst.commodity.clone(),
+        percent_change: worst.median_percent,
+    });
+
+    Some(issue)
+}
+
+/// Link situation reports to the ReliefWeb disasters they cover.
+///
+/// A report naming a disaster already among `issues` becomes a link on that
+/// issue. Any other report is raised as an info-level issue of its own, so
+/// responders still see it.
+fn attach_situation_reports(
+    issues: &mut Vec<Issue>,
+    reports: &[ReliefWebItem<ReliefWebReportFields>],
+) {
+    let mut unlinked = Vec::new();
+
+    for item in reports {
+        let report = &item.fields;
+        let published = report.date.as_ref().and_then(|d| d.created_datetime());
+        let mut linked = false;
+
+        for issue in issues.iter_mut() {
+            let covers = issue.source == IssueSource::ReliefWeb
+                && match &issue.details {
+                    Some(IssueDetails::Disaster(details)) => report
+                        .disaster
+                        .iter()
+                        .any(|d| details.reliefweb_id == d.id.to_string()),
+                    _ => false,
+                };
+            if covers {
+                issue.push_link(IssueLink {
+                    title: report.title.clone(),
+                    url: report.url.clone(),
+                    published,
+                });
+                linked = true;
+            }
+        }
+
+        if !linked {
+            let (name, code) = report
+                .primary_country
+                .as_ref()
+                .map(|c| (c.name.as_str(), c.iso3.as_str()))
+                .unwrap_or(("Unknown", ""));
+            let mut issue = Issue::new(
+                IssueSource::ReliefWeb,
+                IssueCategory::HumanitarianEmergency,
+                IssueSeverity::Info,
+                name,
+                code,
+                &format!("Situation report: {}", report.title),
+                &format!(
+                    "Published by {}",
+                    report.source_name().unwrap_or("an unknown source")
+                ),
+                published.unwrap_or_else(Utc::now),
+            )
+            .with_upstream_id(&format!("report:{}", item.id))
+            .with_url(&report.url)
+            .with_details(HumanitarianDetails {
+                report_format: Some("Situation Report".to_string()),
+                ..HumanitarianDetails::default()
+            });
+            for country in &report.country {
+                issue = issue.with_location(&country.name, &country.iso3);
+            }
+            unlinked.push(issue);
+        }
+    }
+
+    issues.extend(unlinked);
+}
+
+/// Build a conflict issue for one country, or `None` without fatalities.
+fn conflict_issue(
+    name: &str,
+    code: &str,
+    event_count: i64,
+    fatalities: i64,
+    timestamp: DateTime<Utc>,
+    lookback_hours: u32,
+) -> Option<Issue> {
+    let severity = if fatalities >= 100 {
+        IssueSeverity::Emergency
+    } else if fatalities >= 50 {
+        IssueSeverity::Critical
+    } else if fatalities >= 10 {
+        IssueSeverity::Warning
+    } else if fatalities > 0 {
+        IssueSeverity::Info
+    } else {
+        return None;
+    };
+
+    let issue = Issue::new(
+        IssueSource::Acled,
+        IssueCategory::Conflict,
+        severity,
+        name,
+        code,
+        &format!("Conflict activity in {}", name),
+        &format!(
+            "{} conflict events with {} fatalities in the last {} hours",
+            event_count, fatalities, lookback_hours
+        ),
+        timestamp,
+    )
+    // One rolling conflict issue per country
+    .with_upstream_id(code)
+    .with_impact(fatalities as f64, &format!("{} fatalities", fatalities))
+    .with_url(&acled::export_url(
+        name,
+        (timestamp - chrono::Duration::hours(lookback_hours.into())).date_naive(),
+        timestamp.date_naive(),
+    ))
+    .with_details(ConflictDetails {
+        event_count,
+        acled_region: None,
+    });
+
+    Some(issue)
+}
+
+/// Split one regional ACLED response into per-country conflict issues.
+///
+/// Only lethal events count, matching the per-country queries. Countries in
+/// `monitored` are skipped, since they already get an issue of their own.
+fn acled_region_issues(
+    region: AcledRegion,
+    response: &AcledResponse,
+    monitored: &[MonitoredCountry],
+    lookback_hours: u32,
+) -> Vec<Issue> {
+    response
+        .events_by_country()
+        .into_iter()
+        .filter(|(name, events)| {
+            !monitored.iter().any(|m| {
+                m.name.eq_ignore_ascii_case(name)
+                    || events
+                        .first()
+                        .is_some_and(|e| m.alpha3.eq_ignore_ascii_case(&e.iso3))
+            })
+        })
+        .filter_map(|(name, events)| {
+            let lethal: Vec<_> = events.into_iter().filter(|e| e.is_lethal()).collect();
+            let code = lethal.first()?.iso3.clone();
+            let fatalities = lethal.iter().filter_map(|e| e.fatalities).sum();
+            let timestamp = lethal
+                .iter()
+                .max_by_key(|e| &e.event_date)
+                .and_then(|e| e.datetime())
+                .unwrap_or_else(Utc::now);
+
+            conflict_issue(
+                name,
+                &code,
+                lethal.len() as i64,
+                fatalities,
+                timestamp,
+                lookback_hours,
+            )
+            .map(|mut issue| {
+                if let Some(IssueDetails::Conflict(details)) = &mut issue.details {
+                    details.acled_region = Some(region.name().to_string());
+                }
+                issue
+            })
+        })
+        .collect()
+}
+
+/// Humanitarian-risk assessments gathered for one country.
+#[derive(Default)]
+struct CountryRisk<'a> {
+    name: String,
+    hdx: Option<&'a HdxNationalRisk>,
+    hdx_previous: Option<&'a HdxNationalRisk>,
+    acaps: Option<&'a AcapsCrisisSeverity>,
+}
+
+/// Merge HDX national risk and ACAPS crisis severity into one
+/// humanitarian-risk issue per country.
+///
+/// The more severe of the two assessments sets the issue's severity and
+/// source (HDX on a tie); the other is kept in the description as a second
+/// opinion. The `assessed_by` detail lists every source that scored the
+/// country, and `assessments_agree` records whether both reached the same
+/// severity. HDX cutoffs come from `config`, per country where overridden.
+///
+/// Countries neither source rates as high are skipped, unless their HDX
+/// score rose by `hdx_rising_risk_delta` since the previous period: those
+/// get a warning-level issue instead.
+fn humanitarian_risk_issues(
+    hdx: &[HdxNationalRisk],
+    acaps: &[AcapsCrisisSeverity],
+    config: &DashboardConfig,
+) -> Vec<Issue> {
+    let mut countries: BTreeMap<String, CountryRisk> = BTreeMap::new();
+
+    // Oldest period first, so each country ends with its latest two
+    let mut hdx: Vec<&HdxNationalRisk> = hdx.iter().filter(|r| r.overall_risk.is_some()).collect();
+    hdx.sort_by(|a, b| a.reference_period_start.cmp(&b.reference_period_start));
+    for risk in hdx {
+        let country = countries
+            .entry(risk.location_code.to_uppercase())
+            .or_default();
+        country.name = risk.location_name.clone();
+        country.hdx_previous = country.hdx.replace(risk);
+    }
+
+    // A country's most severe crisis stands for the country
+    for crisis in acaps.iter().filter(|c| c.severity_index.is_some()) {
+        for (i, iso3) in crisis.iso3.iter().enumerate() {
+            let country = countries.entry(iso3.to_uppercase()).or_default();
+            if country.name.is_empty() {
+                country.name = crisis.country.get(i).unwrap_or(iso3).clone();
+            }
+            if country
+                .acaps
+                .is_none_or(|worst| crisis.severity_index > worst.severity_index)
+            {
+                country.acaps = Some(crisis);
+            }
+        }
+    }
+
+    let mut issues = Vec::new();
+    for (code, country) in countries {
+        let hdx_score = country.hdx.and_then(|r| r.overall_risk);
+        let hdx_severity = hdx_score.and_then(|s| config.risk_thresholds_for(&code).severity(s));
+        let hdx_change = hdx_score
+            .zip(country.hdx_previous.and_then(|r| r.overall_risk))
+            .map(|(latest, previous)| latest - previous);
+        let acaps_severity = country.acaps.and_then(|c| {
+            if c.is_very_high_severity() {
+                Some(IssueSeverity::Emergency)
+            } else if c.is_high_severity() {
+                Some(IssueSeverity::Critical)
+            } else {
+                None
+            }
+        });
+        let hdx_timestamp = country
+            .hdx
+            .and_then(|r| r.reference_period_start.as_ref())
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let Some(severity) = hdx_severity.max(acaps_severity) else {
+            if let (Some(score), Some(change)) = (hdx_score, hdx_change)
+                && change >= config.hdx_rising_risk_delta
+            {
+                issues.push(
+                    Issue::new(
+                        IssueSource::HdxHapi,
+                        IssueCategory::HumanitarianEmergency,
+                        IssueSeverity::Warning,
+                        &country.name,
+                        &code,
+                        &format!("Rising humanitarian risk in {}", country.name),
+                        &format!(
+                            "National risk score rose by {:.1} to {:.1}/10 since the previous period.",
+                            change, score
+                        ),
+                        hdx_timestamp.unwrap_or_else(Utc::now),
+                    )
+                    // Same ID as the high-risk issue it may escalate into
+                    .with_upstream_id(&code)
+                    .with_impact(score, &format!("{:.1}/10 risk score", score))
+                    .with_url(&hdx_hapi::dataset_search_url(&code, HDX_RISK_QUERY))
+                    .with_details(HumanitarianDetails {
+                        hdx_risk_score: Some(score),
+                        hdx_risk_change: Some(change),
+                        ..HumanitarianDetails::default()
+                    }),
+                );
+            }
+            continue;
+        };
+        let source = if acaps_severity > hdx_severity {
+            IssueSource::Acaps
+        } else {
+            IssueSource::HdxHapi
+        };
+
+        let acaps_timestamp = country
+            .acaps
+            .and_then(|c| c.date.as_ref())
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc());
+
+        let mut description = Vec::new();
+        if let Some(risk) = country.hdx {
+            description.push(format!(
+                "National risk score: {:.1}/10. Hazard exposure: {:.1}, Vulnerability: {:.1}, Coping capacity: {:.1}.",
+                risk.overall_risk.unwrap_or(0.0),
+                risk.hazard_exposure.unwrap_or(0.0),
+                risk.vulnerability.unwrap_or(0.0),
+                risk.coping_capacity.unwrap_or(0.0)
+            ));
+        }
+        if let Some(crisis) = country.acaps {
+            description.push(format!(
+                "ACAPS INFORM Severity Index: {:.1}/5 ({}).",
+                crisis.severity_index.unwrap_or(0.0),
+                crisis.crisis_name
+            ));
+        }
+
+        let title = if severity == IssueSeverity::Emergency {
+            format!("Very high humanitarian risk in {}", country.name)
+        } else {
+            format!("High humanitarian risk in {}", country.name)
+        };
+        let (timestamp, impact, impact_label) = match (source, country.hdx, country.acaps) {
+            (IssueSource::HdxHapi, Some(risk), _) => {
+                let score = risk.overall_risk.unwrap_or(0.0);
+                (hdx_timestamp, score, format!("{:.1}/10 risk score", score))
+            }
+            (_, _, Some(crisis)) => {
+                let index = crisis.severity_index.unwrap_or(0.0);
+                (
+                    acaps_timestamp,
+                    index,
+                    format!("{:.1}/5 severity index", index),
+                )
+            }
+            _ => continue,
+        };
+
+        let assessed_by: Vec<IssueSource> = [
+            country.hdx.map(|_| IssueSource::HdxHapi),
+            country.acaps.map(|_| IssueSource::Acaps),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let issue = Issue::new(
+            source,
+            IssueCategory::HumanitarianEmergency,
+            severity,
+            &country.name,
+            &code,
+            &title,
+            &description.join(" "),
+            timestamp.unwrap_or_else(Utc::now),
+        )
+        .with_upstream_id(&match (source, country.acaps) {
+            (IssueSource::Acaps, Some(crisis)) if !crisis.crisis_id.is_empty() => {
+                crisis.crisis_id.clone()
+            }
+            _ => code.clone(),
+        })
+        .with_impact(impact, &impact_label)
+        .with_url(&match source {
+            IssueSource::Acaps => acaps::country_url(&country.name),
+            _ => hdx_hapi::dataset_search_url(&code, HDX_RISK_QUERY),
+        })
+        .with_details(HumanitarianDetails {
+            assessed_by,
+            hdx_risk_score: hdx_score,
+            hdx_risk_change: hdx_change,
+            acaps_severity_index: country.acaps.and_then(|c| c.severity_index),
+            acaps_crisis_id: country
+                .acaps
+                .map(|c| c.crisis_id.clone())
+                .filter(|id| !id.is_empty()),
+            assessments_agree: (country.hdx.is_some() && country.acaps.is_some())
+                .then_some(hdx_severity == acaps_severity),
+            ..HumanitarianDetails::default()
+        });
+
+        issues.push(issue);
+    }
+
+    issues
 }
 
 /// Dashboard API response.
@@ -680,6 +2336,40 @@ pub struct DashboardResponse {
     /// Errors encountered while fetching from sources.
     #[serde(default)]
     pub errors: Vec<SourceError>,
+
+    /// How each source fared, for a fetch from every source.
+    #[serde(default)]
+    pub health: Vec<SourceHealth>,
+
+    /// Issues dropped by suppression rules, by rule and source.
+    #[serde(default)]
+    pub suppressed: Vec<SuppressedIssues>,
+}
+
+impl DashboardResponse {
+    /// Restrict the response to issues matching the given country and/or source.
+    ///
+    /// The summary is recomputed from the remaining issues, scoring tension
+    /// with `weights`. Suppressed counts are kept for the source but not
+    /// narrowed by country, which they do not record.
+    pub fn filtered(
+        mut self,
+        country: Option<&str>,
+        source: Option<IssueSource>,
+        weights: &TensionWeights,
+    ) -> Self {
+        if let Some(country) = country {
+            self.issues.retain(|i| i.matches_country(country));
+        }
+        if let Some(source) = source {
+            self.issues.retain(|i| i.source == source);
+            self.errors.retain(|e| e.source == source);
+            self.suppressed.retain(|s| s.source == source);
+        }
+        self.summary = DashboardSummary::weighted(&self.issues, weights);
+        self.summary.suppressed_issues = self.suppressed.iter().map(|s| s.count).sum();
+        self
+    }
 }
 
 /// Summary statistics for the dashboard.
@@ -708,11 +2398,34 @@ pub struct DashboardSummary {
 
     /// Countries with most issues.
     pub top_countries: Vec<CountryIssueCount>,
+
+    /// Severity-weighted score of all ongoing issues (see
+    /// [`crate::tension`]).
+    #[serde(default)]
+    pub global_tension: f64,
+
+    /// Severity-weighted score per country, highest first.
+    #[serde(default)]
+    pub country_tension: Vec<CountryTension>,
+
+    /// Issues dropped by suppression rules, not counted above.
+    #[serde(default)]
+    pub suppressed_issues: usize,
+
+    /// Daily counts of the same categories and countries over the last
+    /// week (from `GET /dashboard/summary`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trends: Option<SummaryTrends>,
 }
 
 impl DashboardSummary {
-    /// Compute summary from a list of issues.
+    /// Compute summary from a list of issues, with default tension weights.
     pub fn from_issues(issues: &[Issue]) -> Self {
+        Self::weighted(issues, &TensionWeights::default())
+    }
+
+    /// Compute summary from a list of issues, scoring tension with `weights`.
+    pub fn weighted(issues: &[Issue], weights: &TensionWeights) -> Self {
         let mut by_source: std::collections::HashMap<String, usize> =
             std::collections::HashMap::new();
         let mut by_category: std::collections::HashMap<String, usize> =
@@ -733,21 +2446,26 @@ impl DashboardSummary {
                 IssueSeverity::Info => info_count += 1,
             }
 
-            *by_source.entry(issue.source.label().to_string()).or_insert(0) += 1;
+            *by_source
+                .entry(issue.source.label().to_string())
+                .or_insert(0) += 1;
             *by_category
                 .entry(issue.category.label().to_string())
                 .or_insert(0) += 1;
-            *by_country.entry(issue.location.clone()).or_insert(0) += 1;
+            for name in issue.location_names() {
+                *by_country.entry(name.to_string()).or_insert(0) += 1;
+            }
         }
 
         // Get top 10 countries by issue count
         let mut country_counts: Vec<_> = by_country.into_iter().collect();
-        country_counts.sort_by(|a, b| b.1.cmp(&a.1));
+        country_counts.sort_by_key(|c| std::cmp::Reverse(c.1));
         let top_countries: Vec<CountryIssueCount> = country_counts
             .into_iter()
             .take(10)
             .map(|(country, count)| CountryIssueCount { country, count })
             .collect();
+        let (global_tension, country_tension) = tension::score(issues, weights);
 
         Self {
             total_issues: issues.len(),
@@ -758,8 +2476,92 @@ impl DashboardSummary {
             by_source,
             by_category,
             top_countries,
+            global_tension,
+            country_tension,
+            suppressed_issues: 0,
+            trends: None,
         }
     }
+
+    /// Attach the daily counts of this summary's categories and top
+    /// countries over the [`TREND_DAYS`] UTC days up to `now`, read from
+    /// persisted snapshots.
+    ///
+    /// Each day counts the issues of the last snapshot taken that day; a
+    /// day without one is `None`, so a gap is not mistaken for a calm day.
+    pub async fn attach_trends(
+        &mut self,
+        storage: &dyn SignalStore,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let today = now.date_naive();
+        let days: Vec<NaiveDate> = (0..TREND_DAYS)
+            .rev()
+            .filter_map(|ago| today.checked_sub_days(chrono::Days::new(ago)))
+            .collect();
+
+        let mut by_category: HashMap<String, Vec<Option<usize>>> = self
+            .by_category
+            .keys()
+            .map(|category| (category.clone(), Vec::with_capacity(days.len())))
+            .collect();
+        let mut by_country: HashMap<String, Vec<Option<usize>>> = self
+            .top_countries
+            .iter()
+            .map(|c| (c.country.clone(), Vec::with_capacity(days.len())))
+            .collect();
+
+        for day in &days {
+            let end_of_day = day
+                .succ_opt()
+                .and_then(|next| next.and_hms_opt(0, 0, 0))
+                .map(|midnight| midnight.and_utc() - chrono::Duration::seconds(1))
+                .map_or(now, |end| end.min(now));
+            let snapshot = storage
+                .get_dashboard_snapshot_at(end_of_day)
+                .await?
+                .filter(|snapshot| snapshot.timestamp.date_naive() == *day);
+
+            let (mut categories, mut countries) = (HashMap::new(), HashMap::new());
+            for issue in snapshot.iter().flat_map(|s| &s.issues) {
+                *categories.entry(issue.category.label()).or_insert(0) += 1;
+                for name in issue.location_names() {
+                    *countries.entry(name).or_insert(0) += 1;
+                }
+            }
+            for (category, series) in &mut by_category {
+                let count = categories.get(category.as_str()).copied().unwrap_or(0);
+                series.push(snapshot.is_some().then_some(count));
+            }
+            for (country, series) in &mut by_country {
+                let count = countries.get(country.as_str()).copied().unwrap_or(0);
+                series.push(snapshot.is_some().then_some(count));
+            }
+        }
+
+        self.trends = Some(SummaryTrends {
+            days,
+            by_category,
+            by_country,
+        });
+        Ok(())
+    }
+}
+
+/// Days of history in a summary's [`SummaryTrends`].
+pub const TREND_DAYS: u64 = 7;
+
+/// Daily issue counts by category and country, for trend arrows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryTrends {
+    /// The UTC days counted, oldest first.
+    pub days: Vec<NaiveDate>,
+
+    /// Issues per day by category, aligned with `days`.
+    pub by_category: HashMap<String, Vec<Option<usize>>>,
+
+    /// Issues per day by country, for the summary's top countries.
+    pub by_country: HashMap<String, Vec<Option<usize>>>,
 }
 
 /// Country with issue count.
@@ -776,10 +2578,148 @@ pub struct SourceError {
     pub message: String,
 }
 
+/// How a data source fared in a fetch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceHealth {
+    pub source: IssueSource,
+
+    /// Whether the source was fetched without error.
+    pub ok: bool,
+
+    /// Records skipped because they no longer match their model.
+    #[serde(default)]
+    pub parse_warnings: u64,
+
+    /// When the source was last fetched without error, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+
+    /// Whether the source is failing and its issues are carried over from
+    /// its last successful fetch.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+}
+
+/// Run a source's `fetch` with [`count_parse_warnings`] if it is `due`.
+async fn fetch_if(
+    due: bool,
+    fetch: impl Future<Output = anyhow::Result<Vec<Issue>>>,
+) -> Option<(anyhow::Result<Vec<Issue>>, u64)> {
+    if due {
+        Some(count_parse_warnings(fetch).await)
+    } else {
+        None
+    }
+}
+
+/// Spawn a background task that periodically refreshes the dashboard and
+/// persists a snapshot.
+///
+/// Snapshots back `GET /dashboard?at=...` time-travel queries, so the issue set
+/// can be reconstructed as it was at any past moment for after-action reviews.
+/// The summary's tension scores are recorded alongside, for
+/// `GET /dashboard/tension`. Sources are refreshed on `cadence`, more often
+/// while they report emergency or critical issues (see [`crate::cadence`]);
+/// a tick with no source due takes no snapshot. Refreshes are counted in
+/// `infrared_dashboard_refreshes_total` and
+/// `infrared_dashboard_refresh_failures_total`, fetches of each source in
+/// `infrared_dashboard_source_fetches_total`, and the last successful
+/// refresh is published as `infrared_dashboard_last_refresh_timestamp_seconds`.
+pub fn spawn_snapshot_task(
+    dashboard: Dashboard,
+    storage: Storage,
+    metrics: Metrics,
+    cadence: RefreshCadence,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(cadence.tick());
+        let mut schedule = RefreshSchedule::new(cadence);
+        let mut previous: Option<DashboardResponse> = None;
+        loop {
+            ticker.tick().await;
+
+            let hot = previous
+                .as_ref()
+                .map(|previous| {
+                    hot_sources(&previous.issues, |code| {
+                        dashboard.canonical_country_code(code)
+                    })
+                })
+                .unwrap_or_default();
+            let due = schedule.due(dashboard.fetched_sources(), &hot);
+            if due.is_empty() {
+                continue;
+            }
+            for source in &due {
+                metrics.inc_counter(
+                    "infrared_dashboard_source_fetches_total",
+                    &[("source", source.label())],
+                    1.0,
+                );
+            }
+
+            let response = match &previous {
+                Some(previous) => dashboard.refresh_sources(&due, previous).await,
+                None => match dashboard.get_all_issues().await {
+                    Ok(response) => response,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to refresh dashboard for snapshot");
+                        metrics.inc_counter("infrared_dashboard_refresh_failures_total", &[], 1.0);
+                        continue;
+                    }
+                },
+            };
+            metrics.inc_counter("infrared_dashboard_refreshes_total", &[], 1.0);
+            metrics.set_gauge(
+                "infrared_dashboard_last_refresh_timestamp_seconds",
+                &[],
+                Utc::now().timestamp() as f64,
+            );
+            for rule in dashboard.suppression.rules() {
+                let count: usize = response
+                    .suppressed
+                    .iter()
+                    .filter(|s| s.rule == rule.name)
+                    .map(|s| s.count)
+                    .sum();
+                metrics.set_gauge(
+                    "infrared_dashboard_suppressed_issues",
+                    &[("rule", &rule.name)],
+                    count as f64,
+                );
+            }
+
+            match storage.insert_dashboard_snapshot(&response).await {
+                Ok(()) => info!(
+                    issue_count = response.issues.len(),
+                    refreshed_sources = due.len(),
+                    hot_sources = hot.len(),
+                    "Dashboard snapshot persisted"
+                ),
+                Err(e) => warn!(error = %e, "Failed to persist dashboard snapshot"),
+            }
+            if let Err(e) = storage
+                .insert_tension(response.timestamp, &response.summary)
+                .await
+            {
+                warn!(error = %e, "Failed to record tension scores");
+            }
+            previous = Some(response);
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn humanitarian(issue: &Issue) -> &HumanitarianDetails {
+        match &issue.details {
+            Some(IssueDetails::HumanitarianEmergency(details)) => details,
+            other => panic!("expected humanitarian details, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_issue_creation() {
         let issue = Issue::new(
@@ -798,6 +2738,426 @@ mod tests {
         assert_eq!(issue.severity, IssueSeverity::Critical);
     }
 
+    #[test]
+    fn test_issue_ids_are_stable() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let issue = |description: &str, title: &str| {
+            Issue::new(
+                IssueSource::Ioda,
+                IssueCategory::InternetOutage,
+                IssueSeverity::Warning,
+                "Ukraine",
+                "UA",
+                title,
+                description,
+                start,
+            )
+        };
+
+        // Refreshed figures in the description keep the ID
+        let first = issue("Dropped by 40%", "Internet outage in Ukraine");
+        let refreshed = issue("Dropped by 55%", "Internet outage in Ukraine");
+        assert_eq!(first.id, refreshed.id);
+        assert_ne!(first.id, issue("Dropped by 40%", "Other outage").id);
+
+        // An upstream ID replaces the hash entirely
+        let upstream = issue("Dropped by 40%", "Internet outage in Ukraine")
+            .with_upstream_id("Country:UA:BGP:1714564800");
+        assert_eq!(upstream.id, "ioda:country:ua:bgp:1714564800");
+
+        assert_eq!(stable_hash(&["a"]), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(stable_hash(&["ab", "c"]), stable_hash(&["a", "bc"]));
+    }
+
+    #[test]
+    fn test_issue_with_multiple_locations() {
+        let issue = Issue::new(
+            IssueSource::ReliefWeb,
+            IssueCategory::Disaster,
+            IssueSeverity::Critical,
+            "Bangladesh",
+            "BGD",
+            "South Asia: Floods",
+            "Test",
+            Utc::now(),
+        )
+        .with_location("Bangladesh", "BGD")
+        .with_location("India", "IND")
+        .with_location("Nepal", "NPL");
+
+        assert_eq!(issue.locations.len(), 3);
+        assert_eq!(issue.locations[0].code, "BGD");
+        assert!(issue.matches_country("BGD"));
+        assert!(issue.matches_country("ind"));
+        assert!(issue.matches_country("nepal"));
+        assert!(!issue.matches_country("PAK"));
+
+        let summary = DashboardSummary::from_issues(&[issue]);
+        assert_eq!(summary.top_countries.len(), 3);
+        assert!(summary.top_countries.iter().all(|c| c.count == 1));
+    }
+
+    #[test]
+    fn test_acled_region_issues() {
+        let event = |country: &str, iso3: &str, date: &str, fatalities: i64| {
+            serde_json::from_value::<crate::data_sources::acled::AcledEvent>(serde_json::json!({
+                "event_id_cnty": format!("{}1", iso3),
+                "event_date": date,
+                "country": country,
+                "iso3": iso3,
+                "fatalities": fatalities,
+            }))
+            .unwrap()
+        };
+        let response = AcledResponse {
+            success: true,
+            error: None,
+            count: 5,
+            data: vec![
+                event("Syria", "SYR", "2024-05-01", 30),
+                event("Syria", "SYR", "2024-05-03", 25),
+                event("Syria", "SYR", "2024-05-04", 0),
+                event("Jordan", "JOR", "2024-05-02", 0),
+                event("Yemen", "YEM", "2024-05-02", 200),
+            ]
+            .into(),
+        };
+        let monitored = vec![MonitoredCountry {
+            alpha2: "YE".to_string(),
+            alpha3: "YEM".to_string(),
+            name: "Yemen".to_string(),
+        }];
+
+        let issues = acled_region_issues(AcledRegion::MiddleEast, &response, &monitored, 24);
+
+        // Jordan had no lethal events; Yemen is queried on its own
+        assert_eq!(issues.len(), 1);
+        let syria = &issues[0];
+        assert_eq!(syria.location_code, "SYR");
+        assert_eq!(syria.severity, IssueSeverity::Critical);
+        assert_eq!(syria.impact_value, Some(55.0));
+        assert_eq!(
+            syria.details,
+            Some(IssueDetails::Conflict(ConflictDetails {
+                event_count: 2,
+                acled_region: Some("Middle East".to_string()),
+            }))
+        );
+        assert_eq!(syria.id, "acled:syr");
+        assert_eq!(syria.timestamp.date_naive().to_string(), "2024-05-03");
+        assert_eq!(
+            syria.url.as_deref(),
+            Some(
+                "https://api.acleddata.com/acled/read.csv?country=Syria\
+                 &event_date=2024-05-02%7C2024-05-03&event_date_where=BETWEEN\
+                 &fatalities=1&fatalities_where=%3E%3D"
+            )
+        );
+    }
+
+    #[test]
+    fn test_attach_situation_reports() {
+        let disaster = Issue::new(
+            IssueSource::ReliefWeb,
+            IssueCategory::Disaster,
+            IssueSeverity::Critical,
+            "Sudan",
+            "SDN",
+            "Sudan: Floods - Aug 2024",
+            "Test",
+            Utc::now(),
+        )
+        .with_details(DisasterDetails {
+            reliefweb_id: "52000".to_string(),
+            ..DisasterDetails::default()
+        });
+        let report = |id: &str, json: serde_json::Value| ReliefWebItem {
+            id: id.to_string(),
+            score: 1.0,
+            fields: serde_json::from_value(json).unwrap(),
+            href: String::new(),
+        };
+        let reports = vec![
+            report(
+                "1",
+                serde_json::json!({
+                    "title": "Flash Update No. 1",
+                    "disaster": [{ "id": 52000 }],
+                    "url": "https://reliefweb.int/node/1",
+                    "date": { "created": "2024-08-10T00:00:00+00:00" }
+                }),
+            ),
+            report(
+                "2",
+                serde_json::json!({
+                    "title": "Flash Update No. 2",
+                    "disaster": [{ "id": 52000 }],
+                    "url": "https://reliefweb.int/node/2",
+                    "date": { "created": "2024-08-12T00:00:00+00:00" }
+                }),
+            ),
+            report(
+                "3",
+                serde_json::json!({
+                    "title": "Cholera Outbreak Update",
+                    "primary_country": { "name": "Chad", "iso3": "TCD" },
+                    "url": "https://reliefweb.int/node/3"
+                }),
+            ),
+        ];
+
+        let mut issues = vec![disaster];
+        attach_situation_reports(&mut issues, &reports);
+
+        assert_eq!(issues.len(), 2);
+        let links = &issues[0].links;
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].title, "Flash Update No. 2");
+
+        let standalone = &issues[1];
+        assert_eq!(standalone.severity, IssueSeverity::Info);
+        assert_eq!(standalone.location_code, "TCD");
+        assert_eq!(standalone.id, "reliefweb:report:3");
+        assert_eq!(
+            standalone.url.as_deref(),
+            Some("https://reliefweb.int/node/3")
+        );
+    }
+
+    #[test]
+    fn test_hdx_change_issue() {
+        let country = MonitoredCountry {
+            alpha2: "SD".to_string(),
+            alpha3: "SDN".to_string(),
+            name: "Sudan".to_string(),
+        };
+        let change = |previous, latest| HdxFigureChange {
+            previous_period: "2023-01-01T00:00:00".to_string(),
+            latest_period: "2024-01-01T00:00:00".to_string(),
+            previous,
+            latest,
+        };
+
+        let issue = hdx_change_issue(
+            "IDPs",
+            IssueCategory::Displacement,
+            &country,
+            &change(1000, 1300),
+            25.0,
+        )
+        .unwrap();
+        assert_eq!(issue.severity, IssueSeverity::Warning);
+        assert_eq!(issue.id, "hdx_hapi:idps:sdn:2024-01-01");
+        assert_eq!(
+            issue.details,
+            Some(IssueDetails::Displacement(DisplacementDetails {
+                figure_change: Some(FigureChange {
+                    previous: 1000,
+                    latest: 1300,
+                    percent_change: 30.0,
+                }),
+                ..DisplacementDetails::default()
+            }))
+        );
+        assert_eq!(issue.timestamp.date_naive().to_string(), "2024-01-01");
+
+        let critical = hdx_change_issue(
+            "IDPs",
+            IssueCategory::Displacement,
+            &country,
+            &change(1000, 1600),
+            25.0,
+        );
+        assert_eq!(critical.unwrap().severity, IssueSeverity::Critical);
+
+        assert!(
+            hdx_change_issue(
+                "IDPs",
+                IssueCategory::Displacement,
+                &country,
+                &change(1000, 1100),
+                25.0,
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_food_price_issue() {
+        let country = MonitoredCountry {
+            alpha2: "SD".to_string(),
+            alpha3: "SDN".to_string(),
+            name: "Sudan".to_string(),
+        };
+        let change = |commodity: &str, median_percent| HdxPriceChange {
+            commodity: commodity.to_string(),
+            month: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+            markets: 4,
+            median_percent,
+        };
+
+        let issue = food_price_issue(
+            &country,
+            &[
+                change("Sorghum", 45.0),
+                change("Millet", 22.0),
+                change("Wheat", 5.0),
+            ],
+            20.0,
+        )
+        .unwrap();
+        assert_eq!(issue.category, IssueCategory::FoodSecurity);
+        assert_eq!(issue.severity, IssueSeverity::Critical);
+        assert_eq!(issue.id, "hdx_hapi:food_prices:sdn:2024-05");
+        assert_eq!(
+            issue.url.as_deref(),
+            Some("https://data.humdata.org/dataset?groups=sdn&q=food%20prices")
+        );
+        let Some(IssueDetails::FoodSecurity(details)) = &issue.details else {
+            panic!("expected food security details");
+        };
+        assert_eq!(details.commodity, "Sorghum");
+        assert!(issue.description.contains("Millet +22%"));
+        assert!(!issue.description.contains("Wheat"));
+
+        assert!(food_price_issue(&country, &[change("Wheat", 5.0)], 20.0).is_none());
+    }
+
+    #[test]
+    fn test_humanitarian_risk_thresholds() {
+        let risk = |code: &str, period: &str, score: f64| HdxNationalRisk {
+            reference_period_start: Some(period.to_string()),
+            location_code: code.to_string(),
+            location_name: code.to_string(),
+            overall_risk: Some(score),
+            ..Default::default()
+        };
+        let hdx = vec![
+            risk("SOM", "2024-01-01T00:00:00Z", 6.0),
+            risk("KEN", "2024-01-01T00:00:00Z", 4.5),
+            risk("ETH", "2024-01-01T00:00:00Z", 4.6),
+            risk("ETH", "2023-01-01T00:00:00Z", 3.2),
+            risk("UGA", "2024-01-01T00:00:00Z", 4.0),
+            risk("UGA", "2023-01-01T00:00:00Z", 3.8),
+        ];
+        let config = DashboardConfig {
+            hdx_risk_thresholds: RiskThresholds {
+                high: 4.5,
+                very_high: 6.5,
+            },
+            hdx_country_risk_thresholds: RiskThresholds::parse_country_list("som=6.5:8").unwrap(),
+            ..DashboardConfig::default()
+        };
+
+        let issues = humanitarian_risk_issues(&hdx, &[], &config);
+        let find = |code: &str| issues.iter().find(|i| i.location_code == code);
+
+        // Somalia's own cutoffs are stricter than the global ones
+        assert!(find("SOM").is_none());
+        assert_eq!(find("KEN").unwrap().severity, IssueSeverity::Critical);
+
+        // Ethiopia is high and rising; the change is noted on the issue
+        let ethiopia = find("ETH").unwrap();
+        assert_eq!(ethiopia.severity, IssueSeverity::Critical);
+        let change = humanitarian(ethiopia).hdx_risk_change.unwrap();
+        assert!((change - 1.4).abs() < 1e-9, "{}", change);
+
+        // Uganda rose, but by less than the delta
+        assert!(find("UGA").is_none());
+
+        let rising = DashboardConfig {
+            hdx_rising_risk_delta: 0.2,
+            ..DashboardConfig::default()
+        };
+        let issues = humanitarian_risk_issues(&hdx, &[], &rising);
+        let uganda = issues.iter().find(|i| i.location_code == "UGA").unwrap();
+        assert_eq!(uganda.severity, IssueSeverity::Warning);
+        assert_eq!(uganda.title, "Rising humanitarian risk in UGA");
+        assert_eq!(uganda.id, "hdx_hapi:uga");
+
+        assert!(RiskThresholds::parse_country_list("SOM=8:6").is_err());
+        assert!(RiskThresholds::parse_country_list("SOM=6").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_publish_events() {
+        let bus = EventBus::new();
+        let mut events = bus.subscribe();
+        let dashboard = Dashboard::new(DashboardConfig::default()).with_events(bus);
+        let issue = |code: &str| {
+            Issue::new(
+                IssueSource::Ioda,
+                IssueCategory::InternetOutage,
+                IssueSeverity::Warning,
+                code,
+                code,
+                "Outage",
+                "Outage",
+                Utc::now(),
+            )
+            .with_upstream_id(code)
+        };
+        let failure = SourceError {
+            source: IssueSource::Acled,
+            message: "timeout".to_string(),
+        };
+
+        let health = [
+            SourceHealth {
+                source: IssueSource::Acled,
+                ok: false,
+                parse_warnings: 0,
+                last_success: None,
+                stale: false,
+            },
+            SourceHealth {
+                source: IssueSource::Wikimedia,
+                ok: true,
+                parse_warnings: 2,
+                last_success: None,
+                stale: false,
+            },
+        ];
+
+        dashboard.publish_events(&[issue("IR")], &[failure], &health);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            Event::SourceFailed {
+                source: IssueSource::Acled,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            Event::SourceParseWarnings {
+                source: IssueSource::Wikimedia,
+                count: 2,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            Event::SourceFetched {
+                source: IssueSource::Wikimedia,
+                ..
+            }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            Event::IssueDetected(i) if i.id == "ioda:ir"
+        ));
+
+        // Only issues missing from the previous fetch are new
+        dashboard.publish_events(&[issue("IR"), issue("MM")], &[], &[]);
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            Event::IssueDetected(i) if i.id == "ioda:mm"
+        ));
+        assert!(events.try_recv().is_err());
+    }
+
     #[test]
     fn test_issue_with_end() {
         let start = Utc::now();
@@ -819,6 +3179,171 @@ mod tests {
         assert_eq!(issue.end_timestamp, Some(end));
     }
 
+    #[tokio::test]
+    async fn test_sources_not_fetched_are_skipped() {
+        let sources = IssueSource::parse_list("External, ioda").unwrap();
+        assert_eq!(
+            sources,
+            HashSet::from([IssueSource::External, IssueSource::Ioda])
+        );
+        assert!(IssueSource::parse_list("ioda,gdelt").is_err());
+
+        // With nothing fetched, no request is made and nothing fails
+        let dashboard = Dashboard::new(DashboardConfig {
+            sources: Some(HashSet::from([IssueSource::External])),
+            ..DashboardConfig::default()
+        });
+        let response = dashboard.get_all_issues().await.unwrap();
+        assert!(response.issues.is_empty());
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.health.iter().map(|h| h.source).collect::<Vec<_>>(),
+            [IssueSource::External]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hdx_change_issues_survive_failed_figures() {
+        use axum::extract::Query;
+        use axum::http::StatusCode;
+        use axum::routing::get;
+
+        type Params = Query<HashMap<String, String>>;
+        let idps = |Query(params): Params| async move {
+            let location = &params["location_code"];
+            axum::Json(serde_json::json!({ "data": [
+                { "reference_period_start": "2024-01-01", "location_code": location, "population": 1000 },
+                { "reference_period_start": "2024-04-01", "location_code": location, "population": 2000 },
+            ]}))
+        };
+        // Sudan's food prices fail, Chad's needs do
+        let prices = |Query(params): Params| async move {
+            if params["location_code"] == "SDN" {
+                Err(StatusCode::INTERNAL_SERVER_ERROR)
+            } else {
+                Ok(axum::Json(serde_json::json!({ "data": [] })))
+            }
+        };
+        let needs = |Query(params): Params| async move {
+            if params["location_code"] == "TCD" {
+                Err(StatusCode::BAD_GATEWAY)
+            } else {
+                Ok(axum::Json(serde_json::json!({ "data": [] })))
+            }
+        };
+        let app = axum::Router::new()
+            .route("/affected-people/humanitarian-needs", get(needs))
+            .route("/affected-people/idps", get(idps))
+            .route("/food/food-price", get(prices));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let country = |alpha2: &str, alpha3: &str, name: &str| MonitoredCountry {
+            alpha2: alpha2.to_string(),
+            alpha3: alpha3.to_string(),
+            name: name.to_string(),
+        };
+        let mut dashboard = Dashboard::new(DashboardConfig {
+            monitored_countries: vec![country("SD", "SDN", "Sudan"), country("TD", "TCD", "Chad")],
+            ..DashboardConfig::default()
+        });
+        dashboard.hdx_hapi = HdxHapiClient::with_base_url(&format!("http://{}", addr), "test");
+
+        // Both countries' IDP jumps are still reported
+        let issues = dashboard.fetch_hdx_change_issues().await.unwrap();
+        let mut countries: Vec<_> = issues.iter().map(|i| i.location_code.as_str()).collect();
+        countries.sort();
+        assert_eq!(countries, ["SDN", "TCD"]);
+        assert!(
+            issues
+                .iter()
+                .all(|i| i.category == IssueCategory::Displacement)
+        );
+
+        // Only a complete outage fails the source
+        dashboard.hdx_hapi = HdxHapiClient::with_base_url("http://127.0.0.1:1", "test");
+        assert!(dashboard.fetch_hdx_change_issues().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_sources_not_due() {
+        let dashboard = Dashboard::new(DashboardConfig {
+            sources: Some(HashSet::from([IssueSource::Ioda, IssueSource::External])),
+            ..DashboardConfig::default()
+        });
+        let outage = Issue::new(
+            IssueSource::Ioda,
+            IssueCategory::InternetOutage,
+            IssueSeverity::Critical,
+            "Sudan",
+            "SD",
+            "Internet outage in Sudan",
+            "BGP connectivity dropped by 80%",
+            Utc::now(),
+        );
+        let previous = DashboardResponse {
+            timestamp: Utc::now(),
+            summary: dashboard.summarize(std::slice::from_ref(&outage)),
+            issues: vec![outage.clone()],
+            errors: vec![SourceError {
+                source: IssueSource::External,
+                message: "database is locked".to_string(),
+            }],
+            health: vec![
+                SourceHealth {
+                    source: IssueSource::Ioda,
+                    ok: true,
+                    parse_warnings: 2,
+                    last_success: None,
+                    stale: false,
+                },
+                SourceHealth {
+                    source: IssueSource::External,
+                    ok: false,
+                    parse_warnings: 0,
+                    last_success: None,
+                    stale: false,
+                },
+            ],
+            suppressed: vec![
+                SuppressedIssues {
+                    rule: "ioda-bgp".to_string(),
+                    source: IssueSource::Ioda,
+                    count: 3,
+                },
+                SuppressedIssues {
+                    rule: "partner-test".to_string(),
+                    source: IssueSource::External,
+                    count: 1,
+                },
+            ],
+        };
+
+        // Only the partner issues are refetched; IODA's outage stays
+        let due = HashSet::from([IssueSource::External]);
+        let response = dashboard.refresh_sources(&due, &previous).await;
+        assert_eq!(
+            response.issues.iter().map(|i| &i.id).collect::<Vec<_>>(),
+            [&outage.id]
+        );
+        assert_eq!(response.summary.total_issues, 1);
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response
+                .health
+                .iter()
+                .map(|h| (h.source, h.ok, h.parse_warnings))
+                .collect::<Vec<_>>(),
+            [
+                (IssueSource::External, true, 0),
+                (IssueSource::Ioda, true, 2)
+            ]
+        );
+        assert_eq!(response.suppressed, previous.suppressed[..1]);
+        assert_eq!(response.summary.suppressed_issues, 3);
+    }
+
     #[test]
     fn test_severity_ordering() {
         assert!(IssueSeverity::Emergency > IssueSeverity::Critical);
@@ -826,6 +3351,88 @@ mod tests {
         assert!(IssueSeverity::Warning > IssueSeverity::Info);
     }
 
+    #[test]
+    fn test_humanitarian_risk_merges_sources() {
+        let hdx = vec![
+            HdxNationalRisk {
+                location_code: "SDN".to_string(),
+                location_name: "Sudan".to_string(),
+                overall_risk: Some(7.4),
+                ..Default::default()
+            },
+            HdxNationalRisk {
+                location_code: "HTI".to_string(),
+                location_name: "Haiti".to_string(),
+                overall_risk: Some(5.2),
+                ..Default::default()
+            },
+            HdxNationalRisk {
+                location_code: "NOR".to_string(),
+                location_name: "Norway".to_string(),
+                overall_risk: Some(1.0),
+                ..Default::default()
+            },
+        ];
+        let acaps: Vec<AcapsCrisisSeverity> = serde_json::from_value(serde_json::json!([
+            { "crisis_id": "SDN001", "crisis_name": "Sudan: Conflict", "country": ["Sudan"],
+              "iso3": ["SDN"], "INFORM Severity Index": 4.8 },
+            { "crisis_id": "HTI001", "crisis_name": "Haiti: Violence", "country": ["Haiti"],
+              "iso3": ["HTI"], "INFORM Severity Index": 3.9 },
+            { "crisis_id": "HTI002", "crisis_name": "Haiti: Complex crisis", "country": ["Haiti"],
+              "iso3": ["HTI"], "INFORM Severity Index": 4.6 },
+            { "crisis_id": "MMR001", "crisis_name": "Myanmar: Conflict", "country": ["Myanmar"],
+              "iso3": ["MMR"], "INFORM Severity Index": 4.1 }
+        ]))
+        .unwrap();
+
+        let issues = humanitarian_risk_issues(&hdx, &acaps, &DashboardConfig::default());
+        let find = |code: &str| issues.iter().find(|i| i.location_code == code).unwrap();
+        assert_eq!(issues.len(), 3);
+
+        // Both agree: HDX leads, ACAPS attributed as second opinion
+        let sudan = find("SDN");
+        assert_eq!(sudan.source, IssueSource::HdxHapi);
+        assert_eq!(sudan.severity, IssueSeverity::Emergency);
+        assert_eq!(
+            humanitarian(sudan).assessed_by,
+            [IssueSource::HdxHapi, IssueSource::Acaps]
+        );
+        assert_eq!(humanitarian(sudan).assessments_agree, Some(true));
+        assert_eq!(sudan.id, "hdx_hapi:sdn");
+        assert_eq!(
+            sudan.url.as_deref(),
+            Some("https://data.humdata.org/dataset?groups=sdn&q=INFORM%20risk")
+        );
+        assert!(
+            sudan
+                .description
+                .contains("ACAPS INFORM Severity Index: 4.8/5")
+        );
+
+        // ACAPS rates the worst crisis higher than HDX rates the country
+        let haiti = find("HTI");
+        assert_eq!(haiti.source, IssueSource::Acaps);
+        assert_eq!(haiti.severity, IssueSeverity::Emergency);
+        assert_eq!(
+            humanitarian(haiti).acaps_crisis_id.as_deref(),
+            Some("HTI002")
+        );
+        assert_eq!(haiti.id, "acaps:hti002");
+        assert_eq!(
+            haiti.url.as_deref(),
+            Some("https://www.acaps.org/en/countries/haiti")
+        );
+        assert_eq!(humanitarian(haiti).assessments_agree, Some(false));
+        assert_eq!(humanitarian(haiti).hdx_risk_score, Some(5.2));
+
+        // Only ACAPS has the country
+        let myanmar = find("MMR");
+        assert_eq!(myanmar.source, IssueSource::Acaps);
+        assert_eq!(myanmar.severity, IssueSeverity::Critical);
+        assert_eq!(myanmar.location, "Myanmar");
+        assert_eq!(humanitarian(myanmar).assessments_agree, None);
+    }
+
     #[test]
     fn test_summary_from_issues() {
         let issues = vec![
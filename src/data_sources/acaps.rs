@@ -0,0 +1,229 @@
+//! ACAPS INFORM Severity Index client.
+//!
+//! The INFORM Severity Index, published monthly by ACAPS, rates the severity
+//! of each active humanitarian crisis on a 1 (very low) to 5 (very high)
+//! scale from its impact, the conditions of the people affected, and its
+//! complexity. Where HDX national risk scores a country's exposure to future
+//! crises, this index scores crises already under way, so the two give
+//! independent opinions on how bad things are in a country.
+//!
+//! # API Reference
+//!
+//! See: <https://api.acaps.org/>
+//!
+//! # Authentication
+//!
+//! Requires a free ACAPS account; the API token is sent with every request.
+//!
+//! # Privacy
+//!
+//! All data is crisis-level severity scores. No individual persons are tracked.
+
+use chrono::{Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
+/// Base URL for the ACAPS API.
+const ACAPS_API_BASE: &str = "https://api.acaps.org/api/v1";
+
+/// Base URL of the ACAPS country pages.
+const ACAPS_COUNTRIES_BASE: &str = "https://www.acaps.org/en/countries";
+
+/// Upper bound on result pages followed for one month.
+const MAX_PAGES: usize = 20;
+
+/// Client for querying the ACAPS INFORM Severity Index.
+#[derive(Clone)]
+pub struct AcapsClient {
+    client: HttpClient,
+    base_url: String,
+    api_token: String,
+}
+
+impl AcapsClient {
+    /// Create a new ACAPS client.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_token` - API token from an ACAPS account.
+    pub fn new(api_token: &str) -> Self {
+        Self::with_base_url(ACAPS_API_BASE, api_token)
+    }
+
+    /// Create a client with a custom base URL (for testing).
+    pub fn with_base_url(base_url: &str, api_token: &str) -> Self {
+        Self {
+            client: super::http::default_client(),
+            base_url: base_url.to_string(),
+            api_token: api_token.to_string(),
+        }
+    }
+
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Get severity scores for all crises assessed in a month.
+    ///
+    /// # Arguments
+    ///
+    /// * `month` - Any day in the month to fetch
+    pub async fn get_severity_index(
+        &self,
+        month: NaiveDate,
+    ) -> anyhow::Result<Vec<AcapsCrisisSeverity>> {
+        let mut url = Some(format!(
+            "{}/inform-severity-index/{}/",
+            self.base_url,
+            month.format("%b%Y")
+        ));
+        let mut crises = Vec::new();
+
+        for _ in 0..MAX_PAGES {
+            let Some(page_url) = url.take() else {
+                break;
+            };
+            let response = self
+                .client
+                .get(&page_url)
+                .header("Authorization", format!("Token {}", self.api_token))
+                .send()
+                .await?
+                .error_for_status()?;
+            let page = self
+                .client
+                .read_json::<AcapsSeverityResponse>(response)
+                .await?;
+            crises.extend(page.results);
+            url = page.next;
+        }
+
+        Ok(crises)
+    }
+
+    /// Get the most recent month's severity scores.
+    ///
+    /// The index for a month is published during that month, so the
+    /// previous month is used if the current one has no data yet.
+    pub async fn get_latest_severity_index(&self) -> anyhow::Result<Vec<AcapsCrisisSeverity>> {
+        let this_month = Utc::now().date_naive();
+        let crises = self.get_severity_index(this_month).await?;
+        if !crises.is_empty() {
+            return Ok(crises);
+        }
+
+        match this_month.checked_sub_months(Months::new(1)) {
+            Some(last_month) => self.get_severity_index(last_month).await,
+            None => Ok(crises),
+        }
+    }
+}
+
+// ============================================================================
+// Response types
+// ============================================================================
+
+/// A page of INFORM Severity Index results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcapsSeverityResponse {
+    /// Total number of results across pages.
+    #[serde(default)]
+    pub count: u32,
+
+    /// URL of the next page, if any.
+    #[serde(default)]
+    pub next: Option<String>,
+
+    /// Crises on this page.
+    #[serde(default)]
+    pub results: LenientVec<AcapsCrisisSeverity>,
+}
+
+/// Severity assessment of one crisis.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AcapsCrisisSeverity {
+    /// ACAPS crisis identifier (e.g., "SDN001").
+    #[serde(default)]
+    pub crisis_id: String,
+
+    /// Crisis name.
+    #[serde(default)]
+    pub crisis_name: String,
+
+    /// Names of the affected countries.
+    #[serde(default)]
+    pub country: Vec<String>,
+
+    /// ISO 3166-1 alpha-3 codes of the affected countries.
+    #[serde(default)]
+    pub iso3: Vec<String>,
+
+    /// Overall severity, 1 (very low) to 5 (very high).
+    #[serde(default, rename = "INFORM Severity Index")]
+    pub severity_index: Option<f64>,
+
+    /// Assessment date.
+    #[serde(default)]
+    pub date: Option<String>,
+}
+
+impl AcapsCrisisSeverity {
+    /// Check if the crisis is of high severity (index >= 4.0).
+    pub fn is_high_severity(&self) -> bool {
+        self.severity_index.is_some_and(|s| s >= 4.0)
+    }
+
+    /// Check if the crisis is of very high severity (index >= 4.5).
+    pub fn is_very_high_severity(&self) -> bool {
+        self.severity_index.is_some_and(|s| s >= 4.5)
+    }
+}
+
+/// Link to the ACAPS page of a country, by name.
+pub fn country_url(country: &str) -> String {
+    format!("{}/{}", ACAPS_COUNTRIES_BASE, super::country_slug(country))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_severity_response() {
+        let json = r#"{
+            "count": 1,
+            "next": null,
+            "previous": null,
+            "results": [{
+                "crisis_id": "SDN001",
+                "crisis_name": "Sudan: Conflict",
+                "country": ["Sudan"],
+                "iso3": ["SDN"],
+                "INFORM Severity Index": 4.7,
+                "date": "2024-05-01"
+            }]
+        }"#;
+
+        let response: AcapsSeverityResponse = serde_json::from_str(json).unwrap();
+        assert!(response.next.is_none());
+        let crisis = &response.results[0];
+        assert_eq!(crisis.iso3, vec!["SDN"]);
+        assert_eq!(crisis.severity_index, Some(4.7));
+        assert!(crisis.is_high_severity());
+        assert!(crisis.is_very_high_severity());
+    }
+
+    #[test]
+    fn test_severity_levels() {
+        let mut crisis = AcapsCrisisSeverity::default();
+        assert!(!crisis.is_high_severity());
+
+        crisis.severity_index = Some(4.2);
+        assert!(crisis.is_high_severity());
+        assert!(!crisis.is_very_high_severity());
+    }
+}
@@ -26,13 +26,16 @@
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
 /// Base URL for the ACLED API.
 const ACLED_API_BASE: &str = "https://api.acleddata.com/acled/read";
 
 /// Client for querying the ACLED conflict data API.
 #[derive(Clone)]
 pub struct AcledClient {
-    client: reqwest::Client,
+    client: HttpClient,
     base_url: String,
     email: String,
     api_key: String,
@@ -47,7 +50,7 @@ impl AcledClient {
     /// * `api_key` - API key obtained from ACLED registration.
     pub fn new(email: &str, api_key: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: ACLED_API_BASE.to_string(),
             email: email.to_string(),
             api_key: api_key.to_string(),
@@ -57,13 +60,19 @@ impl AcledClient {
     /// Create a client with a custom base URL (for testing).
     pub fn with_base_url(base_url: &str, email: &str, api_key: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: base_url.to_string(),
             email: email.to_string(),
             api_key: api_key.to_string(),
         }
     }
 
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Build the authentication query string.
     fn auth_params(&self) -> String {
         format!("key={}&email={}", self.api_key, self.email)
@@ -90,7 +99,7 @@ impl AcledClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<AcledResponse>().await?;
+        let data = self.client.read_json::<AcledResponse>(response).await?;
         Ok(data)
     }
 
@@ -121,7 +130,7 @@ impl AcledClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<AcledResponse>().await?;
+        let data = self.client.read_json::<AcledResponse>(response).await?;
         Ok(data)
     }
 
@@ -131,7 +140,7 @@ impl AcledClient {
     ///
     /// * `country` - Country name
     /// * `event_type` - Event type (e.g., "Battles", "Explosions/Remote violence",
-    ///                  "Violence against civilians", "Protests", "Riots", "Strategic developments")
+    ///   "Violence against civilians", "Protests", "Riots", "Strategic developments")
     /// * `limit` - Maximum number of events to return
     pub async fn get_events_by_type(
         &self,
@@ -150,7 +159,7 @@ impl AcledClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<AcledResponse>().await?;
+        let data = self.client.read_json::<AcledResponse>(response).await?;
         Ok(data)
     }
 
@@ -178,7 +187,7 @@ impl AcledClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<AcledResponse>().await?;
+        let data = self.client.read_json::<AcledResponse>(response).await?;
         Ok(data)
     }
 
@@ -244,7 +253,7 @@ impl AcledClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<AcledResponse>().await?;
+        let data = self.client.read_json::<AcledResponse>(response).await?;
         Ok(data)
     }
 
@@ -272,7 +281,7 @@ impl AcledClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<AcledResponse>().await?;
+        let data = self.client.read_json::<AcledResponse>(response).await?;
         Ok(data)
     }
 }
@@ -298,7 +307,7 @@ pub struct AcledResponse {
 
     /// List of conflict events.
     #[serde(default)]
-    pub data: Vec<AcledEvent>,
+    pub data: LenientVec<AcledEvent>,
 }
 
 impl AcledResponse {
@@ -325,9 +334,21 @@ impl AcledResponse {
     pub fn with_fatalities(&self) -> Vec<&AcledEvent> {
         self.data
             .iter()
-            .filter(|e| e.fatalities.map_or(false, |f| f > 0))
+            .filter(|e| e.fatalities.is_some_and(|f| f > 0))
             .collect()
     }
+
+    /// Group events by country name, e.g. to split a regional query.
+    pub fn events_by_country(&self) -> std::collections::BTreeMap<&str, Vec<&AcledEvent>> {
+        let mut countries = std::collections::BTreeMap::new();
+        for event in &self.data {
+            countries
+                .entry(event.country.as_str())
+                .or_insert_with(Vec::new)
+                .push(event);
+        }
+        countries
+    }
 }
 
 /// A single ACLED conflict event.
@@ -478,7 +499,7 @@ impl AcledEvent {
 
     /// Check if this was a lethal event.
     pub fn is_lethal(&self) -> bool {
-        self.fatalities.map_or(false, |f| f > 0)
+        self.fatalities.is_some_and(|f| f > 0)
     }
 
     /// Get coordinates as a tuple.
@@ -537,10 +558,90 @@ pub enum AcledRegion {
 }
 
 impl AcledRegion {
+    const ALL: [AcledRegion; 16] = [
+        AcledRegion::WesternAfrica,
+        AcledRegion::MiddleAfrica,
+        AcledRegion::EasternAfrica,
+        AcledRegion::SouthernAfrica,
+        AcledRegion::NorthernAfrica,
+        AcledRegion::SouthAsia,
+        AcledRegion::SoutheastAsia,
+        AcledRegion::MiddleEast,
+        AcledRegion::Europe,
+        AcledRegion::CaucasusCentralAsia,
+        AcledRegion::CentralAmerica,
+        AcledRegion::SouthAmerica,
+        AcledRegion::Caribbean,
+        AcledRegion::EastAsia,
+        AcledRegion::NorthAmerica,
+        AcledRegion::Oceania,
+    ];
+
     /// Get the region number for API calls.
     pub fn number(&self) -> u32 {
         *self as u32
     }
+
+    /// Get a human-readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AcledRegion::WesternAfrica => "Western Africa",
+            AcledRegion::MiddleAfrica => "Middle Africa",
+            AcledRegion::EasternAfrica => "Eastern Africa",
+            AcledRegion::SouthernAfrica => "Southern Africa",
+            AcledRegion::NorthernAfrica => "Northern Africa",
+            AcledRegion::SouthAsia => "South Asia",
+            AcledRegion::SoutheastAsia => "Southeast Asia",
+            AcledRegion::MiddleEast => "Middle East",
+            AcledRegion::Europe => "Europe",
+            AcledRegion::CaucasusCentralAsia => "Caucasus and Central Asia",
+            AcledRegion::CentralAmerica => "Central America",
+            AcledRegion::SouthAmerica => "South America",
+            AcledRegion::Caribbean => "Caribbean",
+            AcledRegion::EastAsia => "East Asia",
+            AcledRegion::NorthAmerica => "North America",
+            AcledRegion::Oceania => "Oceania",
+        }
+    }
+
+    /// Parse a region number or name (e.g., "8", "Middle East", "middle_east").
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Ok(number) = s.parse::<u32>() {
+            return Self::ALL.into_iter().find(|r| r.number() == number);
+        }
+        let normalized = s.replace('_', " ");
+        Self::ALL
+            .into_iter()
+            .find(|r| r.name().eq_ignore_ascii_case(&normalized))
+    }
+
+    /// Parse a comma-separated list of regions, e.g. `middle_east,3`.
+    pub fn parse_list(spec: &str) -> anyhow::Result<Vec<Self>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                Self::parse(entry)
+                    .ok_or_else(|| anyhow::anyhow!("unknown ACLED region '{}'", entry))
+            })
+            .collect()
+    }
+}
+
+/// ACLED export query for lethal events in `country` from `from` to `to`,
+/// as CSV.
+///
+/// Credentials are left out: analysts add their own `key` and `email` to
+/// run it.
+pub fn export_url(country: &str, from: NaiveDate, to: NaiveDate) -> String {
+    format!(
+        "{}.csv?country={}&event_date={}%7C{}&event_date_where=BETWEEN&fatalities=1&fatalities_where=%3E%3D",
+        ACLED_API_BASE,
+        urlencoding::encode(country),
+        from,
+        to
+    )
 }
 
 #[cfg(test)]
@@ -630,7 +731,8 @@ mod tests {
                     event_type: "Protests".to_string(),
                     ..sample_event()
                 },
-            ],
+            ]
+            .into(),
         };
 
         assert_eq!(response.total_fatalities(), 8);
@@ -654,4 +756,49 @@ mod tests {
         assert_eq!(AcledRegion::MiddleEast.number(), 8);
         assert_eq!(AcledRegion::Europe.number(), 9);
     }
+
+    #[test]
+    fn test_region_parsing() {
+        assert_eq!(AcledRegion::parse("8"), Some(AcledRegion::MiddleEast));
+        assert_eq!(
+            AcledRegion::parse("middle_east"),
+            Some(AcledRegion::MiddleEast)
+        );
+        assert_eq!(
+            AcledRegion::parse("Caucasus and Central Asia"),
+            Some(AcledRegion::CaucasusCentralAsia)
+        );
+        assert_eq!(AcledRegion::parse("17"), None);
+        assert_eq!(AcledRegion::parse("atlantis"), None);
+
+        assert_eq!(
+            AcledRegion::parse_list("middle_east, 3,").unwrap(),
+            vec![AcledRegion::MiddleEast, AcledRegion::EasternAfrica]
+        );
+        assert!(AcledRegion::parse_list("middle_east,atlantis").is_err());
+    }
+
+    #[test]
+    fn test_events_by_country() {
+        let response = AcledResponse {
+            success: true,
+            error: None,
+            count: 3,
+            data: vec![
+                sample_event(),
+                AcledEvent {
+                    country: "Moldova".to_string(),
+                    iso3: "MDA".to_string(),
+                    ..sample_event()
+                },
+                sample_event(),
+            ]
+            .into(),
+        };
+
+        let by_country = response.events_by_country();
+        assert_eq!(by_country.len(), 2);
+        assert_eq!(by_country["Ukraine"].len(), 2);
+        assert_eq!(by_country["Moldova"][0].iso3, "MDA");
+    }
 }
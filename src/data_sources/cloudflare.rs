@@ -21,16 +21,22 @@
 //!
 //! All data is aggregate traffic statistics. No individual users are tracked.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
 /// Base URL for the Cloudflare Radar API.
 const CLOUDFLARE_API_BASE: &str = "https://api.cloudflare.com/client/v4/radar";
 
+/// Base URL of the public Cloudflare Radar site.
+const RADAR_BASE: &str = "https://radar.cloudflare.com";
+
 /// Client for querying Cloudflare Radar's traffic data API.
 #[derive(Clone)]
 pub struct CloudflareRadarClient {
-    client: reqwest::Client,
+    client: HttpClient,
     base_url: String,
     api_token: Option<String>,
 }
@@ -47,10 +53,10 @@ impl CloudflareRadarClient {
     /// # Arguments
     ///
     /// * `api_token` - Optional API token for authenticated requests.
-    ///                 Some endpoints work without authentication but may have rate limits.
+    ///   Some endpoints work without authentication but may have rate limits.
     pub fn new(api_token: Option<String>) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: CLOUDFLARE_API_BASE.to_string(),
             api_token,
         }
@@ -59,12 +65,18 @@ impl CloudflareRadarClient {
     /// Create a client with a custom base URL (for testing).
     pub fn with_base_url(base_url: &str, api_token: Option<String>) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: base_url.to_string(),
             api_token,
         }
     }
 
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Build a request with optional authentication.
     fn build_request(&self, url: &str) -> reqwest::RequestBuilder {
         let req = self.client.get(url);
@@ -107,7 +119,10 @@ impl CloudflareRadarClient {
         }
 
         let response = self.build_request(&url).send().await?;
-        let data = response.json::<CloudflareTimeseriesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<CloudflareTimeseriesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -130,7 +145,10 @@ impl CloudflareRadarClient {
         );
 
         let response = self.build_request(&url).send().await?;
-        let data = response.json::<CloudflareTimeseriesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<CloudflareTimeseriesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -165,7 +183,10 @@ impl CloudflareRadarClient {
         );
 
         let response = self.build_request(&url).send().await?;
-        let data = response.json::<CloudflareTimeseriesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<CloudflareTimeseriesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -190,7 +211,10 @@ impl CloudflareRadarClient {
         }
 
         let response = self.build_request(&url).send().await?;
-        let data = response.json::<CloudflareAnomaliesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<CloudflareAnomaliesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -201,7 +225,9 @@ impl CloudflareRadarClient {
         &self,
         country_code: &str,
     ) -> anyhow::Result<Option<CloudflareDataPoint>> {
-        let response = self.get_traffic_timeseries(country_code, "1d", Some("15m")).await?;
+        let response = self
+            .get_traffic_timeseries(country_code, "1d", Some("15m"))
+            .await?;
 
         Ok(response
             .result
@@ -211,8 +237,8 @@ impl CloudflareRadarClient {
                 let values = s.values;
                 timestamps
                     .into_iter()
-                    .zip(values.into_iter())
-                    .last()
+                    .zip(values)
+                    .next_back()
                     .map(|(ts, val)| CloudflareDataPoint {
                         timestamp: ts,
                         value: val,
@@ -275,7 +301,7 @@ pub struct CloudflareError {
 pub struct CloudflareTimeseriesResult {
     /// Time series data.
     #[serde(default)]
-    pub series: Vec<CloudflareSeries>,
+    pub series: LenientVec<CloudflareSeries>,
 
     /// Metadata about the query.
     #[serde(default)]
@@ -335,10 +361,10 @@ impl CloudflareSeries {
     ///
     /// Returns true if the latest value is below `threshold` fraction of the average.
     pub fn has_significant_drop(&self, threshold: f64) -> bool {
-        if let (Some(latest), avg) = (self.latest_value(), self.average()) {
-            if avg > 0.0 {
-                return latest < avg * threshold;
-            }
+        if let (Some(latest), avg) = (self.latest_value(), self.average())
+            && avg > 0.0
+        {
+            return latest < avg * threshold;
         }
         false
     }
@@ -392,7 +418,7 @@ pub struct CloudflareAnomaliesResponse {
 pub struct CloudflareAnomaliesResult {
     /// List of detected anomalies.
     #[serde(default)]
-    pub anomalies: Vec<CloudflareAnomaly>,
+    pub anomalies: LenientVec<CloudflareAnomaly>,
 }
 
 /// A detected traffic anomaly.
@@ -475,6 +501,18 @@ impl CloudflareDataPoint {
     }
 }
 
+/// Link to the Cloudflare Radar traffic page of `location`, a two-letter
+/// country code, from `start` to `end`.
+pub fn radar_url(location: &str, start: NaiveDate, end: NaiveDate) -> String {
+    format!(
+        "{}/traffic/{}?dateStart={}&dateEnd={}",
+        RADAR_BASE,
+        location.to_lowercase(),
+        start,
+        end
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
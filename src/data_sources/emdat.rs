@@ -0,0 +1,523 @@
+//! EM-DAT (Emergency Events Database) client.
+//!
+//! EM-DAT, maintained by CRED at UCLouvain, records mass disasters worldwide
+//! since 1900. An event enters the database when it meets at least one of:
+//! 10+ deaths, 100+ people affected, a declared state of emergency, or a call
+//! for international assistance. That makes it a good baseline for "how
+//! unusual is this?" when a new disaster appears in ReliefWeb.
+//!
+//! # Ingestion
+//!
+//! - **CSV**: the public table exported from the EM-DAT portal
+//!   (see [`EmdatBaseline::from_csv`])
+//! - **API**: the EM-DAT GraphQL API (see [`EmdatClient::get_baseline`])
+//!
+//! Both produce an [`EmdatBaseline`] of per-country, per-type disaster
+//! frequencies used to annotate issues, e.g. "3rd major flood here this year".
+//!
+//! # API Reference
+//!
+//! See: <https://doc.emdat.be/docs/data-structure-and-content/emdat-public-table/>
+//!
+//! # Privacy
+//!
+//! Records describe country-level disaster events. No individual persons are tracked.
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
+/// Base URL for the EM-DAT GraphQL API.
+const EMDAT_API_BASE: &str = "https://api.emdat.be/v1";
+
+/// Years of history fetched by default when building a baseline.
+pub const DEFAULT_BASELINE_YEARS: i32 = 20;
+
+/// GraphQL query for the public EM-DAT table.
+const PUBLIC_EMDAT_QUERY: &str = "query baseline($from: Int, $to: Int) { \
+    public_emdat(cursor: {limit: -1}, filters: {from: $from, to: $to}) { \
+    data { disno type iso country start_year start_month start_day } } }";
+
+/// Client for querying the EM-DAT GraphQL API.
+#[derive(Clone)]
+pub struct EmdatClient {
+    client: HttpClient,
+    base_url: String,
+    api_key: String,
+}
+
+impl EmdatClient {
+    /// Create a new EM-DAT client.
+    ///
+    /// # Arguments
+    ///
+    /// * `api_key` - API key from the EM-DAT portal (free registration)
+    pub fn new(api_key: &str) -> Self {
+        Self::with_base_url(EMDAT_API_BASE, api_key)
+    }
+
+    /// Create a client with a custom base URL (for testing).
+    pub fn with_base_url(base_url: &str, api_key: &str) -> Self {
+        Self {
+            client: super::http::default_client(),
+            base_url: base_url.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Fetch all recorded disasters that started between `from_year` and
+    /// `to_year` (inclusive) and build a frequency baseline from them.
+    pub async fn get_baseline(
+        &self,
+        from_year: i32,
+        to_year: i32,
+    ) -> anyhow::Result<EmdatBaseline> {
+        let body = serde_json::json!({
+            "query": PUBLIC_EMDAT_QUERY,
+            "variables": { "from": from_year, "to": to_year },
+        });
+
+        let response = self
+            .client
+            .post(&self.base_url)
+            .header("Authorization", &self.api_key)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+        let data = self
+            .client
+            .read_json::<EmdatGraphQlResponse>(response)
+            .await?;
+
+        if let Some(error) = data.errors.first() {
+            anyhow::bail!("EM-DAT API error: {}", error.message);
+        }
+        let records = data
+            .data
+            .map(|d| d.public_emdat.data)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(EmdatRow::into_record)
+            .collect();
+
+        Ok(EmdatBaseline::new(records).with_years(from_year, to_year))
+    }
+
+    /// Convenience method: baseline over the last [`DEFAULT_BASELINE_YEARS`]
+    /// years, including the current one.
+    pub async fn get_recent_baseline(&self) -> anyhow::Result<EmdatBaseline> {
+        let this_year = Utc::now().year();
+        self.get_baseline(this_year - DEFAULT_BASELINE_YEARS + 1, this_year)
+            .await
+    }
+}
+
+// ============================================================================
+// Response types
+// ============================================================================
+
+/// GraphQL response envelope.
+#[derive(Debug, Clone, Deserialize)]
+struct EmdatGraphQlResponse {
+    data: Option<EmdatGraphQlData>,
+    #[serde(default)]
+    errors: Vec<EmdatGraphQlError>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EmdatGraphQlData {
+    public_emdat: EmdatPublicTable,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EmdatPublicTable {
+    #[serde(default)]
+    data: LenientVec<EmdatRow>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct EmdatGraphQlError {
+    message: String,
+}
+
+/// One row of the public EM-DAT table as returned by the API.
+#[derive(Debug, Clone, Deserialize)]
+struct EmdatRow {
+    #[serde(default)]
+    disno: String,
+    #[serde(default, rename = "type")]
+    disaster_type: String,
+    #[serde(default)]
+    iso: String,
+    #[serde(default)]
+    country: String,
+    start_year: Option<i32>,
+    start_month: Option<u32>,
+    start_day: Option<u32>,
+}
+
+impl EmdatRow {
+    fn into_record(self) -> Option<EmdatRecord> {
+        if self.iso.is_empty() || self.disaster_type.is_empty() {
+            return None;
+        }
+        Some(EmdatRecord {
+            disno: self.disno,
+            iso: self.iso,
+            country: self.country,
+            disaster_type: self.disaster_type,
+            start_year: self.start_year?,
+            start_month: self.start_month,
+            start_day: self.start_day,
+        })
+    }
+}
+
+/// A recorded disaster event.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmdatRecord {
+    /// EM-DAT disaster number (e.g., "2024-0001-BRA").
+    pub disno: String,
+
+    /// ISO 3166-1 alpha-3 country code.
+    pub iso: String,
+
+    /// Country name.
+    pub country: String,
+
+    /// EM-DAT disaster type (e.g., "Flood", "Storm", "Earthquake").
+    pub disaster_type: String,
+
+    /// Year the event started.
+    pub start_year: i32,
+
+    /// Month the event started, if known.
+    pub start_month: Option<u32>,
+
+    /// Day the event started, if known.
+    pub start_day: Option<u32>,
+}
+
+impl EmdatRecord {
+    /// Earliest possible start date (unknown month/day count as the first).
+    pub fn start_date(&self) -> Option<NaiveDate> {
+        let month = self.start_month.unwrap_or(1);
+        NaiveDate::from_ymd_opt(self.start_year, month, self.start_day.unwrap_or(1))
+            .or_else(|| NaiveDate::from_ymd_opt(self.start_year, month, 1))
+    }
+}
+
+// ============================================================================
+// Baseline
+// ============================================================================
+
+/// Historical disaster frequencies per country and disaster type.
+#[derive(Debug, Clone, Default)]
+pub struct EmdatBaseline {
+    records: Vec<EmdatRecord>,
+    first_year: i32,
+    last_year: i32,
+}
+
+impl EmdatBaseline {
+    /// Build a baseline from records, covering the years they span.
+    pub fn new(records: Vec<EmdatRecord>) -> Self {
+        let first_year = records.iter().map(|r| r.start_year).min().unwrap_or(0);
+        let last_year = records.iter().map(|r| r.start_year).max().unwrap_or(0);
+        Self {
+            records,
+            first_year,
+            last_year,
+        }
+    }
+
+    /// Set the years the baseline covers (quiet years have no records).
+    pub fn with_years(mut self, first_year: i32, last_year: i32) -> Self {
+        self.first_year = first_year;
+        self.last_year = last_year;
+        self
+    }
+
+    /// Parse the public table exported from the EM-DAT portal as CSV.
+    ///
+    /// Requires the `ISO`, `Disaster Type` and `Start Year` columns;
+    /// `DisNo.`, `Country`, `Start Month` and `Start Day` are used if present.
+    pub fn from_csv(text: &str) -> anyhow::Result<Self> {
+        let mut rows = parse_csv(text.trim_start_matches('\u{feff}')).into_iter();
+        let header = rows
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("EM-DAT CSV is empty"))?;
+        let column = |name: &str| header.iter().position(|h| h.trim() == name);
+        let required = |name: &str| {
+            column(name).ok_or_else(|| anyhow::anyhow!("EM-DAT CSV has no '{}' column", name))
+        };
+
+        let iso = required("ISO")?;
+        let disaster_type = required("Disaster Type")?;
+        let start_year = required("Start Year")?;
+        let disno = column("DisNo.");
+        let country = column("Country");
+        let start_month = column("Start Month");
+        let start_day = column("Start Day");
+
+        let field = |row: &[String], index: Option<usize>| {
+            index
+                .and_then(|i| row.get(i))
+                .map(|v| v.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let records = rows
+            .filter_map(|row| {
+                EmdatRow {
+                    disno: field(&row, disno),
+                    disaster_type: field(&row, Some(disaster_type)),
+                    iso: field(&row, Some(iso)),
+                    country: field(&row, country),
+                    start_year: field(&row, Some(start_year)).parse().ok(),
+                    start_month: field(&row, start_month).parse().ok(),
+                    start_day: field(&row, start_day).parse().ok(),
+                }
+                .into_record()
+            })
+            .collect();
+
+        Ok(Self::new(records))
+    }
+
+    /// All records in the baseline.
+    pub fn records(&self) -> &[EmdatRecord] {
+        &self.records
+    }
+
+    /// Number of years the baseline covers.
+    pub fn years_covered(&self) -> i32 {
+        if self.records.is_empty() && self.first_year == 0 {
+            0
+        } else {
+            self.last_year - self.first_year + 1
+        }
+    }
+
+    fn matching<'a>(
+        &'a self,
+        iso3: &'a str,
+        disaster_type: &'a str,
+    ) -> impl Iterator<Item = &'a EmdatRecord> {
+        self.records.iter().filter(move |r| {
+            r.iso.eq_ignore_ascii_case(iso3) && r.disaster_type.eq_ignore_ascii_case(disaster_type)
+        })
+    }
+
+    /// Disasters of a type recorded in a country during `year`.
+    pub fn count_in_year(&self, iso3: &str, disaster_type: &str, year: i32) -> usize {
+        self.matching(iso3, disaster_type)
+            .filter(|r| r.start_year == year)
+            .count()
+    }
+
+    /// Disasters of a type recorded in a country in the same year as `date`
+    /// and starting before it.
+    pub fn count_earlier_in_year(&self, iso3: &str, disaster_type: &str, date: NaiveDate) -> usize {
+        self.matching(iso3, disaster_type)
+            .filter(|r| r.start_year == date.year())
+            .filter(|r| r.start_date().is_some_and(|start| start < date))
+            .count()
+    }
+
+    /// Average disasters of a type per year in a country over the baseline.
+    pub fn annual_average(&self, iso3: &str, disaster_type: &str) -> f64 {
+        let years = self.years_covered();
+        if years <= 0 {
+            return 0.0;
+        }
+        self.matching(iso3, disaster_type).count() as f64 / years as f64
+    }
+
+    /// Describe how a disaster starting at `at` compares with the baseline.
+    ///
+    /// `reliefweb_type` is a ReliefWeb disaster type name; returns `None` if it
+    /// has no EM-DAT equivalent or the baseline is empty.
+    ///
+    /// ```ignore
+    /// // "3rd major flood here this year (1.4 per year on average, 2005-2024)"
+    /// let note = baseline.annotate("SDN", "Flood", Utc::now());
+    /// ```
+    pub fn annotate(&self, iso3: &str, reliefweb_type: &str, at: DateTime<Utc>) -> Option<String> {
+        if self.years_covered() <= 0 {
+            return None;
+        }
+        let disaster_type = emdat_type_for(reliefweb_type)?;
+        let ordinal = self.count_earlier_in_year(iso3, disaster_type, at.date_naive()) + 1;
+
+        Some(format!(
+            "{} major {} here this year ({:.1} per year on average, {}-{})",
+            ordinal_label(ordinal),
+            disaster_type.to_lowercase(),
+            self.annual_average(iso3, disaster_type),
+            self.first_year,
+            self.last_year
+        ))
+    }
+}
+
+/// Map a ReliefWeb disaster type name to the EM-DAT disaster type it falls under.
+pub fn emdat_type_for(reliefweb_type: &str) -> Option<&'static str> {
+    match reliefweb_type {
+        "Flood" | "Flash Flood" => Some("Flood"),
+        "Tropical Cyclone" | "Storm Surge" | "Severe Local Storm" | "Extratropical Cyclone" => {
+            Some("Storm")
+        }
+        // EM-DAT files tsunamis under the triggering earthquake
+        "Earthquake" | "Tsunami" => Some("Earthquake"),
+        "Drought" => Some("Drought"),
+        "Epidemic" => Some("Epidemic"),
+        "Volcano" => Some("Volcanic activity"),
+        "Land Slide" | "Mud Slide" => Some("Mass movement (wet)"),
+        "Wild Fire" => Some("Wildfire"),
+        "Heat Wave" | "Cold Wave" => Some("Extreme temperature"),
+        "Insect Infestation" => Some("Infestation"),
+        _ => None,
+    }
+}
+
+/// English ordinal for `n` ("1st", "2nd", "3rd", "11th", ...).
+fn ordinal_label(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Split CSV text into rows of fields (RFC 4180 quoting, LF or CRLF line ends).
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => row.push(std::mem::take(&mut field)),
+            '\r' if !in_quotes => {}
+            '\n' if !in_quotes => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const SAMPLE_CSV: &str = "\u{feff}DisNo.,Disaster Type,Country,ISO,Start Year,Start Month,Start Day\r\n\
+        2024-0101-SDN,Flood,Sudan,SDN,2024,3,2\r\n\
+        2024-0202-SDN,Flood,Sudan,SDN,2024,5,\r\n\
+        2024-0303-SDN,Flood,Sudan,SDN,2024,9,10\r\n\
+        2023-0404-SDN,Flood,Sudan,SDN,2023,8,1\r\n\
+        2023-0505-SDN,Epidemic,Sudan,SDN,2023,1,1\r\n\
+        2024-0606-BOL,Flood,\"Bolivia (Plurinational State of)\",BOL,2024,,\r\n\
+        2022-0707-XXX,Flood,\"Nowhere, \"\"really\"\"\",,2022,1,1\r\n";
+
+    #[test]
+    fn test_from_csv() {
+        let baseline = EmdatBaseline::from_csv(SAMPLE_CSV).unwrap();
+
+        // The row without an ISO code is skipped
+        assert_eq!(baseline.records().len(), 6);
+        assert_eq!(baseline.years_covered(), 2);
+        assert_eq!(
+            baseline.records()[5].country,
+            "Bolivia (Plurinational State of)"
+        );
+        assert_eq!(baseline.records()[1].start_day, None);
+
+        assert!(EmdatBaseline::from_csv("ISO,Country\nSDN,Sudan\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_csv_quoting() {
+        let rows = parse_csv("a,\"b, \"\"c\"\"\",\"d\ne\"\n1,2,3");
+        assert_eq!(rows[0], vec!["a", "b, \"c\"", "d\ne"]);
+        assert_eq!(rows[1], vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let baseline = EmdatBaseline::from_csv(SAMPLE_CSV).unwrap();
+
+        assert_eq!(baseline.count_in_year("SDN", "Flood", 2024), 3);
+        assert_eq!(baseline.count_in_year("sdn", "flood", 2023), 1);
+        assert!((baseline.annual_average("SDN", "Flood") - 2.0).abs() < 0.01);
+
+        let date = NaiveDate::from_ymd_opt(2024, 9, 1).unwrap();
+        assert_eq!(baseline.count_earlier_in_year("SDN", "Flood", date), 2);
+    }
+
+    #[test]
+    fn test_annotate() {
+        let baseline = EmdatBaseline::from_csv(SAMPLE_CSV)
+            .unwrap()
+            .with_years(2015, 2024);
+        let at = Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap();
+
+        assert_eq!(
+            baseline.annotate("SDN", "Flash Flood", at).unwrap(),
+            "4th major flood here this year (0.4 per year on average, 2015-2024)"
+        );
+        assert_eq!(
+            baseline.annotate("SDN", "Tropical Cyclone", at).unwrap(),
+            "1st major storm here this year (0.0 per year on average, 2015-2024)"
+        );
+        assert!(baseline.annotate("SDN", "Complex Emergency", at).is_none());
+        assert!(
+            EmdatBaseline::default()
+                .annotate("SDN", "Flood", at)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_ordinal_label() {
+        assert_eq!(ordinal_label(1), "1st");
+        assert_eq!(ordinal_label(2), "2nd");
+        assert_eq!(ordinal_label(3), "3rd");
+        assert_eq!(ordinal_label(4), "4th");
+        assert_eq!(ordinal_label(11), "11th");
+        assert_eq!(ordinal_label(13), "13th");
+        assert_eq!(ordinal_label(22), "22nd");
+    }
+}
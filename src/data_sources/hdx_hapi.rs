@@ -19,16 +19,27 @@
 //!
 //! All data is aggregate humanitarian statistics. No individual persons are tracked.
 
-use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
 /// Base URL for the HDX HAPI.
 const HDX_HAPI_BASE: &str = "https://hapi.humdata.org/api/v1";
 
+/// Base URL of the HDX catalogue the HAPI figures are drawn from.
+const HDX_BASE: &str = "https://data.humdata.org";
+
+/// Commodity categories counted as staples in price shock detection.
+pub const STAPLE_CATEGORIES: [&str; 3] = ["cereals and tubers", "pulses and nuts", "oil and fats"];
+
 /// Client for querying the HDX Humanitarian API.
 #[derive(Clone)]
 pub struct HdxHapiClient {
-    client: reqwest::Client,
+    client: HttpClient,
     base_url: String,
     app_identifier: String,
 }
@@ -47,7 +58,7 @@ impl HdxHapiClient {
     /// * `app_identifier` - Application identifier for API tracking (required by HDX).
     pub fn new(app_identifier: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: HDX_HAPI_BASE.to_string(),
             app_identifier: app_identifier.to_string(),
         }
@@ -56,12 +67,18 @@ impl HdxHapiClient {
     /// Create a client with a custom base URL (for testing).
     pub fn with_base_url(base_url: &str, app_identifier: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: base_url.to_string(),
             app_identifier: app_identifier.to_string(),
         }
     }
 
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Get humanitarian needs data for a country.
     ///
     /// # Arguments
@@ -79,7 +96,10 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxHumanitarianNeedsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxHumanitarianNeedsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -107,7 +127,10 @@ impl HdxHapiClient {
         }
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxRefugeesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxRefugeesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -125,7 +148,7 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxIdpsResponse>().await?;
+        let data = self.client.read_json::<HdxIdpsResponse>(response).await?;
         Ok(data)
     }
 
@@ -146,7 +169,10 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxFoodSecurityResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxFoodSecurityResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -167,7 +193,10 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxFoodPricesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxFoodPricesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -190,7 +219,10 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxConflictEventsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxConflictEventsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -211,7 +243,10 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxOperationalPresenceResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxOperationalPresenceResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -220,7 +255,10 @@ impl HdxHapiClient {
     /// # Arguments
     ///
     /// * `country_code` - ISO 3166-1 alpha-3 country code
-    pub async fn get_population(&self, country_code: &str) -> anyhow::Result<HdxPopulationResponse> {
+    pub async fn get_population(
+        &self,
+        country_code: &str,
+    ) -> anyhow::Result<HdxPopulationResponse> {
         let url = format!(
             "{}/population-social/population?location_code={}&app_identifier={}",
             self.base_url,
@@ -229,7 +267,10 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxPopulationResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxPopulationResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -247,7 +288,10 @@ impl HdxHapiClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxPovertyResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxPovertyResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -270,7 +314,10 @@ impl HdxHapiClient {
         }
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<HdxNationalRiskResponse>().await?;
+        let data = self
+            .client
+            .read_json::<HdxNationalRiskResponse>(response)
+            .await?;
         Ok(data)
     }
 }
@@ -284,7 +331,7 @@ impl HdxHapiClient {
 pub struct HdxPaginatedResponse<T> {
     /// List of data items.
     #[serde(default)]
-    pub data: Vec<T>,
+    pub data: LenientVec<T>,
 }
 
 /// Humanitarian needs response.
@@ -341,6 +388,33 @@ impl HdxHumanitarianNeed {
     }
 }
 
+impl HdxHumanitarianNeedsResponse {
+    /// People in need per reference period start.
+    ///
+    /// Only whole-population rows count (all genders, ages and population
+    /// groups); the largest of those in a period is the intersectoral figure,
+    /// as sectoral needs overlap.
+    pub fn people_in_need_by_period(&self) -> BTreeMap<String, i64> {
+        let whole = |value: &str| value.is_empty() || value.eq_ignore_ascii_case("all");
+        let mut totals = BTreeMap::new();
+        for need in &self.data {
+            let (Some(period), Some(population)) = (&need.reference_period_start, need.population)
+            else {
+                continue;
+            };
+            if need.population_status.eq_ignore_ascii_case("INN")
+                && whole(&need.gender)
+                && whole(&need.age_range)
+                && whole(&need.population_group)
+            {
+                let total = totals.entry(period.clone()).or_insert(0);
+                *total = population.max(*total);
+            }
+        }
+        totals
+    }
+}
+
 /// Refugees response.
 pub type HdxRefugeesResponse = HdxPaginatedResponse<HdxRefugee>;
 
@@ -415,6 +489,20 @@ pub struct HdxIdp {
     pub population: Option<i64>,
 }
 
+impl HdxIdpsResponse {
+    /// IDPs per reference period start, summed over the areas reported.
+    pub fn idps_by_period(&self) -> BTreeMap<String, i64> {
+        let mut totals = BTreeMap::new();
+        for idp in &self.data {
+            if let (Some(period), Some(population)) = (&idp.reference_period_start, idp.population)
+            {
+                *totals.entry(period.clone()).or_insert(0) += population;
+            }
+        }
+        totals
+    }
+}
+
 /// Food security response.
 pub type HdxFoodSecurityResponse = HdxPaginatedResponse<HdxFoodSecurity>;
 
@@ -457,12 +545,12 @@ pub struct HdxFoodSecurity {
 impl HdxFoodSecurity {
     /// Check if this is a crisis-level food insecurity (IPC Phase 3+).
     pub fn is_crisis_level(&self) -> bool {
-        self.ipc_phase.map_or(false, |p| p >= 3)
+        self.ipc_phase.is_some_and(|p| p >= 3)
     }
 
     /// Check if this is emergency-level food insecurity (IPC Phase 4+).
     pub fn is_emergency_level(&self) -> bool {
-        self.ipc_phase.map_or(false, |p| p >= 4)
+        self.ipc_phase.is_some_and(|p| p >= 4)
     }
 
     /// Check if this is famine (IPC Phase 5).
@@ -522,6 +610,113 @@ pub struct HdxFoodPrice {
     pub unit: String,
 }
 
+impl HdxFoodPrice {
+    /// Month of the reference period.
+    pub fn month(&self) -> Option<NaiveDate> {
+        let start = self.reference_period_start.as_deref()?.get(..10)?;
+        NaiveDate::parse_from_str(start, "%Y-%m-%d")
+            .ok()?
+            .with_day0(0)
+    }
+
+    /// Check if the commodity is a staple (see [`STAPLE_CATEGORIES`]).
+    pub fn is_staple(&self) -> bool {
+        STAPLE_CATEGORIES
+            .iter()
+            .any(|c| self.commodity_category.eq_ignore_ascii_case(c))
+    }
+}
+
+impl HdxFoodPricesResponse {
+    /// Month-over-month change of each staple commodity in its latest month.
+    ///
+    /// Each market's price series (per price type, unit and currency) is
+    /// compared with the month before; the median across markets stands for
+    /// the commodity, so one market's outlier does not raise an alarm.
+    /// Results are sorted by largest rise first.
+    pub fn staple_price_changes(&self) -> Vec<HdxPriceChange> {
+        type SeriesKey<'a> = (&'a str, &'a str, &'a str, &'a str, &'a str);
+        let mut series: BTreeMap<SeriesKey, BTreeMap<NaiveDate, f64>> = BTreeMap::new();
+        for price in &self.data {
+            let (Some(month), Some(value)) = (price.month(), price.price) else {
+                continue;
+            };
+            if price.is_staple() && value > 0.0 {
+                series
+                    .entry((
+                        &price.commodity_name,
+                        &price.market_name,
+                        &price.price_type,
+                        &price.unit,
+                        &price.currency_code,
+                    ))
+                    .or_default()
+                    .insert(month, value);
+            }
+        }
+
+        // Per commodity: latest month and the market changes into it
+        let mut commodities: BTreeMap<&str, (NaiveDate, Vec<f64>)> = BTreeMap::new();
+        for ((commodity, ..), prices) in &series {
+            let mut months = prices.iter().rev();
+            let (Some((&latest, &current)), Some((&previous, &before))) =
+                (months.next(), months.next())
+            else {
+                continue;
+            };
+            if latest.checked_sub_months(Months::new(1)) != Some(previous) {
+                continue;
+            }
+
+            let change = (current - before) / before * 100.0;
+            let entry = commodities.entry(commodity).or_insert((latest, Vec::new()));
+            if latest > entry.0 {
+                *entry = (latest, Vec::new());
+            }
+            if latest == entry.0 {
+                entry.1.push(change);
+            }
+        }
+
+        let mut changes: Vec<HdxPriceChange> = commodities
+            .into_iter()
+            .map(|(commodity, (month, mut market_changes))| {
+                market_changes.sort_by(f64::total_cmp);
+                let mid = market_changes.len() / 2;
+                let median_percent = if market_changes.len() % 2 == 0 {
+                    (market_changes[mid - 1] + market_changes[mid]) / 2.0
+                } else {
+                    market_changes[mid]
+                };
+                HdxPriceChange {
+                    commodity: commodity.to_string(),
+                    month,
+                    markets: market_changes.len(),
+                    median_percent,
+                }
+            })
+            .collect();
+        changes.sort_by(|a, b| b.median_percent.total_cmp(&a.median_percent));
+        changes
+    }
+}
+
+/// Month-over-month price change of one commodity across markets.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HdxPriceChange {
+    /// Commodity name (e.g., "Sorghum - Retail").
+    pub commodity: String,
+
+    /// First day of the latest month.
+    pub month: NaiveDate,
+
+    /// Markets reporting both months.
+    pub markets: usize,
+
+    /// Median change across those markets, in percent.
+    pub median_percent: f64,
+}
+
 /// Conflict events response.
 pub type HdxConflictEventsResponse = HdxPaginatedResponse<HdxConflictEvent>;
 
@@ -568,7 +763,7 @@ pub struct HdxConflictEvent {
 impl HdxConflictEvent {
     /// Check if there were any fatalities.
     pub fn has_fatalities(&self) -> bool {
-        self.fatalities.map_or(false, |f| f > 0)
+        self.fatalities.is_some_and(|f| f > 0)
     }
 }
 
@@ -727,15 +922,70 @@ pub struct HdxNationalRisk {
 impl HdxNationalRisk {
     /// Check if the country is at high risk (score >= 5.0 on typical 0-10 scale).
     pub fn is_high_risk(&self) -> bool {
-        self.overall_risk.map_or(false, |r| r >= 5.0)
+        self.overall_risk.is_some_and(|r| r >= 5.0)
     }
 
     /// Check if the country is at very high risk (score >= 7.0).
     pub fn is_very_high_risk(&self) -> bool {
-        self.overall_risk.map_or(false, |r| r >= 7.0)
+        self.overall_risk.is_some_and(|r| r >= 7.0)
     }
 }
 
+// ============================================================================
+// Change detection
+// ============================================================================
+
+/// Change in a figure between its two latest reference periods.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HdxFigureChange {
+    /// Start of the earlier period.
+    pub previous_period: String,
+
+    /// Start of the latest period.
+    pub latest_period: String,
+
+    /// Figure in the earlier period.
+    pub previous: i64,
+
+    /// Figure in the latest period.
+    pub latest: i64,
+}
+
+impl HdxFigureChange {
+    /// Change as a percentage of the earlier figure.
+    pub fn percent_change(&self) -> f64 {
+        if self.previous <= 0 {
+            return 0.0;
+        }
+        (self.latest - self.previous) as f64 / self.previous as f64 * 100.0
+    }
+}
+
+/// Compare the two latest periods of a per-period series, such as
+/// [`HdxHumanitarianNeedsResponse::people_in_need_by_period`].
+pub fn latest_change(totals: &BTreeMap<String, i64>) -> Option<HdxFigureChange> {
+    let mut periods = totals.iter().rev();
+    let (latest_period, &latest) = periods.next()?;
+    let (previous_period, &previous) = periods.next()?;
+    Some(HdxFigureChange {
+        previous_period: previous_period.clone(),
+        latest_period: latest_period.clone(),
+        previous,
+        latest,
+    })
+}
+
+/// Link to the HDX datasets on the country `iso3` matching `query`
+/// (e.g., "food prices").
+pub fn dataset_search_url(iso3: &str, query: &str) -> String {
+    format!(
+        "{}/dataset?groups={}&q={}",
+        HDX_BASE,
+        iso3.to_lowercase(),
+        urlencoding::encode(query)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -813,4 +1063,155 @@ mod tests {
 
         assert!(very_high_risk.is_very_high_risk());
     }
+
+    #[test]
+    fn test_people_in_need_change() {
+        let need =
+            |period: &str, status: &str, gender: &str, population: i64| HdxHumanitarianNeed {
+                reference_period_start: Some(period.to_string()),
+                location_code: "SDN".to_string(),
+                location_name: "Sudan".to_string(),
+                population_status: status.to_string(),
+                gender: gender.to_string(),
+                age_range: "ALL".to_string(),
+                population_group: "all".to_string(),
+                population: Some(population),
+                ..Default::default()
+            };
+        let response = HdxHumanitarianNeedsResponse {
+            data: vec![
+                need("2023-01-01T00:00:00", "INN", "all", 15_000_000),
+                need("2023-01-01T00:00:00", "INN", "all", 9_000_000),
+                need("2024-01-01T00:00:00", "INN", "all", 24_000_000),
+                // Disaggregated and non-need rows are ignored
+                need("2024-01-01T00:00:00", "INN", "f", 13_000_000),
+                need("2024-01-01T00:00:00", "TGT", "all", 30_000_000),
+            ]
+            .into(),
+        };
+
+        let totals = response.people_in_need_by_period();
+        assert_eq!(totals.len(), 2);
+        let change = latest_change(&totals).unwrap();
+        assert_eq!(change.previous, 15_000_000);
+        assert_eq!(change.latest, 24_000_000);
+        assert_eq!(change.latest_period, "2024-01-01T00:00:00");
+        assert!((change.percent_change() - 60.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_idps_change() {
+        let idp = |period: &str, population: i64| HdxIdp {
+            reference_period_start: Some(period.to_string()),
+            population: Some(population),
+            ..Default::default()
+        };
+        let response = HdxIdpsResponse {
+            data: vec![
+                idp("2024-03-01", 100),
+                idp("2024-03-01", 300),
+                idp("2024-04-01", 300),
+            ]
+            .into(),
+        };
+
+        let change = latest_change(&response.idps_by_period()).unwrap();
+        assert_eq!(change.previous, 400);
+        assert!((change.percent_change() + 25.0).abs() < 0.01);
+
+        let single = HdxIdpsResponse {
+            data: vec![idp("2024-03-01", 100)].into(),
+        };
+        assert!(latest_change(&single.idps_by_period()).is_none());
+    }
+
+    #[test]
+    fn test_staple_price_changes() {
+        let price =
+            |commodity: &str, category: &str, market: &str, month: &str, value: f64| HdxFoodPrice {
+                reference_period_start: Some(format!("{}T00:00:00", month)),
+                location_code: "SDN".to_string(),
+                market_name: market.to_string(),
+                commodity_category: category.to_string(),
+                commodity_name: commodity.to_string(),
+                currency_code: "SDG".to_string(),
+                price: Some(value),
+                price_type: "Retail".to_string(),
+                unit: "KG".to_string(),
+                ..Default::default()
+            };
+        let response = HdxFoodPricesResponse {
+            data: vec![
+                price(
+                    "Sorghum",
+                    "cereals and tubers",
+                    "El Fasher",
+                    "2024-04-15",
+                    100.0,
+                ),
+                price(
+                    "Sorghum",
+                    "cereals and tubers",
+                    "El Fasher",
+                    "2024-05-01",
+                    160.0,
+                ),
+                price(
+                    "Sorghum",
+                    "cereals and tubers",
+                    "Kassala",
+                    "2024-04-01",
+                    100.0,
+                ),
+                price(
+                    "Sorghum",
+                    "cereals and tubers",
+                    "Kassala",
+                    "2024-05-01",
+                    130.0,
+                ),
+                price(
+                    "Sorghum",
+                    "cereals and tubers",
+                    "Kosti",
+                    "2024-04-01",
+                    100.0,
+                ),
+                price(
+                    "Sorghum",
+                    "cereals and tubers",
+                    "Kosti",
+                    "2024-05-01",
+                    300.0,
+                ),
+                // A gap month is not month-over-month
+                price("Lentils", "pulses and nuts", "Kassala", "2024-02-01", 100.0),
+                price("Lentils", "pulses and nuts", "Kassala", "2024-05-01", 200.0),
+                // Not a staple
+                price(
+                    "Tomatoes",
+                    "vegetables and fruits",
+                    "Kassala",
+                    "2024-04-01",
+                    10.0,
+                ),
+                price(
+                    "Tomatoes",
+                    "vegetables and fruits",
+                    "Kassala",
+                    "2024-05-01",
+                    50.0,
+                ),
+            ]
+            .into(),
+        };
+
+        let changes = response.staple_price_changes();
+        assert_eq!(changes.len(), 1);
+        let sorghum = &changes[0];
+        assert_eq!(sorghum.commodity, "Sorghum");
+        assert_eq!(sorghum.markets, 3);
+        assert_eq!(sorghum.month, NaiveDate::from_ymd_opt(2024, 5, 1).unwrap());
+        assert!((sorghum.median_percent - 60.0).abs() < 0.01);
+    }
 }
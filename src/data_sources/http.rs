@@ -0,0 +1,377 @@
+//! Shared HTTP client construction for data source clients.
+//!
+//! Every data source client talks to its API through an [`HttpClient`]
+//! built here, so deployments behind a proxy, a TLS-intercepting gateway
+//! or a slow uplink configure it once (see `DASHBOARD_HTTP_*`) rather than
+//! per source. Clients built with their plain constructors use
+//! [`HttpClientConfig::default`]; [`crate::dashboard::Dashboard::with_http_client`]
+//! hands a configured client to every source.
+//!
+//! Response bodies are read through [`HttpClient::read_json`] and
+//! [`HttpClient::read_text`], which refuse bodies over
+//! [`HttpClientConfig::max_response_bytes`]. JSON is parsed as it arrives
+//! rather than after buffering the whole body, so a misbehaving upstream
+//! sending hundreds of megabytes costs neither that much memory nor the
+//! dashboard refresh.
+//!
+//! # Privacy
+//!
+//! Only outbound requests to public APIs are configured here. The user
+//! agent names the software, never the operator's users.
+
+use std::io::Read;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use super::lenient;
+
+/// Default time limit for a whole request, including reading the body.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time limit for establishing a connection.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time an idle pooled connection is kept open.
+pub const DEFAULT_POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Default largest response body read, in bytes.
+pub const DEFAULT_MAX_RESPONSE_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Body chunks buffered between download and JSON parser.
+const CHUNKS_IN_FLIGHT: usize = 16;
+
+/// How to build the HTTP client shared by data sources.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpClientConfig {
+    /// `User-Agent` sent with every request. Wikimedia requests still name
+    /// the application, as its API policy requires.
+    pub user_agent: String,
+
+    /// Time limit for a whole request.
+    pub timeout: Duration,
+
+    /// Time limit for establishing a connection.
+    pub connect_timeout: Duration,
+
+    /// Proxy for every request (e.g., `http://proxy.internal:3128`). Without
+    /// one, the `HTTP_PROXY`/`HTTPS_PROXY` environment is honoured.
+    pub proxy: Option<String>,
+
+    /// PEM file of extra root certificates to trust, for TLS-intercepting
+    /// gateways.
+    pub ca_cert: Option<PathBuf>,
+
+    /// Most idle connections kept per host, or unlimited.
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// Time an idle pooled connection is kept open.
+    pub pool_idle_timeout: Duration,
+
+    /// Largest response body read; longer responses fail.
+    pub max_response_bytes: u64,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            user_agent: default_user_agent(),
+            timeout: DEFAULT_TIMEOUT,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            proxy: None,
+            ca_cert: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: DEFAULT_POOL_IDLE_TIMEOUT,
+            max_response_bytes: DEFAULT_MAX_RESPONSE_BYTES,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Build a client with these settings.
+    ///
+    /// Fails on an invalid proxy URL or an unreadable or invalid CA file.
+    pub fn build(&self) -> anyhow::Result<HttpClient> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(&self.user_agent)
+            .timeout(self.timeout)
+            .connect_timeout(self.connect_timeout)
+            .pool_idle_timeout(self.pool_idle_timeout);
+
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        if let Some(path) = &self.ca_cert {
+            let pem = std::fs::read(path).map_err(|e| {
+                anyhow::anyhow!("cannot read CA certificate '{}': {}", path.display(), e)
+            })?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem)?;
+            anyhow::ensure!(
+                !certs.is_empty(),
+                "'{}' holds no certificates",
+                path.display()
+            );
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        Ok(HttpClient {
+            client: builder.build()?,
+            max_response_bytes: self.max_response_bytes,
+        })
+    }
+}
+
+/// A `reqwest::Client` that reads response bodies within a size limit.
+///
+/// Requests are built through the wrapped client, which it dereferences to.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::Client,
+    max_response_bytes: u64,
+}
+
+impl Deref for HttpClient {
+    type Target = reqwest::Client;
+
+    fn deref(&self) -> &reqwest::Client {
+        &self.client
+    }
+}
+
+impl HttpClient {
+    /// Largest response body read.
+    pub fn max_response_bytes(&self) -> u64 {
+        self.max_response_bytes
+    }
+
+    /// Parse `response`'s JSON body as it arrives.
+    ///
+    /// Fails without reading further once the body exceeds the limit,
+    /// including up front when its `Content-Length` does. Records skipped
+    /// by a [`super::LenientVec`] count towards the enclosing
+    /// [`super::count_parse_warnings`].
+    pub async fn read_json<T>(&self, mut response: reqwest::Response) -> anyhow::Result<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        self.check_length(&response)?;
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(CHUNKS_IN_FLIGHT);
+        let parser = tokio::task::spawn_blocking(move || {
+            lenient::take_skipped();
+            let parsed = serde_json::from_reader::<_, T>(std::io::BufReader::new(ChunkReader {
+                receiver,
+                chunk: None,
+                offset: 0,
+            }));
+            (parsed, lenient::take_skipped())
+        });
+
+        let mut read = 0u64;
+        while let Some(chunk) = response.chunk().await? {
+            read += chunk.len() as u64;
+            self.check_read(&response, read)?;
+            if sender.send(chunk).await.is_err() {
+                // The parser stopped early, with a value or an error
+                break;
+            }
+        }
+        drop(sender);
+
+        let (parsed, skipped) = parser.await?;
+        lenient::record_parse_warnings(skipped);
+        Ok(parsed?)
+    }
+
+    /// Read `response`'s body as text.
+    ///
+    /// Fails once the body exceeds the limit, as [`HttpClient::read_json`].
+    pub async fn read_text(&self, mut response: reqwest::Response) -> anyhow::Result<String> {
+        self.check_length(&response)?;
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            self.check_read(&response, body.len() as u64)?;
+        }
+
+        Ok(String::from_utf8(body)?)
+    }
+
+    fn check_length(&self, response: &reqwest::Response) -> anyhow::Result<()> {
+        if let Some(length) = response.content_length() {
+            self.check_read(response, length)?;
+        }
+        Ok(())
+    }
+
+    fn check_read(&self, response: &reqwest::Response, read: u64) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            read <= self.max_response_bytes,
+            "response from {} exceeds {} bytes",
+            response.url(),
+            self.max_response_bytes
+        );
+        Ok(())
+    }
+}
+
+/// Blocking reader over body chunks sent by [`HttpClient::read_json`].
+struct ChunkReader<B> {
+    receiver: tokio::sync::mpsc::Receiver<B>,
+    chunk: Option<B>,
+    offset: usize,
+}
+
+impl<B: AsRef<[u8]>> Read for ChunkReader<B> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if let Some(chunk) = &self.chunk {
+                let rest = &chunk.as_ref()[self.offset..];
+                if !rest.is_empty() {
+                    let n = rest.len().min(buf.len());
+                    buf[..n].copy_from_slice(&rest[..n]);
+                    self.offset += n;
+                    return Ok(n);
+                }
+            }
+
+            match self.receiver.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = Some(chunk);
+                    self.offset = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// `infrared/<version>`.
+pub fn default_user_agent() -> String {
+    format!("infrared/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// A client with the default settings, for clients built without one.
+pub(crate) fn default_client() -> HttpClient {
+    HttpClientConfig::default()
+        .build()
+        .expect("default HTTP client settings are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        assert!(HttpClientConfig::default().build().is_ok());
+
+        let proxied = HttpClientConfig {
+            proxy: Some("http://proxy.internal:3128".to_string()),
+            pool_max_idle_per_host: Some(4),
+            ..Default::default()
+        };
+        assert!(proxied.build().is_ok());
+
+        let invalid_proxy = HttpClientConfig {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(invalid_proxy.build().is_err());
+
+        let missing_ca = HttpClientConfig {
+            ca_cert: Some(PathBuf::from("/nonexistent/ca.pem")),
+            ..Default::default()
+        };
+        assert!(missing_ca.build().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_response_size_limit() {
+        use axum::body::{Body, Bytes};
+        use axum::routing::get;
+
+        // 8 KiB of JSON, sent with a length and streamed without one
+        let items = vec![1u8; 4096];
+        let json = serde_json::to_string(&items).unwrap();
+        let chunks: Vec<Bytes> = json
+            .as_bytes()
+            .chunks(1000)
+            .map(Bytes::copy_from_slice)
+            .collect();
+        let app = axum::Router::new()
+            .route("/sized", get(move || async move { json }))
+            .route(
+                "/streamed",
+                get(move || async move {
+                    Body::from_stream(futures_util::stream::iter(
+                        chunks.into_iter().map(Ok::<_, std::io::Error>),
+                    ))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = HttpClientConfig::default().build().unwrap();
+        for path in ["/sized", "/streamed"] {
+            let response = client
+                .get(format!("{}{}", base, path))
+                .send()
+                .await
+                .unwrap();
+            let parsed: Vec<u8> = client.read_json(response).await.unwrap();
+            assert_eq!(parsed, items);
+        }
+
+        let limited = HttpClientConfig {
+            max_response_bytes: 4096,
+            ..Default::default()
+        }
+        .build()
+        .unwrap();
+        for path in ["/sized", "/streamed"] {
+            let response = limited
+                .get(format!("{}{}", base, path))
+                .send()
+                .await
+                .unwrap();
+            let error = limited.read_json::<Vec<u8>>(response).await.unwrap_err();
+            assert!(
+                error.to_string().contains("exceeds 4096 bytes"),
+                "{}",
+                error
+            );
+        }
+        let response = limited.get(format!("{}/sized", base)).send().await.unwrap();
+        assert!(limited.read_text(response).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parse_warnings_counted() {
+        use super::super::{LenientVec, count_parse_warnings};
+        use axum::routing::get;
+
+        let app = axum::Router::new().route("/", get(|| async { r#"[1, "two", 3, -4]"# }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = default_client();
+        let (parsed, warnings) = count_parse_warnings(async {
+            let response = client.get(&base).send().await.unwrap();
+            client.read_json::<LenientVec<u8>>(response).await.unwrap()
+        })
+        .await;
+        assert_eq!(parsed.into_vec(), vec![1, 3]);
+        assert_eq!(warnings, 2);
+    }
+}
@@ -20,13 +20,19 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
 /// Base URL for the IODA API.
 const IODA_API_BASE: &str = "https://api.ioda.inetintel.cc.gatech.edu/v2";
 
+/// Base URL of the public IODA dashboard.
+const IODA_DASHBOARD_BASE: &str = "https://ioda.inetintel.cc.gatech.edu";
+
 /// Client for querying IODA's Internet outage detection API.
 #[derive(Clone)]
 pub struct IodaClient {
-    client: reqwest::Client,
+    client: HttpClient,
     base_url: String,
 }
 
@@ -40,7 +46,7 @@ impl IodaClient {
     /// Create a new IODA client with default settings.
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: IODA_API_BASE.to_string(),
         }
     }
@@ -48,11 +54,17 @@ impl IodaClient {
     /// Create a new IODA client with a custom base URL (for testing).
     pub fn with_base_url(base_url: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: base_url.to_string(),
         }
     }
 
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Fetch outage alerts for a specific country.
     ///
     /// # Arguments
@@ -84,7 +96,10 @@ impl IodaClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<IodaAlertsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<IodaAlertsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -105,7 +120,10 @@ impl IodaClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<IodaAlertsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<IodaAlertsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -128,7 +146,10 @@ impl IodaClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<IodaEventsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<IodaEventsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -156,7 +177,10 @@ impl IodaClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<IodaSignalsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<IodaSignalsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -179,7 +203,10 @@ impl IodaClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<IodaSummaryResponse>().await?;
+        let data = self
+            .client
+            .read_json::<IodaSummaryResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -211,7 +238,7 @@ impl IodaClient {
 pub struct IodaAlertsResponse {
     /// List of outage alerts.
     #[serde(default)]
-    pub data: Vec<IodaAlert>,
+    pub data: LenientVec<IodaAlert>,
 }
 
 /// A single outage alert from IODA.
@@ -275,7 +302,7 @@ impl IodaAlert {
 pub struct IodaEventsResponse {
     /// List of outage events.
     #[serde(default)]
-    pub data: Vec<IodaEvent>,
+    pub data: LenientVec<IodaEvent>,
 }
 
 /// An outage event (aggregated from multiple alerts).
@@ -328,7 +355,7 @@ impl IodaEvent {
 pub struct IodaSignalsResponse {
     /// Time series data per data source.
     #[serde(default)]
-    pub data: Vec<IodaSignalSeries>,
+    pub data: LenientVec<IodaSignalSeries>,
 }
 
 /// Time series data from a single data source.
@@ -355,7 +382,9 @@ impl IodaSignalSeries {
 
     /// Get the latest timestamp from the time series.
     pub fn latest_timestamp(&self) -> Option<i64> {
-        self.values.last().and_then(|v| v.first().map(|t| *t as i64))
+        self.values
+            .last()
+            .and_then(|v| v.first().map(|t| *t as i64))
     }
 }
 
@@ -364,7 +393,7 @@ impl IodaSignalSeries {
 pub struct IodaSummaryResponse {
     /// Summary data for each entity.
     #[serde(default)]
-    pub data: Vec<IodaSummary>,
+    pub data: LenientVec<IodaSummary>,
 }
 
 /// Summary of outage scores for an entity.
@@ -411,6 +440,19 @@ pub struct IodaScores {
     pub ucsd_nt: f64,
 }
 
+/// Link to the IODA dashboard for an entity, showing the day either side of
+/// the Unix time `time`.
+pub fn dashboard_url(entity_type: &str, entity_code: &str, time: i64) -> String {
+    format!(
+        "{}/{}/{}?from={}&until={}",
+        IODA_DASHBOARD_BASE,
+        entity_type,
+        entity_code,
+        time - 86_400,
+        time + 86_400
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -430,6 +472,10 @@ mod tests {
         };
 
         assert!((alert.drop_percentage() - 80.0).abs() < 0.01);
+        assert_eq!(
+            dashboard_url(&alert.entity_type, &alert.entity_code, alert.time),
+            "https://ioda.inetintel.cc.gatech.edu/country/US?from=1701413600&until=1701586400"
+        );
     }
 
     #[test]
@@ -0,0 +1,346 @@
+//! IOM DTM (Displacement Tracking Matrix) API client.
+//!
+//! The DTM is IOM's system for tracking displacement. Field teams report
+//! internally displaced person (IDP) counts per administrative area in
+//! assessment rounds, which during emergencies are published within days,
+//! well ahead of the aggregated figures that reach HDX.
+//!
+//! # Features
+//!
+//! - IDP counts at country (admin 0), region (admin 1) and district (admin 2) level
+//! - Reporting dates and assessment round numbers
+//! - Primary displacement reason (conflict, disaster, ...)
+//!
+//! # API Reference
+//!
+//! See: <https://dtm.iom.int/data-and-analysis/dtm-api>
+//!
+//! # Authentication
+//!
+//! Requires a free subscription key from the DTM API portal.
+//!
+//! # Privacy
+//!
+//! All data is aggregate counts per administrative area. No individual persons are tracked.
+
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
+/// Base URL for the DTM API.
+const DTM_API_BASE: &str = "https://dtmapi.iom.int/v3";
+
+/// Base URL of the public DTM site.
+const DTM_SITE_BASE: &str = "https://dtm.iom.int";
+
+/// Client for querying IOM's Displacement Tracking Matrix API.
+#[derive(Clone)]
+pub struct IomDtmClient {
+    client: HttpClient,
+    base_url: String,
+    subscription_key: String,
+}
+
+impl IomDtmClient {
+    /// Create a new DTM client.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscription_key` - Key obtained from the DTM API portal.
+    pub fn new(subscription_key: &str) -> Self {
+        Self::with_base_url(DTM_API_BASE, subscription_key)
+    }
+
+    /// Create a client with a custom base URL (for testing).
+    pub fn with_base_url(base_url: &str, subscription_key: &str) -> Self {
+        Self {
+            client: super::http::default_client(),
+            base_url: base_url.to_string(),
+            subscription_key: subscription_key.to_string(),
+        }
+    }
+
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Get admin 1 (region-level) displacement figures reported in a date range.
+    ///
+    /// # Arguments
+    ///
+    /// * `country` - Optional country name filter (e.g., "Sudan")
+    /// * `from` - First reporting date to include
+    /// * `to` - Last reporting date to include
+    pub async fn get_admin1_displacement(
+        &self,
+        country: Option<&str>,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> anyhow::Result<DtmResponse> {
+        let mut url = format!(
+            "{}/displacement/admin1?FromReportingDate={}&ToReportingDate={}",
+            self.base_url, from, to
+        );
+        if let Some(country) = country {
+            url.push_str(&format!("&CountryName={}", urlencoding::encode(country)));
+        }
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Ocp-Apim-Subscription-Key", &self.subscription_key)
+            .send()
+            .await?
+            .error_for_status()?;
+        let data = self.client.read_json::<DtmResponse>(response).await?;
+
+        if !data.is_success {
+            anyhow::bail!("DTM API error: {}", data.message);
+        }
+        Ok(data)
+    }
+
+    /// Convenience method: admin 1 figures reported in the last N days.
+    pub async fn get_recent_admin1_displacement(
+        &self,
+        country: Option<&str>,
+        days: u32,
+    ) -> anyhow::Result<DtmResponse> {
+        let to = Utc::now().date_naive();
+        let from = to - Duration::days(days.into());
+        self.get_admin1_displacement(country, from, to).await
+    }
+}
+
+// ============================================================================
+// Response types
+// ============================================================================
+
+/// Response envelope from the DTM API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DtmResponse {
+    /// Whether the request succeeded.
+    #[serde(default, rename = "isSuccess")]
+    pub is_success: bool,
+
+    /// Status or error message.
+    #[serde(default)]
+    pub message: String,
+
+    /// Displacement records.
+    #[serde(default)]
+    pub result: LenientVec<DtmDisplacementRecord>,
+}
+
+/// IDP figures for one administrative area in one assessment round.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DtmDisplacementRecord {
+    /// DTM operation name (e.g., "Sudan Conflict").
+    #[serde(default)]
+    pub operation: String,
+
+    /// Country name.
+    #[serde(default, rename = "admin0Name")]
+    pub admin0_name: String,
+
+    /// ISO 3166-1 alpha-3 country code.
+    #[serde(default, rename = "admin0Pcode")]
+    pub admin0_pcode: String,
+
+    /// Admin 1 area name.
+    #[serde(default, rename = "admin1Name")]
+    pub admin1_name: String,
+
+    /// Admin 1 place code.
+    #[serde(default, rename = "admin1Pcode")]
+    pub admin1_pcode: String,
+
+    /// Number of IDPs present in the area.
+    #[serde(default, rename = "numPresentIdpInd")]
+    pub num_present_idp_ind: u64,
+
+    /// Reporting date (e.g., "2024-05-01T00:00:00").
+    #[serde(default, rename = "reportingDate")]
+    pub reporting_date: String,
+
+    /// Assessment round number.
+    #[serde(default, rename = "roundNumber")]
+    pub round_number: u32,
+
+    /// Primary reason for displacement.
+    #[serde(default, rename = "displacementReason")]
+    pub displacement_reason: String,
+}
+
+impl DtmDisplacementRecord {
+    /// Parse the reporting date.
+    pub fn reporting_date(&self) -> Option<NaiveDate> {
+        NaiveDateTime::parse_from_str(&self.reporting_date, "%Y-%m-%dT%H:%M:%S")
+            .map(|dt| dt.date())
+            .or_else(|_| NaiveDate::parse_from_str(&self.reporting_date, "%Y-%m-%d"))
+            .ok()
+    }
+}
+
+impl DtmResponse {
+    /// Latest IDP figure per admin 1 area, with the change since the
+    /// earliest figure in the response for the same area.
+    ///
+    /// Areas are returned largest increase first.
+    pub fn area_trends(&self) -> Vec<DtmAreaTrend> {
+        let mut by_area: HashMap<(&str, &str), Vec<&DtmDisplacementRecord>> = HashMap::new();
+        for record in &self.result {
+            if record.reporting_date().is_some() {
+                by_area
+                    .entry((&record.admin0_pcode, &record.admin1_pcode))
+                    .or_default()
+                    .push(record);
+            }
+        }
+
+        let mut trends: Vec<DtmAreaTrend> = by_area
+            .into_values()
+            .filter_map(|mut records| {
+                records.sort_by_key(|r| (r.reporting_date(), r.round_number));
+                let earliest = records.first()?;
+                let latest = records.last()?;
+                Some(DtmAreaTrend {
+                    country: latest.admin0_name.clone(),
+                    country_code: latest.admin0_pcode.clone(),
+                    admin1: latest.admin1_name.clone(),
+                    admin1_pcode: latest.admin1_pcode.clone(),
+                    displacement_reason: latest.displacement_reason.clone(),
+                    idps: latest.num_present_idp_ind,
+                    previous_idps: (records.len() > 1).then_some(earliest.num_present_idp_ind),
+                    reporting_date: latest.reporting_date()?,
+                })
+            })
+            .collect();
+
+        trends.sort_by_key(|t| std::cmp::Reverse(t.increase()));
+        trends
+    }
+}
+
+/// Displacement trend for one admin 1 area.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DtmAreaTrend {
+    /// Country name.
+    pub country: String,
+
+    /// ISO 3166-1 alpha-3 country code.
+    pub country_code: String,
+
+    /// Admin 1 area name.
+    pub admin1: String,
+
+    /// Admin 1 place code.
+    pub admin1_pcode: String,
+
+    /// Primary reason for displacement in the latest round.
+    pub displacement_reason: String,
+
+    /// IDPs present in the latest round.
+    pub idps: u64,
+
+    /// IDPs present in the earliest round seen, if there was more than one.
+    pub previous_idps: Option<u64>,
+
+    /// Reporting date of the latest round.
+    pub reporting_date: NaiveDate,
+}
+
+impl DtmAreaTrend {
+    /// Newly displaced people since the earliest round (0 if none or falling).
+    pub fn increase(&self) -> u64 {
+        self.previous_idps
+            .map_or(0, |previous| self.idps.saturating_sub(previous))
+    }
+}
+
+/// Link to the DTM page of a country, by name.
+pub fn country_url(country: &str) -> String {
+    format!("{}/{}", DTM_SITE_BASE, super::country_slug(country))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(pcode: &str, date: &str, round: u32, idps: u64) -> DtmDisplacementRecord {
+        DtmDisplacementRecord {
+            operation: "Sudan Conflict".to_string(),
+            admin0_name: "Sudan".to_string(),
+            admin0_pcode: "SDN".to_string(),
+            admin1_name: format!("Area {}", pcode),
+            admin1_pcode: pcode.to_string(),
+            num_present_idp_ind: idps,
+            reporting_date: date.to_string(),
+            round_number: round,
+            displacement_reason: "Conflict".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let json = r#"{
+            "isSuccess": true,
+            "message": "",
+            "result": [{
+                "operation": "Sudan Conflict",
+                "admin0Name": "Sudan",
+                "admin0Pcode": "SDN",
+                "admin1Name": "North Darfur",
+                "admin1Pcode": "SD02",
+                "numPresentIdpInd": 1250000,
+                "reportingDate": "2024-05-01T00:00:00",
+                "roundNumber": 12,
+                "displacementReason": "Conflict"
+            }]
+        }"#;
+
+        let response: DtmResponse = serde_json::from_str(json).unwrap();
+        assert!(response.is_success);
+        assert_eq!(response.result[0].admin1_pcode, "SD02");
+        assert_eq!(
+            response.result[0].reporting_date(),
+            NaiveDate::from_ymd_opt(2024, 5, 1)
+        );
+    }
+
+    #[test]
+    fn test_area_trends() {
+        let response = DtmResponse {
+            is_success: true,
+            message: String::new(),
+            result: vec![
+                record("SD02", "2024-05-08T00:00:00", 13, 90_000),
+                record("SD02", "2024-05-01T00:00:00", 12, 40_000),
+                record("SD03", "2024-05-01T00:00:00", 12, 10_000),
+                record("SD03", "2024-05-08T00:00:00", 13, 8_000),
+                record("SD04", "2024-05-08T00:00:00", 13, 5_000),
+                record("SD05", "not a date", 13, 5_000),
+            ]
+            .into(),
+        };
+
+        let trends = response.area_trends();
+        assert_eq!(trends.len(), 3);
+        assert_eq!(trends[0].admin1_pcode, "SD02");
+        assert_eq!(trends[0].idps, 90_000);
+        assert_eq!(trends[0].increase(), 50_000);
+
+        let falling = trends.iter().find(|t| t.admin1_pcode == "SD03").unwrap();
+        assert_eq!(falling.increase(), 0);
+
+        let single = trends.iter().find(|t| t.admin1_pcode == "SD04").unwrap();
+        assert_eq!(single.previous_idps, None);
+        assert_eq!(single.increase(), 0);
+    }
+}
@@ -0,0 +1,249 @@
+//! Record lists that survive schema drift.
+//!
+//! Upstream APIs rename fields, change types and occasionally send a
+//! half-written record. Deserializing a response straight into `Vec<T>`
+//! fails the whole source over one bad record, hiding every good one.
+//! [`LenientVec`] parses each record on its own instead: a record that no
+//! longer fits its model is logged and skipped, and counted as a parse
+//! warning, and the rest of the response is kept.
+//!
+//! Fields a record carries that its model does not know are logged at
+//! debug level but not counted: models read only the fields Infrared uses,
+//! so unknown fields are expected. A renamed field the model requires
+//! makes the record fail, and is counted.
+//!
+//! Warnings are tallied per response by [`crate::data_sources::HttpClient::read_json`]
+//! and collected per source with [`count_parse_warnings`].
+//!
+//! # Privacy
+//!
+//! Warnings name the record type, the error and the unknown field paths,
+//! never record contents.
+
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use serde::de::{Deserializer, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use tracing::{debug, warn};
+
+thread_local! {
+    /// Records skipped while parsing on this thread since the last
+    /// [`take_skipped`].
+    static SKIPPED: Cell<u64> = const { Cell::new(0) };
+}
+
+tokio::task_local! {
+    /// Parse warnings of the responses read within [`count_parse_warnings`].
+    static PARSE_WARNINGS: Cell<u64>;
+}
+
+/// A list of records in which records that fail to parse are skipped.
+///
+/// Serializes as, and dereferences to, a plain `Vec<T>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LenientVec<T>(Vec<T>);
+
+impl<T> LenientVec<T> {
+    /// The records that parsed.
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Default for LenientVec<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> From<Vec<T>> for LenientVec<T> {
+    fn from(records: Vec<T>) -> Self {
+        Self(records)
+    }
+}
+
+impl<T> From<LenientVec<T>> for Vec<T> {
+    fn from(records: LenientVec<T>) -> Self {
+        records.0
+    }
+}
+
+impl<T> Deref for LenientVec<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for LenientVec<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        &mut self.0
+    }
+}
+
+impl<T> IntoIterator for LenientVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LenientVec<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: Serialize> Serialize for LenientVec<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for LenientVec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(LenientVisitor(PhantomData))
+    }
+}
+
+struct LenientVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for LenientVisitor<T> {
+    type Value = LenientVec<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let record_type = std::any::type_name::<T>();
+        let mut records = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        let mut unknown = BTreeSet::new();
+        let mut index = 0usize;
+
+        // Each record is read whole first, so a bad one cannot derail the rest
+        while let Some(value) = seq.next_element::<serde_json::Value>()? {
+            let parsed = serde_ignored::deserialize(value, |path| {
+                unknown.insert(path.to_string());
+            });
+            match parsed {
+                Ok(record) => records.push(record),
+                Err(e) => {
+                    SKIPPED.with(|skipped| skipped.set(skipped.get() + 1));
+                    warn!(
+                        record_type,
+                        index,
+                        error = %e,
+                        "Skipping data source record that does not match its model"
+                    );
+                }
+            }
+            index += 1;
+        }
+
+        if !unknown.is_empty() {
+            debug!(
+                record_type,
+                fields = ?unknown,
+                "Data source records carry fields their model does not know"
+            );
+        }
+
+        Ok(LenientVec(records))
+    }
+}
+
+/// Records skipped on this thread since the last call, resetting the count.
+pub(crate) fn take_skipped() -> u64 {
+    SKIPPED.with(|skipped| skipped.replace(0))
+}
+
+/// Add `warnings` to the count of the enclosing [`count_parse_warnings`],
+/// if any.
+pub(crate) fn record_parse_warnings(warnings: u64) {
+    let _ = PARSE_WARNINGS.try_with(|count| count.set(count.get() + warnings));
+}
+
+/// Run `future`, returning its output with the parse warnings of every
+/// response it read.
+pub async fn count_parse_warnings<F: Future>(future: F) -> (F::Output, u64) {
+    PARSE_WARNINGS
+        .scope(Cell::new(0), async move {
+            let output = future.await;
+            (output, PARSE_WARNINGS.with(Cell::get))
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record {
+        name: String,
+        count: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Response {
+        #[serde(default)]
+        data: LenientVec<Record>,
+    }
+
+    #[test]
+    fn test_bad_records_are_skipped() {
+        take_skipped();
+        let response: Response = serde_json::from_str(
+            r#"{"data": [
+                {"name": "a", "count": 1},
+                {"name": "b", "count": "two"},
+                {"title": "c", "count": 3},
+                {"name": "d", "count": 4, "added_later": true}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            response.data.into_vec(),
+            vec![
+                Record {
+                    name: "a".to_string(),
+                    count: 1
+                },
+                Record {
+                    name: "d".to_string(),
+                    count: 4
+                },
+            ]
+        );
+        assert_eq!(take_skipped(), 2);
+
+        // A list that is not a list still fails the response
+        assert!(serde_json::from_str::<Response>(r#"{"data": {"name": "a"}}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_count_parse_warnings() {
+        let ((), warnings) = count_parse_warnings(async {
+            record_parse_warnings(2);
+            record_parse_warnings(1);
+        })
+        .await;
+        assert_eq!(warnings, 3);
+
+        // Outside a count, warnings go nowhere
+        record_parse_warnings(5);
+    }
+}
@@ -11,29 +11,77 @@
 //!
 //! - [`ioda`]: IODA (Internet Outage Detection and Analysis) - specialized for outage detection
 //! - [`cloudflare`]: Cloudflare Radar - traffic volume and anomaly data
+//! - [`tor_metrics`]: Tor Metrics - per-country Tor user estimates (censorship and outages)
+//! - [`wikimedia`]: Wikimedia pageviews - ambient human activity per country/language
 //!
 //! ## Humanitarian Data
 //!
 //! - [`hdx_hapi`]: HDX HAPI (OCHA) - humanitarian indicators, refugees, IDPs, food security
+//! - [`acaps`]: ACAPS - INFORM Severity Index of ongoing crises
 //! - [`reliefweb`]: ReliefWeb - disasters, reports, humanitarian updates
+//! - [`iom_dtm`]: IOM DTM - subnational displacement tracking
+//! - [`emdat`]: EM-DAT - historical disaster frequency baselines per country
 //!
 //! ## Conflict Data
 //!
 //! - [`acled`]: ACLED - armed conflict events, protests, violence against civilians
 //!
+//! Every client sends its requests through an HTTP client built by [`http`].
+//! Record lists are read with [`lenient`], so a record that no longer
+//! matches its model is skipped rather than failing the whole response.
+//!
 //! # Privacy
 //!
 //! These data sources provide only aggregate, country-level statistics.
 //! No individual user data is collected or processed.
 
+pub mod acaps;
 pub mod acled;
 pub mod cloudflare;
+pub mod emdat;
 pub mod hdx_hapi;
+pub mod http;
 pub mod ioda;
+pub mod iom_dtm;
+pub mod lenient;
 pub mod reliefweb;
+pub mod tor_metrics;
+pub mod wikimedia;
 
+pub use acaps::AcapsClient;
 pub use acled::AcledClient;
 pub use cloudflare::CloudflareRadarClient;
+pub use emdat::{EmdatBaseline, EmdatClient};
 pub use hdx_hapi::HdxHapiClient;
+pub use http::{HttpClient, HttpClientConfig};
 pub use ioda::IodaClient;
+pub use iom_dtm::IomDtmClient;
+pub use lenient::{LenientVec, count_parse_warnings};
 pub use reliefweb::ReliefWebClient;
+pub use tor_metrics::TorMetricsClient;
+pub use wikimedia::WikimediaClient;
+
+/// Lowercase, hyphenated form of a country name, as used in the paths of
+/// sites that key pages by name (e.g., "South Sudan" becomes "south-sudan").
+pub(crate) fn country_slug(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_country_slug() {
+        assert_eq!(country_slug("Sudan"), "sudan");
+        assert_eq!(country_slug("South Sudan"), "south-sudan");
+        assert_eq!(
+            country_slug("Congo, Democratic Republic of the"),
+            "congo-democratic-republic-of-the"
+        );
+    }
+}
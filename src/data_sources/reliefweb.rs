@@ -26,13 +26,16 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
 /// Base URL for the ReliefWeb API.
 const RELIEFWEB_API_BASE: &str = "https://api.reliefweb.int/v1";
 
 /// Client for querying the ReliefWeb humanitarian data API.
 #[derive(Clone)]
 pub struct ReliefWebClient {
-    client: reqwest::Client,
+    client: HttpClient,
     base_url: String,
     app_name: String,
 }
@@ -51,7 +54,7 @@ impl ReliefWebClient {
     /// * `app_name` - Application name for API identification (required as of Nov 2025).
     pub fn new(app_name: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: RELIEFWEB_API_BASE.to_string(),
             app_name: app_name.to_string(),
         }
@@ -60,12 +63,18 @@ impl ReliefWebClient {
     /// Create a client with a custom base URL (for testing).
     pub fn with_base_url(base_url: &str, app_name: &str) -> Self {
         Self {
-            client: reqwest::Client::new(),
+            client: super::http::default_client(),
             base_url: base_url.to_string(),
             app_name: app_name.to_string(),
         }
     }
 
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Get disasters list, optionally filtered by country or status.
     ///
     /// # Arguments
@@ -99,7 +108,10 @@ impl ReliefWebClient {
         }
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebDisastersResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebDisastersResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -111,7 +123,10 @@ impl ReliefWebClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebDisasterResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebDisasterResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -148,7 +163,10 @@ impl ReliefWebClient {
         }
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebReportsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebReportsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -157,7 +175,10 @@ impl ReliefWebClient {
         let url = format!("{}/reports/{}?appname={}", self.base_url, id, self.app_name);
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebReportResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebReportResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -166,7 +187,10 @@ impl ReliefWebClient {
     /// # Arguments
     ///
     /// * `limit` - Maximum number of results
-    pub async fn get_countries(&self, limit: Option<u32>) -> anyhow::Result<ReliefWebCountriesResponse> {
+    pub async fn get_countries(
+        &self,
+        limit: Option<u32>,
+    ) -> anyhow::Result<ReliefWebCountriesResponse> {
         let limit = limit.unwrap_or(50).min(1000);
         let url = format!(
             "{}/countries?appname={}&limit={}",
@@ -174,7 +198,10 @@ impl ReliefWebClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebCountriesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebCountriesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -186,7 +213,10 @@ impl ReliefWebClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebCountryResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebCountryResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -215,7 +245,10 @@ impl ReliefWebClient {
         }
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebJobsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebJobsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -224,7 +257,10 @@ impl ReliefWebClient {
     /// # Arguments
     ///
     /// * `limit` - Maximum number of results
-    pub async fn get_training(&self, limit: Option<u32>) -> anyhow::Result<ReliefWebTrainingResponse> {
+    pub async fn get_training(
+        &self,
+        limit: Option<u32>,
+    ) -> anyhow::Result<ReliefWebTrainingResponse> {
         let limit = limit.unwrap_or(50).min(1000);
         let url = format!(
             "{}/training?appname={}&limit={}&preset=latest",
@@ -232,12 +268,18 @@ impl ReliefWebClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebTrainingResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebTrainingResponse>(response)
+            .await?;
         Ok(data)
     }
 
     /// Get information sources.
-    pub async fn get_sources(&self, limit: Option<u32>) -> anyhow::Result<ReliefWebSourcesResponse> {
+    pub async fn get_sources(
+        &self,
+        limit: Option<u32>,
+    ) -> anyhow::Result<ReliefWebSourcesResponse> {
         let limit = limit.unwrap_or(50).min(1000);
         let url = format!(
             "{}/sources?appname={}&limit={}",
@@ -245,7 +287,10 @@ impl ReliefWebClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebSourcesResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebSourcesResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -270,7 +315,59 @@ impl ReliefWebClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebReportsResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebReportsResponse>(response)
+            .await?;
+        Ok(data)
+    }
+
+    /// Get situation reports published recently about any of several countries.
+    ///
+    /// All countries are covered by one query, which matters under the daily
+    /// call limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `countries` - ISO 3166-1 alpha-3 codes
+    /// * `days` - Number of days to look back
+    /// * `limit` - Maximum number of results
+    pub async fn get_situation_reports(
+        &self,
+        countries: &[&str],
+        days: u32,
+        limit: Option<u32>,
+    ) -> anyhow::Result<ReliefWebReportsResponse> {
+        let limit = limit.unwrap_or(50).min(1000);
+        let since = Utc::now() - chrono::Duration::days(days.into());
+        let mut url = format!(
+            "{}/reports?appname={}&limit={}&preset=latest&profile=list\
+             &fields[include][]=disaster&fields[include][]=format\
+             &filter[operator]=AND\
+             &filter[conditions][0][field]=format.name\
+             &filter[conditions][0][value]={}\
+             &filter[conditions][1][field]=date.created\
+             &filter[conditions][1][value][from]={}\
+             &filter[conditions][2][field]=country.iso3\
+             &filter[conditions][2][operator]=OR",
+            self.base_url,
+            self.app_name,
+            limit,
+            urlencoding::encode("Situation Report"),
+            urlencoding::encode(&since.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        );
+        for country in countries {
+            url.push_str(&format!(
+                "&filter[conditions][2][value][]={}",
+                urlencoding::encode(&country.to_lowercase())
+            ));
+        }
+
+        let response = self.client.get(&url).send().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebReportsResponse>(response)
+            .await?;
         Ok(data)
     }
 
@@ -303,7 +400,10 @@ impl ReliefWebClient {
         );
 
         let response = self.client.get(&url).send().await?;
-        let data = response.json::<ReliefWebDisastersResponse>().await?;
+        let data = self
+            .client
+            .read_json::<ReliefWebDisastersResponse>(response)
+            .await?;
         Ok(data)
     }
 }
@@ -325,7 +425,7 @@ pub struct ReliefWebListResponse<T> {
 
     /// List of data items.
     #[serde(default)]
-    pub data: Vec<ReliefWebItem<T>>,
+    pub data: LenientVec<ReliefWebItem<T>>,
 }
 
 /// A single item wrapper in ReliefWeb responses.
@@ -353,7 +453,7 @@ pub struct ReliefWebItem<T> {
 pub struct ReliefWebSingleResponse<T> {
     /// The data item.
     #[serde(default)]
-    pub data: Vec<ReliefWebItem<T>>,
+    pub data: LenientVec<ReliefWebItem<T>>,
 }
 
 // Disaster types
@@ -520,6 +620,11 @@ impl ReliefWebReportFields {
     pub fn country_name(&self) -> Option<&str> {
         self.primary_country.as_ref().map(|c| c.name.as_str())
     }
+
+    /// Check if this is a situation report.
+    pub fn is_situation_report(&self) -> bool {
+        self.format.iter().any(|f| f.name == "Situation Report")
+    }
 }
 
 // Country types
@@ -893,4 +998,19 @@ mod tests {
 
         assert_eq!(report.source_name(), Some("OCHA"));
     }
+
+    #[test]
+    fn test_situation_report_format() {
+        let json = r#"{
+            "title": "Sudan: Flash Update No. 4",
+            "format": [{"id": 10, "name": "Situation Report"}],
+            "disaster": [{"id": 52000, "name": "Sudan: Floods - Aug 2024", "glide": "FL-2024-000140-SDN"}],
+            "url": "https://reliefweb.int/node/4100000"
+        }"#;
+
+        let report: ReliefWebReportFields = serde_json::from_str(json).unwrap();
+        assert!(report.is_situation_report());
+        assert_eq!(report.disaster[0].id, 52000);
+        assert!(!ReliefWebReportFields::default().is_situation_report());
+    }
 }
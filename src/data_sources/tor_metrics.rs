@@ -0,0 +1,395 @@
+//! Tor Metrics client.
+//!
+//! The Tor Project publishes daily estimates of how many people connect to
+//! Tor from each country, both directly through relays and through bridges.
+//! Sudden changes are a strong signal:
+//!
+//! - **Spikes** usually mark the onset of censorship, as people turn to Tor
+//!   (and, once relays are blocked, to bridges) to reach blocked services
+//! - **Drops** usually mark a total connectivity loss, since Tor users go
+//!   offline along with everyone else
+//!
+//! # API Reference
+//!
+//! See: <https://metrics.torproject.org/stats.html>
+//!
+//! # Data Lag
+//!
+//! Estimates are published with a delay of two to three days.
+//!
+//! # Privacy
+//!
+//! All data is aggregate, noise-added user estimates per country. No individual users are tracked.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::http::HttpClient;
+
+/// Base URL for Tor Metrics.
+const TOR_METRICS_BASE: &str = "https://metrics.torproject.org";
+
+/// Days averaged into the baseline a day is compared against.
+pub const BASELINE_DAYS: usize = 7;
+
+/// Countries averaging fewer users than this are too noisy to judge.
+pub const MIN_BASELINE_USERS: f64 = 100.0;
+
+/// Usage at or above this multiple of the baseline is a spike.
+pub const SPIKE_RATIO: f64 = 2.0;
+
+/// Usage at or below this fraction of the baseline is a drop.
+pub const DROP_RATIO: f64 = 0.5;
+
+/// Client for querying Tor Metrics user estimates.
+#[derive(Clone)]
+pub struct TorMetricsClient {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl Default for TorMetricsClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TorMetricsClient {
+    /// Create a new Tor Metrics client.
+    pub fn new() -> Self {
+        Self::with_base_url(TOR_METRICS_BASE)
+    }
+
+    /// Create a client with a custom base URL (for testing).
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: super::http::default_client(),
+            base_url: base_url.to_string(),
+        }
+    }
+
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Fetch daily user estimates for one transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - Relay (direct) or bridge users
+    /// * `country` - Optional ISO 3166-1 alpha-2 code; all countries if omitted
+    /// * `start` - First day to include
+    /// * `end` - Last day to include
+    pub async fn get_user_estimates(
+        &self,
+        transport: TorTransport,
+        country: Option<&str>,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> anyhow::Result<Vec<TorUserEstimate>> {
+        let mut url = format!(
+            "{}/{}?start={}&end={}",
+            self.base_url,
+            transport.csv_path(),
+            start,
+            end
+        );
+        if let Some(country) = country {
+            url.push_str(&format!("&country={}", country.to_lowercase()));
+        }
+
+        let response = self.client.get(&url).send().await?.error_for_status()?;
+        parse_user_estimates(&self.client.read_text(response).await?)
+    }
+
+    /// Convenience method: estimates for all countries over the last N days.
+    pub async fn get_recent_user_estimates(
+        &self,
+        transport: TorTransport,
+        days: u32,
+    ) -> anyhow::Result<Vec<TorUserEstimate>> {
+        let end = Utc::now().date_naive();
+        let start = end - Duration::days(days.into());
+        self.get_user_estimates(transport, None, start, end).await
+    }
+}
+
+/// How users connect to Tor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TorTransport {
+    /// Directly through public relays.
+    Relay,
+    /// Through unlisted bridges, typically to evade blocking.
+    Bridge,
+}
+
+impl TorTransport {
+    /// Get a lowercase name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TorTransport::Relay => "relay",
+            TorTransport::Bridge => "bridge",
+        }
+    }
+
+    fn csv_path(&self) -> &'static str {
+        match self {
+            TorTransport::Relay => "userstats-relay-country.csv",
+            TorTransport::Bridge => "userstats-bridge-country.csv",
+        }
+    }
+}
+
+// ============================================================================
+// Response types
+// ============================================================================
+
+/// Estimated Tor users from one country on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TorUserEstimate {
+    /// Day of the estimate.
+    pub date: NaiveDate,
+
+    /// ISO 3166-1 alpha-2 country code, lowercase (e.g., "ir").
+    pub country: String,
+
+    /// Estimated number of users.
+    pub users: f64,
+}
+
+/// Parse a Tor Metrics user statistics CSV.
+///
+/// Comment lines start with `#`; the header names the `date`, `country` and
+/// `users` columns. Rows without a country (the global total) or without an
+/// estimate are skipped.
+pub fn parse_user_estimates(csv: &str) -> anyhow::Result<Vec<TorUserEstimate>> {
+    let mut lines = csv
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'));
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Tor Metrics CSV is empty"))?
+        .split(',')
+        .collect();
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| *h == name)
+            .ok_or_else(|| anyhow::anyhow!("Tor Metrics CSV has no '{}' column", name))
+    };
+    let (date, country, users) = (column("date")?, column("country")?, column("users")?);
+
+    Ok(lines
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            let country = fields.get(country).filter(|c| !c.is_empty())?;
+            Some(TorUserEstimate {
+                date: NaiveDate::parse_from_str(fields.get(date)?, "%Y-%m-%d").ok()?,
+                country: country.to_lowercase(),
+                users: fields.get(users)?.parse().ok()?,
+            })
+        })
+        .collect())
+}
+
+// ============================================================================
+// Anomaly detection
+// ============================================================================
+
+/// Direction of a usage anomaly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TorAnomalyKind {
+    /// Usage jumped: likely censorship onset.
+    Spike,
+    /// Usage collapsed: likely total connectivity loss.
+    Drop,
+}
+
+/// A country whose latest estimate departs sharply from its recent baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TorUsageAnomaly {
+    /// ISO 3166-1 alpha-2 country code, lowercase.
+    pub country: String,
+
+    /// Spike or drop.
+    pub kind: TorAnomalyKind,
+
+    /// Day of the latest estimate.
+    pub date: NaiveDate,
+
+    /// Latest estimated users.
+    pub users: f64,
+
+    /// Average users over the preceding [`BASELINE_DAYS`] days.
+    pub baseline: f64,
+}
+
+impl TorUsageAnomaly {
+    /// Latest usage as a multiple of the baseline.
+    pub fn ratio(&self) -> f64 {
+        self.users / self.baseline
+    }
+}
+
+/// Compare each country's latest estimate with the average of the
+/// [`BASELINE_DAYS`] days before it.
+///
+/// Countries with too little history or fewer than [`MIN_BASELINE_USERS`]
+/// are skipped.
+pub fn detect_anomalies(estimates: &[TorUserEstimate]) -> Vec<TorUsageAnomaly> {
+    let mut by_country: BTreeMap<&str, Vec<&TorUserEstimate>> = BTreeMap::new();
+    for estimate in estimates {
+        by_country
+            .entry(&estimate.country)
+            .or_default()
+            .push(estimate);
+    }
+
+    by_country
+        .into_values()
+        .filter_map(|mut series| {
+            series.sort_by_key(|e| e.date);
+            let (latest, history) = series.split_last()?;
+            if history.len() < BASELINE_DAYS {
+                return None;
+            }
+
+            let window = &history[history.len() - BASELINE_DAYS..];
+            let baseline = window.iter().map(|e| e.users).sum::<f64>() / BASELINE_DAYS as f64;
+            if baseline < MIN_BASELINE_USERS {
+                return None;
+            }
+
+            let ratio = latest.users / baseline;
+            let kind = if ratio >= SPIKE_RATIO {
+                TorAnomalyKind::Spike
+            } else if ratio <= DROP_RATIO {
+                TorAnomalyKind::Drop
+            } else {
+                return None;
+            };
+
+            Some(TorUsageAnomaly {
+                country: latest.country.clone(),
+                kind,
+                date: latest.date,
+                users: latest.users,
+                baseline,
+            })
+        })
+        .collect()
+}
+
+/// Link to the Tor Metrics graph of `transport` users in `country`, a
+/// two-letter code, from `start` to `end`.
+pub fn userstats_url(
+    transport: TorTransport,
+    country: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> String {
+    format!(
+        "{}/userstats-{}-country.html?start={}&end={}&country={}&events=on",
+        TOR_METRICS_BASE,
+        transport.name(),
+        start,
+        end,
+        country.to_lowercase()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(country: &str, users: &[f64]) -> Vec<TorUserEstimate> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        users
+            .iter()
+            .enumerate()
+            .map(|(i, &users)| TorUserEstimate {
+                date: start + Duration::days(i as i64),
+                country: country.to_string(),
+                users,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_user_estimates() {
+        let csv = "# Tor Metrics user estimates\n\
+            # More text\n\
+            date,country,users,lower,upper,frac\n\
+            2024-01-01,,2500000,,,95\n\
+            2024-01-01,ir,40000,30000,50000,95\n\
+            2024-01-02,IR,,,,\n\
+            2024-01-02,ru,90000,,,94\n";
+
+        let estimates = parse_user_estimates(csv).unwrap();
+        assert_eq!(estimates.len(), 2);
+        assert_eq!(estimates[0].country, "ir");
+        assert_eq!(estimates[0].users, 40000.0);
+        assert_eq!(
+            estimates[1].date,
+            NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+        );
+
+        assert!(parse_user_estimates("# only comments\n").is_err());
+        assert!(parse_user_estimates("date,users\n2024-01-01,5\n").is_err());
+    }
+
+    #[test]
+    fn test_detect_anomalies() {
+        let mut estimates = series(
+            "ir",
+            &[
+                1000.0, 1000.0, 1000.0, 1000.0, 1000.0, 1000.0, 1000.0, 3500.0,
+            ],
+        );
+        estimates.extend(series(
+            "sd",
+            &[800.0, 800.0, 800.0, 800.0, 800.0, 800.0, 800.0, 50.0],
+        ));
+        estimates.extend(series(
+            "de",
+            &[
+                5000.0, 5000.0, 5000.0, 5000.0, 5000.0, 5000.0, 5000.0, 5200.0,
+            ],
+        ));
+        // Too few users to judge
+        estimates.extend(series(
+            "va",
+            &[10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 10.0, 100.0],
+        ));
+        // Too little history
+        estimates.extend(series("ru", &[1000.0, 5000.0]));
+
+        let anomalies = detect_anomalies(&estimates);
+        assert_eq!(anomalies.len(), 2);
+
+        assert_eq!(anomalies[0].country, "ir");
+        assert_eq!(anomalies[0].kind, TorAnomalyKind::Spike);
+        assert!((anomalies[0].ratio() - 3.5).abs() < 0.01);
+
+        assert_eq!(anomalies[1].country, "sd");
+        assert_eq!(anomalies[1].kind, TorAnomalyKind::Drop);
+        assert_eq!(anomalies[1].baseline, 800.0);
+    }
+
+    #[test]
+    fn test_userstats_url() {
+        let date = |d| NaiveDate::from_ymd_opt(2024, 5, d).unwrap();
+        assert_eq!(
+            userstats_url(TorTransport::Bridge, "IR", date(1), date(15)),
+            "https://metrics.torproject.org/userstats-bridge-country.html\
+             ?start=2024-05-01&end=2024-05-15&country=ir&events=on"
+        );
+    }
+}
@@ -0,0 +1,439 @@
+//! Wikimedia pageviews client.
+//!
+//! Wikipedia reading is an "ambient human activity" signal: people read it
+//! every day, in their own language, independently of any one network
+//! provider. When views of a language edition read mostly in one country
+//! (e.g., Persian for Iran, Burmese for Myanmar) collapse against their
+//! usual weekly pattern, something has happened to the people reading it.
+//! That makes it a useful cross-check on Cloudflare Radar traffic data.
+//!
+//! # Features
+//!
+//! - Daily views per project (language edition), human traffic only
+//! - Top articles read from a country on a given day
+//!
+//! # API Reference
+//!
+//! See: <https://doc.wikimedia.org/generated-data-platform/aqs/analytics-api/>
+//!
+//! # Privacy
+//!
+//! All data is aggregate view counts. Per-country data is published by
+//! Wikimedia with privacy thresholds applied. No individual readers are tracked.
+
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::http::HttpClient;
+use super::lenient::LenientVec;
+
+/// Base URL for the Wikimedia REST API.
+const WIKIMEDIA_API_BASE: &str = "https://wikimedia.org/api/rest_v1";
+
+/// Base URL of the Pageviews Analysis tool.
+const PAGEVIEWS_TOOL_BASE: &str = "https://pageviews.wmcloud.org";
+
+/// Number of previous same-weekday days a day is compared against.
+pub const WEEKS_COMPARED: usize = 4;
+
+/// Views at or below this fraction of the weekly pattern are a collapse.
+pub const COLLAPSE_RATIO: f64 = 0.5;
+
+/// Projects expecting fewer daily views than this are too noisy to judge.
+pub const MIN_EXPECTED_VIEWS: f64 = 1000.0;
+
+/// Client for querying Wikimedia pageview statistics.
+#[derive(Clone)]
+pub struct WikimediaClient {
+    client: HttpClient,
+    base_url: String,
+    user_agent: String,
+}
+
+impl Default for WikimediaClient {
+    fn default() -> Self {
+        Self::new("infrared")
+    }
+}
+
+impl WikimediaClient {
+    /// Create a new Wikimedia client.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_name` - Application name, sent in the User-Agent as Wikimedia's API policy requires.
+    pub fn new(app_name: &str) -> Self {
+        Self::with_base_url(WIKIMEDIA_API_BASE, app_name)
+    }
+
+    /// Create a client with a custom base URL (for testing).
+    pub fn with_base_url(base_url: &str, app_name: &str) -> Self {
+        Self {
+            client: super::http::default_client(),
+            base_url: base_url.to_string(),
+            user_agent: format!("{} (infrared)", app_name),
+        }
+    }
+
+    /// Send requests through `client` (see [`super::http`]).
+    pub fn with_http_client(mut self, client: HttpClient) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Get daily human pageviews for a project.
+    ///
+    /// # Arguments
+    ///
+    /// * `project` - Project domain without `.org` (e.g., "fa.wikipedia")
+    /// * `start` - First day to include
+    /// * `end` - Last day to include
+    pub async fn get_project_daily_views(
+        &self,
+        project: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> anyhow::Result<Vec<DailyViews>> {
+        let url = format!(
+            "{}/metrics/pageviews/aggregate/{}/all-access/user/daily/{}00/{}00",
+            self.base_url,
+            project,
+            start.format("%Y%m%d"),
+            end.format("%Y%m%d")
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("User-Agent", &self.user_agent)
+            .send()
+            .await?
+            .error_for_status()?;
+        let data = self.client.read_json::<PageviewsResponse>(response).await?;
+        Ok(data.daily_views())
+    }
+
+    /// Convenience method: daily views for a project over the last N days.
+    pub async fn get_recent_project_daily_views(
+        &self,
+        project: &str,
+        days: u32,
+    ) -> anyhow::Result<Vec<DailyViews>> {
+        let end = Utc::now().date_naive();
+        let start = end - Duration::days(days.into());
+        self.get_project_daily_views(project, start, end).await
+    }
+
+    /// Get the most-read articles from a country on one day.
+    ///
+    /// # Arguments
+    ///
+    /// * `country_code` - ISO 3166-1 alpha-2 country code (e.g., "IR")
+    /// * `date` - Day to fetch
+    pub async fn get_country_top_articles(
+        &self,
+        country_code: &str,
+        date: NaiveDate,
+    ) -> anyhow::Result<TopPerCountryResponse> {
+        let url = format!(
+            "{}/metrics/pageviews/top-per-country/{}/all-access/{}/{:02}/{:02}",
+            self.base_url,
+            country_code.to_uppercase(),
+            date.year(),
+            date.month(),
+            date.day()
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .header("User-Agent", &self.user_agent)
+            .send()
+            .await?
+            .error_for_status()?;
+        let data = self
+            .client
+            .read_json::<TopPerCountryResponse>(response)
+            .await?;
+        Ok(data)
+    }
+}
+
+/// A Wikipedia project watched as a proxy for activity in one country.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WikimediaProject {
+    /// Project domain without `.org` (e.g., "fa.wikipedia").
+    pub project: String,
+
+    /// ISO 3166-1 alpha-2 code of the country mostly reading it.
+    pub country_code: String,
+}
+
+impl WikimediaProject {
+    /// Parse a list of `project=country` pairs, e.g. `fa.wikipedia=IR,my.wikipedia=MM`.
+    pub fn parse_list(spec: &str) -> anyhow::Result<Vec<Self>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (project, country) = entry
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("expected project=country, got '{}'", entry))?;
+                let (project, country) = (project.trim(), country.trim());
+                anyhow::ensure!(
+                    !project.is_empty() && country.len() == 2,
+                    "invalid Wikimedia project entry '{}'",
+                    entry
+                );
+                Ok(Self {
+                    project: project.to_string(),
+                    country_code: country.to_uppercase(),
+                })
+            })
+            .collect()
+    }
+}
+
+// ============================================================================
+// Response types
+// ============================================================================
+
+/// Response from the aggregate pageviews endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageviewsResponse {
+    /// One item per time bucket.
+    #[serde(default)]
+    pub items: LenientVec<PageviewItem>,
+}
+
+/// Views for one time bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageviewItem {
+    /// Project (e.g., "fa.wikipedia").
+    #[serde(default)]
+    pub project: String,
+
+    /// Bucket start as `YYYYMMDDHH`.
+    #[serde(default)]
+    pub timestamp: String,
+
+    /// Number of views.
+    #[serde(default)]
+    pub views: u64,
+}
+
+impl PageviewItem {
+    /// Day the bucket starts on.
+    pub fn date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(self.timestamp.get(..8)?, "%Y%m%d").ok()
+    }
+}
+
+impl PageviewsResponse {
+    /// Views per day, oldest first.
+    pub fn daily_views(&self) -> Vec<DailyViews> {
+        let mut days: Vec<DailyViews> = self
+            .items
+            .iter()
+            .filter_map(|item| {
+                Some(DailyViews {
+                    date: item.date()?,
+                    views: item.views,
+                })
+            })
+            .collect();
+        days.sort_by_key(|d| d.date);
+        days
+    }
+}
+
+/// Views on one day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyViews {
+    /// The day.
+    pub date: NaiveDate,
+
+    /// Number of views.
+    pub views: u64,
+}
+
+/// Response from the top-per-country endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopPerCountryResponse {
+    /// Usually a single item for the requested day.
+    #[serde(default)]
+    pub items: LenientVec<TopPerCountryItem>,
+}
+
+/// Most-read articles from a country on one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopPerCountryItem {
+    /// ISO 3166-1 alpha-2 country code.
+    #[serde(default)]
+    pub country: String,
+
+    /// Ranked articles.
+    #[serde(default)]
+    pub articles: Vec<TopArticle>,
+}
+
+/// A most-read article.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopArticle {
+    /// Article title.
+    #[serde(default)]
+    pub article: String,
+
+    /// Project the article belongs to.
+    #[serde(default)]
+    pub project: String,
+
+    /// Views, rounded up to protect reader privacy.
+    #[serde(default)]
+    pub views_ceil: u64,
+
+    /// Rank within the country for the day.
+    #[serde(default)]
+    pub rank: u32,
+}
+
+impl TopPerCountryResponse {
+    /// Total views across all listed articles.
+    pub fn total_views(&self) -> u64 {
+        self.items
+            .iter()
+            .flat_map(|item| &item.articles)
+            .map(|a| a.views_ceil)
+            .sum()
+    }
+}
+
+// ============================================================================
+// Collapse detection
+// ============================================================================
+
+/// A day whose views fell far below the usual weekly pattern.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageviewCollapse {
+    /// The day of the collapse.
+    pub date: NaiveDate,
+
+    /// Views on that day.
+    pub views: u64,
+
+    /// Average views on the same weekday over the previous [`WEEKS_COMPARED`] weeks.
+    pub expected: f64,
+}
+
+impl PageviewCollapse {
+    /// Views as a fraction of the expected level.
+    pub fn ratio(&self) -> f64 {
+        self.views as f64 / self.expected
+    }
+}
+
+/// Compare the latest day with the same weekday in the previous
+/// [`WEEKS_COMPARED`] weeks, so regular weekend dips are not flagged.
+///
+/// Returns `None` unless views are at or below [`COLLAPSE_RATIO`] of that
+/// pattern. Series with missing comparison days or expecting fewer than
+/// [`MIN_EXPECTED_VIEWS`] are never flagged.
+pub fn detect_collapse(series: &[DailyViews]) -> Option<PageviewCollapse> {
+    let latest = series.iter().max_by_key(|d| d.date)?;
+
+    let mut total = 0;
+    for week in 1..=WEEKS_COMPARED as i64 {
+        let date = latest.date - Duration::weeks(week);
+        total += series.iter().find(|d| d.date == date)?.views;
+    }
+    let expected = total as f64 / WEEKS_COMPARED as f64;
+    if expected < MIN_EXPECTED_VIEWS {
+        return None;
+    }
+
+    let collapse = PageviewCollapse {
+        date: latest.date,
+        views: latest.views,
+        expected,
+    };
+    (collapse.ratio() <= COLLAPSE_RATIO).then_some(collapse)
+}
+
+/// Link to the Pageviews Analysis chart of `project` (e.g., "fa.wikipedia")
+/// from `start` to `end`.
+pub fn siteviews_url(project: &str, start: NaiveDate, end: NaiveDate) -> String {
+    format!(
+        "{}/siteviews/?platform=all-access&source=pageviews&agent=user&start={}&end={}&sites={}.org",
+        PAGEVIEWS_TOOL_BASE, start, end, project
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 29 days of weekday-patterned views ending on `last`.
+    fn series(last: u64) -> Vec<DailyViews> {
+        let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        (0..29)
+            .map(|i| {
+                let date = start + Duration::days(i);
+                let weekend = date.weekday().number_from_monday() >= 6;
+                let views = if i == 28 {
+                    last
+                } else if weekend {
+                    40_000
+                } else {
+                    100_000
+                };
+                DailyViews { date, views }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_aggregate_response() {
+        let json = r#"{
+            "items": [
+                {"project": "fa.wikipedia", "access": "all-access", "agent": "user",
+                 "granularity": "daily", "timestamp": "2024010200", "views": 1200},
+                {"project": "fa.wikipedia", "access": "all-access", "agent": "user",
+                 "granularity": "daily", "timestamp": "2024010100", "views": 1000}
+            ]
+        }"#;
+
+        let response: PageviewsResponse = serde_json::from_str(json).unwrap();
+        let days = response.daily_views();
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(days[1].views, 1200);
+    }
+
+    #[test]
+    fn test_detect_collapse() {
+        // 2024-01-29 is a Monday, compared with the four previous Mondays
+        assert!(detect_collapse(&series(90_000)).is_none());
+
+        let collapse = detect_collapse(&series(20_000)).unwrap();
+        assert_eq!(collapse.date, NaiveDate::from_ymd_opt(2024, 1, 29).unwrap());
+        assert_eq!(collapse.expected, 100_000.0);
+        assert!((collapse.ratio() - 0.2).abs() < 0.01);
+
+        // Weekend-level traffic on a weekday is a real drop...
+        assert!(detect_collapse(&series(40_000)).is_some());
+
+        // ...but not enough history means no verdict
+        assert!(detect_collapse(&series(20_000)[10..]).is_none());
+    }
+
+    #[test]
+    fn test_parse_project_list() {
+        let projects = WikimediaProject::parse_list("fa.wikipedia=ir, my.wikipedia=MM").unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].project, "fa.wikipedia");
+        assert_eq!(projects[0].country_code, "IR");
+
+        assert!(WikimediaProject::parse_list("fa.wikipedia").is_err());
+        assert!(WikimediaProject::parse_list("fa.wikipedia=Iran").is_err());
+    }
+}
@@ -0,0 +1,425 @@
+//! Dead-man's switch countdowns.
+//!
+//! A community or site can arm a countdown on its bucket. Every accepted
+//! `POST /signal` for the bucket resets it; if the countdown runs out, a
+//! configured escalation sequence of webhook notifications fires, e.g.
+//! "notify the local coordinator immediately, the regional team after 30
+//! minutes, and the emergency desk after 2 hours".
+//!
+//! Escalation progress is persisted, so a restart neither repeats nor skips
+//! notifications.
+//!
+//! # Privacy
+//!
+//! Only bucket-level timing is inspected and sent. Notifications never
+//! contain per-signal data.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::metrics::Metrics;
+use crate::notify::WebhookNotifier;
+use crate::storage::{SignalStore, Storage};
+
+/// One step of an escalation sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EscalationStep {
+    /// Minutes after the countdown expires at which this step fires.
+    pub after_minutes: u32,
+
+    /// Webhook that receives the notification.
+    pub webhook_url: String,
+}
+
+/// Ordered notification steps fired when a switch expires.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EscalationPolicy {
+    steps: Vec<EscalationStep>,
+}
+
+impl EscalationPolicy {
+    /// Parse `minutes=url` pairs, e.g. `0=https://a/hook,30=https://b/hook`.
+    ///
+    /// Steps are sorted by delay, so the order in the string doesn't matter.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let mut steps = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (minutes, url) = entry.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!("invalid escalation step '{}' (expected minutes=url)", entry)
+                })?;
+                let after_minutes = minutes.trim().parse().map_err(|_| {
+                    anyhow::anyhow!("invalid delay '{}' in escalation step", minutes)
+                })?;
+                Ok(EscalationStep {
+                    after_minutes,
+                    webhook_url: url.trim().to_string(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        steps.sort_by_key(|step| step.after_minutes);
+        Ok(Self { steps })
+    }
+
+    /// The configured steps, in firing order.
+    pub fn steps(&self) -> &[EscalationStep] {
+        &self.steps
+    }
+
+    /// Whether no steps are configured.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Indexes of the steps that are due at `now` but haven't fired yet.
+    pub fn due_steps(&self, status: &SwitchStatus, now: DateTime<Utc>) -> Range<usize> {
+        let fired = status.fired_steps().min(self.steps.len());
+        if !status.is_expired(now) {
+            return fired..fired;
+        }
+
+        let overdue = now - status.deadline();
+        let due = self
+            .steps
+            .iter()
+            .take_while(|step| overdue >= chrono::Duration::minutes(step.after_minutes.into()))
+            .count();
+        fired..due.max(fired)
+    }
+}
+
+/// An armed switch and its current state.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwitchStatus {
+    /// The bucket identifier.
+    pub bucket: String,
+
+    /// Countdown length, in seconds.
+    pub countdown_seconds: i64,
+
+    /// When the switch was (last) armed.
+    pub armed_at: DateTime<Utc>,
+
+    /// When the last signal was received (if any).
+    pub last_seen: Option<DateTime<Utc>>,
+
+    /// Deadline the recorded escalation progress belongs to.
+    #[serde(skip)]
+    pub notified_deadline: Option<DateTime<Utc>>,
+
+    /// Escalation steps already fired for `notified_deadline`.
+    #[serde(skip)]
+    pub notified_steps: usize,
+}
+
+impl SwitchStatus {
+    /// When the countdown runs out, counted from arming or the last signal.
+    pub fn deadline(&self) -> DateTime<Utc> {
+        let reset_at = match self.last_seen {
+            Some(seen) if seen > self.armed_at => seen,
+            _ => self.armed_at,
+        };
+        reset_at + chrono::Duration::seconds(self.countdown_seconds)
+    }
+
+    /// Whether the countdown has run out.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now > self.deadline()
+    }
+
+    /// Escalation steps already fired for the current deadline.
+    ///
+    /// A signal moves the deadline, which resets the sequence.
+    pub fn fired_steps(&self) -> usize {
+        if self.notified_deadline == Some(self.deadline()) {
+            self.notified_steps
+        } else {
+            0
+        }
+    }
+}
+
+/// Request body for `PUT /buckets/:bucket/switch`.
+#[derive(Debug, Deserialize)]
+pub struct ArmRequest {
+    /// Countdown length, in minutes.
+    pub countdown_minutes: u32,
+}
+
+/// Response for `GET /switches`.
+#[derive(Debug, Serialize)]
+pub struct SwitchesResponse {
+    /// All armed switches.
+    pub switches: Vec<SwitchEntry>,
+}
+
+/// An armed switch with its current state.
+#[derive(Debug, Serialize)]
+pub struct SwitchEntry {
+    #[serde(flatten)]
+    pub status: SwitchStatus,
+
+    /// When the countdown runs out.
+    pub deadline: DateTime<Utc>,
+
+    /// Whether the countdown has run out.
+    pub expired: bool,
+
+    /// Escalation steps fired since the countdown ran out.
+    pub notifications_sent: usize,
+}
+
+/// Notification POSTed to an escalation step's webhook.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwitchNotification {
+    /// The bucket whose countdown expired.
+    pub bucket: String,
+
+    /// When the countdown ran out.
+    pub deadline: DateTime<Utc>,
+
+    /// Escalation step, starting at 1.
+    pub step: usize,
+
+    /// Whether this is the last configured step.
+    pub final_step: bool,
+
+    /// Human-readable summary.
+    pub message: String,
+}
+
+/// List all armed switches with their state at `now`.
+pub async fn list_switches(
+    storage: &dyn SignalStore,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Vec<SwitchEntry>> {
+    Ok(storage
+        .get_switches()
+        .await?
+        .into_iter()
+        .map(|status| SwitchEntry {
+            deadline: status.deadline(),
+            expired: status.is_expired(now),
+            notifications_sent: status.fired_steps(),
+            status,
+        })
+        .collect())
+}
+
+/// Spawn the background monitor that checks switches every `interval`.
+pub fn spawn_switch_monitor(
+    storage: Storage,
+    metrics: Metrics,
+    policy: EscalationPolicy,
+    notifier: WebhookNotifier,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = check_switches(&storage, &metrics, &policy, &notifier, Utc::now()).await
+            {
+                warn!(error = %e, "Failed to check dead-man's switches");
+            }
+        }
+    })
+}
+
+/// Evaluate every armed switch and fire due escalation steps.
+///
+/// A step whose webhook fails is retried on the next check; later steps wait
+/// for it so the sequence is delivered in order.
+pub async fn check_switches(
+    storage: &dyn SignalStore,
+    metrics: &Metrics,
+    policy: &EscalationPolicy,
+    notifier: &WebhookNotifier,
+    now: DateTime<Utc>,
+) -> anyhow::Result<()> {
+    metrics.clear("infrared_switch_expired");
+
+    for status in storage.get_switches().await? {
+        let expired = status.is_expired(now);
+        metrics.set_gauge(
+            "infrared_switch_expired",
+            &[("bucket", &status.bucket)],
+            if expired { 1.0 } else { 0.0 },
+        );
+
+        let deadline = status.deadline();
+        for index in policy.due_steps(&status, now) {
+            let step = &policy.steps[index];
+            let notification = SwitchNotification {
+                bucket: status.bucket.clone(),
+                deadline,
+                step: index + 1,
+                final_step: index + 1 == policy.steps.len(),
+                message: format!(
+                    "Bucket '{}' has sent no signal since its dead-man's switch expired at {}.",
+                    status.bucket,
+                    deadline.to_rfc3339()
+                ),
+            };
+
+            if let Err(e) = notifier.send(&step.webhook_url, &notification).await {
+                warn!(
+                    bucket = %status.bucket,
+                    step = index + 1,
+                    error = %e,
+                    "Failed to deliver dead-man's switch notification"
+                );
+                metrics.inc_counter(
+                    "infrared_switch_notification_failures_total",
+                    &[("bucket", &status.bucket)],
+                    1.0,
+                );
+                break;
+            }
+
+            info!(
+                bucket = %status.bucket,
+                step = index + 1,
+                "Dead-man's switch notification sent"
+            );
+            metrics.inc_counter(
+                "infrared_switch_notifications_total",
+                &[("bucket", &status.bucket)],
+                1.0,
+            );
+            storage
+                .set_switch_notified(&status.bucket, deadline, index + 1)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(ts: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(ts, 0).unwrap()
+    }
+
+    fn status(last_seen: Option<i64>) -> SwitchStatus {
+        SwitchStatus {
+            bucket: "site-1".to_string(),
+            countdown_seconds: 3600,
+            armed_at: at(10_000),
+            last_seen: last_seen.map(at),
+            notified_deadline: None,
+            notified_steps: 0,
+        }
+    }
+
+    #[test]
+    fn test_parse_policy() {
+        let policy = EscalationPolicy::parse("30=https://b/hook, 0=https://a/hook").unwrap();
+        assert_eq!(policy.steps().len(), 2);
+        assert_eq!(policy.steps()[0].after_minutes, 0);
+        assert_eq!(policy.steps()[0].webhook_url, "https://a/hook");
+
+        assert!(EscalationPolicy::parse("soon=https://a/hook").is_err());
+        assert!(EscalationPolicy::parse("https://a/hook").is_err());
+        assert!(EscalationPolicy::parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_deadline_resets_on_signal() {
+        // Signals before arming don't count
+        assert_eq!(status(Some(5_000)).deadline(), at(13_600));
+        assert_eq!(status(Some(12_000)).deadline(), at(15_600));
+        assert!(!status(None).is_expired(at(13_600)));
+        assert!(status(None).is_expired(at(13_601)));
+    }
+
+    #[test]
+    fn test_due_steps() {
+        let policy = EscalationPolicy::parse("0=http://a,30=http://b,120=http://c").unwrap();
+
+        assert_eq!(policy.due_steps(&status(None), at(13_000)), 0..0);
+        assert_eq!(policy.due_steps(&status(None), at(13_601)), 0..1);
+        assert_eq!(policy.due_steps(&status(None), at(13_600 + 1800)), 0..2);
+
+        // Already-fired steps are skipped for the same deadline...
+        let mut notified = status(None);
+        notified.notified_deadline = Some(at(13_600));
+        notified.notified_steps = 2;
+        assert_eq!(policy.due_steps(&notified, at(13_600 + 7200)), 2..3);
+
+        // ...but a new signal starts the sequence over
+        notified.last_seen = Some(at(12_000));
+        assert_eq!(policy.due_steps(&notified, at(15_601)), 0..1);
+    }
+
+    #[tokio::test]
+    async fn test_check_switches_escalates() {
+        use axum::{Json, Router, extract::State, routing::post};
+        use std::sync::{Arc, Mutex};
+
+        let received: Arc<Mutex<Vec<SwitchNotification>>> = Arc::default();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(received): State<Arc<Mutex<Vec<SwitchNotification>>>>,
+                     Json(n): Json<SwitchNotification>| async move {
+                        received.lock().unwrap().push(n);
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let url = format!("http://{}/hook", addr);
+        let policy = EscalationPolicy::parse(&format!("0={url},30={url}")).unwrap();
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let metrics = Metrics::new();
+        let notifier = WebhookNotifier::new();
+        storage
+            .arm_switch("site-1", 3600, at(10_000))
+            .await
+            .unwrap();
+
+        // Not yet expired
+        check_switches(&storage, &metrics, &policy, &notifier, at(13_000))
+            .await
+            .unwrap();
+        assert!(received.lock().unwrap().is_empty());
+
+        // Expired: first step fires once
+        for _ in 0..2 {
+            check_switches(&storage, &metrics, &policy, &notifier, at(13_700))
+                .await
+                .unwrap();
+        }
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert_eq!(
+            metrics.get("infrared_switch_expired", &[("bucket", "site-1")]),
+            Some(1.0)
+        );
+
+        // Second step after 30 more minutes
+        check_switches(&storage, &metrics, &policy, &notifier, at(13_600 + 1801))
+            .await
+            .unwrap();
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1].step, 2);
+        assert!(received[1].final_step);
+    }
+}
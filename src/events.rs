@@ -0,0 +1,210 @@
+//! In-process event bus.
+//!
+//! Subsystems that react to what happens elsewhere (notifications, metrics,
+//! live feeds) subscribe to an [`EventBus`] instead of polling storage or
+//! each other. Publishers never wait for subscribers: the bus is a tokio
+//! broadcast channel, so a subscriber that falls too far behind skips the
+//! oldest events and is told how many it missed.
+//!
+//! # Privacy
+//!
+//! Events carry the same bucket-level and country-level data the API
+//! already serves. `SignalIngested` holds only a bucket, a weight and a
+//! server timestamp.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::dashboard::{Issue, IssueSource};
+use crate::metrics::Metrics;
+use crate::model::LifeSignal;
+use crate::transitions::StatusTransition;
+
+/// Events buffered per subscriber before the oldest are dropped.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Something that happened, as seen by subscribers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// A life signal was accepted and stored.
+    SignalIngested(LifeSignal),
+    /// A bucket's warmth status changed.
+    StatusChanged(StatusTransition),
+    /// A dashboard issue appeared that was not in the previous fetch.
+    IssueDetected(Box<Issue>),
+    /// A dashboard data source could not be fetched.
+    SourceFailed {
+        source: IssueSource,
+        message: String,
+        at: DateTime<Utc>,
+    },
+    /// A dashboard data source was fetched without error.
+    SourceFetched {
+        source: IssueSource,
+        at: DateTime<Utc>,
+    },
+    /// A dashboard data source sent records that no longer match their
+    /// model, which were skipped.
+    SourceParseWarnings {
+        source: IssueSource,
+        count: u64,
+        at: DateTime<Utc>,
+    },
+}
+
+impl Event {
+    /// Short name of the event type (e.g., "status_changed").
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::SignalIngested(_) => "signal_ingested",
+            Event::StatusChanged(_) => "status_changed",
+            Event::IssueDetected(_) => "issue_detected",
+            Event::SourceFailed { .. } => "source_failed",
+            Event::SourceFetched { .. } => "source_fetched",
+            Event::SourceParseWarnings { .. } => "source_parse_warnings",
+        }
+    }
+}
+
+/// Broadcasts events to every subscriber.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    /// Create a bus with no subscribers.
+    pub fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Publish an event to current subscribers.
+    pub fn publish(&self, event: Event) {
+        // No subscribers is not an error
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive every event published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.sender.subscribe()
+    }
+}
+
+/// Receive the next event, skipping over any dropped while lagging.
+///
+/// Returns `None` once the bus is gone.
+pub async fn next_event(receiver: &mut broadcast::Receiver<Event>) -> Option<Event> {
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                warn!(missed, "Event subscriber fell behind; events dropped");
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+/// Spawn a subscriber counting events by type in
+/// `infrared_events_total{type}`, and skipped data source records in
+/// `infrared_source_parse_warnings_total{source}`.
+pub fn spawn_event_metrics(bus: &EventBus, metrics: Metrics) -> tokio::task::JoinHandle<()> {
+    let mut receiver = bus.subscribe();
+    tokio::spawn(async move {
+        while let Some(event) = next_event(&mut receiver).await {
+            metrics.inc_counter("infrared_events_total", &[("type", event.name())], 1.0);
+            if let Event::SourceParseWarnings { source, count, .. } = &event {
+                metrics.inc_counter(
+                    "infrared_source_parse_warnings_total",
+                    &[("source", source.label())],
+                    *count as f64,
+                );
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(bucket: &str) -> Event {
+        Event::SignalIngested(LifeSignal {
+            bucket: bucket.to_string(),
+            timestamp: Utc::now(),
+            weight: 1,
+            channel: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_subscribe() {
+        let bus = EventBus::new();
+        // Publishing without subscribers is fine
+        bus.publish(signal("zone-a"));
+
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+        bus.publish(signal("zone-b"));
+
+        for receiver in [&mut first, &mut second] {
+            match next_event(receiver).await {
+                Some(Event::SignalIngested(s)) => assert_eq!(s.bucket, "zone-b"),
+                other => panic!("unexpected event: {:?}", other),
+            }
+        }
+
+        let json = serde_json::to_value(signal("zone-c")).unwrap();
+        assert_eq!(json["type"], "signal_ingested");
+        assert_eq!(json["bucket"], "zone-c");
+    }
+
+    #[tokio::test]
+    async fn test_event_metrics() {
+        let bus = EventBus::new();
+        let metrics = Metrics::new();
+        let task = spawn_event_metrics(&bus, metrics.clone());
+
+        bus.publish(signal("zone-a"));
+        bus.publish(signal("zone-a"));
+        bus.publish(Event::SourceFailed {
+            source: IssueSource::Ioda,
+            message: "timeout".to_string(),
+            at: Utc::now(),
+        });
+        bus.publish(Event::SourceParseWarnings {
+            source: IssueSource::Acled,
+            count: 3,
+            at: Utc::now(),
+        });
+        drop(bus);
+        task.await.unwrap();
+
+        assert_eq!(
+            metrics.get("infrared_events_total", &[("type", "signal_ingested")]),
+            Some(2.0)
+        );
+        assert_eq!(
+            metrics.get("infrared_events_total", &[("type", "source_failed")]),
+            Some(1.0)
+        );
+        assert_eq!(
+            metrics.get(
+                "infrared_source_parse_warnings_total",
+                &[("source", "ACLED")]
+            ),
+            Some(3.0)
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Issues from partners' own detectors.
+//!
+//! Partners running their own detectors can contribute without a client
+//! written for each: `POST /ingest/issue` accepts an issue in Infrared's
+//! [`Issue`] schema. The issue is validated, attributed to
+//! [`IssueSource::External`], persisted, and merged into the dashboard like
+//! any other source until it stops being refreshed.
+//!
+//! Reposting an issue with the same `id` replaces it, so partners keep an
+//! issue current (or end it) by posting it again. IDs are namespaced by the
+//! posting token's label, so partners cannot overwrite each other's issues.
+//!
+//! # Privacy
+//!
+//! External issues are held to the dashboard's country-level granularity:
+//! a location name and ISO country code, never coordinates or people.
+
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::dashboard::{Issue, IssueSource};
+use crate::situation::parse_country_code;
+
+/// Longest accepted title, in characters.
+pub const MAX_TITLE_CHARS: usize = 200;
+
+/// Longest accepted description, in characters.
+pub const MAX_DESCRIPTION_CHARS: usize = 5000;
+
+/// How far in the future an issue's start may be, to allow for clock skew.
+const MAX_CLOCK_SKEW_MINUTES: i64 = 5;
+
+/// An issue that failed validation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IssueRejected {
+    /// Always "invalid_issue".
+    pub error: &'static str,
+
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl IntoResponse for IssueRejected {
+    fn into_response(self) -> Response {
+        (StatusCode::UNPROCESSABLE_ENTITY, Json(self)).into_response()
+    }
+}
+
+/// Response for `POST /ingest/issue`.
+#[derive(Debug, Serialize)]
+pub struct IssueAccepted {
+    /// The issue's ID in Infrared.
+    pub id: String,
+
+    /// Whether the ID was new (otherwise an earlier post was replaced).
+    pub created: bool,
+}
+
+/// Validate an issue posted by `actor` and attribute it to
+/// [`IssueSource::External`].
+///
+/// The posted `id` becomes part of the stored ID
+/// (`external:<actor>:<id>`), and `is_ongoing` follows `end_timestamp`.
+pub fn accept(mut issue: Issue, actor: &str, now: DateTime<Utc>) -> Result<Issue, IssueRejected> {
+    let reject = |message: String| IssueRejected {
+        error: "invalid_issue",
+        message,
+    };
+
+    let upstream_id = issue.id.trim();
+    if upstream_id.is_empty() {
+        return Err(reject("id must not be empty".to_string()));
+    }
+    if issue.title.trim().is_empty() || issue.location.trim().is_empty() {
+        return Err(reject("title and location must not be empty".to_string()));
+    }
+    if issue.title.chars().count() > MAX_TITLE_CHARS {
+        return Err(reject(format!(
+            "title is longer than {} characters",
+            MAX_TITLE_CHARS
+        )));
+    }
+    if issue.description.chars().count() > MAX_DESCRIPTION_CHARS {
+        return Err(reject(format!(
+            "description is longer than {} characters",
+            MAX_DESCRIPTION_CHARS
+        )));
+    }
+    issue.location_code =
+        parse_country_code(&issue.location_code).map_err(|e| reject(e.to_string()))?;
+    for location in &mut issue.locations {
+        location.code = parse_country_code(&location.code).map_err(|e| reject(e.to_string()))?;
+    }
+    if issue.timestamp > now + chrono::Duration::minutes(MAX_CLOCK_SKEW_MINUTES) {
+        return Err(reject("timestamp is in the future".to_string()));
+    }
+    if issue.end_timestamp.is_some_and(|end| end < issue.timestamp) {
+        return Err(reject("end_timestamp is before timestamp".to_string()));
+    }
+    if issue
+        .normalized_severity
+        .is_some_and(|s| !(0.0..=100.0).contains(&s))
+    {
+        return Err(reject(
+            "normalized_severity must be between 0 and 100".to_string(),
+        ));
+    }
+    if issue.impact_value.is_some_and(|v| !v.is_finite()) {
+        return Err(reject("impact_value must be a finite number".to_string()));
+    }
+    if let Some(details) = &issue.details
+        && details.category() != issue.category
+    {
+        return Err(reject(format!(
+            "details are for category '{}', not '{}'",
+            details.category().label(),
+            issue.category.label()
+        )));
+    }
+
+    let upstream_id = format!("{}:{}", actor, upstream_id);
+    issue.source = IssueSource::External;
+    issue.is_ongoing = issue.end_timestamp.is_none();
+    Ok(issue.with_upstream_id(&upstream_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::IssueCategory;
+    use crate::issue_details::FoodSecurityDetails;
+    use crate::model::IssueSeverity;
+
+    fn posted() -> Issue {
+        let mut issue = Issue::new(
+            IssueSource::Acled,
+            IssueCategory::Conflict,
+            IssueSeverity::Critical,
+            "Sudan",
+            "sd",
+            "Shelling reported",
+            "Partner field report",
+            Utc::now(),
+        );
+        issue.id = "evt-42".to_string();
+        issue
+    }
+
+    #[test]
+    fn test_accept() {
+        let now = Utc::now();
+        let issue = accept(posted(), "partner-a", now).unwrap();
+        assert_eq!(issue.id, "external:partner-a:evt-42");
+        assert_eq!(issue.source, IssueSource::External);
+        assert_eq!(issue.location_code, "SD");
+        assert!(issue.is_ongoing);
+
+        let mut ended = posted();
+        ended.end_timestamp = Some(ended.timestamp + chrono::Duration::hours(1));
+        assert!(!accept(ended, "partner-a", now).unwrap().is_ongoing);
+    }
+
+    #[test]
+    fn test_accept_rejects_invalid() {
+        let now = Utc::now();
+        let invalid = |change: fn(&mut Issue)| {
+            let mut issue = posted();
+            change(&mut issue);
+            accept(issue, "partner-a", now).unwrap_err()
+        };
+
+        invalid(|i| i.id = " ".to_string());
+        invalid(|i| i.title.clear());
+        invalid(|i| i.location_code = "Sudan".to_string());
+        invalid(|i| i.timestamp = Utc::now() + chrono::Duration::hours(1));
+        invalid(|i| i.end_timestamp = Some(i.timestamp - chrono::Duration::hours(1)));
+        invalid(|i| i.normalized_severity = Some(140.0));
+        invalid(|i| i.details = Some(FoodSecurityDetails::default().into()));
+        let err = invalid(|i| i.description = "x".repeat(MAX_DESCRIPTION_CHARS + 1));
+        assert_eq!(err.error, "invalid_issue");
+    }
+}
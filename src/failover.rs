@@ -0,0 +1,219 @@
+//! Channel failover analysis.
+//!
+//! With [signal channels](crate::channel) configured, a distressed bucket's
+//! per-channel warmth tells two very different situations apart:
+//!
+//! - A **path failure**: one or more channels collapsed while another is
+//!   still alive. People are checking in over what still works, so the
+//!   likely cause is a network outage, not a population in distress. These
+//!   alerts stay at `warning` however long they last.
+//! - An **all-channel collapse**: every channel the bucket normally uses
+//!   collapsed at once. Independent delivery paths failing together is the
+//!   strongest signal Infrared has that people, not infrastructure, went
+//!   silent, so these alerts are at least `critical`.
+//!
+//! Only channels active in the bucket's baseline count, and at least two
+//! are needed: a bucket reached over a single channel cannot tell the two
+//! apart. Anything else (a channel merely stressed, or no healthy channel
+//! left but not all collapsed) is left to the ordinary warmth alert.
+//!
+//! # Privacy
+//!
+//! The analysis reads per-channel aggregates only; channels are shared
+//! delivery paths, never clients.
+
+use chrono::{DateTime, Utc};
+
+use crate::model::{AlertReason, ChannelWarmth, IssueSeverity, WarmthResponse, WarmthStatus};
+use crate::severity::SeverityTiers;
+
+/// How a bucket's channels failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelFailover {
+    /// Some channels collapsed while others stayed alive.
+    PathFailure {
+        /// Collapsing or dead channels.
+        failed: Vec<String>,
+        /// Channels still alive.
+        healthy: Vec<String>,
+    },
+    /// Every active channel collapsed at once.
+    AllChannelsCollapsed {
+        /// The collapsed channels.
+        channels: Vec<String>,
+    },
+}
+
+impl ChannelFailover {
+    /// Classify a bucket's per-channel warmth, as attached by
+    /// [`crate::aggregation::attach_channels`].
+    ///
+    /// Returns `None` unless at least two channels were active in the
+    /// baseline and they failed in one of the two recognised patterns.
+    pub fn analyze(channels: &[ChannelWarmth]) -> Option<Self> {
+        let active: Vec<(&str, WarmthStatus)> = channels
+            .iter()
+            .filter(|c| c.recent_average > 0.0)
+            .filter_map(|c| Some((c.channel.as_deref()?, c.status)))
+            .collect();
+        if active.len() < 2 {
+            return None;
+        }
+
+        let failed = names(&active, |s| {
+            matches!(s, WarmthStatus::Collapsing | WarmthStatus::Dead)
+        });
+        let healthy = names(&active, |s| s == WarmthStatus::Alive);
+
+        if failed.len() == active.len() {
+            Some(ChannelFailover::AllChannelsCollapsed { channels: failed })
+        } else if !failed.is_empty() && !healthy.is_empty() {
+            Some(ChannelFailover::PathFailure { failed, healthy })
+        } else {
+            None
+        }
+    }
+
+    /// The alert reason for this pattern.
+    pub fn reason(&self) -> AlertReason {
+        match self {
+            ChannelFailover::PathFailure { .. } => AlertReason::ChannelFailure,
+            ChannelFailover::AllChannelsCollapsed { .. } => AlertReason::AllChannelsCollapsed,
+        }
+    }
+
+    /// Alert severity: path failures stay `warning`; all-channel collapses
+    /// are at least `critical` and otherwise escalate with `tiers`.
+    pub fn severity(
+        &self,
+        tiers: &SeverityTiers,
+        since: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+    ) -> IssueSeverity {
+        match self {
+            ChannelFailover::PathFailure { .. } => IssueSeverity::Warning,
+            ChannelFailover::AllChannelsCollapsed { .. } => {
+                tiers.severity(since, now).max(IssueSeverity::Critical)
+            }
+        }
+    }
+
+    /// Human-readable alert message.
+    pub fn message(&self, warmth: &WarmthResponse) -> String {
+        match self {
+            ChannelFailover::PathFailure { failed, healthy } => format!(
+                "WARNING: Bucket '{}' stopped receiving signals over {} while {} {} healthy. \
+                 This points to a delivery path outage rather than a population-level drop. \
+                 Current activity ({}) vs recent average ({:.1}).",
+                warmth.bucket,
+                failed.join(", "),
+                healthy.join(", "),
+                if healthy.len() == 1 {
+                    "remains"
+                } else {
+                    "remain"
+                },
+                warmth.current_window_total,
+                warmth.recent_average
+            ),
+            ChannelFailover::AllChannelsCollapsed { channels } => format!(
+                "CRITICAL: Bucket '{}' collapsed on every channel at once ({}). \
+                 Independent delivery paths failing together points to a population-level \
+                 emergency rather than a network outage. \
+                 Current activity ({}) vs recent average ({:.1}).",
+                warmth.bucket,
+                channels.join(", "),
+                warmth.current_window_total,
+                warmth.recent_average
+            ),
+        }
+    }
+}
+
+fn names(active: &[(&str, WarmthStatus)], keep: impl Fn(WarmthStatus) -> bool) -> Vec<String> {
+    active
+        .iter()
+        .filter(|(_, status)| keep(*status))
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(name: Option<&str>, current: i64, average: f64) -> ChannelWarmth {
+        ChannelWarmth {
+            channel: name.map(String::from),
+            current_window_total: current,
+            recent_average: average,
+            status: WarmthStatus::from_activity(current, average),
+        }
+    }
+
+    #[test]
+    fn test_path_failure() {
+        let channels = [
+            channel(None, 0, 1.0),
+            channel(Some("internet"), 0, 30.0),
+            channel(Some("sms"), 19, 20.0),
+        ];
+        assert_eq!(
+            ChannelFailover::analyze(&channels),
+            Some(ChannelFailover::PathFailure {
+                failed: vec!["internet".to_string()],
+                healthy: vec!["sms".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn test_all_channels_collapsed() {
+        let channels = [
+            channel(Some("internet"), 0, 30.0),
+            channel(Some("mesh"), 1, 10.0),
+            channel(Some("sms"), 0, 20.0),
+            // Not active in the baseline, so not counted
+            channel(Some("radio"), 0, 0.0),
+        ];
+        let failover = ChannelFailover::analyze(&channels).unwrap();
+        assert_eq!(
+            failover,
+            ChannelFailover::AllChannelsCollapsed {
+                channels: vec![
+                    "internet".to_string(),
+                    "mesh".to_string(),
+                    "sms".to_string()
+                ],
+            }
+        );
+
+        let tiers = SeverityTiers::default();
+        let now = Utc::now();
+        assert_eq!(
+            failover.severity(&tiers, None, now),
+            IssueSeverity::Critical
+        );
+        assert_eq!(
+            failover.severity(&tiers, Some(now - chrono::Duration::days(1)), now),
+            IssueSeverity::Emergency
+        );
+    }
+
+    #[test]
+    fn test_inconclusive() {
+        // A single channel cannot tell path from population
+        assert_eq!(
+            ChannelFailover::analyze(&[channel(None, 0, 5.0), channel(Some("sms"), 0, 20.0)]),
+            None
+        );
+        // One dead, the other only stressed
+        assert_eq!(
+            ChannelFailover::analyze(&[
+                channel(Some("internet"), 0, 30.0),
+                channel(Some("sms"), 10, 20.0),
+            ]),
+            None
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! A single feed of everything Infrared knows is wrong.
+//!
+//! Bucket alerts (from life signals) and dashboard issues (from external
+//! sources) are graded on the same [`IssueSeverity`] scale. A [`FeedEvent`]
+//! is their common shape, so `GET /events` can serve one list, most severe
+//! first, instead of operators cross-reading `/alerts/recent` and
+//! `/dashboard`.
+//!
+//! # Privacy
+//!
+//! Feed events carry what the underlying alerts and issues already expose:
+//! bucket or group identifiers, countries, and source-level descriptions.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dashboard::{Issue, SourceError};
+use crate::model::{Alert, AlertSubject, IssueSeverity};
+
+/// What a feed event was derived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedKind {
+    /// A bucket alert.
+    BucketAlert,
+    /// A bucket group alert.
+    GroupAlert,
+    /// A dashboard issue from an external source.
+    Issue,
+}
+
+/// An alert or issue in the common feed shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedEvent {
+    /// What the event was derived from.
+    pub kind: FeedKind,
+
+    /// Identifier, stable while the underlying alert or issue lasts:
+    /// `bucket:<name>`, `group:<name>`, or the issue ID.
+    pub id: String,
+
+    /// Severity level.
+    pub severity: IssueSeverity,
+
+    /// Where it was detected: `infrared` for alerts, otherwise the
+    /// external source.
+    pub source: String,
+
+    /// The bucket or group for alerts; the affected location for issues.
+    pub subject: String,
+
+    /// ISO country code, for issues.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location_code: Option<String>,
+
+    /// Short summary.
+    pub title: String,
+
+    /// Detailed description.
+    pub description: String,
+
+    /// When it started, if known.
+    pub since: Option<DateTime<Utc>>,
+}
+
+impl From<&Alert> for FeedEvent {
+    fn from(alert: &Alert) -> Self {
+        let (kind, prefix) = match alert.subject {
+            AlertSubject::Bucket => (FeedKind::BucketAlert, "bucket"),
+            AlertSubject::Group => (FeedKind::GroupAlert, "group"),
+        };
+        Self {
+            kind,
+            id: format!("{}:{}", prefix, alert.bucket),
+            severity: alert.severity,
+            source: "infrared".to_string(),
+            subject: alert.bucket.clone(),
+            location_code: None,
+            title: format!("{} {} is {}", prefix, alert.bucket, alert.status.name()),
+            description: alert.message.clone(),
+            since: alert.distressed_since,
+        }
+    }
+}
+
+impl From<&Issue> for FeedEvent {
+    fn from(issue: &Issue) -> Self {
+        Self {
+            kind: FeedKind::Issue,
+            id: issue.id.clone(),
+            severity: issue.severity,
+            source: issue.source.label().to_string(),
+            subject: issue.location.clone(),
+            location_code: Some(issue.location_code.clone()),
+            title: issue.title.clone(),
+            description: issue.description.clone(),
+            since: Some(issue.timestamp),
+        }
+    }
+}
+
+/// Query parameters for GET /events.
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    /// Only events at or above this severity (`info`, `warning`,
+    /// `critical`, `emergency`).
+    pub min_severity: Option<String>,
+}
+
+/// Response for GET /events.
+#[derive(Debug, Serialize)]
+pub struct FeedResponse {
+    /// When the feed was assembled.
+    pub timestamp: DateTime<Utc>,
+
+    /// Events, most severe first, then most recent first.
+    pub events: Vec<FeedEvent>,
+
+    /// Dashboard sources that could not be fetched.
+    pub errors: Vec<SourceError>,
+}
+
+/// Merge alerts and issues into one feed, most severe first and, within a
+/// severity, most recent first. Events below `min_severity` are dropped.
+pub fn merge(alerts: &[Alert], issues: &[Issue], min_severity: IssueSeverity) -> Vec<FeedEvent> {
+    let mut events: Vec<FeedEvent> = alerts
+        .iter()
+        .map(FeedEvent::from)
+        .chain(issues.iter().map(FeedEvent::from))
+        .filter(|event| event.severity >= min_severity)
+        .collect();
+    events.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| b.since.cmp(&a.since))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::{IssueCategory, IssueSource};
+    use crate::model::{AlertReason, WarmthStatus};
+
+    #[test]
+    fn test_merge_orders_by_severity() {
+        let now = Utc::now();
+        let alert = Alert {
+            bucket: "zone-a".to_string(),
+            subject: AlertSubject::Bucket,
+            status: WarmthStatus::Dead,
+            last_seen_timestamp: None,
+            recent_average: 12.0,
+            reason: AlertReason::WarmthDrop,
+            message: "CRITICAL: Bucket 'zone-a' has gone completely silent.".to_string(),
+            severity: IssueSeverity::Critical,
+            distressed_since: Some(now - chrono::Duration::hours(3)),
+        };
+        let issue = |severity, hours_ago| {
+            Issue::new(
+                IssueSource::Ioda,
+                IssueCategory::InternetOutage,
+                severity,
+                "Sudan",
+                "SD",
+                "Internet outage",
+                "Connectivity dropped",
+                now - chrono::Duration::hours(hours_ago),
+            )
+        };
+        let issues = [
+            issue(IssueSeverity::Warning, 1),
+            issue(IssueSeverity::Emergency, 5),
+            issue(IssueSeverity::Critical, 1),
+        ];
+
+        let events = merge(std::slice::from_ref(&alert), &issues, IssueSeverity::Info);
+        let order: Vec<_> = events.iter().map(|e| (e.severity, e.kind)).collect();
+        assert_eq!(
+            order,
+            [
+                (IssueSeverity::Emergency, FeedKind::Issue),
+                (IssueSeverity::Critical, FeedKind::Issue),
+                (IssueSeverity::Critical, FeedKind::BucketAlert),
+                (IssueSeverity::Warning, FeedKind::Issue),
+            ]
+        );
+        assert_eq!(events[2].id, "bucket:zone-a");
+        assert_eq!(events[2].source, "infrared");
+
+        let events = merge(&[alert], &issues, IssueSeverity::Critical);
+        assert_eq!(events.len(), 3);
+    }
+}
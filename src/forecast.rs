@@ -0,0 +1,319 @@
+//! Holt-Winters forecasts of a bucket's expected activity.
+//!
+//! The warmth detectors compare a window with the windows just before it,
+//! so a bucket that always goes quiet in the evening looks like it is
+//! collapsing every evening, and one that stays flat through what is
+//! usually its busiest hour looks fine. A forecast instead fits additive
+//! Holt-Winters smoothing (level, trend and a daily seasonal pattern) to
+//! the bucket's recent window totals and asks what this window should have
+//! held. A bucket is underperforming when its current total falls more than
+//! `sigmas` standard deviations of the model's past one-step errors below
+//! that forecast.
+//!
+//! The daily pattern needs at least two days of history and a window size
+//! that divides a day; otherwise the forecast follows level and trend
+//! alone.
+//!
+//! # Privacy
+//!
+//! Forecasts are computed from per-window signal totals of one bucket.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::SignalStore;
+
+/// Smoothing constant of the level.
+const ALPHA: f64 = 0.3;
+
+/// Smoothing constant of the trend.
+const BETA: f64 = 0.05;
+
+/// Smoothing constant of the seasonal pattern.
+const GAMMA: f64 = 0.2;
+
+/// Windows needed to fit a model without seasonality.
+const MIN_HISTORY_WINDOWS: usize = 3;
+
+/// Floor of the error deviation, so a perfectly regular history does not
+/// flag a bucket one signal short of its forecast.
+const MIN_RESIDUAL_SD: f64 = 1.0;
+
+/// Default days of history a forecast is fitted on.
+pub const DEFAULT_FORECAST_DAYS: u32 = 3;
+
+/// Query parameters for `GET /warmth/forecast`.
+#[derive(Debug, Deserialize)]
+pub struct ForecastQuery {
+    /// The bucket to forecast.
+    pub bucket: String,
+
+    /// Window size in minutes (default: 10).
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: u32,
+
+    /// Days of history to fit the model on (default: 3).
+    #[serde(default = "default_forecast_days")]
+    pub days: u32,
+
+    /// Deviations below the forecast at which a bucket is underperforming
+    /// (default: `INFRARED_DETECTOR_SIGMAS`).
+    pub sigmas: Option<f64>,
+}
+
+fn default_window_minutes() -> u32 {
+    10
+}
+
+fn default_forecast_days() -> u32 {
+    DEFAULT_FORECAST_DAYS
+}
+
+/// Response for `GET /warmth/forecast`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastResponse {
+    pub bucket: String,
+
+    /// The time window in minutes.
+    pub window_minutes: u32,
+
+    /// Windows before the current one the model was fitted on.
+    pub history_windows: u32,
+
+    /// Windows in the daily seasonal pattern, or `None` when the model
+    /// follows level and trend alone.
+    pub season_windows: Option<u32>,
+
+    /// Total signal weight in the current window.
+    pub current_window_total: i64,
+
+    /// Total the model expected for the current window, from the windows
+    /// before it.
+    pub expected_current: Option<f64>,
+
+    /// Total the model expects for the next window.
+    pub expected_next: Option<f64>,
+
+    /// Standard deviation of the model's one-step errors over the history.
+    pub residual_sd: Option<f64>,
+
+    /// How many deviations the current total is above (positive) or below
+    /// (negative) `expected_current`.
+    pub deviation: Option<f64>,
+
+    /// Whether the current total is more than `sigmas` deviations below
+    /// `expected_current`.
+    pub underperforming: bool,
+}
+
+/// A fitted model's one-step-ahead forecast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Forecast {
+    /// Expected total of the window after the series, never negative.
+    pub next: f64,
+
+    /// Root mean square of the one-step errors while fitting.
+    pub residual_sd: f64,
+}
+
+/// Fit additive Holt-Winters smoothing to `totals` (oldest first) and
+/// forecast the window after them.
+///
+/// With `season` windows per cycle and at least two cycles of totals the
+/// seasonal pattern is fitted too; otherwise only level and trend are.
+/// Returns `None` with fewer than three totals.
+pub fn holt_winters(totals: &[f64], season: Option<usize>) -> Option<Forecast> {
+    let n = totals.len();
+    let season = season.filter(|&m| m >= 2 && n >= 2 * m);
+
+    let mut squared_errors = 0.0;
+    let mut errors = 0usize;
+    let next = match season {
+        Some(m) => {
+            let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+            let first = mean(&totals[..m]);
+            let mut level = first;
+            let mut trend = (mean(&totals[m..2 * m]) - first) / m as f64;
+            let mut seasonal: Vec<f64> = totals[..m].iter().map(|y| y - first).collect();
+
+            for (t, &y) in totals.iter().enumerate().skip(m) {
+                let s = seasonal[t % m];
+                let error = y - (level + trend + s);
+                squared_errors += error * error;
+                errors += 1;
+
+                let previous = level;
+                level = ALPHA * (y - s) + (1.0 - ALPHA) * (level + trend);
+                trend = BETA * (level - previous) + (1.0 - BETA) * trend;
+                seasonal[t % m] = GAMMA * (y - level) + (1.0 - GAMMA) * s;
+            }
+            level + trend + seasonal[n % m]
+        }
+        None if n >= MIN_HISTORY_WINDOWS => {
+            let mut level = totals[0];
+            let mut trend = totals[1] - totals[0];
+
+            for &y in &totals[1..] {
+                let error = y - (level + trend);
+                squared_errors += error * error;
+                errors += 1;
+
+                let previous = level;
+                level = ALPHA * y + (1.0 - ALPHA) * (level + trend);
+                trend = BETA * (level - previous) + (1.0 - BETA) * trend;
+            }
+            level + trend
+        }
+        None => return None,
+    };
+
+    Some(Forecast {
+        next: next.max(0.0),
+        residual_sd: (squared_errors / errors as f64).sqrt(),
+    })
+}
+
+/// Windows per day at `window_minutes`, if a whole number.
+fn daily_season(window_minutes: u32) -> Option<usize> {
+    (window_minutes > 0 && (24 * 60) % window_minutes == 0)
+        .then(|| (24 * 60 / window_minutes) as usize)
+}
+
+/// Forecast `bucket`'s current and next window from `days` of history and
+/// flag it if the current window falls `sigmas` deviations short.
+pub async fn compute_forecast(
+    storage: &dyn SignalStore,
+    bucket: &str,
+    window_minutes: u32,
+    days: u32,
+    sigmas: f64,
+    now: DateTime<Utc>,
+) -> anyhow::Result<ForecastResponse> {
+    let history_windows = (days * 24 * 60 / window_minutes).max(1);
+    let totals: Vec<f64> = storage
+        .window_totals(bucket, window_minutes, history_windows + 1, now)
+        .await?
+        .into_iter()
+        .map(|total| total as f64)
+        .collect();
+    let Some((current, history)) = totals.split_last() else {
+        anyhow::bail!("no window totals for '{}'", bucket);
+    };
+
+    let season = daily_season(window_minutes).filter(|&m| m >= 2 && history.len() >= 2 * m);
+    let expected = holt_winters(history, season);
+    let residual_sd = expected.map(|f| f.residual_sd.max(MIN_RESIDUAL_SD));
+    let deviation = expected
+        .zip(residual_sd)
+        .map(|(f, sd)| (current - f.next) / sd);
+
+    Ok(ForecastResponse {
+        bucket: bucket.to_string(),
+        window_minutes,
+        history_windows,
+        season_windows: season.map(|m| m as u32),
+        current_window_total: *current as i64,
+        expected_current: expected.map(|f| f.next),
+        expected_next: holt_winters(&totals, season).map(|f| f.next),
+        residual_sd,
+        deviation,
+        underperforming: deviation.is_some_and(|d| d < -sigmas),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LifeSignal;
+    use crate::storage::Storage;
+
+    /// 100 during the day (06:00 to 18:00), 20 at night, hour by hour.
+    fn daily(hour: usize) -> f64 {
+        if (6..18).contains(&(hour % 24)) {
+            100.0
+        } else {
+            20.0
+        }
+    }
+
+    #[test]
+    fn test_holt_winters() {
+        // A repeating pattern is forecast exactly
+        let totals: Vec<f64> = (0..72).map(daily).collect();
+        let forecast = holt_winters(&totals, Some(24)).unwrap();
+        assert!((forecast.next - daily(72)).abs() < 1e-9);
+        assert!(forecast.residual_sd < 1e-9);
+        let forecast = holt_winters(&totals[..66], Some(24)).unwrap();
+        assert!((forecast.next - daily(66)).abs() < 1e-9);
+
+        // So is a straight line, without a season
+        let totals: Vec<f64> = (0..10).map(|t| 50.0 + 5.0 * t as f64).collect();
+        let forecast = holt_winters(&totals, None).unwrap();
+        assert!((forecast.next - 100.0).abs() < 1e-9);
+
+        // Less than two seasons falls back to level and trend
+        assert!(holt_winters(&totals, Some(24)).is_some());
+        assert!(holt_winters(&totals[..2], None).is_none());
+
+        // A collapse to zero is not forecast below zero
+        let totals = [100.0, 50.0, 0.0];
+        assert_eq!(holt_winters(&totals, None).unwrap().next, 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_forecast_follows_daily_pattern() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+
+        // Three days of hourly history plus the current hour, which is the
+        // first night hour for zone-a and midday for zone-b
+        for (bucket, offset, current) in [("zone-a", 18, 20), ("zone-b", 12, 30)] {
+            let signals: Vec<LifeSignal> = (0..=72)
+                .map(|windows_ago| LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: now - chrono::Duration::minutes(windows_ago as i64 * 60 + 30),
+                    weight: if windows_ago == 0 {
+                        current
+                    } else {
+                        daily(72 - windows_ago + offset) as i32
+                    },
+                    channel: None,
+                })
+                .collect();
+            storage.insert_life_signals(&signals).await.unwrap();
+        }
+
+        // zone-a dropped from 100 to 20, as it does every evening
+        let forecast = compute_forecast(&storage, "zone-a", 60, 3, 3.0, now)
+            .await
+            .unwrap();
+        assert_eq!(forecast.history_windows, 72);
+        assert_eq!(forecast.season_windows, Some(24));
+        assert_eq!(forecast.current_window_total, 20);
+        assert!((forecast.expected_current.unwrap() - 20.0).abs() < 1e-6);
+        assert!((forecast.expected_next.unwrap() - 20.0).abs() < 1e-6);
+        assert!(!forecast.underperforming);
+
+        // zone-b is at 30 when it is usually at 100
+        let forecast = compute_forecast(&storage, "zone-b", 60, 3, 3.0, now)
+            .await
+            .unwrap();
+        assert!((forecast.expected_current.unwrap() - 100.0).abs() < 1e-6);
+        assert!(forecast.deviation.unwrap() < -3.0);
+        assert!(forecast.underperforming);
+
+        // One day of history is not enough for the daily pattern
+        let forecast = compute_forecast(&storage, "zone-b", 60, 1, 3.0, now)
+            .await
+            .unwrap();
+        assert_eq!(forecast.season_windows, None);
+        assert!(forecast.expected_current.is_some());
+
+        // An unknown bucket is forecast to stay silent
+        let forecast = compute_forecast(&storage, "zone-z", 60, 3, 3.0, now)
+            .await
+            .unwrap();
+        assert_eq!(forecast.expected_current, Some(0.0));
+        assert!(!forecast.underperforming);
+    }
+}
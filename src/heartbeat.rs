@@ -0,0 +1,233 @@
+//! Expected-heartbeat monitoring.
+//!
+//! Sparse buckets (e.g., a site that checks in once every 15 minutes) are
+//! badly served by window-ratio detection: a single missing signal barely
+//! moves the average. Such buckets can instead register an expected
+//! interval, and a bucket that goes longer than that without a signal is
+//! reported as `Dead` immediately.
+//!
+//! # Privacy
+//!
+//! Only bucket-level timing is inspected. No per-signal data is exposed.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::metrics::Metrics;
+use crate::storage::{SignalStore, Storage};
+
+/// Heartbeat registration and state for one bucket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeartbeatStatus {
+    /// The bucket identifier.
+    pub bucket: String,
+
+    /// Expected maximum gap between signals, in seconds.
+    pub interval_seconds: i64,
+
+    /// When the heartbeat was registered.
+    pub registered_at: DateTime<Utc>,
+
+    /// When the last signal was received (if any).
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+impl HeartbeatStatus {
+    /// When the next signal is due at the latest.
+    ///
+    /// Buckets that have never signalled are due one interval after registration.
+    pub fn due_at(&self) -> DateTime<Utc> {
+        self.last_seen.unwrap_or(self.registered_at)
+            + chrono::Duration::seconds(self.interval_seconds)
+    }
+
+    /// Whether the expected interval has elapsed without a signal.
+    pub fn is_missed(&self, now: DateTime<Utc>) -> bool {
+        now > self.due_at()
+    }
+}
+
+/// Request body for `PUT /buckets/:bucket/heartbeat`.
+#[derive(Debug, Deserialize)]
+pub struct HeartbeatRequest {
+    /// Expected maximum gap between signals, in minutes.
+    pub interval_minutes: u32,
+}
+
+/// Response for `GET /heartbeats`.
+#[derive(Debug, Serialize)]
+pub struct HeartbeatsResponse {
+    /// All registered heartbeats.
+    pub heartbeats: Vec<HeartbeatEntry>,
+}
+
+/// A registered heartbeat with its current state.
+#[derive(Debug, Serialize)]
+pub struct HeartbeatEntry {
+    #[serde(flatten)]
+    pub status: HeartbeatStatus,
+
+    /// When the next signal is due.
+    pub due_at: DateTime<Utc>,
+
+    /// Whether the heartbeat is currently missed.
+    pub missed: bool,
+}
+
+/// List all registered heartbeats with their state at `now`.
+pub async fn list_heartbeats(
+    storage: &dyn SignalStore,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Vec<HeartbeatEntry>> {
+    Ok(storage
+        .get_heartbeats()
+        .await?
+        .into_iter()
+        .map(|status| HeartbeatEntry {
+            due_at: status.due_at(),
+            missed: status.is_missed(now),
+            status,
+        })
+        .collect())
+}
+
+/// Spawn the background monitor that checks heartbeats every `interval`.
+///
+/// Newly missed and recovered heartbeats are logged, and the
+/// `infrared_heartbeat_missed{bucket}` gauge is kept current so missed beats
+/// surface without anyone querying `/alerts/recent`.
+pub fn spawn_heartbeat_monitor(
+    storage: Storage,
+    metrics: Metrics,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut missed = HashSet::new();
+        loop {
+            ticker.tick().await;
+
+            match storage.get_heartbeats().await {
+                Ok(statuses) => {
+                    missed = check_heartbeats(&statuses, &missed, &metrics, Utc::now());
+                }
+                Err(e) => warn!(error = %e, "Failed to check heartbeats"),
+            }
+        }
+    })
+}
+
+/// Evaluate heartbeats, log transitions, and publish gauges.
+///
+/// Returns the set of buckets currently missing their heartbeat.
+fn check_heartbeats(
+    statuses: &[HeartbeatStatus],
+    previously_missed: &HashSet<String>,
+    metrics: &Metrics,
+    now: DateTime<Utc>,
+) -> HashSet<String> {
+    metrics.clear("infrared_heartbeat_missed");
+
+    let mut missed = HashSet::new();
+    for status in statuses {
+        let is_missed = status.is_missed(now);
+        let was_missed = previously_missed.contains(&status.bucket);
+
+        if is_missed && !was_missed {
+            warn!(
+                bucket = %status.bucket,
+                interval_seconds = status.interval_seconds,
+                "Bucket missed its expected heartbeat"
+            );
+            metrics.inc_counter(
+                "infrared_heartbeat_misses_total",
+                &[("bucket", &status.bucket)],
+                1.0,
+            );
+        } else if !is_missed && was_missed {
+            info!(bucket = %status.bucket, "Bucket heartbeat recovered");
+        }
+
+        metrics.set_gauge(
+            "infrared_heartbeat_missed",
+            &[("bucket", &status.bucket)],
+            if is_missed { 1.0 } else { 0.0 },
+        );
+        if is_missed {
+            missed.insert(status.bucket.clone());
+        }
+    }
+    missed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn status(last_seen: Option<i64>) -> HeartbeatStatus {
+        HeartbeatStatus {
+            bucket: "site-1".to_string(),
+            interval_seconds: 900,
+            registered_at: Utc.timestamp_opt(1_000, 0).unwrap(),
+            last_seen: last_seen.map(|ts| Utc.timestamp_opt(ts, 0).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_is_missed() {
+        let at = |ts| Utc.timestamp_opt(ts, 0).unwrap();
+
+        // Never signalled: due one interval after registration
+        assert!(!status(None).is_missed(at(1_900)));
+        assert!(status(None).is_missed(at(1_901)));
+
+        // Signalled: due one interval after the last signal
+        assert!(!status(Some(5_000)).is_missed(at(5_900)));
+        assert!(status(Some(5_000)).is_missed(at(5_901)));
+    }
+
+    #[test]
+    fn test_check_heartbeats_transitions() {
+        let metrics = Metrics::new();
+        let statuses = vec![status(Some(5_000))];
+
+        let missed = check_heartbeats(
+            &statuses,
+            &HashSet::new(),
+            &metrics,
+            Utc.timestamp_opt(6_000, 0).unwrap(),
+        );
+        assert!(missed.contains("site-1"));
+        assert_eq!(
+            metrics.get("infrared_heartbeat_missed", &[("bucket", "site-1")]),
+            Some(1.0)
+        );
+
+        // Still missed on the next check: counted only once
+        let missed = check_heartbeats(
+            &statuses,
+            &missed,
+            &metrics,
+            Utc.timestamp_opt(6_030, 0).unwrap(),
+        );
+        assert_eq!(
+            metrics.get("infrared_heartbeat_misses_total", &[("bucket", "site-1")]),
+            Some(1.0)
+        );
+
+        // Recovered
+        let recovered = vec![status(Some(6_040))];
+        let missed = check_heartbeats(
+            &recovered,
+            &missed,
+            &metrics,
+            Utc.timestamp_opt(6_050, 0).unwrap(),
+        );
+        assert!(missed.is_empty());
+    }
+}
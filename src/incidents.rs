@@ -0,0 +1,461 @@
+//! Incidents grouping related bucket alerts.
+//!
+//! During a regional blackout forty zones alert at once, and forty alerts
+//! read like forty problems. An [`Incident`] gathers the distress episodes
+//! of buckets that are related (members of the same bucket group, or tied
+//! to the same country) and overlap in time, so `GET /incidents` shows one
+//! incident with forty affected buckets.
+//!
+//! A bucket's distress episodes are its runs of `collapsing` or `dead`
+//! scheduled snapshots over the lookback, plus its current alert. Related
+//! episodes are linked transitively: if zone A overlaps zone B and B
+//! overlaps C, all three form one incident even if A and C never overlapped.
+//!
+//! An incident's ID is its earliest episode's bucket and start time, so it
+//! stays the same while the incident lasts.
+//!
+//! # Privacy
+//!
+//! Incidents are built from bucket statuses, group memberships and bucket
+//! countries only.
+
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::canary::is_canary;
+use crate::model::{Alert, AlertSubject, BucketGroup, WarmthStatus};
+use crate::scheduler::WarmthSnapshot;
+use crate::storage::SignalStore;
+
+/// Lookback when `hours` is not given.
+pub const DEFAULT_INCIDENT_HOURS: u32 = 24;
+
+/// Longest lookback accepted, in hours.
+pub const MAX_INCIDENT_HOURS: u32 = 168;
+
+/// Query parameters for GET /incidents.
+#[derive(Debug, Deserialize)]
+pub struct IncidentsQuery {
+    /// How far back to look, in hours (default 24, at most 168).
+    pub hours: Option<u32>,
+}
+
+/// A run of distress in one bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Episode {
+    /// The bucket in distress.
+    pub bucket: String,
+
+    /// When the distress began.
+    pub start: DateTime<Utc>,
+
+    /// When the bucket recovered, or None while it lasts.
+    pub end: Option<DateTime<Utc>>,
+
+    /// The worst status seen during the run.
+    pub status: WarmthStatus,
+}
+
+/// Related distress across one or more buckets.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Incident {
+    /// `<bucket>@<unix start>` of the earliest episode.
+    pub id: String,
+
+    /// When the first bucket went into distress.
+    pub started_at: DateTime<Utc>,
+
+    /// When the last bucket recovered, or None while any is in distress.
+    pub ended_at: Option<DateTime<Utc>>,
+
+    /// Whether any bucket is still in distress.
+    pub ongoing: bool,
+
+    /// The worst status any bucket reached.
+    pub status: WarmthStatus,
+
+    /// Affected buckets, sorted.
+    pub buckets: Vec<String>,
+
+    /// Bucket groups the affected buckets belong to, sorted.
+    pub groups: Vec<String>,
+
+    /// Countries the affected buckets report on, sorted.
+    pub countries: Vec<String>,
+}
+
+/// Response for GET /incidents.
+#[derive(Debug, Serialize)]
+pub struct IncidentsResponse {
+    /// When the incidents were assembled.
+    pub timestamp: DateTime<Utc>,
+
+    /// The lookback, in hours.
+    pub hours: u32,
+
+    /// Ongoing incidents first, then the most recently started.
+    pub incidents: Vec<Incident>,
+}
+
+fn severity_rank(status: WarmthStatus) -> u8 {
+    match status {
+        WarmthStatus::Alive => 0,
+        WarmthStatus::Stressed => 1,
+        WarmthStatus::Collapsing => 2,
+        WarmthStatus::Dead => 3,
+    }
+}
+
+fn distressed(status: WarmthStatus) -> bool {
+    matches!(status, WarmthStatus::Collapsing | WarmthStatus::Dead)
+}
+
+/// A bucket's distress episodes, from its snapshots (oldest first) and its
+/// current alert, if any.
+///
+/// Only snapshots of the smallest window size present are read. A run that
+/// reaches the last snapshot is still open if the bucket is alerting now,
+/// and ends at `now` otherwise.
+pub fn episodes(
+    bucket: &str,
+    snapshots: &[WarmthSnapshot],
+    alert: Option<&Alert>,
+    now: DateTime<Utc>,
+) -> Vec<Episode> {
+    let window = snapshots.iter().map(|s| s.window_minutes).min();
+    let mut episodes: Vec<Episode> = Vec::new();
+    let mut open: Option<Episode> = None;
+    for snapshot in snapshots
+        .iter()
+        .filter(|s| Some(s.window_minutes) == window)
+    {
+        match (&mut open, distressed(snapshot.status)) {
+            (Some(episode), true) => {
+                if severity_rank(snapshot.status) > severity_rank(episode.status) {
+                    episode.status = snapshot.status;
+                }
+            }
+            (Some(_), false) => {
+                let mut episode = open.take().unwrap();
+                episode.end = Some(snapshot.taken_at);
+                episodes.push(episode);
+            }
+            (None, true) => {
+                open = Some(Episode {
+                    bucket: bucket.to_string(),
+                    start: snapshot.taken_at,
+                    end: None,
+                    status: snapshot.status,
+                })
+            }
+            (None, false) => {}
+        }
+    }
+
+    match (open, alert) {
+        (Some(mut episode), Some(alert)) => {
+            if severity_rank(alert.status) > severity_rank(episode.status) {
+                episode.status = alert.status;
+            }
+            if let Some(since) = alert.distressed_since {
+                episode.start = episode.start.min(since);
+            }
+            episodes.push(episode);
+        }
+        (Some(mut episode), None) => {
+            episode.end = Some(now);
+            episodes.push(episode);
+        }
+        (None, Some(alert)) => episodes.push(Episode {
+            bucket: bucket.to_string(),
+            start: alert.distressed_since.unwrap_or(now).min(now),
+            end: None,
+            status: alert.status,
+        }),
+        (None, None) => {}
+    }
+    episodes
+}
+
+fn overlapping(a: &Episode, b: &Episode, now: DateTime<Utc>) -> bool {
+    a.start <= b.end.unwrap_or(now) && b.start <= a.end.unwrap_or(now)
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+/// Group episodes into incidents.
+///
+/// Two episodes are related when their buckets share a group or a country
+/// (from `countries`, bucket to code) and their spans overlap; an episode
+/// related to nothing is an incident of its own.
+pub fn group_incidents(
+    episodes: &[Episode],
+    groups: &[BucketGroup],
+    countries: &HashMap<String, String>,
+    now: DateTime<Utc>,
+) -> Vec<Incident> {
+    let groups_of = |bucket: &str| -> Vec<&str> {
+        groups
+            .iter()
+            .filter(|g| g.buckets.iter().any(|b| b == bucket))
+            .map(|g| g.name.as_str())
+            .collect()
+    };
+    let related = |a: &str, b: &str| {
+        let same_country = matches!(
+            (countries.get(a), countries.get(b)),
+            (Some(x), Some(y)) if x.eq_ignore_ascii_case(y)
+        );
+        same_country || groups_of(a).iter().any(|g| groups_of(b).contains(g))
+    };
+
+    let mut parent: Vec<usize> = (0..episodes.len()).collect();
+    for i in 0..episodes.len() {
+        for j in i + 1..episodes.len() {
+            let (a, b) = (&episodes[i], &episodes[j]);
+            if a.bucket != b.bucket && overlapping(a, b, now) && related(&a.bucket, &b.bucket) {
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                parent[ri] = rj;
+            }
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<&Episode>> = HashMap::new();
+    for (i, episode) in episodes.iter().enumerate() {
+        let root = find(&mut parent, i);
+        members.entry(root).or_default().push(episode);
+    }
+
+    let mut incidents: Vec<Incident> = members
+        .into_values()
+        .map(|members| {
+            let first = members
+                .iter()
+                .min_by(|a, b| a.start.cmp(&b.start).then_with(|| a.bucket.cmp(&b.bucket)))
+                .unwrap();
+            let ongoing = members.iter().any(|e| e.end.is_none());
+            let buckets: BTreeSet<&str> = members.iter().map(|e| e.bucket.as_str()).collect();
+            Incident {
+                id: format!("{}@{}", first.bucket, first.start.timestamp()),
+                started_at: first.start,
+                ended_at: if ongoing {
+                    None
+                } else {
+                    members.iter().filter_map(|e| e.end).max()
+                },
+                ongoing,
+                status: members
+                    .iter()
+                    .map(|e| e.status)
+                    .max_by_key(|s| severity_rank(*s))
+                    .unwrap(),
+                groups: buckets
+                    .iter()
+                    .flat_map(|b| groups_of(b))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                countries: buckets
+                    .iter()
+                    .filter_map(|b| countries.get(*b))
+                    .map(|c| c.to_uppercase())
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect(),
+                buckets: buckets.into_iter().map(String::from).collect(),
+            }
+        })
+        .collect();
+    incidents.sort_by(|a, b| {
+        b.ongoing
+            .cmp(&a.ongoing)
+            .then_with(|| b.started_at.cmp(&a.started_at))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    incidents
+}
+
+/// Assemble the incidents of the last `hours`, given the current alerts.
+pub async fn build_incidents(
+    storage: &dyn SignalStore,
+    alerts: &[Alert],
+    hours: u32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Vec<Incident>> {
+    let since = now - chrono::Duration::hours(i64::from(hours));
+    incidents_since(storage, alerts, since, now).await
+}
+
+/// Split an incident ID into its first bucket and start time.
+pub fn parse_incident_id(id: &str) -> Option<(&str, DateTime<Utc>)> {
+    let (bucket, start) = id.rsplit_once('@')?;
+    let start = DateTime::from_timestamp(start.parse().ok()?, 0)?;
+    (!bucket.is_empty()).then_some((bucket, start))
+}
+
+/// Find the incident with the given ID, given the current alerts.
+///
+/// Incidents are rebuilt from the ID's start time, so an incident is found
+/// however long ago it began, for as long as its snapshots are kept.
+pub async fn find_incident(
+    storage: &dyn SignalStore,
+    alerts: &[Alert],
+    id: &str,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Option<Incident>> {
+    let Some((_, start)) = parse_incident_id(id) else {
+        return Ok(None);
+    };
+    if start > now {
+        return Ok(None);
+    }
+    let incidents = incidents_since(storage, alerts, start, now).await?;
+    Ok(incidents.into_iter().find(|incident| incident.id == id))
+}
+
+async fn incidents_since(
+    storage: &dyn SignalStore,
+    alerts: &[Alert],
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Vec<Incident>> {
+    let mut all = Vec::new();
+    let mut countries = HashMap::new();
+    for bucket in storage.get_all_known_buckets().await? {
+        if is_canary(&bucket) {
+            continue;
+        }
+        let alert = alerts
+            .iter()
+            .find(|a| a.subject == AlertSubject::Bucket && a.bucket == bucket);
+        let snapshots = storage.get_warmth_snapshots(&bucket, since, now).await?;
+        let found = episodes(&bucket, &snapshots, alert, now);
+        if found.is_empty() {
+            continue;
+        }
+        if let Some(country) = storage.get_country(&bucket).await? {
+            countries.insert(bucket.clone(), country);
+        }
+        all.extend(found);
+    }
+
+    let groups = storage.get_groups().await?;
+    Ok(group_incidents(&all, &groups, &countries, now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AlertReason, IssueSeverity};
+    use chrono::{Duration, TimeZone};
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    fn episode(bucket: &str, start: i64, end: Option<i64>) -> Episode {
+        Episode {
+            bucket: bucket.to_string(),
+            start: at(start),
+            end: end.map(at),
+            status: WarmthStatus::Dead,
+        }
+    }
+
+    #[test]
+    fn test_episodes() {
+        let snapshot = |minutes, status| WarmthSnapshot {
+            bucket: "zone-a".to_string(),
+            window_minutes: 10,
+            taken_at: at(minutes),
+            status,
+            current_window_total: 0,
+            recent_average: 0.0,
+        };
+        let snapshots = [
+            snapshot(0, WarmthStatus::Alive),
+            snapshot(10, WarmthStatus::Collapsing),
+            snapshot(20, WarmthStatus::Dead),
+            snapshot(30, WarmthStatus::Alive),
+            snapshot(40, WarmthStatus::Collapsing),
+        ];
+        let alert = Alert {
+            bucket: "zone-a".to_string(),
+            subject: AlertSubject::Bucket,
+            status: WarmthStatus::Dead,
+            last_seen_timestamp: None,
+            recent_average: 10.0,
+            reason: AlertReason::WarmthDrop,
+            message: String::new(),
+            severity: IssueSeverity::Warning,
+            distressed_since: Some(at(35)),
+        };
+
+        let found = episodes("zone-a", &snapshots, Some(&alert), at(45));
+        assert_eq!(found.len(), 2);
+        assert_eq!((found[0].start, found[0].end), (at(10), Some(at(30))));
+        assert_eq!(found[0].status, WarmthStatus::Dead);
+        assert_eq!((found[1].start, found[1].end), (at(35), None));
+
+        // Recovered since the last snapshot
+        let found = episodes("zone-a", &snapshots, None, at(45));
+        assert_eq!(found[1].end, Some(at(45)));
+    }
+
+    #[test]
+    fn test_parse_incident_id() {
+        let id = format!("site@north@{}", at(0).timestamp());
+        assert_eq!(parse_incident_id(&id), Some(("site@north", at(0))));
+        assert_eq!(parse_incident_id("zone-a"), None);
+        assert_eq!(parse_incident_id("zone-a@soon"), None);
+        assert_eq!(parse_incident_id("@1709294400"), None);
+    }
+
+    #[test]
+    fn test_group_incidents() {
+        let groups = [BucketGroup {
+            name: "north".to_string(),
+            buckets: vec!["zone-a".to_string(), "zone-b".to_string()],
+        }];
+        let countries = HashMap::from([
+            ("zone-b".to_string(), "SD".to_string()),
+            ("zone-c".to_string(), "sd".to_string()),
+        ]);
+        let episodes = [
+            episode("zone-a", 0, Some(30)),
+            // Same group as zone-a, overlapping
+            episode("zone-b", 20, None),
+            // Same country as zone-b: joins transitively
+            episode("zone-c", 60, None),
+            // Related, but long before
+            episode("zone-a", -600, Some(-500)),
+            // Unrelated
+            episode("zone-d", 10, None),
+        ];
+
+        let incidents = group_incidents(&episodes, &groups, &countries, at(90));
+        assert_eq!(incidents.len(), 3);
+
+        let regional = incidents.iter().find(|i| i.buckets.len() == 3).unwrap();
+        assert_eq!(regional.id, format!("zone-a@{}", at(0).timestamp()));
+        assert!(regional.ongoing);
+        assert_eq!(regional.ended_at, None);
+        assert_eq!(regional.groups, ["north"]);
+        assert_eq!(regional.countries, ["SD"]);
+
+        // Ongoing first, most recently started first
+        assert_eq!(incidents[0].buckets, ["zone-d"]);
+        assert_eq!(incidents[1].id, regional.id);
+        assert_eq!(incidents[2].ended_at, Some(at(-500)));
+    }
+}
@@ -0,0 +1,358 @@
+//! Per-bucket ingest rate tracking and quotas.
+//!
+//! Counts accepted signals per bucket in fixed one-minute windows. When a
+//! quota is configured for a bucket, signals beyond it in the current minute
+//! are rejected so a single runaway integration cannot distort baselines or
+//! fill the disk.
+//!
+//! A bucket's counts are forgotten once it has been idle for
+//! [`IDLE_MINUTES`], so a client posting ever-new bucket names cannot grow
+//! memory without bound. For the same reason the `/metrics` series are
+//! totals across buckets; per-bucket rates are only served by `GET /stats`.
+//!
+//! # Privacy
+//!
+//! Only bucket-level counts are kept in memory. Nothing about the sender is
+//! recorded.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::metrics::Metrics;
+
+/// Minutes without signals after which a bucket's counts are dropped.
+pub const IDLE_MINUTES: i64 = 2;
+
+/// Quota configuration for signal ingestion.
+#[derive(Debug, Clone, Default)]
+pub struct QuotaConfig {
+    /// Signals per minute allowed for any bucket without an override (`None` = unlimited).
+    pub default_per_minute: Option<u32>,
+
+    /// Per-bucket signals-per-minute overrides.
+    pub overrides: HashMap<String, u32>,
+}
+
+impl QuotaConfig {
+    /// Parse per-bucket overrides from `bucket=limit` pairs separated by commas.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use infrared::ingest::QuotaConfig;
+    ///
+    /// let overrides = QuotaConfig::parse_overrides("zone-a=600, zone-b=60").unwrap();
+    /// assert_eq!(overrides["zone-b"], 60);
+    /// ```
+    pub fn parse_overrides(spec: &str) -> anyhow::Result<HashMap<String, u32>> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (bucket, limit) = pair
+                    .rsplit_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("expected bucket=limit, got '{}'", pair))?;
+                Ok((bucket.trim().to_string(), limit.trim().parse()?))
+            })
+            .collect()
+    }
+
+    /// Quota that applies to a bucket, if any.
+    pub fn limit_for(&self, bucket: &str) -> Option<u32> {
+        self.overrides
+            .get(bucket)
+            .copied()
+            .or(self.default_per_minute)
+    }
+}
+
+/// Result of checking a signal against its bucket's quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaStatus {
+    /// Whether the signal was accepted.
+    pub allowed: bool,
+
+    /// Signals per minute allowed for the bucket (`None` = unlimited).
+    pub limit: Option<u32>,
+
+    /// Signals left in the current minute (`None` = unlimited).
+    pub remaining: Option<u32>,
+
+    /// Seconds until the current minute window resets.
+    pub reset_seconds: u32,
+}
+
+#[derive(Debug, Default)]
+struct BucketRate {
+    /// Start of the current minute window (Unix seconds).
+    minute: i64,
+    /// Signals accepted in the current minute.
+    current: u32,
+    /// Signals accepted in the previous complete minute.
+    previous: u32,
+    /// Signals rejected since the bucket was last idle.
+    rejected: u64,
+    /// Minute of the last signal, accepted or rejected.
+    last_seen: i64,
+}
+
+impl BucketRate {
+    fn roll(&mut self, minute: i64) {
+        if minute != self.minute {
+            self.previous = if minute - self.minute == 60 {
+                self.current
+            } else {
+                0
+            };
+            self.current = 0;
+            self.minute = minute;
+        }
+    }
+}
+
+/// Rates of every bucket that is not idle.
+#[derive(Debug, Default)]
+struct RateTable {
+    buckets: HashMap<String, BucketRate>,
+    /// Minute of the last sweep for idle buckets.
+    swept: i64,
+}
+
+impl RateTable {
+    /// Drop buckets idle for [`IDLE_MINUTES`], at most once per minute.
+    fn sweep(&mut self, minute: i64) {
+        if minute != self.swept {
+            self.buckets
+                .retain(|_, rate| minute - rate.last_seen < IDLE_MINUTES * 60);
+            self.swept = minute;
+        }
+    }
+}
+
+/// Ingest rate for a single bucket, as reported by `GET /stats`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketIngestStats {
+    /// The bucket identifier.
+    pub bucket: String,
+
+    /// Signals accepted so far in the current minute.
+    pub current_minute: u32,
+
+    /// Signals accepted in the previous complete minute.
+    pub signals_per_minute: u32,
+
+    /// Configured quota, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota_per_minute: Option<u32>,
+
+    /// Signals rejected for exceeding the quota since the bucket was last
+    /// idle.
+    pub rejected_total: u64,
+}
+
+/// Shared per-bucket ingest tracker.
+///
+/// Cloning is cheap; all clones share the same counters.
+#[derive(Clone, Default)]
+pub struct IngestTracker {
+    config: Arc<QuotaConfig>,
+    rates: Arc<Mutex<RateTable>>,
+}
+
+impl IngestTracker {
+    /// Create a tracker with no quotas (rate tracking only).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a tracker that enforces the given quotas.
+    pub fn with_quotas(config: QuotaConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            rates: Arc::default(),
+        }
+    }
+
+    /// Count a signal for `bucket` at `now`, unless its quota is exhausted.
+    ///
+    /// Accepted and rejected signals are also counted in `metrics`, across
+    /// all buckets.
+    pub fn record(&self, bucket: &str, now: DateTime<Utc>, metrics: &Metrics) -> QuotaStatus {
+        let ts = now.timestamp();
+        let minute = ts - ts.rem_euclid(60);
+        let reset_seconds = (minute + 60 - ts) as u32;
+        let limit = self.config.limit_for(bucket);
+
+        let mut rates = self.rates.lock().unwrap_or_else(|e| e.into_inner());
+        rates.sweep(minute);
+        let rate = rates.buckets.entry(bucket.to_string()).or_default();
+        rate.roll(minute);
+        rate.last_seen = minute;
+
+        let allowed = limit.is_none_or(|limit| rate.current < limit);
+        if allowed {
+            rate.current += 1;
+            metrics.inc_counter("infrared_signals_ingested_total", &[], 1.0);
+        } else {
+            rate.rejected += 1;
+            metrics.inc_counter("infrared_signals_rejected_total", &[], 1.0);
+        }
+
+        QuotaStatus {
+            allowed,
+            limit,
+            remaining: limit.map(|limit| limit.saturating_sub(rate.current)),
+            reset_seconds,
+        }
+    }
+
+    /// Undo a previously accepted signal (e.g., when storing it failed).
+    pub fn forget(&self, bucket: &str) {
+        let mut rates = self.rates.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(rate) = rates.buckets.get_mut(bucket) {
+            rate.current = rate.current.saturating_sub(1);
+        }
+    }
+
+    /// Current ingest rates for every bucket seen in the last
+    /// [`IDLE_MINUTES`], sorted by bucket.
+    pub fn stats(&self, now: DateTime<Utc>) -> Vec<BucketIngestStats> {
+        let ts = now.timestamp();
+        let minute = ts - ts.rem_euclid(60);
+
+        let mut rates = self.rates.lock().unwrap_or_else(|e| e.into_inner());
+        rates.sweep(minute);
+        let mut stats: Vec<BucketIngestStats> = rates
+            .buckets
+            .iter_mut()
+            .map(|(bucket, rate)| {
+                rate.roll(minute);
+                BucketIngestStats {
+                    bucket: bucket.clone(),
+                    current_minute: rate.current,
+                    signals_per_minute: rate.previous,
+                    quota_per_minute: self.config.limit_for(bucket),
+                    rejected_total: rate.rejected,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| a.bucket.cmp(&b.bucket));
+        stats
+    }
+
+    /// Publish the signals accepted in the previous minute, across all
+    /// buckets, to `metrics`, once any bucket has been seen.
+    pub fn publish_rates(&self, metrics: &Metrics, now: DateTime<Utc>) {
+        const GAUGE: &str = "infrared_ingest_signals_per_minute";
+        let stats = self.stats(now);
+        if stats.is_empty() && metrics.get(GAUGE, &[]).is_none() {
+            return;
+        }
+        let total: u64 = stats
+            .iter()
+            .map(|stats| u64::from(stats.signals_per_minute))
+            .sum();
+        metrics.set_gauge(GAUGE, &[], total as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_quota_enforced_per_minute() {
+        let tracker = IngestTracker::with_quotas(QuotaConfig {
+            default_per_minute: Some(2),
+            overrides: HashMap::new(),
+        });
+        let metrics = Metrics::new();
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+
+        assert!(tracker.record("zone-a", now, &metrics).allowed);
+        let second = tracker.record("zone-a", now, &metrics);
+        assert!(second.allowed);
+        assert_eq!(second.remaining, Some(0));
+
+        let third = tracker.record("zone-a", now, &metrics);
+        assert!(!third.allowed);
+        assert_eq!(third.reset_seconds, 40);
+
+        // Other buckets have their own budget
+        assert!(tracker.record("zone-b", now, &metrics).allowed);
+
+        // The next minute starts fresh
+        let later = now + chrono::Duration::seconds(40);
+        assert!(tracker.record("zone-a", later, &metrics).allowed);
+        assert_eq!(
+            metrics.get("infrared_signals_rejected_total", &[]),
+            Some(1.0)
+        );
+        assert_eq!(
+            metrics.get("infrared_signals_ingested_total", &[]),
+            Some(4.0)
+        );
+    }
+
+    #[test]
+    fn test_stats_report_previous_minute() {
+        let tracker = IngestTracker::new();
+        let metrics = Metrics::new();
+        let now = Utc.timestamp_opt(1_700_000_040, 0).unwrap();
+
+        for _ in 0..3 {
+            assert!(tracker.record("zone-a", now, &metrics).allowed);
+        }
+
+        let stats = tracker.stats(now + chrono::Duration::seconds(60));
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].signals_per_minute, 3);
+        assert_eq!(stats[0].current_minute, 0);
+        assert_eq!(stats[0].quota_per_minute, None);
+    }
+
+    #[test]
+    fn test_idle_buckets_are_forgotten() {
+        let tracker = IngestTracker::new();
+        let metrics = Metrics::new();
+        let now = Utc.timestamp_opt(1_700_000_040, 0).unwrap();
+        let minutes = |m| now + chrono::Duration::minutes(m);
+
+        for i in 0..100 {
+            tracker.record(&format!("random-{}", i), now, &metrics);
+        }
+        tracker.record("zone-a", minutes(1), &metrics);
+
+        // Metrics carry no bucket names
+        tracker.publish_rates(&metrics, minutes(1));
+        assert_eq!(
+            metrics.get("infrared_ingest_signals_per_minute", &[]),
+            Some(100.0)
+        );
+        assert_eq!(
+            metrics.get("infrared_signals_ingested_total", &[]),
+            Some(101.0)
+        );
+
+        // Scraping stats every minute does not keep idle buckets alive
+        assert_eq!(tracker.stats(minutes(1)).len(), 101);
+        let stats = tracker.stats(minutes(2));
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].bucket, "zone-a");
+        assert_eq!(stats[0].signals_per_minute, 1);
+        assert!(tracker.stats(minutes(3)).is_empty());
+    }
+
+    #[test]
+    fn test_parse_overrides() {
+        let overrides = QuotaConfig::parse_overrides("zone-a=600,region:north=5").unwrap();
+        assert_eq!(overrides["zone-a"], 600);
+        assert_eq!(overrides["region:north"], 5);
+
+        assert!(QuotaConfig::parse_overrides("zone-a").is_err());
+    }
+}
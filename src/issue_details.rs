@@ -0,0 +1,273 @@
+//! Typed, per-category issue details.
+//!
+//! Each data source knows more about its issues than the common [`Issue`]
+//! fields hold: the IODA signal that fired, ACLED's event count, the
+//! commodity behind a food price shock. [`IssueDetails`] carries those
+//! facts with their real types, one variant per [`IssueCategory`],
+//! serialized under the issue's `details` key with a `category` tag:
+//!
+//! ```json
+//! "details": { "category": "conflict", "event_count": 12, "acled_region": "Middle East" }
+//! ```
+//!
+//! Fields a source does not provide are left out, so consumers read numbers
+//! as numbers and test for presence rather than parsing strings.
+//!
+//! # Privacy
+//!
+//! Details hold the same country- and region-level facts as the rest of an
+//! issue, never people or coordinates.
+//!
+//! [`Issue`]: crate::dashboard::Issue
+
+use serde::{Deserialize, Serialize};
+
+use crate::dashboard::{IssueCategory, IssueSource};
+
+/// Source-specific facts about an issue, by category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "category", rename_all = "snake_case")]
+pub enum IssueDetails {
+    /// Details of an [`IssueCategory::InternetOutage`].
+    InternetOutage(OutageDetails),
+    /// Details of an [`IssueCategory::TrafficAnomaly`].
+    TrafficAnomaly(TrafficAnomalyDetails),
+    /// Details of an [`IssueCategory::Conflict`].
+    Conflict(ConflictDetails),
+    /// Details of an [`IssueCategory::FoodSecurity`].
+    FoodSecurity(FoodSecurityDetails),
+    /// Details of an [`IssueCategory::Displacement`].
+    Displacement(DisplacementDetails),
+    /// Details of an [`IssueCategory::Disaster`].
+    Disaster(DisasterDetails),
+    /// Details of an [`IssueCategory::HumanitarianEmergency`].
+    HumanitarianEmergency(HumanitarianDetails),
+    /// Details of an [`IssueCategory::Censorship`].
+    Censorship(CensorshipDetails),
+}
+
+impl IssueDetails {
+    /// The category these details belong to.
+    pub fn category(&self) -> IssueCategory {
+        match self {
+            IssueDetails::InternetOutage(_) => IssueCategory::InternetOutage,
+            IssueDetails::TrafficAnomaly(_) => IssueCategory::TrafficAnomaly,
+            IssueDetails::Conflict(_) => IssueCategory::Conflict,
+            IssueDetails::FoodSecurity(_) => IssueCategory::FoodSecurity,
+            IssueDetails::Displacement(_) => IssueCategory::Displacement,
+            IssueDetails::Disaster(_) => IssueCategory::Disaster,
+            IssueDetails::HumanitarianEmergency(_) => IssueCategory::HumanitarianEmergency,
+            IssueDetails::Censorship(_) => IssueCategory::Censorship,
+        }
+    }
+}
+
+/// A rise in a periodic figure, such as people in need or IDPs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FigureChange {
+    /// Figure in the earlier period.
+    pub previous: i64,
+
+    /// Figure in the latest period.
+    pub latest: i64,
+
+    /// Change from `previous` to `latest`, in percent.
+    pub percent_change: f64,
+}
+
+/// Details of an Internet outage.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OutageDetails {
+    /// IODA signal that detected the outage (e.g., "bgp", "ping-slash24").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datasource: Option<String>,
+
+    /// IODA alert condition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+
+    /// Tor transport whose users dropped ("relay" or "bridge").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<String>,
+}
+
+/// Details of a traffic anomaly.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrafficAnomalyDetails {
+    /// Cloudflare Radar anomaly type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly_type: Option<String>,
+
+    /// Whether Cloudflare verified the anomaly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
+
+    /// Wikimedia project whose readership collapsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+/// Details of a conflict issue.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConflictDetails {
+    /// Lethal events counted.
+    pub event_count: i64,
+
+    /// ACLED region queried, when the country was found through one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acled_region: Option<String>,
+}
+
+/// Details of a food security issue.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FoodSecurityDetails {
+    /// Staple with the steepest price rise.
+    pub commodity: String,
+
+    /// Its median month-over-month price rise, in percent.
+    pub percent_change: f64,
+}
+
+/// Details of a displacement issue.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisplacementDetails {
+    /// P-code of the first-level administrative area (IOM DTM).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin1_pcode: Option<String>,
+
+    /// IDPs now present in the area (IOM DTM).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idps: Option<u64>,
+
+    /// Rise in the national IDP figure (HDX HAPI).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub figure_change: Option<FigureChange>,
+}
+
+/// Details of a disaster.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DisasterDetails {
+    /// ReliefWeb disaster type (e.g., "Flood").
+    pub disaster_type: String,
+
+    /// ReliefWeb disaster ID.
+    pub reliefweb_id: String,
+
+    /// GLIDE number, when assigned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glide: Option<String>,
+
+    /// ReliefWeb status (e.g., "ongoing", "alert").
+    pub status: String,
+
+    /// How this disaster compares with the country's history (EM-DAT).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub historical_context: Option<String>,
+}
+
+/// Details of a humanitarian emergency.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HumanitarianDetails {
+    /// Every source that assessed the country's risk.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assessed_by: Vec<IssueSource>,
+
+    /// HDX national risk score, 0-10.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdx_risk_score: Option<f64>,
+
+    /// Change in the HDX risk score since the previous period.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hdx_risk_change: Option<f64>,
+
+    /// ACAPS INFORM Severity Index, 1-5.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acaps_severity_index: Option<f64>,
+
+    /// ACAPS crisis ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acaps_crisis_id: Option<String>,
+
+    /// Whether HDX and ACAPS reached the same severity, when both assessed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assessments_agree: Option<bool>,
+
+    /// Rise in people in need (HDX HAPI).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub figure_change: Option<FigureChange>,
+
+    /// Format of a ReliefWeb report raised on its own (e.g., "Situation Report").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report_format: Option<String>,
+}
+
+/// Details of a censorship issue.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CensorshipDetails {
+    /// Tor transport whose users spiked ("relay" or "bridge").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transport: Option<String>,
+}
+
+macro_rules! impl_from_details {
+    ($($variant:ident($details:ty)),* $(,)?) => {
+        $(
+            impl From<$details> for IssueDetails {
+                fn from(details: $details) -> Self {
+                    IssueDetails::$variant(details)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_details!(
+    InternetOutage(OutageDetails),
+    TrafficAnomaly(TrafficAnomalyDetails),
+    Conflict(ConflictDetails),
+    FoodSecurity(FoodSecurityDetails),
+    Displacement(DisplacementDetails),
+    Disaster(DisasterDetails),
+    HumanitarianEmergency(HumanitarianDetails),
+    Censorship(CensorshipDetails),
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialization() {
+        let details = IssueDetails::from(ConflictDetails {
+            event_count: 12,
+            acled_region: None,
+        });
+        assert_eq!(details.category(), IssueCategory::Conflict);
+        assert_eq!(
+            serde_json::to_value(&details).unwrap(),
+            serde_json::json!({ "category": "conflict", "event_count": 12 })
+        );
+
+        let details: IssueDetails = serde_json::from_value(serde_json::json!({
+            "category": "humanitarian_emergency",
+            "assessed_by": ["hdx_hapi", "acaps"],
+            "hdx_risk_score": 7.5,
+            "assessments_agree": true,
+        }))
+        .unwrap();
+        let IssueDetails::HumanitarianEmergency(details) = details else {
+            panic!("wrong variant");
+        };
+        assert_eq!(
+            details.assessed_by,
+            [IssueSource::HdxHapi, IssueSource::Acaps]
+        );
+        assert_eq!(details.hdx_risk_score, Some(7.5));
+        assert_eq!(details.assessments_agree, Some(true));
+
+        assert!(
+            serde_json::from_value::<IssueDetails>(serde_json::json!({ "category": "weather" }))
+                .is_err()
+        );
+    }
+}
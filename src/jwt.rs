@@ -0,0 +1,379 @@
+//! JWT bearer token validation.
+//!
+//! Deployments with an existing identity provider can mint short-lived JWTs
+//! instead of distributing static shared secrets. Tokens are verified with
+//! HS256 (shared secret) or RS256 (PEM public key or a JWKS URL), and the
+//! Infrared role is read from a configurable claim (default `role`).
+//!
+//! # Privacy
+//!
+//! Only the `sub` claim is retained, as the principal label. Tokens and
+//! their other claims are never logged or stored.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+use crate::auth::{Principal, Role};
+use crate::data_sources::http::default_user_agent;
+
+/// How long fetched JWKS keys are trusted before being refreshed.
+const JWKS_CACHE_TTL: Duration = Duration::from_secs(3600);
+
+/// Minimum time between JWKS refreshes triggered by unknown key IDs.
+const JWKS_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Time limit for a whole request to the identity provider.
+///
+/// Kept well below the route timeouts so a hung provider fails the request
+/// it stalls instead of holding it until the route gives up.
+const IDP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time limit for connecting to the identity provider.
+const IDP_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where verification keys come from.
+enum KeySource {
+    /// A single configured key.
+    Static(DecodingKey),
+    /// Keys fetched (and cached) from a JWKS endpoint, selected by `kid`.
+    Jwks {
+        url: String,
+        client: reqwest::Client,
+        cache: RwLock<JwksCache>,
+    },
+}
+
+#[derive(Default)]
+struct JwksCache {
+    keys: HashMap<String, DecodingKey>,
+    fetched_at: Option<Instant>,
+}
+
+/// Claims Infrared reads from a JWT.
+#[derive(Debug, Deserialize)]
+pub(crate) struct Claims {
+    #[serde(default)]
+    pub(crate) sub: Option<String>,
+    #[serde(flatten)]
+    pub(crate) extra: HashMap<String, serde_json::Value>,
+}
+
+/// Validates JWT bearer tokens and maps them to a [`Principal`].
+pub struct JwtValidator {
+    keys: KeySource,
+    validation: Validation,
+    role_claim: String,
+    default_role: Option<Role>,
+}
+
+impl JwtValidator {
+    /// Validate HS256 tokens signed with a shared secret.
+    pub fn hs256(secret: &str) -> Self {
+        Self::new(
+            Algorithm::HS256,
+            KeySource::Static(DecodingKey::from_secret(secret.as_bytes())),
+        )
+    }
+
+    /// Validate RS256 tokens against a PEM-encoded RSA public key.
+    pub fn rs256_pem(pem: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            Algorithm::RS256,
+            KeySource::Static(DecodingKey::from_rsa_pem(pem.as_bytes())?),
+        ))
+    }
+
+    /// Validate tokens against keys published at a JWKS URL.
+    ///
+    /// Keys are cached for an hour and refreshed early when a token names an
+    /// unknown key ID (at most once a minute).
+    pub fn jwks(url: &str, algorithm: Algorithm) -> Self {
+        Self::new(
+            algorithm,
+            KeySource::Jwks {
+                url: url.to_string(),
+                client: idp_client(),
+                cache: RwLock::new(JwksCache::default()),
+            },
+        )
+    }
+
+    fn new(algorithm: Algorithm, keys: KeySource) -> Self {
+        let mut validation = Validation::new(algorithm);
+        // Audience is only checked when one is configured
+        validation.validate_aud = false;
+
+        Self {
+            keys,
+            validation,
+            role_claim: "role".to_string(),
+            default_role: None,
+        }
+    }
+
+    /// Require the `iss` claim to match.
+    pub fn with_issuer(mut self, issuer: &str) -> Self {
+        self.validation.set_issuer(&[issuer]);
+        self
+    }
+
+    /// Require the `aud` claim to contain this audience.
+    pub fn with_audience(mut self, audience: &str) -> Self {
+        self.validation.set_audience(&[audience]);
+        self.validation.validate_aud = true;
+        self
+    }
+
+    /// Read the Infrared role from this claim instead of `role`.
+    pub fn with_role_claim(mut self, claim: &str) -> Self {
+        self.role_claim = claim.to_string();
+        self
+    }
+
+    /// Grant this role to valid tokens that carry no recognized role claim.
+    pub fn with_default_role(mut self, role: Role) -> Self {
+        self.default_role = Some(role);
+        self
+    }
+
+    /// Validate a token, returning the principal it grants.
+    ///
+    /// Returns `None` for invalid or expired tokens and for tokens without a
+    /// recognized role (unless a default role is configured).
+    pub async fn validate(&self, token: &str) -> Option<Principal> {
+        let claims = self.decode_claims(token).await?;
+        self.principal(claims)
+    }
+
+    /// Verify a token's signature and registered claims, returning its claims.
+    pub(crate) async fn decode_claims(&self, token: &str) -> Option<Claims> {
+        let key = match &self.keys {
+            KeySource::Static(key) => key.clone(),
+            KeySource::Jwks { .. } => {
+                let kid = decode_header(token).ok()?.kid?;
+                self.jwks_key(&kid).await?
+            }
+        };
+
+        Some(decode::<Claims>(token, &key, &self.validation).ok()?.claims)
+    }
+
+    /// Map verified claims to a principal.
+    pub(crate) fn principal(&self, claims: Claims) -> Option<Principal> {
+        let role = claims
+            .extra
+            .get(&self.role_claim)
+            .and_then(role_from_claim)
+            .or(self.default_role)?;
+
+        Some(Principal {
+            role,
+            label: claims.sub.unwrap_or_else(|| "jwt".to_string()),
+        })
+    }
+
+    async fn jwks_key(&self, kid: &str) -> Option<DecodingKey> {
+        let KeySource::Jwks { url, client, cache } = &self.keys else {
+            return None;
+        };
+
+        {
+            let cache = cache.read().await;
+            let fresh = cache
+                .fetched_at
+                .is_some_and(|at| at.elapsed() < JWKS_CACHE_TTL);
+            let recently_refreshed = cache
+                .fetched_at
+                .is_some_and(|at| at.elapsed() < JWKS_MIN_REFRESH_INTERVAL);
+            match cache.keys.get(kid) {
+                Some(key) if fresh => return Some(key.clone()),
+                None if recently_refreshed => return None,
+                _ => {}
+            }
+        }
+
+        let mut cache = cache.write().await;
+        match fetch_jwks(client, url).await {
+            Ok(keys) => {
+                cache.keys = keys;
+                cache.fetched_at = Some(Instant::now());
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to refresh JWKS");
+                // Keep serving previously fetched keys, but don't hammer the endpoint
+                cache.fetched_at = Some(Instant::now());
+            }
+        }
+        cache.keys.get(kid).cloned()
+    }
+}
+
+async fn fetch_jwks(
+    client: &reqwest::Client,
+    url: &str,
+) -> anyhow::Result<HashMap<String, DecodingKey>> {
+    let set: JwkSet = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    Ok(set
+        .keys
+        .iter()
+        .filter_map(|jwk| {
+            let kid = jwk.common.key_id.clone()?;
+            let key = DecodingKey::from_jwk(jwk).ok()?;
+            Some((kid, key))
+        })
+        .collect())
+}
+
+/// An HTTP client for talking to the identity provider (JWKS, OIDC).
+pub(crate) fn idp_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(default_user_agent())
+        .connect_timeout(IDP_CONNECT_TIMEOUT)
+        .timeout(IDP_TIMEOUT)
+        .build()
+        .expect("identity provider HTTP client settings are valid")
+}
+
+/// Map a role claim (a string or an array of strings) to the strongest role it names.
+fn role_from_claim(value: &serde_json::Value) -> Option<Role> {
+    let roles: Vec<Role> = match value {
+        serde_json::Value::String(s) => Role::parse(s).into_iter().collect(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_str().and_then(Role::parse))
+            .collect(),
+        _ => vec![],
+    };
+
+    if roles.contains(&Role::Admin) {
+        Some(Role::Admin)
+    } else {
+        roles.first().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{EncodingKey, Header, encode};
+    use serde_json::json;
+
+    fn sign(claims: serde_json::Value, secret: &str) -> String {
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    fn exp_in(seconds: i64) -> i64 {
+        chrono::Utc::now().timestamp() + seconds
+    }
+
+    #[tokio::test]
+    async fn test_hs256_valid_token() {
+        let validator = JwtValidator::hs256("secret");
+        let token = sign(
+            json!({ "sub": "analyst-1", "role": "read", "exp": exp_in(300) }),
+            "secret",
+        );
+
+        let principal = validator.validate(&token).await.unwrap();
+
+        assert_eq!(principal.role, Role::Read);
+        assert_eq!(principal.label, "analyst-1");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_bad_signature_and_expiry() {
+        let validator = JwtValidator::hs256("secret");
+
+        let wrong_key = sign(json!({ "role": "admin", "exp": exp_in(300) }), "other");
+        assert!(validator.validate(&wrong_key).await.is_none());
+
+        let expired = sign(json!({ "role": "admin", "exp": exp_in(-3600) }), "secret");
+        assert!(validator.validate(&expired).await.is_none());
+
+        let no_role = sign(json!({ "exp": exp_in(300) }), "secret");
+        assert!(validator.validate(&no_role).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_issuer_audience_and_custom_claim() {
+        let validator = JwtValidator::hs256("secret")
+            .with_issuer("https://idp.example")
+            .with_audience("infrared")
+            .with_role_claim("infrared_roles");
+
+        let good = sign(
+            json!({
+                "iss": "https://idp.example",
+                "aud": "infrared",
+                "infrared_roles": ["read", "admin"],
+                "exp": exp_in(300),
+            }),
+            "secret",
+        );
+        assert_eq!(validator.validate(&good).await.unwrap().role, Role::Admin);
+
+        let wrong_issuer = sign(
+            json!({
+                "iss": "https://evil.example",
+                "aud": "infrared",
+                "infrared_roles": ["admin"],
+                "exp": exp_in(300),
+            }),
+            "secret",
+        );
+        assert!(validator.validate(&wrong_issuer).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_jwks_key_lookup() {
+        use axum::{Json, Router, routing::get};
+
+        // "secret" base64url-encoded, published as a symmetric JWK
+        let jwks = json!({
+            "keys": [{ "kty": "oct", "kid": "k1", "alg": "HS256", "k": "c2VjcmV0" }]
+        });
+        let app = Router::new().route("/jwks", get(move || async move { Json(jwks) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let validator = JwtValidator::jwks(&format!("http://{}/jwks", addr), Algorithm::HS256);
+
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some("k1".to_string());
+        let token = encode(
+            &header,
+            &json!({ "role": "ingest", "exp": exp_in(300) }),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        assert_eq!(validator.validate(&token).await.unwrap().role, Role::Ingest);
+
+        header.kid = Some("unknown".to_string());
+        let token = encode(
+            &header,
+            &json!({ "role": "ingest", "exp": exp_in(300) }),
+            &EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        assert!(validator.validate(&token).await.is_none());
+    }
+}
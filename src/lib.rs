@@ -23,16 +23,139 @@
 //! - [`model`]: Data types for life signals, warmth responses, and alerts
 //! - [`storage`]: SQLite storage layer
 //! - [`aggregation`]: Logic for computing warmth indices
+//! - [`analyze`]: Long-term history across archived databases for `infrared analyze`
+//! - [`alert_history`]: Persisted history of generated alerts
 //! - [`api`]: HTTP API handlers
-//! - [`data_sources`]: External data source clients (IODA, Cloudflare, HDX, ACLED, ReliefWeb)
+//! - [`archive`]: Portable state archives for `infrared export-state` / `import-state`
+//! - [`signal_export`]: Parquet export of raw signals (`parquet` feature)
+//! - [`signal_stream`]: Streaming NDJSON/CSV export of raw signals
+//! - [`attribution`]: Licensing and attribution of dashboard data sources
+//! - [`audit`]: Append-only audit log of admin operations
+//! - [`auth`]: Role-based API token authentication
+//! - [`bucket_meta`]: Operator-set bucket metadata and threshold overrides
+//! - [`brief`]: Printable PDF situation briefs (`pdf` feature)
+//! - [`backtest`]: Alert-threshold backtesting for `infrared backtest`
+//! - [`backfill`]: Admin import of historical signals with their original timestamps
+//! - [`backup`]: Online database backups for `POST /admin/backup`
+//! - [`cadence`]: Severity-dependent refresh cadence of the dashboard refresher
+//! - [`calibration`]: Severity normalization across data sources
+//! - [`cap`]: Common Alerting Protocol messages for alerts and emergency issues
+//! - [`canary`]: Synthetic canary buckets for self-monitoring
+//! - [`channel`]: Configured signal channels for source attribution
+//! - [`chaos`]: Failure and latency injection for degradation tests (`chaos` feature)
+//! - [`check`]: Database integrity checks for `infrared check`
+//! - [`clock`]: Ingest timestamps protected against system clock jumps
+//! - [`compaction`]: Compaction of old raw signals into hourly sums
+//! - [`config`]: Configuration validation for `infrared check-config`
+//! - [`deadman`]: Dead-man's switch countdowns with escalating notifications
+//! - [`events`]: In-process event bus for decoupled subsystems
+//! - [`data_sources`]: External data source clients (IODA, Cloudflare, HDX, ACLED, ReliefWeb, and more)
 //! - [`dashboard`]: Dashboard for aggregating issues from all data sources
+//! - [`external`]: Issues posted by partners' own detectors
+//! - [`failover`]: Channel failover analysis telling path outages from population drops
+//! - [`feed`]: Bucket alerts and dashboard issues merged into one feed
+//! - [`forecast`]: Holt-Winters forecasts of expected bucket activity
+//! - [`bundle`]: Persisted issues exported as STIX 2.1 bundles
+//! - [`incidents`]: Related bucket alerts grouped into incidents
+//! - [`issue_details`]: Typed, per-category details of dashboard issues
+//! - [`heartbeat`]: Expected-heartbeat registration and missed-beat monitoring
+//! - [`oidc`]: OpenID Connect login for dashboard and admin endpoints
+//! - [`situation`]: Country situation reports combining issues and bucket warmth
+//! - [`severity`]: Alert severity tiers by distress duration, and per-tier routing
+//! - [`scheduler`]: Warmth snapshots of every bucket at each window boundary
+//! - [`ingest`]: Per-bucket ingest rate tracking and quotas
+//! - [`journal`]: Replayable journal of accepted signals
+//! - [`jwt`]: JWT bearer token validation (HS256/RS256, JWKS)
+//! - [`notify`]: Outbound webhook notifications
+//! - [`offsite`]: Scheduled encrypted backups to S3-compatible object storage
+//! - [`metrics`]: In-process metrics registry exposed at `GET /metrics`
+//! - [`naming`]: Bucket naming convention linting and ingest normalization
+//! - [`tension`]: Severity-weighted tension scores for the dashboard summary
+//! - [`telemetry`]: Opt-in anonymous instance-level telemetry
+//! - [`limits`]: Query guard rails against unbounded raw-signal scans
+//! - [`maintenance`]: Scheduled SQLite maintenance (optimize, vacuum, analyze)
+//! - [`partition`]: Daily partitions of the life signal table
+//! - [`profile`]: Deployment profiles presetting defaults for field, regional and global servers
+//! - [`public`]: Anonymized public status mirror with a k-anonymity floor
+//! - [`retention`]: Background pruning of life signals past the retention window
+//! - [`suppression`]: Operator rules suppressing known-noisy dashboard issues
+//! - [`s3`]: Minimal S3-compatible object storage client for off-site backups
+//! - [`timeouts`]: Per-route-class request timeouts
+//! - [`timezone`]: Per-bucket timezones for local-time baselines
+//! - [`timeline`]: Incident timelines, acknowledgments and notes
+//! - [`transitions`]: Bucket status change events and webhooks
+//! - [`watchdog`]: Notifications when a dashboard data source goes stale
+//! - [`write_buffer`]: Batched life signal inserts under heavy ingest
 
 pub mod aggregation;
+pub mod alert_history;
+pub mod analyze;
 pub mod api;
+pub mod archive;
+pub mod attribution;
+pub mod audit;
+pub mod auth;
+pub mod backfill;
+pub mod backtest;
+pub mod backup;
+#[cfg(feature = "pdf")]
+pub mod brief;
+pub mod bucket_meta;
+pub mod bundle;
+pub mod cadence;
+pub mod calibration;
+pub mod canary;
+pub mod cap;
+pub mod channel;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod check;
+pub mod clock;
+pub mod compaction;
+pub mod config;
 pub mod dashboard;
 pub mod data_sources;
+pub mod deadman;
+pub mod events;
+pub mod external;
+pub mod failover;
+pub mod feed;
+pub mod forecast;
+pub mod heartbeat;
+pub mod incidents;
+pub mod ingest;
+pub mod issue_details;
+pub mod journal;
+pub mod jwt;
+pub mod limits;
+pub mod maintenance;
+pub mod metrics;
 pub mod model;
+pub mod naming;
+pub mod notify;
+pub mod offsite;
+pub mod oidc;
+pub mod partition;
+pub mod profile;
+pub mod public;
+pub mod retention;
+pub mod s3;
+pub mod scheduler;
+pub mod severity;
+#[cfg(feature = "parquet")]
+pub mod signal_export;
+pub mod signal_stream;
+pub mod situation;
 pub mod storage;
+pub mod suppression;
+pub mod telemetry;
+pub mod tension;
+pub mod timeline;
+pub mod timeouts;
+pub mod timezone;
+pub mod transitions;
+pub mod watchdog;
+pub mod write_buffer;
 
 // Re-export data source clients for convenience
 pub use data_sources::{
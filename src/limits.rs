@@ -0,0 +1,283 @@
+//! Query guard rails.
+//!
+//! A warmth query reads raw signals for its current window, every baseline
+//! window and every sparkline window. Left unbounded, one careless
+//! `?window_minutes=` or `?baseline_span=` can scan a multi-gigabyte table
+//! end to end. [`QueryLimits`] caps the window size and the span of raw
+//! history a single query may touch; queries over a cap are rejected with
+//! `422 Unprocessable Entity` and a body naming the parameter and the limit,
+//! before any database work.
+//!
+//! # Privacy
+//!
+//! Limits only look at query parameters.
+
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::model::Baseline;
+
+/// Caps applied to query parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryLimits {
+    /// Largest `window_minutes` accepted.
+    pub max_window_minutes: u32,
+
+    /// Longest span of raw signals one query may read, in days.
+    pub max_raw_history_days: u32,
+
+    /// Most buckets one batch warmth query may name.
+    pub max_batch_buckets: u32,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        Self {
+            max_window_minutes: 24 * 60,
+            max_raw_history_days: 31,
+            max_batch_buckets: 100,
+        }
+    }
+}
+
+/// A query parameter over its limit.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LimitExceeded {
+    /// Always "limit_exceeded".
+    pub error: &'static str,
+
+    /// The offending parameter.
+    pub parameter: &'static str,
+
+    /// The limit that applies to it.
+    pub limit: u64,
+
+    /// Human-readable explanation.
+    pub message: String,
+}
+
+impl LimitExceeded {
+    fn new(parameter: &'static str, limit: u64, message: String) -> Self {
+        Self {
+            error: "limit_exceeded",
+            parameter,
+            limit,
+            message,
+        }
+    }
+}
+
+impl IntoResponse for LimitExceeded {
+    fn into_response(self) -> Response {
+        (StatusCode::UNPROCESSABLE_ENTITY, Json(self)).into_response()
+    }
+}
+
+impl QueryLimits {
+    /// Check a warmth query's window size and the raw history its baseline
+    /// and sparkline would read.
+    ///
+    /// A baseline set on the bucket by an admin is not checked here; only
+    /// what the query itself asks for.
+    pub fn check_warmth(
+        &self,
+        window_minutes: u32,
+        baseline: Option<Baseline>,
+        sparkline_windows: Option<u32>,
+    ) -> Result<(), LimitExceeded> {
+        if window_minutes == 0 || window_minutes > self.max_window_minutes {
+            return Err(LimitExceeded::new(
+                "window_minutes",
+                u64::from(self.max_window_minutes),
+                format!(
+                    "window_minutes must be between 1 and {}",
+                    self.max_window_minutes
+                ),
+            ));
+        }
+
+        let max_minutes = u64::from(self.max_raw_history_days) * 24 * 60;
+        let span = |windows: u32| u64::from(window_minutes) * u64::from(windows);
+        let over = |parameter: &'static str, minutes: u64| {
+            LimitExceeded::new(
+                parameter,
+                u64::from(self.max_raw_history_days),
+                format!(
+                    "{} would read {} days of raw signals; the limit is {} days",
+                    parameter,
+                    minutes.div_ceil(24 * 60),
+                    self.max_raw_history_days
+                ),
+            )
+        };
+
+        if let Some(baseline) = baseline {
+            // The current window plus every baseline window
+            let minutes = span(baseline.windows(window_minutes) + 1);
+            if minutes > max_minutes {
+                let parameter = match baseline {
+                    Baseline::Windows(_) => "baseline_windows",
+                    Baseline::SpanMinutes(_) => "baseline_span",
+                };
+                return Err(over(parameter, minutes));
+            }
+        }
+        if let Some(windows) = sparkline_windows {
+            let minutes = span(windows);
+            if minutes > max_minutes {
+                return Err(over("sparkline_windows", minutes));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check a forecast's window size and the days of history it fits.
+    pub fn check_forecast(&self, window_minutes: u32, days: u32) -> Result<(), LimitExceeded> {
+        self.check_warmth(window_minutes, None, None)?;
+        if days == 0 || days > self.max_raw_history_days {
+            return Err(LimitExceeded::new(
+                "days",
+                u64::from(self.max_raw_history_days),
+                format!("days must be between 1 and {}", self.max_raw_history_days),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check an alerts query's lookback, which every known bucket is
+    /// evaluated over.
+    pub fn check_alerts(&self, minutes: u32) -> Result<(), LimitExceeded> {
+        if minutes == 0 || minutes > self.max_window_minutes {
+            return Err(LimitExceeded::new(
+                "minutes",
+                u64::from(self.max_window_minutes),
+                format!("minutes must be between 1 and {}", self.max_window_minutes),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the number of buckets named by a batch warmth query.
+    pub fn check_batch(&self, buckets: usize) -> Result<(), LimitExceeded> {
+        if buckets > self.max_batch_buckets as usize {
+            return Err(LimitExceeded::new(
+                "buckets",
+                u64::from(self.max_batch_buckets),
+                format!(
+                    "{} buckets requested; at most {} are allowed per batch",
+                    buckets, self.max_batch_buckets
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the span of raw signals a signal export would read.
+    pub fn check_export(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<(), LimitExceeded> {
+        let seconds = (to - from).num_seconds().max(0) as u64;
+        let days = seconds.div_ceil(24 * 60 * 60);
+        if days > u64::from(self.max_raw_history_days) {
+            return Err(LimitExceeded::new(
+                "from",
+                u64::from(self.max_raw_history_days),
+                format!(
+                    "the export would read {} days of raw signals; the limit is {} days",
+                    days, self.max_raw_history_days
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_warmth() {
+        let limits = QueryLimits::default();
+        assert!(limits.check_warmth(10, None, None).is_ok());
+        assert!(
+            limits
+                .check_warmth(60, Some(Baseline::SpanMinutes(28 * 24 * 60)), Some(100))
+                .is_ok()
+        );
+
+        let err = limits.check_warmth(0, None, None).unwrap_err();
+        assert_eq!(err.parameter, "window_minutes");
+        let err = limits.check_warmth(1441, None, None).unwrap_err();
+        assert_eq!(err.limit, 1440);
+
+        // 1000 hourly windows is about six weeks of raw signals
+        let err = limits
+            .check_warmth(60, Some(Baseline::Windows(1000)), None)
+            .unwrap_err();
+        assert_eq!(err.parameter, "baseline_windows");
+        assert_eq!(err.limit, 31);
+        assert!(err.message.contains("42 days"), "{}", err.message);
+
+        let err = limits.check_warmth(1440, None, Some(32)).unwrap_err();
+        assert_eq!(err.parameter, "sparkline_windows");
+    }
+
+    #[test]
+    fn test_check_export() {
+        let limits = QueryLimits::default();
+        let to = Utc::now();
+        assert!(
+            limits
+                .check_export(to - chrono::Duration::days(31), to)
+                .is_ok()
+        );
+        assert!(limits.check_export(to, to).is_ok());
+
+        let err = limits
+            .check_export(to - chrono::Duration::days(40), to)
+            .unwrap_err();
+        assert_eq!(err.parameter, "from");
+        assert!(err.message.contains("40 days"), "{}", err.message);
+    }
+
+    #[test]
+    fn test_check_forecast() {
+        let limits = QueryLimits::default();
+        assert!(limits.check_forecast(10, 31).is_ok());
+        assert_eq!(limits.check_forecast(10, 0).unwrap_err().parameter, "days");
+        assert_eq!(limits.check_forecast(10, 32).unwrap_err().parameter, "days");
+        assert_eq!(
+            limits.check_forecast(0, 3).unwrap_err().parameter,
+            "window_minutes"
+        );
+    }
+
+    #[test]
+    fn test_check_alerts() {
+        let limits = QueryLimits::default();
+        assert!(limits.check_alerts(60).is_ok());
+        assert!(limits.check_alerts(1440).is_ok());
+
+        let err = limits.check_alerts(0).unwrap_err();
+        assert_eq!(err.parameter, "minutes");
+        let err = limits.check_alerts(1441).unwrap_err();
+        assert_eq!(err.limit, 1440);
+    }
+
+    #[test]
+    fn test_check_batch() {
+        let limits = QueryLimits::default();
+        assert!(limits.check_batch(100).is_ok());
+
+        let err = limits.check_batch(101).unwrap_err();
+        assert_eq!(err.parameter, "buckets");
+        assert_eq!(err.limit, 100);
+    }
+}
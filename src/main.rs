@@ -24,30 +24,134 @@
 //!
 //! - `POST /signal` - Record a life signal
 //! - `GET /warmth` - Query the warmth index for a bucket
+//! - `GET /warmth/history` - Scheduled warmth snapshots for a bucket
 //! - `GET /alerts/recent` - Get alerts for buckets in distress
+//! - `GET /alerts/history` - Alerts generated over time, one entry per episode
 //! - `GET /health` - Health check
+//! - `GET /stats` - Per-bucket ingest rates and quota usage
+//! - `GET /metrics` - Operational metrics (Prometheus text format)
+//! - `GET /heartbeats` - Registered heartbeats and whether each is missed
+//! - `GET /switches` - Armed dead-man's switches and their countdowns
+//! - `GET /groups` - Bucket groups (`/warmth?group=` and `/alerts/recent?group=` aggregate them)
+//!
+//! ## Admin Endpoints
+//!
+//! - `GET /admin/audit` - Append-only log of admin operations
+//! - `GET /admin/storage/stats` - Row counts, signal time span and database size
+//! - `PUT /buckets/:bucket/heartbeat` - Register a bucket's expected signal interval
+//! - `DELETE /buckets/:bucket/heartbeat` - Remove a registered heartbeat
+//! - `PUT /buckets/:bucket/switch` - Arm a bucket's dead-man's switch
+//! - `DELETE /buckets/:bucket/switch` - Disarm a bucket's dead-man's switch
+//! - `PUT /groups/:group` - Create or replace a bucket group
+//! - `DELETE /groups/:group` - Delete a bucket group
 //!
 //! ## Dashboard Endpoints (requires configuration)
 //!
-//! - `GET /dashboard` - Aggregated issues from all data sources
+//! - `GET /dashboard` - Aggregated issues from all data sources (`?at=` for past snapshots)
 //! - `GET /dashboard/summary` - Summary statistics only
 //! - `GET /dashboard/country/:code` - Issues for a specific country
 //! - `GET /dashboard/source/:source` - Issues from a specific source
+//!
+//! ## SSO Endpoints (when OIDC is configured)
+//!
+//! - `GET /auth/login` - Start the login flow
+//! - `GET /auth/callback` - Complete the login flow
+//! - `GET /auth/logout` - Clear the session
+//!
+//! # Commands
+//!
+//! - `infrared` / `infrared serve [--migrate]` - Run the HTTP server; `--migrate` upgrades a
+//!   database written by an older build (a database from a newer build is always refused)
+//! - `infrared check` - Verify database integrity, print a JSON report, exit nonzero on failure
+//! - `infrared check-config [--probe]` - Validate the configuration, print the effective settings
+//!   as JSON, exit nonzero on failure; `--probe` also checks notification endpoints are reachable
+//! - `infrared export-state <file>` - Write the database's state to a portable JSON archive
+//! - `infrared import-state <file>` - Load an archive into an empty database
+//! - `infrared backtest --from <time> --to <time> --thresholds <file>` - Replay stored signals
+//!   through candidate alert thresholds and print a JSON report
+//! - `infrared export-signals <file> [--bucket <bucket>] [--from <time>] [--to <time>]` - Write
+//!   raw life signals to a Parquet file (requires the `parquet` feature)
+//! - `infrared decrypt-backup <in> <out>` - Decrypt an off-site backup with
+//!   `INFRARED_OFFSITE_BACKUP_KEY`, writing a database file ready to serve
+//! - `infrared replay-journal <dir>` - Load the ingestion journal in `<dir>` into a database
+//!   holding no signals yet, and print what was replayed
+//! - `infrared analyze [--attach <file>]... [--bucket <bucket>] [--from <time>] [--to <time>]` -
+//!   Print each bucket's daily history and uptime across the database and archived database
+//!   files, attached read-only
+//! - `infrared buckets rename <old> <new>` / `infrared buckets merge <bucket>... --into <bucket>` -
+//!   Move a bucket's signals, settings, snapshots and alert history to another name in one
+//!   transaction, and print the rows moved per table
+//! - `infrared buckets lint` - Check bucket names against `INFRARED_BUCKET_PATTERN`, print
+//!   violations and likely duplicates as JSON, exit nonzero if there are any
 
 use std::env;
 use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
-use axum::{Router, routing::get, routing::post};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::Algorithm;
 use tokio::net::TcpListener;
-use tracing::info;
+use tracing::{info, warn};
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
-use infrared::api::{
-    AppState, get_alerts, get_dashboard, get_dashboard_by_country, get_dashboard_by_source,
-    get_dashboard_summary, get_warmth, health_check, post_signal,
+use infrared::aggregation::DEFAULT_DETECTOR_SIGMAS;
+use infrared::alert_history::spawn_alert_recorder;
+use infrared::analyze::analyze;
+use infrared::api::{AppState, router};
+use infrared::archive::{StateArchive, export_state, import_state};
+use infrared::audit::CLI_ACTOR;
+use infrared::auth::{AuthConfig, Role};
+use infrared::backfill::DEFAULT_IDEMPOTENCY_TTL_HOURS;
+use infrared::backtest::{BacktestConfig, run_backtest};
+use infrared::cadence::RefreshCadence;
+use infrared::calibration::Calibration;
+use infrared::canary::{
+    CANARY_PREFIX, DEFAULT_CANARY_BUCKET, spawn_canary, spawn_pipeline_signals,
 };
-use infrared::dashboard::{Dashboard, DashboardConfig};
-use infrared::storage::Storage;
+use infrared::cap::{DEFAULT_CAP_SENDER, spawn_cap_push};
+use infrared::channel::SignalChannels;
+use infrared::check::run_checks;
+use infrared::compaction::spawn_compaction_task;
+use infrared::config::{check_config, probe_notification_channels};
+use infrared::dashboard::{
+    Dashboard, DashboardConfig, IssueSource, RiskThresholds, spawn_snapshot_task,
+};
+use infrared::data_sources::acled::AcledRegion;
+use infrared::data_sources::wikimedia::WikimediaProject;
+use infrared::data_sources::{EmdatBaseline, EmdatClient, HttpClient, HttpClientConfig};
+use infrared::deadman::{EscalationPolicy, spawn_switch_monitor};
+use infrared::events::{EventBus, spawn_event_metrics};
+use infrared::heartbeat::spawn_heartbeat_monitor;
+use infrared::ingest::{IngestTracker, QuotaConfig};
+use infrared::journal::{Journal, JournalConfig, replay};
+use infrared::jwt::JwtValidator;
+use infrared::limits::QueryLimits;
+use infrared::maintenance::{QuietHours, spawn_maintenance_task};
+use infrared::metrics::Metrics;
+use infrared::model::{Baseline, BucketGroup, Detector, WarmthThresholds};
+use infrared::naming::NamingConvention;
+use infrared::notify::WebhookNotifier;
+use infrared::offsite::{BackupKey, OffsiteConfig, decrypt_file, spawn_offsite_backup_task};
+use infrared::oidc::{Oidc, OidcConfig};
+use infrared::profile::{Profile, lookup};
+use infrared::public::{DEFAULT_PUBLIC_MIN_BUCKETS, PublicMirror};
+use infrared::retention::spawn_retention_task;
+use infrared::s3::{S3Bucket, S3Config};
+use infrared::scheduler::spawn_warmth_scheduler;
+use infrared::severity::{SeverityRoutes, SeverityTiers, spawn_alert_router};
+use infrared::storage::{
+    ConnectionOptions, JournalMode, Migration, SignalFilter, SignalStore, Storage, Synchronous,
+};
+use infrared::suppression::SuppressionRules;
+use infrared::telemetry::spawn_telemetry;
+use infrared::tension::TensionWeights;
+use infrared::timeouts::RequestTimeouts;
+use infrared::timezone::parse_timezone;
+use infrared::transitions::spawn_transition_webhooks;
+use infrared::watchdog::spawn_source_watchdog;
+use infrared::write_buffer::{DEFAULT_MAX_DELAY, DEFAULT_MAX_ROWS, spawn_flush_task};
 
 /// Default port if not specified via environment variable.
 const DEFAULT_PORT: u16 = 3000;
@@ -55,8 +159,111 @@ const DEFAULT_PORT: u16 = 3000;
 /// Default database path if not specified via environment variable.
 const DEFAULT_DB_PATH: &str = "sqlite:infrared.db?mode=rwc";
 
+/// Default interval between persisted dashboard snapshots, in minutes.
+const DEFAULT_SNAPSHOT_INTERVAL_MINUTES: u64 = 60;
+
+/// Default interval between refreshes of dashboard sources reporting
+/// emergency or critical issues, in minutes.
+const DEFAULT_HOT_REFRESH_MINUTES: u64 = 15;
+
+/// Default hours without a successful fetch before a data source is
+/// reported stale.
+const DEFAULT_STALE_SOURCE_HOURS: u64 = 6;
+
+/// Default interval between database maintenance runs, in minutes.
+const DEFAULT_MAINTENANCE_INTERVAL_MINUTES: u64 = 360;
+
+/// Default number of days raw life signals are kept.
+const DEFAULT_RETENTION_DAYS: u32 = 90;
+
+/// Default number of days persisted dashboard snapshots are kept.
+const DEFAULT_SNAPSHOT_RETENTION_DAYS: u32 = 90;
+
+/// Default interval between heartbeat checks, in seconds.
+const DEFAULT_HEARTBEAT_CHECK_SECONDS: u64 = 30;
+
+/// Default interval between dead-man's switch checks, in seconds.
+const DEFAULT_SWITCH_CHECK_SECONDS: u64 = 30;
+
+/// Default interval between alert routing checks, in seconds.
+const DEFAULT_ALERT_CHECK_SECONDS: u64 = 60;
+
+/// Default window size of scheduled warmth snapshots, in minutes.
+const DEFAULT_WARMTH_SNAPSHOT_MINUTES: u32 = 10;
+
+/// Default interval between canary signals, in seconds.
+const DEFAULT_CANARY_INTERVAL_SECONDS: u64 = 60;
+
+/// Default interval between telemetry reports, in hours.
+const DEFAULT_TELEMETRY_INTERVAL_HOURS: u64 = 24;
+
+/// Default interval between off-site backups, in hours.
+const DEFAULT_OFFSITE_INTERVAL_HOURS: u64 = 24;
+
+/// Default number of off-site backups kept.
+const DEFAULT_OFFSITE_KEEP: usize = 7;
+
+/// Default size at which a journal file is closed, in MiB.
+const DEFAULT_JOURNAL_MAX_MB: u64 = 64;
+
+/// Default number of journal files kept.
+const DEFAULT_JOURNAL_KEEP: usize = 16;
+
+/// Deployment profile from `INFRARED_PROFILE`, read once at startup.
+static PROFILE: OnceLock<Option<Profile>> = OnceLock::new();
+
+/// An environment variable, else the deployment profile's value for it.
+fn var(name: &str) -> Result<String, env::VarError> {
+    let profile = PROFILE.get().copied().flatten();
+    lookup(|name| env::var(name).ok(), profile, name).ok_or(env::VarError::NotPresent)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // check-config reports an unknown profile itself
+    let profile = match env::args().nth(1).as_deref() {
+        Some("check-config") => None,
+        _ => Profile::from_env(|name| env::var(name).ok())?,
+    };
+    PROFILE.get_or_init(|| profile);
+    let db_url = var("INFRARED_DATABASE_URL").unwrap_or_else(|_| DEFAULT_DB_PATH.to_string());
+
+    // Subcommands run before tracing is set up so their stdout stays machine-readable
+    let mut migration = Migration::Refuse;
+    match env::args().nth(1).as_deref() {
+        None => {}
+        Some("serve") => {
+            for arg in env::args().skip(2) {
+                match arg.as_str() {
+                    "--migrate" => migration = Migration::Apply,
+                    other => anyhow::bail!("unknown serve option '{}'", other),
+                }
+            }
+        }
+        Some("check") => return run_check(&db_url).await,
+        Some("check-config") => return run_check_config(env::args().skip(2).collect()).await,
+        Some("export-state") => return run_export_state(&db_url, env::args().nth(2)).await,
+        Some("import-state") => return run_import_state(&db_url, env::args().nth(2)).await,
+        Some("backtest") => {
+            return run_backtest_command(&db_url, env::args().skip(2).collect()).await;
+        }
+        Some("export-signals") => {
+            return run_export_signals(&db_url, env::args().skip(2).collect()).await;
+        }
+        Some("decrypt-backup") => return run_decrypt_backup(env::args().skip(2).collect()),
+        Some("replay-journal") => return run_replay_journal(&db_url, env::args().nth(2)).await,
+        Some("analyze") => return run_analyze(&db_url, env::args().skip(2).collect()).await,
+        Some("buckets") => return run_buckets(&db_url, env::args().skip(2).collect()).await,
+        Some(other) => {
+            anyhow::bail!(
+                "unknown command '{}' (expected: serve, check, check-config, export-state, \
+                 import-state, backtest, export-signals, decrypt-backup, replay-journal, \
+                 analyze, buckets)",
+                other
+            )
+        }
+    }
+
     // Initialize tracing with environment filter
     // PRIVACY NOTE: Default log level is INFO to avoid accidentally logging sensitive data
     tracing_subscriber::registry()
@@ -64,48 +271,603 @@ async fn main() -> anyhow::Result<()> {
         .with(EnvFilter::from_default_env().add_directive("infrared=info".parse()?))
         .init();
 
+    if let Some(profile) = profile {
+        info!(profile = profile.name(), "Deployment profile applied");
+    }
+
     // Load configuration from environment
-    let port: u16 = env::var("INFRARED_PORT")
+    let port: u16 = var("INFRARED_PORT")
         .ok()
         .and_then(|p| p.parse().ok())
         .unwrap_or(DEFAULT_PORT);
 
-    let db_url = env::var("INFRARED_DATABASE_URL").unwrap_or_else(|_| DEFAULT_DB_PATH.to_string());
-
     info!(port, db_url = %db_url, "Starting Infrared server");
 
-    // Initialize storage
-    let storage = Storage::new(&db_url).await?;
+    // Initialize storage, upgrading an older schema only when asked to
+    let mut storage = Storage::open_with(&db_url, migration, connection_options()?).await?;
     info!("Database initialized");
 
+    // Warmth thresholds of buckets without their own overrides
+    let thresholds = warmth_thresholds()?;
+    if thresholds != WarmthThresholds::default() {
+        info!(
+            stressed_ratio = thresholds.stressed_ratio,
+            collapsing_ratio = thresholds.collapsing_ratio,
+            "Default warmth thresholds configured"
+        );
+    }
+    storage = storage.with_default_thresholds(thresholds);
+
+    // Detector deriving warmth statuses, unless a query names another
+    let detector = match var("INFRARED_DETECTOR") {
+        Ok(name) => Detector::parse(name.trim())
+            .ok_or_else(|| anyhow::anyhow!("INFRARED_DETECTOR: unknown detector '{}'", name))?,
+        Err(_) => Detector::Ratio,
+    };
+    let detector_sigmas: f64 = var("INFRARED_DETECTOR_SIGMAS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&s: &f64| s.is_finite() && s > 0.0)
+        .unwrap_or(DEFAULT_DETECTOR_SIGMAS);
+    if detector != Detector::Ratio {
+        info!(
+            ?detector,
+            sigmas = detector_sigmas,
+            "Warmth detector configured"
+        );
+    }
+    storage = storage.with_detector(detector, detector_sigmas);
+
+    // Batch signal inserts under load; 0 writes each signal as it arrives
+    let write_buffer_size: usize = var("INFRARED_WRITE_BUFFER_SIZE")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(DEFAULT_MAX_ROWS);
+    let write_buffer_delay = var("INFRARED_WRITE_BUFFER_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+        .filter(|&ms| ms > 0)
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_MAX_DELAY);
+
+    if write_buffer_size > 0 {
+        storage = storage.with_write_buffer(write_buffer_size, write_buffer_delay);
+        spawn_flush_task(storage.clone());
+        info!(
+            max_rows = write_buffer_size,
+            max_delay_ms = write_buffer_delay.as_millis() as u64,
+            "Life signal write buffer enabled"
+        );
+    }
+
+    // Groups from configuration replace same-named groups defined via the API
+    if let Ok(spec) = var("INFRARED_BUCKET_GROUPS") {
+        let groups = BucketGroup::parse_list(&spec)?;
+        for group in &groups {
+            storage.set_group(&group.name, &group.buckets).await?;
+        }
+        info!(
+            count = groups.len(),
+            "Bucket groups loaded from configuration"
+        );
+    }
+
+    // Baselines from configuration replace those set via the API
+    if let Ok(spec) = var("INFRARED_BUCKET_BASELINES") {
+        let baselines = Baseline::parse_overrides(&spec)?;
+        for (bucket, baseline) in &baselines {
+            storage.set_baseline(bucket, *baseline).await?;
+        }
+        info!(
+            count = baselines.len(),
+            "Bucket baselines loaded from configuration"
+        );
+    }
+
     // Initialize dashboard if configured
-    let dashboard = create_dashboard_if_configured();
+    let http_client = http_client_config().build()?;
+    let mut dashboard = create_dashboard_if_configured(&http_client)?;
+    if let Some(baseline) = load_emdat_baseline_if_configured(&http_client).await? {
+        dashboard = dashboard.map(|d| d.with_emdat_baseline(baseline));
+    }
     let dashboard_enabled = dashboard.is_some();
 
+    // One registry for the API and every background task
+    let metrics = Metrics::new();
+
+    // Periodically persist dashboard snapshots for time-travel queries
+    let snapshot_interval_minutes: u64 = var("DASHBOARD_SNAPSHOT_INTERVAL_MINUTES")
+        .ok()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(DEFAULT_SNAPSHOT_INTERVAL_MINUTES);
+
+    if let Some(dashboard) = &dashboard
+        && snapshot_interval_minutes > 0
+    {
+        // Sources with emergency or critical issues are refreshed sooner,
+        // within their budgets
+        let hot_refresh_minutes: u64 = var("DASHBOARD_HOT_REFRESH_MINUTES")
+            .ok()
+            .and_then(|m| m.parse().ok())
+            .unwrap_or(DEFAULT_HOT_REFRESH_MINUTES);
+        let mut cadence =
+            RefreshCadence::fixed(Duration::from_secs(snapshot_interval_minutes * 60));
+        if hot_refresh_minutes > 0 {
+            cadence.hot_interval = Duration::from_secs(hot_refresh_minutes * 60);
+        }
+        if let Ok(spec) = var("DASHBOARD_SOURCE_BUDGETS") {
+            cadence.budgets = RefreshCadence::parse_budgets(&spec)?;
+        }
+
+        info!(
+            interval_minutes = snapshot_interval_minutes,
+            hot_interval_minutes = cadence.tick().as_secs() / 60,
+            budgets = cadence.budgets.len(),
+            "Dashboard snapshots enabled"
+        );
+        spawn_snapshot_task(dashboard.clone(), storage.clone(), metrics.clone(), cadence);
+    }
+
+    // Per-bucket ingest quotas (unlimited unless configured)
+    let quotas = QuotaConfig {
+        default_per_minute: var("INFRARED_BUCKET_QUOTA_PER_MINUTE")
+            .ok()
+            .and_then(|q| q.parse().ok()),
+        overrides: match var("INFRARED_BUCKET_QUOTAS") {
+            Ok(spec) => QuotaConfig::parse_overrides(&spec)?,
+            Err(_) => Default::default(),
+        },
+    };
+
+    // Delivery paths signals may be attributed to (none unless configured)
+    let channels = match var("INFRARED_SIGNAL_CHANNELS") {
+        Ok(spec) => SignalChannels::parse(&spec)?,
+        Err(_) => SignalChannels::default(),
+    };
+    if !channels.is_empty() {
+        info!(
+            channels = %channels.names().collect::<Vec<_>>().join(","),
+            "Signal channels enabled"
+        );
+    }
+
+    // Per-route-class request time limits
+    let timeouts = match var("INFRARED_REQUEST_TIMEOUTS") {
+        Ok(spec) => RequestTimeouts::parse(&spec)?,
+        Err(_) => RequestTimeouts::default(),
+    };
+
+    // Caps on what a single query may scan
+    let default_limits = QueryLimits::default();
+    let limits = QueryLimits {
+        max_window_minutes: var("INFRARED_MAX_WINDOW_MINUTES")
+            .ok()
+            .and_then(|m| m.parse().ok())
+            .unwrap_or(default_limits.max_window_minutes),
+        max_raw_history_days: var("INFRARED_MAX_RAW_HISTORY_DAYS")
+            .ok()
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(default_limits.max_raw_history_days),
+        max_batch_buckets: var("INFRARED_MAX_BATCH_BUCKETS")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(default_limits.max_batch_buckets),
+    };
+
+    // Role-based API tokens (all endpoints open unless configured)
+    let mut auth = match var("INFRARED_API_TOKENS") {
+        Ok(spec) => AuthConfig::parse(&spec)?,
+        Err(_) => AuthConfig::disabled(),
+    };
+    if let Some(jwt) = create_jwt_validator_if_configured()? {
+        auth = auth.with_jwt(jwt);
+        info!("JWT bearer token validation enabled");
+    }
+    if let Some(config) = create_oidc_config_if_configured()? {
+        let issuer = config.issuer_url.clone();
+        auth = auth.with_oidc(Oidc::discover(config).await?);
+        info!(issuer = %issuer, "OIDC login enabled for dashboard and admin endpoints");
+    }
+    if auth.is_enabled() {
+        info!("API token authentication enabled");
+    } else {
+        info!("API token authentication disabled (set INFRARED_API_TOKENS to enable)");
+    }
+
+    // Alert severity escalates with how long a bucket has been in distress
+    let severity_tiers = match var("INFRARED_ALERT_SEVERITY_HOURS") {
+        Ok(spec) => SeverityTiers::parse(&spec)?,
+        Err(_) => SeverityTiers::default(),
+    };
+
+    // Status transition webhooks
+    let transition_webhooks: Vec<String> = match var("INFRARED_STATUS_WEBHOOKS") {
+        Ok(urls) => urls
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(String::from)
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    if !transition_webhooks.is_empty() {
+        info!(
+            count = transition_webhooks.len(),
+            "Status transition webhooks enabled"
+        );
+    }
+
+    // Civil-protection systems that only consume CAP
+    let cap_sender = var("INFRARED_CAP_SENDER").unwrap_or_else(|_| DEFAULT_CAP_SENDER.to_string());
+    let cap_endpoints: Vec<String> = match var("INFRARED_CAP_ENDPOINTS") {
+        Ok(urls) => urls
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(String::from)
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    // Subsystems exchange signals, status changes and dashboard findings
+    // over one in-process bus
+    let events = EventBus::new();
+    let dashboard = dashboard.map(|d| {
+        d.with_events(events.clone())
+            .with_external_issues(Arc::new(storage.clone()))
+    });
+
     // Create application state
-    let state = AppState { storage, dashboard };
+    let mut state = AppState::new(storage.clone(), dashboard)
+        .with_metrics(metrics)
+        .with_ingest(IngestTracker::with_quotas(quotas))
+        .with_auth(auth)
+        .with_timeouts(timeouts)
+        .with_limits(limits)
+        .with_severity_tiers(severity_tiers)
+        .with_cap_sender(cap_sender.clone())
+        .with_channels(channels)
+        .with_events(events);
+    if snapshot_interval_minutes > 0 {
+        state = state.with_snapshot_interval(Duration::from_secs(snapshot_interval_minutes * 60));
+    }
+    if let Ok(dir) = var("INFRARED_BACKUP_DIR") {
+        info!(dir = %dir, "Backups enabled in directory");
+        state = state.with_backup_dir(dir);
+    }
+
+    let naming = naming_convention()?;
+    if naming.normalizes() {
+        info!("Bucket names are normalized on ingest");
+    }
+    state = state.with_naming(naming);
+
+    if let Some(mirror) = public_mirror_if_configured()? {
+        info!(
+            min_buckets = mirror.min_buckets,
+            "Serving the public status mirror only"
+        );
+        state = state.with_public_mirror(mirror);
+    }
+
+    // Remember backfill idempotency keys long enough to cover client retries
+    let idempotency_hours = var("INFRARED_IDEMPOTENCY_TTL_HOURS")
+        .ok()
+        .and_then(|hours| hours.parse::<u64>().ok())
+        .filter(|&hours| hours > 0)
+        .unwrap_or(DEFAULT_IDEMPOTENCY_TTL_HOURS);
+    state = state.with_idempotency_ttl(Duration::from_secs(idempotency_hours * 3600));
+
+    // Journal accepted signals so a corrupted database can be rebuilt
+    if let Some(journal) = open_journal_if_configured()? {
+        info!("Ingestion journal enabled");
+        state = state.with_journal(journal);
+    }
+
+    // Ship encrypted backups off the server, so it can be rebuilt elsewhere
+    if let Some(offsite) = create_offsite_config_if_configured()? {
+        info!(
+            prefix = %offsite.prefix,
+            interval_hours = offsite.interval.as_secs() / 3600,
+            keep = offsite.keep,
+            "Off-site backups enabled"
+        );
+        spawn_offsite_backup_task(storage.clone(), offsite, state.metrics.clone());
+    }
+
+    spawn_event_metrics(&state.events, state.metrics.clone());
+
+    // Notifications are counted, so their delivery can be watched
+    let notifier = WebhookNotifier::new().with_metrics(state.metrics.clone());
+    if !transition_webhooks.is_empty() {
+        spawn_transition_webhooks(&state.events, transition_webhooks, notifier.clone());
+    }
+
+    // Tell operators when a data source has quietly stopped working; only
+    // the background refresher fetches sources on a schedule
+    let stale_source_hours: u64 = var("DASHBOARD_STALE_SOURCE_HOURS")
+        .ok()
+        .and_then(|h| h.parse().ok())
+        .unwrap_or(DEFAULT_STALE_SOURCE_HOURS);
+    if let Some(dashboard) = &state.dashboard
+        && snapshot_interval_minutes > 0
+        && stale_source_hours > 0
+    {
+        let ops_webhooks: Vec<String> = match var("INFRARED_OPS_WEBHOOKS") {
+            Ok(urls) => urls
+                .split(',')
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(String::from)
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        info!(
+            threshold_hours = stale_source_hours,
+            webhooks = ops_webhooks.len(),
+            "Stale data source watchdog enabled"
+        );
+        spawn_source_watchdog(
+            &state.events,
+            dashboard.fetched_sources(),
+            Duration::from_secs(stale_source_hours * 3600),
+            ops_webhooks,
+            notifier.clone(),
+            state.metrics.clone(),
+        );
+    }
+
+    // Periodically run SQLite maintenance to keep query plans healthy
+    let maintenance_interval_minutes: u64 = var("INFRARED_MAINTENANCE_INTERVAL_MINUTES")
+        .ok()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(DEFAULT_MAINTENANCE_INTERVAL_MINUTES);
+
+    if maintenance_interval_minutes > 0 {
+        // Vacuum and analyze when ingest is light, if told when that is
+        let quiet_hours = match var("INFRARED_MAINTENANCE_QUIET_HOURS") {
+            Ok(spec) => {
+                let timezone = match var("INFRARED_MAINTENANCE_TIMEZONE") {
+                    Ok(name) => parse_timezone(&name)?,
+                    Err(_) => chrono_tz::Tz::UTC,
+                };
+                Some(QuietHours::parse(&spec, timezone)?)
+            }
+            Err(_) => None,
+        };
+        spawn_maintenance_task(
+            storage.clone(),
+            state.metrics.clone(),
+            Duration::from_secs(maintenance_interval_minutes * 60),
+            quiet_hours,
+        );
+        info!(
+            interval_minutes = maintenance_interval_minutes,
+            quiet_hours = ?quiet_hours.map(|q| (q.start, q.end)),
+            "Database maintenance enabled"
+        );
+    }
+
+    // Prune raw signals past the retention window, so they do not live forever
+    let retention_days: u32 = var("INFRARED_RETENTION_DAYS")
+        .ok()
+        .and_then(|d| d.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS);
+
+    let snapshot_retention_days: u32 = var("DASHBOARD_SNAPSHOT_RETENTION_DAYS")
+        .ok()
+        .and_then(|d| d.parse().ok())
+        .unwrap_or(DEFAULT_SNAPSHOT_RETENTION_DAYS);
+
+    if retention_days > 0 || snapshot_retention_days > 0 {
+        spawn_retention_task(
+            storage.clone(),
+            state.metrics.clone(),
+            retention_days,
+            snapshot_retention_days,
+        );
+        info!(retention_days, snapshot_retention_days, "Retention enabled");
+    }
+
+    // Sum old raw signals into hourly rows, keeping baselines but not
+    // exact arrival times
+    let compact_after_days: u32 = var("INFRARED_COMPACT_AFTER_DAYS")
+        .ok()
+        .and_then(|d| d.parse().ok())
+        .unwrap_or(0);
+
+    if compact_after_days > 0 {
+        spawn_compaction_task(storage.clone(), state.metrics.clone(), compact_after_days);
+        info!(compact_after_days, "Life signal compaction enabled");
+    }
+
+    // Evaluate every bucket at each window boundary, so transitions fire and
+    // history accrues without anyone querying
+    let warmth_snapshot_minutes: u32 = var("INFRARED_WARMTH_SNAPSHOT_MINUTES")
+        .ok()
+        .and_then(|m| m.parse().ok())
+        .unwrap_or(DEFAULT_WARMTH_SNAPSHOT_MINUTES);
+
+    if warmth_snapshot_minutes > 0 {
+        spawn_warmth_scheduler(
+            storage.clone(),
+            state.transitions.clone(),
+            warmth_snapshot_minutes,
+        );
+        info!(
+            window_minutes = warmth_snapshot_minutes,
+            "Warmth snapshot scheduler enabled"
+        );
+    }
+
+    // Check registered heartbeats so missed beats surface promptly
+    let heartbeat_check_seconds: u64 = var("INFRARED_HEARTBEAT_CHECK_SECONDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_HEARTBEAT_CHECK_SECONDS);
+
+    if heartbeat_check_seconds > 0 {
+        spawn_heartbeat_monitor(
+            storage.clone(),
+            state.metrics.clone(),
+            Duration::from_secs(heartbeat_check_seconds),
+        );
+        info!(
+            interval_seconds = heartbeat_check_seconds,
+            "Heartbeat monitor enabled"
+        );
+    }
+
+    // Fire escalation notifications for expired dead-man's switches
+    let escalation = match var("INFRARED_SWITCH_ESCALATION") {
+        Ok(spec) => EscalationPolicy::parse(&spec)?,
+        Err(_) => EscalationPolicy::default(),
+    };
+    let switch_check_seconds: u64 = var("INFRARED_SWITCH_CHECK_SECONDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_SWITCH_CHECK_SECONDS);
+
+    if escalation.is_empty() {
+        info!("Dead-man's switch notifications disabled (set INFRARED_SWITCH_ESCALATION)");
+    } else if switch_check_seconds > 0 {
+        info!(
+            steps = escalation.steps().len(),
+            interval_seconds = switch_check_seconds,
+            "Dead-man's switch monitor enabled"
+        );
+        spawn_switch_monitor(
+            storage.clone(),
+            state.metrics.clone(),
+            escalation,
+            notifier.clone(),
+            Duration::from_secs(switch_check_seconds),
+        );
+    }
+
+    // Route alerts to per-severity webhooks as they escalate
+    let alert_routes = match var("INFRARED_ALERT_ROUTES") {
+        Ok(spec) => SeverityRoutes::parse(&spec)?,
+        Err(_) => SeverityRoutes::default(),
+    };
+    let alert_check_seconds: u64 = var("INFRARED_ALERT_CHECK_SECONDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_ALERT_CHECK_SECONDS);
+
+    if alert_routes.is_empty() {
+        info!("Alert routing disabled (set INFRARED_ALERT_ROUTES)");
+        // The router records alerts as it checks them; without it, keep
+        // the alert history on the same cadence
+        if alert_check_seconds > 0 {
+            spawn_alert_recorder(
+                storage.clone(),
+                state.severity,
+                Duration::from_secs(alert_check_seconds),
+            );
+        }
+    } else if alert_check_seconds > 0 {
+        info!(
+            interval_seconds = alert_check_seconds,
+            "Alert routing enabled"
+        );
+        spawn_alert_router(
+            storage.clone(),
+            state.severity,
+            alert_routes,
+            notifier.clone(),
+            Duration::from_secs(alert_check_seconds),
+        );
+    }
+
+    if !cap_endpoints.is_empty() && alert_check_seconds > 0 {
+        info!(count = cap_endpoints.len(), "CAP push enabled");
+        spawn_cap_push(
+            storage.clone(),
+            state.severity,
+            &state.events,
+            cap_endpoints,
+            cap_sender,
+            notifier.clone(),
+            Duration::from_secs(alert_check_seconds),
+        );
+    }
+
+    // Emit synthetic canary signals so broken ingest or clocks are noticed
+    let canary_interval_seconds: u64 = var("INFRARED_CANARY_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_CANARY_INTERVAL_SECONDS);
+
+    if canary_interval_seconds > 0 {
+        let canaries: Vec<String> = match var("INFRARED_CANARY_BUCKETS") {
+            Ok(names) => names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| {
+                    if name.starts_with(CANARY_PREFIX) {
+                        name.to_string()
+                    } else {
+                        format!("{}{}", CANARY_PREFIX, name)
+                    }
+                })
+                .collect(),
+            Err(_) => vec![DEFAULT_CANARY_BUCKET.to_string()],
+        };
+        info!(
+            buckets = ?canaries,
+            interval_seconds = canary_interval_seconds,
+            "Canary generator enabled"
+        );
+        spawn_canary(
+            storage.clone(),
+            state.metrics.clone(),
+            canaries,
+            Duration::from_secs(canary_interval_seconds),
+        );
+
+        // Feed Infrared's own pipeline activity into reserved buckets, so
+        // a stalled subsystem raises an alert like any other bucket
+        spawn_pipeline_signals(
+            storage.clone(),
+            state.metrics.clone(),
+            Duration::from_secs(canary_interval_seconds),
+            (dashboard_enabled && snapshot_interval_minutes > 0)
+                .then(|| Duration::from_secs(snapshot_interval_minutes * 60)),
+        );
+    }
+
+    // Anonymous instance-level telemetry is strictly opt-in
+    if let Ok(url) = var("INFRARED_TELEMETRY_URL") {
+        let interval_hours: u64 = var("INFRARED_TELEMETRY_INTERVAL_HOURS")
+            .ok()
+            .and_then(|h| h.parse().ok())
+            .filter(|h| *h > 0)
+            .unwrap_or(DEFAULT_TELEMETRY_INTERVAL_HOURS);
+        info!(
+            url = %url,
+            interval_hours,
+            "Telemetry enabled: reporting version, bucket count and signal rate only"
+        );
+        spawn_telemetry(
+            storage.clone(),
+            &state.events,
+            url,
+            Duration::from_secs(interval_hours * 3600),
+            WebhookNotifier::new(),
+        );
+    }
 
     // Build router
     // PRIVACY NOTE: We do NOT use any middleware that logs IP addresses or headers
-    let mut app = Router::new()
-        .route("/signal", post(post_signal))
-        .route("/warmth", get(get_warmth))
-        .route("/alerts/recent", get(get_alerts))
-        .route("/health", get(health_check));
-
-    // Add dashboard routes if configured
     if dashboard_enabled {
-        app = app
-            .route("/dashboard", get(get_dashboard))
-            .route("/dashboard/summary", get(get_dashboard_summary))
-            .route("/dashboard/country/:code", get(get_dashboard_by_country))
-            .route("/dashboard/source/:source", get(get_dashboard_by_source));
         info!("Dashboard enabled with external data sources");
     } else {
         info!("Dashboard disabled (set ACLED_EMAIL/ACLED_KEY for full functionality)");
     }
 
-    let app = app.with_state(state);
+    let app = router(state);
 
     // Start server
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
@@ -114,11 +876,710 @@ async fn main() -> anyhow::Result<()> {
     info!(%addr, "Infrared is listening");
     info!("Privacy mode: ENABLED (no PII logging, no IP tracking)");
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    // Requests have drained; write what they buffered before exiting
+    let flushed = storage.flush_writes().await?;
+    storage.close().await;
+    info!(flushed, "Infrared stopped");
+
+    Ok(())
+}
+
+/// Resolve on Ctrl-C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!(error = %e, "Failed to listen for Ctrl-C");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+    info!("Shutting down");
+}
+
+/// `infrared check` - verify database integrity and print a JSON report.
+///
+/// Exits with status 1 if any check fails.
+async fn run_check(db_url: &str) -> anyhow::Result<()> {
+    let storage = Storage::connect_with(db_url, connection_options()?).await?;
+    let report = run_checks(&storage).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if !report.ok {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// `infrared check-config [--probe]` - validate the configuration and print
+/// the effective settings as JSON.
+///
+/// Exits with status 1 if anything is invalid or, with `--probe`, if a
+/// notification endpoint is unreachable.
+async fn run_check_config(args: Vec<String>) -> anyhow::Result<()> {
+    let mut probe = false;
+    for arg in args {
+        match arg.as_str() {
+            "--probe" => probe = true,
+            other => anyhow::bail!("unknown check-config option '{}'", other),
+        }
+    }
+
+    let mut report = check_config(|name| env::var(name).ok());
+    if probe {
+        probe_notification_channels(&mut report, |name| env::var(name).ok()).await;
+    }
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if !report.ok {
+        std::process::exit(1);
+    }
 
     Ok(())
 }
 
+/// `infrared export-state <file>` - write the database's state to a
+/// portable JSON archive and print the row counts.
+async fn run_export_state(db_url: &str, path: Option<String>) -> anyhow::Result<()> {
+    let path = path.ok_or_else(|| anyhow::anyhow!("usage: infrared export-state <file>"))?;
+    let storage = Storage::open_with(db_url, Migration::Refuse, connection_options()?).await?;
+    let archive = export_state(&storage, Utc::now()).await?;
+
+    std::fs::write(&path, serde_json::to_vec(&archive)?)?;
+    println!("{}", serde_json::to_string_pretty(&archive.counts())?);
+    Ok(())
+}
+
+/// `infrared decrypt-backup <in> <out>` - decrypt an off-site backup into
+/// a database file.
+fn run_decrypt_backup(args: Vec<String>) -> anyhow::Result<()> {
+    let [encrypted, plaintext] = <[String; 2]>::try_from(args)
+        .map_err(|_| anyhow::anyhow!("usage: infrared decrypt-backup <in> <out>"))?;
+    let key = var("INFRARED_OFFSITE_BACKUP_KEY")
+        .map_err(|_| anyhow::anyhow!("INFRARED_OFFSITE_BACKUP_KEY is required"))?;
+
+    decrypt_file(
+        encrypted.as_ref(),
+        plaintext.as_ref(),
+        &BackupKey::parse(&key)?,
+    )?;
+    println!("{}", plaintext);
+    Ok(())
+}
+
+/// `infrared replay-journal <dir>` - load journaled signals into a database
+/// holding none yet, and print the replay report.
+async fn run_replay_journal(db_url: &str, dir: Option<String>) -> anyhow::Result<()> {
+    let dir = dir.ok_or_else(|| anyhow::anyhow!("usage: infrared replay-journal <dir>"))?;
+    let storage = Storage::open_with(db_url, Migration::Refuse, connection_options()?).await?;
+    let report = replay(&storage, dir.as_ref()).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// Run `infrared analyze [--attach <file>]... [--bucket <bucket>]
+/// [--from <RFC 3339>] [--to <RFC 3339>]` and print the history report.
+///
+/// Every database, including the live one, is opened read-only, so this is
+/// safe to run next to a serving instance.
+async fn run_analyze(db_url: &str, args: Vec<String>) -> anyhow::Result<()> {
+    let mut archives = Vec::new();
+    let mut filter = SignalFilter::default();
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("{} requires a value", flag))?;
+        match flag.as_str() {
+            "--attach" => archives.push(PathBuf::from(value)),
+            "--bucket" => filter.bucket = Some(value),
+            "--from" => {
+                filter.from = Some(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc))
+            }
+            "--to" => filter.to = Some(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc)),
+            other => anyhow::bail!("unknown analyze option '{}'", other),
+        }
+    }
+
+    let storage = Storage::open_with_archives(db_url, connection_options()?, &archives).await?;
+    let report = analyze(&storage, &filter).await?;
+    storage.close().await;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// Run `infrared buckets rename <old> <new>` or `infrared buckets merge
+/// <bucket>... --into <bucket>` and print the rewrite report.
+///
+/// Senders still using an old name would recreate it, so switch them over
+/// (or stop the server) first.
+async fn run_buckets(db_url: &str, args: Vec<String>) -> anyhow::Result<()> {
+    let usage = "usage: infrared buckets rename <old> <new> | \
+                 infrared buckets merge <bucket>... --into <bucket> | infrared buckets lint";
+    if args.first().is_some_and(|command| command == "lint") {
+        anyhow::ensure!(args.len() == 1, usage);
+        return run_bucket_lint(db_url).await;
+    }
+
+    let storage = Storage::open_with(db_url, Migration::Refuse, connection_options()?).await?;
+    let report = match args.split_first() {
+        Some((command, rest)) if command == "rename" => {
+            let [old, new] = rest else {
+                anyhow::bail!(usage);
+            };
+            storage
+                .rename_bucket(old, new, CLI_ACTOR, Utc::now())
+                .await?
+        }
+        Some((command, rest)) if command == "merge" => {
+            let mut sources = Vec::new();
+            let mut into = None;
+            let mut rest = rest.iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--into" => {
+                        into = Some(
+                            rest.next()
+                                .ok_or_else(|| anyhow::anyhow!("--into requires a value"))?,
+                        )
+                    }
+                    other if other.starts_with("--") => {
+                        anyhow::bail!("unknown buckets merge option '{}'", other)
+                    }
+                    bucket => sources.push(bucket.to_string()),
+                }
+            }
+            let Some(into) = into.filter(|_| !sources.is_empty()) else {
+                anyhow::bail!(usage);
+            };
+            storage
+                .merge_buckets(&sources, into, CLI_ACTOR, Utc::now())
+                .await?
+        }
+        _ => anyhow::bail!(usage),
+    };
+    storage.close().await;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// `infrared buckets lint` - check bucket names against the naming
+/// convention and print the report.
+///
+/// Exits with status 1 if any name breaks the convention or looks like a
+/// duplicate of another.
+async fn run_bucket_lint(db_url: &str) -> anyhow::Result<()> {
+    let naming = naming_convention()?;
+    let storage = Storage::connect_with(db_url, connection_options()?).await?;
+    let buckets = storage.get_buckets().await?;
+    let report = naming.lint(buckets.iter().map(|entry| entry.bucket.as_str()));
+    storage.close().await;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if !report.is_clean() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// `infrared import-state <file>` - load an archive into an empty database
+/// and print the row counts.
+async fn run_import_state(db_url: &str, path: Option<String>) -> anyhow::Result<()> {
+    let path = path.ok_or_else(|| anyhow::anyhow!("usage: infrared import-state <file>"))?;
+    let archive: StateArchive = serde_json::from_slice(&std::fs::read(&path)?)?;
+    let storage = Storage::open_with(db_url, Migration::Refuse, connection_options()?).await?;
+    let imported = import_state(&storage, &archive).await?;
+
+    println!("{}", serde_json::to_string_pretty(&imported)?);
+    Ok(())
+}
+
+/// Run `infrared backtest --from <RFC 3339> --to <RFC 3339> --thresholds <file>`.
+async fn run_backtest_command(db_url: &str, args: Vec<String>) -> anyhow::Result<()> {
+    let mut from = None;
+    let mut to = None;
+    let mut thresholds = None;
+
+    let mut args = args.into_iter();
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("{} requires a value", flag))?;
+        match flag.as_str() {
+            "--from" => from = Some(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc)),
+            "--to" => to = Some(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc)),
+            "--thresholds" => thresholds = Some(value),
+            other => anyhow::bail!("unknown backtest option '{}'", other),
+        }
+    }
+
+    let usage = "usage: infrared backtest --from <time> --to <time> --thresholds <file>";
+    let (Some(from), Some(to), Some(thresholds)) = (from, to, thresholds) else {
+        anyhow::bail!(usage);
+    };
+
+    let config: BacktestConfig = serde_json::from_str(&std::fs::read_to_string(thresholds)?)?;
+    let storage = Storage::connect_with(db_url, connection_options()?).await?;
+    let report = run_backtest(&storage, &config, warmth_thresholds()?, from, to).await?;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// Run `infrared export-signals <file> [--bucket <bucket>] [--from <RFC 3339>]
+/// [--to <RFC 3339>]` and print the number of signals written.
+///
+/// Unlike `GET /admin/signals.parquet`, the range is not capped: an
+/// operator may export the whole table.
+async fn run_export_signals(db_url: &str, args: Vec<String>) -> anyhow::Result<()> {
+    let mut path = None;
+    let mut filter = SignalFilter::default();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with("--") {
+            anyhow::ensure!(path.is_none(), "unexpected argument '{}'", arg);
+            path = Some(arg);
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("{} requires a value", arg))?;
+        match arg.as_str() {
+            "--bucket" => filter.bucket = Some(value),
+            "--from" => {
+                filter.from = Some(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc))
+            }
+            "--to" => filter.to = Some(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc)),
+            other => anyhow::bail!("unknown export-signals option '{}'", other),
+        }
+    }
+
+    let path = path.ok_or_else(|| {
+        anyhow::anyhow!(
+            "usage: infrared export-signals <file> [--bucket <bucket>] [--from <time>] [--to <time>]"
+        )
+    })?;
+    let storage = Storage::connect_with(db_url, connection_options()?).await?;
+    let written = export_signals(&storage, &filter, &path).await?;
+
+    println!("{}", serde_json::json!({ "life_signals": written }));
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+async fn export_signals(
+    storage: &Storage,
+    filter: &SignalFilter,
+    path: &str,
+) -> anyhow::Result<u64> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let written = storage.export_signals(filter, file).await;
+    if written.is_err() {
+        // Don't leave a truncated file behind
+        let _ = std::fs::remove_file(path);
+    }
+    written
+}
+
+#[cfg(not(feature = "parquet"))]
+async fn export_signals(
+    _storage: &Storage,
+    _filter: &SignalFilter,
+    _path: &str,
+) -> anyhow::Result<u64> {
+    anyhow::bail!("infrared was built without the `parquet` feature")
+}
+
+/// Warmth thresholds of buckets without their own overrides, from
+/// `INFRARED_STRESSED_RATIO` and `INFRARED_COLLAPSING_RATIO`.
+fn warmth_thresholds() -> anyhow::Result<WarmthThresholds> {
+    let defaults = WarmthThresholds::default();
+    let thresholds = WarmthThresholds {
+        stressed_ratio: var("INFRARED_STRESSED_RATIO")
+            .ok()
+            .and_then(|r| r.parse().ok())
+            .unwrap_or(defaults.stressed_ratio),
+        collapsing_ratio: var("INFRARED_COLLAPSING_RATIO")
+            .ok()
+            .and_then(|r| r.parse().ok())
+            .unwrap_or(defaults.collapsing_ratio),
+        ewma_alpha: None,
+    };
+    thresholds.validate()?;
+    Ok(thresholds)
+}
+
+/// SQLite connection options from environment variables.
+///
+/// Every command uses these: the journal mode is stored in the database
+/// file, so a command opening it with different settings would change it.
+///
+/// # Environment Variables
+///
+/// - `INFRARED_SQLITE_JOURNAL_MODE` - wal, delete or truncate (default: wal)
+/// - `INFRARED_SQLITE_SYNCHRONOUS` - off, normal, full or extra (default: normal)
+/// - `INFRARED_SQLITE_BUSY_TIMEOUT_MS` - How long a write waits for the lock (default: 5000)
+/// - `INFRARED_SQLITE_CACHE_KIB` - Page cache per connection, in KiB (default: SQLite's)
+/// - `INFRARED_SQLITE_WRITE_CONNECTIONS` - Connections used for writes (default: 1)
+/// - `INFRARED_SQLITE_READ_CONNECTIONS` - Read-only connections used for queries (default: 4)
+fn connection_options() -> anyhow::Result<ConnectionOptions> {
+    let mut options = ConnectionOptions::default();
+    if let Ok(mode) = var("INFRARED_SQLITE_JOURNAL_MODE") {
+        options.journal_mode = JournalMode::parse(&mode)?;
+    }
+    if let Ok(level) = var("INFRARED_SQLITE_SYNCHRONOUS") {
+        options.synchronous = Synchronous::parse(&level)?;
+    }
+    if let Some(ms) = var("INFRARED_SQLITE_BUSY_TIMEOUT_MS")
+        .ok()
+        .and_then(|ms| ms.parse().ok())
+    {
+        options.busy_timeout = Duration::from_millis(ms);
+    }
+    options.cache_size_kib = var("INFRARED_SQLITE_CACHE_KIB")
+        .ok()
+        .and_then(|kib| kib.parse().ok())
+        .filter(|&kib| kib > 0);
+    let connections = |name: &str| {
+        var(name)
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+    };
+    if let Some(n) = connections("INFRARED_SQLITE_WRITE_CONNECTIONS") {
+        options.write_connections = n;
+    }
+    if let Some(n) = connections("INFRARED_SQLITE_READ_CONNECTIONS") {
+        options.read_connections = n;
+    }
+    Ok(options)
+}
+
+/// HTTP client settings for data sources from environment variables.
+///
+/// # Environment Variables
+///
+/// - `DASHBOARD_HTTP_USER_AGENT` - User-Agent header (default: "infrared/<version>")
+/// - `DASHBOARD_HTTP_TIMEOUT_SECONDS` - Time limit per request (default: 30)
+/// - `DASHBOARD_HTTP_CONNECT_TIMEOUT_SECONDS` - Time limit per connection (default: 10)
+/// - `DASHBOARD_HTTP_PROXY` - Proxy URL for every request (default: `HTTPS_PROXY` and friends)
+/// - `DASHBOARD_HTTP_CA_CERT` - PEM file of extra root certificates to trust (optional)
+/// - `DASHBOARD_HTTP_POOL_MAX_IDLE_PER_HOST` - Idle connections kept per host (default: unlimited)
+/// - `DASHBOARD_HTTP_POOL_IDLE_SECONDS` - How long idle connections are kept (default: 90)
+/// - `DASHBOARD_HTTP_MAX_RESPONSE_MB` - Largest response body read, in MiB (default: 32)
+fn http_client_config() -> HttpClientConfig {
+    let seconds = |name: &str| {
+        var(name)
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|&s| s > 0)
+            .map(Duration::from_secs)
+    };
+
+    let mut config = HttpClientConfig::default();
+    if let Ok(user_agent) = var("DASHBOARD_HTTP_USER_AGENT") {
+        config.user_agent = user_agent;
+    }
+    if let Some(timeout) = seconds("DASHBOARD_HTTP_TIMEOUT_SECONDS") {
+        config.timeout = timeout;
+    }
+    if let Some(timeout) = seconds("DASHBOARD_HTTP_CONNECT_TIMEOUT_SECONDS") {
+        config.connect_timeout = timeout;
+    }
+    if let Some(idle) = seconds("DASHBOARD_HTTP_POOL_IDLE_SECONDS") {
+        config.pool_idle_timeout = idle;
+    }
+    config.proxy = var("DASHBOARD_HTTP_PROXY").ok();
+    config.ca_cert = var("DASHBOARD_HTTP_CA_CERT").ok().map(PathBuf::from);
+    config.pool_max_idle_per_host = var("DASHBOARD_HTTP_POOL_MAX_IDLE_PER_HOST")
+        .ok()
+        .and_then(|n| n.parse().ok());
+    if let Some(mb) = var("DASHBOARD_HTTP_MAX_RESPONSE_MB")
+        .ok()
+        .and_then(|n| n.parse::<u64>().ok())
+        .filter(|&n| n > 0)
+    {
+        config.max_response_bytes = mb * 1024 * 1024;
+    }
+    config
+}
+
+/// Create off-site backup configuration from environment variables, if
+/// configured.
+///
+/// # Environment Variables
+///
+/// - `INFRARED_OFFSITE_BUCKET` - Bucket to upload backups to (enables off-site backups)
+/// - `INFRARED_OFFSITE_ENDPOINT` - S3-compatible endpoint URL (default: AWS in the region)
+/// - `INFRARED_OFFSITE_REGION` - Region requests are signed for (default: "us-east-1")
+/// - `INFRARED_OFFSITE_PREFIX` - Prefix of backup object keys (default: "infrared/")
+/// - `INFRARED_OFFSITE_ACCESS_KEY_ID` - Access key ID (required with bucket)
+/// - `INFRARED_OFFSITE_SECRET_ACCESS_KEY` - Secret access key (required with bucket)
+/// - `INFRARED_OFFSITE_BACKUP_KEY` - 64 hex digits of AES-256 key (required with bucket)
+/// - `INFRARED_OFFSITE_INTERVAL_HOURS` - Hours between backups (default: 24)
+/// - `INFRARED_OFFSITE_KEEP` - Backups kept in the bucket (default: 7)
+///
+/// Backups are staged in `INFRARED_BACKUP_DIR` if set, otherwise in the
+/// system's temporary directory.
+fn create_offsite_config_if_configured() -> anyhow::Result<Option<OffsiteConfig>> {
+    let Ok(bucket) = var("INFRARED_OFFSITE_BUCKET") else {
+        return Ok(None);
+    };
+    let required = |name: &str| {
+        var(name)
+            .map_err(|_| anyhow::anyhow!("{} is required when off-site backups are enabled", name))
+    };
+
+    let region = var("INFRARED_OFFSITE_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+    let endpoint = var("INFRARED_OFFSITE_ENDPOINT")
+        .unwrap_or_else(|_| format!("https://s3.{}.amazonaws.com", region));
+    let s3 = S3Config {
+        endpoint: endpoint.parse()?,
+        bucket,
+        region,
+        access_key_id: required("INFRARED_OFFSITE_ACCESS_KEY_ID")?,
+        secret_access_key: required("INFRARED_OFFSITE_SECRET_ACCESS_KEY")?,
+    };
+    let hours: u64 = var("INFRARED_OFFSITE_INTERVAL_HOURS")
+        .ok()
+        .and_then(|h| h.parse().ok())
+        .filter(|&h| h > 0)
+        .unwrap_or(DEFAULT_OFFSITE_INTERVAL_HOURS);
+
+    Ok(Some(OffsiteConfig {
+        bucket: S3Bucket::new(s3, reqwest::Client::new()),
+        prefix: var("INFRARED_OFFSITE_PREFIX").unwrap_or_else(|_| "infrared/".to_string()),
+        key: BackupKey::parse(&required("INFRARED_OFFSITE_BACKUP_KEY")?)?,
+        interval: Duration::from_secs(hours * 3600),
+        keep: var("INFRARED_OFFSITE_KEEP")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_OFFSITE_KEEP),
+        work_dir: var("INFRARED_BACKUP_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| env::temp_dir()),
+    }))
+}
+
+/// Bucket naming convention from environment variables.
+///
+/// # Environment Variables
+///
+/// - `INFRARED_BUCKET_PATTERN` - Regular expression every bucket name must match in full
+/// - `INFRARED_BUCKET_NORMALIZE` - `true` to trim and lowercase bucket names on ingest
+///   (default: false)
+fn naming_convention() -> anyhow::Result<NamingConvention> {
+    let normalize = match var("INFRARED_BUCKET_NORMALIZE") {
+        Ok(value) => value.trim().parse().map_err(|_| {
+            anyhow::anyhow!(
+                "INFRARED_BUCKET_NORMALIZE must be true or false, not '{}'",
+                value
+            )
+        })?,
+        Err(_) => false,
+    };
+    NamingConvention::new(var("INFRARED_BUCKET_PATTERN").ok().as_deref(), normalize)
+}
+
+/// Public status mirror from environment variables, if enabled.
+///
+/// # Environment Variables
+///
+/// - `INFRARED_PUBLIC_MIRROR` - `true` to serve only `/health` and `/public/status` (default: false)
+/// - `INFRARED_PUBLIC_MIN_BUCKETS` - Buckets a country or group must cover to be listed (default: 5)
+/// - `INFRARED_PUBLIC_LABELS` - Display labels as `name=Label` pairs separated by `;` (optional)
+fn public_mirror_if_configured() -> anyhow::Result<Option<PublicMirror>> {
+    let enabled = match var("INFRARED_PUBLIC_MIRROR") {
+        Ok(value) => value.trim().parse().map_err(|_| {
+            anyhow::anyhow!(
+                "INFRARED_PUBLIC_MIRROR must be true or false, not '{}'",
+                value
+            )
+        })?,
+        Err(_) => false,
+    };
+    if !enabled {
+        return Ok(None);
+    }
+    Ok(Some(PublicMirror {
+        min_buckets: var("INFRARED_PUBLIC_MIN_BUCKETS")
+            .ok()
+            .and_then(|n| n.trim().parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_PUBLIC_MIN_BUCKETS),
+        labels: match var("INFRARED_PUBLIC_LABELS") {
+            Ok(spec) => PublicMirror::parse_labels(&spec)?,
+            Err(_) => Default::default(),
+        },
+    }))
+}
+
+/// Open the ingestion journal from environment variables, if configured.
+///
+/// # Environment Variables
+///
+/// - `INFRARED_JOURNAL_DIR` - Directory to journal accepted signals into (enables the journal)
+/// - `INFRARED_JOURNAL_MAX_MB` - Size at which a journal file is closed (default: 64)
+/// - `INFRARED_JOURNAL_KEEP` - Journal files kept (default: 16)
+fn open_journal_if_configured() -> anyhow::Result<Option<Journal>> {
+    let Ok(dir) = var("INFRARED_JOURNAL_DIR") else {
+        return Ok(None);
+    };
+    let max_mb: u64 = var("INFRARED_JOURNAL_MAX_MB")
+        .ok()
+        .and_then(|mb| mb.parse().ok())
+        .filter(|&mb| mb > 0)
+        .unwrap_or(DEFAULT_JOURNAL_MAX_MB);
+
+    Ok(Some(Journal::open(JournalConfig {
+        dir: PathBuf::from(dir),
+        max_file_bytes: max_mb * 1024 * 1024,
+        keep: var("INFRARED_JOURNAL_KEEP")
+            .ok()
+            .and_then(|n| n.parse().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_JOURNAL_KEEP),
+    })?))
+}
+
+/// Create a JWT validator from environment variables, if configured.
+///
+/// # Environment Variables
+///
+/// - `INFRARED_JWT_SECRET` - Shared secret for HS256 tokens
+/// - `INFRARED_JWT_PUBLIC_KEY_FILE` - PEM RSA public key for RS256 tokens
+/// - `INFRARED_JWT_JWKS_URL` - JWKS endpoint for RS256 tokens
+/// - `INFRARED_JWT_ISSUER` - Required `iss` claim (optional)
+/// - `INFRARED_JWT_AUDIENCE` - Required `aud` claim (optional)
+/// - `INFRARED_JWT_ROLE_CLAIM` - Claim holding the Infrared role (default: "role")
+fn create_jwt_validator_if_configured() -> anyhow::Result<Option<JwtValidator>> {
+    let validator = if let Ok(secret) = var("INFRARED_JWT_SECRET") {
+        JwtValidator::hs256(&secret)
+    } else if let Ok(path) = var("INFRARED_JWT_PUBLIC_KEY_FILE") {
+        JwtValidator::rs256_pem(&std::fs::read_to_string(path)?)?
+    } else if let Ok(url) = var("INFRARED_JWT_JWKS_URL") {
+        JwtValidator::jwks(&url, Algorithm::RS256)
+    } else {
+        return Ok(None);
+    };
+
+    let mut validator = validator;
+    if let Ok(issuer) = var("INFRARED_JWT_ISSUER") {
+        validator = validator.with_issuer(&issuer);
+    }
+    if let Ok(audience) = var("INFRARED_JWT_AUDIENCE") {
+        validator = validator.with_audience(&audience);
+    }
+    if let Ok(claim) = var("INFRARED_JWT_ROLE_CLAIM") {
+        validator = validator.with_role_claim(&claim);
+    }
+
+    Ok(Some(validator))
+}
+
+/// Create OIDC login configuration from environment variables, if configured.
+///
+/// # Environment Variables
+///
+/// - `INFRARED_OIDC_ISSUER` - Identity provider issuer URL (enables OIDC)
+/// - `INFRARED_OIDC_CLIENT_ID` - OAuth client ID (required with issuer)
+/// - `INFRARED_OIDC_CLIENT_SECRET` - OAuth client secret (required with issuer)
+/// - `INFRARED_OIDC_REDIRECT_URL` - Public URL of `/auth/callback` (required with issuer)
+/// - `INFRARED_SESSION_SECRET` - Secret for signing session cookies (required with issuer)
+/// - `INFRARED_OIDC_ROLE_CLAIM` - ID token claim holding the role (default: "role")
+/// - `INFRARED_OIDC_DEFAULT_ROLE` - Role for users without the claim (default: "read")
+fn create_oidc_config_if_configured() -> anyhow::Result<Option<OidcConfig>> {
+    let Ok(issuer) = var("INFRARED_OIDC_ISSUER") else {
+        return Ok(None);
+    };
+    let required = |name: &str| {
+        var(name).map_err(|_| anyhow::anyhow!("{} is required when OIDC is enabled", name))
+    };
+
+    let mut config = OidcConfig::new(
+        &issuer,
+        &required("INFRARED_OIDC_CLIENT_ID")?,
+        &required("INFRARED_OIDC_CLIENT_SECRET")?,
+        &required("INFRARED_OIDC_REDIRECT_URL")?,
+        &required("INFRARED_SESSION_SECRET")?,
+    );
+    if let Ok(claim) = var("INFRARED_OIDC_ROLE_CLAIM") {
+        config.role_claim = claim;
+    }
+    if let Ok(role) = var("INFRARED_OIDC_DEFAULT_ROLE") {
+        config.default_role =
+            Role::parse(&role).ok_or_else(|| anyhow::anyhow!("unknown role '{}'", role))?;
+    }
+
+    Ok(Some(config))
+}
+
+/// Load the EM-DAT disaster baseline used to annotate dashboard issues.
+///
+/// # Environment Variables
+///
+/// - `EMDAT_CSV` - Path to a public table exported from the EM-DAT portal (preferred)
+/// - `EMDAT_API_KEY` - EM-DAT API key; the last 20 years are fetched at startup
+///
+/// A failing API fetch is logged and the dashboard runs without annotations.
+async fn load_emdat_baseline_if_configured(
+    http_client: &HttpClient,
+) -> anyhow::Result<Option<EmdatBaseline>> {
+    let baseline = if let Ok(path) = var("EMDAT_CSV") {
+        EmdatBaseline::from_csv(&std::fs::read_to_string(path)?)?
+    } else if let Ok(key) = var("EMDAT_API_KEY") {
+        let client = EmdatClient::new(&key).with_http_client(http_client.clone());
+        match client.get_recent_baseline().await {
+            Ok(baseline) => baseline,
+            Err(e) => {
+                warn!(error = %e, "Failed to fetch EM-DAT baseline");
+                return Ok(None);
+            }
+        }
+    } else {
+        return Ok(None);
+    };
+
+    info!(
+        records = baseline.records().len(),
+        "EM-DAT disaster baseline loaded"
+    );
+    Ok(Some(baseline))
+}
+
 /// Create dashboard configuration from environment variables.
 ///
 /// # Environment Variables
@@ -126,21 +1587,82 @@ async fn main() -> anyhow::Result<()> {
 /// - `ACLED_EMAIL` - Email for ACLED API authentication (optional)
 /// - `ACLED_KEY` - API key for ACLED API authentication (optional)
 /// - `CLOUDFLARE_TOKEN` - Cloudflare API token for higher rate limits (optional)
+/// - `ACAPS_TOKEN` - ACAPS API token for the INFORM Severity Index (optional)
+/// - `IOM_DTM_KEY` - IOM DTM API subscription key for displacement tracking (optional)
 /// - `DASHBOARD_APP_ID` - Application identifier for HDX/ReliefWeb (default: "infrared")
 /// - `DASHBOARD_LOOKBACK_HOURS` - Hours to look back for issues (default: 24)
-fn create_dashboard_if_configured() -> Option<Dashboard> {
+/// - `WIKIMEDIA_PROJECTS` - Wikipedia projects to watch as `project=country` pairs (optional)
+/// - `DASHBOARD_SOURCES` - Comma-separated sources to fetch, e.g. `ioda,reliefweb` (default: all)
+/// - `INFRARED_SEVERITY_CALIBRATION` - Path to a severity calibration JSON file (optional)
+/// - `INFRARED_TENSION_WEIGHTS` - Severity/category weights for tension scores (optional)
+/// - `INFRARED_SUPPRESSION_RULES` - Path to an issue suppression rules JSON file (optional)
+fn create_dashboard_if_configured(http_client: &HttpClient) -> anyhow::Result<Option<Dashboard>> {
     let config = DashboardConfig {
-        acled_email: env::var("ACLED_EMAIL").ok(),
-        acled_key: env::var("ACLED_KEY").ok(),
-        cloudflare_token: env::var("CLOUDFLARE_TOKEN").ok(),
-        app_identifier: env::var("DASHBOARD_APP_ID").unwrap_or_else(|_| "infrared".to_string()),
+        acled_email: var("ACLED_EMAIL").ok(),
+        acled_key: var("ACLED_KEY").ok(),
+        cloudflare_token: var("CLOUDFLARE_TOKEN").ok(),
+        acaps_token: var("ACAPS_TOKEN").ok(),
+        iom_dtm_key: var("IOM_DTM_KEY").ok(),
+        app_identifier: var("DASHBOARD_APP_ID").unwrap_or_else(|_| "infrared".to_string()),
         monitored_countries: vec![], // Countries can be configured via API or extended config
-        lookback_hours: env::var("DASHBOARD_LOOKBACK_HOURS")
+        acled_regions: match var("ACLED_REGIONS") {
+            Ok(spec) => AcledRegion::parse_list(&spec)?,
+            Err(_) => vec![],
+        },
+        hdx_risk_thresholds: RiskThresholds {
+            high: var("HDX_RISK_HIGH")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(RiskThresholds::default().high),
+            very_high: var("HDX_RISK_VERY_HIGH")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(RiskThresholds::default().very_high),
+        },
+        hdx_country_risk_thresholds: match var("HDX_COUNTRY_RISK_THRESHOLDS") {
+            Ok(spec) => RiskThresholds::parse_country_list(&spec)?,
+            Err(_) => Default::default(),
+        },
+        hdx_rising_risk_delta: var("HDX_RISING_RISK_DELTA")
+            .ok()
+            .and_then(|d| d.parse().ok())
+            .unwrap_or(1.0),
+        hdx_change_threshold_percent: var("HDX_CHANGE_THRESHOLD_PERCENT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(25.0),
+        food_price_spike_percent: var("FOOD_PRICE_SPIKE_PERCENT")
+            .ok()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or(20.0),
+        lookback_hours: var("DASHBOARD_LOOKBACK_HOURS")
             .ok()
             .and_then(|h| h.parse().ok())
             .unwrap_or(24),
+        wikimedia_projects: match var("WIKIMEDIA_PROJECTS") {
+            Ok(spec) => WikimediaProject::parse_list(&spec)?,
+            Err(_) => vec![],
+        },
+        sources: match var("DASHBOARD_SOURCES") {
+            Ok(spec) => Some(IssueSource::parse_list(&spec)?),
+            Err(_) => None,
+        },
     };
 
+    let mut dashboard = Dashboard::new(config).with_http_client(http_client.clone());
+    if let Ok(path) = var("INFRARED_SEVERITY_CALIBRATION") {
+        dashboard =
+            dashboard.with_calibration(Calibration::from_json(&std::fs::read_to_string(path)?)?);
+    }
+    if let Ok(spec) = var("INFRARED_TENSION_WEIGHTS") {
+        dashboard = dashboard.with_tension_weights(TensionWeights::parse(&spec)?);
+    }
+    if let Ok(path) = var("INFRARED_SUPPRESSION_RULES") {
+        dashboard = dashboard.with_suppression_rules(SuppressionRules::from_json(
+            &std::fs::read_to_string(path)?,
+        )?);
+    }
+
     // Dashboard is always enabled, but ACLED data requires authentication
-    Some(Dashboard::new(config))
+    Ok(Some(dashboard))
 }
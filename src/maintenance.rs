@@ -0,0 +1,257 @@
+//! Scheduled SQLite maintenance.
+//!
+//! Long-running deployments accumulate stale query-planner statistics and free
+//! pages. The maintenance worker periodically runs `PRAGMA optimize`, an
+//! incremental vacuum, and `ANALYZE`, recording how long each step took.
+//!
+//! Vacuuming and analyzing a large database holds the write lock long
+//! enough to stall ingest. With [`QuietHours`] configured, a run that falls
+//! due outside them waits for the next quiet period instead, and is counted
+//! in `infrared_maintenance_deferred_total`.
+//!
+//! # Privacy
+//!
+//! Maintenance only touches database internals; no signal data is read or logged.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use tokio::time::MissedTickBehavior;
+use tracing::{info, warn};
+
+use crate::metrics::Metrics;
+use crate::storage::Storage;
+
+/// Durations of each step of a maintenance run.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceReport {
+    /// Time spent in `PRAGMA optimize`.
+    pub optimize: Duration,
+
+    /// Time spent in `PRAGMA incremental_vacuum`.
+    pub incremental_vacuum: Duration,
+
+    /// Time spent in `ANALYZE`.
+    pub analyze: Duration,
+}
+
+impl MaintenanceReport {
+    /// Total duration of the run.
+    pub fn total(&self) -> Duration {
+        self.optimize + self.incremental_vacuum + self.analyze
+    }
+}
+
+/// Hours of the day when maintenance may run, e.g. `01-05` for 01:00 up to
+/// 05:00 in the given timezone. Windows may wrap past midnight (`22-04`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuietHours {
+    /// First hour of the window.
+    pub start: u32,
+
+    /// Hour the window ends, exclusive.
+    pub end: u32,
+
+    /// Timezone the hours are in.
+    pub timezone: Tz,
+}
+
+impl QuietHours {
+    /// Parse `start-end` hours (0-23) in `timezone`.
+    pub fn parse(spec: &str, timezone: Tz) -> anyhow::Result<Self> {
+        let hours = spec.split_once('-').and_then(|(start, end)| {
+            let start: u32 = start.trim().parse().ok()?;
+            let end: u32 = end.trim().parse().ok()?;
+            (start < 24 && end < 24 && start != end).then_some((start, end))
+        });
+        let (start, end) = hours.ok_or_else(|| {
+            anyhow::anyhow!(
+                "expected quiet hours as start-end, e.g. 01-05, got '{}'",
+                spec
+            )
+        })?;
+        Ok(Self {
+            start,
+            end,
+            timezone,
+        })
+    }
+
+    /// Whether `now` is within the window.
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let hour = now.with_timezone(&self.timezone).hour();
+        if self.start < self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+
+    /// How long from `now` until the window next opens; zero within it.
+    pub fn wait_from(&self, now: DateTime<Utc>) -> Duration {
+        if self.contains(now) {
+            return Duration::ZERO;
+        }
+        let local = now.with_timezone(&self.timezone);
+        let hours = (self.start + 24 - local.hour()) % 24;
+        let into_hour = u64::from(local.minute() * 60 + local.second());
+        Duration::from_secs(u64::from(hours) * 3600 - into_hour)
+    }
+}
+
+/// Run all maintenance steps once, timing each of them.
+pub async fn run_maintenance(storage: &Storage) -> anyhow::Result<MaintenanceReport> {
+    let started = Instant::now();
+    storage.optimize().await?;
+    let optimize = started.elapsed();
+
+    let started = Instant::now();
+    storage.incremental_vacuum().await?;
+    let incremental_vacuum = started.elapsed();
+
+    let started = Instant::now();
+    storage.analyze().await?;
+    let analyze = started.elapsed();
+
+    Ok(MaintenanceReport {
+        optimize,
+        incremental_vacuum,
+        analyze,
+    })
+}
+
+/// Publish a maintenance report to the metrics registry.
+fn record_metrics(metrics: &Metrics, report: &MaintenanceReport) {
+    for (step, duration) in [
+        ("optimize", report.optimize),
+        ("incremental_vacuum", report.incremental_vacuum),
+        ("analyze", report.analyze),
+    ] {
+        metrics.set_gauge(
+            "infrared_maintenance_step_duration_seconds",
+            &[("step", step)],
+            duration.as_secs_f64(),
+        );
+    }
+    metrics.set_gauge(
+        "infrared_maintenance_duration_seconds",
+        &[],
+        report.total().as_secs_f64(),
+    );
+    metrics.inc_counter("infrared_maintenance_runs_total", &[], 1.0);
+}
+
+/// Spawn the background maintenance worker.
+///
+/// The first run happens one `interval` after startup, so maintenance never
+/// competes with boot-time load. With `quiet_hours`, runs falling due
+/// outside them wait for the window; runs missed while waiting are skipped.
+pub fn spawn_maintenance_task(
+    storage: Storage,
+    metrics: Metrics,
+    interval: Duration,
+    quiet_hours: Option<QuietHours>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+
+            if let Some(quiet_hours) = &quiet_hours {
+                let wait = quiet_hours.wait_from(Utc::now());
+                if !wait.is_zero() {
+                    metrics.inc_counter("infrared_maintenance_deferred_total", &[], 1.0);
+                    info!(
+                        wait_minutes = wait.as_secs() / 60,
+                        "Database maintenance deferred to quiet hours"
+                    );
+                    tokio::time::sleep(wait).await;
+                }
+            }
+
+            match run_maintenance(&storage).await {
+                Ok(report) => {
+                    record_metrics(&metrics, &report);
+                    info!(
+                        duration_ms = report.total().as_millis() as u64,
+                        "Database maintenance completed"
+                    );
+                }
+                Err(e) => {
+                    metrics.inc_counter("infrared_maintenance_failures_total", &[], 1.0);
+                    warn!(error = %e, "Database maintenance failed");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_maintenance() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        let report = run_maintenance(&storage).await.unwrap();
+
+        assert_eq!(
+            report.total(),
+            report.optimize + report.incremental_vacuum + report.analyze
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        use chrono::TimeZone;
+
+        let at =
+            |hour: u32, minute: u32| Utc.with_ymd_and_hms(2024, 5, 1, hour, minute, 0).unwrap();
+        let night = QuietHours::parse("01-05", Tz::UTC).unwrap();
+        assert!(night.contains(at(1, 0)));
+        assert!(night.contains(at(4, 59)));
+        assert!(!night.contains(at(5, 0)));
+        assert_eq!(night.wait_from(at(3, 0)), Duration::ZERO);
+        assert_eq!(night.wait_from(at(23, 30)), Duration::from_secs(90 * 60));
+        assert_eq!(night.wait_from(at(5, 0)), Duration::from_secs(20 * 3600));
+
+        // Wrapping past midnight, in local time (UTC+3 in Nairobi)
+        let late = QuietHours::parse("22-04", chrono_tz::Africa::Nairobi).unwrap();
+        assert!(late.contains(at(19, 0)));
+        assert!(late.contains(at(0, 59)));
+        assert!(!late.contains(at(1, 0)));
+        assert_eq!(late.wait_from(at(18, 15)), Duration::from_secs(45 * 60));
+
+        assert!(QuietHours::parse("5", Tz::UTC).is_err());
+        assert!(QuietHours::parse("3-3", Tz::UTC).is_err());
+        assert!(QuietHours::parse("22-24", Tz::UTC).is_err());
+    }
+
+    #[test]
+    fn test_record_metrics() {
+        let metrics = Metrics::new();
+        let report = MaintenanceReport {
+            optimize: Duration::from_millis(10),
+            incremental_vacuum: Duration::from_millis(20),
+            analyze: Duration::from_millis(30),
+        };
+
+        record_metrics(&metrics, &report);
+
+        assert_eq!(
+            metrics.get(
+                "infrared_maintenance_step_duration_seconds",
+                &[("step", "analyze")]
+            ),
+            Some(0.03)
+        );
+        assert_eq!(
+            metrics.get("infrared_maintenance_runs_total", &[]),
+            Some(1.0)
+        );
+    }
+}
@@ -0,0 +1,182 @@
+//! In-process metrics registry for Infrared.
+//!
+//! Metrics are kept in memory and rendered in the Prometheus text exposition
+//! format at `GET /metrics`.
+//!
+//! # Privacy Guarantees
+//!
+//! Metric labels only ever carry operational names (maintenance steps, bucket
+//! identifiers, data source names). Request metadata such as client IPs or
+//! headers is never recorded.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+/// Label set attached to a metric sample, kept sorted for stable output.
+type Labels = Vec<(String, String)>;
+
+/// Kind of metric, as reported in the `# TYPE` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    Counter,
+    Gauge,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Family {
+    kind: Option<MetricKind>,
+    samples: BTreeMap<Labels, f64>,
+}
+
+/// Shared metrics registry.
+///
+/// Cloning is cheap; all clones record into the same registry.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    families: Arc<RwLock<BTreeMap<String, Family>>>,
+}
+
+impl Metrics {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increment a counter by `value`.
+    pub fn inc_counter(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, MetricKind::Counter, labels, |v| *v += value);
+    }
+
+    /// Set a gauge to `value`.
+    pub fn set_gauge(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.update(name, MetricKind::Gauge, labels, |v| *v = value);
+    }
+
+    /// Read the current value of a metric sample, if it has been recorded.
+    pub fn get(&self, name: &str, labels: &[(&str, &str)]) -> Option<f64> {
+        let families = self.families.read().unwrap_or_else(|e| e.into_inner());
+        families
+            .get(name)
+            .and_then(|f| f.samples.get(&to_labels(labels)).copied())
+    }
+
+    /// Sum of every sample of a metric, across all label sets.
+    pub fn sum(&self, name: &str) -> f64 {
+        let families = self.families.read().unwrap_or_else(|e| e.into_inner());
+        families
+            .get(name)
+            .map(|f| f.samples.values().sum())
+            .unwrap_or(0.0)
+    }
+
+    /// Remove every sample of a metric (e.g., before re-publishing a gauge set).
+    pub fn clear(&self, name: &str) {
+        let mut families = self.families.write().unwrap_or_else(|e| e.into_inner());
+        if let Some(family) = families.get_mut(name) {
+            family.samples.clear();
+        }
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let families = self.families.read().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+
+        for (name, family) in families.iter() {
+            if let Some(kind) = family.kind {
+                let _ = writeln!(out, "# TYPE {} {}", name, kind.as_str());
+            }
+            for (labels, value) in &family.samples {
+                if labels.is_empty() {
+                    let _ = writeln!(out, "{} {}", name, value);
+                } else {
+                    let rendered: Vec<String> = labels
+                        .iter()
+                        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label_value(v)))
+                        .collect();
+                    let _ = writeln!(out, "{}{{{}}} {}", name, rendered.join(","), value);
+                }
+            }
+        }
+
+        out
+    }
+
+    fn update(
+        &self,
+        name: &str,
+        kind: MetricKind,
+        labels: &[(&str, &str)],
+        apply: impl FnOnce(&mut f64),
+    ) {
+        let mut families = self.families.write().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(name.to_string()).or_default();
+        family.kind = Some(kind);
+        apply(family.samples.entry(to_labels(labels)).or_insert(0.0));
+    }
+}
+
+fn to_labels(labels: &[(&str, &str)]) -> Labels {
+    let mut labels: Labels = labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    labels.sort();
+    labels
+}
+
+/// Escape a label value per the Prometheus text format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_accumulates() {
+        let metrics = Metrics::new();
+        metrics.inc_counter("infrared_test_total", &[], 1.0);
+        metrics.inc_counter("infrared_test_total", &[], 2.0);
+
+        assert_eq!(metrics.get("infrared_test_total", &[]), Some(3.0));
+
+        metrics.inc_counter("infrared_test_total", &[("bucket", "zone-a")], 4.0);
+        assert_eq!(metrics.sum("infrared_test_total"), 7.0);
+        assert_eq!(metrics.sum("infrared_missing_total"), 0.0);
+    }
+
+    #[test]
+    fn test_render_format() {
+        let metrics = Metrics::new();
+        metrics.set_gauge("infrared_duration_seconds", &[("step", "analyze")], 0.5);
+        metrics.inc_counter("infrared_runs_total", &[], 1.0);
+
+        let rendered = metrics.render();
+
+        assert!(rendered.contains("# TYPE infrared_duration_seconds gauge"));
+        assert!(rendered.contains("infrared_duration_seconds{step=\"analyze\"} 0.5"));
+        assert!(rendered.contains("# TYPE infrared_runs_total counter"));
+        assert!(rendered.contains("infrared_runs_total 1"));
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        assert_eq!(escape_label_value("a\"b"), "a\\\"b");
+        assert_eq!(escape_label_value("a\\b"), "a\\\\b");
+    }
+}
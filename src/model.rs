@@ -17,6 +17,8 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::scheduler::WarmthSnapshot;
+
 /// A single "life signal" event.
 ///
 /// Represents anonymous evidence that "something is alive" in a given bucket.
@@ -28,6 +30,7 @@ use serde::{Deserialize, Serialize};
 /// - `bucket`: A coarse category defined by system configuration, not users
 /// - `timestamp`: When the signal was recorded (server-side, not client-provided)
 /// - `weight`: A numeric intensity, with no identifying characteristics
+/// - `channel`: The delivery path, from a configured set (see [`crate::channel`])
 ///
 /// No field in this struct can be used to identify, locate, or profile any individual.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,13 +53,18 @@ pub struct LifeSignal {
     /// Default = 1. This allows batching multiple life signals into one event
     /// for efficiency, while maintaining aggregate-only semantics.
     pub weight: i32,
+
+    /// The path the signal arrived over (e.g., "sms", "mesh"), one of the
+    /// configured [`crate::channel::SignalChannels`]; `None` if unattributed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
 }
 
 /// Request body for POST /signal endpoint.
 ///
 /// # Privacy
 ///
-/// Clients provide only the bucket and optional weight.
+/// Clients provide only the bucket, optional weight and optional channel.
 /// The timestamp is set server-side to prevent timing attacks.
 /// No identifying information is accepted or stored.
 #[derive(Debug, Clone, Deserialize)]
@@ -67,6 +75,16 @@ pub struct SignalRequest {
     /// Optional weight for this signal (defaults to 1).
     #[serde(default = "default_weight")]
     pub weight: i32,
+
+    /// Optional delivery path, one of the configured channels.
+    #[serde(default)]
+    pub channel: Option<String>,
+
+    /// A client-supplied timestamp. Signals are always timestamped by the
+    /// server, so any value here is rejected; historical signals are
+    /// imported through `POST /signals/backfill` instead.
+    #[serde(default)]
+    pub timestamp: Option<serde::de::IgnoredAny>,
 }
 
 fn default_weight() -> i32 {
@@ -108,7 +126,107 @@ impl WarmthStatus {
     /// - `dead`: current == 0 && recent_average > 0
     ///
     /// If recent_average is 0, we return `Alive` (no baseline to compare against).
+    ///
+    /// These are the built-in [`WarmthThresholds`]; warmth computed from
+    /// storage uses the server's configured defaults and bucket overrides
+    /// instead (see [`crate::storage::SignalStore::default_thresholds`]).
     pub fn from_activity(current: i64, recent_average: f64) -> Self {
+        WarmthThresholds::default().classify(current, recent_average)
+    }
+
+    /// Get the lowercase name used in the API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarmthStatus::Alive => "alive",
+            WarmthStatus::Stressed => "stressed",
+            WarmthStatus::Collapsing => "collapsing",
+            WarmthStatus::Dead => "dead",
+        }
+    }
+
+    /// Parse a lowercase status name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "alive" => Some(WarmthStatus::Alive),
+            "stressed" => Some(WarmthStatus::Stressed),
+            "collapsing" => Some(WarmthStatus::Collapsing),
+            "dead" => Some(WarmthStatus::Dead),
+            _ => None,
+        }
+    }
+}
+
+/// Ratio thresholds separating warmth statuses.
+///
+/// The server's defaults (`INFRARED_STRESSED_RATIO` and
+/// `INFRARED_COLLAPSING_RATIO`, else 0.8 and 0.2) are used unless a
+/// bucket's metadata overrides them (see `PUT /buckets/:bucket/meta`);
+/// other values can also be evaluated as candidates with `infrared
+/// backtest`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WarmthThresholds {
+    /// Below this ratio of current to recent average, a bucket is `stressed`.
+    #[serde(default = "default_stressed_ratio")]
+    pub stressed_ratio: f64,
+
+    /// Below this ratio (but above zero activity), a bucket is `collapsing`.
+    #[serde(default = "default_collapsing_ratio")]
+    pub collapsing_ratio: f64,
+
+    /// Smoothing factor of an exponentially weighted baseline. When set,
+    /// the status is judged against that baseline instead of the flat
+    /// average of the baseline windows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ewma_alpha: Option<f64>,
+}
+
+fn default_stressed_ratio() -> f64 {
+    0.8
+}
+
+fn default_collapsing_ratio() -> f64 {
+    0.2
+}
+
+impl Default for WarmthThresholds {
+    fn default() -> Self {
+        Self {
+            stressed_ratio: default_stressed_ratio(),
+            collapsing_ratio: default_collapsing_ratio(),
+            ewma_alpha: None,
+        }
+    }
+}
+
+impl WarmthThresholds {
+    /// Check the ratios satisfy `0 < collapsing_ratio < stressed_ratio <= 1`
+    /// and any `ewma_alpha` satisfies `0 < ewma_alpha <= 1`.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.collapsing_ratio > 0.0
+                && self.collapsing_ratio < self.stressed_ratio
+                && self.stressed_ratio <= 1.0,
+            "thresholds must satisfy 0 < collapsing_ratio < stressed_ratio <= 1"
+        );
+        if let Some(alpha) = self.ewma_alpha {
+            anyhow::ensure!(
+                alpha > 0.0 && alpha <= 1.0,
+                "ewma_alpha must satisfy 0 < ewma_alpha <= 1"
+            );
+        }
+        Ok(())
+    }
+
+    /// Which baseline the status is judged against.
+    pub fn baseline_method(&self) -> BaselineMethod {
+        match self.ewma_alpha {
+            Some(_) => BaselineMethod::Ewma,
+            None => BaselineMethod::Mean,
+        }
+    }
+
+    /// Determine status based on current vs recent average activity.
+    pub fn classify(&self, current: i64, recent_average: f64) -> WarmthStatus {
         if recent_average <= 0.0 {
             // No historical baseline; assume alive
             return WarmthStatus::Alive;
@@ -118,9 +236,9 @@ impl WarmthStatus {
 
         if current == 0 {
             WarmthStatus::Dead
-        } else if ratio < 0.2 {
+        } else if ratio < self.collapsing_ratio {
             WarmthStatus::Collapsing
-        } else if ratio < 0.8 {
+        } else if ratio < self.stressed_ratio {
             WarmthStatus::Stressed
         } else {
             WarmthStatus::Alive
@@ -128,6 +246,78 @@ impl WarmthStatus {
     }
 }
 
+/// A baseline a warmth status can be judged against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaselineMethod {
+    /// The flat average of the baseline windows (`recent_average`).
+    #[default]
+    Mean,
+
+    /// Their exponentially weighted moving average (`ewma_average`).
+    Ewma,
+}
+
+/// How a bucket's status is derived from its current and baseline windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Detector {
+    /// The ratio of the current total to the baseline average, judged
+    /// against the bucket's [`WarmthThresholds`].
+    #[default]
+    Ratio,
+
+    /// How many standard deviations the current total lies below the mean
+    /// of the baseline windows (`z_score`), so that buckets with noisy
+    /// history are not flagged for ordinary dips.
+    Zscore,
+
+    /// Like `Zscore`, but measured from the median of the baseline windows
+    /// in median absolute deviations, so that one past spike cannot drag
+    /// the baseline up and make an ordinary window look like a collapse.
+    Mad,
+}
+
+impl Detector {
+    /// Parse a lowercase detector name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ratio" => Some(Detector::Ratio),
+            "zscore" => Some(Detector::Zscore),
+            "mad" => Some(Detector::Mad),
+            _ => None,
+        }
+    }
+}
+
+/// Direction of a bucket's activity over its last few windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrendDirection {
+    Rising,
+    Flat,
+    Declining,
+}
+
+/// Least-squares trend of a bucket's last few window totals.
+///
+/// A bucket can lose a little activity every window for hours while each
+/// window stays within its thresholds; the trend shows the slide early.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Trend {
+    pub direction: TrendDirection,
+
+    /// Change in total weight per window.
+    pub slope: f64,
+
+    /// `slope` as a fraction of the windows' mean total (e.g., -0.1 is 10%
+    /// less activity every window).
+    pub rate: f64,
+
+    /// Windows the trend was fitted over, including the current one.
+    pub windows: u32,
+}
+
 /// Response for GET /warmth endpoint.
 ///
 /// Provides the warmth index and status for a specific bucket.
@@ -145,16 +335,297 @@ pub struct WarmthResponse {
     /// Average weight per window over recent history.
     pub recent_average: f64,
 
+    /// Number of windows averaged into `recent_average`.
+    pub baseline_windows: u32,
+
+    /// Exponentially weighted moving average of the same windows, weighing
+    /// recent windows more than older ones.
+    pub ewma_average: f64,
+
+    /// Which of the two averages `status` is judged against.
+    pub baseline_method: BaselineMethod,
+
+    /// How `status` was derived (`?detector=`).
+    pub detector: Detector,
+
+    /// Deviations of the current total from the baseline windows: standard
+    /// deviations from their mean with the `zscore` detector, scaled median
+    /// absolute deviations from their median with `mad` (with some history).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z_score: Option<f64>,
+
     /// Health status derived from current vs recent activity.
     pub status: WarmthStatus,
+
+    /// Trend of the last few windows, current included (with enough
+    /// history).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trend: Option<Trend>,
+
+    /// Totals of the most recent windows, oldest first (with `?include=sparkline`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sparkline: Option<Vec<i64>>,
+
+    /// Average total of the same window at the same local weekday and time
+    /// in previous weeks (with `?include=seasonal`). Informational only:
+    /// `status` is derived from `recent_average`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seasonal_average: Option<f64>,
+
+    /// The bucket's IANA timezone, if one is set (with `?include=seasonal`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+
+    /// Warmth per signal channel (with `?include=channels`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<Vec<ChannelWarmth>>,
+}
+
+/// A bucket's warmth counting only the signals of one channel.
+///
+/// Measured over the same windows and baseline as the bucket as a whole.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChannelWarmth {
+    /// The channel; `None` for signals not attributed to any.
+    pub channel: Option<String>,
+
+    /// Total weight of the channel's signals in the current window.
+    pub current_window_total: i64,
+
+    /// Average weight per window of the channel's signals.
+    pub recent_average: f64,
+
+    /// Health status of the channel's signal flow.
+    pub status: WarmthStatus,
+}
+
+/// A named set of buckets aggregated as one entity.
+///
+/// Groups let operators watch a region (e.g., "north-region" = zone-1,
+/// zone-2, zone-5) as a whole, with warmth summed across members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketGroup {
+    /// The group name.
+    pub name: String,
+
+    /// Member buckets, sorted.
+    pub buckets: Vec<String>,
+}
+
+impl BucketGroup {
+    /// Parse groups from configuration, e.g.
+    /// `north-region=zone-1,zone-2,zone-5;south-region=zone-3`.
+    pub fn parse_list(spec: &str) -> anyhow::Result<Vec<Self>> {
+        spec.split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (name, members) = entry.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "invalid bucket group '{}' (expected name=bucket,...)",
+                        entry
+                    )
+                })?;
+                let mut buckets: Vec<String> = members
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|b| !b.is_empty())
+                    .map(String::from)
+                    .collect();
+                if buckets.is_empty() {
+                    anyhow::bail!("bucket group '{}' has no members", name.trim());
+                }
+                buckets.sort();
+                buckets.dedup();
+                Ok(Self {
+                    name: name.trim().to_string(),
+                    buckets,
+                })
+            })
+            .collect()
+    }
+}
+
+/// A bucket known to this instance, as listed by GET /buckets.
+///
+/// Archived buckets are decommissioned sites: their history is kept for
+/// reporting, but they raise no alerts and are left out of monitoring.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BucketEntry {
+    /// The bucket identifier.
+    pub bucket: String,
+
+    /// When the last signal was received (if any).
+    pub last_seen: Option<DateTime<Utc>>,
+
+    /// When the bucket was archived, if it is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+/// Upper bound on the windows in a warmth baseline.
+pub const MAX_BASELINE_WINDOWS: u32 = 10_000;
+
+/// How much history a bucket's current window is compared against.
+///
+/// Six ten-minute windows suit busy buckets, but a bucket that reports
+/// weekly needs weeks of memory. A baseline is either a fixed number of
+/// windows or a span of time, which is divided by the window size at query
+/// time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Baseline {
+    /// This many windows before the current one.
+    Windows(u32),
+    /// As many windows as fit in this many minutes before the current one.
+    SpanMinutes(u32),
+}
+
+impl Baseline {
+    /// Number of historical windows for the given window size, at least 1
+    /// and at most [`MAX_BASELINE_WINDOWS`].
+    pub fn windows(&self, window_minutes: u32) -> u32 {
+        let windows = match *self {
+            Baseline::Windows(windows) => windows,
+            Baseline::SpanMinutes(span) => span.div_ceil(window_minutes.max(1)),
+        };
+        windows.clamp(1, MAX_BASELINE_WINDOWS)
+    }
+
+    /// Parse a baseline: a bare number of windows (`12`) or a span with a
+    /// unit of `m`, `h`, `d` or `w` (`90m`, `48h`, `14d`, `4w`).
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let spec = spec.trim();
+        let invalid = || anyhow::anyhow!("invalid baseline '{}'", spec);
+        let (number, unit) = match spec.find(|c: char| !c.is_ascii_digit()) {
+            Some(at) => spec.split_at(at),
+            None => (spec, ""),
+        };
+        let value: u32 = number.parse().map_err(|_| invalid())?;
+        if value == 0 {
+            return Err(invalid());
+        }
+        let minutes_per_unit = match unit {
+            "" => return Ok(Baseline::Windows(value)),
+            "m" => 1,
+            "h" => 60,
+            "d" => 24 * 60,
+            "w" => 7 * 24 * 60,
+            _ => return Err(invalid()),
+        };
+        value
+            .checked_mul(minutes_per_unit)
+            .map(Baseline::SpanMinutes)
+            .ok_or_else(invalid)
+    }
+
+    /// Parse per-bucket baselines from configuration, e.g.
+    /// `zone-a=12;weekly-site=4w`.
+    pub fn parse_overrides(spec: &str) -> anyhow::Result<Vec<(String, Self)>> {
+        spec.split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (bucket, baseline) = entry.split_once('=').ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "invalid bucket baseline '{}' (expected bucket=baseline)",
+                        entry
+                    )
+                })?;
+                Ok((bucket.trim().to_string(), Self::parse(baseline)?))
+            })
+            .collect()
+    }
+}
+
+/// Response for GET /warmth?group=... .
+///
+/// Member windows are summed, so the group's status reflects total activity
+/// across the region rather than an average of member statuses.
+#[derive(Debug, Clone, Serialize)]
+pub struct GroupWarmthResponse {
+    /// The group being queried.
+    pub group: String,
+
+    /// The time window in minutes used for the current measurement.
+    pub window_minutes: u32,
+
+    /// Total weight of member signals in the current window.
+    pub current_window_total: i64,
+
+    /// Sum of member recent averages.
+    pub recent_average: f64,
+
+    /// Health status derived from the summed current vs recent activity.
+    pub status: WarmthStatus,
+
+    /// Fraction of members (0.0-1.0) that are not `dead`.
+    pub uptime: f64,
+
+    /// Summed member window totals, oldest first (with `?include=sparkline`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sparkline: Option<Vec<i64>>,
+
+    /// Per-member warmth.
+    pub members: Vec<WarmthResponse>,
+}
+
+/// Severity levels shared by dashboard issues and bucket alerts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// Informational - worth noting but not urgent.
+    Info,
+    /// Warning - potential problem developing.
+    Warning,
+    /// Critical - serious ongoing issue.
+    Critical,
+    /// Emergency - requires immediate attention.
+    Emergency,
 }
 
-/// A single alert for a bucket in distress.
+impl IssueSeverity {
+    /// Get a human-readable label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            IssueSeverity::Info => "Info",
+            IssueSeverity::Warning => "Warning",
+            IssueSeverity::Critical => "Critical",
+            IssueSeverity::Emergency => "Emergency",
+        }
+    }
+
+    /// Get the lowercase name, as serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IssueSeverity::Info => "info",
+            IssueSeverity::Warning => "warning",
+            IssueSeverity::Critical => "critical",
+            IssueSeverity::Emergency => "emergency",
+        }
+    }
+
+    /// Parse a lowercase severity name, as serialized.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "info" => Some(IssueSeverity::Info),
+            "warning" => Some(IssueSeverity::Warning),
+            "critical" => Some(IssueSeverity::Critical),
+            "emergency" => Some(IssueSeverity::Emergency),
+            _ => None,
+        }
+    }
+}
+
+/// A single alert for a bucket (or bucket group) in distress.
 #[derive(Debug, Clone, Serialize)]
 pub struct Alert {
-    /// The bucket experiencing the issue.
+    /// The bucket experiencing the issue, or the group name for group alerts.
     pub bucket: String,
 
+    /// Whether the alert concerns a single bucket or a bucket group.
+    pub subject: AlertSubject,
+
     /// Current status of the bucket.
     pub status: WarmthStatus,
 
@@ -164,8 +635,94 @@ pub struct Alert {
     /// Historical average for context.
     pub recent_average: f64,
 
+    /// Why the alert was raised.
+    pub reason: AlertReason,
+
     /// Human-readable description of the alert.
     pub message: String,
+
+    /// How urgent the alert is, escalating with how long the distress has
+    /// lasted (see [`crate::severity`]).
+    pub severity: IssueSeverity,
+
+    /// When the distress began, if known.
+    pub distressed_since: Option<DateTime<Utc>>,
+}
+
+/// What an alert is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSubject {
+    /// A single bucket.
+    Bucket,
+    /// A bucket group, aggregated across its members.
+    Group,
+}
+
+impl AlertSubject {
+    /// Get the lowercase name, as serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertSubject::Bucket => "bucket",
+            AlertSubject::Group => "group",
+        }
+    }
+
+    /// Parse a lowercase subject name, as serialized.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "bucket" => Some(AlertSubject::Bucket),
+            "group" => Some(AlertSubject::Group),
+            _ => None,
+        }
+    }
+}
+
+/// What triggered an alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertReason {
+    /// Current activity dropped relative to the recent average.
+    WarmthDrop,
+    /// A registered heartbeat interval elapsed without a signal.
+    MissedHeartbeat,
+    /// A synthetic canary bucket lost warmth, so Infrared itself is unhealthy.
+    CanaryDegraded,
+    /// A pipeline self-monitoring bucket collapsed, so one of Infrared's
+    /// own subsystems stopped working (see [`crate::canary`]).
+    PipelineStalled,
+    /// Some signal channels collapsed while another stayed alive, pointing
+    /// to a delivery path outage (see [`crate::failover`]).
+    ChannelFailure,
+    /// Every signal channel collapsed at once (see [`crate::failover`]).
+    AllChannelsCollapsed,
+}
+
+impl AlertReason {
+    /// Get the snake_case name, as serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertReason::WarmthDrop => "warmth_drop",
+            AlertReason::MissedHeartbeat => "missed_heartbeat",
+            AlertReason::CanaryDegraded => "canary_degraded",
+            AlertReason::PipelineStalled => "pipeline_stalled",
+            AlertReason::ChannelFailure => "channel_failure",
+            AlertReason::AllChannelsCollapsed => "all_channels_collapsed",
+        }
+    }
+
+    /// Parse a snake_case reason name, as serialized.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "warmth_drop" => Some(AlertReason::WarmthDrop),
+            "missed_heartbeat" => Some(AlertReason::MissedHeartbeat),
+            "canary_degraded" => Some(AlertReason::CanaryDegraded),
+            "pipeline_stalled" => Some(AlertReason::PipelineStalled),
+            "channel_failure" => Some(AlertReason::ChannelFailure),
+            "all_channels_collapsed" => Some(AlertReason::AllChannelsCollapsed),
+            _ => None,
+        }
+    }
 }
 
 /// Response for GET /alerts/recent endpoint.
@@ -179,26 +736,175 @@ pub struct AlertsResponse {
 }
 
 /// Query parameters for GET /warmth endpoint.
+///
+/// Exactly one of `bucket` or `group` must be given.
 #[derive(Debug, Deserialize)]
 pub struct WarmthQuery {
     /// The bucket to query.
-    pub bucket: String,
+    pub bucket: Option<String>,
+
+    /// The bucket group to query.
+    pub group: Option<String>,
 
     /// Time window in minutes (default: 10).
     #[serde(default = "default_window_minutes")]
     pub window_minutes: u32,
+
+    /// Comma-separated extras to include (`sparkline`, `seasonal`).
+    pub include: Option<String>,
+
+    /// Number of windows in the sparkline (default: 12, max: 100).
+    #[serde(default = "default_sparkline_windows")]
+    pub sparkline_windows: u32,
+
+    /// Number of historical windows to compare against, overriding the
+    /// bucket's baseline.
+    pub baseline_windows: Option<u32>,
+
+    /// Span of history to compare against (e.g., `7d`), overriding the
+    /// bucket's baseline.
+    pub baseline_span: Option<String>,
+
+    /// How to derive the status: `ratio`, `zscore` or `mad` (default: the
+    /// server's `INFRARED_DETECTOR`).
+    pub detector: Option<String>,
+
+    /// Deviations below the baseline at which `zscore` or `mad` flags a
+    /// bucket (default: the server's `INFRARED_DETECTOR_SIGMAS`).
+    pub sigmas: Option<f64>,
+}
+
+impl WarmthQuery {
+    /// The detector requested with `?detector=`, and its `?sigmas=`, each
+    /// falling back to the given `default`.
+    ///
+    /// An unknown detector, or a `sigmas` that is not positive, is an error.
+    pub fn detector(&self, default: (Detector, f64)) -> anyhow::Result<(Detector, f64)> {
+        let detector = match self.detector.as_deref() {
+            None => default.0,
+            Some(name) => Detector::parse(name)
+                .ok_or_else(|| anyhow::anyhow!("unknown detector '{}'", name))?,
+        };
+        let sigmas = self.sigmas.unwrap_or(default.1);
+        anyhow::ensure!(
+            sigmas.is_finite() && sigmas > 0.0,
+            "sigmas must be positive"
+        );
+        Ok((detector, sigmas))
+    }
+
+    /// The baseline requested with `?baseline_windows=` or `?baseline_span=`.
+    ///
+    /// Giving both, a zero window count, or an unparseable span is an error.
+    pub fn baseline(&self) -> anyhow::Result<Option<Baseline>> {
+        requested_baseline(self.baseline_windows, self.baseline_span.as_deref())
+    }
+
+    /// Whether `?include=` lists the given extra.
+    pub fn includes(&self, extra: &str) -> bool {
+        self.include
+            .as_deref()
+            .is_some_and(|include| include.split(',').any(|item| item.trim() == extra))
+    }
 }
 
 fn default_window_minutes() -> u32 {
     10
 }
 
+/// A baseline given as `baseline_windows` or `baseline_span`.
+fn requested_baseline(
+    windows: Option<u32>,
+    span: Option<&str>,
+) -> anyhow::Result<Option<Baseline>> {
+    match (windows, span) {
+        (None, None) => Ok(None),
+        (Some(0), None) => anyhow::bail!("baseline_windows must be positive"),
+        (Some(windows), None) => Ok(Some(Baseline::Windows(windows))),
+        (None, Some(span)) => match Baseline::parse(span)? {
+            Baseline::Windows(_) => anyhow::bail!("baseline_span needs a unit (m, h, d, w)"),
+            span => Ok(Some(span)),
+        },
+        (Some(_), Some(_)) => anyhow::bail!("give baseline_windows or baseline_span, not both"),
+    }
+}
+
+/// Request body for POST /warmth/batch.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BatchWarmthRequest {
+    /// The buckets to query.
+    pub buckets: Vec<String>,
+
+    /// Time window in minutes (default: 10).
+    #[serde(default = "default_window_minutes")]
+    pub window_minutes: u32,
+
+    /// Number of historical windows to compare against, overriding each
+    /// bucket's baseline.
+    pub baseline_windows: Option<u32>,
+
+    /// Span of history to compare against (e.g., `7d`), overriding each
+    /// bucket's baseline.
+    pub baseline_span: Option<String>,
+}
+
+impl BatchWarmthRequest {
+    /// The baseline requested with `baseline_windows` or `baseline_span`.
+    ///
+    /// Giving both, a zero window count, or an unparseable span is an error.
+    pub fn baseline(&self) -> anyhow::Result<Option<Baseline>> {
+        requested_baseline(self.baseline_windows, self.baseline_span.as_deref())
+    }
+}
+
+/// Response for POST /warmth/batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchWarmthResponse {
+    /// The time window in minutes used for every bucket.
+    pub window_minutes: u32,
+
+    /// Warmth of each requested bucket, in request order.
+    pub buckets: Vec<WarmthResponse>,
+}
+
+fn default_sparkline_windows() -> u32 {
+    12
+}
+
+/// Query parameters for GET /warmth/history endpoint.
+#[derive(Debug, Deserialize)]
+pub struct WarmthHistoryQuery {
+    /// The bucket to query.
+    pub bucket: String,
+
+    /// Lookback in hours (default: 24).
+    #[serde(default = "default_history_hours")]
+    pub hours: u32,
+}
+
+fn default_history_hours() -> u32 {
+    24
+}
+
+/// Response for GET /warmth/history endpoint.
+#[derive(Debug, Serialize)]
+pub struct WarmthHistoryResponse {
+    /// The bucket queried.
+    pub bucket: String,
+
+    /// Scheduled snapshots, oldest first.
+    pub snapshots: Vec<WarmthSnapshot>,
+}
+
 /// Query parameters for GET /alerts/recent endpoint.
 #[derive(Debug, Deserialize)]
 pub struct AlertsQuery {
     /// Lookback window in minutes (default: 60).
     #[serde(default = "default_lookback_minutes")]
     pub minutes: u32,
+
+    /// Only return alerts for this bucket group and its members.
+    pub group: Option<String>,
 }
 
 fn default_lookback_minutes() -> u32 {
@@ -260,4 +966,61 @@ mod tests {
         assert_eq!(WarmthStatus::from_activity(0, 0.0), WarmthStatus::Alive);
         assert_eq!(WarmthStatus::from_activity(10, 0.0), WarmthStatus::Alive);
     }
+
+    #[test]
+    fn test_parse_bucket_groups() {
+        let groups = BucketGroup::parse_list("north=zone-5, zone-1;south=zone-3;").unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].name, "north");
+        assert_eq!(groups[0].buckets, vec!["zone-1", "zone-5"]);
+
+        assert!(BucketGroup::parse_list("north").is_err());
+        assert!(BucketGroup::parse_list("north=").is_err());
+    }
+
+    #[test]
+    fn test_status_names() {
+        for status in [
+            WarmthStatus::Alive,
+            WarmthStatus::Stressed,
+            WarmthStatus::Collapsing,
+            WarmthStatus::Dead,
+        ] {
+            assert_eq!(WarmthStatus::parse(status.name()), Some(status));
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                serde_json::json!(status.name())
+            );
+        }
+        assert_eq!(WarmthStatus::parse("Dead"), None);
+    }
+
+    #[test]
+    fn test_baseline() {
+        assert_eq!(Baseline::parse("12").unwrap(), Baseline::Windows(12));
+        assert_eq!(Baseline::parse("48h").unwrap(), Baseline::SpanMinutes(2880));
+        assert_eq!(
+            Baseline::parse(" 4w ").unwrap(),
+            Baseline::SpanMinutes(40_320)
+        );
+        assert!(Baseline::parse("0").is_err());
+        assert!(Baseline::parse("3y").is_err());
+        assert!(Baseline::parse("h").is_err());
+
+        assert_eq!(Baseline::Windows(12).windows(10), 12);
+        assert_eq!(Baseline::SpanMinutes(2880).windows(60), 48);
+        // Partial windows round up; huge spans are capped
+        assert_eq!(Baseline::SpanMinutes(25).windows(10), 3);
+        assert_eq!(
+            Baseline::SpanMinutes(40_320).windows(1),
+            MAX_BASELINE_WINDOWS
+        );
+
+        let overrides = Baseline::parse_overrides("zone-a=12; weekly=4w;").unwrap();
+        assert_eq!(
+            overrides[1],
+            ("weekly".to_string(), Baseline::SpanMinutes(40_320))
+        );
+        assert!(Baseline::parse_overrides("zone-a").is_err());
+    }
 }
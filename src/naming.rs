@@ -0,0 +1,221 @@
+//! Bucket naming conventions.
+//!
+//! Buckets are created by whatever name the first signal carries, so after
+//! a few months of field devices, scripts and hand-typed `curl` calls a
+//! deployment collects names like `Zone-A`, `zone-a ` and `zone-a` that
+//! are the same place split three ways. `INFRARED_BUCKET_PATTERN` states
+//! the convention as a regular expression every name must match in full,
+//! and `infrared buckets lint` (or `GET /admin/buckets/lint`) reports:
+//!
+//! - names that do not match the pattern,
+//! - names with leading or trailing whitespace,
+//! - likely duplicates: names equal once whitespace is trimmed and case
+//!   folded.
+//!
+//! With `INFRARED_BUCKET_NORMALIZE=true`, bucket names are trimmed and
+//! lowercased as signals are ingested, so new variants stop appearing.
+//! Existing ones are reported by the linter and can be folded together
+//! with `infrared buckets merge`.
+//!
+//! Reserved `_canary:*` buckets are never linted or normalized.
+//!
+//! # Privacy
+//!
+//! The linter reads bucket names only, which are operator-defined areas,
+//! and stores nothing.
+
+use std::collections::BTreeMap;
+
+use regex_automata::meta::Regex;
+use serde::Serialize;
+
+use crate::canary::is_canary;
+
+/// How bucket names should look.
+#[derive(Debug, Clone, Default)]
+pub struct NamingConvention {
+    /// The pattern as configured, and compiled to match whole names.
+    pattern: Option<(String, Regex)>,
+
+    /// Whether to normalize names on ingest.
+    normalize: bool,
+}
+
+impl NamingConvention {
+    /// A convention requiring names to match `pattern` in full, if given,
+    /// and normalizing names on ingest if `normalize` is set.
+    pub fn new(pattern: Option<&str>, normalize: bool) -> anyhow::Result<Self> {
+        let pattern = pattern
+            .map(|pattern| {
+                Regex::new(&format!("^(?:{})$", pattern))
+                    .map(|regex| (pattern.to_string(), regex))
+                    .map_err(|e| anyhow::anyhow!("invalid bucket pattern '{}': {}", pattern, e))
+            })
+            .transpose()?;
+        Ok(Self { pattern, normalize })
+    }
+
+    /// Whether names are normalized on ingest.
+    pub fn normalizes(&self) -> bool {
+        self.normalize
+    }
+
+    /// The name a signal for `bucket` is stored under: normalized if the
+    /// convention asks for it, otherwise unchanged.
+    pub fn ingest_name(&self, bucket: String) -> String {
+        if self.normalize && !is_canary(&bucket) {
+            normalize(&bucket)
+        } else {
+            bucket
+        }
+    }
+
+    /// Check `buckets` against the convention.
+    pub fn lint<'a>(&self, buckets: impl IntoIterator<Item = &'a str>) -> LintReport {
+        let mut report = LintReport {
+            pattern: self.pattern.as_ref().map(|(pattern, _)| pattern.clone()),
+            checked: 0,
+            violations: Vec::new(),
+            duplicates: Vec::new(),
+        };
+        let mut variants: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for bucket in buckets.into_iter().filter(|bucket| !is_canary(bucket)) {
+            report.checked += 1;
+
+            let mut problems = Vec::new();
+            if let Some((_, regex)) = &self.pattern
+                && !regex.is_match(bucket)
+            {
+                problems.push(NameProblem::PatternMismatch);
+            }
+            if bucket.trim() != bucket {
+                problems.push(NameProblem::SurroundingWhitespace);
+            }
+            if self.normalize && normalize(bucket) != bucket {
+                problems.push(NameProblem::NotNormalized);
+            }
+            if !problems.is_empty() {
+                report.violations.push(NameViolation {
+                    bucket: bucket.to_string(),
+                    problems,
+                });
+            }
+
+            variants
+                .entry(normalize(bucket))
+                .or_default()
+                .push(bucket.to_string());
+        }
+        report.duplicates = variants
+            .into_values()
+            .filter(|names| names.len() > 1)
+            .collect();
+        report
+    }
+}
+
+/// A bucket name trimmed and lowercased.
+pub fn normalize(bucket: &str) -> String {
+    bucket.trim().to_lowercase()
+}
+
+/// What is wrong with a bucket name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameProblem {
+    /// It does not match `INFRARED_BUCKET_PATTERN`.
+    PatternMismatch,
+    /// It starts or ends with whitespace.
+    SurroundingWhitespace,
+    /// Normalization is on, but the name predates it.
+    NotNormalized,
+}
+
+/// A bucket name breaking the convention.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NameViolation {
+    pub bucket: String,
+    pub problems: Vec<NameProblem>,
+}
+
+/// Result of linting bucket names.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LintReport {
+    /// The configured pattern, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Bucket names checked.
+    pub checked: usize,
+
+    /// Names breaking the convention, in the order checked.
+    pub violations: Vec<NameViolation>,
+
+    /// Groups of names that are likely the same bucket.
+    pub duplicates: Vec<Vec<String>>,
+}
+
+impl LintReport {
+    /// Whether every name follows the convention and none look duplicated.
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint() {
+        let convention = NamingConvention::new(Some("[a-z]+-[0-9]+"), false).unwrap();
+        let report = convention.lint(["zone-1", "Zone-1", "zone-1 ", "zone-22x", "_canary:probe"]);
+
+        assert_eq!(report.checked, 4);
+        assert_eq!(
+            report.violations,
+            vec![
+                NameViolation {
+                    bucket: "Zone-1".to_string(),
+                    problems: vec![NameProblem::PatternMismatch],
+                },
+                NameViolation {
+                    bucket: "zone-1 ".to_string(),
+                    problems: vec![
+                        NameProblem::PatternMismatch,
+                        NameProblem::SurroundingWhitespace
+                    ],
+                },
+                NameViolation {
+                    bucket: "zone-22x".to_string(),
+                    problems: vec![NameProblem::PatternMismatch],
+                },
+            ]
+        );
+        assert_eq!(report.duplicates, vec![vec!["zone-1", "Zone-1", "zone-1 "]]);
+        assert!(!report.is_clean());
+
+        assert!(NamingConvention::new(Some("zone-("), false).is_err());
+    }
+
+    #[test]
+    fn test_ingest_name() {
+        let convention = NamingConvention::new(None, true).unwrap();
+        assert_eq!(convention.ingest_name(" Zone-A ".to_string()), "zone-a");
+        assert_eq!(
+            convention.ingest_name("_canary:Probe".to_string()),
+            "_canary:Probe"
+        );
+        assert_eq!(
+            NamingConvention::default().ingest_name(" Zone-A ".to_string()),
+            " Zone-A "
+        );
+
+        let report = convention.lint(["zone-a", "Zone-B"]);
+        assert_eq!(
+            report.violations[0].problems,
+            vec![NameProblem::NotNormalized]
+        );
+        assert!(report.duplicates.is_empty());
+    }
+}
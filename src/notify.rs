@@ -0,0 +1,88 @@
+//! Outbound webhook notifications.
+//!
+//! Notifications are JSON documents (or, for CAP endpoints, XML) POSTed to
+//! operator-configured URLs. A notifier given a metrics registry counts
+//! deliveries in `infrared_notifications_delivered_total` and
+//! `infrared_notification_failures_total`.
+//!
+//! # Privacy
+//!
+//! Payloads only ever describe buckets (identifiers, timings, statuses).
+//! Nothing about individual signals or senders is included.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::metrics::Metrics;
+
+/// How long to wait for a webhook endpoint before giving up.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delivers notifications to webhook URLs.
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    metrics: Option<Metrics>,
+}
+
+impl Default for WebhookNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookNotifier {
+    /// Create a notifier.
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            metrics: None,
+        }
+    }
+
+    /// Count deliveries and failures in `metrics`.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// POST `payload` as JSON to `url`, failing on non-2xx responses.
+    pub async fn send<T: Serialize>(&self, url: &str, payload: &T) -> anyhow::Result<()> {
+        let request = self.client.post(url).timeout(WEBHOOK_TIMEOUT).json(payload);
+        self.deliver(request).await
+    }
+
+    /// POST `body` as `content_type` to `url`, failing on non-2xx responses.
+    pub async fn send_raw(
+        &self,
+        url: &str,
+        content_type: &str,
+        body: String,
+    ) -> anyhow::Result<()> {
+        let request = self
+            .client
+            .post(url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body(body);
+        self.deliver(request).await
+    }
+
+    async fn deliver(&self, request: reqwest::RequestBuilder) -> anyhow::Result<()> {
+        let result = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Some(metrics) = &self.metrics {
+            let name = if result.is_ok() {
+                "infrared_notifications_delivered_total"
+            } else {
+                "infrared_notification_failures_total"
+            };
+            metrics.inc_counter(name, &[], 1.0);
+        }
+        result?;
+        Ok(())
+    }
+}
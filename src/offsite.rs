@@ -0,0 +1,555 @@
+//! Scheduled, encrypted off-site backups to S3-compatible object storage.
+//!
+//! A field server can be seized, flooded or burned. With
+//! `INFRARED_OFFSITE_BUCKET` set, a background task takes a backup (see
+//! [`crate::backup`]) every `INFRARED_OFFSITE_INTERVAL_HOURS`, encrypts it,
+//! and uploads it as `{prefix}infrared-YYYYMMDDTHHMMSSZ.db.enc`. Only the
+//! newest `INFRARED_OFFSITE_KEEP` backups under the prefix are kept; older
+//! ones are deleted after each upload. A backup holds the whole database,
+//! persisted dashboard and warmth snapshots included, so a replacement
+//! server restores from the latest object alone:
+//!
+//! ```text
+//! infrared decrypt-backup infrared-20240501T120000Z.db.enc infrared.db
+//! ```
+//!
+//! Backups are encrypted before they leave the server with AES-256-GCM
+//! under `INFRARED_OFFSITE_BACKUP_KEY`, so the storage provider never holds
+//! a readable copy. The file is sealed in chunks ([`CHUNK_BYTES`]), each
+//! authenticated with its position and whether it is the last, so a
+//! truncated, reordered or altered backup fails to decrypt rather than
+//! restoring silently wrong data.
+//!
+//! # Privacy
+//!
+//! Off-site copies hold what the database holds (see [`crate::storage`]),
+//! readable only with the backup key. Plaintext copies exist only as
+//! temporary files on the server, removed once encrypted.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, Nonce, UnboundKey};
+use ring::digest;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::backup;
+use crate::metrics::Metrics;
+use crate::s3::S3Bucket;
+use crate::storage::{SignalStore, Storage};
+
+/// First bytes of an encrypted backup, naming the format version.
+pub const MAGIC: &[u8; 8] = b"IRBAK\x00\x00\x01";
+
+/// Plaintext bytes sealed per chunk.
+pub const CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Suffix of encrypted backup objects.
+pub const OBJECT_SUFFIX: &str = ".enc";
+
+/// Bytes of random nonce prefix per backup; the rest of each nonce counts
+/// chunks.
+const NONCE_PREFIX_BYTES: usize = 8;
+
+/// Authentication tag bytes per chunk.
+const TAG_BYTES: usize = 16;
+
+/// How and where off-site backups are taken.
+#[derive(Clone)]
+pub struct OffsiteConfig {
+    /// The bucket backups are uploaded to.
+    pub bucket: S3Bucket,
+
+    /// Prefix of backup object keys (e.g., `field-1/`).
+    pub prefix: String,
+
+    /// Key backups are encrypted with.
+    pub key: BackupKey,
+
+    /// Time between backups.
+    pub interval: Duration,
+
+    /// Backups kept under the prefix.
+    pub keep: usize,
+
+    /// Directory for temporary files while a backup is encrypted.
+    pub work_dir: PathBuf,
+}
+
+/// A 256-bit backup encryption key.
+#[derive(Clone)]
+pub struct BackupKey([u8; 32]);
+
+impl BackupKey {
+    /// Parse a key written as 64 hex digits.
+    pub fn parse(hex_key: &str) -> anyhow::Result<Self> {
+        let bytes = hex::decode(hex_key.trim())
+            .map_err(|_| anyhow::anyhow!("backup key must be 64 hex digits"))?;
+        let key = <[u8; 32]>::try_from(bytes)
+            .map_err(|_| anyhow::anyhow!("backup key must be 64 hex digits"))?;
+        Ok(Self(key))
+    }
+
+    fn aead(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.0).expect("key is 32 bytes"))
+    }
+}
+
+/// What an off-site backup run did.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OffsiteReport {
+    /// Key of the uploaded backup.
+    pub key: String,
+
+    /// Size of the encrypted backup.
+    pub bytes: u64,
+
+    /// Keys of older backups deleted.
+    pub removed: Vec<String>,
+}
+
+/// Nonce of chunk `index` of a backup.
+fn nonce(prefix: &[u8; NONCE_PREFIX_BYTES], index: u32) -> Nonce {
+    let mut nonce = [0; 12];
+    nonce[..NONCE_PREFIX_BYTES].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_BYTES..].copy_from_slice(&index.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+/// Fill `buf` from `reader` as far as it goes, returning the bytes read.
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Encrypt `plaintext` into `encrypted` under `key`, returning the hex
+/// SHA-256 of what was written.
+pub fn encrypt_file(plaintext: &Path, encrypted: &Path, key: &BackupKey) -> anyhow::Result<String> {
+    let aead = key.aead();
+    let mut prefix = [0; NONCE_PREFIX_BYTES];
+    SystemRandom::new()
+        .fill(&mut prefix)
+        .map_err(|_| anyhow::anyhow!("no randomness for a backup nonce"))?;
+
+    let mut input = std::io::BufReader::new(std::fs::File::open(plaintext)?);
+    let mut output = std::io::BufWriter::new(
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(encrypted)?,
+    );
+    let mut hash = digest::Context::new(&digest::SHA256);
+    let mut write = |bytes: &[u8]| -> std::io::Result<()> {
+        hash.update(bytes);
+        output.write_all(bytes)
+    };
+
+    write(MAGIC)?;
+    write(&prefix)?;
+
+    // Read one chunk ahead, so the last one is known when sealed
+    let mut chunk = vec![0; CHUNK_BYTES];
+    let mut next = vec![0; CHUNK_BYTES];
+    let mut len = read_full(&mut input, &mut chunk)?;
+    let mut index = 0u32;
+    loop {
+        let next_len = if len == CHUNK_BYTES {
+            read_full(&mut input, &mut next)?
+        } else {
+            0
+        };
+        let last = next_len == 0;
+
+        let mut sealed = chunk[..len].to_vec();
+        aead.seal_in_place_append_tag(
+            nonce(&prefix, index),
+            Aad::from([u8::from(last)]),
+            &mut sealed,
+        )
+        .map_err(|_| anyhow::anyhow!("backup encryption failed"))?;
+        write(&(sealed.len() as u32).to_be_bytes())?;
+        write(&sealed)?;
+
+        if last {
+            break;
+        }
+        std::mem::swap(&mut chunk, &mut next);
+        len = next_len;
+        index = index
+            .checked_add(1)
+            .ok_or_else(|| anyhow::anyhow!("backup too large to encrypt"))?;
+    }
+
+    output.flush()?;
+    output.get_ref().sync_all()?;
+    Ok(hex::encode(hash.finish()))
+}
+
+/// Decrypt a backup written by [`encrypt_file`] into `plaintext`.
+///
+/// Fails if the backup is not one, was encrypted under another key, or was
+/// altered or truncated.
+pub fn decrypt_file(encrypted: &Path, plaintext: &Path, key: &BackupKey) -> anyhow::Result<()> {
+    let aead = key.aead();
+    let mut input = std::io::BufReader::new(std::fs::File::open(encrypted)?);
+    let mut output = std::io::BufWriter::new(
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(plaintext)?,
+    );
+
+    let mut magic = [0; MAGIC.len()];
+    let mut prefix = [0; NONCE_PREFIX_BYTES];
+    anyhow::ensure!(
+        read_full(&mut input, &mut magic)? == MAGIC.len() && &magic == MAGIC,
+        "'{}' is not an encrypted infrared backup",
+        encrypted.display()
+    );
+    anyhow::ensure!(
+        read_full(&mut input, &mut prefix)? == NONCE_PREFIX_BYTES,
+        "backup is truncated"
+    );
+
+    let mut index = 0u32;
+    loop {
+        let mut len = [0; 4];
+        anyhow::ensure!(read_full(&mut input, &mut len)? == 4, "backup is truncated");
+        let len = u32::from_be_bytes(len) as usize;
+        anyhow::ensure!(
+            (TAG_BYTES..=CHUNK_BYTES + TAG_BYTES).contains(&len),
+            "backup is corrupt"
+        );
+        let mut sealed = vec![0; len];
+        anyhow::ensure!(
+            read_full(&mut input, &mut sealed)? == len,
+            "backup is truncated"
+        );
+
+        // A chunk opens only as what it was sealed as: last or not
+        let last = [true, false].into_iter().find_map(|last| {
+            let mut attempt = sealed.clone();
+            aead.open_in_place(
+                nonce(&prefix, index),
+                Aad::from([u8::from(last)]),
+                &mut attempt,
+            )
+            .ok()
+            .map(|opened| (last, opened.to_vec()))
+        });
+        let Some((last, opened)) = last else {
+            anyhow::bail!("backup does not decrypt with this key, or was altered");
+        };
+        output.write_all(&opened)?;
+
+        if last {
+            anyhow::ensure!(
+                input.read(&mut [0])? == 0,
+                "backup has data after its last chunk"
+            );
+            break;
+        }
+        index = index
+            .checked_add(1)
+            .ok_or_else(|| anyhow::anyhow!("backup is corrupt"))?;
+    }
+
+    output.flush()?;
+    Ok(())
+}
+
+/// Take, encrypt and upload one backup, then delete all but the newest
+/// `config.keep` under the prefix.
+pub async fn upload_backup(
+    storage: &dyn SignalStore,
+    config: &OffsiteConfig,
+    now: DateTime<Utc>,
+) -> anyhow::Result<OffsiteReport> {
+    let plaintext = backup::temp_path(&config.work_dir, now);
+    let encrypted = plaintext.with_extension("enc.tmp");
+
+    let sealed = async {
+        storage.backup_to(&plaintext).await?;
+        let (from, to, key) = (plaintext.clone(), encrypted.clone(), config.key.clone());
+        tokio::task::spawn_blocking(move || encrypt_file(&from, &to, &key)).await?
+    }
+    .await;
+    let _ = tokio::fs::remove_file(&plaintext).await;
+
+    let uploaded = async {
+        let sha256 = sealed?;
+        let bytes = tokio::fs::metadata(&encrypted).await?.len();
+        let key = format!(
+            "{}{}{}",
+            config.prefix,
+            backup::file_name(now),
+            OBJECT_SUFFIX
+        );
+        config.bucket.put_file(&key, &encrypted, &sha256).await?;
+        anyhow::Ok((key, bytes))
+    }
+    .await;
+    let _ = tokio::fs::remove_file(&encrypted).await;
+    let (key, bytes) = uploaded?;
+
+    let removed = rotate(config).await?;
+    Ok(OffsiteReport {
+        key,
+        bytes,
+        removed,
+    })
+}
+
+/// Delete all but the newest `config.keep` backups under the prefix.
+///
+/// Backup names sort by the time they were taken; other objects under the
+/// prefix are left alone.
+async fn rotate(config: &OffsiteConfig) -> anyhow::Result<Vec<String>> {
+    let backups: Vec<String> = config
+        .bucket
+        .list(&config.prefix)
+        .await?
+        .into_iter()
+        .filter(|key| {
+            key[config.prefix.len()..].starts_with("infrared-") && key.ends_with(OBJECT_SUFFIX)
+        })
+        .collect();
+
+    let expired = backups.len().saturating_sub(config.keep);
+    let mut removed = Vec::new();
+    for key in &backups[..expired] {
+        config.bucket.delete(key).await?;
+        removed.push(key.clone());
+    }
+    Ok(removed)
+}
+
+/// Spawn the background task uploading a backup every `config.interval`.
+///
+/// The first backup is taken one interval after startup, so a crash loop
+/// does not flood the bucket.
+pub fn spawn_offsite_backup_task(
+    storage: Storage,
+    config: OffsiteConfig,
+    metrics: Metrics,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(config.interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+
+            let now = Utc::now();
+            match upload_backup(&storage, &config, now).await {
+                Ok(report) => {
+                    metrics.inc_counter("infrared_offsite_backups_total", &[], 1.0);
+                    metrics.set_gauge(
+                        "infrared_offsite_backup_last_success_timestamp_seconds",
+                        &[],
+                        now.timestamp() as f64,
+                    );
+                    info!(
+                        key = %report.key,
+                        bytes = report.bytes,
+                        removed = report.removed.len(),
+                        "Off-site backup uploaded"
+                    );
+                }
+                Err(e) => {
+                    metrics.inc_counter("infrared_offsite_backup_failures_total", &[], 1.0);
+                    warn!(error = %e, "Failed to upload off-site backup");
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LifeSignal;
+    use crate::s3::S3Config;
+    use chrono::TimeZone;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    fn key() -> BackupKey {
+        BackupKey::parse(&"ab".repeat(32)).unwrap()
+    }
+
+    #[test]
+    fn test_encryption_round_trip() {
+        let dir = std::env::temp_dir().join(format!("infrared-offsite-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let plaintext: Vec<u8> = (0..CHUNK_BYTES * 2 + 100)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        std::fs::write(dir.join("plain"), &plaintext).unwrap();
+
+        let sha256 = encrypt_file(&dir.join("plain"), &dir.join("sealed"), &key()).unwrap();
+        let sealed = std::fs::read(dir.join("sealed")).unwrap();
+        assert_eq!(sha256, crate::s3::sha256_hex(&sealed));
+        assert!(sealed.starts_with(MAGIC));
+
+        decrypt_file(&dir.join("sealed"), &dir.join("opened"), &key()).unwrap();
+        assert_eq!(std::fs::read(dir.join("opened")).unwrap(), plaintext);
+
+        // Another key, a flipped bit and a missing last chunk are all refused
+        let other = BackupKey::parse(&"cd".repeat(32)).unwrap();
+        let mut tampered = sealed.clone();
+        tampered[100] ^= 1;
+        let truncated = &sealed[..MAGIC.len() + NONCE_PREFIX_BYTES + 4 + CHUNK_BYTES + TAG_BYTES];
+        for (name, bytes, key) in [
+            ("other", &sealed[..], &other),
+            ("tampered", &tampered[..], &key()),
+            ("truncated", truncated, &key()),
+        ] {
+            std::fs::write(dir.join(name), bytes).unwrap();
+            let out = dir.join(format!("{}.out", name));
+            assert!(
+                decrypt_file(&dir.join(name), &out, key).is_err(),
+                "{}",
+                name
+            );
+        }
+
+        assert!(BackupKey::parse("abcd").is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// An in-memory stand-in for S3, ignoring signatures.
+    async fn fake_s3(objects: Arc<Mutex<BTreeMap<String, Vec<u8>>>>) -> String {
+        use axum::body::Bytes;
+        use axum::extract::{Path, Query, State};
+        use axum::routing::get;
+
+        type Objects = Arc<Mutex<BTreeMap<String, Vec<u8>>>>;
+        async fn list(
+            State(objects): State<Objects>,
+            Query(query): Query<BTreeMap<String, String>>,
+        ) -> String {
+            let prefix = query.get("prefix").cloned().unwrap_or_default();
+            let keys: String = objects
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|k| k.starts_with(&prefix))
+                .map(|k| format!("<Contents><Key>{}</Key></Contents>", k))
+                .collect();
+            format!(
+                "<ListBucketResult><IsTruncated>false</IsTruncated>{}</ListBucketResult>",
+                keys
+            )
+        }
+        async fn put(State(objects): State<Objects>, Path(key): Path<String>, body: Bytes) {
+            objects.lock().unwrap().insert(key, body.to_vec());
+        }
+        async fn delete(State(objects): State<Objects>, Path(key): Path<String>) {
+            objects.lock().unwrap().remove(&key);
+        }
+
+        let app = axum::Router::new()
+            .route("/backups/", get(list))
+            .route("/backups/*key", axum::routing::put(put).delete(delete))
+            .with_state(objects);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        base
+    }
+
+    #[tokio::test]
+    async fn test_upload_and_rotate() {
+        let objects = Arc::new(Mutex::new(BTreeMap::new()));
+        let endpoint = fake_s3(objects.clone()).await;
+        objects
+            .lock()
+            .unwrap()
+            .insert("field-1/notes.txt".to_string(), Vec::new());
+
+        let root = std::env::temp_dir().join(format!("infrared-offsite-up-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let url = format!("sqlite:{}?mode=rwc", root.join("live.db").display());
+        let storage = Storage::new(&url).await.unwrap();
+        let noon = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: noon,
+                weight: 2,
+                channel: None,
+            })
+            .await
+            .unwrap();
+
+        let config = OffsiteConfig {
+            bucket: S3Bucket::new(
+                S3Config {
+                    endpoint: endpoint.parse().unwrap(),
+                    bucket: "backups".to_string(),
+                    region: "us-east-1".to_string(),
+                    access_key_id: "id".to_string(),
+                    secret_access_key: "secret".to_string(),
+                },
+                reqwest::Client::new(),
+            ),
+            prefix: "field-1/".to_string(),
+            key: key(),
+            interval: Duration::from_secs(3600),
+            keep: 2,
+            work_dir: root.clone(),
+        };
+
+        for hour in 0..3 {
+            upload_backup(&storage, &config, noon + chrono::Duration::hours(hour))
+                .await
+                .unwrap();
+        }
+        let keys: Vec<String> = objects.lock().unwrap().keys().cloned().collect();
+        assert_eq!(
+            keys,
+            vec![
+                "field-1/infrared-20240501T130000Z.db.enc",
+                "field-1/infrared-20240501T140000Z.db.enc",
+                "field-1/notes.txt",
+            ]
+        );
+        // Nothing but the live database is left in the work directory
+        assert!(std::fs::read_dir(&root).unwrap().all(|entry| {
+            let name = entry.unwrap().file_name();
+            name.to_string_lossy().starts_with("live.db")
+        }));
+
+        // The latest backup restores the database
+        let sealed = objects.lock().unwrap()[&keys[1]].clone();
+        std::fs::write(root.join("download.enc"), sealed).unwrap();
+        decrypt_file(
+            &root.join("download.enc"),
+            &root.join("restored.db"),
+            &key(),
+        )
+        .unwrap();
+        let restored = Storage::connect(&format!("sqlite:{}", root.join("restored.db").display()))
+            .await
+            .unwrap();
+        assert_eq!(
+            restored
+                .query_bucket_window("zone-a", 60, noon)
+                .await
+                .unwrap(),
+            2
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
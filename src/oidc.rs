@@ -0,0 +1,551 @@
+//! OpenID Connect login for dashboard and admin endpoints.
+//!
+//! Analysts sign in through the organization's SSO (authorization code flow)
+//! and receive a signed session cookie. The cookie is accepted on read and
+//! admin endpoints only; ingestion stays token-based.
+//!
+//! # Flow
+//!
+//! 1. `GET /auth/login?next=/dashboard` redirects to the identity provider
+//! 2. The provider redirects back to `GET /auth/callback?code=...&state=...`
+//! 3. The code is exchanged for an ID token, which is verified against the
+//!    provider's JWKS (or the client secret, for providers that only sign
+//!    with HS256), and an `infrared_session` cookie is set
+//! 4. `GET /auth/logout` clears the session
+//!
+//! # Privacy
+//!
+//! Sessions carry only the ID token's `sub` (used as the actor label) and the
+//! granted role. No profile claims, IPs, or user agents are stored.
+
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    response::{AppendHeaders, IntoResponse, Redirect, Response},
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tracing::{info, warn};
+
+use crate::api::AppState;
+use crate::auth::{Principal, Role};
+use crate::jwt::{JwtValidator, idp_client};
+
+/// Cookie holding the signed session.
+pub const SESSION_COOKIE: &str = "infrared_session";
+
+/// Cookie holding the pending login's state and nonce.
+const STATE_COOKIE: &str = "infrared_oidc_state";
+
+/// `iss` claim of session tokens, so they can't be confused with other JWTs.
+const SESSION_ISSUER: &str = "infrared-session";
+
+/// `iss` claim of login state tokens.
+const STATE_ISSUER: &str = "infrared-oidc-state";
+
+/// How long a login attempt may take before its state expires.
+const LOGIN_TIMEOUT_MINUTES: i64 = 10;
+
+/// OIDC client configuration.
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL; discovery is fetched from `{issuer}/.well-known/openid-configuration`.
+    pub issuer_url: String,
+
+    /// OAuth client ID registered with the provider.
+    pub client_id: String,
+
+    /// OAuth client secret.
+    pub client_secret: String,
+
+    /// Public URL of `GET /auth/callback`.
+    pub redirect_url: String,
+
+    /// Secret used to sign session cookies.
+    pub session_secret: String,
+
+    /// ID token claim holding the Infrared role; users without it get `default_role`.
+    pub role_claim: String,
+
+    /// Role granted to signed-in users without a role claim.
+    pub default_role: Role,
+
+    /// Session lifetime in hours.
+    pub session_hours: i64,
+}
+
+impl OidcConfig {
+    /// Create a configuration granting signed-in users read access for 8 hours.
+    pub fn new(
+        issuer_url: &str,
+        client_id: &str,
+        client_secret: &str,
+        redirect_url: &str,
+        session_secret: &str,
+    ) -> Self {
+        Self {
+            issuer_url: issuer_url.trim_end_matches('/').to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            redirect_url: redirect_url.to_string(),
+            session_secret: session_secret.to_string(),
+            role_claim: "role".to_string(),
+            default_role: Role::Read,
+            session_hours: 8,
+        }
+    }
+}
+
+/// Subset of the provider's discovery document Infrared needs.
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+    #[serde(default)]
+    id_token_signing_alg_values_supported: Vec<String>,
+}
+
+/// Token endpoint response.
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// Claims of the pending-login state cookie.
+#[derive(Debug, Serialize, Deserialize)]
+struct StateClaims {
+    iss: String,
+    exp: i64,
+    state: String,
+    nonce: String,
+    next: String,
+}
+
+/// Claims of the session cookie.
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionClaims {
+    iss: String,
+    exp: i64,
+    sub: String,
+    role: Role,
+}
+
+/// A discovered OIDC provider plus session signing keys.
+pub struct Oidc {
+    config: OidcConfig,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    client: reqwest::Client,
+    id_tokens: JwtValidator,
+    session_encoding: EncodingKey,
+    session_decoding: DecodingKey,
+}
+
+impl Oidc {
+    /// Fetch the provider's discovery document and prepare the client.
+    pub async fn discover(config: OidcConfig) -> anyhow::Result<Self> {
+        let client = idp_client();
+        let url = format!("{}/.well-known/openid-configuration", config.issuer_url);
+        let metadata: ProviderMetadata = client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        // Prefer RS256 (mandatory for OIDC providers), otherwise HS256, which
+        // providers sign with the client secret rather than a published key
+        let supports = |alg: &str| {
+            metadata.id_token_signing_alg_values_supported.is_empty()
+                || metadata
+                    .id_token_signing_alg_values_supported
+                    .iter()
+                    .any(|a| a == alg)
+        };
+        let id_tokens = if supports("RS256") {
+            JwtValidator::jwks(&metadata.jwks_uri, Algorithm::RS256)
+        } else if supports("HS256") {
+            JwtValidator::hs256(&config.client_secret)
+        } else {
+            anyhow::bail!("identity provider supports neither RS256 nor HS256 ID tokens");
+        };
+        let id_tokens = id_tokens
+            .with_issuer(&config.issuer_url)
+            .with_audience(&config.client_id)
+            .with_role_claim(&config.role_claim)
+            .with_default_role(config.default_role);
+
+        Ok(Self {
+            authorization_endpoint: metadata.authorization_endpoint,
+            token_endpoint: metadata.token_endpoint,
+            client,
+            id_tokens,
+            session_encoding: EncodingKey::from_secret(config.session_secret.as_bytes()),
+            session_decoding: DecodingKey::from_secret(config.session_secret.as_bytes()),
+            config,
+        })
+    }
+
+    /// Build the provider redirect URL and the matching state cookie value.
+    fn begin_login(&self, next: &str) -> anyhow::Result<(String, String)> {
+        let claims = StateClaims {
+            iss: STATE_ISSUER.to_string(),
+            exp: (Utc::now() + Duration::minutes(LOGIN_TIMEOUT_MINUTES)).timestamp(),
+            state: random_token(),
+            nonce: random_token(),
+            next: next.to_string(),
+        };
+
+        let url = format!(
+            "{}{}response_type=code&client_id={}&redirect_uri={}&scope=openid&state={}&nonce={}",
+            self.authorization_endpoint,
+            if self.authorization_endpoint.contains('?') {
+                '&'
+            } else {
+                '?'
+            },
+            urlencoding::encode(&self.config.client_id),
+            urlencoding::encode(&self.config.redirect_url),
+            claims.state,
+            claims.nonce,
+        );
+
+        Ok((url, self.sign(&claims)?))
+    }
+
+    /// Exchange an authorization code for a session cookie value.
+    ///
+    /// Returns the session value and the post-login redirect path.
+    async fn complete_login(
+        &self,
+        code: &str,
+        state: &str,
+        state_cookie: &str,
+    ) -> anyhow::Result<(String, String)> {
+        let pending: StateClaims = self.verify(state_cookie, STATE_ISSUER)?;
+        if pending.state != state {
+            anyhow::bail!("login state mismatch");
+        }
+
+        let tokens: TokenResponse = self
+            .client
+            .post(&self.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.config.redirect_url),
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let claims = self
+            .id_tokens
+            .decode_claims(&tokens.id_token)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("invalid ID token"))?;
+        if claims.extra.get("nonce").and_then(|n| n.as_str()) != Some(pending.nonce.as_str()) {
+            anyhow::bail!("ID token nonce mismatch");
+        }
+        let principal = self
+            .id_tokens
+            .principal(claims)
+            .ok_or_else(|| anyhow::anyhow!("ID token grants no role"))?;
+
+        let session = self.create_session(&principal)?;
+        Ok((session, pending.next))
+    }
+
+    /// Sign a session for a principal.
+    fn create_session(&self, principal: &Principal) -> anyhow::Result<String> {
+        self.sign(&SessionClaims {
+            iss: SESSION_ISSUER.to_string(),
+            exp: (Utc::now() + Duration::hours(self.config.session_hours)).timestamp(),
+            sub: principal.label.clone(),
+            role: principal.role,
+        })
+    }
+
+    /// Principal of a valid session cookie value.
+    pub fn session_principal(&self, session: &str) -> Option<Principal> {
+        let claims: SessionClaims = self.verify(session, SESSION_ISSUER).ok()?;
+        Some(Principal {
+            role: claims.role,
+            label: claims.sub,
+        })
+    }
+
+    fn sign<T: Serialize>(&self, claims: &T) -> anyhow::Result<String> {
+        Ok(encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &self.session_encoding,
+        )?)
+    }
+
+    fn verify<T: DeserializeOwned>(&self, token: &str, issuer: &str) -> anyhow::Result<T> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_issuer(&[issuer]);
+        Ok(decode::<T>(token, &self.session_decoding, &validation)?.claims)
+    }
+
+    fn cookie(&self, name: &str, value: &str, max_age_seconds: i64) -> HeaderValue {
+        let secure = if self.config.redirect_url.starts_with("https://") {
+            "; Secure"
+        } else {
+            ""
+        };
+        HeaderValue::from_str(&format!(
+            "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+            name, value, max_age_seconds, secure
+        ))
+        .unwrap_or_else(|_| HeaderValue::from_static(""))
+    }
+}
+
+/// Random URL-safe token for state and nonce values.
+fn random_token() -> String {
+    rand::random::<[u8; 16]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Read a cookie value from request headers.
+pub fn cookie_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Only allow local redirect targets after login (no open redirects).
+///
+/// Browsers read `\` as `/` and drop tabs and newlines from URLs, so
+/// `/\evil.example` would leave the site as well.
+fn safe_next(next: Option<&str>) -> &str {
+    match next {
+        Some(path)
+            if path.starts_with('/')
+                && !path.starts_with("//")
+                && !path.contains(|c: char| c == '\\' || c.is_control()) =>
+        {
+            path
+        }
+        _ => "/dashboard",
+    }
+}
+
+/// Query parameters for `GET /auth/login`.
+#[derive(Debug, Deserialize)]
+pub struct LoginQuery {
+    /// Local path to return to after signing in (default: `/dashboard`).
+    pub next: Option<String>,
+}
+
+/// Query parameters for `GET /auth/callback`.
+#[derive(Debug, Deserialize)]
+pub struct CallbackQuery {
+    pub code: String,
+    pub state: String,
+}
+
+/// GET /auth/login - Redirect to the identity provider.
+pub async fn login(
+    State(state): State<AppState>,
+    Query(query): Query<LoginQuery>,
+) -> Result<Response, StatusCode> {
+    let oidc = state.auth.oidc().ok_or(StatusCode::NOT_FOUND)?;
+
+    let (url, pending) = oidc
+        .begin_login(safe_next(query.next.as_deref()))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok((
+        [(
+            header::SET_COOKIE,
+            oidc.cookie(STATE_COOKIE, &pending, LOGIN_TIMEOUT_MINUTES * 60),
+        )],
+        Redirect::to(&url),
+    )
+        .into_response())
+}
+
+/// GET /auth/callback - Complete the login and set the session cookie.
+pub async fn callback(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<CallbackQuery>,
+) -> Result<Response, StatusCode> {
+    let oidc = state.auth.oidc().ok_or(StatusCode::NOT_FOUND)?;
+    let pending = cookie_value(&headers, STATE_COOKIE).ok_or(StatusCode::BAD_REQUEST)?;
+
+    match oidc
+        .complete_login(&query.code, &query.state, pending)
+        .await
+    {
+        Ok((session, next)) => {
+            info!("SSO login completed");
+            Ok((
+                // Two cookies: headers must be appended, not replaced
+                AppendHeaders([
+                    (
+                        header::SET_COOKIE,
+                        oidc.cookie(SESSION_COOKIE, &session, oidc.config.session_hours * 3600),
+                    ),
+                    (header::SET_COOKIE, oidc.cookie(STATE_COOKIE, "", 0)),
+                ]),
+                Redirect::to(&next),
+            )
+                .into_response())
+        }
+        Err(e) => {
+            warn!(error = %e, "SSO login failed");
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+/// GET /auth/logout - Clear the session cookie.
+pub async fn logout(State(state): State<AppState>) -> Result<Response, StatusCode> {
+    let oidc = state.auth.oidc().ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok((
+        [(header::SET_COOKIE, oidc.cookie(SESSION_COOKIE, "", 0))],
+        StatusCode::NO_CONTENT,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_oidc() -> Oidc {
+        let config = OidcConfig::new(
+            "https://idp.example",
+            "infrared",
+            "client-secret",
+            "https://infrared.example/auth/callback",
+            "session-secret",
+        );
+        Oidc {
+            authorization_endpoint: "https://idp.example/authorize".to_string(),
+            token_endpoint: "https://idp.example/token".to_string(),
+            client: idp_client(),
+            id_tokens: JwtValidator::hs256("unused"),
+            session_encoding: EncodingKey::from_secret(config.session_secret.as_bytes()),
+            session_decoding: DecodingKey::from_secret(config.session_secret.as_bytes()),
+            config,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hs256_id_tokens_use_client_secret() {
+        use axum::{Json, Router, routing::get};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let metadata = serde_json::json!({
+            "authorization_endpoint": format!("{}/authorize", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "jwks_uri": format!("{}/jwks", issuer),
+            "id_token_signing_alg_values_supported": ["HS256"],
+        });
+        let app = Router::new().route(
+            "/.well-known/openid-configuration",
+            get(move || async move { Json(metadata) }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let config = OidcConfig::new(
+            &issuer,
+            "infrared",
+            "client-secret",
+            "https://infrared.example/auth/callback",
+            "session-secret",
+        );
+        let oidc = Oidc::discover(config).await.unwrap();
+
+        let id_token = |secret: &str| {
+            let claims = serde_json::json!({
+                "iss": issuer,
+                "aud": "infrared",
+                "sub": "analyst",
+                "exp": (Utc::now() + Duration::minutes(5)).timestamp(),
+            });
+            encode(
+                &Header::new(Algorithm::HS256),
+                &claims,
+                &EncodingKey::from_secret(secret.as_bytes()),
+            )
+            .unwrap()
+        };
+        let principal = oidc.id_tokens.validate(&id_token("client-secret")).await;
+        assert_eq!(principal.unwrap().label, "analyst");
+        assert!(
+            oidc.id_tokens
+                .validate(&id_token("session-secret"))
+                .await
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let oidc = test_oidc();
+        let principal = Principal {
+            role: Role::Admin,
+            label: "analyst".to_string(),
+        };
+
+        let session = oidc.create_session(&principal).unwrap();
+
+        assert_eq!(oidc.session_principal(&session), Some(principal));
+        assert!(oidc.session_principal("garbage").is_none());
+    }
+
+    #[test]
+    fn test_state_token_is_not_a_session() {
+        let oidc = test_oidc();
+        let (url, pending) = oidc.begin_login("/dashboard").unwrap();
+
+        assert!(url.starts_with("https://idp.example/authorize?response_type=code"));
+        assert!(url.contains("redirect_uri=https%3A%2F%2Finfrared.example%2Fauth%2Fcallback"));
+        assert!(oidc.session_principal(&pending).is_none());
+    }
+
+    #[test]
+    fn test_safe_next() {
+        assert_eq!(safe_next(Some("/dashboard/summary")), "/dashboard/summary");
+        assert_eq!(safe_next(Some("//evil.example")), "/dashboard");
+        assert_eq!(safe_next(Some("https://evil.example")), "/dashboard");
+        assert_eq!(safe_next(Some("/\\evil.example")), "/dashboard");
+        assert_eq!(safe_next(Some("/\t/evil.example")), "/dashboard");
+        assert_eq!(safe_next(Some("/dashboard\r\nSet-Cookie: x")), "/dashboard");
+        assert_eq!(safe_next(None), "/dashboard");
+    }
+
+    #[test]
+    fn test_cookie_value() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; infrared_session=abc.def"),
+        );
+
+        assert_eq!(cookie_value(&headers, SESSION_COOKIE), Some("abc.def"));
+        assert_eq!(cookie_value(&headers, "missing"), None);
+    }
+}
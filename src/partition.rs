@@ -0,0 +1,185 @@
+//! Daily partitions of the life signal table.
+//!
+//! Life signals are written to one table per UTC day, `life_signals_YYYYMMDD`,
+//! created on first write. Queries over a time range only read the days it
+//! covers, and retention drops whole expired days with a cheap `DROP TABLE`
+//! instead of deleting their rows one by one. The storage layer routes every
+//! read and write, so callers still see a single `life_signals` table.
+//!
+//! The original `life_signals` table is kept and always read: it holds
+//! signals written before partitioning, until retention deletes them.
+//!
+//! Row IDs stay unique across partitions: each partition's IDs start at its
+//! day number times [`ID_SPAN`], so they also grow from one day to the next
+//! and export cursors keep working.
+//!
+//! A migration changing the `life_signals` columns must change
+//! [`create_statements`] to match.
+//!
+//! # Privacy
+//!
+//! Partitioning changes where signals are stored, not what: a bucket, a
+//! timestamp, a weight and a shared channel name.
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// The unpartitioned signal table, which partitions mirror.
+pub const SIGNALS_TABLE: &str = "life_signals";
+
+/// Prefix of partition table names, followed by `YYYYMMDD`.
+pub const PARTITION_PREFIX: &str = "life_signals_";
+
+/// Seconds per partition.
+pub const DAY_SECONDS: i64 = 24 * 60 * 60;
+
+/// Row IDs reserved for each day's partition.
+pub const ID_SPAN: i64 = 1 << 32;
+
+/// The day (since the Unix epoch) a timestamp falls on.
+pub fn day_of(ts: i64) -> i64 {
+    ts.div_euclid(DAY_SECONDS)
+}
+
+/// The table holding signals of `day`.
+pub fn table_name(day: i64) -> String {
+    let date = DateTime::<Utc>::from_timestamp(day * DAY_SECONDS, 0)
+        .map(|t| t.date_naive())
+        .unwrap_or_default();
+    format!("{}{}", PARTITION_PREFIX, date.format("%Y%m%d"))
+}
+
+/// The day a partition table holds, or `None` if `name` is not one.
+pub fn parse_table_name(name: &str) -> Option<i64> {
+    let suffix = name.strip_prefix(PARTITION_PREFIX)?;
+    if suffix.len() != 8 || !suffix.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let date = NaiveDate::parse_from_str(suffix, "%Y%m%d").ok()?;
+    Some(day_of(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp()))
+}
+
+/// Statements creating `day`'s partition, its index, and its ID range,
+/// each a no-op if already done.
+pub fn create_statements(day: i64) -> [String; 3] {
+    let table = table_name(day);
+    [
+        format!(
+            "CREATE TABLE IF NOT EXISTS \"{}\" (\
+                id INTEGER PRIMARY KEY AUTOINCREMENT, \
+                bucket TEXT NOT NULL, \
+                ts INTEGER NOT NULL, \
+                weight INTEGER NOT NULL, \
+                channel TEXT)",
+            table
+        ),
+        format!(
+            "CREATE INDEX IF NOT EXISTS \"idx_{}_bucket_ts\" ON \"{}\"(bucket, ts)",
+            table, table
+        ),
+        format!(
+            "INSERT INTO sqlite_sequence (name, seq) SELECT '{}', {} \
+             WHERE NOT EXISTS (SELECT 1 FROM sqlite_sequence WHERE name = '{}')",
+            table,
+            day.max(0) * ID_SPAN,
+            table
+        ),
+    ]
+}
+
+/// Most terms of one compound SELECT, below SQLite's limit of 500.
+const MAX_UNION_TERMS: usize = 400;
+
+/// Join `selects` with `UNION ALL`. Past [`MAX_UNION_TERMS`] selects, they
+/// are unioned in chunks that `outer` (a `SELECT` list) reads from, so no
+/// compound SELECT has too many terms however many partitions there are.
+fn union_all(selects: Vec<String>, outer: &str) -> String {
+    if selects.len() <= MAX_UNION_TERMS {
+        return selects.join(" UNION ALL ");
+    }
+    let chunks = selects
+        .chunks(MAX_UNION_TERMS)
+        .map(|chunk| format!("{} FROM ({})", outer, chunk.join(" UNION ALL ")))
+        .collect();
+    union_all(chunks, outer)
+}
+
+/// A `FROM` source reading `tables` as one: the table itself if there is
+/// only one, otherwise a `UNION ALL` of them.
+pub fn union_source(tables: &[String]) -> String {
+    const COLUMNS: &str = "SELECT id, bucket, ts, weight, channel";
+    match tables {
+        [table] => format!("\"{}\"", table),
+        _ => format!(
+            "({})",
+            union_all(
+                tables
+                    .iter()
+                    .map(|t| format!("{} FROM \"{}\"", COLUMNS, t))
+                    .collect(),
+                COLUMNS
+            )
+        ),
+    }
+}
+
+/// A scalar expression for the latest signal timestamp of `bucket` (an SQL
+/// expression, e.g., a column of the outer query) across `tables`.
+///
+/// Each table is asked for its own maximum, so every one can use its
+/// `(bucket, ts)` index.
+pub fn last_ts_expr(tables: &[String], bucket: &str) -> String {
+    format!(
+        "(SELECT MAX(last_ts) FROM ({}))",
+        union_all(
+            tables
+                .iter()
+                .map(|t| format!(
+                    "SELECT MAX(ts) AS last_ts FROM \"{}\" WHERE bucket = {}",
+                    t, bucket
+                ))
+                .collect(),
+            "SELECT MAX(last_ts) AS last_ts"
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_table_names() {
+        let ts = Utc
+            .with_ymd_and_hms(2024, 5, 1, 23, 59, 59)
+            .unwrap()
+            .timestamp();
+        let day = day_of(ts);
+        assert_eq!(table_name(day), "life_signals_20240501");
+        assert_eq!(day_of(ts + 1), day + 1);
+        assert_eq!(parse_table_name("life_signals_20240501"), Some(day));
+
+        assert_eq!(parse_table_name("life_signals"), None);
+        assert_eq!(parse_table_name("life_signals_2024051"), None);
+        assert_eq!(parse_table_name("life_signals_20241301"), None);
+    }
+
+    #[test]
+    fn test_union_source() {
+        assert_eq!(
+            union_source(&["life_signals".to_string()]),
+            "\"life_signals\""
+        );
+        assert_eq!(
+            union_source(&["a".to_string(), "b".to_string()]),
+            "(SELECT id, bucket, ts, weight, channel FROM \"a\" \
+             UNION ALL SELECT id, bucket, ts, weight, channel FROM \"b\")"
+        );
+
+        // Past the term limit, tables are unioned in nested chunks
+        let tables: Vec<String> = (0..1000).map(|i| format!("t{}", i)).collect();
+        let source = union_source(&tables);
+        assert_eq!(source.matches("UNION ALL").count(), 999);
+        assert_eq!(source.matches("FROM (").count(), 3);
+    }
+}
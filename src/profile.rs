@@ -0,0 +1,159 @@
+//! Deployment profiles: preset defaults for common deployment sizes.
+//!
+//! Infrared has grown dozens of settings, most of which only need changing
+//! together when a deployment is much smaller or larger than the defaults
+//! assume. `INFRARED_PROFILE` picks one of three presets instead:
+//!
+//! - `field`: a single small box on an intermittent, metered link. Raw
+//!   signals are kept for 45 days and compacted after 14, the dashboard is
+//!   refreshed every three hours from the outage and disaster sources only,
+//!   and caches and buffers are kept small.
+//! - `regional`: a server covering a region, which is what the built-in
+//!   defaults are tuned for. Only the SQLite page cache is raised.
+//! - `global`: a large instance aggregating many regions. A year of raw
+//!   signals is kept, compacted after 90 days, the dashboard is refreshed
+//!   every 15 minutes, and caches, write batches and connection pools are
+//!   larger.
+//!
+//! A profile only supplies defaults: any setting in the environment still
+//! wins, so a profile can be adjusted one setting at a time.
+//!
+//! # Privacy
+//!
+//! Profiles only pick values for existing settings, so they store nothing
+//! new. The field profile keeps raw signals for less time than the default
+//! and the global profile for longer (see `INFRARED_RETENTION_DAYS`).
+
+/// Environment variable selecting the profile.
+pub const PROFILE_ENV: &str = "INFRARED_PROFILE";
+
+/// A deployment profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// A single small server on a poor link.
+    Field,
+    /// A server covering a region.
+    Regional,
+    /// A large instance aggregating many regions.
+    Global,
+}
+
+impl Profile {
+    /// Every profile.
+    pub const ALL: [Profile; 3] = [Profile::Field, Profile::Regional, Profile::Global];
+
+    /// Parse a profile name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "field" => Some(Profile::Field),
+            "regional" => Some(Profile::Regional),
+            "global" => Some(Profile::Global),
+            _ => None,
+        }
+    }
+
+    /// The profile's name, as set in `INFRARED_PROFILE`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::Field => "field",
+            Profile::Regional => "regional",
+            Profile::Global => "global",
+        }
+    }
+
+    /// The settings the profile supplies, by environment variable name.
+    pub fn settings(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Profile::Field => &[
+                ("INFRARED_RETENTION_DAYS", "45"),
+                ("INFRARED_COMPACT_AFTER_DAYS", "14"),
+                ("INFRARED_MAX_RAW_HISTORY_DAYS", "14"),
+                ("INFRARED_SQLITE_CACHE_KIB", "8192"),
+                ("INFRARED_SQLITE_READ_CONNECTIONS", "2"),
+                ("INFRARED_WRITE_BUFFER_SIZE", "100"),
+                ("INFRARED_MAINTENANCE_INTERVAL_MINUTES", "720"),
+                ("DASHBOARD_SNAPSHOT_INTERVAL_MINUTES", "180"),
+                ("DASHBOARD_HOT_REFRESH_MINUTES", "60"),
+                (
+                    "DASHBOARD_SOURCES",
+                    "ioda,cloudflare_radar,reliefweb,external",
+                ),
+                ("DASHBOARD_HTTP_TIMEOUT_SECONDS", "60"),
+                ("DASHBOARD_HTTP_MAX_RESPONSE_MB", "8"),
+                ("DASHBOARD_HTTP_POOL_MAX_IDLE_PER_HOST", "1"),
+            ],
+            Profile::Regional => &[("INFRARED_SQLITE_CACHE_KIB", "65536")],
+            Profile::Global => &[
+                ("INFRARED_RETENTION_DAYS", "365"),
+                ("INFRARED_COMPACT_AFTER_DAYS", "90"),
+                ("INFRARED_SQLITE_CACHE_KIB", "262144"),
+                ("INFRARED_SQLITE_READ_CONNECTIONS", "16"),
+                ("INFRARED_WRITE_BUFFER_SIZE", "5000"),
+                ("INFRARED_MAX_BATCH_BUCKETS", "500"),
+                ("DASHBOARD_SNAPSHOT_INTERVAL_MINUTES", "15"),
+                ("DASHBOARD_HOT_REFRESH_MINUTES", "5"),
+                ("DASHBOARD_HTTP_POOL_MAX_IDLE_PER_HOST", "16"),
+                ("DASHBOARD_HTTP_MAX_RESPONSE_MB", "64"),
+            ],
+        }
+    }
+
+    /// The profile's value for the setting `name`, if it supplies one.
+    pub fn default_for(self, name: &str) -> Option<&'static str> {
+        self.settings()
+            .iter()
+            .find(|(setting, _)| *setting == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// The profile named in `INFRARED_PROFILE`, if set.
+    pub fn from_env(env: impl Fn(&str) -> Option<String>) -> anyhow::Result<Option<Self>> {
+        env(PROFILE_ENV)
+            .map(|name| {
+                Self::parse(&name).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "unknown profile '{}' (expected: field, regional, global)",
+                        name
+                    )
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Look up the setting `name` in `env`, falling back to `profile`'s value.
+pub fn lookup(
+    env: impl Fn(&str) -> Option<String>,
+    profile: Option<Profile>,
+    name: &str,
+) -> Option<String> {
+    env(name).or_else(|| {
+        profile
+            .and_then(|profile| profile.default_for(name))
+            .map(str::to_string)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment_overrides_profile() {
+        let env = |name: &str| (name == "INFRARED_RETENTION_DAYS").then(|| "30".to_string());
+
+        assert_eq!(
+            lookup(env, Some(Profile::Global), "INFRARED_RETENTION_DAYS").as_deref(),
+            Some("30")
+        );
+        assert_eq!(
+            lookup(env, Some(Profile::Global), "INFRARED_COMPACT_AFTER_DAYS").as_deref(),
+            Some("90")
+        );
+        assert_eq!(lookup(env, None, "INFRARED_COMPACT_AFTER_DAYS"), None);
+
+        assert_eq!(Profile::parse(" Field "), Some(Profile::Field));
+        assert!(Profile::from_env(|_| Some("huge".to_string())).is_err());
+        assert_eq!(Profile::from_env(|_| None).unwrap(), None);
+    }
+}
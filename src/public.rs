@@ -0,0 +1,209 @@
+//! Anonymized public status mirror.
+//!
+//! Some deployments want to show the public that monitoring is running and
+//! where things look wrong, without publishing how they are instrumented.
+//! With `INFRARED_PUBLIC_MIRROR=true` an instance serves only `/health` and
+//! `GET /public/status`, which reports one coarse status per country and
+//! per bucket group and nothing else: no bucket names, no counts, no
+//! signal totals.
+//!
+//! Countries and groups are only listed when they cover at least
+//! `INFRARED_PUBLIC_MIN_BUCKETS` buckets (the k-anonymity floor), so no
+//! listed status can be traced back to a single bucket. They are listed
+//! under display labels from `INFRARED_PUBLIC_LABELS`, e.g.
+//! `SD=Sudan;north-region=Northern Districts`. Countries without a label
+//! are listed by code; groups without one are left out, since their names
+//! are internal.
+//!
+//! # Privacy
+//!
+//! Only labels, country codes and [`WarmthStatus`] values leave the
+//! instance.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::compute_group_warmth;
+use crate::model::{BucketGroup, WarmthStatus};
+use crate::storage::SignalStore;
+
+/// Default k-anonymity floor: buckets a country or group must cover.
+pub const DEFAULT_PUBLIC_MIN_BUCKETS: usize = 5;
+
+/// Window size of the published statuses, in minutes.
+pub const PUBLIC_WINDOW_MINUTES: u32 = 10;
+
+/// What the public mirror publishes, and under which names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicMirror {
+    /// Buckets a country or group must cover to be listed.
+    pub min_buckets: usize,
+
+    /// Display labels by country code or group name.
+    pub labels: HashMap<String, String>,
+}
+
+impl PublicMirror {
+    /// Parse display labels as `name=Label` pairs separated by `;`.
+    ///
+    /// ```
+    /// use infrared::public::PublicMirror;
+    ///
+    /// let labels = PublicMirror::parse_labels("SD=Sudan; north-region=North, East").unwrap();
+    /// assert_eq!(labels["north-region"], "North, East");
+    /// ```
+    pub fn parse_labels(spec: &str) -> anyhow::Result<HashMap<String, String>> {
+        spec.split(';')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, label) = pair
+                    .split_once('=')
+                    .ok_or_else(|| anyhow::anyhow!("expected name=Label, got '{}'", pair))?;
+                anyhow::ensure!(
+                    !label.trim().is_empty(),
+                    "label for '{}' must not be empty",
+                    name.trim()
+                );
+                Ok((name.trim().to_string(), label.trim().to_string()))
+            })
+            .collect()
+    }
+
+    /// The display label of a country code or group name.
+    fn label(&self, name: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, label)| label.as_str())
+    }
+}
+
+/// One published status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicEntry {
+    /// Display label, or the country code for unlabeled countries.
+    pub label: String,
+
+    pub status: WarmthStatus,
+}
+
+/// Response for `GET /public/status`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PublicStatus {
+    pub generated_at: DateTime<Utc>,
+
+    /// The time window in minutes the statuses were computed over.
+    pub window_minutes: u32,
+
+    /// Countries covering enough buckets, sorted by label.
+    pub countries: Vec<PublicEntry>,
+
+    /// Labeled groups covering enough buckets, sorted by label.
+    pub groups: Vec<PublicEntry>,
+}
+
+/// Compute the statuses `mirror` publishes.
+pub async fn build_public_status(
+    storage: &dyn SignalStore,
+    mirror: &PublicMirror,
+    now: DateTime<Utc>,
+) -> anyhow::Result<PublicStatus> {
+    let mut countries = Vec::new();
+    for (code, buckets) in storage.get_countries().await? {
+        if buckets.len() < mirror.min_buckets {
+            continue;
+        }
+        let label = mirror.label(&code).unwrap_or(&code).to_string();
+        let group = BucketGroup {
+            name: code,
+            buckets,
+        };
+        let warmth =
+            compute_group_warmth(storage, &group, PUBLIC_WINDOW_MINUTES, None, now).await?;
+        countries.push(PublicEntry {
+            label,
+            status: warmth.status,
+        });
+    }
+
+    let mut groups = Vec::new();
+    for group in storage.get_groups().await? {
+        if group.buckets.len() < mirror.min_buckets {
+            continue;
+        }
+        let Some(label) = mirror.label(&group.name) else {
+            continue;
+        };
+        let label = label.to_string();
+        let warmth =
+            compute_group_warmth(storage, &group, PUBLIC_WINDOW_MINUTES, None, now).await?;
+        groups.push(PublicEntry {
+            label,
+            status: warmth.status,
+        });
+    }
+
+    countries.sort_by(|a, b| a.label.cmp(&b.label));
+    groups.sort_by(|a, b| a.label.cmp(&b.label));
+    Ok(PublicStatus {
+        generated_at: now,
+        window_minutes: PUBLIC_WINDOW_MINUTES,
+        countries,
+        groups,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+
+    #[test]
+    fn test_parse_labels() {
+        let labels = PublicMirror::parse_labels("sd=Sudan;;north-region = North ").unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels["north-region"], "North");
+
+        assert!(PublicMirror::parse_labels("north-region").is_err());
+        assert!(PublicMirror::parse_labels("north-region= ").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_public_status_floor_and_labels() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        for i in 0..3 {
+            storage
+                .set_country(&format!("sd-{}", i), "SD")
+                .await
+                .unwrap();
+        }
+        storage.set_country("ye-0", "YE").await.unwrap();
+        let members = ["sd-0".to_string(), "sd-1".to_string(), "ye-0".to_string()];
+        storage.set_group("north", &members).await.unwrap();
+        storage.set_group("unlabeled", &members).await.unwrap();
+        storage.set_group("small", &members[..1]).await.unwrap();
+
+        let mirror = PublicMirror {
+            min_buckets: 3,
+            labels: PublicMirror::parse_labels("sd=Sudan;north=Northern Districts;small=Small")
+                .unwrap(),
+        };
+        let status = build_public_status(&storage, &mirror, Utc::now())
+            .await
+            .unwrap();
+
+        // Yemen has one bucket, "unlabeled" has no label, "small" one member
+        let labels = |entries: &[PublicEntry]| -> Vec<String> {
+            entries.iter().map(|e| e.label.clone()).collect()
+        };
+        assert_eq!(labels(&status.countries), vec!["Sudan"]);
+        assert_eq!(labels(&status.groups), vec!["Northern Districts"]);
+
+        let json = serde_json::to_string(&status).unwrap();
+        assert!(!json.contains("sd-0"));
+        assert!(!json.contains("unlabeled"));
+    }
+}
@@ -0,0 +1,211 @@
+//! Retention of raw life signals.
+//!
+//! Raw signals are only read back for warmth windows, baselines and
+//! sparklines, none of which look further back than
+//! `INFRARED_MAX_RAW_HISTORY_DAYS`. Keeping them longer costs disk and
+//! weakens the promise that raw data does not live forever, so a background
+//! task deletes signals older than `INFRARED_RETENTION_DAYS`.
+//!
+//! Deletes run in batches of [`PRUNE_BATCH_SIZE`], so a large backlog never
+//! holds the database lock for long. Days wholly past the cutoff are
+//! dropped with their partition (see [`crate::partition`]) rather than
+//! deleted row by row. A bucket whose every signal has been
+//! pruned is no longer known, and stops being checked for alerts.
+//!
+//! The same task deletes persisted dashboard snapshots older than
+//! `DASHBOARD_SNAPSHOT_RETENTION_DAYS`, keeping the latest one.
+//!
+//! # Privacy
+//!
+//! Pruning only deletes; nothing about the deleted signals is logged beyond
+//! how many there were.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use crate::metrics::Metrics;
+use crate::storage::{SignalStore, Storage};
+
+/// Most signals deleted per statement.
+pub const PRUNE_BATCH_SIZE: u32 = 10_000;
+
+/// Time between pruning runs.
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The oldest timestamp kept when retaining `retention_days` of signals.
+pub fn cutoff(now: DateTime<Utc>, retention_days: u32) -> DateTime<Utc> {
+    now - chrono::Duration::days(i64::from(retention_days))
+}
+
+/// Delete every signal older than `cutoff`, one batch at a time.
+///
+/// # Returns
+///
+/// The number of signals deleted.
+pub async fn prune_signals(
+    storage: &dyn SignalStore,
+    cutoff: DateTime<Utc>,
+) -> anyhow::Result<u64> {
+    let mut deleted = 0;
+    loop {
+        let batch = storage
+            .delete_signals_before(cutoff, PRUNE_BATCH_SIZE)
+            .await?;
+        deleted += batch;
+        if batch < u64::from(PRUNE_BATCH_SIZE) {
+            return Ok(deleted);
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+/// Spawn the background task pruning signals older than `retention_days`
+/// and dashboard snapshots older than `snapshot_retention_days`; either is
+/// kept forever if 0.
+///
+/// The first run happens right away, so a lowered retention takes effect on
+/// restart.
+pub fn spawn_retention_task(
+    storage: Storage,
+    metrics: Metrics,
+    retention_days: u32,
+    snapshot_retention_days: u32,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(PRUNE_INTERVAL);
+        loop {
+            ticker.tick().await;
+
+            if retention_days > 0 {
+                match prune_signals(&storage, cutoff(Utc::now(), retention_days)).await {
+                    Ok(deleted) => {
+                        metrics.inc_counter("infrared_retention_pruned_total", &[], deleted as f64);
+                        if deleted > 0 {
+                            info!(deleted, retention_days, "Pruned expired life signals");
+                        }
+                    }
+                    Err(e) => {
+                        metrics.inc_counter("infrared_retention_failures_total", &[], 1.0);
+                        warn!(error = %e, "Failed to prune expired life signals");
+                    }
+                }
+            }
+
+            if snapshot_retention_days > 0 {
+                let cutoff = cutoff(Utc::now(), snapshot_retention_days);
+                match storage.delete_dashboard_snapshots_before(cutoff).await {
+                    Ok(deleted) => {
+                        metrics.inc_counter(
+                            "infrared_retention_pruned_snapshots_total",
+                            &[],
+                            deleted as f64,
+                        );
+                        if deleted > 0 {
+                            info!(
+                                deleted,
+                                snapshot_retention_days, "Pruned expired dashboard snapshots"
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        metrics.inc_counter("infrared_retention_failures_total", &[], 1.0);
+                        warn!(error = %e, "Failed to prune expired dashboard snapshots");
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LifeSignal;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_cutoff() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 31, 12, 0, 0).unwrap();
+        assert_eq!(
+            cutoff(now, 30),
+            Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prune_signals() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+
+        // More expired signals than fit in one batch
+        let expired = PRUNE_BATCH_SIZE as i64 + 5;
+        for i in 0..expired + 3 {
+            let age = if i < expired {
+                chrono::Duration::days(40)
+            } else {
+                chrono::Duration::days(1)
+            };
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: "zone-a".to_string(),
+                    timestamp: now - age,
+                    weight: 1,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let deleted = prune_signals(&storage, cutoff(now, 30)).await.unwrap();
+        assert_eq!(deleted, expired as u64);
+        assert_eq!(prune_signals(&storage, cutoff(now, 30)).await.unwrap(), 0);
+
+        let remaining = storage
+            .query_bucket_window("zone-a", 60 * 24 * 60, now)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 3);
+    }
+
+    #[tokio::test]
+    async fn test_prune_dashboard_snapshots() {
+        use crate::dashboard::{DashboardResponse, DashboardSummary};
+
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        let snapshot = |days_ago| DashboardResponse {
+            timestamp: now - chrono::Duration::days(days_ago),
+            summary: DashboardSummary::from_issues(&[]),
+            issues: vec![],
+            errors: vec![],
+            health: vec![],
+            suppressed: vec![],
+        };
+        for days_ago in [100, 95, 1] {
+            storage
+                .insert_dashboard_snapshot(&snapshot(days_ago))
+                .await
+                .unwrap();
+        }
+
+        let deleted = storage
+            .delete_dashboard_snapshots_before(cutoff(now, 90))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 2);
+        let oldest = storage
+            .get_dashboard_snapshot_at(now - chrono::Duration::days(2))
+            .await
+            .unwrap();
+        assert!(oldest.is_none());
+
+        // The latest snapshot is kept however old it is
+        let deleted = storage
+            .delete_dashboard_snapshots_before(cutoff(now, 0))
+            .await
+            .unwrap();
+        assert_eq!(deleted, 0);
+    }
+}
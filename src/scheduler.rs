@@ -0,0 +1,165 @@
+//! Warmth snapshot scheduler.
+//!
+//! Without a scheduler, a bucket's status only exists when someone asks
+//! for it, so a bucket nobody watches can die and come back unrecorded.
+//! The scheduler wakes at every window boundary, computes the warmth of
+//! every known bucket, feeds the result to the [`StatusTracker`] (so status
+//! transitions fire without HTTP traffic) and persists it as a
+//! [`WarmthSnapshot`], giving status history a fixed cadence.
+//!
+//! # Privacy
+//!
+//! Snapshots hold bucket-level totals and statuses only.
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::aggregation::compute_warmth;
+use crate::model::WarmthStatus;
+use crate::storage::{SignalStore, Storage};
+use crate::transitions::StatusTracker;
+
+/// A bucket's warmth as evaluated by the scheduler.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WarmthSnapshot {
+    /// The bucket identifier.
+    pub bucket: String,
+
+    /// Window size the status was computed over, in minutes.
+    pub window_minutes: u32,
+
+    /// The window boundary the snapshot was taken at.
+    pub taken_at: DateTime<Utc>,
+
+    /// Status at that moment.
+    pub status: WarmthStatus,
+
+    /// Total weight in the window ending at `taken_at`.
+    pub current_window_total: i64,
+
+    /// Baseline average the window was compared against.
+    pub recent_average: f64,
+}
+
+/// The first window boundary strictly after `now`.
+///
+/// Boundaries are multiples of the window size since the Unix epoch, so
+/// every instance snapshots at the same moments.
+pub fn next_boundary(now: DateTime<Utc>, window_minutes: u32) -> DateTime<Utc> {
+    let window_seconds = i64::from(window_minutes.max(1)) * 60;
+    let next = (now.timestamp().div_euclid(window_seconds) + 1) * window_seconds;
+    Utc.timestamp_opt(next, 0).unwrap()
+}
+
+/// Evaluate and persist the warmth of every known bucket at `now`.
+///
+/// Returns the number of snapshots written.
+pub async fn snapshot_all(
+    storage: &Storage,
+    transitions: &StatusTracker,
+    window_minutes: u32,
+    now: DateTime<Utc>,
+) -> anyhow::Result<usize> {
+    let mut snapshots = Vec::new();
+    for bucket in storage.get_all_known_buckets().await? {
+        let warmth = compute_warmth(storage, &bucket, window_minutes, now).await?;
+        transitions.observe(&warmth, now);
+        snapshots.push(WarmthSnapshot {
+            bucket,
+            window_minutes,
+            taken_at: now,
+            status: warmth.status,
+            current_window_total: warmth.current_window_total,
+            recent_average: warmth.recent_average,
+        });
+    }
+
+    storage.insert_warmth_snapshots(&snapshots).await?;
+    Ok(snapshots.len())
+}
+
+/// Spawn the scheduler, snapshotting every bucket at each boundary of
+/// `window_minutes`.
+pub fn spawn_warmth_scheduler(
+    storage: Storage,
+    transitions: StatusTracker,
+    window_minutes: u32,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            let boundary = next_boundary(Utc::now(), window_minutes);
+            let wait = (boundary - Utc::now()).to_std().unwrap_or(Duration::ZERO);
+            tokio::time::sleep(wait).await;
+
+            match snapshot_all(&storage, &transitions, window_minutes, boundary).await {
+                Ok(count) => info!(buckets = count, "Warmth snapshots persisted"),
+                Err(e) => warn!(error = %e, "Failed to snapshot bucket warmth"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{Event, EventBus};
+    use crate::model::LifeSignal;
+
+    #[test]
+    fn test_next_boundary() {
+        let at = |h, m, s| Utc.with_ymd_and_hms(2024, 1, 15, h, m, s).unwrap();
+        assert_eq!(next_boundary(at(10, 3, 20), 10), at(10, 10, 0));
+        // Exactly on a boundary: the next one
+        assert_eq!(next_boundary(at(10, 10, 0), 10), at(10, 20, 0));
+        assert_eq!(next_boundary(at(10, 59, 59), 60), at(11, 0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_all() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let bus = EventBus::new();
+        let mut events = bus.subscribe();
+        let transitions = StatusTracker::new().with_events(bus);
+        let start = Utc.with_ymd_and_hms(2024, 1, 15, 10, 0, 0).unwrap();
+
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: start - chrono::Duration::minutes(5),
+                weight: 10,
+                channel: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            snapshot_all(&storage, &transitions, 10, start)
+                .await
+                .unwrap(),
+            1
+        );
+        // The next boundary sees an empty window against a busy one
+        let next = start + chrono::Duration::minutes(10);
+        snapshot_all(&storage, &transitions, 10, next)
+            .await
+            .unwrap();
+
+        let history = storage
+            .get_warmth_snapshots("zone-a", start, next)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].status, WarmthStatus::Alive);
+        assert_eq!(history[0].current_window_total, 10);
+        assert_eq!(history[1].status, WarmthStatus::Dead);
+        assert_eq!(history[1].taken_at, next);
+
+        let Event::StatusChanged(transition) = events.try_recv().unwrap() else {
+            panic!("expected a status change");
+        };
+        assert_eq!(transition.status, WarmthStatus::Dead);
+    }
+}
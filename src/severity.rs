@@ -0,0 +1,315 @@
+//! Alert severity tiers.
+//!
+//! A bucket that has been dead for ten minutes and one that has been dead
+//! since yesterday are not equally urgent. Every alert carries a severity
+//! on the dashboard's [`IssueSeverity`] scale, escalating with how long the
+//! bucket has been in distress: `warning` at first, `critical` after
+//! [`SeverityTiers::critical_after`] and `emergency` after
+//! [`SeverityTiers::emergency_after`].
+//!
+//! [`SeverityRoutes`] send each tier to its own webhooks: the alert router
+//! checks alerts periodically and POSTs an alert once each time it reaches
+//! a routed tier, so a team paged for emergencies is not woken for every
+//! ten-minute dip.
+//!
+//! # Privacy
+//!
+//! Routed notifications are the alerts served by `/alerts/recent`: bucket
+//! identifiers, statuses and averages only.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+use crate::aggregation::generate_alerts_with_tiers;
+use crate::model::{Alert, AlertSubject, IssueSeverity};
+use crate::notify::WebhookNotifier;
+use crate::storage::Storage;
+
+/// How long an alert lasts before it escalates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeverityTiers {
+    /// Distress lasting at least this long is `critical`.
+    pub critical_after: chrono::Duration,
+
+    /// Distress lasting at least this long is `emergency`.
+    pub emergency_after: chrono::Duration,
+}
+
+impl Default for SeverityTiers {
+    fn default() -> Self {
+        Self {
+            critical_after: chrono::Duration::hours(2),
+            emergency_after: chrono::Duration::hours(12),
+        }
+    }
+}
+
+impl SeverityTiers {
+    /// Parse `critical_hours:emergency_hours`, e.g. `2:12`.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let parsed = spec.split_once(':').and_then(|(critical, emergency)| {
+            let critical: u32 = critical.trim().parse().ok()?;
+            let emergency: u32 = emergency.trim().parse().ok()?;
+            (critical <= emergency).then(|| Self {
+                critical_after: chrono::Duration::hours(i64::from(critical)),
+                emergency_after: chrono::Duration::hours(i64::from(emergency)),
+            })
+        });
+        parsed.ok_or_else(|| {
+            anyhow::anyhow!(
+                "expected critical_hours:emergency_hours with critical <= emergency, got '{}'",
+                spec
+            )
+        })
+    }
+
+    /// Severity of an alert whose distress began at `since`.
+    ///
+    /// Alerts with no known start are `warning`.
+    pub fn severity(&self, since: Option<DateTime<Utc>>, now: DateTime<Utc>) -> IssueSeverity {
+        let Some(since) = since else {
+            return IssueSeverity::Warning;
+        };
+        let lasted = now - since;
+        if lasted >= self.emergency_after {
+            IssueSeverity::Emergency
+        } else if lasted >= self.critical_after {
+            IssueSeverity::Critical
+        } else {
+            IssueSeverity::Warning
+        }
+    }
+}
+
+/// Webhooks per severity tier.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeverityRoutes {
+    routes: BTreeMap<IssueSeverity, Vec<String>>,
+}
+
+impl SeverityRoutes {
+    /// Parse `severity=webhook_url` pairs, e.g.
+    /// `critical=https://a/hook,emergency=https://b/hook`. A severity may be
+    /// listed more than once.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let mut routes: BTreeMap<IssueSeverity, Vec<String>> = BTreeMap::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (severity, url) = entry.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("invalid route '{}' (expected severity=webhook_url)", entry)
+            })?;
+            let severity = match severity.trim() {
+                "warning" => IssueSeverity::Warning,
+                "critical" => IssueSeverity::Critical,
+                "emergency" => IssueSeverity::Emergency,
+                other => anyhow::bail!("unknown alert severity '{}'", other),
+            };
+            routes
+                .entry(severity)
+                .or_default()
+                .push(url.trim().to_string());
+        }
+        Ok(Self { routes })
+    }
+
+    /// Whether no tier is routed anywhere.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Webhooks for a tier.
+    pub fn urls(&self, severity: IssueSeverity) -> &[String] {
+        self.routes.get(&severity).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every routed webhook, with its tier.
+    pub fn all(&self) -> impl Iterator<Item = (IssueSeverity, &str)> {
+        self.routes
+            .iter()
+            .flat_map(|(severity, urls)| urls.iter().map(move |url| (*severity, url.as_str())))
+    }
+}
+
+/// Highest tier already routed, per alert.
+type Routed = HashMap<(AlertSubject, String), IssueSeverity>;
+
+/// Route current alerts that reached a new tier since the last check.
+///
+/// `routed` carries state between checks; alerts that cleared are forgotten,
+/// so a bucket that recovers and fails again is routed afresh. A failed
+/// delivery is retried on the next check.
+pub async fn route_alerts(
+    alerts: &[Alert],
+    routes: &SeverityRoutes,
+    notifier: &WebhookNotifier,
+    routed: &mut Routed,
+) {
+    routed.retain(|key, _| {
+        alerts
+            .iter()
+            .any(|alert| alert.subject == key.0 && alert.bucket == key.1)
+    });
+
+    for alert in alerts {
+        let key = (alert.subject, alert.bucket.clone());
+        if routed.get(&key).is_some_and(|done| *done >= alert.severity) {
+            continue;
+        }
+
+        let mut delivered = true;
+        for url in routes.urls(alert.severity) {
+            if let Err(e) = notifier.send(url, alert).await {
+                warn!(bucket = %alert.bucket, error = %e, "Failed to route alert");
+                delivered = false;
+            }
+        }
+        if delivered {
+            if !routes.urls(alert.severity).is_empty() {
+                info!(
+                    bucket = %alert.bucket,
+                    severity = alert.severity.label(),
+                    "Alert routed"
+                );
+            }
+            routed.insert(key, alert.severity);
+        }
+    }
+}
+
+/// Spawn the alert router, checking alerts every `interval`.
+pub fn spawn_alert_router(
+    storage: Storage,
+    tiers: SeverityTiers,
+    routes: SeverityRoutes,
+    notifier: WebhookNotifier,
+    interval: Duration,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut routed = Routed::new();
+        loop {
+            ticker.tick().await;
+
+            match generate_alerts_with_tiers(&storage, 60, &tiers, Utc::now()).await {
+                Ok(response) => {
+                    route_alerts(&response.alerts, &routes, &notifier, &mut routed).await
+                }
+                Err(e) => warn!(error = %e, "Failed to check alerts for routing"),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{AlertReason, WarmthStatus};
+
+    #[test]
+    fn test_severity() {
+        let tiers = SeverityTiers::default();
+        let now = Utc::now();
+        let ago = |minutes| Some(now - chrono::Duration::minutes(minutes));
+
+        assert_eq!(tiers.severity(None, now), IssueSeverity::Warning);
+        assert_eq!(tiers.severity(ago(30), now), IssueSeverity::Warning);
+        assert_eq!(tiers.severity(ago(120), now), IssueSeverity::Critical);
+        assert_eq!(tiers.severity(ago(13 * 60), now), IssueSeverity::Emergency);
+    }
+
+    #[test]
+    fn test_parse() {
+        let tiers = SeverityTiers::parse("1:6").unwrap();
+        assert_eq!(tiers.critical_after, chrono::Duration::hours(1));
+        assert_eq!(tiers.emergency_after, chrono::Duration::hours(6));
+        assert!(SeverityTiers::parse("6:1").is_err());
+        assert!(SeverityTiers::parse("6").is_err());
+
+        let routes = SeverityRoutes::parse(
+            "critical=https://a/hook, emergency=https://b/hook,emergency=https://c/hook",
+        )
+        .unwrap();
+        assert_eq!(routes.urls(IssueSeverity::Critical), ["https://a/hook"]);
+        assert_eq!(routes.urls(IssueSeverity::Emergency).len(), 2);
+        assert!(routes.urls(IssueSeverity::Warning).is_empty());
+        assert!(SeverityRoutes::parse("info=https://a/hook").is_err());
+        assert!(SeverityRoutes::parse("https://a/hook").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_route_alerts_once_per_tier() {
+        use axum::extract::State;
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        let (sent, mut received) = tokio::sync::mpsc::unbounded_channel::<serde_json::Value>();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(sent): State<tokio::sync::mpsc::UnboundedSender<serde_json::Value>>,
+                     Json(alert): Json<serde_json::Value>| async move {
+                        sent.send(alert).unwrap();
+                    },
+                ),
+            )
+            .with_state(sent);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let routes = SeverityRoutes::parse(&format!("critical=http://{}/hook", addr)).unwrap();
+        let notifier = WebhookNotifier::new();
+        let mut routed = Routed::new();
+        let alert = |severity| Alert {
+            bucket: "zone-a".to_string(),
+            subject: AlertSubject::Bucket,
+            status: WarmthStatus::Dead,
+            last_seen_timestamp: None,
+            recent_average: 5.0,
+            reason: AlertReason::WarmthDrop,
+            message: String::new(),
+            severity,
+            distressed_since: None,
+        };
+
+        // Warnings are not routed; reaching critical is, once
+        route_alerts(
+            &[alert(IssueSeverity::Warning)],
+            &routes,
+            &notifier,
+            &mut routed,
+        )
+        .await;
+        route_alerts(
+            &[alert(IssueSeverity::Critical)],
+            &routes,
+            &notifier,
+            &mut routed,
+        )
+        .await;
+        route_alerts(
+            &[alert(IssueSeverity::Critical)],
+            &routes,
+            &notifier,
+            &mut routed,
+        )
+        .await;
+        let body = received.recv().await.unwrap();
+        assert_eq!(body["severity"], "critical");
+        assert!(received.try_recv().is_err());
+
+        // Recovery resets the alert
+        route_alerts(&[], &routes, &notifier, &mut routed).await;
+        route_alerts(
+            &[alert(IssueSeverity::Critical)],
+            &routes,
+            &notifier,
+            &mut routed,
+        )
+        .await;
+        assert_eq!(received.recv().await.unwrap()["bucket"], "zone-a");
+    }
+}
@@ -0,0 +1,238 @@
+//! Parquet export of raw life signals.
+//!
+//! Analysts exploring signal history in DuckDB or pandas should not have to
+//! query the live database. `infrared export-signals` and
+//! `GET /admin/signals.parquet` write the `life_signals` table, optionally
+//! narrowed to one bucket and a time range, as a Parquet file with columns
+//! `bucket` (string), `ts` (UTC timestamp, millisecond precision, whole
+//! seconds), `weight` (32-bit integer) and `channel` (string, null when
+//! unattributed).
+//!
+//! Signals are read [`EXPORT_BATCH_ROWS`] at a time, each batch a short read
+//! that becomes one row group, so an export never holds a long read on the
+//! database or the whole table in memory. Built only with the `parquet`
+//! feature.
+//!
+//! # Privacy
+//!
+//! An export holds exactly what the table holds: bucket, server-assigned
+//! timestamp, weight and channel. Row IDs are left out.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use parquet::basic::Compression;
+use parquet::data_type::{ByteArray, ByteArrayType, Int32Type, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+
+use crate::model::LifeSignal;
+use crate::storage::{SignalFilter, SignalStore};
+
+/// Media type of an export.
+pub const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+/// Signals read per batch, and so per row group.
+pub const EXPORT_BATCH_ROWS: u32 = 65_536;
+
+const SCHEMA: &str = "
+message life_signals {
+    REQUIRED BYTE_ARRAY bucket (STRING);
+    REQUIRED INT64 ts (TIMESTAMP(MILLIS, true));
+    REQUIRED INT32 weight;
+    OPTIONAL BYTE_ARRAY channel (STRING);
+}
+";
+
+/// Write the signals matching `filter` to `out` as Parquet, oldest row
+/// first.
+///
+/// # Returns
+///
+/// The number of signals written.
+pub async fn write_signals<W: Write + Send>(
+    storage: &dyn SignalStore,
+    filter: &SignalFilter,
+    out: W,
+) -> anyhow::Result<u64> {
+    let schema = Arc::new(parse_message_type(SCHEMA)?);
+    let properties = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+    let mut writer = SerializedFileWriter::new(out, schema, properties)?;
+
+    let mut written = 0;
+    let mut after = 0;
+    loop {
+        let page = storage
+            .get_signals_after(filter, after, EXPORT_BATCH_ROWS)
+            .await?;
+        let Some((last, _)) = page.last() else {
+            break;
+        };
+        after = *last;
+        let signals: Vec<&LifeSignal> = page.iter().map(|(_, signal)| signal).collect();
+        write_row_group(&mut writer, &signals)?;
+        written += signals.len() as u64;
+        if signals.len() < EXPORT_BATCH_ROWS as usize {
+            break;
+        }
+    }
+
+    writer.close()?;
+    Ok(written)
+}
+
+fn write_row_group<W: Write + Send>(
+    writer: &mut SerializedFileWriter<W>,
+    signals: &[&LifeSignal],
+) -> anyhow::Result<()> {
+    let buckets: Vec<ByteArray> = signals
+        .iter()
+        .map(|s| ByteArray::from(s.bucket.as_str()))
+        .collect();
+    let timestamps: Vec<i64> = signals
+        .iter()
+        .map(|s| s.timestamp.timestamp_millis())
+        .collect();
+    let weights: Vec<i32> = signals.iter().map(|s| s.weight).collect();
+    // Only present values are written; definition levels mark the nulls
+    let channels: Vec<ByteArray> = signals
+        .iter()
+        .filter_map(|s| s.channel.as_deref().map(ByteArray::from))
+        .collect();
+    let channel_levels: Vec<i16> = signals
+        .iter()
+        .map(|s| i16::from(s.channel.is_some()))
+        .collect();
+
+    let mut row_group = writer.next_row_group()?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        match index {
+            0 => {
+                column
+                    .typed::<ByteArrayType>()
+                    .write_batch(&buckets, None, None)?;
+            }
+            1 => {
+                column
+                    .typed::<Int64Type>()
+                    .write_batch(&timestamps, None, None)?;
+            }
+            2 => {
+                column
+                    .typed::<Int32Type>()
+                    .write_batch(&weights, None, None)?;
+            }
+            _ => {
+                column.typed::<ByteArrayType>().write_batch(
+                    &channels,
+                    Some(&channel_levels),
+                    None,
+                )?;
+            }
+        }
+        column.close()?;
+        index += 1;
+    }
+    row_group.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::RowAccessor;
+
+    type Row = (String, i64, i32, Option<String>);
+
+    fn read_back(name: &str, bytes: Vec<u8>) -> Vec<Row> {
+        let path = std::env::temp_dir().join(format!(
+            "infrared-export-{}-{}.parquet",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&path, bytes).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+        let rows = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| {
+                let row = row.unwrap();
+                (
+                    row.get_string(0).unwrap().clone(),
+                    row.get_timestamp_millis(1).unwrap(),
+                    row.get_int(2).unwrap(),
+                    row.get_string(3).ok().cloned(),
+                )
+            })
+            .collect();
+        std::fs::remove_file(&path).unwrap();
+        rows
+    }
+
+    #[tokio::test]
+    async fn test_write_signals() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let start: DateTime<Utc> = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        for (bucket, hours, weight, channel) in [
+            ("zone-a", 0, 1, None),
+            ("zone-b", 1, 2, Some("sms")),
+            ("zone-a", 2, 3, None),
+        ] {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: start + Duration::hours(hours),
+                    weight,
+                    channel: channel.map(String::from),
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut out = Vec::new();
+        let written = write_signals(&storage, &SignalFilter::default(), &mut out)
+            .await
+            .unwrap();
+        assert_eq!(written, 3);
+        let rows = read_back("all", out);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[1],
+            (
+                "zone-b".to_string(),
+                (start + Duration::hours(1)).timestamp_millis(),
+                2,
+                Some("sms".to_string())
+            )
+        );
+
+        let filter = SignalFilter {
+            bucket: Some("zone-a".to_string()),
+            from: Some(start + Duration::hours(1)),
+            to: None,
+        };
+        let mut out = Vec::new();
+        assert_eq!(write_signals(&storage, &filter, &mut out).await.unwrap(), 1);
+        let rows = read_back("filtered", out);
+        assert_eq!(rows[0].2, 3);
+        assert_eq!(rows[0].3, None);
+
+        // An empty export is still a valid file
+        let filter = SignalFilter {
+            bucket: Some("zone-z".to_string()),
+            ..SignalFilter::default()
+        };
+        let mut out = Vec::new();
+        assert_eq!(write_signals(&storage, &filter, &mut out).await.unwrap(), 0);
+        assert!(read_back("empty", out).is_empty());
+    }
+}
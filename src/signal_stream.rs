@@ -0,0 +1,199 @@
+//! Streaming NDJSON and CSV export of raw life signals.
+//!
+//! `GET /signals/export` writes the `life_signals` table, optionally
+//! narrowed to one bucket and a time range, as newline-delimited JSON or
+//! CSV. Rows are read [`STREAM_BATCH_ROWS`] at a time and sent as each batch
+//! is read, so an export of any size holds only one batch in memory and no
+//! long read on the database. Unlike the Parquet export this needs no
+//! optional feature and suits `curl | jq`, spreadsheets and plain archives.
+//!
+//! # Privacy
+//!
+//! Each row is exactly what the table holds: bucket, server-assigned
+//! timestamp, weight and channel. Row IDs are left out.
+
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use chrono::SecondsFormat;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+
+use crate::model::LifeSignal;
+use crate::storage::{SignalFilter, SignalStore};
+
+/// Signals read per batch, and so per chunk of the response.
+pub const STREAM_BATCH_ROWS: u32 = 1000;
+
+/// Output format of a streamed export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One JSON object per line.
+    #[default]
+    Ndjson,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+impl ExportFormat {
+    /// Media type of the export.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    /// File extension for downloads.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    /// Text preceding the first row.
+    fn header(&self) -> &'static str {
+        match self {
+            ExportFormat::Ndjson => "",
+            ExportFormat::Csv => "bucket,timestamp,weight,channel\n",
+        }
+    }
+
+    /// Append one signal, with its line ending.
+    fn write_row(&self, out: &mut String, signal: &LifeSignal) -> anyhow::Result<()> {
+        match self {
+            ExportFormat::Ndjson => {
+                out.push_str(&serde_json::to_string(signal)?);
+            }
+            ExportFormat::Csv => {
+                write!(
+                    out,
+                    "{},{},{},{}",
+                    csv_field(&signal.bucket),
+                    signal.timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+                    signal.weight,
+                    csv_field(signal.channel.as_deref().unwrap_or_default())
+                )?;
+            }
+        }
+        out.push('\n');
+        Ok(())
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+/// Stream the signals matching `filter` in `format`, oldest row first.
+///
+/// Each item is one batch of rows (the CSV header comes first). An error
+/// ends the stream, so a response cut short is visibly truncated.
+pub fn stream_signals(
+    storage: Arc<dyn SignalStore>,
+    filter: SignalFilter,
+    format: ExportFormat,
+) -> impl Stream<Item = anyhow::Result<Bytes>> + Send {
+    let header = Some(format.header())
+        .filter(|h| !h.is_empty())
+        .map(|h| Ok(Bytes::from_static(h.as_bytes())));
+
+    let rows = stream::try_unfold(Some(0), move |after| {
+        let storage = storage.clone();
+        let filter = filter.clone();
+        async move {
+            let Some(after) = after else {
+                return Ok(None);
+            };
+            let page = storage
+                .get_signals_after(&filter, after, STREAM_BATCH_ROWS)
+                .await?;
+            let Some((last, _)) = page.last() else {
+                return Ok(None);
+            };
+            let next = (page.len() == STREAM_BATCH_ROWS as usize).then_some(*last);
+
+            let mut chunk = String::new();
+            for (_, signal) in &page {
+                format.write_row(&mut chunk, signal)?;
+            }
+            Ok(Some((Bytes::from(chunk), next)))
+        }
+    });
+
+    stream::iter(header).chain(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+    use chrono::{TimeZone, Utc};
+    use futures_util::TryStreamExt;
+
+    async fn collect(storage: &Storage, filter: SignalFilter, format: ExportFormat) -> String {
+        let chunks: Vec<Bytes> = stream_signals(Arc::new(storage.clone()), filter, format)
+            .try_collect()
+            .await
+            .unwrap();
+        String::from_utf8(chunks.concat()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_stream_signals() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let signals: Vec<LifeSignal> = (0..STREAM_BATCH_ROWS + 5)
+            .map(|i| LifeSignal {
+                bucket: if i == 0 {
+                    "zone \"a\", north"
+                } else {
+                    "zone-b"
+                }
+                .to_string(),
+                timestamp: start + chrono::Duration::seconds(i64::from(i)),
+                weight: 1,
+                channel: (i == 0).then(|| "sms".to_string()),
+            })
+            .collect();
+        storage.insert_life_signals(&signals).await.unwrap();
+
+        // Spans more than one batch
+        let ndjson = collect(&storage, SignalFilter::default(), ExportFormat::Ndjson).await;
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), signals.len());
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["bucket"], "zone \"a\", north");
+        assert_eq!(first["timestamp"], "2024-03-01T00:00:00Z");
+        assert_eq!(first["channel"], "sms");
+
+        let csv = collect(&storage, SignalFilter::default(), ExportFormat::Csv).await;
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("bucket,timestamp,weight,channel"));
+        assert_eq!(
+            lines.next(),
+            Some("\"zone \"\"a\"\", north\",2024-03-01T00:00:00Z,1,sms")
+        );
+        assert_eq!(lines.next(), Some("zone-b,2024-03-01T00:00:01Z,1,"));
+        assert_eq!(lines.count(), signals.len() - 2);
+
+        // Nothing matches: just the header
+        let filter = SignalFilter {
+            bucket: Some("zone-z".to_string()),
+            ..SignalFilter::default()
+        };
+        assert_eq!(
+            collect(&storage, filter.clone(), ExportFormat::Csv).await,
+            "bucket,timestamp,weight,channel\n"
+        );
+        assert_eq!(collect(&storage, filter, ExportFormat::Ndjson).await, "");
+    }
+}
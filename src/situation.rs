@@ -0,0 +1,310 @@
+//! Country situation reports.
+//!
+//! `GET /situation/:country` gathers what Infrared knows about one country
+//! into a single response meant to be rendered as a one-page brief: the
+//! country's dashboard issues, a composite risk score across sources, the
+//! warmth of the buckets that report on it, their recent status
+//! changepoints, and the latest ReliefWeb situation reports.
+//!
+//! Buckets are tied to a country with `PUT /buckets/:bucket/country`.
+//!
+//! # Privacy
+//!
+//! A report combines country-level issues with bucket-level warmth and
+//! statuses; nothing finer than a bucket is included.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::aggregation::compute_warmth;
+use crate::calibration::severity_fallback;
+use crate::dashboard::{Dashboard, Issue, IssueLink, IssueSource, SourceError, SourceHealth};
+use crate::model::{WarmthResponse, WarmthStatus};
+use crate::scheduler::WarmthSnapshot;
+use crate::storage::SignalStore;
+
+/// Window size of the bucket warmth in a report, in minutes.
+pub const SITUATION_WINDOW_MINUTES: u32 = 10;
+
+/// How far back changepoints are listed, in hours.
+pub const CHANGEPOINT_HOURS: i64 = 72;
+
+/// Most ReliefWeb reports listed.
+pub const MAX_REPORTS: usize = 5;
+
+/// Request body for `PUT /buckets/:bucket/country`.
+#[derive(Debug, Deserialize)]
+pub struct CountryRequest {
+    /// ISO 3166-1 alpha-2 or alpha-3 code (e.g., "SD" or "SDN").
+    pub country: String,
+}
+
+/// Check and normalize an ISO 3166-1 alpha-2 or alpha-3 code.
+pub fn parse_country_code(code: &str) -> anyhow::Result<String> {
+    let code = code.trim();
+    anyhow::ensure!(
+        matches!(code.len(), 2 | 3) && code.chars().all(|c| c.is_ascii_alphabetic()),
+        "'{}' is not an ISO 3166-1 alpha-2 or alpha-3 country code",
+        code
+    );
+    Ok(code.to_uppercase())
+}
+
+/// A change in a bucket's scheduled status.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Changepoint {
+    /// The bucket whose status changed.
+    pub bucket: String,
+
+    /// The snapshot at which the new status was first seen.
+    pub at: DateTime<Utc>,
+
+    /// Status before the change.
+    pub previous_status: WarmthStatus,
+
+    /// Status after the change.
+    pub status: WarmthStatus,
+}
+
+/// Everything known about one country.
+#[derive(Debug, Serialize)]
+pub struct SituationReport {
+    /// The country queried, uppercased.
+    pub country: String,
+
+    /// When the report was assembled.
+    pub generated_at: DateTime<Utc>,
+
+    /// Composite risk on the common 0-100 severity scale (see
+    /// [`composite_risk`]).
+    pub composite_risk: f64,
+
+    /// The country's dashboard issues, most severe first.
+    pub issues: Vec<Issue>,
+
+    /// Current warmth of every bucket reporting on the country.
+    pub buckets: Vec<WarmthResponse>,
+
+    /// Status changes of those buckets over the last [`CHANGEPOINT_HOURS`],
+    /// newest first.
+    pub changepoints: Vec<Changepoint>,
+
+    /// Latest ReliefWeb situation reports, newest first.
+    pub reports: Vec<IssueLink>,
+
+    /// Dashboard sources that could not be fetched.
+    pub errors: Vec<SourceError>,
+
+    /// How each dashboard source fared, including skipped records.
+    pub health: Vec<SourceHealth>,
+}
+
+/// Assemble the situation report for `country`.
+pub async fn build_situation(
+    storage: &dyn SignalStore,
+    dashboard: &Dashboard,
+    country: &str,
+    now: DateTime<Utc>,
+) -> anyhow::Result<SituationReport> {
+    let codes = dashboard.country_codes(country);
+    let response = dashboard.get_all_issues().await?;
+    let issues: Vec<Issue> = response
+        .issues
+        .into_iter()
+        .filter(|issue| codes.iter().any(|code| issue.matches_country(code)))
+        .collect();
+
+    let mut buckets = Vec::new();
+    let mut changes = Vec::new();
+    let since = now - chrono::Duration::hours(CHANGEPOINT_HOURS);
+    for bucket in storage.get_buckets_in_country(&codes).await? {
+        buckets.push(compute_warmth(storage, &bucket, SITUATION_WINDOW_MINUTES, now).await?);
+        changes.extend(changepoints(
+            &storage.get_warmth_snapshots(&bucket, since, now).await?,
+        ));
+    }
+    changes.sort_by(|a, b| b.at.cmp(&a.at).then_with(|| a.bucket.cmp(&b.bucket)));
+
+    Ok(SituationReport {
+        country: country.to_uppercase(),
+        generated_at: now,
+        composite_risk: composite_risk(&issues),
+        reports: latest_reports(&issues, MAX_REPORTS),
+        issues,
+        buckets,
+        changepoints: changes,
+        errors: response.errors,
+        health: response.health,
+    })
+}
+
+/// Combine the country's ongoing issues into one 0-100 score.
+///
+/// Each source contributes its most severe ongoing issue (calibrated
+/// severity, or the level's fallback), and the contributions are combined
+/// as independent evidence: `100 * (1 - Π(1 - s / 100))`. One source alone
+/// yields its own score; agreeing sources push the score up; it never
+/// exceeds 100.
+pub fn composite_risk(issues: &[Issue]) -> f64 {
+    let mut worst: Vec<(IssueSource, f64)> = Vec::new();
+    for issue in issues.iter().filter(|issue| issue.is_ongoing) {
+        let score = issue
+            .normalized_severity
+            .unwrap_or_else(|| severity_fallback(issue.severity))
+            .clamp(0.0, 100.0);
+        match worst.iter_mut().find(|(source, _)| *source == issue.source) {
+            Some((_, best)) => *best = best.max(score),
+            None => worst.push((issue.source, score)),
+        }
+    }
+
+    let calm: f64 = worst.iter().map(|(_, score)| 1.0 - score / 100.0).product();
+    ((1.0 - calm) * 1000.0).round() / 10.0
+}
+
+/// The newest ReliefWeb situation reports linked from `issues`.
+pub fn latest_reports(issues: &[Issue], limit: usize) -> Vec<IssueLink> {
+    let mut reports: Vec<IssueLink> = issues
+        .iter()
+        .filter(|issue| issue.source == IssueSource::ReliefWeb)
+        .flat_map(|issue| issue.links.iter().cloned())
+        .collect();
+    reports.sort_by_key(|report| std::cmp::Reverse(report.published));
+    let mut seen = std::collections::HashSet::new();
+    reports.retain(|report| seen.insert(report.url.clone()));
+    reports.truncate(limit);
+    reports
+}
+
+/// Status changes within a bucket's snapshots, which must be oldest first.
+///
+/// Only snapshots of the same window size are compared.
+pub fn changepoints(snapshots: &[WarmthSnapshot]) -> Vec<Changepoint> {
+    let mut changes = Vec::new();
+    let mut last: Vec<(u32, WarmthStatus)> = Vec::new();
+    for snapshot in snapshots {
+        match last
+            .iter_mut()
+            .find(|(window, _)| *window == snapshot.window_minutes)
+        {
+            Some((_, status)) => {
+                if *status != snapshot.status {
+                    changes.push(Changepoint {
+                        bucket: snapshot.bucket.clone(),
+                        at: snapshot.taken_at,
+                        previous_status: *status,
+                        status: snapshot.status,
+                    });
+                    *status = snapshot.status;
+                }
+            }
+            None => last.push((snapshot.window_minutes, snapshot.status)),
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::IssueCategory;
+    use crate::model::IssueSeverity;
+
+    fn issue(source: IssueSource, severity: IssueSeverity, normalized: Option<f64>) -> Issue {
+        let mut issue = Issue::new(
+            source,
+            IssueCategory::Disaster,
+            severity,
+            "Sudan",
+            "SDN",
+            "Flooding",
+            "River levels rising",
+            Utc::now(),
+        );
+        issue.normalized_severity = normalized;
+        issue
+    }
+
+    #[test]
+    fn test_composite_risk() {
+        assert_eq!(composite_risk(&[]), 0.0);
+
+        // A single source is its own score; only its worst issue counts
+        let acled = [
+            issue(IssueSource::Acled, IssueSeverity::Critical, Some(60.0)),
+            issue(IssueSource::Acled, IssueSeverity::Warning, Some(20.0)),
+        ];
+        assert_eq!(composite_risk(&acled), 60.0);
+
+        // Agreement raises it: 1 - 0.4 * 0.5 = 0.8
+        let mut both = acled.to_vec();
+        both.push(issue(IssueSource::Ioda, IssueSeverity::Warning, Some(50.0)));
+        assert_eq!(composite_risk(&both), 80.0);
+
+        // Uncalibrated issues use the level fallback; ended issues are ignored
+        let ended =
+            issue(IssueSource::ReliefWeb, IssueSeverity::Emergency, None).with_end(Utc::now());
+        both.push(ended);
+        assert_eq!(composite_risk(&both), 80.0);
+        let fallback = [issue(
+            IssueSource::ReliefWeb,
+            IssueSeverity::Emergency,
+            None,
+        )];
+        assert_eq!(composite_risk(&fallback), 90.0);
+    }
+
+    #[test]
+    fn test_latest_reports() {
+        let at = |day| chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 3, day, 0, 0, 0).single();
+        let issues = [
+            issue(IssueSource::ReliefWeb, IssueSeverity::Critical, None)
+                .with_link("Sitrep 1", "https://reliefweb.int/r/1", at(1))
+                .with_link("Sitrep 3", "https://reliefweb.int/r/3", at(3)),
+            issue(IssueSource::ReliefWeb, IssueSeverity::Warning, None)
+                .with_link("Sitrep 3", "https://reliefweb.int/r/3", at(3))
+                .with_link("Sitrep 2", "https://reliefweb.int/r/2", at(2)),
+            issue(IssueSource::Acled, IssueSeverity::Warning, None).with_link(
+                "Not a report",
+                "https://acleddata.com/x",
+                at(4),
+            ),
+        ];
+
+        let reports = latest_reports(&issues, 2);
+        let titles: Vec<_> = reports.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, ["Sitrep 3", "Sitrep 2"]);
+    }
+
+    #[test]
+    fn test_changepoints() {
+        let now = Utc::now();
+        let snapshot = |minutes, status| WarmthSnapshot {
+            bucket: "khartoum-1".to_string(),
+            window_minutes: 10,
+            taken_at: now + chrono::Duration::minutes(minutes),
+            status,
+            current_window_total: 0,
+            recent_average: 0.0,
+        };
+        let changes = changepoints(&[
+            snapshot(0, WarmthStatus::Alive),
+            snapshot(10, WarmthStatus::Alive),
+            snapshot(20, WarmthStatus::Collapsing),
+            snapshot(30, WarmthStatus::Dead),
+            snapshot(40, WarmthStatus::Dead),
+        ]);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].previous_status, WarmthStatus::Alive);
+        assert_eq!(changes[0].status, WarmthStatus::Collapsing);
+        assert_eq!(changes[1].at, now + chrono::Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_parse_country_code() {
+        assert_eq!(parse_country_code(" sd ").unwrap(), "SD");
+        assert_eq!(parse_country_code("SDN").unwrap(), "SDN");
+        assert!(parse_country_code("Sudan").is_err());
+        assert!(parse_country_code("S1").is_err());
+    }
+}
@@ -1,4 +1,8 @@
-//! SQLite storage layer for Infrared.
+//! Storage layer for Infrared.
+//!
+//! [`SignalStore`] is what the API and aggregation depend on; [`Storage`] is
+//! its SQLite implementation. Another backend only needs to implement the
+//! trait to be passed to [`crate::api::AppState::new`].
 //!
 //! # Privacy Guarantees
 //!
@@ -10,313 +14,4800 @@
 //!
 //! **No identifying information is ever stored in the database.**
 //! If the entire database were leaked, no individual could be identified.
+//!
+//! Built with the `sqlcipher` feature, the database file is also encrypted
+//! with the key in `INFRARED_DATABASE_KEY`, so a seized disk does not even
+//! reveal bucket names and activity patterns.
 
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
 use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
 use sqlx::Row;
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteSynchronous,
+};
+
+use crate::aggregation::DEFAULT_DETECTOR_SIGMAS;
+use crate::alert_history::{AlertRecord, REFIRE_GAP_MINUTES};
+use crate::attribution;
+use crate::audit::{AuditEntry, actions};
+use crate::bucket_meta::BucketMeta;
+use crate::compaction::{COMPACTION_SECONDS, CompactionReport};
+use crate::dashboard::{DashboardResponse, DashboardSummary, Issue};
+use crate::deadman::SwitchStatus;
+use crate::heartbeat::HeartbeatStatus;
+use crate::model::{
+    Alert, AlertReason, AlertSubject, Baseline, BucketEntry, BucketGroup, Detector, IssueSeverity,
+    LifeSignal, WarmthStatus, WarmthThresholds,
+};
+use crate::partition;
+use crate::scheduler::WarmthSnapshot;
+use crate::tension::TensionPoint;
+use crate::timeline::{Annotation, AnnotationKind};
+use crate::write_buffer::WriteBuffer;
+
+/// Schema version written by this build: the version of the last migration
+/// in `migrations/`, also stored in `PRAGMA user_version`.
+pub const SCHEMA_VERSION: i64 = 19;
+
+/// Environment variable holding the SQLCipher key of the database.
+pub const DATABASE_KEY_ENV: &str = "INFRARED_DATABASE_KEY";
+
+/// Schema migrations, embedded from `migrations/` and applied in version
+/// order. Applied versions are recorded in the `_sqlx_migrations` table.
+///
+/// Migration `14` is the baseline: the schema as the pre-migration
+/// initialization left it. New schema changes go in new files numbered
+/// from `15`, and [`SCHEMA_VERSION`] follows the last one.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// `buckets` columns added by `ALTER TABLE` before migrations (schema
+/// versions 4 to 11), in the order they were added.
+const LEGACY_BUCKET_COLUMNS: [(&str, &str); 9] = [
+    ("switch_seconds", "INTEGER"),
+    ("switch_armed_at", "INTEGER"),
+    ("switch_notified_deadline", "INTEGER"),
+    ("switch_notified_steps", "INTEGER NOT NULL DEFAULT 0"),
+    ("timezone", "TEXT"),
+    ("baseline_windows", "INTEGER"),
+    ("baseline_span_minutes", "INTEGER"),
+    ("archived_at", "INTEGER"),
+    ("country", "TEXT"),
+];
+
+/// How [`Storage::open`] treats a database written by an older build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Migration {
+    /// Refuse to open it until an operator asks for the upgrade.
+    Refuse,
+    /// Upgrade its schema to [`SCHEMA_VERSION`].
+    Apply,
+}
+
+/// Indexes every fully initialized database is expected to have.
+pub const EXPECTED_INDEXES: &[&str] = &[
+    "idx_life_signals_bucket_ts",
+    "idx_dashboard_snapshots_taken_at",
+    "idx_audit_log_ts",
+    "idx_alert_history_bucket_last_seen",
+    "idx_alert_history_last_seen",
+    "idx_idempotency_keys_received_at",
+];
+
+/// `tension_history` scope of the global tension score.
+const GLOBAL_TENSION_SCOPE: &str = "*";
+
+/// Most signals written per `INSERT` statement, keeping a statement's bind
+/// parameters under SQLite's 999-variable default.
+const SIGNALS_PER_STATEMENT: usize = 240;
+
+/// Most buckets named per `IN (...)` list, for the same reason.
+const BUCKETS_PER_STATEMENT: usize = 900;
+
+/// Which signals to read back in bulk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignalFilter {
+    /// Only signals in this bucket.
+    pub bucket: Option<String>,
+
+    /// Only signals at or after this time.
+    pub from: Option<DateTime<Utc>>,
+
+    /// Only signals before this time.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Size and contents of the store, for `GET /admin/storage/stats`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StorageStats {
+    /// Rows per table. `life_signals` counts every partition, and compacted
+    /// hourly sums as one row each.
+    pub tables: BTreeMap<String, u64>,
+
+    /// Daily life signal partitions.
+    pub partitions: u64,
+
+    /// Life signal rows per bucket.
+    pub buckets: Vec<BucketStorageStats>,
+
+    /// Timestamp of the oldest stored signal.
+    pub oldest_signal: Option<DateTime<Utc>>,
+
+    /// Timestamp of the newest stored signal.
+    pub newest_signal: Option<DateTime<Utc>>,
+
+    /// Size of the database, from its page count.
+    pub database_bytes: u64,
+
+    /// Bytes of free pages in the database, reclaimable by vacuuming.
+    pub free_bytes: u64,
+
+    /// Size of the write-ahead log, when there is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wal_bytes: Option<u64>,
+}
+
+/// Rows rewritten by [`Storage::rename_bucket`] and [`Storage::merge_buckets`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BucketRewrite {
+    /// Rows moved to the new name, per table.
+    pub moved: BTreeMap<String, u64>,
+
+    /// Rows deleted because the new name already had a row with the same
+    /// key (e.g., a warmth snapshot of the same moment), per table.
+    pub dropped: BTreeMap<String, u64>,
+
+    /// Incident notes and acknowledgments moved to the new incident IDs.
+    pub annotations: u64,
+}
+
+/// A batch already stored under an idempotency key (see
+/// [`SignalStore::insert_life_signals_once`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StoredBatch {
+    /// When the batch was first stored.
+    pub received_at: DateTime<Utc>,
+
+    /// Signals it held.
+    pub signals: u64,
+
+    /// Distinct buckets they were in.
+    pub buckets: u64,
+}
+
+/// Stored life signals of one bucket.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BucketStorageStats {
+    /// Bucket identifier.
+    pub bucket: String,
+
+    /// Life signal rows.
+    pub rows: u64,
+
+    /// Timestamp of the bucket's oldest signal.
+    pub oldest: DateTime<Utc>,
+
+    /// Timestamp of the bucket's newest signal.
+    pub newest: DateTime<Utc>,
+}
+
+/// A database open on an analysis connection (see
+/// [`Storage::open_with_archives`]).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttachedDatabase {
+    /// Schema name: `main` for the live database, `archive1` onwards for
+    /// archives in the order given.
+    pub schema: String,
+
+    /// Path of the database file.
+    pub file: String,
+}
+
+/// Signals of one bucket on one UTC day, in one database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailySignals {
+    /// Days since the Unix epoch.
+    pub day: i64,
+    pub bucket: String,
+    /// Signal rows (compacted hourly sums count once).
+    pub signals: u64,
+    /// Total weight.
+    pub weight: i64,
+}
+
+/// Scheduled warmth snapshots of one bucket on one UTC day, in one
+/// database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailySnapshots {
+    /// Days since the Unix epoch.
+    pub day: i64,
+    pub bucket: String,
+    /// Snapshots taken.
+    pub snapshots: u64,
+    /// Snapshots with status `dead`.
+    pub dead: u64,
+}
+
+/// SQLite journal mode (see <https://www.sqlite.org/pragma.html#pragma_journal_mode>).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JournalMode {
+    /// Write-ahead log: readers never block on, or block, the writer.
+    #[default]
+    Wal,
+    /// Rollback journal deleted after each transaction (SQLite's default).
+    Delete,
+    /// Rollback journal truncated after each transaction.
+    Truncate,
+}
+
+impl JournalMode {
+    /// Parse `wal`, `delete` or `truncate`.
+    pub fn parse(name: &str) -> anyhow::Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "wal" => Ok(JournalMode::Wal),
+            "delete" => Ok(JournalMode::Delete),
+            "truncate" => Ok(JournalMode::Truncate),
+            other => anyhow::bail!(
+                "unknown journal mode '{}' (expected wal, delete or truncate)",
+                other
+            ),
+        }
+    }
+}
+
+/// How often SQLite syncs to disk (see <https://www.sqlite.org/pragma.html#pragma_synchronous>).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Synchronous {
+    /// Never sync; fastest, but a power loss can corrupt the database.
+    Off,
+    /// Sync at critical moments. In WAL mode a power loss can only roll back
+    /// the last transactions, never corrupt the database.
+    #[default]
+    Normal,
+    /// Sync on every commit.
+    Full,
+    /// As `Full`, also syncing the directory after a journal is deleted.
+    Extra,
+}
+
+impl Synchronous {
+    /// Parse `off`, `normal`, `full` or `extra`.
+    pub fn parse(name: &str) -> anyhow::Result<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Synchronous::Off),
+            "normal" => Ok(Synchronous::Normal),
+            "full" => Ok(Synchronous::Full),
+            "extra" => Ok(Synchronous::Extra),
+            other => anyhow::bail!(
+                "unknown synchronous level '{}' (expected off, normal, full or extra)",
+                other
+            ),
+        }
+    }
+}
+
+/// Settings applied to every pooled SQLite connection.
+///
+/// The defaults suit a server under heavy ingest: WAL, so dashboards and
+/// alert checks read while signals are written; `synchronous=NORMAL`, which
+/// WAL makes safe; and a busy timeout, so concurrent writers wait for the
+/// lock instead of failing with `SQLITE_BUSY`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionOptions {
+    /// Journal mode.
+    pub journal_mode: JournalMode,
+
+    /// Disk sync level.
+    pub synchronous: Synchronous,
+
+    /// How long a connection waits for a lock before giving up.
+    pub busy_timeout: Duration,
+
+    /// Page cache per connection, in KiB (SQLite's default if `None`).
+    pub cache_size_kib: Option<u32>,
+
+    /// Connections in the write pool. SQLite only ever runs one write at
+    /// a time, so more mostly adds lock contention.
+    pub write_connections: u32,
 
-use crate::model::LifeSignal;
+    /// Connections in the read-only pool serving queries.
+    pub read_connections: u32,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self {
+            journal_mode: JournalMode::default(),
+            synchronous: Synchronous::default(),
+            busy_timeout: Duration::from_secs(5),
+            cache_size_kib: None,
+            write_connections: 1,
+            read_connections: 4,
+        }
+    }
+}
+
+impl ConnectionOptions {
+    fn apply(&self, options: SqliteConnectOptions) -> SqliteConnectOptions {
+        let options = options
+            .journal_mode(match self.journal_mode {
+                JournalMode::Wal => SqliteJournalMode::Wal,
+                JournalMode::Delete => SqliteJournalMode::Delete,
+                JournalMode::Truncate => SqliteJournalMode::Truncate,
+            })
+            .synchronous(match self.synchronous {
+                Synchronous::Off => SqliteSynchronous::Off,
+                Synchronous::Normal => SqliteSynchronous::Normal,
+                Synchronous::Full => SqliteSynchronous::Full,
+                Synchronous::Extra => SqliteSynchronous::Extra,
+            })
+            .busy_timeout(self.busy_timeout);
+        match self.cache_size_kib {
+            // Negative sizes are in KiB rather than pages
+            Some(kib) => options.pragma("cache_size", format!("-{}", kib)),
+            None => options,
+        }
+    }
+}
+
+/// The SQLCipher key in [`DATABASE_KEY_ENV`], quoted as a `PRAGMA key`
+/// value, or `None` if unset or empty.
+///
+/// Fails if a key is set but this build lacks the `sqlcipher` feature:
+/// silently writing an unencrypted database where an encrypted one was
+/// asked for is worse than not starting.
+pub fn database_key() -> anyhow::Result<Option<String>> {
+    let key = std::env::var(DATABASE_KEY_ENV).unwrap_or_default();
+    if key.is_empty() {
+        return Ok(None);
+    }
+    anyhow::ensure!(
+        cfg!(feature = "sqlcipher"),
+        "{} is set, but this build lacks the sqlcipher feature; rebuild with \
+         `--features sqlcipher` to encrypt the database",
+        DATABASE_KEY_ENV
+    );
+    Ok(Some(format!("'{}'", key.replace('\'', "''"))))
+}
 
 /// Database connection pool wrapper.
+///
+/// Clones share the pool and the write buffer, if any.
 #[derive(Clone)]
 pub struct Storage {
+    /// Connections for writes, and for reads that decide what to write.
     pool: SqlitePool,
+    /// Read-only connections for queries, so long scans and exports never
+    /// hold up a write. The same pool as `pool` for in-memory databases.
+    read_pool: SqlitePool,
+    buffer: Option<Arc<WriteBuffer>>,
+    default_thresholds: WarmthThresholds,
+    detector: (Detector, f64),
 }
 
 impl Storage {
-    /// Create a new storage instance and initialize the schema.
+    /// Create a new storage instance and initialize the schema, with the
+    /// default [`ConnectionOptions`].
+    ///
+    /// Databases written by an older build are refused; see [`Storage::open`].
     ///
     /// # Arguments
     ///
     /// * `database_url` - SQLite connection string (e.g., "sqlite:infrared.db" or "sqlite::memory:")
     pub async fn new(database_url: &str) -> anyhow::Result<Self> {
-        let pool = SqlitePoolOptions::new()
-            .max_connections(5)
-            .connect(database_url)
-            .await?;
+        Self::open(database_url, Migration::Refuse).await
+    }
 
-        let storage = Self { pool };
-        storage.initialize_schema().await?;
+    /// Open a database, creating the schema if it is empty.
+    ///
+    /// A database whose schema is newer than this build's is always refused:
+    /// an older binary cannot know what the newer schema means, and writing
+    /// to it risks silent corruption. One whose schema is older is upgraded
+    /// only with [`Migration::Apply`], so upgrades happen when an operator
+    /// has had the chance to take a backup.
+    pub async fn open(database_url: &str, migration: Migration) -> anyhow::Result<Self> {
+        Self::open_with(database_url, migration, ConnectionOptions::default()).await
+    }
+
+    /// [`Storage::open`] with the given connection options.
+    pub async fn open_with(
+        database_url: &str,
+        migration: Migration,
+        options: ConnectionOptions,
+    ) -> anyhow::Result<Self> {
+        let storage = Self::connect_with(database_url, options).await?;
+
+        let found = storage.schema_version().await?;
+        if found > SCHEMA_VERSION {
+            let written_by = match storage.schema_written_by().await? {
+                Some(version) => format!(" by infrared {}", version),
+                None => String::new(),
+            };
+            anyhow::bail!(
+                "database schema version {} was written{}, but infrared {} only understands \
+                 version {} or older; upgrade infrared to open this database",
+                found,
+                written_by,
+                env!("CARGO_PKG_VERSION"),
+                SCHEMA_VERSION
+            );
+        }
+        if found < SCHEMA_VERSION && migration == Migration::Refuse && !storage.is_empty().await? {
+            anyhow::bail!(
+                "database schema version {} is older than this build's version {}; back up \
+                 the database, then run `infrared serve --migrate` to upgrade it",
+                found,
+                SCHEMA_VERSION
+            );
+        }
 
+        storage.migrate().await?;
         Ok(storage)
     }
 
-    /// Create the database schema if it doesn't exist.
+    /// Connect to an existing database without touching its schema.
     ///
-    /// # Privacy Note
+    /// Used by diagnostics such as `infrared check`, which must inspect the
+    /// database exactly as found.
+    pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
+        Self::connect_with(database_url, ConnectionOptions::default()).await
+    }
+
+    /// [`Storage::connect`] with the given connection options.
     ///
-    /// The schema contains ONLY aggregate-safe columns:
-    /// - No user IDs, IPs, device IDs, or any identifying fields
-    /// - Only bucket (category), timestamp, and weight
-    async fn initialize_schema(&self) -> anyhow::Result<()> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS life_signals (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                bucket TEXT NOT NULL,
-                ts INTEGER NOT NULL,
-                weight INTEGER NOT NULL
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Writes go through a pool of `options.write_connections` and queries
+    /// through a separate read-only pool of `options.read_connections`, so
+    /// a long read never keeps a write waiting for a connection. An
+    /// in-memory database lives in its connections, so it gets one pool.
+    ///
+    /// With [`DATABASE_KEY_ENV`] set, the database is opened with that
+    /// SQLCipher key (see [`database_key`]).
+    pub async fn connect_with(
+        database_url: &str,
+        options: ConnectionOptions,
+    ) -> anyhow::Result<Self> {
+        let mut connect_options = options.apply(SqliteConnectOptions::from_str(database_url)?);
+        if let Some(key) = database_key()? {
+            connect_options = connect_options.pragma("key", key);
+        }
+        let in_memory = database_url.contains(":memory:") || database_url.contains("mode=memory");
+        if in_memory {
+            let pool = SqlitePoolOptions::new()
+                .max_connections(options.write_connections + options.read_connections)
+                .connect_with(connect_options)
+                .await?;
+            return Ok(Self {
+                read_pool: pool.clone(),
+                pool,
+                buffer: None,
+                default_thresholds: WarmthThresholds::default(),
+                detector: (Detector::Ratio, DEFAULT_DETECTOR_SIGMAS),
+            });
+        }
 
-        // Index for efficient time-range queries by bucket
-        sqlx::query(
-            r#"
-            CREATE INDEX IF NOT EXISTS idx_life_signals_bucket_ts
-            ON life_signals(bucket, ts)
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+        // The write pool creates the file if asked to, so it comes first
+        let pool = SqlitePoolOptions::new()
+            .max_connections(options.write_connections.max(1))
+            .connect_with(connect_options.clone())
+            .await?;
+        let read_pool = SqlitePoolOptions::new()
+            .max_connections(options.read_connections.max(1))
+            .connect_with(connect_options.read_only(true))
+            .await?;
 
-        Ok(())
+        Ok(Self {
+            pool,
+            read_pool,
+            buffer: None,
+            default_thresholds: WarmthThresholds::default(),
+            detector: (Detector::Ratio, DEFAULT_DETECTOR_SIGMAS),
+        })
     }
 
-    /// Insert a new life signal into storage.
-    ///
-    /// # Privacy Note
+    /// Close every connection, in both pools.
     ///
-    /// This function intentionally does NOT log or store:
-    /// - Client IP addresses
-    /// - Request headers
-    /// - Any identifying information
+    /// Readers close first: only the last connection to a WAL database
+    /// checkpoints it on close, and a read-only one cannot.
+    pub async fn close(&self) {
+        self.read_pool.close().await;
+        self.pool.close().await;
+    }
+
+    /// Buffer inserted signals and write them in batches of `max_rows`, or
+    /// every `max_delay` once [`crate::write_buffer::spawn_flush_task`] runs.
     ///
-    /// Only the bucket, server-assigned timestamp, and weight are recorded.
-    pub async fn insert_life_signal(&self, signal: &LifeSignal) -> anyhow::Result<()> {
-        let ts = signal.timestamp.timestamp();
+    /// Buffered signals are not visible to queries until flushed; see
+    /// [`crate::write_buffer`].
+    pub fn with_write_buffer(mut self, max_rows: usize, max_delay: Duration) -> Self {
+        self.buffer = Some(Arc::new(WriteBuffer::new(max_rows, max_delay)));
+        self
+    }
 
-        sqlx::query(
-            r#"
-            INSERT INTO life_signals (bucket, ts, weight)
-            VALUES (?, ?, ?)
-            "#,
-        )
-        .bind(&signal.bucket)
-        .bind(ts)
-        .bind(signal.weight)
-        .execute(&self.pool)
-        .await?;
+    /// Classify buckets without threshold overrides by `thresholds` instead
+    /// of the built-in 0.8 and 0.2.
+    pub fn with_default_thresholds(mut self, thresholds: WarmthThresholds) -> Self {
+        self.default_thresholds = thresholds;
+        self
+    }
 
-        Ok(())
+    /// Derive warmth statuses with `detector`, flagging buckets `sigmas`
+    /// deviations below their baseline, unless a query asks for another.
+    pub fn with_detector(mut self, detector: Detector, sigmas: f64) -> Self {
+        self.detector = (detector, sigmas);
+        self
     }
 
-    /// Query the total weight of signals in a bucket within a time window.
-    ///
-    /// # Arguments
+    /// The write buffer, if inserts are buffered.
+    pub fn write_buffer(&self) -> Option<&WriteBuffer> {
+        self.buffer.as_deref()
+    }
+
+    /// Write every buffered signal now.
     ///
-    /// * `bucket` - The bucket to query
-    /// * `window_minutes` - Size of the time window in minutes
-    /// * `now` - The reference timestamp (typically current time)
+    /// On failure the signals stay buffered for the next flush.
     ///
     /// # Returns
     ///
-    /// Sum of weights for signals in the window, or 0 if none found.
-    pub async fn query_bucket_window(
+    /// The number of signals written.
+    pub async fn flush_writes(&self) -> anyhow::Result<usize> {
+        let Some(buffer) = &self.buffer else {
+            return Ok(0);
+        };
+        let mut pending = buffer.pending.lock().await;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        self.insert_life_signals(&pending).await?;
+        let written = pending.len();
+        pending.clear();
+        Ok(written)
+    }
+
+    /// Write the signals matching `filter` to `out` as Parquet (see
+    /// [`crate::signal_export`]), returning how many were written.
+    ///
+    /// Only reads, in short batches, so it is safe against a live database.
+    #[cfg(feature = "parquet")]
+    pub async fn export_signals<W: std::io::Write + Send>(
         &self,
-        bucket: &str,
-        window_minutes: u32,
-        now: DateTime<Utc>,
-    ) -> anyhow::Result<i64> {
-        let window_seconds = i64::from(window_minutes) * 60;
-        let now_ts = now.timestamp();
-        let start_ts = now_ts - window_seconds;
+        filter: &SignalFilter,
+        out: W,
+    ) -> anyhow::Result<u64> {
+        crate::signal_export::write_signals(self, filter, out).await
+    }
 
-        let row = sqlx::query(
-            r#"
-            SELECT COALESCE(SUM(weight), 0) as total
-            FROM life_signals
-            WHERE bucket = ? AND ts >= ? AND ts <= ?
-            "#,
+    /// Bring the schema up to [`SCHEMA_VERSION`] by running pending
+    /// migrations from [`MIGRATOR`].
+    ///
+    /// # Privacy Note
+    ///
+    /// The schema contains ONLY aggregate-safe columns:
+    /// - No user IDs, IPs, device IDs, or any identifying fields
+    /// - Only bucket (category), timestamp, and weight
+    async fn migrate(&self) -> anyhow::Result<()> {
+        // Must be set before the first table is created to take effect;
+        // lets the maintenance worker reclaim free pages incrementally.
+        sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+            .execute(&self.pool)
+            .await?;
+
+        if !self.has_table("_sqlx_migrations").await? {
+            self.adopt_legacy_schema().await?;
+        }
+        MIGRATOR.run(&self.pool).await?;
+
+        // Mirrored in `PRAGMA user_version`, which builds from before
+        // migrations check to refuse databases they do not understand
+        if self.schema_version().await? < SCHEMA_VERSION {
+            // PRAGMA arguments cannot be bound as parameters
+            sqlx::query(&format!("PRAGMA user_version = {}", SCHEMA_VERSION))
+                .execute(&self.pool)
+                .await?;
+            sqlx::query(
+                r#"
+                INSERT INTO schema_meta (key, value) VALUES ('written_by', ?)
+                ON CONFLICT(key) DO UPDATE SET value = excluded.value
+                "#,
+            )
+            .bind(env!("CARGO_PKG_VERSION"))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Add the `buckets` columns that databases created before migrations
+    /// gained one schema version at a time, so the baseline migration
+    /// finds the layout it would have created.
+    async fn adopt_legacy_schema(&self) -> anyhow::Result<()> {
+        if !self.has_table("buckets").await? {
+            return Ok(());
+        }
+        for (column, decl) in LEGACY_BUCKET_COLUMNS {
+            self.add_column_if_missing("buckets", column, decl).await?;
+        }
+        Ok(())
+    }
+
+    /// Whether the database has a table named `name`.
+    async fn has_table(&self, name: &str) -> anyhow::Result<bool> {
+        Ok(sqlx::query_scalar(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?",
         )
-        .bind(bucket)
-        .bind(start_ts)
-        .bind(now_ts)
+        .bind(name)
         .fetch_one(&self.pool)
-        .await?;
-
-        Ok(row.get("total"))
+        .await?)
     }
 
-    /// Compute the average weight per window over recent history.
-    ///
-    /// # Arguments
-    ///
-    /// * `bucket` - The bucket to query
-    /// * `window_minutes` - Size of each time window in minutes
-    /// * `num_windows` - Number of historical windows to average
-    /// * `now` - The reference timestamp
-    ///
-    /// # Returns
-    ///
-    /// Average weight per window. Returns 0.0 if no data exists.
-    pub async fn compute_recent_average(
+    /// Delete up to `limit` rows of signal table `table` timestamped before
+    /// `cutoff_ts`, oldest first.
+    async fn delete_rows_before(
         &self,
-        bucket: &str,
-        window_minutes: u32,
-        num_windows: u32,
-        now: DateTime<Utc>,
-    ) -> anyhow::Result<f64> {
-        let window_seconds = i64::from(window_minutes) * 60;
-        let total_seconds = window_seconds * i64::from(num_windows);
-        let now_ts = now.timestamp();
-        // Start from one window ago (exclude current window)
-        let end_ts = now_ts - window_seconds;
-        let start_ts = end_ts - total_seconds;
+        table: &str,
+        cutoff_ts: i64,
+        limit: u64,
+    ) -> anyhow::Result<u64> {
+        if limit == 0 {
+            return Ok(0);
+        }
 
-        // Use SQL to bin signals into windows and compute average
-        let row = sqlx::query(
+        let result = sqlx::query(&format!(
             r#"
-            SELECT COALESCE(AVG(window_total), 0.0) as avg_total
-            FROM (
-                SELECT (ts / ?) as window_id, SUM(weight) as window_total
-                FROM life_signals
-                WHERE bucket = ? AND ts >= ? AND ts < ?
-                GROUP BY window_id
+            DELETE FROM "{}"
+            WHERE rowid IN (
+                SELECT rowid FROM "{}"
+                WHERE ts < ?
+                ORDER BY ts
+                LIMIT ?
             )
             "#,
-        )
-        .bind(window_seconds)
-        .bind(bucket)
-        .bind(start_ts)
-        .bind(end_ts)
-        .fetch_one(&self.pool)
+            table, table
+        ))
+        .bind(cutoff_ts)
+        .bind(limit as i64)
+        .execute(&self.pool)
         .await?;
 
-        Ok(row.get("avg_total"))
+        Ok(result.rows_affected())
     }
 
-    /// Get the timestamp of the most recent signal for a bucket.
-    ///
-    /// # Returns
-    ///
-    /// The timestamp of the last signal, or None if no signals exist.
-    pub async fn get_last_seen(&self, bucket: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
-        let row = sqlx::query(
-            r#"
-            SELECT MAX(ts) as last_ts
-            FROM life_signals
-            WHERE bucket = ?
-            "#,
+    /// Days with a signal partition, oldest first.
+    async fn partition_days(&self) -> anyhow::Result<Vec<i64>> {
+        let names: Vec<String> = sqlx::query_scalar(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name LIKE ?",
         )
-        .bind(bucket)
-        .fetch_one(&self.pool)
+        .bind(format!("{}%", partition::PARTITION_PREFIX))
+        .fetch_all(&self.pool)
         .await?;
 
-        let last_ts: Option<i64> = row.get("last_ts");
-        Ok(last_ts.map(|ts| Utc.timestamp_opt(ts, 0).unwrap()))
+        let mut days: Vec<i64> = names
+            .iter()
+            .filter_map(|name| partition::parse_table_name(name))
+            .collect();
+        days.sort_unstable();
+        Ok(days)
     }
 
-    /// Get all distinct buckets that have signals within a time range.
-    ///
-    /// # Arguments
-    ///
-    /// * `minutes` - Lookback window in minutes
-    /// * `now` - The reference timestamp
-    pub async fn get_active_buckets(
+    /// Tables that may hold signals timestamped between `from` and `to`
+    /// (both inclusive, unbounded if `None`): the unpartitioned table, then
+    /// the partitions of those days, oldest first.
+    async fn signal_tables(
         &self,
-        minutes: u32,
-        now: DateTime<Utc>,
+        from: Option<i64>,
+        to: Option<i64>,
     ) -> anyhow::Result<Vec<String>> {
-        let window_seconds = i64::from(minutes) * 60;
-        let now_ts = now.timestamp();
-        let start_ts = now_ts - window_seconds;
+        let first = from.map(partition::day_of);
+        let last = to.map(partition::day_of);
+        let mut tables = vec![partition::SIGNALS_TABLE.to_string()];
+        tables.extend(
+            self.partition_days()
+                .await?
+                .into_iter()
+                .filter(|day| first.is_none_or(|first| *day >= first))
+                .filter(|day| last.is_none_or(|last| *day <= last))
+                .map(partition::table_name),
+        );
+        Ok(tables)
+    }
 
-        let rows = sqlx::query(
-            r#"
-            SELECT DISTINCT bucket
-            FROM life_signals
+    /// A `FROM` source of the signals between `from` and `to`, as
+    /// [`Storage::signal_tables`].
+    async fn signal_source(&self, from: Option<i64>, to: Option<i64>) -> anyhow::Result<String> {
+        Ok(partition::union_source(
+            &self.signal_tables(from, to).await?,
+        ))
+    }
+
+    /// Add a column to an existing table unless it is already present.
+    async fn add_column_if_missing(
+        &self,
+        table: &str,
+        column: &str,
+        decl: &str,
+    ) -> anyhow::Result<()> {
+        let exists: bool = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = ?",
+            table
+        ))
+        .bind(column)
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !exists {
+            sqlx::query(&format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                table, column, decl
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Run `PRAGMA optimize` so SQLite can refresh stale planner statistics.
+    pub async fn optimize(&self) -> anyhow::Result<()> {
+        sqlx::query("PRAGMA optimize").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Reclaim free pages via `PRAGMA incremental_vacuum`.
+    ///
+    /// Has no effect on databases created before incremental auto-vacuum was enabled.
+    pub async fn incremental_vacuum(&self) -> anyhow::Result<()> {
+        sqlx::query("PRAGMA incremental_vacuum")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Gather fresh table and index statistics with `ANALYZE`.
+    pub async fn analyze(&self) -> anyhow::Result<()> {
+        sqlx::query("ANALYZE").execute(&self.pool).await?;
+        Ok(())
+    }
+    /// Read the schema version recorded in the database (`0` if never set).
+    pub async fn schema_version(&self) -> anyhow::Result<i64> {
+        let row = sqlx::query("PRAGMA user_version")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(row.get(0))
+    }
+
+    /// Version of the build that last wrote the schema, if recorded.
+    pub async fn schema_written_by(&self) -> anyhow::Result<Option<String>> {
+        if !self.has_table("schema_meta").await? {
+            return Ok(None);
+        }
+
+        Ok(
+            sqlx::query_scalar("SELECT value FROM schema_meta WHERE key = 'written_by'")
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    /// Every row of `table` as a JSON object keyed by column name, in
+    /// insertion order. `life_signals` includes every partition.
+    ///
+    /// `table` must be a trusted name; it is interpolated into the query.
+    pub async fn export_table(&self, table: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info(?) ORDER BY cid")
+                .bind(table)
+                .fetch_all(&self.read_pool)
+                .await?;
+        anyhow::ensure!(!columns.is_empty(), "no such table '{}'", table);
+
+        let fields = columns
+            .iter()
+            .map(|c| format!("'{}', \"{}\"", c, c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (source, order) = if table == partition::SIGNALS_TABLE {
+            (self.signal_source(None, None).await?, "id")
+        } else {
+            (format!("\"{}\"", table), "rowid")
+        };
+        let rows: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT json_object({}) FROM {} ORDER BY {}",
+            fields, source, order
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(row)?))
+            .collect()
+    }
+
+    /// Insert rows produced by [`Storage::export_table`], table by table, in
+    /// one transaction: either every row is inserted or none is.
+    ///
+    /// `life_signals` rows go to the partition of their day, under new row
+    /// IDs from its range.
+    ///
+    /// Table names must be trusted; they are interpolated into the queries.
+    /// Column names are checked against each table before use.
+    pub async fn import_tables(
+        &self,
+        tables: &[(&str, &[serde_json::Value])],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let mut partitions = std::collections::BTreeSet::new();
+        for (table, rows) in tables {
+            let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info(?)")
+                .bind(table)
+                .fetch_all(&mut *tx)
+                .await?;
+            let is_signals = *table == partition::SIGNALS_TABLE;
+            for row in rows.iter() {
+                let mut object = row
+                    .as_object()
+                    .ok_or_else(|| anyhow::anyhow!("{} row is not an object", table))?
+                    .clone();
+                if let Some(unknown) = object.keys().find(|k| !columns.contains(k)) {
+                    anyhow::bail!("{} has no column '{}'", table, unknown);
+                }
+
+                let target = if is_signals {
+                    let ts = object
+                        .get("ts")
+                        .and_then(serde_json::Value::as_i64)
+                        .ok_or_else(|| anyhow::anyhow!("{} row has no ts", table))?;
+                    let day = partition::day_of(ts);
+                    if partitions.insert(day) {
+                        for statement in partition::create_statements(day) {
+                            sqlx::query(&statement).execute(&mut *tx).await?;
+                        }
+                    }
+                    object.remove("id");
+                    partition::table_name(day)
+                } else {
+                    table.to_string()
+                };
+
+                let names = object
+                    .keys()
+                    .map(|k| format!("\"{}\"", k))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let placeholders = vec!["?"; object.len()].join(", ");
+                let sql = format!(
+                    "INSERT INTO \"{}\" ({}) VALUES ({})",
+                    target, names, placeholders
+                );
+                let mut query = sqlx::query(&sql);
+                for value in object.values() {
+                    query = match value {
+                        serde_json::Value::Null => query.bind(None::<String>),
+                        serde_json::Value::Bool(b) => query.bind(i64::from(*b)),
+                        serde_json::Value::Number(n) => match n.as_i64() {
+                            Some(i) => query.bind(i),
+                            None => query.bind(n.as_f64()),
+                        },
+                        serde_json::Value::String(s) => query.bind(s.clone()),
+                        other => query.bind(other.to_string()),
+                    };
+                }
+                query.execute(&mut *tx).await?;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Open `database_url` read-only for analysis, with each of `archives`
+    /// attached read-only as `archive1`, `archive2` and so on.
+    ///
+    /// The attachments only live as long as the returned storage's single
+    /// connection, and nothing can be written through it.
+    pub async fn open_with_archives(
+        database_url: &str,
+        options: ConnectionOptions,
+        archives: &[PathBuf],
+    ) -> anyhow::Result<Self> {
+        let mut connect_options = SqliteConnectOptions::from_str(database_url)?
+            .read_only(true)
+            .busy_timeout(options.busy_timeout);
+        if let Some(key) = database_key()? {
+            connect_options = connect_options.pragma("key", key);
+        }
+
+        let mut uris = Vec::with_capacity(archives.len());
+        for archive in archives {
+            anyhow::ensure!(archive.is_file(), "'{}' is not a file", archive.display());
+            let path = archive
+                .to_str()
+                .ok_or_else(|| anyhow::anyhow!("'{}' is not valid UTF-8", archive.display()))?;
+            let path = path
+                .replace('%', "%25")
+                .replace('?', "%3f")
+                .replace('#', "%23");
+            uris.push(format!("file:{}?mode=ro", path));
+        }
+        let uris = Arc::new(uris);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .after_connect(move |conn, _| {
+                let uris = uris.clone();
+                Box::pin(async move {
+                    for (index, uri) in uris.iter().enumerate() {
+                        // Schema names cannot be bound as parameters
+                        sqlx::query(&format!("ATTACH DATABASE ? AS archive{}", index + 1))
+                            .bind(uri)
+                            .execute(&mut *conn)
+                            .await?;
+                    }
+                    sqlx::query("PRAGMA query_only = ON")
+                        .execute(&mut *conn)
+                        .await?;
+                    Ok(())
+                })
+            })
+            .connect_with(connect_options)
+            .await?;
+
+        Ok(Self {
+            read_pool: pool.clone(),
+            pool,
+            buffer: None,
+            default_thresholds: WarmthThresholds::default(),
+            detector: (Detector::Ratio, DEFAULT_DETECTOR_SIGMAS),
+        })
+    }
+
+    /// The live database and every attached archive, in attachment order.
+    pub async fn attached_databases(&self) -> anyhow::Result<Vec<AttachedDatabase>> {
+        let rows = sqlx::query("SELECT name, file FROM pragma_database_list ORDER BY seq")
+            .fetch_all(&self.read_pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| AttachedDatabase {
+                schema: row.get("name"),
+                file: row.get("file"),
+            })
+            .filter(|database| database.schema != "temp")
+            .collect())
+    }
+
+    /// Signal totals per UTC day and bucket in the database `schema`,
+    /// narrowed by `filter`.
+    ///
+    /// Reads only the columns every schema version has, so archives from
+    /// older builds can be analyzed too.
+    pub async fn daily_signals(
+        &self,
+        schema: &str,
+        filter: &SignalFilter,
+    ) -> anyhow::Result<Vec<DailySignals>> {
+        let from = filter.from.map(|t| t.timestamp());
+        let to = filter.to.map(|t| t.timestamp());
+        let (first, last) = (from.map(partition::day_of), to.map(partition::day_of));
+        let names: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT name FROM \"{}\".sqlite_master WHERE type = 'table'",
+            schema
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+        let tables = names.into_iter().filter(|name| {
+            name == partition::SIGNALS_TABLE
+                || partition::parse_table_name(name).is_some_and(|day| {
+                    first.is_none_or(|first| day >= first) && last.is_none_or(|last| day <= last)
+                })
+        });
+
+        let mut totals: BTreeMap<(i64, String), (u64, i64)> = BTreeMap::new();
+        for table in tables {
+            let rows = sqlx::query(&format!(
+                r#"
+                SELECT ts / {day} AS day, bucket, COUNT(*) AS signals, SUM(weight) AS weight
+                FROM "{schema}"."{table}"
+                WHERE (?1 IS NULL OR ts >= ?1) AND (?2 IS NULL OR ts < ?2)
+                  AND (?3 IS NULL OR bucket = ?3)
+                GROUP BY day, bucket
+                "#,
+                day = partition::DAY_SECONDS,
+            ))
+            .bind(from)
+            .bind(to)
+            .bind(&filter.bucket)
+            .fetch_all(&self.read_pool)
+            .await?;
+            for row in rows {
+                let total = totals
+                    .entry((row.get("day"), row.get("bucket")))
+                    .or_default();
+                total.0 += row.get::<i64, _>("signals") as u64;
+                total.1 += row.get::<i64, _>("weight");
+            }
+        }
+
+        Ok(totals
+            .into_iter()
+            .map(|((day, bucket), (signals, weight))| DailySignals {
+                day,
+                bucket,
+                signals,
+                weight,
+            })
+            .collect())
+    }
+
+    /// Scheduled warmth snapshot counts per UTC day and bucket in the
+    /// database `schema`, narrowed by `filter`. Empty for archives from
+    /// before snapshots were recorded.
+    pub async fn daily_snapshots(
+        &self,
+        schema: &str,
+        filter: &SignalFilter,
+    ) -> anyhow::Result<Vec<DailySnapshots>> {
+        let exists: bool = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) > 0 FROM \"{}\".sqlite_master \
+             WHERE type = 'table' AND name = 'warmth_snapshots'",
+            schema
+        ))
+        .fetch_one(&self.read_pool)
+        .await?;
+        if !exists {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT taken_at / {day} AS day, bucket, COUNT(*) AS snapshots,
+                   SUM(status = 'dead') AS dead
+            FROM "{schema}".warmth_snapshots
+            WHERE (?1 IS NULL OR taken_at >= ?1) AND (?2 IS NULL OR taken_at < ?2)
+              AND (?3 IS NULL OR bucket = ?3)
+            GROUP BY day, bucket
+            ORDER BY day, bucket
+            "#,
+            day = partition::DAY_SECONDS,
+        ))
+        .bind(filter.from.map(|t| t.timestamp()))
+        .bind(filter.to.map(|t| t.timestamp()))
+        .bind(&filter.bucket)
+        .fetch_all(&self.read_pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DailySnapshots {
+                day: row.get("day"),
+                bucket: row.get("bucket"),
+                snapshots: row.get::<i64, _>("snapshots") as u64,
+                dead: row.get::<i64, _>("dead") as u64,
+            })
+            .collect())
+    }
+
+    /// Whether none of `tables` holds any rows. `life_signals` includes
+    /// every partition.
+    pub async fn tables_empty(&self, tables: &[&str]) -> anyhow::Result<bool> {
+        for table in tables {
+            let source = if *table == partition::SIGNALS_TABLE {
+                self.signal_source(None, None).await?
+            } else {
+                format!("\"{}\"", table)
+            };
+            let any: bool =
+                sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {})", source))
+                    .fetch_one(&self.pool)
+                    .await?;
+            if any {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Rename the bucket `from` to `to`, which must not hold any data yet,
+    /// as [`Storage::merge_buckets`].
+    pub async fn rename_bucket(
+        &self,
+        from: &str,
+        to: &str,
+        actor: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<BucketRewrite> {
+        anyhow::ensure!(from != to, "bucket '{}' already has that name", from);
+        self.rewrite_buckets(&[from.to_string()], to, true, actor, now)
+            .await
+    }
+
+    /// Move everything stored under each of `sources` to the bucket `into`,
+    /// in one transaction: either every table is rewritten or none is.
+    ///
+    /// Every table with a `bucket` column is rewritten (signals in every
+    /// partition, rollups, settings, groups, warmth snapshots, alert
+    /// history), as are the IDs of incident notes. Where a table's key
+    /// allows one row per bucket, rows already under `into` win, then the
+    /// sources in the order given; the losing rows are deleted. The audit
+    /// log is never rewritten; the operation is appended to it instead.
+    ///
+    /// `into` may be one of `sources`, or a new name.
+    pub async fn merge_buckets(
+        &self,
+        sources: &[String],
+        into: &str,
+        actor: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<BucketRewrite> {
+        self.rewrite_buckets(sources, into, false, actor, now).await
+    }
+
+    async fn rewrite_buckets(
+        &self,
+        sources: &[String],
+        into: &str,
+        rename: bool,
+        actor: &str,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<BucketRewrite> {
+        anyhow::ensure!(!into.is_empty(), "bucket names cannot be empty");
+        let mut moving: Vec<&str> = Vec::new();
+        for source in sources {
+            if source != into && !moving.contains(&source.as_str()) {
+                moving.push(source);
+            }
+        }
+        anyhow::ensure!(!moving.is_empty(), "nothing to move into '{}'", into);
+
+        let mut tx = self.pool.begin().await?;
+        let tables: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT m.name FROM sqlite_master m
+            WHERE m.type = 'table'
+              AND EXISTS (SELECT 1 FROM pragma_table_info(m.name) c WHERE c.name = 'bucket')
+            ORDER BY m.name
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for source in &moving {
+            anyhow::ensure!(
+                bucket_exists(&mut tx, &tables, source).await?,
+                "unknown bucket '{}'",
+                source
+            );
+        }
+        if rename {
+            anyhow::ensure!(
+                !bucket_exists(&mut tx, &tables, into).await?,
+                "bucket '{}' already exists; merge into it instead",
+                into
+            );
+        }
+
+        let mut rewrite = BucketRewrite::default();
+        for table in &tables {
+            for source in &moving {
+                let moved = sqlx::query(&format!(
+                    "UPDATE OR IGNORE \"{}\" SET bucket = ? WHERE bucket = ?",
+                    table
+                ))
+                .bind(into)
+                .bind(source)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+                let dropped = sqlx::query(&format!("DELETE FROM \"{}\" WHERE bucket = ?", table))
+                    .bind(source)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                if moved > 0 {
+                    *rewrite.moved.entry(table.clone()).or_default() += moved;
+                }
+                if dropped > 0 {
+                    *rewrite.dropped.entry(table.clone()).or_default() += dropped;
+                }
+            }
+        }
+
+        // Incident IDs are `<bucket>@<start timestamp>`
+        for source in &moving {
+            let prefix = format!("{}@", source);
+            let rest = prefix.chars().count() as i64 + 1;
+            rewrite.annotations += sqlx::query(
+                r#"
+                UPDATE incident_annotations
+                SET incident_id = ? || substr(incident_id, ?)
+                WHERE substr(incident_id, 1, ?) = ?
+                  AND CAST(substr(incident_id, ?) AS INTEGER) || '' = substr(incident_id, ?)
+                "#,
+            )
+            .bind(format!("{}@", into))
+            .bind(rest)
+            .bind(rest - 1)
+            .bind(&prefix)
+            .bind(rest)
+            .bind(rest)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        sqlx::query("INSERT INTO audit_log (ts, actor, action, details) VALUES (?, ?, ?, ?)")
+            .bind(now.timestamp())
+            .bind(actor)
+            .bind(actions::BUCKET_RENAME)
+            .bind(format!("{} -> {}", moving.join(", "), into))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(rewrite)
+    }
+
+    /// Whether the database has no tables at all.
+    async fn is_empty(&self) -> anyhow::Result<bool> {
+        let tables: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'")
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(tables == 0)
+    }
+
+    /// Run `PRAGMA integrity_check`, returning the reported problems.
+    ///
+    /// An empty list means SQLite found the database file to be consistent.
+    pub async fn integrity_check(&self) -> anyhow::Result<Vec<String>> {
+        let rows = sqlx::query("PRAGMA integrity_check")
+            .fetch_all(&self.read_pool)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| r.get::<String, _>(0))
+            .filter(|line| line != "ok")
+            .collect())
+    }
+
+    /// List the names of all indexes present in the database.
+    pub async fn index_names(&self) -> anyhow::Result<Vec<String>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT name FROM sqlite_master
+            WHERE type = 'index' AND name NOT LIKE 'sqlite_%'
+            "#,
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows.iter().map(|r| r.get("name")).collect())
+    }
+
+    /// Count rows derived from or attached to buckets that have no raw
+    /// signals left: warmth snapshots, bucket alerts in the alert history,
+    /// group memberships and bucket settings.
+    ///
+    /// Returns `(table, orphaned_rows)` for tables with orphans. Tables that
+    /// do not exist yet are skipped.
+    pub async fn orphaned_rows(&self) -> anyhow::Result<Vec<(String, i64)>> {
+        const TABLES: [(&str, &str); 4] = [
+            ("warmth_snapshots", "1 = 1"),
+            ("alert_history", "r.subject = 'bucket'"),
+            ("bucket_groups", "1 = 1"),
+            ("buckets", "1 = 1"),
+        ];
+
+        let existing: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+                .fetch_all(&self.read_pool)
+                .await?;
+
+        let source = self.signal_source(None, None).await?;
+        let mut orphaned = Vec::new();
+        for (table, filter) in TABLES {
+            if !existing.iter().any(|name| name == table) {
+                continue;
+            }
+            let orphans: i64 = sqlx::query_scalar(&format!(
+                r#"
+                SELECT COUNT(*)
+                FROM {} r
+                WHERE {}
+                  AND NOT EXISTS (SELECT 1 FROM {} s WHERE s.bucket = r.bucket)
+                "#,
+                table, filter, source
+            ))
+            .fetch_one(&self.read_pool)
+            .await?;
+
+            if orphans > 0 {
+                orphaned.push((table.to_string(), orphans));
+            }
+        }
+
+        Ok(orphaned)
+    }
+}
+
+/// Signal, bucket-metadata and history operations, independent of the backend.
+///
+/// [`Storage`] is the SQLite implementation. Everything the API, alerting
+/// and aggregation need goes through this trait, so another store (in-memory,
+/// Postgres, remote) can be plugged into [`crate::api::AppState`].
+///
+/// SQLite-specific operations (schema upgrades, maintenance, archives and
+/// integrity checks) stay on [`Storage`] itself.
+#[async_trait]
+pub trait SignalStore: Send + Sync {
+    /// Insert a new life signal into storage.
+    ///
+    /// # Privacy Note
+    ///
+    /// This function intentionally does NOT log or store:
+    /// - Client IP addresses
+    /// - Request headers
+    /// - Any identifying information
+    ///
+    /// Only the bucket, server-assigned timestamp, and weight are recorded.
+    async fn insert_life_signal(&self, signal: &LifeSignal) -> anyhow::Result<()>;
+
+    /// Insert signals in one transaction, bypassing any write buffer.
+    ///
+    /// Either every signal is written or none is.
+    async fn insert_life_signals(&self, signals: &[LifeSignal]) -> anyhow::Result<()>;
+
+    /// Insert `signals` and record them under the idempotency key `key`, in
+    /// one transaction, unless a batch was already recorded under `key`
+    /// since `since`; that batch is returned instead and nothing is
+    /// inserted. Keys recorded before `since` are forgotten.
+    async fn insert_life_signals_once(
+        &self,
+        key: &str,
+        signals: &[LifeSignal],
+        now: DateTime<Utc>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Option<StoredBatch>>;
+
+    /// Query the total weight of signals in a bucket within a time window.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket` - The bucket to query
+    /// * `window_minutes` - Size of the time window in minutes
+    /// * `now` - The reference timestamp (typically current time)
+    ///
+    /// # Returns
+    ///
+    /// Sum of weights for signals in the window, or 0 if none found.
+    async fn query_bucket_window(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<i64>;
+
+    /// Compute the average weight per window over recent history.
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket` - The bucket to query
+    /// * `window_minutes` - Size of each time window in minutes
+    /// * `num_windows` - Number of historical windows to average
+    /// * `now` - The reference timestamp
+    ///
+    /// # Returns
+    ///
+    /// Average weight per window. Returns 0.0 if no data exists.
+    async fn compute_recent_average(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<f64>;
+
+    /// Get the window totals [`Self::compute_recent_average`] averages,
+    /// oldest first.
+    ///
+    /// Windows without signals are left out, as they are from the average.
+    async fn baseline_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i64>>;
+
+    /// Get the totals of the `count` most recent windows ending at `now`, oldest first.
+    ///
+    /// Windows are `(end - window, end]`, so no signal is counted twice.
+    async fn window_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        count: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i64>>;
+
+    /// Get [`Self::query_bucket_window`] and [`Self::compute_recent_average`]
+    /// per signal channel, as `(channel, current window total, recent
+    /// average)` ordered by channel, unattributed signals (`None`) first.
+    ///
+    /// Channels without signals in the current window or the baseline are
+    /// left out.
+    async fn channel_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<(Option<String>, i64, f64)>>;
+
+    /// Get [`Self::query_bucket_window`] and [`Self::baseline_totals`] for
+    /// several buckets at once, as `(current window total, baseline window
+    /// totals)` keyed by bucket, from one grouped query.
+    ///
+    /// Buckets without signals in the current window or the baseline are
+    /// left out.
+    async fn bucket_totals(
+        &self,
+        buckets: &[String],
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<BTreeMap<String, (i64, Vec<i64>)>>;
+
+    /// Get the timestamp of the most recent signal for a bucket.
+    ///
+    /// # Returns
+    ///
+    /// The timestamp of the last signal, or None if no signals exist.
+    async fn get_last_seen(&self, bucket: &str) -> anyhow::Result<Option<DateTime<Utc>>>;
+
+    /// Get all distinct buckets that have signals within a time range.
+    ///
+    /// # Arguments
+    ///
+    /// * `minutes` - Lookback window in minutes
+    /// * `now` - The reference timestamp
+    async fn get_active_buckets(
+        &self,
+        minutes: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<String>>;
+
+    /// Get all buckets that have ever had signals and are not archived (for
+    /// alert checking).
+    async fn get_all_known_buckets(&self) -> anyhow::Result<Vec<String>>;
+
+    /// Delete up to `limit` signals older than `cutoff`, oldest first.
+    ///
+    /// A backend may delete a whole expired partition at once, even past
+    /// `limit`; callers repeat until fewer than `limit` are deleted.
+    ///
+    /// # Returns
+    ///
+    /// The number of signals deleted.
+    async fn delete_signals_before(&self, cutoff: DateTime<Utc>, limit: u32)
+    -> anyhow::Result<u64>;
+
+    /// Replace the raw signals of every whole day before `cutoff` with
+    /// hourly sums per bucket and channel (see [`crate::compaction`]).
+    async fn compact_signals_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> anyhow::Result<CompactionReport>;
+
+    /// Write a consistent copy of the whole store to a new file at `path`,
+    /// without pausing reads or writes (see [`crate::backup`]).
+    ///
+    /// Fails if `path` already exists.
+    async fn backup_to(&self, path: &Path) -> anyhow::Result<()>;
+
+    /// Row counts, signal time span and size of the store.
+    ///
+    /// Scans every signal, so this is for occasional monitoring rather than
+    /// request paths.
+    async fn stats(&self) -> anyhow::Result<StorageStats>;
+
+    /// Up to `limit` signals matching `filter` whose row ID is above
+    /// `after`, in row ID order, each with its row ID.
+    ///
+    /// Reading in pages keyed by the last row ID seen lets bulk exports walk
+    /// the table in short reads.
+    async fn get_signals_after(
+        &self,
+        filter: &SignalFilter,
+        after: i64,
+        limit: u32,
+    ) -> anyhow::Result<Vec<(i64, LifeSignal)>>;
+
+    /// Persist scheduled warmth evaluations, replacing any taken at the same
+    /// moment for the same bucket and window size.
+    async fn insert_warmth_snapshots(&self, snapshots: &[WarmthSnapshot]) -> anyhow::Result<()>;
+
+    /// Get a bucket's warmth snapshots taken between `from` and `to`
+    /// (inclusive), oldest first.
+    async fn get_warmth_snapshots(
+        &self,
+        bucket: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<WarmthSnapshot>>;
+
+    /// When the bucket's latest unbroken run of collapsing or dead scheduled
+    /// snapshots began, if its latest snapshot is one of them.
+    async fn distressed_since(&self, bucket: &str) -> anyhow::Result<Option<DateTime<Utc>>>;
+
+    /// Persist a dashboard response as a point-in-time snapshot.
+    ///
+    /// The snapshot is keyed by the response's own generation timestamp.
+    async fn insert_dashboard_snapshot(&self, response: &DashboardResponse) -> anyhow::Result<()>;
+
+    /// Delete dashboard snapshots taken before `cutoff`, except the latest
+    /// snapshot, which is always kept.
+    ///
+    /// # Returns
+    ///
+    /// The number of snapshots deleted.
+    async fn delete_dashboard_snapshots_before(&self, cutoff: DateTime<Utc>)
+    -> anyhow::Result<u64>;
+
+    /// Record a summary's global and per-country tension scores at `at`.
+    async fn insert_tension(
+        &self,
+        at: DateTime<Utc>,
+        summary: &DashboardSummary,
+    ) -> anyhow::Result<()>;
+
+    /// Get recorded tension scores since `since`, oldest first: a country's
+    /// (matched case-insensitively), or the global score when `country` is
+    /// None.
+    ///
+    /// A country is absent from a recording when it had no weighted issues,
+    /// which is reported as a score of 0.
+    async fn get_tension_history(
+        &self,
+        country: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<TensionPoint>>;
+
+    /// Get the most recent dashboard snapshot taken at or before `at`.
+    ///
+    /// # Returns
+    ///
+    /// The snapshot as it was persisted, or None if no snapshot predates `at`.
+    async fn get_dashboard_snapshot_at(
+        &self,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Option<DashboardResponse>>;
+
+    /// Insert or replace an externally reported issue, keyed by its ID.
+    ///
+    /// # Returns
+    ///
+    /// Whether the ID was new.
+    async fn upsert_external_issue(
+        &self,
+        issue: &Issue,
+        received_at: DateTime<Utc>,
+    ) -> anyhow::Result<bool>;
+
+    /// Get externally reported issues received (or last replaced) at or
+    /// after `since`, most recently received first.
+    async fn get_external_issues(&self, since: DateTime<Utc>) -> anyhow::Result<Vec<Issue>>;
+
+    /// Register (or replace) a bucket's expected heartbeat interval.
+    async fn set_heartbeat(
+        &self,
+        bucket: &str,
+        interval_seconds: i64,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()>;
+
+    /// Remove a bucket's heartbeat registration.
+    ///
+    /// Returns false if the bucket had no heartbeat registered.
+    async fn clear_heartbeat(&self, bucket: &str) -> anyhow::Result<bool>;
+
+    /// Get every registered heartbeat of a non-archived bucket along with its
+    /// last signal time.
+    async fn get_heartbeats(&self) -> anyhow::Result<Vec<HeartbeatStatus>>;
+
+    /// Arm (or re-arm) a bucket's dead-man's switch with the given countdown.
+    ///
+    /// Re-arming restarts the countdown and the escalation sequence.
+    async fn arm_switch(
+        &self,
+        bucket: &str,
+        countdown_seconds: i64,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()>;
+
+    /// Disarm a bucket's dead-man's switch.
+    ///
+    /// Returns false if the bucket had no armed switch.
+    async fn disarm_switch(&self, bucket: &str) -> anyhow::Result<bool>;
+
+    /// Get every armed switch of a non-archived bucket along with its last
+    /// signal time.
+    async fn get_switches(&self) -> anyhow::Result<Vec<SwitchStatus>>;
+
+    /// Record how many escalation steps have fired for the given deadline.
+    async fn set_switch_notified(
+        &self,
+        bucket: &str,
+        deadline: DateTime<Utc>,
+        steps: usize,
+    ) -> anyhow::Result<()>;
+
+    /// Set (or replace) a bucket's IANA timezone.
+    async fn set_timezone(&self, bucket: &str, timezone: &str) -> anyhow::Result<()>;
+
+    /// Remove a bucket's timezone, returning it to UTC.
+    ///
+    /// Returns false if the bucket had no timezone set.
+    async fn clear_timezone(&self, bucket: &str) -> anyhow::Result<bool>;
+
+    /// Get a bucket's IANA timezone, if one is set.
+    async fn get_timezone(&self, bucket: &str) -> anyhow::Result<Option<String>>;
+
+    /// Set (or replace) the country a bucket reports on.
+    async fn set_country(&self, bucket: &str, country: &str) -> anyhow::Result<()>;
+
+    /// Remove a bucket's country.
+    ///
+    /// Returns false if the bucket had no country set.
+    async fn clear_country(&self, bucket: &str) -> anyhow::Result<bool>;
+
+    /// Get the country a bucket reports on, if one is set.
+    async fn get_country(&self, bucket: &str) -> anyhow::Result<Option<String>>;
+
+    /// Non-archived buckets reporting on any of the given country codes,
+    /// sorted by name.
+    async fn get_buckets_in_country(&self, codes: &[String]) -> anyhow::Result<Vec<String>>;
+
+    /// Every non-archived bucket with a country, sorted by name, keyed by
+    /// its uppercased country code.
+    async fn get_countries(&self) -> anyhow::Result<BTreeMap<String, Vec<String>>>;
+
+    /// Set (or replace) a bucket's warmth baseline.
+    async fn set_baseline(&self, bucket: &str, baseline: Baseline) -> anyhow::Result<()>;
+
+    /// Remove a bucket's warmth baseline, returning it to the default.
+    ///
+    /// Returns false if the bucket had no baseline set.
+    async fn clear_baseline(&self, bucket: &str) -> anyhow::Result<bool>;
+
+    /// Get a bucket's warmth baseline, if one is set.
+    async fn get_baseline(&self, bucket: &str) -> anyhow::Result<Option<Baseline>>;
+
+    /// Get the warmth baselines set on any of `buckets`, keyed by bucket.
+    async fn get_baselines(&self, buckets: &[String])
+    -> anyhow::Result<BTreeMap<String, Baseline>>;
+
+    /// Set (or replace) a bucket's metadata. Unset fields are cleared.
+    async fn set_bucket_meta(&self, bucket: &str, meta: &BucketMeta) -> anyhow::Result<()>;
+
+    /// Remove a bucket's metadata.
+    ///
+    /// Returns false if the bucket had no metadata set.
+    async fn clear_bucket_meta(&self, bucket: &str) -> anyhow::Result<bool>;
+
+    /// Get a bucket's metadata, if any is set.
+    async fn get_bucket_meta(&self, bucket: &str) -> anyhow::Result<Option<BucketMeta>>;
+
+    /// Thresholds of buckets without overrides.
+    fn default_thresholds(&self) -> WarmthThresholds;
+
+    /// Detector deriving warmth statuses, and the deviations below the
+    /// baseline at which it flags a bucket (see [`Detector`]).
+    fn detector(&self) -> (Detector, f64);
+
+    /// Get the warmth threshold overrides set on any of `buckets`, keyed by
+    /// bucket.
+    async fn get_thresholds(
+        &self,
+        buckets: &[String],
+    ) -> anyhow::Result<BTreeMap<String, WarmthThresholds>>;
+
+    /// Archive a bucket. Its signals and settings are kept, but it no longer
+    /// raises alerts, is monitored, or is listed.
+    ///
+    /// Archiving an archived bucket keeps the original archival time.
+    async fn archive_bucket(&self, bucket: &str, now: DateTime<Utc>) -> anyhow::Result<()>;
+
+    /// Return an archived bucket to service.
+    ///
+    /// Returns false if the bucket was not archived.
+    async fn unarchive_bucket(&self, bucket: &str) -> anyhow::Result<bool>;
+
+    /// List every bucket that has signals or settings, with its last signal
+    /// time and archival time, sorted by name.
+    async fn get_buckets(&self) -> anyhow::Result<Vec<BucketEntry>>;
+
+    /// Create or replace a bucket group with the given members.
+    async fn set_group(&self, name: &str, buckets: &[String]) -> anyhow::Result<()>;
+
+    /// Delete a bucket group.
+    ///
+    /// Returns false if no such group existed.
+    async fn delete_group(&self, name: &str) -> anyhow::Result<bool>;
+
+    /// Get a single bucket group by name.
+    async fn get_group(&self, name: &str) -> anyhow::Result<Option<BucketGroup>>;
+
+    /// Get all bucket groups, sorted by name.
+    async fn get_groups(&self) -> anyhow::Result<Vec<BucketGroup>>;
+
+    /// Append an entry to the audit log.
+    async fn insert_audit_entry(
+        &self,
+        timestamp: DateTime<Utc>,
+        actor: &str,
+        action: &str,
+        details: Option<&str>,
+    ) -> anyhow::Result<()>;
+
+    /// Get audit log entries, newest first, optionally filtered by action.
+    async fn get_audit_entries(
+        &self,
+        action: Option<&str>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<AuditEntry>>;
+
+    /// Record an acknowledgment of or a note on an incident.
+    async fn insert_annotation(&self, annotation: &Annotation) -> anyhow::Result<()>;
+
+    /// Get an incident's acknowledgments and notes, oldest first.
+    async fn get_annotations(&self, incident_id: &str) -> anyhow::Result<Vec<Annotation>>;
+
+    /// Record `alerts` as generated at `now`, extending the entry of each
+    /// one last seen within [`REFIRE_GAP_MINUTES`] and opening a new entry
+    /// for the rest (see [`crate::alert_history`]).
+    async fn record_alerts(&self, alerts: &[Alert], now: DateTime<Utc>) -> anyhow::Result<()>;
+
+    /// Get up to `limit` alert history entries seen between `from`
+    /// (inclusive) and `until` (exclusive), optionally for one bucket, most
+    /// recently seen first.
+    async fn get_alert_history(
+        &self,
+        bucket: Option<&str>,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<AlertRecord>>;
+}
+
+#[async_trait]
+impl SignalStore for Storage {
+    async fn insert_life_signal(&self, signal: &LifeSignal) -> anyhow::Result<()> {
+        if let Some(buffer) = &self.buffer {
+            let mut pending = buffer.pending.lock().await;
+            pending.push(signal.clone());
+            if pending.len() >= buffer.max_rows() {
+                if let Err(e) = self.insert_life_signals(&pending).await {
+                    // Reject this signal so its client retries; keep the rest
+                    pending.pop();
+                    return Err(e);
+                }
+                pending.clear();
+            }
+            return Ok(());
+        }
+
+        self.insert_life_signals(std::slice::from_ref(signal)).await
+    }
+
+    async fn insert_life_signals(&self, signals: &[LifeSignal]) -> anyhow::Result<()> {
+        // Take the write lock up front: creating an existing partition only
+        // reads, and a read transaction cannot always be upgraded
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        insert_signals(&mut tx, signals).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn insert_life_signals_once(
+        &self,
+        key: &str,
+        signals: &[LifeSignal],
+        now: DateTime<Utc>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Option<StoredBatch>> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        sqlx::query("DELETE FROM idempotency_keys WHERE received_at < ?")
+            .bind(since.timestamp())
+            .execute(&mut *tx)
+            .await?;
+
+        let stored =
+            sqlx::query("SELECT received_at, signals, buckets FROM idempotency_keys WHERE key = ?")
+                .bind(key)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if let Some(row) = stored {
+            tx.commit().await?;
+            return Ok(Some(StoredBatch {
+                received_at: Utc
+                    .timestamp_opt(row.get("received_at"), 0)
+                    .single()
+                    .unwrap_or_default(),
+                signals: row.get::<i64, _>("signals") as u64,
+                buckets: row.get::<i64, _>("buckets") as u64,
+            }));
+        }
+
+        let buckets: std::collections::BTreeSet<&str> =
+            signals.iter().map(|s| s.bucket.as_str()).collect();
+        sqlx::query(
+            "INSERT INTO idempotency_keys (key, received_at, signals, buckets) VALUES (?, ?, ?, ?)",
+        )
+        .bind(key)
+        .bind(now.timestamp())
+        .bind(signals.len() as i64)
+        .bind(buckets.len() as i64)
+        .execute(&mut *tx)
+        .await?;
+        insert_signals(&mut tx, signals).await?;
+        tx.commit().await?;
+        Ok(None)
+    }
+
+    async fn query_bucket_window(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<i64> {
+        let window_seconds = i64::from(window_minutes) * 60;
+        let now_ts = now.timestamp();
+        let start_ts = now_ts - window_seconds;
+
+        let source = self.signal_source(Some(start_ts), Some(now_ts)).await?;
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT COALESCE(SUM(weight), 0) as total
+            FROM {}
+            WHERE bucket = ? AND ts >= ? AND ts <= ?
+            "#,
+            source
+        ))
+        .bind(bucket)
+        .bind(start_ts)
+        .bind(now_ts)
+        .fetch_one(&self.read_pool)
+        .await?;
+
+        Ok(row.get("total"))
+    }
+
+    async fn compute_recent_average(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<f64> {
+        let window_seconds = i64::from(window_minutes) * 60;
+        let total_seconds = window_seconds * i64::from(num_windows);
+        let now_ts = now.timestamp();
+        // Start from one window ago (exclude current window)
+        let end_ts = now_ts - window_seconds;
+        let start_ts = end_ts - total_seconds;
+
+        // Use SQL to bin signals into windows and compute average
+        let source = self.signal_source(Some(start_ts), Some(end_ts)).await?;
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT COALESCE(AVG(window_total), 0.0) as avg_total
+            FROM (
+                SELECT (ts / ?) as window_id, SUM(weight) as window_total
+                FROM {}
+                WHERE bucket = ? AND ts >= ? AND ts < ?
+                GROUP BY window_id
+            )
+            "#,
+            source
+        ))
+        .bind(window_seconds)
+        .bind(bucket)
+        .bind(start_ts)
+        .bind(end_ts)
+        .fetch_one(&self.read_pool)
+        .await?;
+
+        Ok(row.get("avg_total"))
+    }
+
+    async fn baseline_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i64>> {
+        let window_seconds = i64::from(window_minutes) * 60;
+        // Same windows as compute_recent_average
+        let end_ts = now.timestamp() - window_seconds;
+        let start_ts = end_ts - window_seconds * i64::from(num_windows);
+
+        let source = self.signal_source(Some(start_ts), Some(end_ts)).await?;
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT (ts / ?) as window_id, SUM(weight) as window_total
+            FROM {}
+            WHERE bucket = ? AND ts >= ? AND ts < ?
+            GROUP BY window_id
+            ORDER BY window_id
+            "#,
+            source
+        ))
+        .bind(window_seconds)
+        .bind(bucket)
+        .bind(start_ts)
+        .bind(end_ts)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows.iter().map(|row| row.get("window_total")).collect())
+    }
+
+    async fn window_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        count: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<i64>> {
+        let window_seconds = i64::from(window_minutes) * 60;
+        let now_ts = now.timestamp();
+        let start_ts = now_ts - window_seconds * i64::from(count);
+
+        let source = self.signal_source(Some(start_ts), Some(now_ts)).await?;
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT (? - ts) / ? AS windows_ago, SUM(weight) AS total
+            FROM {}
+            WHERE bucket = ? AND ts > ? AND ts <= ?
+            GROUP BY windows_ago
+            "#,
+            source
+        ))
+        .bind(now_ts)
+        .bind(window_seconds)
+        .bind(bucket)
+        .bind(start_ts)
+        .bind(now_ts)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let mut totals = vec![0; count as usize];
+        for row in rows {
+            let windows_ago: i64 = row.get("windows_ago");
+            // Oldest window first
+            if let Some(slot) = usize::try_from(windows_ago)
+                .ok()
+                .and_then(|ago| (count as usize).checked_sub(ago + 1))
+            {
+                totals[slot] += row.get::<i64, _>("total");
+            }
+        }
+        Ok(totals)
+    }
+
+    async fn channel_totals(
+        &self,
+        bucket: &str,
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<(Option<String>, i64, f64)>> {
+        let window_seconds = i64::from(window_minutes) * 60;
+        let now_ts = now.timestamp();
+        let current_start_ts = now_ts - window_seconds;
+        // Same windows as compute_recent_average
+        let end_ts = now_ts - window_seconds;
+        let start_ts = end_ts - window_seconds * i64::from(num_windows);
+
+        let source = self
+            .signal_source(Some(current_start_ts), Some(now_ts))
+            .await?;
+        let current = sqlx::query(&format!(
+            r#"
+            SELECT channel, SUM(weight) AS total
+            FROM {}
+            WHERE bucket = ? AND ts >= ? AND ts <= ?
+            GROUP BY channel
+            "#,
+            source
+        ))
+        .bind(bucket)
+        .bind(current_start_ts)
+        .bind(now_ts)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let source = self.signal_source(Some(start_ts), Some(end_ts)).await?;
+        let averages = sqlx::query(&format!(
+            r#"
+            SELECT channel, AVG(window_total) AS avg_total
+            FROM (
+                SELECT channel, (ts / ?) AS window_id, SUM(weight) AS window_total
+                FROM {}
+                WHERE bucket = ? AND ts >= ? AND ts < ?
+                GROUP BY channel, window_id
+            )
+            GROUP BY channel
+            "#,
+            source
+        ))
+        .bind(window_seconds)
+        .bind(bucket)
+        .bind(start_ts)
+        .bind(end_ts)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let mut totals: BTreeMap<Option<String>, (i64, f64)> = BTreeMap::new();
+        for row in current {
+            totals.entry(row.get("channel")).or_default().0 = row.get("total");
+        }
+        for row in averages {
+            totals.entry(row.get("channel")).or_default().1 = row.get("avg_total");
+        }
+        Ok(totals
+            .into_iter()
+            .map(|(channel, (current, average))| (channel, current, average))
+            .collect())
+    }
+
+    async fn bucket_totals(
+        &self,
+        buckets: &[String],
+        window_minutes: u32,
+        num_windows: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<BTreeMap<String, (i64, Vec<i64>)>> {
+        let window_seconds = i64::from(window_minutes) * 60;
+        let now_ts = now.timestamp();
+        // Same windows as query_bucket_window and compute_recent_average:
+        // the current window is [current_start_ts, now_ts], the baseline
+        // [start_ts, current_start_ts)
+        let current_start_ts = now_ts - window_seconds;
+        let start_ts = current_start_ts - window_seconds * i64::from(num_windows);
+
+        let source = self.signal_source(Some(start_ts), Some(now_ts)).await?;
+        let mut totals: BTreeMap<String, (i64, Vec<i64>)> = BTreeMap::new();
+        for chunk in buckets.chunks(BUCKETS_PER_STATEMENT) {
+            // Bin -1 is the current window; the rest are baseline windows
+            let mut query = sqlx::QueryBuilder::new(
+                r#"
+                SELECT bucket,
+                       CASE WHEN ts >= "#,
+            );
+            query
+                .push_bind(current_start_ts)
+                .push(" THEN -1 ELSE ts / ")
+                .push_bind(window_seconds)
+                .push(
+                    r#" END AS window_id,
+                       SUM(weight) AS window_total
+                FROM "#,
+                )
+                .push(&source)
+                .push(" WHERE ts >= ")
+                .push_bind(start_ts)
+                .push(" AND ts <= ")
+                .push_bind(now_ts)
+                .push(" AND bucket IN (");
+            let mut separated = query.separated(", ");
+            for bucket in chunk {
+                separated.push_bind(bucket);
+            }
+            query.push(
+                r#")
+                GROUP BY bucket, window_id
+                ORDER BY bucket, window_id
+                "#,
+            );
+
+            for row in query.build().fetch_all(&self.read_pool).await? {
+                let entry = totals.entry(row.get("bucket")).or_default();
+                let window_total: i64 = row.get("window_total");
+                if row.get::<i64, _>("window_id") == -1 {
+                    entry.0 = window_total;
+                } else {
+                    entry.1.push(window_total);
+                }
+            }
+        }
+        Ok(totals)
+    }
+
+    async fn get_last_seen(&self, bucket: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let tables = self.signal_tables(None, None).await?;
+        let row = sqlx::query(&format!(
+            "SELECT {} as last_ts",
+            partition::last_ts_expr(&tables, "?1")
+        ))
+        .bind(bucket)
+        .fetch_one(&self.read_pool)
+        .await?;
+
+        let last_ts: Option<i64> = row.get("last_ts");
+        Ok(last_ts.map(|ts| Utc.timestamp_opt(ts, 0).unwrap()))
+    }
+
+    async fn get_active_buckets(
+        &self,
+        minutes: u32,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<String>> {
+        let window_seconds = i64::from(minutes) * 60;
+        let now_ts = now.timestamp();
+        let start_ts = now_ts - window_seconds;
+
+        let source = self.signal_source(Some(start_ts), None).await?;
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT DISTINCT bucket
+            FROM {}
             WHERE ts >= ?
             "#,
+            source
+        ))
+        .bind(start_ts)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows.iter().map(|r| r.get("bucket")).collect())
+    }
+
+    async fn get_all_known_buckets(&self) -> anyhow::Result<Vec<String>> {
+        let source = self.signal_source(None, None).await?;
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT DISTINCT bucket FROM {}
+            WHERE bucket NOT IN (SELECT bucket FROM buckets WHERE archived_at IS NOT NULL)
+            "#,
+            source
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows.iter().map(|r| r.get("bucket")).collect())
+    }
+
+    async fn delete_signals_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<u64> {
+        let cutoff_ts = cutoff.timestamp();
+        let cutoff_day = partition::day_of(cutoff_ts);
+        let limit = u64::from(limit);
+        let days = self.partition_days().await?;
+
+        // Signals from before partitioning are the oldest
+        let mut deleted = self
+            .delete_rows_before(partition::SIGNALS_TABLE, cutoff_ts, limit)
+            .await?;
+
+        // Days wholly before the cutoff go with their table
+        for day in days.iter().filter(|day| **day < cutoff_day) {
+            if deleted >= limit {
+                return Ok(deleted);
+            }
+
+            let table = partition::table_name(*day);
+            let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+            let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM \"{}\"", table))
+                .fetch_one(&mut *tx)
+                .await?;
+            sqlx::query(&format!("DROP TABLE \"{}\"", table))
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM sqlite_sequence WHERE name = ?")
+                .bind(&table)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            deleted += rows as u64;
+        }
+
+        // The cutoff's own day is only partly expired
+        if deleted < limit && days.contains(&cutoff_day) {
+            deleted += self
+                .delete_rows_before(
+                    &partition::table_name(cutoff_day),
+                    cutoff_ts,
+                    limit - deleted,
+                )
+                .await?;
+        }
+
+        Ok(deleted)
+    }
+
+    async fn compact_signals_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> anyhow::Result<CompactionReport> {
+        let cutoff_day = partition::day_of(cutoff.timestamp());
+
+        // Days with signals from before partitioning, or with a partition
+        let mut days: Vec<i64> = sqlx::query_scalar(&format!(
+            "SELECT DISTINCT ts / {} FROM \"{}\" WHERE ts >= 0 AND ts < ?",
+            partition::DAY_SECONDS,
+            partition::SIGNALS_TABLE
+        ))
+        .bind(cutoff_day * partition::DAY_SECONDS)
+        .fetch_all(&self.pool)
+        .await?;
+        let unpartitioned = days.clone();
+        days.extend(self.partition_days().await?);
+        days.sort_unstable();
+        days.dedup();
+
+        let mut report = CompactionReport::default();
+        for day in days.into_iter().filter(|day| *day < cutoff_day) {
+            let table = partition::table_name(day);
+            let start = day * partition::DAY_SECONDS;
+            let end = start + partition::DAY_SECONDS;
+
+            let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+            for statement in partition::create_statements(day) {
+                sqlx::query(&statement).execute(&mut *tx).await?;
+            }
+            let has_unpartitioned = unpartitioned.contains(&day);
+            let compacted: bool = sqlx::query_scalar(&format!(
+                r#"
+                SELECT NOT EXISTS (SELECT 1 FROM "{table}" WHERE ts % ? <> 0)
+                   AND NOT EXISTS (
+                       SELECT 1 FROM "{table}" GROUP BY bucket, ts, channel HAVING COUNT(*) > 1
+                   )
+                "#
+            ))
+            .bind(COMPACTION_SECONDS)
+            .fetch_one(&mut *tx)
+            .await?;
+            if compacted && !has_unpartitioned {
+                continue;
+            }
+
+            let raw =
+                partition::union_source(&[partition::SIGNALS_TABLE.to_string(), table.clone()]);
+            let sums = sqlx::query(&format!(
+                r#"
+                SELECT bucket, ts - ts % ? AS hour, channel,
+                       SUM(weight) AS weight, COUNT(*) AS raw_rows
+                FROM {}
+                WHERE ts >= ? AND ts < ?
+                GROUP BY bucket, hour, channel
+                "#,
+                raw
+            ))
+            .bind(COMPACTION_SECONDS)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            sqlx::query(&format!(
+                "DELETE FROM \"{}\" WHERE ts >= ? AND ts < ?",
+                partition::SIGNALS_TABLE
+            ))
+            .bind(start)
+            .bind(end)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query(&format!("DELETE FROM \"{}\"", table))
+                .execute(&mut *tx)
+                .await?;
+            for chunk in sums.chunks(SIGNALS_PER_STATEMENT) {
+                let mut insert = sqlx::QueryBuilder::new(format!(
+                    "INSERT INTO \"{}\" (bucket, ts, weight, channel) ",
+                    table
+                ));
+                insert.push_values(chunk, |mut row, sum| {
+                    row.push_bind(sum.get::<String, _>("bucket"))
+                        .push_bind(sum.get::<i64, _>("hour"))
+                        .push_bind(sum.get::<i64, _>("weight"))
+                        .push_bind(sum.get::<Option<String>, _>("channel"));
+                });
+                insert.build().execute(&mut *tx).await?;
+            }
+            tx.commit().await?;
+
+            report.days += 1;
+            report.raw_rows += sums
+                .iter()
+                .map(|sum| sum.get::<i64, _>("raw_rows") as u64)
+                .sum::<u64>();
+            report.compacted_rows += sums.len() as u64;
+        }
+
+        Ok(report)
+    }
+
+    async fn backup_to(&self, path: &Path) -> anyhow::Result<()> {
+        anyhow::ensure!(!path.exists(), "'{}' already exists", path.display());
+        let path = path
+            .to_str()
+            .ok_or_else(|| anyhow::anyhow!("'{}' is not valid UTF-8", path.display()))?;
+        // An in-memory database would be copied into memory, not the file
+        let file: String =
+            sqlx::query_scalar("SELECT file FROM pragma_database_list WHERE name = 'main'")
+                .fetch_one(&self.read_pool)
+                .await?;
+        anyhow::ensure!(
+            !file.is_empty(),
+            "an in-memory database cannot be backed up"
+        );
+
+        // Buffered signals belong in the copy
+        self.flush_writes().await?;
+        sqlx::query("VACUUM INTO ?")
+            .bind(path)
+            .execute(&self.read_pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn stats(&self) -> anyhow::Result<StorageStats> {
+        // Buffered signals have been accepted, so they count
+        self.flush_writes().await?;
+
+        let names: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'table'")
+                .fetch_all(&self.read_pool)
+                .await?;
+        let source = self.signal_source(None, None).await?;
+
+        let mut tables = BTreeMap::new();
+        let mut partitions = 0;
+        for name in names {
+            if name.starts_with("sqlite_") || name.starts_with("_sqlx") {
+                continue;
+            }
+            if partition::parse_table_name(&name).is_some() {
+                partitions += 1;
+                continue;
+            }
+            let from = if name == partition::SIGNALS_TABLE {
+                source.clone()
+            } else {
+                format!("\"{}\"", name)
+            };
+            let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", from))
+                .fetch_one(&self.read_pool)
+                .await?;
+            tables.insert(name, rows as u64);
+        }
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT bucket, COUNT(*) AS rows, MIN(ts) AS oldest, MAX(ts) AS newest
+            FROM {}
+            GROUP BY bucket
+            ORDER BY bucket
+            "#,
+            source
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+        let buckets: Vec<BucketStorageStats> = rows
+            .iter()
+            .map(|row| BucketStorageStats {
+                bucket: row.get("bucket"),
+                rows: row.get::<i64, _>("rows") as u64,
+                oldest: Utc.timestamp_opt(row.get("oldest"), 0).unwrap(),
+                newest: Utc.timestamp_opt(row.get("newest"), 0).unwrap(),
+            })
+            .collect();
+
+        let pragma = |name: &str| {
+            let query = format!("PRAGMA {}", name);
+            async move {
+                let value: i64 = sqlx::query_scalar(&query)
+                    .fetch_one(&self.read_pool)
+                    .await?;
+                anyhow::Ok(value as u64)
+            }
+        };
+        let page_size = pragma("page_size").await?;
+        let file: String =
+            sqlx::query_scalar("SELECT file FROM pragma_database_list WHERE name = 'main'")
+                .fetch_one(&self.read_pool)
+                .await?;
+        let wal_bytes = if file.is_empty() {
+            None
+        } else {
+            tokio::fs::metadata(format!("{}-wal", file))
+                .await
+                .ok()
+                .map(|meta| meta.len())
+        };
+
+        Ok(StorageStats {
+            tables,
+            partitions,
+            oldest_signal: buckets.iter().map(|b| b.oldest).min(),
+            newest_signal: buckets.iter().map(|b| b.newest).max(),
+            buckets,
+            database_bytes: pragma("page_count").await? * page_size,
+            free_bytes: pragma("freelist_count").await? * page_size,
+            wal_bytes,
+        })
+    }
+
+    async fn get_signals_after(
+        &self,
+        filter: &SignalFilter,
+        after: i64,
+        limit: u32,
+    ) -> anyhow::Result<Vec<(i64, LifeSignal)>> {
+        let from = filter.from.map(|t| t.timestamp());
+        let to = filter.to.map(|t| t.timestamp());
+
+        // Row IDs grow from one table to the next, so reading the tables in
+        // order reads the signals in row ID order
+        let mut signals = Vec::new();
+        for table in self.signal_tables(from, to.map(|ts| ts - 1)).await? {
+            let remaining = i64::from(limit) - signals.len() as i64;
+            if remaining <= 0 {
+                break;
+            }
+
+            let rows = sqlx::query(&format!(
+                r#"
+                SELECT id, bucket, ts, weight, channel
+                FROM "{}"
+                WHERE id > ?
+                  AND (? IS NULL OR bucket = ?)
+                  AND (? IS NULL OR ts >= ?)
+                  AND (? IS NULL OR ts < ?)
+                ORDER BY id
+                LIMIT ?
+                "#,
+                table
+            ))
+            .bind(after)
+            .bind(&filter.bucket)
+            .bind(&filter.bucket)
+            .bind(from)
+            .bind(from)
+            .bind(to)
+            .bind(to)
+            .bind(remaining)
+            .fetch_all(&self.read_pool)
+            .await?;
+
+            signals.extend(rows.iter().map(|r| {
+                (
+                    r.get("id"),
+                    LifeSignal {
+                        bucket: r.get("bucket"),
+                        timestamp: Utc.timestamp_opt(r.get("ts"), 0).unwrap(),
+                        weight: r.get("weight"),
+                        channel: r.get("channel"),
+                    },
+                )
+            }));
+        }
+        Ok(signals)
+    }
+
+    async fn insert_warmth_snapshots(&self, snapshots: &[WarmthSnapshot]) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for snapshot in snapshots {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO warmth_snapshots
+                    (bucket, window_minutes, taken_at, status, current_window_total, recent_average)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&snapshot.bucket)
+            .bind(i64::from(snapshot.window_minutes))
+            .bind(snapshot.taken_at.timestamp())
+            .bind(snapshot.status.name())
+            .bind(snapshot.current_window_total)
+            .bind(snapshot.recent_average)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_warmth_snapshots(
+        &self,
+        bucket: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<WarmthSnapshot>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT bucket, window_minutes, taken_at, status, current_window_total, recent_average
+            FROM warmth_snapshots
+            WHERE bucket = ? AND taken_at >= ? AND taken_at <= ?
+            ORDER BY taken_at, window_minutes
+            "#,
+        )
+        .bind(bucket)
+        .bind(from.timestamp())
+        .bind(to.timestamp())
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        rows.iter()
+            .map(|r| {
+                let status: String = r.get("status");
+                Ok(WarmthSnapshot {
+                    bucket: r.get("bucket"),
+                    window_minutes: u32::try_from(r.get::<i64, _>("window_minutes"))?,
+                    taken_at: Utc.timestamp_opt(r.get("taken_at"), 0).unwrap(),
+                    status: WarmthStatus::parse(&status)
+                        .ok_or_else(|| anyhow::anyhow!("unknown status '{}'", status))?,
+                    current_window_total: r.get("current_window_total"),
+                    recent_average: r.get("recent_average"),
+                })
+            })
+            .collect()
+    }
+
+    async fn distressed_since(&self, bucket: &str) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let since: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT MIN(taken_at)
+            FROM warmth_snapshots
+            WHERE bucket = ?
+              AND taken_at > COALESCE(
+                  (SELECT MAX(taken_at) FROM warmth_snapshots
+                   WHERE bucket = ? AND status NOT IN ('collapsing', 'dead')),
+                  -1)
+            "#,
+        )
+        .bind(bucket)
+        .bind(bucket)
+        .fetch_one(&self.read_pool)
+        .await?;
+
+        Ok(since.map(|ts| Utc.timestamp_opt(ts, 0).unwrap()))
+    }
+
+    async fn insert_dashboard_snapshot(&self, response: &DashboardResponse) -> anyhow::Result<()> {
+        let payload = serde_json::to_string(response)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO dashboard_snapshots (taken_at, payload)
+            VALUES (?, ?)
+            "#,
+        )
+        .bind(response.timestamp.timestamp())
+        .bind(payload)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_dashboard_snapshots_before(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> anyhow::Result<u64> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM dashboard_snapshots
+            WHERE taken_at < ?
+              AND id <> (
+                  SELECT id FROM dashboard_snapshots
+                  ORDER BY taken_at DESC, id DESC
+                  LIMIT 1
+              )
+            "#,
+        )
+        .bind(cutoff.timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn insert_tension(
+        &self,
+        at: DateTime<Utc>,
+        summary: &DashboardSummary,
+    ) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let scores = std::iter::once((GLOBAL_TENSION_SCOPE, summary.global_tension)).chain(
+            summary
+                .country_tension
+                .iter()
+                .map(|c| (c.country.as_str(), c.score)),
+        );
+        for (scope, score) in scores {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO tension_history (scope, taken_at, score)
+                VALUES (?, ?, ?)
+                "#,
+            )
+            .bind(scope)
+            .bind(at.timestamp())
+            .bind(score)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_tension_history(
+        &self,
+        country: Option<&str>,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<TensionPoint>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT g.taken_at, COALESCE(c.score, 0.0) AS score
+            FROM tension_history g
+            LEFT JOIN tension_history c
+                ON c.taken_at = g.taken_at AND c.scope = ? COLLATE NOCASE
+            WHERE g.scope = ? AND g.taken_at >= ?
+            ORDER BY g.taken_at
+            "#,
+        )
+        .bind(country.unwrap_or(GLOBAL_TENSION_SCOPE))
+        .bind(GLOBAL_TENSION_SCOPE)
+        .bind(since.timestamp())
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some(TensionPoint {
+                    at: Utc.timestamp_opt(row.get("taken_at"), 0).single()?,
+                    score: row.get("score"),
+                })
+            })
+            .collect())
+    }
+
+    async fn get_dashboard_snapshot_at(
+        &self,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Option<DashboardResponse>> {
+        let row = sqlx::query(
+            r#"
+            SELECT payload
+            FROM dashboard_snapshots
+            WHERE taken_at <= ?
+            ORDER BY taken_at DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(at.timestamp())
+        .fetch_optional(&self.read_pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let payload: String = row.get("payload");
+                let mut response: DashboardResponse = serde_json::from_str(&payload)?;
+                attribution::fill_missing(&mut response.issues);
+                Ok(Some(response))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn upsert_external_issue(
+        &self,
+        issue: &Issue,
+        received_at: DateTime<Utc>,
+    ) -> anyhow::Result<bool> {
+        let payload = serde_json::to_string(issue)?;
+        let mut tx = self.pool.begin().await?;
+
+        let existing = sqlx::query("SELECT 1 FROM external_issues WHERE id = ?")
+            .bind(&issue.id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO external_issues (id, received_at, payload)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(&issue.id)
+        .bind(received_at.timestamp())
+        .bind(payload)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(existing.is_none())
+    }
+
+    async fn get_external_issues(&self, since: DateTime<Utc>) -> anyhow::Result<Vec<Issue>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT payload
+            FROM external_issues
+            WHERE received_at >= ?
+            ORDER BY received_at DESC, id
+            "#,
+        )
+        .bind(since.timestamp())
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| Ok(serde_json::from_str(row.get::<&str, _>("payload"))?))
+            .collect()
+    }
+
+    async fn set_heartbeat(
+        &self,
+        bucket: &str,
+        interval_seconds: i64,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO buckets (bucket, heartbeat_seconds, heartbeat_since)
+            VALUES (?, ?, ?)
+            ON CONFLICT(bucket) DO UPDATE SET
+                heartbeat_seconds = excluded.heartbeat_seconds,
+                heartbeat_since = excluded.heartbeat_since
+            "#,
+        )
+        .bind(bucket)
+        .bind(interval_seconds)
+        .bind(now.timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn clear_heartbeat(&self, bucket: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE buckets
+            SET heartbeat_seconds = NULL, heartbeat_since = NULL
+            WHERE bucket = ? AND heartbeat_seconds IS NOT NULL
+            "#,
+        )
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_heartbeats(&self) -> anyhow::Result<Vec<HeartbeatStatus>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT b.bucket AS bucket,
+                   b.heartbeat_seconds AS interval_seconds,
+                   b.heartbeat_since AS registered_at,
+                   {} AS last_ts
+            FROM buckets b
+            WHERE b.heartbeat_seconds IS NOT NULL AND b.archived_at IS NULL
+            ORDER BY b.bucket
+            "#,
+            partition::last_ts_expr(&self.signal_tables(None, None).await?, "b.bucket")
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| HeartbeatStatus {
+                bucket: r.get("bucket"),
+                interval_seconds: r.get("interval_seconds"),
+                registered_at: Utc.timestamp_opt(r.get("registered_at"), 0).unwrap(),
+                last_seen: r
+                    .get::<Option<i64>, _>("last_ts")
+                    .map(|ts| Utc.timestamp_opt(ts, 0).unwrap()),
+            })
+            .collect())
+    }
+
+    async fn arm_switch(
+        &self,
+        bucket: &str,
+        countdown_seconds: i64,
+        now: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO buckets (bucket, switch_seconds, switch_armed_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT(bucket) DO UPDATE SET
+                switch_seconds = excluded.switch_seconds,
+                switch_armed_at = excluded.switch_armed_at,
+                switch_notified_deadline = NULL,
+                switch_notified_steps = 0
+            "#,
+        )
+        .bind(bucket)
+        .bind(countdown_seconds)
+        .bind(now.timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn disarm_switch(&self, bucket: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE buckets
+            SET switch_seconds = NULL,
+                switch_armed_at = NULL,
+                switch_notified_deadline = NULL,
+                switch_notified_steps = 0
+            WHERE bucket = ? AND switch_seconds IS NOT NULL
+            "#,
+        )
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_switches(&self) -> anyhow::Result<Vec<SwitchStatus>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT b.bucket AS bucket,
+                   b.switch_seconds AS countdown_seconds,
+                   b.switch_armed_at AS armed_at,
+                   b.switch_notified_deadline AS notified_deadline,
+                   b.switch_notified_steps AS notified_steps,
+                   {} AS last_ts
+            FROM buckets b
+            WHERE b.switch_seconds IS NOT NULL AND b.archived_at IS NULL
+            ORDER BY b.bucket
+            "#,
+            partition::last_ts_expr(&self.signal_tables(None, None).await?, "b.bucket")
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let at = |ts: i64| Utc.timestamp_opt(ts, 0).unwrap();
+        Ok(rows
+            .iter()
+            .map(|r| SwitchStatus {
+                bucket: r.get("bucket"),
+                countdown_seconds: r.get("countdown_seconds"),
+                armed_at: at(r.get("armed_at")),
+                last_seen: r.get::<Option<i64>, _>("last_ts").map(at),
+                notified_deadline: r.get::<Option<i64>, _>("notified_deadline").map(at),
+                notified_steps: r.get::<i64, _>("notified_steps") as usize,
+            })
+            .collect())
+    }
+
+    async fn set_switch_notified(
+        &self,
+        bucket: &str,
+        deadline: DateTime<Utc>,
+        steps: usize,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE buckets
+            SET switch_notified_deadline = ?, switch_notified_steps = ?
+            WHERE bucket = ?
+            "#,
+        )
+        .bind(deadline.timestamp())
+        .bind(steps as i64)
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_timezone(&self, bucket: &str, timezone: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO buckets (bucket, timezone)
+            VALUES (?, ?)
+            ON CONFLICT(bucket) DO UPDATE SET timezone = excluded.timezone
+            "#,
+        )
+        .bind(bucket)
+        .bind(timezone)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn clear_timezone(&self, bucket: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE buckets SET timezone = NULL WHERE bucket = ? AND timezone IS NOT NULL",
+        )
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_timezone(&self, bucket: &str) -> anyhow::Result<Option<String>> {
+        let timezone: Option<Option<String>> =
+            sqlx::query_scalar("SELECT timezone FROM buckets WHERE bucket = ?")
+                .bind(bucket)
+                .fetch_optional(&self.read_pool)
+                .await?;
+
+        Ok(timezone.flatten())
+    }
+
+    async fn set_country(&self, bucket: &str, country: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO buckets (bucket, country)
+            VALUES (?, ?)
+            ON CONFLICT(bucket) DO UPDATE SET country = excluded.country
+            "#,
+        )
+        .bind(bucket)
+        .bind(country)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn clear_country(&self, bucket: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE buckets SET country = NULL WHERE bucket = ? AND country IS NOT NULL",
+        )
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_country(&self, bucket: &str) -> anyhow::Result<Option<String>> {
+        let country: Option<Option<String>> =
+            sqlx::query_scalar("SELECT country FROM buckets WHERE bucket = ?")
+                .bind(bucket)
+                .fetch_optional(&self.read_pool)
+                .await?;
+
+        Ok(country.flatten())
+    }
+
+    async fn get_buckets_in_country(&self, codes: &[String]) -> anyhow::Result<Vec<String>> {
+        let mut buckets = Vec::new();
+        for code in codes {
+            let rows: Vec<String> = sqlx::query_scalar(
+                r#"
+                SELECT bucket FROM buckets
+                WHERE country = ? COLLATE NOCASE AND archived_at IS NULL
+                "#,
+            )
+            .bind(code)
+            .fetch_all(&self.read_pool)
+            .await?;
+            buckets.extend(rows);
+        }
+        buckets.sort();
+        buckets.dedup();
+        Ok(buckets)
+    }
+
+    async fn get_countries(&self) -> anyhow::Result<BTreeMap<String, Vec<String>>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT UPPER(country) AS country, bucket FROM buckets
+            WHERE country IS NOT NULL AND archived_at IS NULL
+            ORDER BY country, bucket
+            "#,
+        )
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let mut countries: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for row in &rows {
+            countries
+                .entry(row.get("country"))
+                .or_default()
+                .push(row.get("bucket"));
+        }
+        Ok(countries)
+    }
+
+    async fn set_baseline(&self, bucket: &str, baseline: Baseline) -> anyhow::Result<()> {
+        let (windows, span_minutes) = match baseline {
+            Baseline::Windows(windows) => (Some(windows), None),
+            Baseline::SpanMinutes(span) => (None, Some(span)),
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO buckets (bucket, baseline_windows, baseline_span_minutes)
+            VALUES (?, ?, ?)
+            ON CONFLICT(bucket) DO UPDATE SET
+                baseline_windows = excluded.baseline_windows,
+                baseline_span_minutes = excluded.baseline_span_minutes
+            "#,
+        )
+        .bind(bucket)
+        .bind(windows.map(i64::from))
+        .bind(span_minutes.map(i64::from))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn clear_baseline(&self, bucket: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE buckets
+            SET baseline_windows = NULL, baseline_span_minutes = NULL
+            WHERE bucket = ?
+              AND (baseline_windows IS NOT NULL OR baseline_span_minutes IS NOT NULL)
+            "#,
+        )
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_baseline(&self, bucket: &str) -> anyhow::Result<Option<Baseline>> {
+        let row = sqlx::query(
+            "SELECT baseline_windows, baseline_span_minutes FROM buckets WHERE bucket = ?",
+        )
+        .bind(bucket)
+        .fetch_optional(&self.read_pool)
+        .await?;
+
+        match row {
+            Some(row) => baseline_from_row(&row),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_baselines(
+        &self,
+        buckets: &[String],
+    ) -> anyhow::Result<BTreeMap<String, Baseline>> {
+        let mut baselines = BTreeMap::new();
+        for chunk in buckets.chunks(BUCKETS_PER_STATEMENT) {
+            let mut query = sqlx::QueryBuilder::new(
+                "SELECT bucket, baseline_windows, baseline_span_minutes FROM buckets WHERE bucket IN (",
+            );
+            let mut separated = query.separated(", ");
+            for bucket in chunk {
+                separated.push_bind(bucket);
+            }
+            query.push(")");
+
+            for row in query.build().fetch_all(&self.read_pool).await? {
+                if let Some(baseline) = baseline_from_row(&row)? {
+                    baselines.insert(row.get("bucket"), baseline);
+                }
+            }
+        }
+        Ok(baselines)
+    }
+
+    async fn set_bucket_meta(&self, bucket: &str, meta: &BucketMeta) -> anyhow::Result<()> {
+        let (stressed_ratio, collapsing_ratio, ewma_alpha) = match meta.thresholds {
+            Some(t) => (
+                Some(t.stressed_ratio),
+                Some(t.collapsing_ratio),
+                t.ewma_alpha,
+            ),
+            None => (None, None, None),
+        };
+        sqlx::query(
+            r#"
+            INSERT INTO buckets (
+                bucket, display_name, description, expected_interval_minutes,
+                stressed_ratio, collapsing_ratio, ewma_alpha
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(bucket) DO UPDATE SET
+                display_name = excluded.display_name,
+                description = excluded.description,
+                expected_interval_minutes = excluded.expected_interval_minutes,
+                stressed_ratio = excluded.stressed_ratio,
+                collapsing_ratio = excluded.collapsing_ratio,
+                ewma_alpha = excluded.ewma_alpha
+            "#,
+        )
+        .bind(bucket)
+        .bind(&meta.display_name)
+        .bind(&meta.description)
+        .bind(meta.expected_interval_minutes.map(i64::from))
+        .bind(stressed_ratio)
+        .bind(collapsing_ratio)
+        .bind(ewma_alpha)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn clear_bucket_meta(&self, bucket: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE buckets
+            SET display_name = NULL, description = NULL, expected_interval_minutes = NULL,
+                stressed_ratio = NULL, collapsing_ratio = NULL, ewma_alpha = NULL
+            WHERE bucket = ?
+              AND (display_name IS NOT NULL OR description IS NOT NULL
+                   OR expected_interval_minutes IS NOT NULL OR stressed_ratio IS NOT NULL)
+            "#,
+        )
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_bucket_meta(&self, bucket: &str) -> anyhow::Result<Option<BucketMeta>> {
+        let row = sqlx::query(
+            r#"
+            SELECT display_name, description, expected_interval_minutes,
+                   stressed_ratio, collapsing_ratio, ewma_alpha
+            FROM buckets
+            WHERE bucket = ?
+            "#,
+        )
+        .bind(bucket)
+        .fetch_optional(&self.read_pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let interval: Option<i64> = row.get("expected_interval_minutes");
+        let meta = BucketMeta {
+            display_name: row.get("display_name"),
+            description: row.get("description"),
+            expected_interval_minutes: interval.map(u32::try_from).transpose()?,
+            thresholds: thresholds_from_row(&row),
+        };
+        Ok((!meta.is_empty()).then_some(meta))
+    }
+
+    fn default_thresholds(&self) -> WarmthThresholds {
+        self.default_thresholds
+    }
+
+    fn detector(&self) -> (Detector, f64) {
+        self.detector
+    }
+
+    async fn get_thresholds(
+        &self,
+        buckets: &[String],
+    ) -> anyhow::Result<BTreeMap<String, WarmthThresholds>> {
+        let mut thresholds = BTreeMap::new();
+        for chunk in buckets.chunks(BUCKETS_PER_STATEMENT) {
+            let mut query = sqlx::QueryBuilder::new(
+                "SELECT bucket, stressed_ratio, collapsing_ratio, ewma_alpha \
+                 FROM buckets WHERE bucket IN (",
+            );
+            let mut separated = query.separated(", ");
+            for bucket in chunk {
+                separated.push_bind(bucket);
+            }
+            query.push(")");
+
+            for row in query.build().fetch_all(&self.read_pool).await? {
+                if let Some(t) = thresholds_from_row(&row) {
+                    thresholds.insert(row.get("bucket"), t);
+                }
+            }
+        }
+        Ok(thresholds)
+    }
+
+    async fn archive_bucket(&self, bucket: &str, now: DateTime<Utc>) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO buckets (bucket, archived_at)
+            VALUES (?, ?)
+            ON CONFLICT(bucket) DO UPDATE SET
+                archived_at = COALESCE(buckets.archived_at, excluded.archived_at)
+            "#,
         )
-        .bind(start_ts)
-        .fetch_all(&self.pool)
+        .bind(bucket)
+        .bind(now.timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn unarchive_bucket(&self, bucket: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE buckets SET archived_at = NULL WHERE bucket = ? AND archived_at IS NOT NULL",
+        )
+        .bind(bucket)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_buckets(&self) -> anyhow::Result<Vec<BucketEntry>> {
+        let tables = self.signal_tables(None, None).await?;
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT k.bucket AS bucket,
+                   {} AS last_ts,
+                   b.archived_at AS archived_at
+            FROM (SELECT bucket FROM {} UNION SELECT bucket FROM buckets) k
+            LEFT JOIN buckets b ON b.bucket = k.bucket
+            ORDER BY k.bucket
+            "#,
+            partition::last_ts_expr(&tables, "k.bucket"),
+            partition::union_source(&tables)
+        ))
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        let at = |ts: i64| Utc.timestamp_opt(ts, 0).unwrap();
+        Ok(rows
+            .iter()
+            .map(|r| BucketEntry {
+                bucket: r.get("bucket"),
+                last_seen: r.get::<Option<i64>, _>("last_ts").map(at),
+                archived_at: r.get::<Option<i64>, _>("archived_at").map(at),
+            })
+            .collect())
+    }
+
+    async fn set_group(&self, name: &str, buckets: &[String]) -> anyhow::Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM bucket_groups WHERE group_name = ?")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+
+        for bucket in buckets {
+            sqlx::query("INSERT OR IGNORE INTO bucket_groups (group_name, bucket) VALUES (?, ?)")
+                .bind(name)
+                .bind(bucket)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn delete_group(&self, name: &str) -> anyhow::Result<bool> {
+        let result = sqlx::query("DELETE FROM bucket_groups WHERE group_name = ?")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_group(&self, name: &str) -> anyhow::Result<Option<BucketGroup>> {
+        let buckets: Vec<String> = sqlx::query_scalar(
+            "SELECT bucket FROM bucket_groups WHERE group_name = ? ORDER BY bucket",
+        )
+        .bind(name)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok((!buckets.is_empty()).then(|| BucketGroup {
+            name: name.to_string(),
+            buckets,
+        }))
+    }
+
+    async fn get_groups(&self) -> anyhow::Result<Vec<BucketGroup>> {
+        let rows =
+            sqlx::query("SELECT group_name, bucket FROM bucket_groups ORDER BY group_name, bucket")
+                .fetch_all(&self.read_pool)
+                .await?;
+
+        let mut groups: Vec<BucketGroup> = Vec::new();
+        for row in rows {
+            let name: String = row.get("group_name");
+            let bucket: String = row.get("bucket");
+            match groups.last_mut() {
+                Some(group) if group.name == name => group.buckets.push(bucket),
+                _ => groups.push(BucketGroup {
+                    name,
+                    buckets: vec![bucket],
+                }),
+            }
+        }
+        Ok(groups)
+    }
+
+    async fn insert_audit_entry(
+        &self,
+        timestamp: DateTime<Utc>,
+        actor: &str,
+        action: &str,
+        details: Option<&str>,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO audit_log (ts, actor, action, details)
+            VALUES (?, ?, ?, ?)
+            "#,
+        )
+        .bind(timestamp.timestamp())
+        .bind(actor)
+        .bind(action)
+        .bind(details)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_audit_entries(
+        &self,
+        action: Option<&str>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<AuditEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, ts, actor, action, details
+            FROM audit_log
+            WHERE ? IS NULL OR action = ?
+            ORDER BY id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(action)
+        .bind(action)
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| AuditEntry {
+                id: r.get("id"),
+                timestamp: Utc.timestamp_opt(r.get("ts"), 0).unwrap(),
+                actor: r.get("actor"),
+                action: r.get("action"),
+                details: r.get("details"),
+            })
+            .collect())
+    }
+
+    async fn insert_annotation(&self, annotation: &Annotation) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO incident_annotations (incident_id, ts, kind, actor, text)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&annotation.incident_id)
+        .bind(annotation.timestamp.timestamp())
+        .bind(annotation.kind.name())
+        .bind(&annotation.actor)
+        .bind(&annotation.text)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_annotations(&self, incident_id: &str) -> anyhow::Result<Vec<Annotation>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT incident_id, ts, kind, actor, text
+            FROM incident_annotations
+            WHERE incident_id = ?
+            ORDER BY ts, id
+            "#,
+        )
+        .bind(incident_id)
+        .fetch_all(&self.read_pool)
+        .await?;
+
+        rows.iter()
+            .map(|r| {
+                let kind: String = r.get("kind");
+                Ok(Annotation {
+                    incident_id: r.get("incident_id"),
+                    timestamp: Utc.timestamp_opt(r.get("ts"), 0).unwrap(),
+                    kind: AnnotationKind::parse(&kind)
+                        .ok_or_else(|| anyhow::anyhow!("unknown annotation kind '{}'", kind))?,
+                    actor: r.get("actor"),
+                    text: r.get("text"),
+                })
+            })
+            .collect()
+    }
+
+    async fn record_alerts(&self, alerts: &[Alert], now: DateTime<Utc>) -> anyhow::Result<()> {
+        let seen_since = (now - chrono::Duration::minutes(REFIRE_GAP_MINUTES)).timestamp();
+        let mut tx = self.pool.begin().await?;
+        for alert in alerts {
+            let extended = sqlx::query(
+                r#"
+                UPDATE alert_history
+                SET status = ?, severity = ?, message = ?, last_seen = MAX(last_seen, ?)
+                WHERE id = (
+                    SELECT id FROM alert_history
+                    WHERE bucket = ? AND subject = ? AND reason = ? AND last_seen >= ?
+                    ORDER BY last_seen DESC
+                    LIMIT 1
+                )
+                "#,
+            )
+            .bind(alert.status.name())
+            .bind(alert.severity.name())
+            .bind(&alert.message)
+            .bind(now.timestamp())
+            .bind(&alert.bucket)
+            .bind(alert.subject.name())
+            .bind(alert.reason.name())
+            .bind(seen_since)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+            if extended == 0 {
+                sqlx::query(
+                    r#"
+                    INSERT INTO alert_history
+                        (bucket, subject, reason, status, severity, message, first_seen, last_seen)
+                    VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(&alert.bucket)
+                .bind(alert.subject.name())
+                .bind(alert.reason.name())
+                .bind(alert.status.name())
+                .bind(alert.severity.name())
+                .bind(&alert.message)
+                .bind(now.timestamp())
+                .bind(now.timestamp())
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_alert_history(
+        &self,
+        bucket: Option<&str>,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<AlertRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, bucket, subject, reason, status, severity, message, first_seen, last_seen
+            FROM alert_history
+            WHERE (? IS NULL OR bucket = ?) AND last_seen >= ? AND first_seen < ?
+            ORDER BY last_seen DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(bucket)
+        .bind(bucket)
+        .bind(from.timestamp())
+        // Entries are stored in whole seconds, so one seen earlier in the
+        // second `until` falls in is before it
+        .bind(until.timestamp() + i64::from(until.timestamp_subsec_nanos() > 0))
+        .bind(i64::from(limit))
+        .fetch_all(&self.read_pool)
         .await?;
 
-        Ok(rows.iter().map(|r| r.get("bucket")).collect())
+        rows.iter()
+            .map(|r| {
+                let column = |name: &str| -> String { r.get(name) };
+                let unknown = |name: &str| anyhow::anyhow!("unknown {} '{}'", name, column(name));
+                Ok(AlertRecord {
+                    id: r.get("id"),
+                    bucket: r.get("bucket"),
+                    subject: AlertSubject::parse(&column("subject"))
+                        .ok_or_else(|| unknown("subject"))?,
+                    reason: AlertReason::parse(&column("reason"))
+                        .ok_or_else(|| unknown("reason"))?,
+                    status: WarmthStatus::parse(&column("status"))
+                        .ok_or_else(|| unknown("status"))?,
+                    severity: IssueSeverity::parse(&column("severity"))
+                        .ok_or_else(|| unknown("severity"))?,
+                    message: r.get("message"),
+                    first_seen: Utc.timestamp_opt(r.get("first_seen"), 0).unwrap(),
+                    last_seen: Utc.timestamp_opt(r.get("last_seen"), 0).unwrap(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Read a `buckets` row's baseline columns.
+fn baseline_from_row(row: &sqlx::sqlite::SqliteRow) -> anyhow::Result<Option<Baseline>> {
+    let windows: Option<i64> = row.get("baseline_windows");
+    let span_minutes: Option<i64> = row.get("baseline_span_minutes");
+    Ok(match (windows, span_minutes) {
+        (Some(windows), _) => Some(Baseline::Windows(u32::try_from(windows)?)),
+        (None, Some(span)) => Some(Baseline::SpanMinutes(u32::try_from(span)?)),
+        (None, None) => None,
+    })
+}
+
+fn thresholds_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<WarmthThresholds> {
+    let stressed_ratio: Option<f64> = row.get("stressed_ratio");
+    let collapsing_ratio: Option<f64> = row.get("collapsing_ratio");
+    Some(WarmthThresholds {
+        stressed_ratio: stressed_ratio?,
+        collapsing_ratio: collapsing_ratio?,
+        ewma_alpha: row.get("ewma_alpha"),
+    })
+}
+
+/// Insert `signals`, each into its day's partition, created on first use.
+async fn insert_signals(
+    conn: &mut sqlx::SqliteConnection,
+    signals: &[LifeSignal],
+) -> anyhow::Result<()> {
+    let mut by_day: BTreeMap<i64, Vec<&LifeSignal>> = BTreeMap::new();
+    for signal in signals {
+        by_day
+            .entry(partition::day_of(signal.timestamp.timestamp()))
+            .or_default()
+            .push(signal);
+    }
+
+    for (day, signals) in by_day {
+        for statement in partition::create_statements(day) {
+            sqlx::query(&statement).execute(&mut *conn).await?;
+        }
+        for chunk in signals.chunks(SIGNALS_PER_STATEMENT) {
+            let mut insert = sqlx::QueryBuilder::new(format!(
+                "INSERT INTO \"{}\" (bucket, ts, weight, channel) ",
+                partition::table_name(day)
+            ));
+            insert.push_values(chunk, |mut row, signal| {
+                row.push_bind(&signal.bucket)
+                    .push_bind(signal.timestamp.timestamp())
+                    .push_bind(signal.weight)
+                    .push_bind(&signal.channel);
+            });
+            insert.build().execute(&mut *conn).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether any of `tables` holds a row for `bucket`.
+async fn bucket_exists(
+    conn: &mut sqlx::SqliteConnection,
+    tables: &[String],
+    bucket: &str,
+) -> anyhow::Result<bool> {
+    for table in tables {
+        let sql = format!(
+            "SELECT EXISTS (SELECT 1 FROM \"{}\" WHERE bucket = ?)",
+            table
+        );
+        if sqlx::query_scalar(&sql)
+            .bind(bucket)
+            .fetch_one(&mut *conn)
+            .await?
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_insert_and_query() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        let now = Utc::now();
+        let signal = LifeSignal {
+            bucket: "test-bucket".to_string(),
+            timestamp: now,
+            weight: 5,
+            channel: None,
+        };
+
+        storage.insert_life_signal(&signal).await.unwrap();
+
+        let total = storage
+            .query_bucket_window("test-bucket", 10, now + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+
+        assert_eq!(total, 5);
+    }
+
+    #[tokio::test]
+    async fn test_multiple_signals() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        let now = Utc::now();
+
+        for i in 0..5 {
+            let signal = LifeSignal {
+                bucket: "test-bucket".to_string(),
+                timestamp: now - chrono::Duration::minutes(i),
+                weight: 10,
+                channel: None,
+            };
+            storage.insert_life_signal(&signal).await.unwrap();
+        }
+
+        let total = storage
+            .query_bucket_window("test-bucket", 10, now + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+
+        assert_eq!(total, 50);
+    }
+
+    #[tokio::test]
+    async fn test_get_last_seen() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        // No signals yet
+        let last = storage.get_last_seen("test-bucket").await.unwrap();
+        assert!(last.is_none());
+
+        let now = Utc::now();
+        let signal = LifeSignal {
+            bucket: "test-bucket".to_string(),
+            timestamp: now,
+            weight: 1,
+            channel: None,
+        };
+        storage.insert_life_signal(&signal).await.unwrap();
+
+        let last = storage.get_last_seen("test-bucket").await.unwrap();
+        assert!(last.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_delete_signals_before() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        let now = Utc::now();
+        for days in 0..5 {
+            let signal = LifeSignal {
+                bucket: "test-bucket".to_string(),
+                timestamp: now - chrono::Duration::days(days),
+                weight: 1,
+                channel: None,
+            };
+            storage.insert_life_signal(&signal).await.unwrap();
+        }
+
+        let cutoff = now - chrono::Duration::hours(36);
+        assert_eq!(storage.delete_signals_before(cutoff, 2).await.unwrap(), 2);
+        assert_eq!(storage.delete_signals_before(cutoff, 2).await.unwrap(), 1);
+        assert_eq!(storage.delete_signals_before(cutoff, 2).await.unwrap(), 0);
+
+        let total = storage
+            .query_bucket_window(
+                "test-bucket",
+                7 * 24 * 60,
+                now + chrono::Duration::seconds(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+    }
+
+    #[tokio::test]
+    async fn test_signal_partitions() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        let day = partition::day_of(Utc::now().timestamp());
+        let at = |day: i64, hour: i64| {
+            Utc.timestamp_opt(day * partition::DAY_SECONDS + hour * 3600, 0)
+                .unwrap()
+        };
+        let signal = |timestamp| LifeSignal {
+            bucket: "zone-a".to_string(),
+            timestamp,
+            weight: 1,
+            channel: None,
+        };
+        storage
+            .insert_life_signals(&[
+                signal(at(day - 3, 12)),
+                signal(at(day - 1, 23)),
+                signal(at(day, 1)),
+                signal(at(day, 2)),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.partition_days().await.unwrap(),
+            vec![day - 3, day - 1, day]
+        );
+
+        // A window spanning midnight reads both days
+        let window = storage
+            .query_bucket_window("zone-a", 3 * 60, at(day, 1))
+            .await
+            .unwrap();
+        assert_eq!(window, 2);
+        assert_eq!(
+            storage.get_last_seen("zone-a").await.unwrap(),
+            Some(at(day, 2))
+        );
+
+        // Row IDs are unique and ordered across partitions
+        let signals = storage
+            .get_signals_after(&SignalFilter::default(), 0, 3)
+            .await
+            .unwrap();
+        assert_eq!(signals.len(), 3);
+        assert!(signals.windows(2).all(|w| w[0].0 < w[1].0));
+        let rest = storage
+            .get_signals_after(&SignalFilter::default(), signals[2].0, 10)
+            .await
+            .unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].1.timestamp, at(day, 2));
+
+        // Expired days are dropped, the cutoff's day is trimmed
+        let deleted = storage
+            .delete_signals_before(at(day - 1, 23) + chrono::Duration::seconds(1), 10)
+            .await
+            .unwrap();
+        assert_eq!(deleted, 2);
+        assert_eq!(storage.partition_days().await.unwrap(), vec![day - 1, day]);
+        assert_eq!(storage.export_table("life_signals").await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_more_partitions_than_compound_terms() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+
+        // SQLite caps a compound SELECT at 500 terms
+        let now = Utc::now();
+        let signals: Vec<LifeSignal> = (0..600)
+            .map(|days_ago| LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: now - chrono::Duration::days(days_ago),
+                weight: 1,
+                channel: None,
+            })
+            .collect();
+        storage.insert_life_signals(&signals).await.unwrap();
+        assert!(storage.partition_days().await.unwrap().len() >= 600);
+
+        let last_seen = storage.get_last_seen("zone-a").await.unwrap();
+        assert_eq!(last_seen.map(|t| t.timestamp()), Some(now.timestamp()));
+        storage.set_heartbeat("zone-a", 60, now).await.unwrap();
+        let heartbeats = storage.get_heartbeats().await.unwrap();
+        assert_eq!(heartbeats[0].last_seen, last_seen);
+        assert_eq!(storage.get_buckets().await.unwrap().len(), 1);
+
+        let signals = storage
+            .get_signals_after(&SignalFilter::default(), 0, 1000)
+            .await
+            .unwrap();
+        assert_eq!(signals.len(), 600);
+    }
+
+    #[tokio::test]
+    async fn test_tension_history() {
+        use crate::dashboard::{Issue, IssueCategory, IssueSource};
+        use crate::model::IssueSeverity;
+
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        let issue = |country: &str| {
+            Issue::new(
+                IssueSource::Acled,
+                IssueCategory::Conflict,
+                IssueSeverity::Critical,
+                country,
+                "",
+                "Clashes",
+                "Clashes reported",
+                now,
+            )
+        };
+
+        let earlier = DashboardSummary::from_issues(&[issue("Sudan"), issue("Chad")]);
+        let later = DashboardSummary::from_issues(&[issue("Chad")]);
+        storage
+            .insert_tension(now - chrono::Duration::hours(2), &earlier)
+            .await
+            .unwrap();
+        storage.insert_tension(now, &later).await.unwrap();
+
+        let since = now - chrono::Duration::hours(3);
+        let global = storage.get_tension_history(None, since).await.unwrap();
+        let scores: Vec<f64> = global.iter().map(|p| p.score).collect();
+        assert_eq!(scores, [6.0, 3.0]);
+
+        // A country without issues in a recording scores 0
+        let sudan = storage
+            .get_tension_history(Some("sudan"), since)
+            .await
+            .unwrap();
+        let scores: Vec<f64> = sudan.iter().map(|p| p.score).collect();
+        assert_eq!(scores, [3.0, 0.0]);
+
+        let recent = storage
+            .get_tension_history(None, now - chrono::Duration::hours(1))
+            .await
+            .unwrap();
+        assert_eq!(recent.len(), 1);
     }
 
-    /// Get all buckets that have ever had signals (for alert checking).
-    pub async fn get_all_known_buckets(&self) -> anyhow::Result<Vec<String>> {
-        let rows = sqlx::query(
-            r#"
-            SELECT DISTINCT bucket FROM life_signals
-            "#,
-        )
-        .fetch_all(&self.pool)
-        .await?;
+    #[tokio::test]
+    async fn test_dashboard_snapshot_at() {
+        use crate::dashboard::DashboardSummary;
 
-        Ok(rows.iter().map(|r| r.get("bucket")).collect())
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+
+        for hours_ago in [48, 24] {
+            let snapshot = DashboardResponse {
+                timestamp: now - chrono::Duration::hours(hours_ago),
+                summary: DashboardSummary::from_issues(&[]),
+                issues: vec![],
+                errors: vec![],
+                health: vec![],
+                suppressed: vec![],
+            };
+            storage.insert_dashboard_snapshot(&snapshot).await.unwrap();
+        }
+
+        // Before the first snapshot there is nothing to reconstruct
+        let before = storage
+            .get_dashboard_snapshot_at(now - chrono::Duration::hours(72))
+            .await
+            .unwrap();
+        assert!(before.is_none());
+
+        // Between snapshots, the older one is returned
+        let between = storage
+            .get_dashboard_snapshot_at(now - chrono::Duration::hours(36))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            between.timestamp.timestamp(),
+            (now - chrono::Duration::hours(48)).timestamp()
+        );
+
+        // After both, the latest one is returned
+        let latest = storage
+            .get_dashboard_snapshot_at(now)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            latest.timestamp.timestamp(),
+            (now - chrono::Duration::hours(24)).timestamp()
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[tokio::test]
+    async fn test_orphaned_rows() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc::now();
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "zone-a".to_string(),
+                timestamp: now,
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+        assert!(storage.orphaned_rows().await.unwrap().is_empty());
+
+        // zone-gone has settings, snapshots and alerts but no signals
+        let snapshot = |bucket: &str| WarmthSnapshot {
+            bucket: bucket.to_string(),
+            window_minutes: 10,
+            taken_at: now,
+            status: WarmthStatus::Dead,
+            current_window_total: 0,
+            recent_average: 1.0,
+        };
+        storage
+            .insert_warmth_snapshots(&[snapshot("zone-a"), snapshot("zone-gone")])
+            .await
+            .unwrap();
+        let alert = |bucket: &str, subject| Alert {
+            bucket: bucket.to_string(),
+            subject,
+            status: WarmthStatus::Dead,
+            last_seen_timestamp: None,
+            recent_average: 1.0,
+            reason: AlertReason::WarmthDrop,
+            message: format!("{} is dead", bucket),
+            severity: IssueSeverity::Critical,
+            distressed_since: None,
+        };
+        storage
+            .record_alerts(
+                &[
+                    alert("zone-gone", AlertSubject::Bucket),
+                    // Group alerts carry the group name, not a bucket
+                    alert("north", AlertSubject::Group),
+                ],
+                now,
+            )
+            .await
+            .unwrap();
+        storage
+            .set_group("north", &["zone-a".to_string(), "zone-gone".to_string()])
+            .await
+            .unwrap();
+        storage.set_heartbeat("zone-gone", 60, now).await.unwrap();
+
+        let orphaned = storage.orphaned_rows().await.unwrap();
+
+        assert_eq!(
+            orphaned,
+            vec![
+                ("warmth_snapshots".to_string(), 1),
+                ("alert_history".to_string(), 1),
+                ("bucket_groups".to_string(), 1),
+                ("buckets".to_string(), 1),
+            ]
+        );
+    }
 
     #[tokio::test]
-    async fn test_insert_and_query() {
+    async fn test_audit_log_is_append_only() {
         let storage = Storage::new("sqlite::memory:").await.unwrap();
+        storage
+            .insert_audit_entry(Utc::now(), "ops", "bucket.archive", None)
+            .await
+            .unwrap();
+
+        assert!(
+            sqlx::query("UPDATE audit_log SET actor = 'someone-else'")
+                .execute(&storage.pool)
+                .await
+                .is_err()
+        );
+        assert!(
+            sqlx::query("DELETE FROM audit_log")
+                .execute(&storage.pool)
+                .await
+                .is_err()
+        );
+        assert_eq!(storage.get_audit_entries(None, 10).await.unwrap().len(), 1);
+    }
 
+    #[tokio::test]
+    async fn test_heartbeat_registration() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
         let now = Utc::now();
-        let signal = LifeSignal {
-            bucket: "test-bucket".to_string(),
-            timestamp: now,
-            weight: 5,
+
+        storage.set_heartbeat("site-1", 900, now).await.unwrap();
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "site-1".to_string(),
+                timestamp: now,
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+
+        let heartbeats = storage.get_heartbeats().await.unwrap();
+        assert_eq!(heartbeats.len(), 1);
+        assert_eq!(heartbeats[0].interval_seconds, 900);
+        assert_eq!(
+            heartbeats[0].last_seen.unwrap().timestamp(),
+            now.timestamp()
+        );
+
+        assert!(storage.clear_heartbeat("site-1").await.unwrap());
+        assert!(!storage.clear_heartbeat("site-1").await.unwrap());
+        assert!(storage.get_heartbeats().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bucket_timezone() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        assert_eq!(storage.get_timezone("site-1").await.unwrap(), None);
+
+        storage
+            .set_heartbeat("site-1", 900, Utc::now())
+            .await
+            .unwrap();
+        storage.set_timezone("site-1", "Asia/Kabul").await.unwrap();
+        assert_eq!(
+            storage.get_timezone("site-1").await.unwrap().as_deref(),
+            Some("Asia/Kabul")
+        );
+        // Other settings on the bucket are left alone
+        assert_eq!(storage.get_heartbeats().await.unwrap().len(), 1);
+
+        assert!(storage.clear_timezone("site-1").await.unwrap());
+        assert!(!storage.clear_timezone("site-1").await.unwrap());
+        assert_eq!(storage.get_timezone("site-1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_baseline() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        assert_eq!(storage.get_baseline("site-1").await.unwrap(), None);
+
+        storage
+            .set_baseline("site-1", Baseline::Windows(48))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_baseline("site-1").await.unwrap(),
+            Some(Baseline::Windows(48))
+        );
+
+        // Replacing a window count with a span clears the count
+        storage
+            .set_baseline("site-1", Baseline::SpanMinutes(10_080))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get_baseline("site-1").await.unwrap(),
+            Some(Baseline::SpanMinutes(10_080))
+        );
+
+        assert!(storage.clear_baseline("site-1").await.unwrap());
+        assert!(!storage.clear_baseline("site-1").await.unwrap());
+        assert_eq!(storage.get_baseline("site-1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_bucket_meta() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        assert_eq!(storage.get_bucket_meta("site-1").await.unwrap(), None);
+
+        storage.set_timezone("site-1", "Asia/Kabul").await.unwrap();
+        assert_eq!(storage.get_bucket_meta("site-1").await.unwrap(), None);
+
+        let thresholds = WarmthThresholds {
+            stressed_ratio: 0.5,
+            collapsing_ratio: 0.1,
+            ewma_alpha: Some(0.4),
+        };
+        let meta = BucketMeta {
+            display_name: Some("Gulu market".to_string()),
+            description: None,
+            expected_interval_minutes: Some(15),
+            thresholds: Some(thresholds),
         };
+        storage.set_bucket_meta("site-1", &meta).await.unwrap();
+        assert_eq!(storage.get_bucket_meta("site-1").await.unwrap(), Some(meta));
+        let buckets = vec!["site-1".to_string(), "site-2".to_string()];
+        assert_eq!(
+            storage.get_thresholds(&buckets).await.unwrap(),
+            BTreeMap::from([("site-1".to_string(), thresholds)])
+        );
 
-        storage.insert_life_signal(&signal).await.unwrap();
+        // Replacing the metadata clears fields left out
+        let renamed = BucketMeta {
+            display_name: Some("Gulu central market".to_string()),
+            ..Default::default()
+        };
+        storage.set_bucket_meta("site-1", &renamed).await.unwrap();
+        assert_eq!(
+            storage.get_bucket_meta("site-1").await.unwrap(),
+            Some(renamed)
+        );
+        assert!(storage.get_thresholds(&buckets).await.unwrap().is_empty());
 
-        let total = storage
-            .query_bucket_window("test-bucket", 10, now + chrono::Duration::seconds(1))
+        assert!(storage.clear_bucket_meta("site-1").await.unwrap());
+        assert!(!storage.clear_bucket_meta("site-1").await.unwrap());
+        assert_eq!(storage.get_bucket_meta("site-1").await.unwrap(), None);
+        // Other settings on the bucket are left alone
+        assert_eq!(
+            storage.get_timezone("site-1").await.unwrap().as_deref(),
+            Some("Asia/Kabul")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_switch_arm_and_notify() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc.timestamp_opt(10_000, 0).unwrap();
+
+        storage.arm_switch("site-1", 3600, now).await.unwrap();
+        let switches = storage.get_switches().await.unwrap();
+        assert_eq!(switches.len(), 1);
+        assert_eq!(switches[0].countdown_seconds, 3600);
+        assert_eq!(switches[0].notified_steps, 0);
+
+        storage.set_switch_notified("site-1", now, 2).await.unwrap();
+        let switches = storage.get_switches().await.unwrap();
+        assert_eq!(switches[0].notified_deadline, Some(now));
+        assert_eq!(switches[0].notified_steps, 2);
+
+        // Re-arming restarts the escalation sequence
+        storage.arm_switch("site-1", 600, now).await.unwrap();
+        assert_eq!(storage.get_switches().await.unwrap()[0].notified_steps, 0);
+
+        assert!(storage.disarm_switch("site-1").await.unwrap());
+        assert!(!storage.disarm_switch("site-1").await.unwrap());
+        assert!(storage.get_switches().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bucket_groups() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let members = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        storage
+            .set_group("north", &members(&["zone-5", "zone-1"]))
+            .await
+            .unwrap();
+        storage
+            .set_group("south", &members(&["zone-3"]))
             .await
             .unwrap();
 
-        assert_eq!(total, 5);
+        let north = storage.get_group("north").await.unwrap().unwrap();
+        assert_eq!(north.buckets, members(&["zone-1", "zone-5"]));
+        assert_eq!(storage.get_groups().await.unwrap().len(), 2);
+
+        // Replacing a group replaces its members
+        storage
+            .set_group("north", &members(&["zone-2"]))
+            .await
+            .unwrap();
+        let north = storage.get_group("north").await.unwrap().unwrap();
+        assert_eq!(north.buckets, members(&["zone-2"]));
+
+        assert!(storage.delete_group("north").await.unwrap());
+        assert!(!storage.delete_group("north").await.unwrap());
+        assert!(storage.get_group("north").await.unwrap().is_none());
     }
 
     #[tokio::test]
-    async fn test_multiple_signals() {
+    async fn test_window_totals() {
         let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let now = Utc.timestamp_opt(100_000, 0).unwrap();
+
+        for (minutes_ago, weight) in [(0, 1), (5, 2), (10, 4), (25, 8), (40, 16)] {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: "zone-a".to_string(),
+                    timestamp: now - chrono::Duration::minutes(minutes_ago),
+                    weight,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        // Windows (now-30m, now-20m], (now-20m, now-10m], (now-10m, now]
+        let totals = storage.window_totals("zone-a", 10, 3, now).await.unwrap();
+        assert_eq!(totals, vec![8, 4, 3]);
+    }
 
+    #[tokio::test]
+    async fn test_archive_bucket() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
         let now = Utc::now();
+        for bucket in ["site-old", "site-new"] {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: now,
+                    weight: 1,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+            storage.set_heartbeat(bucket, 60, now).await.unwrap();
+        }
 
-        for i in 0..5 {
-            let signal = LifeSignal {
-                bucket: "test-bucket".to_string(),
-                timestamp: now - chrono::Duration::minutes(i),
-                weight: 10,
-            };
-            storage.insert_life_signal(&signal).await.unwrap();
+        storage.archive_bucket("site-old", now).await.unwrap();
+        let later = now + chrono::Duration::hours(1);
+        storage.archive_bucket("site-old", later).await.unwrap();
+
+        assert_eq!(
+            storage.get_all_known_buckets().await.unwrap(),
+            vec!["site-new".to_string()]
+        );
+        assert_eq!(storage.get_heartbeats().await.unwrap().len(), 1);
+        let buckets = storage.get_buckets().await.unwrap();
+        assert_eq!(buckets[1].bucket, "site-old");
+        assert_eq!(
+            buckets[1].archived_at.map(|at| at.timestamp()),
+            Some(now.timestamp())
+        );
+        // History is kept
+        assert_eq!(
+            storage.window_totals("site-old", 10, 1, now).await.unwrap(),
+            vec![1]
+        );
+
+        assert!(storage.unarchive_bucket("site-old").await.unwrap());
+        assert!(!storage.unarchive_bucket("site-old").await.unwrap());
+        assert_eq!(storage.get_all_known_buckets().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rename_and_merge_buckets() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let start = Utc.timestamp_opt(1700000000, 0).unwrap();
+        let day_later = start + chrono::Duration::days(1);
+        for (bucket, at, weight) in [
+            ("zone-a", start, 1),
+            ("zone-a", day_later, 2),
+            ("zone-b", day_later, 4),
+        ] {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: at,
+                    weight,
+                    channel: None,
+                })
+                .await
+                .unwrap();
         }
+        storage.set_heartbeat("zone-a", 60, start).await.unwrap();
+        storage.set_heartbeat("zone-b", 120, start).await.unwrap();
+        storage
+            .set_group("north", &["zone-a".to_string(), "zone-b".to_string()])
+            .await
+            .unwrap();
+        let snapshot = |bucket: &str, status| WarmthSnapshot {
+            bucket: bucket.to_string(),
+            window_minutes: 10,
+            taken_at: day_later,
+            status,
+            current_window_total: 0,
+            recent_average: 1.0,
+        };
+        storage
+            .insert_warmth_snapshots(&[
+                snapshot("zone-a", WarmthStatus::Alive),
+                snapshot("zone-b", WarmthStatus::Dead),
+            ])
+            .await
+            .unwrap();
+        let note = |incident_id: &str| Annotation {
+            incident_id: incident_id.to_string(),
+            timestamp: day_later,
+            kind: AnnotationKind::Note,
+            actor: "ops".to_string(),
+            text: Some("generator down".to_string()),
+        };
+        storage
+            .insert_annotation(&note("zone-b@1700000000"))
+            .await
+            .unwrap();
+        storage
+            .insert_annotation(&note("zone-b@x@1"))
+            .await
+            .unwrap();
 
-        let total = storage
-            .query_bucket_window("test-bucket", 10, now + chrono::Duration::seconds(1))
+        // A rename never lands on an existing bucket
+        assert!(
+            storage
+                .rename_bucket("zone-b", "zone-a", "cli", start)
+                .await
+                .is_err()
+        );
+        assert!(
+            storage
+                .rename_bucket("zone-x", "zone-y", "cli", start)
+                .await
+                .is_err()
+        );
+
+        let renamed = storage
+            .rename_bucket("zone-b", "zone-c", "cli", start)
             .await
             .unwrap();
+        assert_eq!(renamed.moved["buckets"], 1);
+        assert_eq!(renamed.annotations, 1);
+        assert!(renamed.dropped.is_empty());
+        assert_eq!(
+            storage
+                .get_annotations("zone-c@1700000000")
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        // Only IDs of that bucket's incidents change
+        assert_eq!(
+            storage.get_annotations("zone-b@x@1").await.unwrap().len(),
+            1
+        );
 
-        assert_eq!(total, 50);
+        // The target's own rows win over the merged bucket's
+        let merged = storage
+            .merge_buckets(
+                &["zone-c".to_string(), "zone-a".to_string()],
+                "zone-a",
+                "cli",
+                day_later,
+            )
+            .await
+            .unwrap();
+        assert_eq!(merged.dropped["buckets"], 1);
+        assert_eq!(merged.dropped["warmth_snapshots"], 1);
+        assert_eq!(
+            storage
+                .window_totals("zone-a", 10, 1, day_later)
+                .await
+                .unwrap(),
+            vec![6]
+        );
+        assert_eq!(
+            storage.get_all_known_buckets().await.unwrap(),
+            vec!["zone-a".to_string()]
+        );
+        let heartbeats = storage.get_heartbeats().await.unwrap();
+        assert_eq!(heartbeats.len(), 1);
+        assert_eq!(heartbeats[0].interval_seconds, 60);
+        let north = storage.get_group("north").await.unwrap().unwrap();
+        assert_eq!(north.buckets, vec!["zone-a".to_string()]);
+        let snapshots = storage
+            .get_warmth_snapshots("zone-a", start, day_later + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].status, WarmthStatus::Alive);
+
+        let audit = storage
+            .get_audit_entries(Some(actions::BUCKET_RENAME), 10)
+            .await
+            .unwrap();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0].details.as_deref(), Some("zone-c -> zone-a"));
     }
 
     #[tokio::test]
-    async fn test_get_last_seen() {
+    async fn test_stats() {
         let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        for (days, bucket) in [(0, "zone-a"), (1, "zone-b"), (2, "zone-a")] {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: start + chrono::Duration::days(days),
+                    weight: 1,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+        storage
+            .insert_audit_entry(start, "ops", "backup", None)
+            .await
+            .unwrap();
 
-        // No signals yet
-        let last = storage.get_last_seen("test-bucket").await.unwrap();
-        assert!(last.is_none());
+        let stats = storage.stats().await.unwrap();
+        assert_eq!(stats.tables["life_signals"], 3);
+        assert_eq!(stats.tables["audit_log"], 1);
+        assert!(!stats.tables.contains_key("_sqlx_migrations"));
+        assert_eq!(stats.partitions, 3);
+        assert_eq!(
+            stats.buckets,
+            vec![
+                BucketStorageStats {
+                    bucket: "zone-a".to_string(),
+                    rows: 2,
+                    oldest: start,
+                    newest: start + chrono::Duration::days(2),
+                },
+                BucketStorageStats {
+                    bucket: "zone-b".to_string(),
+                    rows: 1,
+                    oldest: start + chrono::Duration::days(1),
+                    newest: start + chrono::Duration::days(1),
+                },
+            ]
+        );
+        assert_eq!(stats.oldest_signal, Some(start));
+        assert_eq!(stats.newest_signal, Some(start + chrono::Duration::days(2)));
+        assert!(stats.database_bytes > 0);
+        assert_eq!(stats.wal_bytes, None);
+    }
 
-        let now = Utc::now();
+    #[tokio::test]
+    async fn test_get_signals_after() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        for (i, bucket) in ["zone-a", "zone-b", "zone-a", "zone-a"].iter().enumerate() {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: start + chrono::Duration::minutes(i as i64),
+                    weight: 1,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let all = SignalFilter::default();
+        let first = storage.get_signals_after(&all, 0, 3).await.unwrap();
+        assert_eq!(first.len(), 3);
+        let rest = storage
+            .get_signals_after(&all, first[2].0, 3)
+            .await
+            .unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].1.timestamp, start + chrono::Duration::minutes(3));
+
+        let filter = SignalFilter {
+            bucket: Some("zone-a".to_string()),
+            from: Some(start + chrono::Duration::minutes(1)),
+            to: Some(start + chrono::Duration::minutes(3)),
+        };
+        let page = storage.get_signals_after(&filter, 0, 10).await.unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].1.timestamp, start + chrono::Duration::minutes(2));
+    }
+
+    #[tokio::test]
+    async fn test_channel_totals() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        // On a window boundary, so each baseline window is one SQL bin
+        let now = Utc.timestamp_opt(1_700_001_000, 0).unwrap();
+        let signal = |minutes_ago: i64, weight: i32, channel: Option<&str>| LifeSignal {
+            bucket: "zone-a".to_string(),
+            timestamp: now - chrono::Duration::minutes(minutes_ago),
+            weight,
+            channel: channel.map(String::from),
+        };
+        storage
+            .insert_life_signals(&[
+                signal(25, 4, Some("internet")),
+                signal(15, 4, Some("internet")),
+                signal(25, 2, Some("sms")),
+                signal(15, 2, Some("sms")),
+                signal(5, 2, Some("sms")),
+                signal(1, 1, None),
+            ])
+            .await
+            .unwrap();
+
+        let totals = storage.channel_totals("zone-a", 10, 2, now).await.unwrap();
+        assert_eq!(
+            totals,
+            vec![
+                (None, 1, 0.0),
+                (Some("internet".to_string()), 0, 4.0),
+                (Some("sms".to_string()), 2, 2.0),
+            ]
+        );
+        // The channels add up to the bucket as a whole
+        assert_eq!(
+            storage
+                .query_bucket_window("zone-a", 10, now)
+                .await
+                .unwrap(),
+            3
+        );
+
+        let page = storage
+            .get_signals_after(&SignalFilter::default(), 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page[0].1.channel.as_deref(), Some("internet"));
+        assert_eq!(page[5].1.channel, None);
+    }
+
+    #[tokio::test]
+    async fn test_failed_flush_keeps_pending() {
+        let storage = Storage::new("sqlite::memory:")
+            .await
+            .unwrap()
+            .with_write_buffer(2, Duration::from_secs(3600));
         let signal = LifeSignal {
-            bucket: "test-bucket".to_string(),
-            timestamp: now,
+            bucket: "zone-a".to_string(),
+            timestamp: Utc::now(),
             weight: 1,
+            channel: None,
         };
         storage.insert_life_signal(&signal).await.unwrap();
 
-        let last = storage.get_last_seen("test-bucket").await.unwrap();
-        assert!(last.is_some());
+        storage.close().await;
+        assert!(storage.insert_life_signal(&signal).await.is_err());
+        assert_eq!(storage.write_buffer().unwrap().len().await, 1);
+        assert!(storage.flush_writes().await.is_err());
+        assert_eq!(storage.write_buffer().unwrap().len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_options() {
+        let path = std::env::temp_dir().join(format!("infrared-pragma-{}.db", std::process::id()));
+        let url = format!("sqlite:{}?mode=rwc", path.display());
+        let pragma = |storage: Storage, name: &'static str| async move {
+            sqlx::query_scalar::<_, String>(&format!(
+                "SELECT CAST({} AS TEXT) FROM pragma_{}",
+                name, name
+            ))
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap()
+        };
+
+        // WAL by default, so concurrent writers wait rather than fail
+        let storage = Storage::new(&url).await.unwrap();
+        assert_eq!(pragma(storage.clone(), "journal_mode").await, "wal");
+        assert_eq!(pragma(storage.clone(), "synchronous").await, "1");
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let storage = storage.clone();
+                tokio::spawn(async move {
+                    for _ in 0..25 {
+                        storage
+                            .insert_life_signal(&LifeSignal {
+                                bucket: format!("zone-{}", i),
+                                timestamp: Utc::now(),
+                                weight: 1,
+                                channel: None,
+                            })
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.await.unwrap();
+        }
+        assert_eq!(storage.get_all_known_buckets().await.unwrap().len(), 8);
+
+        // Queries run on their own read-only connections, so a long read
+        // neither blocks a write nor can write itself
+        let mut read = storage.read_pool.begin().await.unwrap();
+        let before: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM buckets")
+            .fetch_one(&mut *read)
+            .await
+            .unwrap();
+        storage.set_timezone("zone-new", "UTC").await.unwrap();
+        assert!(
+            sqlx::query("DELETE FROM buckets")
+                .execute(&mut *read)
+                .await
+                .is_err()
+        );
+        read.rollback().await.unwrap();
+        let after: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM buckets")
+            .fetch_one(&storage.read_pool)
+            .await
+            .unwrap();
+        assert_eq!(after, before + 1);
+        storage.close().await;
+
+        let options = ConnectionOptions {
+            journal_mode: JournalMode::Delete,
+            synchronous: Synchronous::Full,
+            busy_timeout: Duration::from_secs(1),
+            cache_size_kib: Some(4096),
+            ..ConnectionOptions::default()
+        };
+        let storage = Storage::open_with(&url, Migration::Refuse, options)
+            .await
+            .unwrap();
+        assert_eq!(pragma(storage.clone(), "journal_mode").await, "delete");
+        assert_eq!(pragma(storage.clone(), "synchronous").await, "2");
+        assert_eq!(pragma(storage.clone(), "cache_size").await, "-4096");
+        storage.close().await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(JournalMode::parse("WAL").unwrap(), JournalMode::Wal);
+        assert!(JournalMode::parse("memory").is_err());
+        assert_eq!(Synchronous::parse("extra").unwrap(), Synchronous::Extra);
+        assert!(Synchronous::parse("2").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_schema_version_pinning() {
+        let path = std::env::temp_dir().join(format!("infrared-schema-{}.db", std::process::id()));
+        let url = format!("sqlite:{}?mode=rwc", path.display());
+        let set_version = |storage: Storage, version: i64| async move {
+            sqlx::query(&format!("PRAGMA user_version = {}", version))
+                .execute(&storage.pool)
+                .await
+                .unwrap();
+            storage.close().await;
+        };
+
+        // A fresh database is created without ceremony
+        let storage = Storage::new(&url).await.unwrap();
+        assert_eq!(storage.schema_version().await.unwrap(), SCHEMA_VERSION);
+        assert_eq!(
+            storage.schema_written_by().await.unwrap().as_deref(),
+            Some(env!("CARGO_PKG_VERSION"))
+        );
+
+        // An older schema needs an explicit migration
+        set_version(storage, SCHEMA_VERSION - 1).await;
+        let err = Storage::new(&url).await.err().unwrap().to_string();
+        assert!(err.contains("--migrate"), "{}", err);
+        let storage = Storage::open(&url, Migration::Apply).await.unwrap();
+        assert_eq!(storage.schema_version().await.unwrap(), SCHEMA_VERSION);
+
+        // A newer schema is refused outright
+        set_version(storage, SCHEMA_VERSION + 1).await;
+        let err = Storage::open(&url, Migration::Apply)
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("upgrade infrared"), "{}", err);
+        assert!(err.contains(env!("CARGO_PKG_VERSION")), "{}", err);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_schema_version_follows_migrations() {
+        let last = MIGRATOR.iter().map(|m| m.version).max();
+        assert_eq!(last, Some(SCHEMA_VERSION));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_schema_adopted() {
+        let path = std::env::temp_dir().join(format!("infrared-legacy-{}.db", std::process::id()));
+        let url = format!("sqlite:{}?mode=rwc", path.display());
+
+        // A version 3 database, as created before migrations
+        let legacy = Storage::connect(&url).await.unwrap();
+        for statement in [
+            "CREATE TABLE life_signals (id INTEGER PRIMARY KEY AUTOINCREMENT, \
+             bucket TEXT NOT NULL, ts INTEGER NOT NULL, weight INTEGER NOT NULL)",
+            "CREATE TABLE buckets (bucket TEXT PRIMARY KEY, heartbeat_seconds INTEGER, \
+             heartbeat_since INTEGER)",
+            "INSERT INTO life_signals (bucket, ts, weight) VALUES ('zone-a', 1700000000, 2)",
+            "INSERT INTO buckets (bucket, heartbeat_seconds) VALUES ('zone-a', 60)",
+            "PRAGMA user_version = 3",
+        ] {
+            sqlx::query(statement).execute(&legacy.pool).await.unwrap();
+        }
+        legacy.close().await;
+
+        let storage = Storage::open(&url, Migration::Apply).await.unwrap();
+        assert_eq!(storage.schema_version().await.unwrap(), SCHEMA_VERSION);
+        let applied: Vec<i64> =
+            sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success")
+                .fetch_all(&storage.pool)
+                .await
+                .unwrap();
+        let versions: Vec<i64> = MIGRATOR.iter().map(|m| m.version).collect();
+        assert_eq!(applied, versions);
+
+        // Old rows are kept, and the added columns work
+        storage.set_country("zone-a", "SD").await.unwrap();
+        assert_eq!(
+            storage.get_country("zone-a").await.unwrap().as_deref(),
+            Some("SD")
+        );
+        assert_eq!(storage.get_heartbeats().await.unwrap().len(), 1);
+        let at = Utc.timestamp_opt(1700000000, 0).unwrap();
+        assert_eq!(
+            storage.query_bucket_window("zone-a", 1, at).await.unwrap(),
+            2
+        );
+
+        // Reopening runs nothing new
+        storage.close().await;
+        Storage::new(&url).await.unwrap();
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_alert_history_episodes() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let start = Utc.timestamp_opt(1700000000, 0).unwrap();
+        let minutes = |m| start + chrono::Duration::minutes(m);
+        let alert = |bucket: &str, status, severity| Alert {
+            bucket: bucket.to_string(),
+            subject: AlertSubject::Bucket,
+            status,
+            last_seen_timestamp: None,
+            recent_average: 10.0,
+            reason: AlertReason::WarmthDrop,
+            message: format!("{} is {}", bucket, status.name()),
+            severity,
+            distressed_since: None,
+        };
+
+        // Seen every few minutes: one episode, escalating
+        let collapsing = alert("zone-a", WarmthStatus::Collapsing, IssueSeverity::Warning);
+        storage
+            .record_alerts(std::slice::from_ref(&collapsing), start)
+            .await
+            .unwrap();
+        storage
+            .record_alerts(&[collapsing], minutes(5))
+            .await
+            .unwrap();
+        let dead = alert("zone-a", WarmthStatus::Dead, IssueSeverity::Critical);
+        storage
+            .record_alerts(std::slice::from_ref(&dead), minutes(10))
+            .await
+            .unwrap();
+
+        // Quiet for longer than the gap: a new episode
+        storage.record_alerts(&[dead], minutes(60)).await.unwrap();
+        let other = alert("zone-b", WarmthStatus::Dead, IssueSeverity::Warning);
+        storage.record_alerts(&[other], minutes(60)).await.unwrap();
+
+        let history = storage
+            .get_alert_history(None, start, minutes(61), 100)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 3);
+        let first = history.iter().find(|r| r.last_seen == minutes(10)).unwrap();
+        assert_eq!(first.bucket, "zone-a");
+        assert_eq!(first.first_seen, start);
+        assert_eq!(first.status, WarmthStatus::Dead);
+        assert_eq!(first.severity, IssueSeverity::Critical);
+        assert_eq!(first.message, "zone-a is dead");
+
+        let zone_a = storage
+            .get_alert_history(Some("zone-a"), start, minutes(61), 100)
+            .await
+            .unwrap();
+        assert_eq!(zone_a.len(), 2);
+        assert_eq!(zone_a[0].first_seen, minutes(60));
+        assert_eq!(zone_a[1].first_seen, start);
+
+        // Only episodes overlapping the range
+        let early = storage
+            .get_alert_history(None, start, minutes(30), 100)
+            .await
+            .unwrap();
+        assert_eq!(early.len(), 1);
+        assert_eq!(early[0].id, first.id);
+        let limited = storage
+            .get_alert_history(None, start, minutes(61), 1)
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
     }
 }
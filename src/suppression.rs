@@ -0,0 +1,306 @@
+//! Operator rules suppressing known-noisy dashboard issues.
+//!
+//! Some issues are accurate but useless to a given deployment: unverified
+//! Cloudflare anomalies for a country whose traffic is always erratic, or
+//! ReliefWeb cold waves in a region that has them every winter. A
+//! [`SuppressionRules`] file names such issues by source, category,
+//! country and [`IssueDetails`] fields. Matching issues are dropped right
+//! after a fetch, before they reach summaries, tension scores, events and
+//! notifications.
+//!
+//! Nothing disappears invisibly: each response lists how many issues every
+//! rule suppressed, per source, under `suppressed`, and the summary counts
+//! them in `suppressed_issues`.
+//!
+//! # Configuration File
+//!
+//! Every field but `name` is optional; a rule suppresses issues matching
+//! all the fields it sets. Sources are named as accepted by `?source=`,
+//! countries by code or name, and `details` values are compared with the
+//! issue's `details` fields (strings case-insensitively).
+//!
+//! ```json
+//! [
+//!     { "name": "cloudflare-unverified-ye", "source": "cloudflare", "country": "YE",
+//!       "details": { "verified": false } },
+//!     { "name": "reliefweb-cold-wave", "source": "reliefweb",
+//!       "details": { "disaster_type": "Cold Wave" } }
+//! ]
+//! ```
+//!
+//! # Privacy
+//!
+//! Rules and counts only refer to sources, categories and countries.
+//!
+//! [`IssueDetails`]: crate::issue_details::IssueDetails
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::dashboard::{Issue, IssueCategory, IssueSource};
+
+/// One rule as written in the configuration file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: String,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    country: Option<String>,
+    #[serde(default)]
+    details: Map<String, Value>,
+}
+
+/// A rule suppressing the issues matching all of its set fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuppressionRule {
+    /// Name reported with the rule's suppressed count.
+    pub name: String,
+
+    pub source: Option<IssueSource>,
+
+    pub category: Option<IssueCategory>,
+
+    /// Country code or name, matched against every location of an issue.
+    pub country: Option<String>,
+
+    /// Fields the issue's `details` must have, with these values.
+    pub details: Map<String, Value>,
+}
+
+impl SuppressionRule {
+    /// Whether this rule suppresses `issue`.
+    pub fn matches(&self, issue: &Issue) -> bool {
+        if self.source.is_some_and(|s| s != issue.source)
+            || self.category.is_some_and(|c| c != issue.category)
+        {
+            return false;
+        }
+        if let Some(country) = &self.country {
+            let codes = std::iter::once(issue.location_code.as_str())
+                .chain(issue.locations.iter().map(|l| l.code.as_str()));
+            if !codes
+                .chain(issue.location_names())
+                .any(|c| c.eq_ignore_ascii_case(country))
+            {
+                return false;
+            }
+        }
+        if self.details.is_empty() {
+            return true;
+        }
+        let Some(Value::Object(details)) = issue
+            .details
+            .as_ref()
+            .and_then(|d| serde_json::to_value(d).ok())
+        else {
+            return false;
+        };
+        self.details
+            .iter()
+            .all(|(key, expected)| match (details.get(key), expected) {
+                (Some(Value::String(actual)), Value::String(expected)) => {
+                    actual.eq_ignore_ascii_case(expected)
+                }
+                (Some(actual), expected) => actual == expected,
+                (None, _) => false,
+            })
+    }
+}
+
+/// Issues one rule suppressed from one source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuppressedIssues {
+    /// Name of the rule.
+    pub rule: String,
+
+    pub source: IssueSource,
+
+    pub count: usize,
+}
+
+/// Ordered suppression rules; an issue is counted under the first match.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SuppressionRules {
+    rules: Vec<SuppressionRule>,
+}
+
+impl SuppressionRules {
+    /// Parse a suppression rules file.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let specs: Vec<RuleSpec> = serde_json::from_str(json)?;
+        let mut rules: Vec<SuppressionRule> = Vec::with_capacity(specs.len());
+        for spec in specs {
+            anyhow::ensure!(!spec.name.trim().is_empty(), "rule names must not be empty");
+            anyhow::ensure!(
+                !rules.iter().any(|r| r.name == spec.name),
+                "duplicate rule '{}'",
+                spec.name
+            );
+            let source = spec
+                .source
+                .map(|name| {
+                    IssueSource::parse(&name.to_lowercase())
+                        .ok_or_else(|| anyhow::anyhow!("unknown source '{}'", name))
+                })
+                .transpose()?;
+            let category = spec
+                .category
+                .map(|name| {
+                    IssueCategory::parse(&name.to_lowercase())
+                        .ok_or_else(|| anyhow::anyhow!("unknown category '{}'", name))
+                })
+                .transpose()?;
+            anyhow::ensure!(
+                source.is_some()
+                    || category.is_some()
+                    || spec.country.is_some()
+                    || !spec.details.is_empty(),
+                "rule '{}' would suppress every issue",
+                spec.name
+            );
+            rules.push(SuppressionRule {
+                name: spec.name,
+                source,
+                category,
+                country: spec.country.map(|c| c.trim().to_string()),
+                details: spec.details,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// The configured rules, in order.
+    pub fn rules(&self) -> &[SuppressionRule] {
+        &self.rules
+    }
+
+    /// Remove suppressed issues from `issues`, returning how many each rule
+    /// suppressed per source, in rule order.
+    pub fn apply(&self, issues: &mut Vec<Issue>) -> Vec<SuppressedIssues> {
+        let mut suppressed: Vec<SuppressedIssues> = Vec::new();
+        if self.rules.is_empty() {
+            return suppressed;
+        }
+        issues.retain(|issue| {
+            let Some(rule) = self.rules.iter().find(|r| r.matches(issue)) else {
+                return true;
+            };
+            match suppressed
+                .iter_mut()
+                .find(|s| s.rule == rule.name && s.source == issue.source)
+            {
+                Some(entry) => entry.count += 1,
+                None => suppressed.push(SuppressedIssues {
+                    rule: rule.name.clone(),
+                    source: issue.source,
+                    count: 1,
+                }),
+            }
+            false
+        });
+        let position = |name: &str| self.rules.iter().position(|r| r.name == name);
+        suppressed.sort_by_key(|s| position(&s.rule));
+        suppressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issue_details::{DisasterDetails, TrafficAnomalyDetails};
+    use chrono::Utc;
+
+    fn anomaly(code: &str, verified: bool) -> Issue {
+        Issue::new(
+            IssueSource::CloudflareRadar,
+            IssueCategory::TrafficAnomaly,
+            crate::dashboard::IssueSeverity::Warning,
+            code,
+            code,
+            "Traffic anomaly",
+            "Test",
+            Utc::now(),
+        )
+        .with_details(TrafficAnomalyDetails {
+            verified: Some(verified),
+            ..TrafficAnomalyDetails::default()
+        })
+    }
+
+    fn disaster(disaster_type: &str) -> Issue {
+        Issue::new(
+            IssueSource::ReliefWeb,
+            IssueCategory::Disaster,
+            crate::dashboard::IssueSeverity::Warning,
+            "Mongolia",
+            "MNG",
+            disaster_type,
+            "Test",
+            Utc::now(),
+        )
+        .with_details(DisasterDetails {
+            disaster_type: disaster_type.to_string(),
+            reliefweb_id: "1".to_string(),
+            glide: None,
+            status: "ongoing".to_string(),
+            historical_context: None,
+        })
+    }
+
+    #[test]
+    fn test_suppression_rules() {
+        let rules = SuppressionRules::from_json(
+            r#"[
+                { "name": "cf-unverified-ye", "source": "cloudflare", "country": "ye",
+                  "details": { "verified": false } },
+                { "name": "cold-wave", "source": "ReliefWeb",
+                  "details": { "disaster_type": "cold wave" } }
+            ]"#,
+        )
+        .unwrap();
+
+        let mut issues = vec![
+            anomaly("YE", false),
+            anomaly("YE", false),
+            anomaly("YE", true),
+            anomaly("SD", false),
+            disaster("Cold Wave"),
+            disaster("Flood"),
+        ];
+        let suppressed = rules.apply(&mut issues);
+
+        assert_eq!(issues.len(), 3);
+        assert_eq!(
+            suppressed,
+            vec![
+                SuppressedIssues {
+                    rule: "cf-unverified-ye".to_string(),
+                    source: IssueSource::CloudflareRadar,
+                    count: 2,
+                },
+                SuppressedIssues {
+                    rule: "cold-wave".to_string(),
+                    source: IssueSource::ReliefWeb,
+                    count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_rejects() {
+        for json in [
+            r#"[{ "name": "all" }]"#,
+            r#"[{ "name": "x", "source": "myspace" }]"#,
+            r#"[{ "name": "x", "category": "weather" }]"#,
+            r#"[{ "name": "x", "source": "ioda", "severity": "info" }]"#,
+            r#"[{ "name": "x", "source": "ioda" }, { "name": "x", "source": "tor" }]"#,
+        ] {
+            assert!(SuppressionRules::from_json(json).is_err(), "{}", json);
+        }
+    }
+}
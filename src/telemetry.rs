@@ -0,0 +1,199 @@
+//! Anonymous aggregate telemetry.
+//!
+//! Off unless an endpoint is configured. When enabled, the instance
+//! periodically POSTs a [`TelemetryReport`] holding nothing but its own
+//! operational counters (how many buckets it holds, how fast signals
+//! arrive, which version it runs), so the project can understand how
+//! Infrared is deployed. The report is the same shape every time and is
+//! logged at startup, so operators can see exactly what leaves the
+//! instance.
+//!
+//! # Privacy
+//!
+//! Reports never contain bucket names, warmth values, statuses, dashboard
+//! data or anything derived from an individual signal; only instance-wide
+//! counts. Canary buckets are left out of the bucket count.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::time::{Instant, interval_at};
+use tracing::{info, warn};
+
+use crate::canary::is_canary;
+use crate::events::{Event, EventBus, next_event};
+use crate::notify::WebhookNotifier;
+use crate::storage::{SignalStore, Storage};
+
+/// Instance-level counters reported to the telemetry endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryReport {
+    /// Infrared version.
+    pub version: String,
+
+    /// Number of buckets that have ever received a signal.
+    pub buckets: usize,
+
+    /// Accepted signals per minute, averaged since the previous report.
+    pub signals_per_minute: f64,
+
+    /// When the report was built.
+    pub reported_at: DateTime<Utc>,
+}
+
+/// Build a report for `signals` accepted over `elapsed`.
+pub async fn build_report(
+    storage: &Storage,
+    signals: u64,
+    elapsed: Duration,
+    now: DateTime<Utc>,
+) -> anyhow::Result<TelemetryReport> {
+    let buckets = storage
+        .get_all_known_buckets()
+        .await?
+        .iter()
+        .filter(|bucket| !is_canary(bucket))
+        .count();
+    let minutes = elapsed.as_secs_f64() / 60.0;
+
+    Ok(TelemetryReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        buckets,
+        signals_per_minute: if minutes > 0.0 {
+            signals as f64 / minutes
+        } else {
+            0.0
+        },
+        reported_at: now,
+    })
+}
+
+/// Spawn the telemetry reporter, POSTing a report to `url` every
+/// `interval`.
+///
+/// Signals are counted from [`Event::SignalIngested`] on `bus`. The first
+/// report is sent one interval after startup. Failed deliveries are logged
+/// and not retried.
+pub fn spawn_telemetry(
+    storage: Storage,
+    bus: &EventBus,
+    url: String,
+    interval: Duration,
+    notifier: WebhookNotifier,
+) -> tokio::task::JoinHandle<()> {
+    let mut receiver = bus.subscribe();
+    tokio::spawn(async move {
+        let mut ticker = interval_at(Instant::now() + interval, interval);
+        let mut signals = 0u64;
+        let mut since = Instant::now();
+        loop {
+            tokio::select! {
+                event = next_event(&mut receiver) => match event {
+                    Some(Event::SignalIngested(_)) => signals += 1,
+                    Some(_) => {}
+                    None => return,
+                },
+                _ = ticker.tick() => {
+                    // A failed report leaves both counters running, so the
+                    // next one covers the whole interval since the last
+                    let tick = Instant::now();
+                    let elapsed = tick - since;
+                    let report = match build_report(&storage, signals, elapsed, Utc::now()).await {
+                        Ok(report) => report,
+                        Err(e) => {
+                            warn!(error = %e, "Failed to build telemetry report");
+                            continue;
+                        }
+                    };
+                    signals = 0;
+                    since = tick;
+                    match notifier.send(&url, &report).await {
+                        Ok(()) => info!(buckets = report.buckets, "Telemetry report sent"),
+                        Err(e) => warn!(error = %e, "Failed to send telemetry report"),
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LifeSignal;
+
+    fn signal(bucket: &str) -> LifeSignal {
+        LifeSignal {
+            bucket: bucket.to_string(),
+            timestamp: Utc::now(),
+            weight: 1,
+            channel: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_build_report() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        for bucket in ["zone-a", "zone-b", "zone-a", "_canary:ingest"] {
+            storage.insert_life_signal(&signal(bucket)).await.unwrap();
+        }
+
+        let now = Utc::now();
+        let report = build_report(&storage, 30, Duration::from_secs(600), now)
+            .await
+            .unwrap();
+        assert_eq!(report.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(report.buckets, 2);
+        assert_eq!(report.signals_per_minute, 3.0);
+        assert_eq!(report.reported_at, now);
+
+        // Only the documented counters are sent
+        let json = serde_json::to_value(&report).unwrap();
+        let mut keys: Vec<_> = json.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            ["buckets", "reported_at", "signals_per_minute", "version"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_telemetry_delivery() {
+        use axum::extract::State;
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        let (sent, mut received) = tokio::sync::mpsc::unbounded_channel::<TelemetryReport>();
+        let app = Router::new()
+            .route(
+                "/telemetry",
+                post(
+                    |State(sent): State<tokio::sync::mpsc::UnboundedSender<TelemetryReport>>,
+                     Json(r): Json<TelemetryReport>| async move {
+                        sent.send(r).unwrap();
+                    },
+                ),
+            )
+            .with_state(sent);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        storage.insert_life_signal(&signal("zone-a")).await.unwrap();
+        let bus = EventBus::new();
+        spawn_telemetry(
+            storage,
+            &bus,
+            format!("http://{}/telemetry", addr),
+            Duration::from_millis(200),
+            WebhookNotifier::new(),
+        );
+        bus.publish(Event::SignalIngested(signal("zone-a")));
+
+        let report = received.recv().await.unwrap();
+        assert_eq!(report.buckets, 1);
+        assert!(report.signals_per_minute > 0.0);
+    }
+}
@@ -0,0 +1,219 @@
+//! Severity-weighted tension scores.
+//!
+//! A wallboard gauge needs one number, not four severity counts. Each
+//! ongoing dashboard issue contributes its severity weight times its
+//! category weight; the sum over all issues is the global tension and the
+//! sum over a country's issues is that country's tension. Weights are
+//! configurable, so a deployment focused on connectivity can weigh internet
+//! outages above food security, and vice versa.
+//!
+//! Scores are recorded with every dashboard snapshot, so
+//! `GET /dashboard/tension` can chart how tension has moved over time.
+//!
+//! # Privacy
+//!
+//! Scores are derived from country-level issues from public sources only.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dashboard::{Issue, IssueCategory};
+use crate::model::IssueSeverity;
+
+/// Weights applied to issues when scoring tension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TensionWeights {
+    severity: BTreeMap<IssueSeverity, f64>,
+    category: HashMap<IssueCategory, f64>,
+}
+
+impl Default for TensionWeights {
+    /// Each severity level weighs three times the one below it; info-level
+    /// issues do not count. Every category weighs 1.
+    fn default() -> Self {
+        Self {
+            severity: BTreeMap::from([
+                (IssueSeverity::Info, 0.0),
+                (IssueSeverity::Warning, 1.0),
+                (IssueSeverity::Critical, 3.0),
+                (IssueSeverity::Emergency, 9.0),
+            ]),
+            category: HashMap::new(),
+        }
+    }
+}
+
+impl TensionWeights {
+    /// Parse `name=weight` overrides of the defaults, where a name is a
+    /// severity (`critical`) or a category (`internet_outage`), e.g.
+    /// `emergency=12,conflict=1.5,food_security=0.5`.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let mut weights = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, weight) = entry.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("invalid weight '{}' (expected name=weight)", entry)
+            })?;
+            let name = name.trim();
+            let weight: f64 = weight
+                .trim()
+                .parse()
+                .ok()
+                .filter(|w: &f64| w.is_finite() && *w >= 0.0)
+                .ok_or_else(|| anyhow::anyhow!("invalid weight for '{}': '{}'", name, weight))?;
+
+            if let Some(severity) = IssueSeverity::parse(name) {
+                weights.severity.insert(severity, weight);
+            } else if let Some(category) = IssueCategory::parse(name) {
+                weights.category.insert(category, weight);
+            } else {
+                anyhow::bail!("unknown severity or category '{}'", name);
+            }
+        }
+        Ok(weights)
+    }
+
+    /// What one issue contributes; ended issues contribute nothing.
+    pub fn weight(&self, issue: &Issue) -> f64 {
+        if !issue.is_ongoing {
+            return 0.0;
+        }
+        let severity = self.severity.get(&issue.severity).copied().unwrap_or(0.0);
+        let category = self.category.get(&issue.category).copied().unwrap_or(1.0);
+        severity * category
+    }
+}
+
+/// Tension of one country.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountryTension {
+    /// Country name, as in `top_countries`.
+    pub country: String,
+
+    /// Weighted sum of the country's ongoing issues.
+    pub score: f64,
+}
+
+/// Score `issues`: the global tension and every country's, highest first.
+///
+/// An issue spanning several countries counts fully toward each of them,
+/// but once toward the global score.
+pub fn score(issues: &[Issue], weights: &TensionWeights) -> (f64, Vec<CountryTension>) {
+    let mut global = 0.0;
+    let mut by_country: HashMap<&str, f64> = HashMap::new();
+    for issue in issues {
+        let weight = weights.weight(issue);
+        if weight == 0.0 {
+            continue;
+        }
+        global += weight;
+        for name in issue.location_names() {
+            *by_country.entry(name).or_insert(0.0) += weight;
+        }
+    }
+
+    let mut countries: Vec<CountryTension> = by_country
+        .into_iter()
+        .map(|(country, score)| CountryTension {
+            country: country.to_string(),
+            score,
+        })
+        .collect();
+    countries.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.country.cmp(&b.country))
+    });
+    (global, countries)
+}
+
+/// Query parameters for GET /dashboard/tension.
+#[derive(Debug, Deserialize)]
+pub struct TensionQuery {
+    /// A country's history instead of the global one.
+    pub country: Option<String>,
+
+    /// How far back to go, in hours (default 168).
+    pub hours: Option<u32>,
+}
+
+/// A recorded tension score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TensionPoint {
+    /// When the score was recorded.
+    pub at: DateTime<Utc>,
+
+    /// The score.
+    pub score: f64,
+}
+
+/// Response for GET /dashboard/tension.
+#[derive(Debug, Serialize)]
+pub struct TensionHistory {
+    /// The country queried, or None for global tension.
+    pub country: Option<String>,
+
+    /// Recorded scores, oldest first.
+    pub points: Vec<TensionPoint>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::IssueSource;
+
+    fn issue(severity: IssueSeverity, category: IssueCategory, country: &str) -> Issue {
+        Issue::new(
+            IssueSource::Acled,
+            category,
+            severity,
+            country,
+            "",
+            "Event",
+            "Details",
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_score() {
+        let weights = TensionWeights::default();
+        let issues = [
+            issue(IssueSeverity::Emergency, IssueCategory::Conflict, "Sudan"),
+            issue(IssueSeverity::Warning, IssueCategory::Disaster, "Sudan"),
+            issue(IssueSeverity::Critical, IssueCategory::Conflict, "Chad"),
+            issue(IssueSeverity::Info, IssueCategory::Conflict, "Niger"),
+            issue(IssueSeverity::Emergency, IssueCategory::Conflict, "Mali").with_end(Utc::now()),
+        ];
+
+        let (global, countries) = score(&issues, &weights);
+        assert_eq!(global, 13.0);
+        assert_eq!(
+            countries,
+            [
+                CountryTension {
+                    country: "Sudan".to_string(),
+                    score: 10.0
+                },
+                CountryTension {
+                    country: "Chad".to_string(),
+                    score: 3.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        let weights = TensionWeights::parse("emergency=12, conflict=0.5").unwrap();
+        let conflict = issue(IssueSeverity::Emergency, IssueCategory::Conflict, "Sudan");
+        let disaster = issue(IssueSeverity::Critical, IssueCategory::Disaster, "Sudan");
+        assert_eq!(weights.weight(&conflict), 6.0);
+        assert_eq!(weights.weight(&disaster), 3.0);
+
+        assert!(TensionWeights::parse("severe=2").is_err());
+        assert!(TensionWeights::parse("critical=-1").is_err());
+        assert!(TensionWeights::parse("critical").is_err());
+    }
+}
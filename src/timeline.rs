@@ -0,0 +1,424 @@
+//! Incident timelines for post-incident review.
+//!
+//! `GET /incidents/:id/timeline` lays out, oldest first, everything that
+//! happened during an [`Incident`]: the status changes of its buckets, the
+//! dashboard issues in its countries, and the acknowledgments and notes
+//! operators recorded on it.
+//!
+//! Status changes come from scheduled warmth snapshots (plus the current
+//! alert of a bucket still in distress), so their resolution is the
+//! snapshot interval. Issues come from the dashboard snapshot at the end of
+//! the incident (or the latest one while it is ongoing), and are included
+//! when they affect one of the incident's countries and overlap its span.
+//!
+//! # Privacy
+//!
+//! Annotations record the credential label that made them, never a network
+//! identifier. Notes are free text written by operators and should stay at
+//! the same level as the rest of Infrared: places and infrastructure, never
+//! people.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::dashboard::{Dashboard, Issue, IssueSource};
+use crate::incidents::Incident;
+use crate::model::{Alert, AlertSubject, IssueSeverity, WarmthStatus};
+use crate::scheduler::WarmthSnapshot;
+use crate::situation::{Changepoint, changepoints};
+use crate::storage::SignalStore;
+
+/// Longest accepted note, in characters.
+pub const MAX_NOTE_CHARS: usize = 2000;
+
+/// How long before an incident's start snapshots are read, so the status
+/// each bucket changed from is known.
+const SNAPSHOT_LEAD_HOURS: i64 = 6;
+
+/// What an operator recorded on an incident.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationKind {
+    /// Someone has seen the incident and is on it.
+    Acknowledgment,
+    /// Free-text context.
+    Note,
+}
+
+impl AnnotationKind {
+    /// Get the lowercase name used in the API and in storage.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnnotationKind::Acknowledgment => "acknowledgment",
+            AnnotationKind::Note => "note",
+        }
+    }
+
+    /// Parse a lowercase kind name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "acknowledgment" => Some(AnnotationKind::Acknowledgment),
+            "note" => Some(AnnotationKind::Note),
+            _ => None,
+        }
+    }
+}
+
+/// An acknowledgment of or a note on an incident.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Annotation {
+    /// The incident's ID.
+    pub incident_id: String,
+
+    /// When it was recorded.
+    pub timestamp: DateTime<Utc>,
+
+    /// Acknowledgment or note.
+    pub kind: AnnotationKind,
+
+    /// Label of the credential that recorded it.
+    pub actor: String,
+
+    /// The note's text; None for acknowledgments.
+    pub text: Option<String>,
+}
+
+/// Request body for `POST /incidents/:id/notes`.
+#[derive(Debug, Deserialize)]
+pub struct NoteRequest {
+    /// The note, at most [`MAX_NOTE_CHARS`] characters.
+    pub text: String,
+}
+
+/// What happened at one point of an incident.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimelineEvent {
+    /// A bucket's scheduled status changed.
+    StatusChange {
+        bucket: String,
+        previous_status: WarmthStatus,
+        status: WarmthStatus,
+    },
+    /// A dashboard issue in one of the incident's countries began.
+    Issue {
+        id: String,
+        source: IssueSource,
+        severity: IssueSeverity,
+        title: String,
+        location_code: String,
+        url: Option<String>,
+    },
+    /// An operator acknowledged the incident.
+    Acknowledgment { actor: String },
+    /// An operator added a note.
+    Note { actor: String, text: String },
+}
+
+/// One timeline entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineEntry {
+    /// When it happened.
+    pub at: DateTime<Utc>,
+
+    /// What happened.
+    #[serde(flatten)]
+    pub event: TimelineEvent,
+}
+
+/// Response for `GET /incidents/:id/timeline`.
+#[derive(Debug, Serialize)]
+pub struct IncidentTimeline {
+    /// The incident as it stands.
+    pub incident: Incident,
+
+    /// Everything that happened during it, oldest first.
+    pub entries: Vec<TimelineEntry>,
+}
+
+/// A bucket's status changes from its snapshots (oldest first), plus the
+/// change to its current alert's status if the snapshots have not caught up.
+///
+/// Only snapshots of the smallest window size present are compared.
+pub fn bucket_changes(
+    bucket: &str,
+    snapshots: &[WarmthSnapshot],
+    alert: Option<&Alert>,
+    now: DateTime<Utc>,
+) -> Vec<Changepoint> {
+    let window = snapshots.iter().map(|s| s.window_minutes).min();
+    let snapshots: Vec<WarmthSnapshot> = snapshots
+        .iter()
+        .filter(|s| Some(s.window_minutes) == window)
+        .cloned()
+        .collect();
+    let mut changes = changepoints(&snapshots);
+
+    if let Some(alert) = alert {
+        let last = snapshots.last();
+        let previous_status = last.map_or(WarmthStatus::Alive, |s| s.status);
+        if previous_status != alert.status {
+            let since = alert.distressed_since.unwrap_or(now);
+            changes.push(Changepoint {
+                bucket: bucket.to_string(),
+                at: last.map_or(since, |s| since.max(s.taken_at)),
+                previous_status,
+                status: alert.status,
+            });
+        }
+    }
+    changes
+}
+
+/// Merge an incident's status changes, issues and annotations into one
+/// chronological timeline.
+///
+/// Status changes outside the incident's span are dropped, as are issues
+/// that ended before it began or started after it ended. `issues` must
+/// already be limited to the incident's countries.
+pub fn build_timeline(
+    incident: &Incident,
+    changes: &[Changepoint],
+    issues: &[Issue],
+    annotations: &[Annotation],
+    now: DateTime<Utc>,
+) -> Vec<TimelineEntry> {
+    let end = incident.ended_at.unwrap_or(now);
+    let mut entries: Vec<TimelineEntry> = changes
+        .iter()
+        .filter(|c| c.at >= incident.started_at && c.at <= end)
+        .map(|c| TimelineEntry {
+            at: c.at,
+            event: TimelineEvent::StatusChange {
+                bucket: c.bucket.clone(),
+                previous_status: c.previous_status,
+                status: c.status,
+            },
+        })
+        .collect();
+
+    entries.extend(
+        issues
+            .iter()
+            .filter(|i| {
+                i.timestamp <= end && i.end_timestamp.is_none_or(|e| e >= incident.started_at)
+            })
+            .map(|i| TimelineEntry {
+                at: i.timestamp,
+                event: TimelineEvent::Issue {
+                    id: i.id.clone(),
+                    source: i.source,
+                    severity: i.severity,
+                    title: i.title.clone(),
+                    location_code: i.location_code.clone(),
+                    url: i.url.clone(),
+                },
+            }),
+    );
+
+    entries.extend(annotations.iter().map(|a| TimelineEntry {
+        at: a.timestamp,
+        event: match a.kind {
+            AnnotationKind::Acknowledgment => TimelineEvent::Acknowledgment {
+                actor: a.actor.clone(),
+            },
+            AnnotationKind::Note => TimelineEvent::Note {
+                actor: a.actor.clone(),
+                text: a.text.clone().unwrap_or_default(),
+            },
+        },
+    }));
+
+    // Stable, so entries at the same moment keep the order above
+    entries.sort_by_key(|e| e.at);
+    entries
+}
+
+/// Assemble the timeline of `incident`, given the current alerts.
+///
+/// With a dashboard, issues are matched against both ISO codes of each of
+/// the incident's monitored countries.
+pub async fn build_incident_timeline(
+    storage: &dyn SignalStore,
+    dashboard: Option<&Dashboard>,
+    incident: Incident,
+    alerts: &[Alert],
+    now: DateTime<Utc>,
+) -> anyhow::Result<IncidentTimeline> {
+    let end = incident.ended_at.unwrap_or(now);
+    let from = incident.started_at - chrono::Duration::hours(SNAPSHOT_LEAD_HOURS);
+
+    let mut changes = Vec::new();
+    for bucket in &incident.buckets {
+        let snapshots = storage.get_warmth_snapshots(bucket, from, end).await?;
+        let alert = alerts
+            .iter()
+            .find(|a| a.subject == AlertSubject::Bucket && &a.bucket == bucket);
+        changes.extend(bucket_changes(bucket, &snapshots, alert, now));
+    }
+
+    let codes: Vec<String> = incident
+        .countries
+        .iter()
+        .flat_map(|country| match dashboard {
+            Some(dashboard) => dashboard.country_codes(country),
+            None => vec![country.clone()],
+        })
+        .collect();
+    let issues: Vec<Issue> = match storage.get_dashboard_snapshot_at(end).await? {
+        Some(snapshot) => snapshot
+            .issues
+            .into_iter()
+            .filter(|issue| codes.iter().any(|code| issue.matches_country(code)))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let annotations = storage.get_annotations(&incident.id).await?;
+    let entries = build_timeline(&incident, &changes, &issues, &annotations, now);
+    Ok(IncidentTimeline { incident, entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dashboard::IssueCategory;
+    use chrono::{Duration, TimeZone};
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    fn snapshot(minutes: i64, window_minutes: u32, status: WarmthStatus) -> WarmthSnapshot {
+        WarmthSnapshot {
+            bucket: "zone-a".to_string(),
+            window_minutes,
+            taken_at: at(minutes),
+            status,
+            current_window_total: 0,
+            recent_average: 10.0,
+        }
+    }
+
+    fn incident() -> Incident {
+        Incident {
+            id: format!("zone-a@{}", at(10).timestamp()),
+            started_at: at(10),
+            ended_at: Some(at(60)),
+            ongoing: false,
+            status: WarmthStatus::Dead,
+            buckets: vec!["zone-a".to_string()],
+            groups: Vec::new(),
+            countries: vec!["SD".to_string()],
+        }
+    }
+
+    fn issue(start: i64, end: Option<i64>) -> Issue {
+        let mut issue = Issue::new(
+            IssueSource::Ioda,
+            IssueCategory::InternetOutage,
+            IssueSeverity::Critical,
+            "Sudan",
+            "SD",
+            "Internet outage",
+            "Regional outage",
+            at(start),
+        );
+        issue.end_timestamp = end.map(at);
+        issue
+    }
+
+    #[test]
+    fn test_annotation_kind_names() {
+        for kind in [AnnotationKind::Acknowledgment, AnnotationKind::Note] {
+            assert_eq!(AnnotationKind::parse(kind.name()), Some(kind));
+        }
+        assert_eq!(AnnotationKind::parse("ack"), None);
+    }
+
+    #[test]
+    fn test_bucket_changes() {
+        let snapshots = vec![
+            snapshot(0, 10, WarmthStatus::Alive),
+            snapshot(0, 60, WarmthStatus::Alive),
+            snapshot(10, 10, WarmthStatus::Collapsing),
+            snapshot(10, 60, WarmthStatus::Stressed),
+            snapshot(20, 10, WarmthStatus::Collapsing),
+        ];
+        let changes = bucket_changes("zone-a", &snapshots, None, at(25));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].at, at(10));
+        assert_eq!(changes[0].status, WarmthStatus::Collapsing);
+
+        // The alert has gone further than the last snapshot
+        let alert = Alert {
+            bucket: "zone-a".to_string(),
+            subject: AlertSubject::Bucket,
+            status: WarmthStatus::Dead,
+            last_seen_timestamp: None,
+            recent_average: 10.0,
+            reason: crate::model::AlertReason::WarmthDrop,
+            message: String::new(),
+            severity: IssueSeverity::Critical,
+            distressed_since: Some(at(10)),
+        };
+        let changes = bucket_changes("zone-a", &snapshots, Some(&alert), at(25));
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[1].at, at(20));
+        assert_eq!(changes[1].previous_status, WarmthStatus::Collapsing);
+        assert_eq!(changes[1].status, WarmthStatus::Dead);
+    }
+
+    #[test]
+    fn test_build_timeline() {
+        let change = |minutes, previous_status, status| Changepoint {
+            bucket: "zone-a".to_string(),
+            at: at(minutes),
+            previous_status,
+            status,
+        };
+        let changes = vec![
+            change(-30, WarmthStatus::Stressed, WarmthStatus::Alive),
+            change(10, WarmthStatus::Alive, WarmthStatus::Dead),
+            change(60, WarmthStatus::Dead, WarmthStatus::Alive),
+        ];
+        let issues = vec![issue(-120, None), issue(-120, Some(-60)), issue(90, None)];
+        let note = |minutes, kind, text: Option<&str>| Annotation {
+            incident_id: incident().id,
+            timestamp: at(minutes),
+            kind,
+            actor: "ops".to_string(),
+            text: text.map(String::from),
+        };
+        let annotations = vec![
+            note(15, AnnotationKind::Acknowledgment, None),
+            note(
+                70,
+                AnnotationKind::Note,
+                Some("Power restored by the grid operator"),
+            ),
+        ];
+
+        let entries = build_timeline(&incident(), &changes, &issues, &annotations, at(120));
+        let times: Vec<DateTime<Utc>> = entries.iter().map(|e| e.at).collect();
+        assert_eq!(times, vec![at(-120), at(10), at(15), at(60), at(70)]);
+        assert!(matches!(entries[0].event, TimelineEvent::Issue { .. }));
+        assert!(matches!(
+            entries[1].event,
+            TimelineEvent::StatusChange {
+                status: WarmthStatus::Dead,
+                ..
+            }
+        ));
+        assert_eq!(
+            entries[2].event,
+            TimelineEvent::Acknowledgment {
+                actor: "ops".to_string()
+            }
+        );
+
+        let json = serde_json::to_value(&entries[4]).unwrap();
+        assert_eq!(json["kind"], "note");
+        assert_eq!(json["text"], "Power restored by the grid operator");
+    }
+}
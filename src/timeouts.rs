@@ -0,0 +1,255 @@
+//! Per-route-class request timeouts.
+//!
+//! Every route belongs to a [`RouteClass`] with its own time limit: signal
+//! ingest should answer in moments, while a dashboard aggregate over many
+//! external sources may legitimately take minutes. A request still running
+//! when its limit expires is dropped and answered with `504 Gateway
+//! Timeout` and a JSON body, so one slow aggregate query cannot hold a
+//! worker task indefinitely.
+//!
+//! # Privacy
+//!
+//! Timeouts are counted per route class only; nothing about the request
+//! is logged.
+
+use std::time::Duration;
+
+use axum::Json;
+use axum::extract::{Request, State};
+use axum::http::StatusCode;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::api::AppState;
+
+/// Groups of routes sharing a time limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteClass {
+    /// `POST /signal`.
+    Ingest,
+    /// Warmth, alerts, stats and other read endpoints.
+    Read,
+    /// `/dashboard/*`, which aggregates external sources.
+    Dashboard,
+    /// Admin endpoints.
+    Admin,
+}
+
+impl RouteClass {
+    /// Name used in configuration, metrics and error bodies.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RouteClass::Ingest => "ingest",
+            RouteClass::Read => "read",
+            RouteClass::Dashboard => "dashboard",
+            RouteClass::Admin => "admin",
+        }
+    }
+
+    /// Parse a class name.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ingest" => Some(RouteClass::Ingest),
+            "read" => Some(RouteClass::Read),
+            "dashboard" => Some(RouteClass::Dashboard),
+            "admin" => Some(RouteClass::Admin),
+            _ => None,
+        }
+    }
+}
+
+/// Time limit per route class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTimeouts {
+    pub ingest: Duration,
+    pub read: Duration,
+    pub dashboard: Duration,
+    pub admin: Duration,
+}
+
+impl Default for RequestTimeouts {
+    fn default() -> Self {
+        Self {
+            ingest: Duration::from_secs(5),
+            read: Duration::from_secs(30),
+            dashboard: Duration::from_secs(120),
+            admin: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RequestTimeouts {
+    /// Parse `class=seconds` overrides of the defaults, e.g.
+    /// `ingest=2,dashboard=300`.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let mut timeouts = Self::default();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (class, seconds) = entry.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("invalid timeout '{}' (expected class=seconds)", entry)
+            })?;
+            let class = RouteClass::parse(class.trim())
+                .ok_or_else(|| anyhow::anyhow!("unknown route class '{}'", class))?;
+            let seconds: u64 = seconds
+                .trim()
+                .parse()
+                .ok()
+                .filter(|s| *s > 0)
+                .ok_or_else(|| anyhow::anyhow!("invalid timeout seconds '{}'", seconds))?;
+            *timeouts.limit_mut(class) = Duration::from_secs(seconds);
+        }
+        Ok(timeouts)
+    }
+
+    /// Time limit of a route class.
+    pub fn limit(&self, class: RouteClass) -> Duration {
+        match class {
+            RouteClass::Ingest => self.ingest,
+            RouteClass::Read => self.read,
+            RouteClass::Dashboard => self.dashboard,
+            RouteClass::Admin => self.admin,
+        }
+    }
+
+    fn limit_mut(&mut self, class: RouteClass) -> &mut Duration {
+        match class {
+            RouteClass::Ingest => &mut self.ingest,
+            RouteClass::Read => &mut self.read,
+            RouteClass::Dashboard => &mut self.dashboard,
+            RouteClass::Admin => &mut self.admin,
+        }
+    }
+}
+
+/// Body of a `504 Gateway Timeout` response.
+#[derive(Debug, Serialize)]
+pub struct TimeoutBody {
+    /// Always "timeout".
+    pub error: &'static str,
+
+    /// Route class whose limit was hit.
+    pub route_class: &'static str,
+
+    /// The limit, in seconds.
+    pub timeout_seconds: u64,
+}
+
+/// Run the rest of the request under the class's time limit.
+async fn enforce(state: AppState, request: Request, next: Next, class: RouteClass) -> Response {
+    let limit = state.timeouts.limit(class);
+    match tokio::time::timeout(limit, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            warn!(route_class = class.name(), "Request timed out");
+            state.metrics.inc_counter(
+                "infrared_request_timeouts_total",
+                &[("class", class.name())],
+                1.0,
+            );
+            let body = TimeoutBody {
+                error: "timeout",
+                route_class: class.name(),
+                timeout_seconds: limit.as_secs(),
+            };
+            (StatusCode::GATEWAY_TIMEOUT, Json(body)).into_response()
+        }
+    }
+}
+
+/// Middleware: apply the ingest time limit.
+pub async fn timeout_ingest(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    enforce(state, request, next, RouteClass::Ingest).await
+}
+
+/// Middleware: apply the read time limit.
+pub async fn timeout_read(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    enforce(state, request, next, RouteClass::Read).await
+}
+
+/// Middleware: apply the dashboard time limit.
+pub async fn timeout_dashboard(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    enforce(state, request, next, RouteClass::Dashboard).await
+}
+
+/// Middleware: apply the admin time limit.
+pub async fn timeout_admin(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    enforce(state, request, next, RouteClass::Admin).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Storage;
+    use axum::Router;
+    use axum::body::Body;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_parse() {
+        let timeouts = RequestTimeouts::parse("ingest=2, dashboard=300").unwrap();
+        assert_eq!(timeouts.ingest, Duration::from_secs(2));
+        assert_eq!(timeouts.dashboard, Duration::from_secs(300));
+        assert_eq!(timeouts.read, RequestTimeouts::default().read);
+
+        assert!(RequestTimeouts::parse("ingest").is_err());
+        assert!(RequestTimeouts::parse("stream=5").is_err());
+        assert!(RequestTimeouts::parse("read=0").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_slow_request_times_out() {
+        let storage = Storage::new("sqlite::memory:").await.unwrap();
+        let mut state = AppState::new(storage, None);
+        state.timeouts.read = Duration::from_millis(50);
+        let metrics = state.metrics.clone();
+
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async { tokio::time::sleep(Duration::from_secs(5)).await }),
+            )
+            .route("/fast", get(|| async { "ok" }))
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                timeout_read,
+            ))
+            .with_state(state);
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/fast").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .oneshot(Request::get("/slow").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "timeout");
+        assert_eq!(body["route_class"], "read");
+        assert_eq!(
+            metrics.get("infrared_request_timeouts_total", &[("class", "read")]),
+            Some(1.0)
+        );
+    }
+}
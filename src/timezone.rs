@@ -0,0 +1,95 @@
+//! Per-bucket local time.
+//!
+//! Activity follows local daily and weekly rhythms: a market town is busy on
+//! its own market day, not on the UTC one. A bucket can declare its IANA
+//! timezone (e.g., "Africa/Nairobi"), and comparisons against the same time
+//! on previous days or weeks are then made at the same local wall-clock
+//! time, so day boundaries and daylight saving changes line up with the
+//! people behind the signals. Buckets without a timezone use UTC.
+//!
+//! # Privacy
+//!
+//! The timezone is a bucket-level setting. Nothing about senders is stored.
+
+use chrono::{DateTime, Days, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+/// Weeks averaged into the seasonal (same local weekday and time) baseline.
+pub const SEASONAL_WEEKS: u32 = 4;
+
+/// Request body for `PUT /buckets/:bucket/timezone`.
+#[derive(Debug, Deserialize)]
+pub struct TimezoneRequest {
+    /// IANA timezone name (e.g., "Asia/Kabul").
+    pub timezone: String,
+}
+
+/// Parse an IANA timezone name.
+pub fn parse_timezone(name: &str) -> anyhow::Result<Tz> {
+    name.trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("unknown timezone '{}'", name))
+}
+
+/// The same local wall-clock time on the same weekday over the previous
+/// `weeks` weeks, most recent first.
+///
+/// Weeks are counted in local days, so across a daylight saving change the
+/// gap is 167 or 169 hours rather than 168. A local time skipped by a
+/// change is left out; an ambiguous one resolves to its earlier instant.
+pub fn seasonal_reference_times(now: DateTime<Utc>, tz: Tz, weeks: u32) -> Vec<DateTime<Utc>> {
+    let local = now.with_timezone(&tz).naive_local();
+    (1..=u64::from(weeks))
+        .filter_map(|week| local.checked_sub_days(Days::new(week * 7)))
+        .filter_map(|earlier| tz.from_local_datetime(&earlier).earliest())
+        .map(|earlier| earlier.with_timezone(&Utc))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(
+            parse_timezone("Africa/Nairobi").unwrap(),
+            Tz::Africa__Nairobi
+        );
+        assert_eq!(parse_timezone(" UTC ").unwrap(), Tz::UTC);
+        assert!(parse_timezone("Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_seasonal_reference_times() {
+        // 09:00 in New York on Monday 2024-03-11, the day after clocks went forward
+        let now = Utc.with_ymd_and_hms(2024, 3, 11, 13, 0, 0).unwrap();
+
+        let utc = seasonal_reference_times(now, Tz::UTC, 2);
+        assert_eq!(
+            utc,
+            vec![
+                Utc.with_ymd_and_hms(2024, 3, 4, 13, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 2, 26, 13, 0, 0).unwrap(),
+            ]
+        );
+
+        // A week earlier 09:00 local was 14:00 UTC
+        let local = seasonal_reference_times(now, Tz::America__New_York, 2);
+        assert_eq!(
+            local,
+            vec![
+                Utc.with_ymd_and_hms(2024, 3, 4, 14, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2024, 2, 26, 14, 0, 0).unwrap(),
+            ]
+        );
+
+        // 02:30 on 2024-03-10 did not exist in New York
+        let now = Utc.with_ymd_and_hms(2024, 3, 17, 6, 30, 0).unwrap();
+        assert_eq!(
+            seasonal_reference_times(now, Tz::America__New_York, 2).len(),
+            1
+        );
+    }
+}
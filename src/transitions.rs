@@ -0,0 +1,262 @@
+//! Bucket status transitions.
+//!
+//! A warmth status on its own says how a bucket is doing; what operators
+//! act on is the moment it changes. The [`StatusTracker`] remembers the last
+//! status seen for each bucket and window size, and whenever a newly
+//! computed status differs it emits a [`StatusTransition`] carrying the old
+//! and new status, when each began, and the window values that triggered
+//! the change. Transitions are published on the [`EventBus`] as
+//! [`Event::StatusChanged`]; [`spawn_transition_webhooks`] POSTs them to
+//! configured webhooks.
+//!
+//! The first status seen for a bucket only records a starting point; it is
+//! not a transition.
+//!
+//! # Privacy
+//!
+//! Transitions describe buckets only (identifiers, statuses, window totals).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::events::{Event, EventBus, next_event};
+use crate::model::{WarmthResponse, WarmthStatus};
+use crate::notify::WebhookNotifier;
+
+/// Last status per (bucket, window size), and when it was first seen.
+type LastSeen = HashMap<(String, u32), (WarmthStatus, DateTime<Utc>)>;
+
+/// A change in a bucket's warmth status.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    /// The bucket whose status changed.
+    pub bucket: String,
+
+    /// Window size the status was computed over, in minutes.
+    pub window_minutes: u32,
+
+    /// Status before the change.
+    pub previous_status: WarmthStatus,
+
+    /// When the previous status was first seen.
+    pub previous_since: DateTime<Utc>,
+
+    /// Status after the change.
+    pub status: WarmthStatus,
+
+    /// When the change was seen.
+    pub changed_at: DateTime<Utc>,
+
+    /// Total weight in the window that triggered the change.
+    pub current_window_total: i64,
+
+    /// Baseline average the window was compared against.
+    pub recent_average: f64,
+}
+
+/// Remembers the last status per bucket and emits transitions.
+#[derive(Clone)]
+pub struct StatusTracker {
+    last: Arc<Mutex<LastSeen>>,
+    events: EventBus,
+}
+
+impl Default for StatusTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusTracker {
+    /// Create a tracker publishing to a bus of its own.
+    pub fn new() -> Self {
+        Self {
+            last: Arc::default(),
+            events: EventBus::new(),
+        }
+    }
+
+    /// Publish transitions on the given bus.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Record a freshly computed warmth, emitting a transition if the
+    /// bucket's status changed since it was last seen.
+    pub fn observe(&self, warmth: &WarmthResponse, now: DateTime<Utc>) -> Option<StatusTransition> {
+        let transition = {
+            let mut last = self.last.lock().unwrap();
+            let key = (warmth.bucket.clone(), warmth.window_minutes);
+            match last.get(&key).copied() {
+                Some((status, _)) if status == warmth.status => return None,
+                Some((previous_status, previous_since)) => {
+                    last.insert(key, (warmth.status, now));
+                    StatusTransition {
+                        bucket: warmth.bucket.clone(),
+                        window_minutes: warmth.window_minutes,
+                        previous_status,
+                        previous_since,
+                        status: warmth.status,
+                        changed_at: now,
+                        current_window_total: warmth.current_window_total,
+                        recent_average: warmth.recent_average,
+                    }
+                }
+                None => {
+                    last.insert(key, (warmth.status, now));
+                    return None;
+                }
+            }
+        };
+
+        info!(
+            bucket = %transition.bucket,
+            from = ?transition.previous_status,
+            to = ?transition.status,
+            "Bucket status changed"
+        );
+        self.events
+            .publish(Event::StatusChanged(transition.clone()));
+        Some(transition)
+    }
+}
+
+/// Spawn a subscriber POSTing every status transition to each URL.
+///
+/// Failed deliveries are logged and not retried.
+pub fn spawn_transition_webhooks(
+    bus: &EventBus,
+    urls: Vec<String>,
+    notifier: WebhookNotifier,
+) -> tokio::task::JoinHandle<()> {
+    let mut receiver = bus.subscribe();
+    tokio::spawn(async move {
+        while let Some(event) = next_event(&mut receiver).await {
+            let Event::StatusChanged(transition) = event else {
+                continue;
+            };
+            for url in &urls {
+                if let Err(e) = notifier.send(url, &transition).await {
+                    warn!(
+                        bucket = %transition.bucket,
+                        error = %e,
+                        "Failed to deliver status transition"
+                    );
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{BaselineMethod, Detector};
+    use chrono::Duration;
+
+    fn warmth(status: WarmthStatus, current: i64) -> WarmthResponse {
+        WarmthResponse {
+            bucket: "zone-a".to_string(),
+            window_minutes: 10,
+            current_window_total: current,
+            recent_average: 50.0,
+            baseline_windows: 6,
+            ewma_average: 50.0,
+            baseline_method: BaselineMethod::Mean,
+            detector: Detector::Ratio,
+            z_score: None,
+            status,
+            trend: None,
+            sparkline: None,
+            seasonal_average: None,
+            timezone: None,
+            channels: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observe_emits_on_change() {
+        let bus = EventBus::new();
+        let tracker = StatusTracker::new().with_events(bus.clone());
+        let mut events = bus.subscribe();
+        let start = Utc::now();
+
+        // First sighting and repeats are not transitions
+        assert!(
+            tracker
+                .observe(&warmth(WarmthStatus::Alive, 50), start)
+                .is_none()
+        );
+        assert!(
+            tracker
+                .observe(
+                    &warmth(WarmthStatus::Alive, 45),
+                    start + Duration::minutes(5)
+                )
+                .is_none()
+        );
+
+        let later = start + Duration::minutes(20);
+        let transition = tracker
+            .observe(&warmth(WarmthStatus::Dead, 0), later)
+            .unwrap();
+        assert_eq!(transition.previous_status, WarmthStatus::Alive);
+        assert_eq!(transition.previous_since, start);
+        assert_eq!(transition.status, WarmthStatus::Dead);
+        assert_eq!(transition.changed_at, later);
+        assert_eq!(transition.current_window_total, 0);
+        match events.try_recv().unwrap() {
+            Event::StatusChanged(published) => assert_eq!(published, transition),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // Other window sizes are tracked separately
+        let mut hourly = warmth(WarmthStatus::Alive, 300);
+        hourly.window_minutes = 60;
+        assert!(tracker.observe(&hourly, later).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_transition_webhook() {
+        use axum::extract::State;
+        use axum::routing::post;
+        use axum::{Json, Router};
+
+        let (sent, mut received) = tokio::sync::mpsc::unbounded_channel::<StatusTransition>();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(
+                    |State(sent): State<tokio::sync::mpsc::UnboundedSender<StatusTransition>>,
+                     Json(t): Json<StatusTransition>| async move {
+                        sent.send(t).unwrap();
+                    },
+                ),
+            )
+            .with_state(sent);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let bus = EventBus::new();
+        spawn_transition_webhooks(
+            &bus,
+            vec![format!("http://{}/hook", addr)],
+            WebhookNotifier::new(),
+        );
+        let tracker = StatusTracker::new().with_events(bus);
+        let now = Utc::now();
+        tracker.observe(&warmth(WarmthStatus::Alive, 50), now);
+        tracker.observe(&warmth(WarmthStatus::Stressed, 20), now);
+
+        let delivered = received.recv().await.unwrap();
+        assert_eq!(delivered.previous_status, WarmthStatus::Alive);
+        assert_eq!(delivered.status, WarmthStatus::Stressed);
+        assert_eq!(delivered.current_window_total, 20);
+    }
+}
@@ -0,0 +1,237 @@
+//! Stale data source watchdog.
+//!
+//! A data source that keeps failing shows up in each refresh's `errors`,
+//! but nobody reads those unless they are looking at the dashboard. The
+//! watchdog remembers when each fetched source last succeeded (from
+//! [`Event::SourceFetched`]) and, once one has gone longer than
+//! `DASHBOARD_STALE_SOURCE_HOURS` without success, POSTs a `source_stale`
+//! notification to every `INFRARED_OPS_WEBHOOKS` URL. A `source_recovered`
+//! notification follows its next success. Sources that have not succeeded
+//! since startup are counted from startup.
+//!
+//! Each source's last success is published as
+//! `infrared_source_last_success_timestamp_seconds{source}`, and whether it
+//! is stale as `infrared_source_stale{source}`.
+//!
+//! # Privacy
+//!
+//! Notifications name a public data source and timings only.
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::dashboard::IssueSource;
+use crate::events::{Event, EventBus, next_event};
+use crate::metrics::Metrics;
+use crate::notify::WebhookNotifier;
+
+/// How often sources are checked for staleness.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What happened to a source's freshness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StalenessChange {
+    /// The source went past the threshold without a successful fetch.
+    SourceStale,
+    /// A stale source was fetched successfully again.
+    SourceRecovered,
+}
+
+/// Notification POSTed when a source goes stale or recovers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StalenessNotice {
+    #[serde(rename = "type")]
+    pub change: StalenessChange,
+
+    pub source: IssueSource,
+
+    /// When the source was last fetched successfully, if since startup.
+    pub last_success: Option<DateTime<Utc>>,
+
+    /// Hours without success after which a source is stale.
+    pub threshold_hours: u64,
+
+    /// When the change was noticed.
+    pub at: DateTime<Utc>,
+}
+
+/// Last successful fetch of each watched source.
+#[derive(Debug, Clone)]
+pub struct SourceWatchdog {
+    threshold: chrono::Duration,
+    started_at: DateTime<Utc>,
+    last_success: HashMap<IssueSource, DateTime<Utc>>,
+    watched: Vec<IssueSource>,
+    stale: HashSet<IssueSource>,
+}
+
+impl SourceWatchdog {
+    /// Watch `sources` from `now`, calling one stale after `threshold`
+    /// without success.
+    pub fn new(sources: Vec<IssueSource>, threshold: Duration, now: DateTime<Utc>) -> Self {
+        Self {
+            threshold: chrono::Duration::from_std(threshold).unwrap_or(chrono::Duration::MAX),
+            started_at: now,
+            last_success: HashMap::new(),
+            watched: sources,
+            stale: HashSet::new(),
+        }
+    }
+
+    /// Record a successful fetch of `source`.
+    pub fn record_success(&mut self, source: IssueSource, at: DateTime<Utc>) {
+        let last = self.last_success.entry(source).or_insert(at);
+        *last = (*last).max(at);
+    }
+
+    /// When `source` last succeeded, if since startup.
+    pub fn last_success(&self, source: IssueSource) -> Option<DateTime<Utc>> {
+        self.last_success.get(&source).copied()
+    }
+
+    /// Whether `source` is currently reported stale.
+    pub fn is_stale(&self, source: IssueSource) -> bool {
+        self.stale.contains(&source)
+    }
+
+    /// Sources that went stale or recovered since the last check.
+    pub fn check(&mut self, now: DateTime<Utc>) -> Vec<StalenessNotice> {
+        let mut notices = Vec::new();
+        for &source in &self.watched {
+            let last_success = self.last_success.get(&source).copied();
+            let stale = now - last_success.unwrap_or(self.started_at) > self.threshold;
+            let change = match (stale, self.stale.contains(&source)) {
+                (true, false) => StalenessChange::SourceStale,
+                (false, true) => StalenessChange::SourceRecovered,
+                _ => continue,
+            };
+            if stale {
+                self.stale.insert(source);
+            } else {
+                self.stale.remove(&source);
+            }
+            notices.push(StalenessNotice {
+                change,
+                source,
+                last_success,
+                threshold_hours: (self.threshold.num_minutes() / 60).max(0) as u64,
+                at: now,
+            });
+        }
+        notices
+    }
+}
+
+/// Spawn the watchdog over `sources`, POSTing changes to `urls`.
+pub fn spawn_source_watchdog(
+    bus: &EventBus,
+    sources: Vec<IssueSource>,
+    threshold: Duration,
+    urls: Vec<String>,
+    notifier: WebhookNotifier,
+    metrics: Metrics,
+) -> tokio::task::JoinHandle<()> {
+    let mut receiver = bus.subscribe();
+    let mut watchdog = SourceWatchdog::new(sources, threshold, Utc::now());
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                event = next_event(&mut receiver) => {
+                    match event {
+                        Some(Event::SourceFetched { source, at }) => {
+                            watchdog.record_success(source, at);
+                        }
+                        Some(_) => {}
+                        None => return,
+                    }
+                    continue;
+                }
+            }
+
+            for notice in watchdog.check(Utc::now()) {
+                match notice.change {
+                    StalenessChange::SourceStale => warn!(
+                        source = notice.source.label(),
+                        last_success = ?notice.last_success,
+                        "Data source is stale"
+                    ),
+                    StalenessChange::SourceRecovered => {
+                        info!(source = notice.source.label(), "Data source recovered")
+                    }
+                }
+                for url in &urls {
+                    if let Err(e) = notifier.send(url, &notice).await {
+                        warn!(error = %e, "Failed to deliver source staleness notice");
+                    }
+                }
+            }
+
+            for &source in &watchdog.watched {
+                let labels = [("source", source.label())];
+                if let Some(at) = watchdog.last_success(source) {
+                    metrics.set_gauge(
+                        "infrared_source_last_success_timestamp_seconds",
+                        &labels,
+                        at.timestamp() as f64,
+                    );
+                }
+                let stale = if watchdog.is_stale(source) { 1.0 } else { 0.0 };
+                metrics.set_gauge("infrared_source_stale", &labels, stale);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_and_recovered() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let hours = chrono::Duration::hours;
+        let mut watchdog = SourceWatchdog::new(
+            vec![IssueSource::Ioda, IssueSource::Acled],
+            Duration::from_secs(6 * 3600),
+            start,
+        );
+
+        watchdog.record_success(IssueSource::Ioda, start + hours(1));
+        assert!(watchdog.check(start + hours(5)).is_empty());
+
+        // ACLED never succeeded, so it is counted from startup
+        let notices = watchdog.check(start + hours(7));
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].change, StalenessChange::SourceStale);
+        assert_eq!(notices[0].source, IssueSource::Acled);
+        assert_eq!(notices[0].last_success, None);
+        assert_eq!(notices[0].threshold_hours, 6);
+
+        // Reported once, not on every check
+        let notices = watchdog.check(start + hours(8));
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].source, IssueSource::Ioda);
+        assert!(watchdog.check(start + hours(9)).is_empty());
+
+        watchdog.record_success(IssueSource::Acled, start + hours(9));
+        let notices = watchdog.check(start + hours(9));
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].change, StalenessChange::SourceRecovered);
+        assert_eq!(notices[0].last_success, Some(start + hours(9)));
+        assert!(watchdog.is_stale(IssueSource::Ioda));
+        assert!(!watchdog.is_stale(IssueSource::Acled));
+
+        let json = serde_json::to_value(&notices[0]).unwrap();
+        assert_eq!(json["type"], "source_recovered");
+        assert_eq!(json["source"], "acled");
+    }
+}
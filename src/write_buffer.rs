@@ -0,0 +1,185 @@
+//! Write-buffered life signal inserts.
+//!
+//! Under heavy load, one `INSERT` (and one SQLite commit) per `POST /signal`
+//! caps ingest throughput. With a write buffer, [`Storage`] collects
+//! accepted signals in memory and writes them in a single transaction once
+//! [`WriteBuffer::max_rows`] are pending, or after
+//! [`WriteBuffer::max_delay`] at the latest (see [`spawn_flush_task`]).
+//!
+//! The trade-offs: a buffered signal counts towards warmth only once
+//! flushed, and signals still buffered when the process dies are lost. The
+//! server flushes on graceful shutdown, and the delay bounds both effects.
+//! If a flush fails, the signal that filled the buffer is rejected, so its
+//! client retries, and the rest are kept for the next flush.
+//!
+//! # Privacy
+//!
+//! The buffer holds the same bucket, timestamp and weight that would be
+//! written directly, and nothing else.
+
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::model::LifeSignal;
+use crate::storage::Storage;
+
+/// Default number of pending signals that triggers a flush.
+pub const DEFAULT_MAX_ROWS: usize = 500;
+
+/// Default longest time a signal waits in the buffer.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(1);
+
+/// Signals awaiting a batched insert.
+pub struct WriteBuffer {
+    pub(crate) pending: Mutex<Vec<LifeSignal>>,
+    max_rows: usize,
+    max_delay: Duration,
+}
+
+impl WriteBuffer {
+    /// A buffer flushing at `max_rows` pending signals or every `max_delay`.
+    pub fn new(max_rows: usize, max_delay: Duration) -> Self {
+        Self {
+            pending: Mutex::new(Vec::with_capacity(max_rows)),
+            max_rows: max_rows.max(1),
+            max_delay,
+        }
+    }
+
+    /// Number of pending signals that triggers a flush.
+    pub fn max_rows(&self) -> usize {
+        self.max_rows
+    }
+
+    /// Longest time a signal waits before being flushed.
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Number of signals not yet written.
+    pub async fn len(&self) -> usize {
+        self.pending.lock().await.len()
+    }
+
+    /// Whether every accepted signal has been written.
+    pub async fn is_empty(&self) -> bool {
+        self.pending.lock().await.is_empty()
+    }
+}
+
+/// Spawn the background task flushing `storage`'s write buffer every
+/// [`WriteBuffer::max_delay`].
+///
+/// Returns `None` if `storage` has no write buffer.
+pub fn spawn_flush_task(storage: Storage) -> Option<tokio::task::JoinHandle<()>> {
+    let max_delay = storage.write_buffer()?.max_delay();
+    Some(tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(max_delay);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = storage.flush_writes().await {
+                warn!(error = %e, "Failed to flush buffered life signals");
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SignalStore;
+    use chrono::Utc;
+
+    fn signal(bucket: &str) -> LifeSignal {
+        LifeSignal {
+            bucket: bucket.to_string(),
+            timestamp: Utc::now(),
+            weight: 1,
+            channel: None,
+        }
+    }
+
+    async fn buffered(max_rows: usize, max_delay: Duration) -> Storage {
+        Storage::new("sqlite::memory:")
+            .await
+            .unwrap()
+            .with_write_buffer(max_rows, max_delay)
+    }
+
+    #[tokio::test]
+    async fn test_flush_on_size() {
+        let storage = buffered(3, Duration::from_secs(3600)).await;
+
+        storage.insert_life_signal(&signal("zone-a")).await.unwrap();
+        storage.insert_life_signal(&signal("zone-a")).await.unwrap();
+        assert_eq!(storage.write_buffer().unwrap().len().await, 2);
+        assert_eq!(
+            storage
+                .query_bucket_window("zone-a", 5, Utc::now())
+                .await
+                .unwrap(),
+            0
+        );
+
+        storage.insert_life_signal(&signal("zone-a")).await.unwrap();
+        assert!(storage.write_buffer().unwrap().is_empty().await);
+        assert_eq!(
+            storage
+                .query_bucket_window("zone-a", 5, Utc::now())
+                .await
+                .unwrap(),
+            3
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flush_writes() {
+        let storage = buffered(DEFAULT_MAX_ROWS, Duration::from_secs(3600)).await;
+
+        for _ in 0..1000 {
+            storage.insert_life_signal(&signal("zone-b")).await.unwrap();
+        }
+        storage.insert_life_signal(&signal("zone-c")).await.unwrap();
+
+        assert_eq!(storage.flush_writes().await.unwrap(), 1);
+        assert_eq!(storage.flush_writes().await.unwrap(), 0);
+        assert_eq!(
+            storage
+                .query_bucket_window("zone-b", 5, Utc::now())
+                .await
+                .unwrap(),
+            1000
+        );
+        assert_eq!(
+            storage
+                .query_bucket_window("zone-c", 5, Utc::now())
+                .await
+                .unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flush_task() {
+        let storage = buffered(DEFAULT_MAX_ROWS, Duration::from_millis(20)).await;
+        let task = spawn_flush_task(storage.clone()).unwrap();
+
+        storage.insert_life_signal(&signal("zone-d")).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        task.abort();
+
+        assert!(storage.write_buffer().unwrap().is_empty().await);
+        assert_eq!(
+            storage
+                .query_bucket_window("zone-d", 5, Utc::now())
+                .await
+                .unwrap(),
+            1
+        );
+        let unbuffered = Storage::new("sqlite::memory:").await.unwrap();
+        assert!(spawn_flush_task(unbuffered).is_none());
+    }
+}
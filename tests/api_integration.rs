@@ -7,21 +7,30 @@ use axum_test::TestServer;
 use serde_json::json;
 
 // Import from the infrared crate
-use infrared::api::{AppState, get_alerts, get_warmth, health_check, post_signal};
-use infrared::storage::Storage;
+use infrared::api::{
+    AppState, get_alerts, get_metrics, get_stats, get_warmth, health_check, post_signal, router,
+};
+use infrared::auth::AuthConfig;
+use infrared::channel::SignalChannels;
+use infrared::events::Event;
+use infrared::ingest::{IngestTracker, QuotaConfig};
+use infrared::journal::{Journal, JournalConfig, replay};
+use infrared::model::{LifeSignal, WarmthStatus};
+use infrared::naming::NamingConvention;
+use infrared::storage::{SignalStore, Storage};
 
 async fn create_test_server() -> TestServer {
     let storage = Storage::new("sqlite::memory:").await.unwrap();
-    let state = AppState {
-        storage,
-        dashboard: None, // Dashboard not needed for core API tests
-    };
+    // Dashboard not needed for core API tests
+    let state = AppState::new(storage, None);
 
     let app = Router::new()
         .route("/signal", post(post_signal))
         .route("/warmth", get(get_warmth))
         .route("/alerts/recent", get(get_alerts))
         .route("/health", get(health_check))
+        .route("/stats", get(get_stats))
+        .route("/metrics", get(get_metrics))
         .with_state(state);
 
     TestServer::new(app).unwrap()
@@ -51,6 +60,66 @@ async fn test_post_signal() {
     response.assert_status(axum::http::StatusCode::ACCEPTED);
 }
 
+#[tokio::test]
+async fn test_post_external_issue() {
+    use infrared::dashboard::{Dashboard, DashboardConfig, IssueSource};
+    use std::sync::Arc;
+
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+    let issue = |id: &str, country: &str| {
+        json!({
+            "id": id,
+            "source": "acled",
+            "category": "conflict",
+            "severity": "critical",
+            "location": "Sudan",
+            "location_code": country,
+            "title": "Shelling reported in Omdurman",
+            "description": "Partner field report",
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "end_timestamp": null,
+            "is_ongoing": true,
+            "impact_value": null,
+            "impact_label": null,
+            "url": null
+        })
+    };
+
+    let response = server
+        .post("/ingest/issue")
+        .json(&issue("evt-1", "SD"))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["id"], "external:anonymous:evt-1");
+
+    // Reposting replaces the issue
+    server
+        .post("/ingest/issue")
+        .json(&issue("evt-1", "SD"))
+        .await
+        .assert_status_ok();
+
+    let response = server
+        .post("/ingest/issue")
+        .json(&issue("evt-2", "Sudan"))
+        .await;
+    response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "invalid_issue");
+
+    // Merged into the dashboard as an external source
+    let dashboard =
+        Dashboard::new(DashboardConfig::default()).with_external_issues(Arc::new(storage.clone()));
+    let issues = dashboard
+        .get_issues_by_source(IssueSource::External)
+        .await
+        .unwrap();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].source, IssueSource::External);
+}
+
 #[tokio::test]
 async fn test_post_signal_default_weight() {
     let server = create_test_server().await;
@@ -65,6 +134,20 @@ async fn test_post_signal_default_weight() {
     response.assert_status(axum::http::StatusCode::ACCEPTED);
 }
 
+#[tokio::test]
+async fn test_post_signal_rejects_canary_bucket() {
+    let server = create_test_server().await;
+
+    let response = server
+        .post("/signal")
+        .json(&json!({
+            "bucket": "_canary:ingest"
+        }))
+        .await;
+
+    response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn test_get_warmth_empty_bucket() {
     let server = create_test_server().await;
@@ -106,6 +189,27 @@ async fn test_get_warmth_with_signals() {
     assert_eq!(body["window_minutes"], 10);
 }
 
+#[tokio::test]
+async fn test_get_warmth_zscore_detector() {
+    let server = create_test_server().await;
+
+    let response = server
+        .get("/warmth?bucket=empty-bucket&detector=zscore&sigmas=2")
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["detector"], "zscore");
+    assert_eq!(body["status"], "alive");
+    assert!(body.get("z_score").is_none()); // No history to compare against
+
+    for query in ["detector=median", "detector=zscore&sigmas=0"] {
+        server
+            .get(&format!("/warmth?bucket=empty-bucket&{}", query))
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+}
+
 #[tokio::test]
 async fn test_get_alerts_empty() {
     let server = create_test_server().await;
@@ -168,3 +272,2034 @@ async fn test_full_workflow() {
     let body: serde_json::Value = response.json();
     assert!(body["alerts"].as_array().unwrap().is_empty());
 }
+
+#[tokio::test]
+async fn test_feed_bundle() {
+    use chrono::{Duration, Utc};
+    use infrared::dashboard::{
+        Dashboard, DashboardConfig, DashboardResponse, DashboardSummary, Issue, IssueCategory,
+        IssueSeverity, IssueSource,
+    };
+
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let dashboard = Dashboard::new(DashboardConfig::default());
+    let server = TestServer::new(router(AppState::new(storage.clone(), Some(dashboard)))).unwrap();
+
+    // Nothing persisted yet
+    server
+        .get("/feed/bundle")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    let taken_at = Utc::now() - Duration::minutes(5);
+    let issue = |source, code| {
+        Issue::new(
+            source,
+            IssueCategory::InternetOutage,
+            IssueSeverity::Critical,
+            "Ukraine",
+            code,
+            "Internet outage detected in Ukraine",
+            "Test",
+            taken_at - Duration::hours(1),
+        )
+    };
+    let issues = vec![
+        issue(IssueSource::Ioda, "UA"),
+        issue(IssueSource::CloudflareRadar, "UA"),
+    ];
+    let snapshot = DashboardResponse {
+        timestamp: taken_at,
+        summary: DashboardSummary::from_issues(&issues),
+        issues,
+        errors: vec![],
+        health: vec![],
+        suppressed: vec![],
+    };
+    storage.insert_dashboard_snapshot(&snapshot).await.unwrap();
+
+    let response = server.get("/feed/bundle").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["type"], "bundle");
+    let objects = body["objects"].as_array().unwrap();
+    let count = |kind: &str| objects.iter().filter(|o| o["type"] == kind).count();
+    assert_eq!(count("identity"), 1);
+    assert_eq!(count("location"), 1);
+    assert_eq!(count("incident"), 2);
+    // Two located-at, one related-to between the sources
+    assert_eq!(count("relationship"), 3);
+
+    // The same snapshot exports to the same bundle
+    let again: serde_json::Value = server.get("/feed/bundle").await.json();
+    assert_eq!(body, again);
+}
+
+#[tokio::test]
+async fn test_dashboard_summary_trends() {
+    use chrono::{Duration, Utc};
+    use infrared::dashboard::{
+        Dashboard, DashboardConfig, DashboardResponse, DashboardSummary, Issue, IssueCategory,
+        IssueSeverity, IssueSource,
+    };
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let now = Utc::now();
+    let conflict = |id: &str| {
+        Issue::new(
+            IssueSource::External,
+            IssueCategory::Conflict,
+            IssueSeverity::Warning,
+            "Sudan",
+            "SD",
+            id,
+            "Test",
+            now,
+        )
+    };
+    storage
+        .upsert_external_issue(&conflict("Clashes"), now)
+        .await
+        .unwrap();
+
+    // Two issues two days ago, none recorded yesterday, one today
+    for (taken_at, issues) in [
+        (now - Duration::days(2), vec![conflict("a"), conflict("b")]),
+        (now, vec![conflict("a")]),
+    ] {
+        let snapshot = DashboardResponse {
+            timestamp: taken_at,
+            summary: DashboardSummary::from_issues(&issues),
+            issues,
+            errors: vec![],
+            health: vec![],
+            suppressed: vec![],
+        };
+        storage.insert_dashboard_snapshot(&snapshot).await.unwrap();
+    }
+
+    let dashboard = Dashboard::new(DashboardConfig {
+        sources: Some(HashSet::from([IssueSource::External])),
+        ..DashboardConfig::default()
+    })
+    .with_external_issues(Arc::new(storage.clone()));
+    let server = TestServer::new(router(AppState::new(storage, Some(dashboard)))).unwrap();
+
+    let response = server.get("/dashboard/summary").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["total_issues"], 1);
+    let trends = &body["trends"];
+    assert_eq!(trends["days"].as_array().unwrap().len(), 7);
+    assert_eq!(trends["days"][6], now.date_naive().to_string());
+    let expected = json!([null, null, null, null, 2, null, 1]);
+    assert_eq!(trends["by_category"]["Conflict"], expected);
+    assert_eq!(trends["by_country"]["Sudan"], expected);
+}
+
+#[tokio::test]
+async fn test_dashboard_snapshots_rate_limited() {
+    use infrared::dashboard::{Dashboard, DashboardConfig, IssueSource};
+    use std::collections::HashSet;
+    use std::sync::Arc;
+
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let dashboard = Dashboard::new(DashboardConfig {
+        sources: Some(HashSet::from([IssueSource::External])),
+        ..DashboardConfig::default()
+    })
+    .with_external_issues(Arc::new(storage.clone()));
+    let server = TestServer::new(router(AppState::new(storage.clone(), Some(dashboard)))).unwrap();
+
+    // Polling clients do not persist a snapshot per request
+    for _ in 0..5 {
+        server.get("/dashboard").await.assert_status_ok();
+    }
+    let snapshots = storage.export_table("dashboard_snapshots").await.unwrap();
+    assert_eq!(snapshots.len(), 1);
+}
+
+#[tokio::test]
+async fn test_dashboard_time_travel() {
+    use chrono::{Duration, Utc};
+    use infrared::api::{get_dashboard, get_dashboard_attribution, get_tension_history};
+    use infrared::dashboard::{
+        Dashboard, DashboardConfig, DashboardResponse, DashboardSummary, Issue, IssueCategory,
+        IssueSeverity, IssueSource,
+    };
+    use infrared::tension::TensionWeights;
+
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let taken_at = Utc::now() - Duration::hours(2);
+    let mut issues = vec![
+        Issue::new(
+            IssueSource::Ioda,
+            IssueCategory::InternetOutage,
+            IssueSeverity::Critical,
+            "Ukraine",
+            "UA",
+            "Internet outage detected in Ukraine",
+            "Test",
+            taken_at,
+        ),
+        Issue::new(
+            IssueSource::ReliefWeb,
+            IssueCategory::Disaster,
+            IssueSeverity::Warning,
+            "Syria",
+            "SYR",
+            "Flood",
+            "Test",
+            taken_at,
+        ),
+    ];
+    // As stored before issues carried their attribution
+    issues[0].attribution = None;
+    let snapshot = DashboardResponse {
+        timestamp: taken_at,
+        summary: DashboardSummary::from_issues(&issues),
+        issues,
+        errors: vec![],
+        health: vec![],
+        suppressed: vec![],
+    };
+    storage.insert_dashboard_snapshot(&snapshot).await.unwrap();
+
+    storage
+        .insert_tension(taken_at, &snapshot.summary)
+        .await
+        .unwrap();
+
+    let dashboard = Dashboard::new(DashboardConfig::default())
+        .with_tension_weights(TensionWeights::parse("critical=5").unwrap());
+    let state = AppState::new(storage, Some(dashboard));
+    let app = Router::new()
+        .route("/dashboard", get(get_dashboard))
+        .route("/dashboard/tension", get(get_tension_history))
+        .route("/dashboard/attribution", get(get_dashboard_attribution))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    // Before any snapshot existed
+    let before = (taken_at - Duration::hours(1)).to_rfc3339();
+    server
+        .get("/dashboard")
+        .add_query_param("at", &before)
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    // After the snapshot, filtered by country
+    let after = Utc::now().to_rfc3339();
+    let response = server
+        .get("/dashboard")
+        .add_query_param("at", &after)
+        .add_query_param("country", "UA")
+        .await;
+    response.assert_status_ok();
+
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["issues"].as_array().unwrap().len(), 1);
+    assert_eq!(body["summary"]["total_issues"], 1);
+    assert_eq!(body["issues"][0]["location_code"], "UA");
+    assert_eq!(
+        body["issues"][0]["attribution"]["credit"],
+        "Data from IODA (ioda.inetintel.cc.gatech.edu)"
+    );
+
+    // The filtered summary is re-scored with the configured weights
+    assert_eq!(body["summary"]["global_tension"], 5.0);
+    assert_eq!(body["summary"]["country_tension"][0]["country"], "Ukraine");
+
+    // Tension recorded with the snapshot, at the default weights (3 + 1)
+    let response = server.get("/dashboard/tension").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["points"].as_array().unwrap().len(), 1);
+    assert_eq!(body["points"][0]["score"], 4.0);
+    let body: serde_json::Value = server
+        .get("/dashboard/tension")
+        .add_query_param("country", "Syria")
+        .await
+        .json();
+    assert_eq!(body["country"], "Syria");
+    assert_eq!(body["points"][0]["score"], 1.0);
+
+    let body: serde_json::Value = server.get("/dashboard/attribution").await.json();
+    let sources = body["sources"].as_array().unwrap();
+    let radar = sources
+        .iter()
+        .find(|s| s["source"] == "cloudflare_radar")
+        .unwrap();
+    assert_eq!(radar["license"], "CC BY-NC 4.0");
+    assert_eq!(radar["non_commercial"], true);
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint() {
+    let server = create_test_server().await;
+
+    let response = server.get("/metrics").await;
+
+    response.assert_status_ok();
+    assert!(response.text().is_empty());
+}
+
+#[tokio::test]
+async fn test_bucket_quota_returns_429() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let quotas = QuotaConfig {
+        default_per_minute: None,
+        overrides: [("zone-noisy".to_string(), 1)].into(),
+    };
+    let state = AppState::new(storage, None).with_ingest(IngestTracker::with_quotas(quotas));
+    let app = Router::new()
+        .route("/signal", post(post_signal))
+        .route("/stats", get(get_stats))
+        .with_state(state);
+    let server = TestServer::new(app).unwrap();
+
+    let signal = json!({ "bucket": "zone-noisy" });
+    let first = server.post("/signal").json(&signal).await;
+    first.assert_status(axum::http::StatusCode::ACCEPTED);
+    assert_eq!(first.header("ratelimit-limit"), "1");
+    assert_eq!(first.header("ratelimit-remaining"), "0");
+    let reset: u32 = first
+        .header("ratelimit-reset")
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!((1..=60).contains(&reset));
+
+    // A minute boundary between the two posts would reset the quota
+    let second = server.post("/signal").json(&signal).await;
+    if second.status_code() != axum::http::StatusCode::ACCEPTED {
+        second.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(second.header("ratelimit-remaining"), "0");
+        assert!(second.maybe_header("retry-after").is_some());
+
+        let stats: serde_json::Value = server.get("/stats").await.json();
+        assert_eq!(stats["buckets"][0]["bucket"], "zone-noisy");
+        assert_eq!(stats["buckets"][0]["quota_per_minute"], 1);
+        assert_eq!(stats["buckets"][0]["rejected_total"], 1);
+    }
+
+    // Buckets without an override are unlimited and carry no rate-limit headers
+    for _ in 0..3 {
+        let response = server
+            .post("/signal")
+            .json(&json!({ "bucket": "zone-quiet" }))
+            .await;
+        response.assert_status(axum::http::StatusCode::ACCEPTED);
+        assert!(response.maybe_header("ratelimit-limit").is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_role_based_tokens() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let auth =
+        AuthConfig::parse("ingest:field:ingest-token,read:read-token,admin:admin-token").unwrap();
+    let state = AppState::new(storage, None).with_auth(auth);
+    let server = TestServer::new(router(state)).unwrap();
+
+    let signal = json!({ "bucket": "zone-a" });
+    let bearer =
+        |token: &str| axum::http::HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+    let auth_header = axum::http::header::AUTHORIZATION;
+
+    // Health stays open
+    server.get("/health").await.assert_status_ok();
+
+    // No token
+    server
+        .post("/signal")
+        .json(&signal)
+        .await
+        .assert_status(axum::http::StatusCode::UNAUTHORIZED);
+
+    // Ingest tokens can submit but not read
+    server
+        .post("/signal")
+        .add_header(auth_header.clone(), bearer("ingest-token"))
+        .json(&signal)
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .add_header(auth_header.clone(), bearer("ingest-token"))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    // Read tokens can read but not submit
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .add_header(auth_header.clone(), bearer("read-token"))
+        .await
+        .assert_status_ok();
+    server
+        .post("/signal")
+        .add_header(auth_header.clone(), bearer("read-token"))
+        .json(&signal)
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    // Admin tokens can do both
+    server
+        .post("/signal")
+        .add_header(auth_header.clone(), bearer("admin-token"))
+        .json(&signal)
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+    server
+        .get("/stats")
+        .add_header(auth_header, bearer("admin-token"))
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_oidc_login_protects_read_endpoints() {
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::State;
+    use axum::http::{StatusCode, header};
+    use infrared::oidc::{Oidc, OidcConfig};
+    use jsonwebtoken::{EncodingKey, Header, encode};
+
+    // Mock identity provider issuing HS256 ID tokens signed with the client
+    // secret
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let issuer = format!("http://{}", listener.local_addr().unwrap());
+    let nonce = Arc::new(Mutex::new(String::new()));
+
+    let discovery = json!({
+        "authorization_endpoint": format!("{}/authorize", issuer),
+        "token_endpoint": format!("{}/token", issuer),
+        "jwks_uri": format!("{}/jwks", issuer),
+        "id_token_signing_alg_values_supported": ["HS256"],
+    });
+    let token_issuer = issuer.clone();
+    let idp = Router::new()
+        .route(
+            "/.well-known/openid-configuration",
+            get(move || async move { axum::Json(discovery) }),
+        )
+        .route(
+            "/token",
+            post(move |State(nonce): State<Arc<Mutex<String>>>| async move {
+                let id_token = encode(
+                    &Header::new(jsonwebtoken::Algorithm::HS256),
+                    &json!({
+                        "iss": token_issuer,
+                        "aud": "infrared",
+                        "sub": "analyst-7",
+                        "nonce": *nonce.lock().unwrap(),
+                        "exp": chrono::Utc::now().timestamp() + 300,
+                    }),
+                    &EncodingKey::from_secret(b"client-secret"),
+                )
+                .unwrap();
+                axum::Json(json!({ "id_token": id_token, "token_type": "Bearer" }))
+            }),
+        )
+        .with_state(nonce.clone());
+    tokio::spawn(async move { axum::serve(listener, idp).await.unwrap() });
+
+    let config = OidcConfig::new(
+        &issuer,
+        "infrared",
+        "client-secret",
+        "http://localhost/auth/callback",
+        "session-secret",
+    );
+    let oidc = Oidc::discover(config).await.unwrap();
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let state = AppState::new(storage, None).with_auth(AuthConfig::disabled().with_oidc(oidc));
+    let server = TestServer::new(router(state)).unwrap();
+
+    // Read endpoints need a session; ingestion stays open without tokens
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-a" }))
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+
+    // Start login: redirect to the provider with state and nonce
+    let login = server
+        .get("/auth/login")
+        .add_query_param("next", "/warmth?bucket=zone-a")
+        .await;
+    login.assert_status(StatusCode::SEE_OTHER);
+    let location = login.header(header::LOCATION).to_str().unwrap().to_string();
+    assert!(location.starts_with(&format!("{}/authorize?", issuer)));
+    let param = |name: &str| {
+        location
+            .split(['?', '&'])
+            .find_map(|pair| pair.strip_prefix(&format!("{}=", name)))
+            .unwrap()
+            .to_string()
+    };
+    *nonce.lock().unwrap() = param("nonce");
+    let state_cookie = login
+        .header(header::SET_COOKIE)
+        .to_str()
+        .unwrap()
+        .to_string();
+    let state_cookie = state_cookie.split(';').next().unwrap().to_string();
+
+    // A forged state is rejected
+    server
+        .get("/auth/callback")
+        .add_query_param("code", "abc")
+        .add_query_param("state", "forged")
+        .add_header(
+            header::COOKIE,
+            axum::http::HeaderValue::from_str(&state_cookie).unwrap(),
+        )
+        .await
+        .assert_status(StatusCode::UNAUTHORIZED);
+
+    // Complete login
+    let callback = server
+        .get("/auth/callback")
+        .add_query_param("code", "abc")
+        .add_query_param("state", param("state"))
+        .add_header(
+            header::COOKIE,
+            axum::http::HeaderValue::from_str(&state_cookie).unwrap(),
+        )
+        .await;
+    callback.assert_status(StatusCode::SEE_OTHER);
+    assert_eq!(callback.header(header::LOCATION), "/warmth?bucket=zone-a");
+    let session = callback
+        .headers()
+        .get_all(header::SET_COOKIE)
+        .iter()
+        .map(|v| v.to_str().unwrap())
+        .find(|v| v.starts_with("infrared_session="))
+        .unwrap()
+        .split(';')
+        .next()
+        .unwrap()
+        .to_string();
+
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .add_header(
+            header::COOKIE,
+            axum::http::HeaderValue::from_str(&session).unwrap(),
+        )
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_admin_audit_requires_admin() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    storage
+        .insert_audit_entry(chrono::Utc::now(), "ops", "bucket.archive", Some("zone-a"))
+        .await
+        .unwrap();
+    let auth = AuthConfig::parse("read:read-token,admin:ops:admin-token").unwrap();
+    let server = TestServer::new(router(AppState::new(storage, None).with_auth(auth))).unwrap();
+    let bearer =
+        |token: &str| axum::http::HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+
+    server
+        .get("/admin/audit")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("read-token"))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    let response = server
+        .get("/admin/audit")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("admin-token"))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["entries"][0]["actor"], "ops");
+    assert_eq!(body["entries"][0]["action"], "bucket.archive");
+}
+
+#[tokio::test]
+async fn test_heartbeat_registration_and_missed_alert() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    server
+        .put("/buckets/site-1/heartbeat")
+        .json(&json!({ "interval_minutes": 0 }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    server
+        .put("/buckets/site-1/heartbeat")
+        .json(&json!({ "interval_minutes": 15 }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let response = server.get("/heartbeats").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["heartbeats"][0]["bucket"], "site-1");
+    assert_eq!(body["heartbeats"][0]["interval_seconds"], 900);
+    assert_eq!(body["heartbeats"][0]["missed"], false);
+
+    // Backdate the registration so the interval has already passed
+    storage
+        .set_heartbeat(
+            "site-1",
+            900,
+            chrono::Utc::now() - chrono::Duration::minutes(16),
+        )
+        .await
+        .unwrap();
+
+    let body: serde_json::Value = server.get("/alerts/recent").await.json();
+    assert_eq!(body["alerts"][0]["bucket"], "site-1");
+    assert_eq!(body["alerts"][0]["status"], "dead");
+    assert_eq!(body["alerts"][0]["reason"], "missed_heartbeat");
+
+    server
+        .delete("/buckets/site-1/heartbeat")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .delete("/buckets/site-1/heartbeat")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    let audit = storage
+        .get_audit_entries(Some("heartbeat.change"), 10)
+        .await
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+}
+
+#[tokio::test]
+async fn test_switch_arm_and_reset() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage, None))).unwrap();
+
+    server
+        .put("/buckets/site-1/switch")
+        .json(&json!({ "countdown_minutes": 60 }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let body: serde_json::Value = server.get("/switches").await.json();
+    assert_eq!(body["switches"][0]["bucket"], "site-1");
+    assert_eq!(body["switches"][0]["countdown_seconds"], 3600);
+    assert_eq!(body["switches"][0]["expired"], false);
+    assert!(body["switches"][0]["last_seen"].is_null());
+
+    // A signal resets the countdown
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "site-1" }))
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+    let body: serde_json::Value = server.get("/switches").await.json();
+    assert!(!body["switches"][0]["last_seen"].is_null());
+
+    server
+        .delete("/buckets/site-1/switch")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    let body: serde_json::Value = server.get("/switches").await.json();
+    assert_eq!(body["switches"], json!([]));
+}
+
+#[tokio::test]
+async fn test_bucket_group_warmth_and_alerts() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage, None))).unwrap();
+
+    server
+        .put("/groups/north")
+        .json(&json!({ "buckets": [] }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    server
+        .put("/groups/north")
+        .json(&json!({ "buckets": ["zone-1", "zone-2"] }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let body: serde_json::Value = server.get("/groups").await.json();
+    assert_eq!(body["groups"][0]["name"], "north");
+    assert_eq!(body["groups"][0]["buckets"], json!(["zone-1", "zone-2"]));
+
+    for bucket in ["zone-1", "zone-2"] {
+        server
+            .post("/signal")
+            .json(&json!({ "bucket": bucket, "weight": 3 }))
+            .await
+            .assert_status(axum::http::StatusCode::ACCEPTED);
+    }
+
+    let response = server
+        .get("/warmth")
+        .add_query_param("group", "north")
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["group"], "north");
+    assert_eq!(body["current_window_total"], 6);
+    assert_eq!(body["uptime"], 1.0);
+    assert_eq!(body["members"].as_array().unwrap().len(), 2);
+
+    server
+        .get("/warmth")
+        .add_query_param("group", "missing")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+    server
+        .get("/warmth")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let response = server
+        .get("/alerts/recent")
+        .add_query_param("group", "north")
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["alerts"], json!([]));
+
+    server
+        .delete("/groups/north")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .get("/alerts/recent")
+        .add_query_param("group", "north")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_warmth_sparkline() {
+    let server = create_test_server().await;
+
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-spark", "weight": 4 }))
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+
+    // Not included unless asked for
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-spark")
+        .await
+        .json();
+    assert!(body.get("sparkline").is_none());
+
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-spark")
+        .add_query_param("include", "sparkline")
+        .add_query_param("sparkline_windows", "5")
+        .await
+        .json();
+    assert_eq!(body["sparkline"], json!([0, 0, 0, 0, 4]));
+    assert_eq!(body["status"], "alive");
+}
+
+#[tokio::test]
+async fn test_bucket_timezone_and_seasonal_warmth() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    server
+        .put("/buckets/zone-tz/timezone")
+        .json(&json!({ "timezone": "Mars/Olympus_Mons" }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    server
+        .put("/buckets/zone-tz/timezone")
+        .json(&json!({ "timezone": "Asia/Kabul" }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-tz")
+        .add_query_param("include", "seasonal")
+        .await
+        .json();
+    assert_eq!(body["timezone"], "Asia/Kabul");
+    assert_eq!(body["seasonal_average"], 0.0);
+
+    server
+        .delete("/buckets/zone-tz/timezone")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .delete("/buckets/zone-tz/timezone")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    let audit = storage
+        .get_audit_entries(Some("timezone.change"), 10)
+        .await
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+}
+
+#[tokio::test]
+async fn test_warmth_baseline_overrides() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-weekly")
+        .await
+        .json();
+    assert_eq!(body["baseline_windows"], 6);
+
+    server
+        .put("/buckets/zone-weekly/baseline")
+        .json(&json!({ "span_minutes": 10080 }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .put("/buckets/zone-weekly/baseline")
+        .json(&json!({ "windows": 0 }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-weekly")
+        .add_query_param("window_minutes", "60")
+        .await
+        .json();
+    assert_eq!(body["baseline_windows"], 168);
+
+    // Query parameters override the bucket's baseline
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-weekly")
+        .add_query_param("window_minutes", "60")
+        .add_query_param("baseline_span", "2d")
+        .await
+        .json();
+    assert_eq!(body["baseline_windows"], 48);
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-weekly")
+        .add_query_param("baseline_windows", "12")
+        .add_query_param("baseline_span", "2d")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    server
+        .delete("/buckets/zone-weekly/baseline")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    let audit = storage
+        .get_audit_entries(Some("baseline.change"), 10)
+        .await
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+}
+
+#[tokio::test]
+async fn test_bucket_meta_and_threshold_overrides() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    // Six earlier windows at 10 each, the current one at 6: a ratio of 0.6
+    let now = chrono::Utc::now();
+    for (minutes, weight) in [
+        (2, 6),
+        (15, 10),
+        (25, 10),
+        (35, 10),
+        (45, 10),
+        (55, 10),
+        (65, 10),
+    ] {
+        storage
+            .insert_life_signal(&infrared::model::LifeSignal {
+                bucket: "zone-bursty".to_string(),
+                timestamp: now - chrono::Duration::minutes(minutes),
+                weight,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+    let status = |body: serde_json::Value| body["status"].as_str().unwrap().to_string();
+
+    server
+        .get("/buckets/zone-bursty/meta")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+    let body = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-bursty")
+        .await
+        .json();
+    assert_eq!(status(body), "stressed");
+
+    for invalid in [
+        json!({}),
+        json!({ "expected_interval_minutes": 0 }),
+        json!({ "thresholds": { "stressed_ratio": 0.1, "collapsing_ratio": 0.2 } }),
+    ] {
+        server
+            .put("/buckets/zone-bursty/meta")
+            .json(&invalid)
+            .await
+            .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+    server
+        .put("/buckets/zone-bursty/meta")
+        .json(&json!({
+            "display_name": "Bursty zone",
+            "expected_interval_minutes": 10,
+            "thresholds": { "stressed_ratio": 0.5 }
+        }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let body: serde_json::Value = server.get("/buckets/zone-bursty/meta").await.json();
+    assert_eq!(body["display_name"], "Bursty zone");
+    assert_eq!(body["expected_interval_minutes"], 10);
+    assert_eq!(body["thresholds"]["collapsing_ratio"], 0.2);
+    assert!(body.get("description").is_none());
+
+    let body = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-bursty")
+        .await
+        .json();
+    assert_eq!(status(body), "alive");
+    let body: serde_json::Value = server
+        .post("/warmth/batch")
+        .json(&json!({ "buckets": ["zone-bursty"] }))
+        .await
+        .json();
+    assert_eq!(status(body["buckets"][0].clone()), "alive");
+
+    server
+        .delete("/buckets/zone-bursty/meta")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .delete("/buckets/zone-bursty/meta")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+    let body = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-bursty")
+        .await
+        .json();
+    assert_eq!(status(body), "stressed");
+
+    let audit = storage
+        .get_audit_entries(Some("bucket_meta.change"), 10)
+        .await
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+}
+
+#[tokio::test]
+async fn test_archived_bucket_leaves_alerts_and_listing() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    // A site that went quiet an hour ago is Dead
+    let now = chrono::Utc::now();
+    for minutes in 20..60 {
+        storage
+            .insert_life_signal(&infrared::model::LifeSignal {
+                bucket: "site-closed".to_string(),
+                timestamp: now - chrono::Duration::minutes(minutes),
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+    let body: serde_json::Value = server.get("/alerts/recent").await.json();
+    assert_eq!(body["alerts"][0]["bucket"], "site-closed");
+
+    // Generated alerts are kept in the history
+    let body: serde_json::Value = server
+        .get("/alerts/history")
+        .add_query_param("bucket", "site-closed")
+        .await
+        .json();
+    let history = body["alerts"].as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["status"], "dead");
+    assert_eq!(history[0]["reason"], "warmth_drop");
+    assert!(history[0]["first_seen"].is_string());
+    server
+        .get("/alerts/history")
+        .add_query_param("from", "2024-02-01T00:00:00Z")
+        .add_query_param("until", "2024-01-01T00:00:00Z")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    server
+        .put("/buckets/site-closed/archive")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+
+    let body: serde_json::Value = server.get("/alerts/recent").await.json();
+    assert_eq!(body["alerts"], json!([]));
+    let body: serde_json::Value = server.get("/buckets").await.json();
+    assert_eq!(body["buckets"], json!([]));
+    let body: serde_json::Value = server
+        .get("/buckets")
+        .add_query_param("include", "archived")
+        .await
+        .json();
+    assert_eq!(body["buckets"][0]["bucket"], "site-closed");
+    assert!(body["buckets"][0]["archived_at"].is_string());
+
+    // History stays queryable for reporting
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "site-closed")
+        .add_query_param("window_minutes", "60")
+        .await
+        .json();
+    assert_eq!(body["current_window_total"], 40);
+
+    server
+        .delete("/buckets/site-closed/archive")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .delete("/buckets/site-closed/archive")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+    let body: serde_json::Value = server.get("/buckets").await.json();
+    assert_eq!(body["buckets"][0]["bucket"], "site-closed");
+
+    let audit = storage
+        .get_audit_entries(Some("bucket.archive"), 10)
+        .await
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+}
+
+#[tokio::test]
+async fn test_events_feed() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    let now = chrono::Utc::now();
+    for minutes in 20..60 {
+        storage
+            .insert_life_signal(&infrared::model::LifeSignal {
+                bucket: "zone-silent".to_string(),
+                timestamp: now - chrono::Duration::minutes(minutes),
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let body: serde_json::Value = server.get("/events").await.json();
+    assert_eq!(body["events"][0]["kind"], "bucket_alert");
+    assert_eq!(body["events"][0]["id"], "bucket:zone-silent");
+    assert_eq!(body["events"][0]["severity"], "warning");
+    assert_eq!(body["errors"], json!([]));
+
+    let body: serde_json::Value = server
+        .get("/events")
+        .add_query_param("min_severity", "critical")
+        .await
+        .json();
+    assert_eq!(body["events"], json!([]));
+
+    server
+        .get("/events")
+        .add_query_param("min_severity", "severe")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_bucket_country() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    server
+        .put("/buckets/khartoum-1/country")
+        .json(&json!({ "country": "sd" }))
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .put("/buckets/khartoum-1/country")
+        .json(&json!({ "country": "Sudan" }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    assert_eq!(
+        storage
+            .get_buckets_in_country(&["SD".to_string(), "SDN".to_string()])
+            .await
+            .unwrap(),
+        vec!["khartoum-1".to_string()]
+    );
+
+    // Situation reports need a dashboard
+    server
+        .get("/situation/SD")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+
+    server
+        .delete("/buckets/khartoum-1/country")
+        .await
+        .assert_status(axum::http::StatusCode::NO_CONTENT);
+    server
+        .delete("/buckets/khartoum-1/country")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+    let audit = storage
+        .get_audit_entries(Some("bucket_country.change"), 10)
+        .await
+        .unwrap();
+    assert_eq!(audit.len(), 2);
+}
+
+#[tokio::test]
+async fn test_warmth_query_limits() {
+    let server = create_test_server().await;
+
+    let response = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .add_query_param("window_minutes", "5000")
+        .await;
+    response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["error"], "limit_exceeded");
+    assert_eq!(body["parameter"], "window_minutes");
+    assert_eq!(body["limit"], 1440);
+
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .add_query_param("window_minutes", "60")
+        .add_query_param("baseline_span", "8w")
+        .await
+        .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .add_query_param("window_minutes", "60")
+        .add_query_param("baseline_span", "2w")
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_alerts_query_limits() {
+    let server = create_test_server().await;
+
+    for minutes in ["0", "5000"] {
+        let response = server
+            .get("/alerts/recent")
+            .add_query_param("minutes", minutes)
+            .await;
+        response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["error"], "limit_exceeded");
+        assert_eq!(body["parameter"], "minutes");
+        assert_eq!(body["limit"], 1440);
+    }
+
+    server
+        .get("/alerts/recent")
+        .add_query_param("minutes", "1440")
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_warmth_query_emits_status_transition() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let state = AppState::new(storage.clone(), None);
+    let mut events = state.events.subscribe();
+    let server = TestServer::new(router(state)).unwrap();
+
+    // No history yet: alive
+    server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-t")
+        .await
+        .assert_status_ok();
+    assert!(events.try_recv().is_err());
+
+    // Activity in an earlier window, silence now: dead
+    storage
+        .insert_life_signal(&LifeSignal {
+            bucket: "zone-t".to_string(),
+            timestamp: chrono::Utc::now() - chrono::Duration::minutes(15),
+            weight: 20,
+            channel: None,
+        })
+        .await
+        .unwrap();
+    let body: serde_json::Value = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-t")
+        .await
+        .json();
+    assert_eq!(body["status"], "dead");
+
+    let Event::StatusChanged(transition) = events.try_recv().unwrap() else {
+        panic!("expected a status change");
+    };
+    assert_eq!(transition.bucket, "zone-t");
+    assert_eq!(transition.previous_status, WarmthStatus::Alive);
+    assert_eq!(transition.status, WarmthStatus::Dead);
+    assert_eq!(transition.recent_average, 20.0);
+}
+
+#[tokio::test]
+async fn test_post_signal_publishes_event() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let state = AppState::new(storage, None);
+    let mut events = state.events.subscribe();
+    let server = TestServer::new(router(state)).unwrap();
+
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-e", "weight": 3 }))
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+
+    let Event::SignalIngested(signal) = events.try_recv().unwrap() else {
+        panic!("expected an ingested signal");
+    };
+    assert_eq!(signal.bucket, "zone-e");
+    assert_eq!(signal.weight, 3);
+}
+
+#[tokio::test]
+async fn test_warmth_history_from_scheduler() {
+    use infrared::scheduler::snapshot_all;
+
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let state = AppState::new(storage.clone(), None);
+    let now = chrono::Utc::now();
+    storage
+        .insert_life_signal(&LifeSignal {
+            bucket: "zone-h".to_string(),
+            timestamp: now - chrono::Duration::minutes(2),
+            weight: 4,
+            channel: None,
+        })
+        .await
+        .unwrap();
+    snapshot_all(&storage, &state.transitions, 10, now)
+        .await
+        .unwrap();
+    let server = TestServer::new(router(state)).unwrap();
+
+    let body: serde_json::Value = server
+        .get("/warmth/history")
+        .add_query_param("bucket", "zone-h")
+        .await
+        .json();
+    assert_eq!(body["bucket"], "zone-h");
+    let snapshots = body["snapshots"].as_array().unwrap();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0]["status"], "alive");
+    assert_eq!(snapshots[0]["current_window_total"], 4);
+
+    let body: serde_json::Value = server
+        .get("/warmth/history")
+        .add_query_param("bucket", "zone-unknown")
+        .await
+        .json();
+    assert!(body["snapshots"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_warmth_forecast() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let now = chrono::Utc::now();
+
+    // A steady 50 per window, then an empty current window
+    for windows_ago in 1..=30 {
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "zone-f".to_string(),
+                timestamp: now - chrono::Duration::minutes(windows_ago * 10 + 5),
+                weight: 50,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+    let server = TestServer::new(router(AppState::new(storage, None))).unwrap();
+
+    let response = server
+        .get("/warmth/forecast")
+        .add_query_param("bucket", "zone-f")
+        .add_query_param("days", "1")
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["history_windows"], 144);
+    assert_eq!(body["season_windows"], serde_json::Value::Null);
+    assert_eq!(body["current_window_total"], 0);
+    assert!(body["expected_current"].as_f64().unwrap() > 40.0);
+    assert_eq!(body["underperforming"], true);
+
+    server
+        .get("/warmth/forecast")
+        .add_query_param("bucket", "zone-f")
+        .add_query_param("days", "90")
+        .await
+        .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    server
+        .get("/warmth/forecast")
+        .add_query_param("bucket", "zone-f")
+        .add_query_param("sigmas", "0")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    server
+        .get("/warmth/forecast")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_cap_alerts() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let state = AppState::new(storage.clone(), None).with_cap_sender("ops@example.org");
+    let server = TestServer::new(router(state)).unwrap();
+
+    let response = server.get("/alerts/cap").await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.header("content-type").to_str().unwrap(),
+        "application/atom+xml"
+    );
+    assert!(!response.text().contains("<entry>"));
+
+    // A site that went quiet twenty minutes ago is Dead
+    let now = chrono::Utc::now();
+    for minutes in 20..60 {
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: "site-a".to_string(),
+                timestamp: now - chrono::Duration::minutes(minutes),
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let feed = server.get("/alerts/cap").await.text();
+    assert_eq!(feed.matches("<entry>").count(), 1);
+    assert!(feed.contains(r#"<alert xmlns="urn:oasis:names:tc:emergency:cap:1.2">"#));
+    assert!(feed.contains("<sender>ops@example.org</sender>"));
+    assert!(feed.contains("<areaDesc>site-a</areaDesc>"));
+}
+
+#[tokio::test]
+async fn test_incidents() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    // Three zones went quiet together; two share a group, one the country
+    let now = chrono::Utc::now();
+    for bucket in ["zone-1", "zone-2", "zone-3", "zone-far"] {
+        for minutes in 20..60 {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: now - chrono::Duration::minutes(minutes),
+                    weight: 1,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+    }
+    storage
+        .set_group("north", &["zone-1".to_string(), "zone-2".to_string()])
+        .await
+        .unwrap();
+    storage.set_country("zone-2", "SD").await.unwrap();
+    storage.set_country("zone-3", "SD").await.unwrap();
+
+    let response = server.get("/incidents").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let incidents = body["incidents"].as_array().unwrap();
+    assert_eq!(incidents.len(), 2, "{:?}", incidents);
+    let regional = incidents
+        .iter()
+        .find(|i| i["buckets"].as_array().unwrap().len() == 3)
+        .unwrap();
+    assert_eq!(regional["buckets"], json!(["zone-1", "zone-2", "zone-3"]));
+    assert_eq!(regional["ongoing"], true);
+    assert_eq!(regional["groups"], json!(["north"]));
+    assert_eq!(regional["countries"], json!(["SD"]));
+
+    server
+        .get("/incidents")
+        .add_query_param("hours", "1000")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_incident_timeline() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    let now = chrono::Utc::now();
+    for bucket in ["zone-1", "zone-2"] {
+        for minutes in 20..60 {
+            storage
+                .insert_life_signal(&LifeSignal {
+                    bucket: bucket.to_string(),
+                    timestamp: now - chrono::Duration::minutes(minutes),
+                    weight: 1,
+                    channel: None,
+                })
+                .await
+                .unwrap();
+        }
+    }
+    storage
+        .set_group("north", &["zone-1".to_string(), "zone-2".to_string()])
+        .await
+        .unwrap();
+
+    let body: serde_json::Value = server.get("/incidents").await.json();
+    let id = body["incidents"][0]["id"].as_str().unwrap().to_string();
+
+    server
+        .post(&format!("/incidents/{}/ack", id))
+        .await
+        .assert_status(axum::http::StatusCode::CREATED);
+    let response = server
+        .post(&format!("/incidents/{}/notes", id))
+        .json(&json!({"text": "Regional grid failure"}))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let note: serde_json::Value = response.json();
+    assert_eq!(note["kind"], "note");
+    assert_eq!(note["actor"], "anonymous");
+
+    let response = server.get(&format!("/incidents/{}/timeline", id)).await;
+    response.assert_status_ok();
+    let timeline: serde_json::Value = response.json();
+    assert_eq!(timeline["incident"]["id"], id.as_str());
+    let kinds: Vec<&str> = timeline["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec!["status_change", "status_change", "acknowledgment", "note"]
+    );
+    assert_eq!(timeline["entries"][3]["text"], "Regional grid failure");
+
+    server
+        .post(&format!("/incidents/{}/notes", id))
+        .json(&json!({"text": "  "}))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    server
+        .get("/incidents/zone-9@1700000000/timeline")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+    server
+        .post("/incidents/zone-9@1700000000/ack")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_signal_export() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    storage
+        .insert_life_signal(&LifeSignal {
+            bucket: "zone-a".to_string(),
+            timestamp: chrono::Utc::now() - chrono::Duration::minutes(5),
+            weight: 1,
+            channel: None,
+        })
+        .await
+        .unwrap();
+
+    // Past the raw history limit
+    server
+        .get("/admin/signals.parquet")
+        .add_query_param("from", "2000-01-01T00:00:00Z")
+        .await
+        .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    server
+        .get("/admin/signals.parquet")
+        .add_query_param("from", "2100-01-01T00:00:00Z")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let response = server
+        .get("/admin/signals.parquet")
+        .add_query_param("bucket", "zone-a")
+        .await;
+    if cfg!(feature = "parquet") {
+        response.assert_status_ok();
+        assert_eq!(
+            response.header("content-type"),
+            "application/vnd.apache.parquet"
+        );
+        assert!(response.as_bytes().starts_with(b"PAR1"));
+    } else {
+        response.assert_status(axum::http::StatusCode::NOT_IMPLEMENTED);
+    }
+}
+
+#[tokio::test]
+async fn test_signal_channels() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let state = AppState::new(storage.clone(), None)
+        .with_channels(SignalChannels::parse("sms,internet").unwrap());
+    let server = TestServer::new(router(state)).unwrap();
+
+    for (channel, weight) in [(json!("sms"), 3), (json!("internet"), 2), (json!(null), 1)] {
+        server
+            .post("/signal")
+            .json(&json!({ "bucket": "zone-a", "weight": weight, "channel": channel }))
+            .await
+            .assert_status(axum::http::StatusCode::ACCEPTED);
+    }
+    // Not one of the configured channels
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-a", "channel": "satellite" }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let response = server
+        .get("/warmth")
+        .add_query_param("bucket", "zone-a")
+        .add_query_param("include", "channels")
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["current_window_total"], 6);
+    let channels = body["channels"].as_array().unwrap();
+    assert_eq!(channels.len(), 3);
+    assert_eq!(channels[0]["channel"], json!(null));
+    assert_eq!(channels[1]["channel"], "internet");
+    assert_eq!(channels[1]["current_window_total"], 2);
+    assert_eq!(channels[2]["channel"], "sms");
+    assert_eq!(channels[2]["current_window_total"], 3);
+
+    // Without channels configured, attributed signals are refused
+    let server = TestServer::new(router(AppState::new(storage, None))).unwrap();
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-a", "channel": "sms" }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_signal_stream_export() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    let now = chrono::Utc::now();
+    for (bucket, minutes) in [("zone-a", 30), ("zone-b", 20), ("zone-a", 10)] {
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: bucket.to_string(),
+                timestamp: now - chrono::Duration::minutes(minutes),
+                weight: 2,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let response = server
+        .get("/signals/export")
+        .add_query_param("bucket", "zone-a")
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "application/x-ndjson");
+    let rows: Vec<serde_json::Value> = response
+        .text()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["bucket"], "zone-a");
+    assert_eq!(rows[0]["weight"], 2);
+
+    let response = server
+        .get("/signals/export")
+        .add_query_param("format", "csv")
+        .add_query_param(
+            "from",
+            (now - chrono::Duration::minutes(25))
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        )
+        .await;
+    response.assert_status_ok();
+    assert_eq!(response.header("content-type"), "text/csv; charset=utf-8");
+    let csv = response.text();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "bucket,timestamp,weight,channel");
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("zone-b,"));
+
+    server
+        .get("/signals/export")
+        .add_query_param("format", "xml")
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+    server
+        .get("/signals/export")
+        .add_query_param("from", "2000-01-01T00:00:00Z")
+        .await
+        .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_signal_backfill() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let auth = AuthConfig::parse("ingest:field:ingest-token,admin:ops:admin-token").unwrap();
+    let state = AppState::new(storage.clone(), None).with_auth(auth);
+    let server = TestServer::new(router(state)).unwrap();
+    let bearer =
+        |token: &str| axum::http::HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+
+    let now = chrono::Utc::now();
+    let signals: Vec<serde_json::Value> = (2..5)
+        .map(|hours| {
+            json!({
+                "bucket": "zone-a",
+                "timestamp": (now - chrono::Duration::hours(hours)).to_rfc3339(),
+                "weight": 10,
+            })
+        })
+        .collect();
+    let body = json!({ "signals": signals });
+
+    server
+        .post("/signals/backfill")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("ingest-token"))
+        .json(&body)
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    let response = server
+        .post("/signals/backfill")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("admin-token"))
+        .json(&body)
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let accepted: serde_json::Value = response.json();
+    assert_eq!(accepted["inserted"], 3);
+    assert_eq!(accepted["buckets"], 1);
+
+    // Backfilled history forms the baseline the silent present is judged against
+    let average = storage
+        .compute_recent_average("zone-a", 60, 6, now)
+        .await
+        .unwrap();
+    assert_eq!(average, 10.0);
+    assert_eq!(
+        storage
+            .query_bucket_window("zone-a", 60, now)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let audit = storage.get_audit_entries(None, 10).await.unwrap();
+    assert_eq!(audit[0].action, "signals.backfill");
+    assert_eq!(
+        audit[0].details.as_deref(),
+        Some("3 signals into 1 buckets")
+    );
+
+    server
+        .post("/signals/backfill")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("admin-token"))
+        .json(&json!({ "signals": [{
+            "bucket": "zone-a",
+            "timestamp": (now + chrono::Duration::hours(1)).to_rfc3339(),
+        }] }))
+        .await
+        .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Live ingest never takes a client's word for when a signal happened
+    server
+        .post("/signal")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("ingest-token"))
+        .json(&json!({ "bucket": "zone-a", "timestamp": "2024-01-01T00:00:00Z" }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_backfill_retry_with_idempotency_key() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    let now = chrono::Utc::now();
+    let body = json!({ "signals": [
+        { "bucket": "zone-a", "timestamp": (now - chrono::Duration::hours(2)).to_rfc3339(), "weight": 5 },
+        { "bucket": "zone-b", "timestamp": (now - chrono::Duration::hours(2)).to_rfc3339() },
+    ] });
+    let post = |key: &'static str| {
+        server
+            .post("/signals/backfill")
+            .add_header(
+                axum::http::HeaderName::from_static("idempotency-key"),
+                axum::http::HeaderValue::from_static(key),
+            )
+            .json(&body)
+    };
+
+    post("1b4e28ba-2fa1-41d2-883f-0016d3cca427")
+        .await
+        .assert_status(axum::http::StatusCode::CREATED);
+
+    // The response was lost; the client retries with the same key
+    let response = post("1B4E28BA-2FA1-41D2-883F-0016D3CCA427").await;
+    response.assert_status_ok();
+    let replayed: serde_json::Value = response.json();
+    assert_eq!(replayed["inserted"], 2);
+    assert_eq!(replayed["buckets"], 2);
+    assert_eq!(replayed["replayed"], true);
+
+    assert_eq!(
+        storage
+            .query_bucket_window("zone-a", 180, now)
+            .await
+            .unwrap(),
+        5
+    );
+
+    // A new batch takes a new key
+    post("6f9619ff-8b86-4d01-b42d-00c04fc964ff")
+        .await
+        .assert_status(axum::http::StatusCode::CREATED);
+    assert_eq!(
+        storage
+            .query_bucket_window("zone-a", 180, now)
+            .await
+            .unwrap(),
+        10
+    );
+    assert_eq!(storage.get_audit_entries(None, 10).await.unwrap().len(), 2);
+
+    post("device-17")
+        .await
+        .assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn test_bucket_naming_lint_and_normalization() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    for bucket in ["clinic-1", "Clinic-1", "clinic 2"] {
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: bucket.to_string(),
+                timestamp: chrono::Utc::now(),
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+    let naming = NamingConvention::new(Some("[a-z]+-[0-9]+"), true).unwrap();
+    let state = AppState::new(storage.clone(), None).with_naming(naming);
+    let server = TestServer::new(router(state)).unwrap();
+
+    let report: serde_json::Value = server.get("/admin/buckets/lint").await.json();
+    assert_eq!(report["checked"], 3);
+    assert_eq!(report["duplicates"], json!([["Clinic-1", "clinic-1"]]));
+    assert_eq!(report["violations"][0]["bucket"], "Clinic-1");
+    assert_eq!(
+        report["violations"][0]["problems"],
+        json!(["pattern_mismatch", "not_normalized"])
+    );
+    assert_eq!(report["violations"][1]["bucket"], "clinic 2");
+
+    // New variants are folded into the normalized name
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": " CLINIC-1 " }))
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+    let now = chrono::Utc::now() + chrono::Duration::seconds(1);
+    assert_eq!(
+        storage
+            .query_bucket_window("clinic-1", 10, now)
+            .await
+            .unwrap(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_batch_warmth() {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let server = TestServer::new(router(AppState::new(storage.clone(), None))).unwrap();
+
+    let now = chrono::Utc::now();
+    for (bucket, minutes) in [("zone-a", 2), ("zone-a", 15), ("zone-b", 25)] {
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: bucket.to_string(),
+                timestamp: now - chrono::Duration::minutes(minutes),
+                weight: 4,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    let response = server
+        .post("/warmth/batch")
+        .json(&json!({ "buckets": ["zone-b", "zone-a", "unknown"] }))
+        .await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["window_minutes"], 10);
+    let buckets = body["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 3);
+    assert_eq!(buckets[0]["bucket"], "zone-b");
+    assert_eq!(buckets[0]["status"], "dead");
+    assert_eq!(buckets[1]["bucket"], "zone-a");
+    assert_eq!(buckets[1]["current_window_total"], 4);
+    assert_eq!(buckets[1]["status"], "alive");
+    assert_eq!(buckets[2]["current_window_total"], 0);
+
+    server
+        .post("/warmth/batch")
+        .json(&json!({ "buckets": [] }))
+        .await
+        .assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+    let many: Vec<String> = (0..101).map(|i| format!("zone-{}", i)).collect();
+    let response = server
+        .post("/warmth/batch")
+        .json(&json!({ "buckets": many }))
+        .await;
+    response.assert_status(axum::http::StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["parameter"], "buckets");
+}
+
+#[tokio::test]
+async fn test_admin_backup() {
+    let root = std::env::temp_dir().join(format!("infrared-api-backup-{}", std::process::id()));
+    let dir = root.join("backups");
+    std::fs::create_dir_all(&dir).unwrap();
+    let url = format!("sqlite:{}?mode=rwc", root.join("live.db").display());
+    let storage = Storage::new(&url).await.unwrap();
+    let auth = AuthConfig::parse("read:viewer:read-token,admin:ops:admin-token").unwrap();
+    let state = AppState::new(storage.clone(), None)
+        .with_auth(auth)
+        .with_backup_dir(&dir);
+    let server = TestServer::new(router(state)).unwrap();
+    let bearer =
+        |token: &str| axum::http::HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+
+    server
+        .post("/admin/backup")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("read-token"))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+
+    let response = server
+        .post("/admin/backup")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("admin-token"))
+        .await;
+    response.assert_status(axum::http::StatusCode::CREATED);
+    let written: serde_json::Value = response.json();
+    let path = std::path::PathBuf::from(written["path"].as_str().unwrap());
+    assert!(path.starts_with(&dir));
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().len(),
+        written["bytes"].as_u64().unwrap()
+    );
+
+    let response = server
+        .post("/admin/backup?download=true")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("admin-token"))
+        .await;
+    response.assert_status_ok();
+    assert_eq!(
+        response.header(axum::http::header::CONTENT_TYPE),
+        "application/vnd.sqlite3"
+    );
+    assert!(response.as_bytes().starts_with(b"SQLite format 3\0"));
+
+    let audit = storage.get_audit_entries(None, 10).await.unwrap();
+    assert_eq!(audit[0].action, "backup");
+    assert_eq!(audit[0].details.as_deref(), Some("download"));
+
+    server
+        .get("/admin/storage/stats")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("read-token"))
+        .await
+        .assert_status(axum::http::StatusCode::FORBIDDEN);
+    let response = server
+        .get("/admin/storage/stats")
+        .add_header(axum::http::header::AUTHORIZATION, bearer("admin-token"))
+        .await;
+    response.assert_status_ok();
+    let stats: serde_json::Value = response.json();
+    assert_eq!(stats["tables"]["audit_log"], 2);
+    assert_eq!(stats["tables"]["life_signals"], 0);
+    assert!(stats["database_bytes"].as_u64().unwrap() > 0);
+
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn test_journal_replays_accepted_signals() {
+    let dir = std::env::temp_dir().join(format!("infrared-api-journal-{}", std::process::id()));
+    let journal = Journal::open(JournalConfig {
+        dir: dir.clone(),
+        max_file_bytes: 1024 * 1024,
+        keep: 4,
+    })
+    .unwrap();
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let state = AppState::new(storage, None).with_journal(journal);
+    let server = TestServer::new(router(state)).unwrap();
+
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-a", "weight": 3 }))
+        .await
+        .assert_status(axum::http::StatusCode::ACCEPTED);
+    // Rejected signals are not journaled
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "_canary:ingest" }))
+        .await
+        .assert_status_bad_request();
+    let now = chrono::Utc::now();
+    server
+        .post("/signals/backfill")
+        .json(&json!({ "signals": [{
+            "bucket": "zone-b",
+            "timestamp": (now - chrono::Duration::minutes(5)).to_rfc3339(),
+            "weight": 4,
+        }] }))
+        .await
+        .assert_status(axum::http::StatusCode::CREATED);
+
+    // The live database is lost; a fresh one is rebuilt from the journal
+    let rebuilt = Storage::new("sqlite::memory:").await.unwrap();
+    let report = replay(&rebuilt, &dir).await.unwrap();
+    assert_eq!(report.signals, 2);
+    assert_eq!(report.skipped_lines, 0);
+    let later = chrono::Utc::now();
+    assert_eq!(
+        rebuilt
+            .query_bucket_window("zone-a", 60, later)
+            .await
+            .unwrap(),
+        3
+    );
+    assert_eq!(
+        rebuilt
+            .query_bucket_window("zone-b", 60, later)
+            .await
+            .unwrap(),
+        4
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_public_mirror_mode() {
+    use infrared::public::PublicMirror;
+
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    for bucket in ["zone-1", "zone-2", "zone-3"] {
+        storage.set_country(bucket, "SD").await.unwrap();
+        storage
+            .insert_life_signal(&LifeSignal {
+                bucket: bucket.to_string(),
+                timestamp: chrono::Utc::now() - chrono::Duration::minutes(1),
+                weight: 1,
+                channel: None,
+            })
+            .await
+            .unwrap();
+    }
+    storage
+        .set_group("north", &["zone-1".to_string(), "zone-2".to_string()])
+        .await
+        .unwrap();
+
+    let mirror = PublicMirror {
+        min_buckets: 2,
+        labels: PublicMirror::parse_labels("north=Northern Districts").unwrap(),
+    };
+    let server = TestServer::new(router(
+        AppState::new(storage.clone(), None).with_public_mirror(mirror),
+    ))
+    .unwrap();
+
+    let response = server.get("/public/status").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["countries"][0]["label"], "SD");
+    assert_eq!(body["groups"][0]["label"], "Northern Districts");
+    assert!(!body.to_string().contains("zone-"));
+
+    server.get("/health").await.assert_status_ok();
+    for path in ["/warmth?bucket=zone-1", "/groups", "/buckets"] {
+        server
+            .get(path)
+            .await
+            .assert_status(axum::http::StatusCode::NOT_FOUND);
+    }
+
+    // The full endpoint set does not include the mirror
+    let server = TestServer::new(router(AppState::new(storage, None))).unwrap();
+    server
+        .get("/public/status")
+        .await
+        .assert_status(axum::http::StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,230 @@
+//! Degradation tests with injected storage and data source faults.
+//!
+//! Built only with the `chaos` feature: `cargo test --features chaos`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use axum_test::TestServer;
+use chrono::Utc;
+use serde_json::json;
+
+use infrared::api::{AppState, router};
+use infrared::chaos::{Chaos, ChaosStore, EVERY_OPERATION, Fault};
+use infrared::dashboard::{
+    Dashboard, DashboardConfig, DashboardResponse, DashboardSummary, Issue, IssueCategory,
+    IssueSeverity, IssueSource, SourceHealth,
+};
+use infrared::storage::{SignalStore, Storage};
+use infrared::timeouts::RequestTimeouts;
+
+fn issue(source: IssueSource, title: &str, hours_ago: i64) -> Issue {
+    Issue::new(
+        source,
+        IssueCategory::InternetOutage,
+        IssueSeverity::Critical,
+        "Sudan",
+        "SD",
+        title,
+        "Test",
+        Utc::now() - chrono::Duration::hours(hours_ago),
+    )
+}
+
+/// A server over `chaos`'s storage, with a dashboard fetching IODA and
+/// partner issues, and the unwrapped storage for setting up data.
+async fn chaotic_server(chaos: &Chaos, timeouts: RequestTimeouts) -> (TestServer, Storage) {
+    let storage = Storage::new("sqlite::memory:").await.unwrap();
+    let store = ChaosStore::new(storage.clone(), chaos.clone());
+    let dashboard = Dashboard::new(DashboardConfig {
+        sources: Some(HashSet::from([IssueSource::Ioda, IssueSource::External])),
+        ..DashboardConfig::default()
+    })
+    .with_external_issues(Arc::new(store.clone()))
+    .with_chaos(chaos.clone());
+    let state = AppState::new(store, Some(dashboard)).with_timeouts(timeouts);
+    (TestServer::new(router(state)).unwrap(), storage)
+}
+
+fn titles(body: &serde_json::Value) -> Vec<&str> {
+    body["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["title"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_failing_source_partial_results() {
+    let chaos = Chaos::new();
+    chaos.fault_source(IssueSource::Ioda, Fault::Fail);
+    let (server, storage) = chaotic_server(&chaos, RequestTimeouts::default()).await;
+    storage
+        .upsert_external_issue(
+            &issue(IssueSource::External, "Partner outage", 1),
+            Utc::now(),
+        )
+        .await
+        .unwrap();
+
+    let response = server.get("/dashboard").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    assert_eq!(titles(&body), ["Partner outage"]);
+    assert_eq!(body["errors"][0]["source"], "ioda");
+    let health = body["health"].as_array().unwrap();
+    assert!(health.contains(&json!({ "source": "ioda", "ok": false, "parse_warnings": 0 })));
+    let external = health.iter().find(|h| h["source"] == "external").unwrap();
+    assert_eq!(external["ok"], true);
+    assert!(external["last_success"].is_string());
+
+    // Asked for by name, the failing source is unavailable
+    for path in ["/dashboard/source/ioda", "/dashboard?source=ioda"] {
+        server
+            .get(path)
+            .await
+            .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    server
+        .get("/dashboard/source/external")
+        .await
+        .assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_failing_source_falls_back_to_snapshot() {
+    let chaos = Chaos::new();
+    chaos.fault_source(IssueSource::Ioda, Fault::Fail);
+    let hours_ago = |hours| Utc::now() - chrono::Duration::hours(hours);
+
+    // IODA's issues stand in while its last success is within the lookback
+    // window, however recent the snapshot carrying them
+    for (taken_at, last_success, expected) in [
+        (hours_ago(1), None, vec!["Earlier outage"]),
+        (hours_ago(1), Some(hours_ago(2)), vec!["Earlier outage"]),
+        (hours_ago(48), None, vec![]),
+        (hours_ago(1), Some(hours_ago(48)), vec![]),
+    ] {
+        let (server, storage) = chaotic_server(&chaos, RequestTimeouts::default()).await;
+        let issues = vec![issue(IssueSource::Ioda, "Earlier outage", 1)];
+        let snapshot = DashboardResponse {
+            timestamp: taken_at,
+            summary: DashboardSummary::from_issues(&issues),
+            issues,
+            errors: vec![],
+            health: vec![SourceHealth {
+                source: IssueSource::Ioda,
+                ok: last_success.is_none(),
+                parse_warnings: 0,
+                last_success,
+                stale: last_success.is_some(),
+            }],
+            suppressed: vec![],
+        };
+        storage.insert_dashboard_snapshot(&snapshot).await.unwrap();
+
+        let response = server.get("/dashboard").await;
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(titles(&body), expected);
+        assert_eq!(body["errors"][0]["source"], "ioda");
+        let ioda = |body: &serde_json::Value| {
+            body["health"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|h| h["source"] == "ioda")
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(ioda(&body)["ok"], false);
+        assert_eq!(
+            ioda(&body)["stale"].as_bool(),
+            (!expected.is_empty()).then_some(true)
+        );
+
+        // The fallback is persisted with the original last success, so it
+        // outlasts further failures but not the lookback window
+        let again: serde_json::Value = server.get("/dashboard").await.json();
+        assert_eq!(titles(&again), expected);
+        assert_eq!(ioda(&again)["last_success"], ioda(&body)["last_success"]);
+    }
+}
+
+#[tokio::test]
+async fn test_slow_source_times_out() {
+    let chaos = Chaos::new();
+    chaos.fault_source(IssueSource::Ioda, Fault::Delay(Duration::from_secs(60)));
+    let timeouts = RequestTimeouts {
+        dashboard: Duration::from_millis(100),
+        ..RequestTimeouts::default()
+    };
+    let (server, _) = chaotic_server(&chaos, timeouts).await;
+
+    server
+        .get("/dashboard")
+        .await
+        .assert_status(StatusCode::GATEWAY_TIMEOUT);
+    server.get("/health").await.assert_status_ok();
+}
+
+#[tokio::test]
+async fn test_failing_storage() {
+    let chaos = Chaos::new();
+    chaos.fault_source(IssueSource::Ioda, Fault::Fail);
+    let (server, _) = chaotic_server(&chaos, RequestTimeouts::default()).await;
+
+    // Refused ingests ask the client to retry
+    chaos.fault_storage("insert_life_signal", Fault::Fail);
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-a" }))
+        .await
+        .assert_status(StatusCode::SERVICE_UNAVAILABLE);
+
+    // The dashboard is served without partner issues or snapshots
+    chaos.fault_storage(EVERY_OPERATION, Fault::Fail);
+    let response = server.get("/dashboard").await;
+    response.assert_status_ok();
+    let body: serde_json::Value = response.json();
+    let failed: HashSet<&str> = body["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["source"].as_str().unwrap())
+        .collect();
+    assert_eq!(failed, HashSet::from(["ioda", "external"]));
+    server.get("/health").await.assert_status_ok();
+
+    chaos.heal();
+    server
+        .post("/signal")
+        .json(&json!({ "bucket": "zone-a" }))
+        .await
+        .assert_status(StatusCode::ACCEPTED);
+}
+
+#[tokio::test]
+async fn test_slow_storage_times_out() {
+    let chaos = Chaos::new();
+    let timeouts = RequestTimeouts {
+        read: Duration::from_millis(100),
+        ..RequestTimeouts::default()
+    };
+    let (server, _) = chaotic_server(&chaos, timeouts).await;
+
+    chaos.fault_storage(EVERY_OPERATION, Fault::Delay(Duration::from_secs(60)));
+    for path in ["/warmth?bucket=zone-a", "/groups", "/buckets"] {
+        server
+            .get(path)
+            .await
+            .assert_status(StatusCode::GATEWAY_TIMEOUT);
+    }
+    server.get("/health").await.assert_status_ok();
+
+    chaos.heal();
+    server.get("/warmth?bucket=zone-a").await.assert_status_ok();
+}